@@ -0,0 +1,386 @@
+//! Base58check encoded payload kinds
+//!
+//! Every human-readable Mina value (hashes, keys, commands, etc.) is base58check
+//! encoded with a version byte identifying its kind, see
+//! [crate::protocol::serialization_types::version_bytes]
+
+use crate::protocol::serialization_types::version_bytes::*;
+use anyhow::{anyhow, bail};
+use serde::Serialize;
+use std::fmt::Display;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Base58Kind {
+    CoinbaseHash,
+    SecretBoxByteswr,
+    FeeTransferSingle,
+    FrontierHash,
+    LedgerHash,
+    LitePrecomputed,
+    Proof,
+    RandomOracleBase,
+    ReceiptChainHash,
+    EpochSeed,
+    StagedLedgerHashAuxHash,
+    StagedLedgerHashPendingCoinbaseAux,
+    StateHash,
+    StateBodyHash,
+    V1TxnHash,
+    V2TxnHash,
+    UserCommand,
+    UserCommandMemo,
+    VrfTruncatedOutput,
+    WebPipe,
+    CoinbaseStackData,
+    CoinbaseStackHash,
+    PendingCoinbaseHashBuilder,
+    ZkappCommand,
+    VerificationKey,
+    TokenId,
+    PrivateKey,
+    PublicKey,
+    Signature,
+}
+
+/// Result of inspecting an arbitrary base58check string
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Base58Inspection {
+    /// Kind identified by the version byte
+    pub kind: Option<Base58Kind>,
+
+    pub version_byte: u8,
+
+    /// Hex encoded payload, excluding the version byte & checksum
+    pub payload: String,
+
+    pub payload_len: usize,
+
+    /// Whether the payload length matches the kind's
+    pub valid_len: bool,
+
+    pub valid_checksum: bool,
+}
+
+impl Base58Kind {
+    pub const ALL: [Self; 29] = [
+        Self::CoinbaseHash,
+        Self::SecretBoxByteswr,
+        Self::FeeTransferSingle,
+        Self::FrontierHash,
+        Self::LedgerHash,
+        Self::LitePrecomputed,
+        Self::Proof,
+        Self::RandomOracleBase,
+        Self::ReceiptChainHash,
+        Self::EpochSeed,
+        Self::StagedLedgerHashAuxHash,
+        Self::StagedLedgerHashPendingCoinbaseAux,
+        Self::StateHash,
+        Self::StateBodyHash,
+        Self::V1TxnHash,
+        Self::V2TxnHash,
+        Self::UserCommand,
+        Self::UserCommandMemo,
+        Self::VrfTruncatedOutput,
+        Self::WebPipe,
+        Self::CoinbaseStackData,
+        Self::CoinbaseStackHash,
+        Self::PendingCoinbaseHashBuilder,
+        Self::ZkappCommand,
+        Self::VerificationKey,
+        Self::TokenId,
+        Self::PrivateKey,
+        Self::PublicKey,
+        Self::Signature,
+    ];
+
+    pub fn version_byte(self) -> u8 {
+        match self {
+            Self::CoinbaseHash => COINBASE_HASH,
+            Self::SecretBoxByteswr => SECRET_BOX_BYTESWR,
+            Self::FeeTransferSingle => FEE_TRANSFER_SINGLE,
+            Self::FrontierHash => FRONTIER_HASH,
+            Self::LedgerHash => LEDGER_HASH,
+            Self::LitePrecomputed => LITE_PRECOMPUTED,
+            Self::Proof => PROOF,
+            Self::RandomOracleBase => RANDOM_ORACLE_BASE,
+            Self::ReceiptChainHash => RECEIPT_CHAIN_HASH,
+            Self::EpochSeed => EPOCH_SEED,
+            Self::StagedLedgerHashAuxHash => STAGED_LEDGER_HASH_AUX_HASH,
+            Self::StagedLedgerHashPendingCoinbaseAux => STAGED_LEDGER_HASH_PENDING_COINBASE_AUX,
+            Self::StateHash => STATE_HASH,
+            Self::StateBodyHash => STATE_BODY_HASH,
+            Self::V1TxnHash => V1_TXN_HASH,
+            Self::V2TxnHash => V2_TXN_HASH,
+            Self::UserCommand => USER_COMMAND,
+            Self::UserCommandMemo => USER_COMMAND_MEMO,
+            Self::VrfTruncatedOutput => VRF_TRUNCATED_OUTPUT,
+            Self::WebPipe => WEB_PIPE,
+            Self::CoinbaseStackData => COINBASE_STACK_DATA,
+            Self::CoinbaseStackHash => COINBASE_STACK_HASH,
+            Self::PendingCoinbaseHashBuilder => PENDING_COINBASE_HASH_BUILDER,
+            Self::ZkappCommand => ZKAPP_COMMAND,
+            Self::VerificationKey => VERIFICATION_KEY,
+            Self::TokenId => TOKEN_ID_KEY,
+            Self::PrivateKey => PRIVATE_KEY,
+            Self::PublicKey => NON_ZERO_CURVE_POINT_COMPRESSED,
+            Self::Signature => SIGNATURE,
+        }
+    }
+
+    /// Human readable name
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::CoinbaseHash => "coinbase hash",
+            Self::SecretBoxByteswr => "secret box",
+            Self::FeeTransferSingle => "fee transfer",
+            Self::FrontierHash => "frontier hash",
+            Self::LedgerHash => "ledger hash",
+            Self::LitePrecomputed => "lite precomputed block",
+            Self::Proof => "proof",
+            Self::RandomOracleBase => "random oracle base",
+            Self::ReceiptChainHash => "receipt chain hash",
+            Self::EpochSeed => "epoch seed",
+            Self::StagedLedgerHashAuxHash => "staged ledger aux hash",
+            Self::StagedLedgerHashPendingCoinbaseAux => "pending coinbase aux hash",
+            Self::StateHash => "state hash",
+            Self::StateBodyHash => "state body hash",
+            Self::V1TxnHash => "v1 txn hash",
+            Self::V2TxnHash => "v2 txn hash",
+            Self::UserCommand => "user command",
+            Self::UserCommandMemo => "user command memo",
+            Self::VrfTruncatedOutput => "vrf output",
+            Self::WebPipe => "web pipe",
+            Self::CoinbaseStackData => "coinbase stack data",
+            Self::CoinbaseStackHash => "coinbase stack hash",
+            Self::PendingCoinbaseHashBuilder => "pending coinbase hash builder",
+            Self::ZkappCommand => "zkapp command",
+            Self::VerificationKey => "verification key",
+            Self::TokenId => "token id",
+            Self::PrivateKey => "private key",
+            Self::PublicKey => "public key",
+            Self::Signature => "signature",
+        }
+    }
+
+    /// Length of the payload (excluding version byte & checksum), if fixed
+    pub fn payload_len(self) -> Option<usize> {
+        match self {
+            // version tag + hash
+            Self::LedgerHash
+            | Self::EpochSeed
+            | Self::ReceiptChainHash
+            | Self::StateHash
+            | Self::StateBodyHash => Some(33),
+
+            // version tag + length + hash
+            Self::V1TxnHash => Some(34),
+
+            // length + hash
+            Self::V2TxnHash => Some(33),
+
+            // length + tag + 32 bytes
+            Self::UserCommandMemo => Some(34),
+
+            // version tags + x coordinate + parity
+            Self::PublicKey => Some(35),
+
+            // version tag + field + scalar
+            Self::Signature => Some(65),
+
+            // field element
+            Self::TokenId => Some(32),
+            _ => None,
+        }
+    }
+
+    pub fn from_version_byte(version_byte: u8) -> Option<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|kind| kind.version_byte() == version_byte)
+    }
+
+    /// Base58check encodes the payload
+    pub fn encode(self, payload: &[u8]) -> String {
+        bs58::encode(payload)
+            .with_check_version(self.version_byte())
+            .into_string()
+    }
+
+    /// Decodes the payload of a base58check string of this kind
+    pub fn decode(self, input: &str) -> anyhow::Result<Vec<u8>> {
+        let bytes = bs58::decode(input)
+            .with_check(None)
+            .into_vec()
+            .map_err(|e| anyhow!("Invalid {self} {input}: {e}"))?;
+
+        let Some(&version_byte) = bytes.first() else {
+            bail!("Invalid {self} {input}: missing version byte")
+        };
+        if version_byte != self.version_byte() {
+            match Self::from_version_byte(version_byte) {
+                Some(kind) => bail!("Invalid {self} {input}: this is a {kind}, not a {self}"),
+                None => bail!("Invalid {self} {input}: unknown version byte {version_byte:#04x}"),
+            }
+        }
+
+        let payload = bytes[1..].to_vec();
+        match self.payload_len() {
+            Some(len) if len != payload.len() => bail!(
+                "Invalid {self} {input}: expected a {len} byte payload, got {}",
+                payload.len()
+            ),
+            _ => Ok(payload),
+        }
+    }
+
+    /// Checks the input is a well-formed base58check string of this kind
+    pub fn validate(self, input: &str) -> anyhow::Result<()> {
+        self.decode(input).map(|_| ())
+    }
+
+    /// Inspects an arbitrary base58check string, regardless of its checksum
+    pub fn inspect(input: &str) -> anyhow::Result<Base58Inspection> {
+        let bytes = bs58::decode(input)
+            .into_vec()
+            .map_err(|e| anyhow!("Invalid base58 {input}: {e}"))?;
+
+        // version byte + 4 checksum bytes
+        if bytes.len() < 5 {
+            bail!("Invalid base58check {input}: too short")
+        }
+
+        let kind = Self::from_version_byte(bytes[0]);
+        let payload = &bytes[1..bytes.len() - 4];
+        Ok(Base58Inspection {
+            kind,
+            version_byte: bytes[0],
+            payload: hex::encode(payload),
+            payload_len: payload.len(),
+            valid_len: kind
+                .and_then(Self::payload_len)
+                .map_or(true, |len| len == payload.len()),
+            valid_checksum: bs58::decode(input).with_check(None).into_vec().is_ok(),
+        })
+    }
+}
+
+impl Base58Inspection {
+    pub fn is_valid(&self) -> bool {
+        self.kind.is_some() && self.valid_len && self.valid_checksum
+    }
+}
+
+/////////////
+// display //
+/////////////
+
+impl Display for Base58Kind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+impl Display for Base58Inspection {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.kind {
+            Some(kind) => writeln!(f, "kind:         {kind}")?,
+            None => writeln!(f, "kind:         unknown")?,
+        }
+
+        writeln!(f, "version byte: {:#04x}", self.version_byte)?;
+        writeln!(f, "payload:      {}", self.payload)?;
+        writeln!(
+            f,
+            "payload len:  {}{}",
+            self.payload_len,
+            if self.valid_len { "" } else { " (invalid)" }
+        )?;
+        writeln!(
+            f,
+            "checksum:     {}",
+            if self.valid_checksum {
+                "valid"
+            } else {
+                "invalid"
+            }
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Base58Kind;
+    use crate::constants::{MAINNET_GENESIS_HASH, MINA_TOKEN_ADDRESS};
+
+    const LEDGER_HASH: &str = "jx7buQVWFLsXTtzRgSxbYcT8EYLS8KCZbLrfDcJxMtyy4thw2Ee";
+
+    #[test]
+    fn version_bytes_unique() {
+        for kind in Base58Kind::ALL {
+            assert_eq!(
+                Base58Kind::from_version_byte(kind.version_byte()),
+                Some(kind)
+            );
+        }
+    }
+
+    #[test]
+    fn encode_decode() -> anyhow::Result<()> {
+        for (kind, input) in [
+            (Base58Kind::StateHash, MAINNET_GENESIS_HASH),
+            (Base58Kind::LedgerHash, LEDGER_HASH),
+            (Base58Kind::TokenId, MINA_TOKEN_ADDRESS),
+        ] {
+            let payload = kind.decode(input)?;
+            assert_eq!(kind.encode(&payload), input);
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn validation_errors() {
+        assert_eq!(
+            Base58Kind::StateHash
+                .validate(LEDGER_HASH)
+                .unwrap_err()
+                .to_string(),
+            format!("Invalid state hash {LEDGER_HASH}: this is a ledger hash, not a state hash")
+        );
+
+        // corrupt the last character
+        let mut state_hash = MAINNET_GENESIS_HASH.to_string();
+        state_hash.pop();
+        state_hash.push('x');
+        assert!(Base58Kind::StateHash.validate(&state_hash).is_err());
+
+        // wrong length
+        let token = Base58Kind::TokenId.encode(&[0; 8]);
+        assert_eq!(
+            Base58Kind::TokenId
+                .validate(&token)
+                .unwrap_err()
+                .to_string(),
+            format!("Invalid token id {token}: expected a 32 byte payload, got 8")
+        );
+    }
+
+    #[test]
+    fn inspect() -> anyhow::Result<()> {
+        let inspection = Base58Kind::inspect(LEDGER_HASH)?;
+        assert_eq!(inspection.kind, Some(Base58Kind::LedgerHash));
+        assert_eq!(inspection.payload_len, 33);
+        assert!(inspection.is_valid());
+
+        let mut state_hash = MAINNET_GENESIS_HASH.to_string();
+        state_hash.pop();
+        state_hash.push('x');
+
+        let inspection = Base58Kind::inspect(&state_hash)?;
+        assert_eq!(inspection.kind, Some(Base58Kind::StateHash));
+        assert!(!inspection.valid_checksum);
+        Ok(())
+    }
+}
@@ -1,6 +1,7 @@
 //! Indexer base types
 
 pub mod amount;
+pub mod base58;
 pub mod blockchain_length;
 pub mod nonce;
 pub mod numeric;
@@ -1,4 +1,5 @@
 use crate::{
+    base::base58::Base58Kind,
     proof_systems::signer::pubkey::{CompressedPubKey, PubKey},
     protocol::serialization_types::signatures::{PublicKey2V1, PublicKeyV1},
 };
@@ -38,9 +39,9 @@ impl PublicKey {
         bail!("Invalid public key from bytes")
     }
 
-    /// Checks length & prefix
+    /// Checks length, version byte, payload length & checksum
     pub fn is_valid(pk: &str) -> bool {
-        pk.starts_with(Self::PREFIX) && pk.len() == Self::LEN
+        pk.len() == Self::LEN && Base58Kind::PublicKey.validate(pk).is_ok()
     }
 
     /// [PublicKey] upper bound
@@ -71,9 +72,11 @@ impl<'de> Deserialize<'de> for PublicKey {
 // default //
 /////////////
 
+/// Well-formed placeholder whose all-ones x coordinate exceeds the field
+/// modulus, so it's never a real public key
 impl std::default::Default for PublicKey {
     fn default() -> Self {
-        Self("B62qDEFAULTDEFAULTDEFAULTDEFAULTDEFAULTDEFAULTDEFAULTPK".into())
+        Self("B62qs2xPPkdScMRYNu7gyvc7rschELewEcVXjwMFn14ft5rj3veVhVv".into())
     }
 }
 
@@ -167,8 +170,12 @@ mod test {
     #[test]
     fn fixed_pks_are_valid() {
         assert!(PublicKey::is_valid(&PublicKey::default().0));
-        assert!(PublicKey::is_valid(&PublicKey::lower_bound().0));
-        assert!(PublicKey::is_valid(&PublicKey::upper_bound().0));
+
+        // bounds are key-shaped, not base58check encoded
+        for bound in [PublicKey::lower_bound(), PublicKey::upper_bound()] {
+            assert!(bound.0.starts_with(PublicKey::PREFIX));
+            assert_eq!(bound.0.len(), PublicKey::LEN);
+        }
     }
 
     #[test]
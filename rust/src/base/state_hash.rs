@@ -1,8 +1,11 @@
 //! Indexer state hash type
 
-use crate::protocol::serialization_types::{
-    common::{Base58EncodableVersionedType, HashV1},
-    version_bytes,
+use crate::{
+    base::base58::Base58Kind,
+    protocol::serialization_types::{
+        common::{Base58EncodableVersionedType, HashV1},
+        version_bytes,
+    },
 };
 use anyhow::bail;
 use serde::{Deserialize, Serialize};
@@ -41,8 +44,9 @@ impl StateHash {
         res
     }
 
+    /// Checks length, version byte, payload length & checksum
     pub fn is_valid(input: &str) -> bool {
-        input.starts_with(StateHash::PREFIX) && input.len() == StateHash::LEN
+        input.len() == StateHash::LEN && Base58Kind::StateHash.validate(input).is_ok()
     }
 }
 
@@ -88,6 +92,28 @@ where
 mod tests {
     use super::StateHash;
 
+    #[test]
+    fn is_valid() {
+        assert!(StateHash::is_valid(
+            "3NKxEA9gztvEGxL4uk4eTncZAxuRmMsB8n81UkeAMevUjMbLHmkC"
+        ));
+
+        // bad checksum
+        assert!(!StateHash::is_valid(
+            "3NKxEA9gztvEGxL4uk4eTncZAxuRmMsB8n81UkeAMevUjMbLHmkD"
+        ));
+
+        // right length & prefix, not base58
+        assert!(!StateHash::is_valid(
+            "3N0000000000000000000000000000000000000000000000000O"
+        ));
+
+        // ledger hash
+        assert!(!StateHash::is_valid(
+            "jxueCcoUGiBCzCWpxhrLVsgJxnwZAkcxBZKZVcMGd2JiszEeyoh"
+        ));
+    }
+
     #[test]
     fn roundtrip() -> anyhow::Result<()> {
         let hash = StateHash::default();
@@ -1,8 +1,10 @@
+use anyhow::bail;
 use clap::{Parser, Subcommand};
 use log::{debug, error, info, warn, LevelFilter};
 use mina_indexer::{
-    block::precomputed::PcbVersion,
-    chain::ChainId,
+    base::base58::Base58Kind,
+    block::{precomputed::PcbVersion, store::BlockStore, verify::verify_blocks_dir},
+    chain::{profile::NetworkProfile, store::ChainStore, Network},
     cli::{
         database::DatabaseArgs,
         server::{ServerArgs, ServerArgsJson},
@@ -10,10 +12,10 @@ use mina_indexer::{
     client,
     constants::*,
     ledger::genesis::GenesisLedger,
-    server::{GenesisVersion, IndexerConfiguration, IndexerVersion, InitializationMode},
-    store::{restore_snapshot, version::IndexerStoreVersion, IndexerStore},
+    server::{IndexerConfiguration, IndexerVersion, InitializationMode},
+    store::{fixed_keys::FixedKeys, restore_snapshot, version::IndexerStoreVersion, IndexerStore},
     unix_socket_server::remove_unix_socket,
-    web::start_web_server,
+    web::{rest::upload::Uploader, start_web_server},
 };
 use std::{
     fs::{self, File},
@@ -25,8 +27,18 @@ use std::{
 };
 use stderrlog::{ColorChoice, Timestamp};
 use tempfile::TempDir;
+use tokio::sync::mpsc;
 use tokio_graceful_shutdown::{SubsystemBuilder, SubsystemHandle, Toplevel};
 
+#[cfg(feature = "loose_deserialization")]
+use anyhow::Context;
+#[cfg(feature = "loose_deserialization")]
+use clap::Args;
+#[cfg(feature = "loose_deserialization")]
+use mina_indexer::protocol::bin_prot::{value::json, BinProtRule, Layout};
+#[cfg(feature = "loose_deserialization")]
+use std::io::{self, Read};
+
 #[derive(Parser, Debug)]
 #[command(name = "mina-indexer", author, version = VERSION, about, long_about = Some("Mina Indexer\n\n\
 Efficiently index and query the Mina blockchain"))]
@@ -37,11 +49,28 @@ struct Cli {
     /// Path to the Unix domain socket file
     #[arg(long, default_value = "./mina-indexer.sock", num_args = 1)]
     socket: PathBuf,
+
+    /// Network to query with client commands [default: primary network]
+    #[arg(long)]
+    network: Option<Network>,
 }
 
 #[derive(Subcommand, Debug)]
-#[allow(clippy::large_enum_variant)]
 enum IndexerCommand {
+    /// Tools commands
+    Tools {
+        #[command(subcommand)]
+        tools_command: ToolsCommand,
+    },
+
+    #[clap(flatten)]
+    Main(MainCommand),
+}
+
+/// Commands run in the main subsystem
+#[derive(Subcommand, Debug)]
+#[allow(clippy::large_enum_variant)]
+enum MainCommand {
     /// Database commands
     Database {
         #[command(subcommand)]
@@ -87,6 +116,11 @@ enum DatabaseCommand {
     /// Create a new mina indexer database to use with `mina-indexer start`
     Create(Box<DatabaseArgs>),
 
+    /// Add a network to an existing mina indexer database. It is indexed
+    /// alongside the primary network, in column families & keys scoped by its
+    /// name, & followed by `mina-indexer server start`.
+    AddNetwork(Box<DatabaseArgs>),
+
     /// Create a snapshot of a mina indexer database
     Snapshot {
         /// Full path to the snapshot file to be created
@@ -116,21 +150,121 @@ enum DatabaseCommand {
         #[arg(long)]
         json: bool,
     },
+
+    /// Re-encode blocks stored as JSON by older versions in the compact
+    /// binary encoding
+    MigrateBlocks {
+        /// Full path to a mina indexer database directory
+        #[arg(long)]
+        database_dir: PathBuf,
+    },
+
+    /// Check precomputed block file names against the block contents
+    VerifyBlocks {
+        /// Directory of precomputed blocks
+        #[arg(long)]
+        blocks_dir: PathBuf,
+
+        /// Output JSON data
+        #[arg(long)]
+        json: bool,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+enum ToolsCommand {
+    /// Bin_prot commands
+    #[cfg(feature = "loose_deserialization")]
+    Binprot {
+        #[command(subcommand)]
+        binprot_command: BinprotCommand,
+    },
+
+    /// Base58check commands
+    Base58 {
+        #[command(subcommand)]
+        base58_command: Base58Command,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+enum Base58Command {
+    /// Identify the kind of a base58check string, decode its payload & check
+    /// its checksum
+    Inspect {
+        /// Base58check string
+        input: String,
+
+        /// Output JSON data
+        #[arg(long)]
+        json: bool,
+    },
+}
+
+#[cfg(feature = "loose_deserialization")]
+#[derive(Subcommand, Debug)]
+enum BinprotCommand {
+    /// Decode a bin_prot payload & print it as JSON
+    Decode {
+        /// Path to the layout file describing the payload
+        #[arg(long)]
+        layout: PathBuf,
+
+        #[command(flatten)]
+        encoding: PayloadEncoding,
+
+        /// Payload to decode. If null, read from stdin.
+        payload: Option<String>,
+    },
+
+    /// Encode JSON as a bin_prot payload
+    Encode {
+        /// Path to the layout file describing the payload
+        #[arg(long)]
+        layout: PathBuf,
+
+        #[command(flatten)]
+        encoding: PayloadEncoding,
+
+        /// JSON to encode. If null, read from stdin.
+        json: Option<String>,
+    },
+}
+
+/// Text encoding of bin_prot payloads, raw bytes if neither is given
+#[cfg(feature = "loose_deserialization")]
+#[derive(Args, Debug)]
+struct PayloadEncoding {
+    /// Hex encoded payload
+    #[arg(long, conflicts_with = "base58check")]
+    hex: bool,
+
+    /// Base58check encoded payload with the given version byte
+    #[arg(long, value_name = "VERSION")]
+    base58check: Option<u8>,
 }
 
 #[tokio::main]
 pub async fn main() -> anyhow::Result<()> {
     let args = Cli::parse();
     let domain_socket_path = args.socket;
+    let network = args.network;
+
+    // tools are run directly so their errors reach the user
+    let command = match args.command {
+        IndexerCommand::Tools { tools_command } => return tools_command.run(),
+        IndexerCommand::Main(command) => command,
+    };
+
     Toplevel::new(|s| async move {
         s.start(SubsystemBuilder::new("Main", |s| async move {
-            match args.command {
-                IndexerCommand::Client(cli) => cli.run(domain_socket_path).await,
-                IndexerCommand::Database { db_command } => db_command.run(domain_socket_path).await,
-                IndexerCommand::Server { server_command } => {
+            match command {
+                MainCommand::Client(cli) => cli.run_on_network(domain_socket_path, network).await,
+                MainCommand::Database { db_command } => db_command.run(domain_socket_path).await,
+                MainCommand::Server { server_command } => {
                     server_command.run(s, domain_socket_path).await
                 }
-                IndexerCommand::Version => Ok(println!("{VERSION}")),
+                MainCommand::Version => Ok(println!("{VERSION}")),
             }
         }));
     })
@@ -160,6 +294,8 @@ impl ServerCommand {
         let database_dir = args.db.database_dir.clone();
         let web_hostname = args.web_hostname.clone();
         let web_port = args.web_port;
+        let graphql_limits = args.graphql_limits()?;
+        let upload_token = args.upload_token()?;
 
         // initialize logging
         stderrlog::new()
@@ -175,13 +311,34 @@ impl ServerCommand {
         debug!("Reading mina indexer config from store");
         let db = Arc::new(IndexerStore::new(&database_dir)?);
         let mut config = IndexerConfiguration::read_indexer_config(&db)?;
-        config.initialization_mode = mode;
+        config.initialization_mode = mode.clone();
+
+        // networks indexed alongside the primary network
+        let networks = db
+            .networks()
+            .iter()
+            .map(|network| {
+                let store = db.network_store(network)?;
+                let mut config = IndexerConfiguration::read_indexer_config(&store)?;
+                config.initialization_mode = mode.clone();
+                Ok((store, config))
+            })
+            .collect::<anyhow::Result<Vec<_>>>()?;
+
+        // uploads are only accepted with a token
+        let (upload_tx, upload_rx) = mpsc::channel(UPLOAD_CHANNEL_CAPACITY);
+        let uploader = upload_token.map(|token| Uploader {
+            token,
+            network: config.version.network.clone(),
+            genesis_state_hash: config.version.genesis.state_hash.clone(),
+            sender: upload_tx,
+        });
 
         info!("Starting the mina indexer filesystem watchers & UDS server");
         let store = db.clone();
 
         subsys.start(SubsystemBuilder::new("Indexer", move |s| {
-            config.start_indexer(s, store)
+            config.start_indexer(s, store, networks, upload_rx)
         }));
 
         info!("Starting the web server listening on {web_hostname}:{web_port}");
@@ -189,7 +346,7 @@ impl ServerCommand {
         let host = web_hostname.clone();
 
         subsys.start(SubsystemBuilder::new("Web Server", move |s| {
-            start_web_server(s, store, (host, web_port))
+            start_web_server(s, store, (host, web_port), graphql_limits, uploader)
         }));
 
         println!("GraphQL server started at: http://{web_hostname}:{web_port}/graphql");
@@ -205,6 +362,138 @@ impl ServerCommand {
     }
 }
 
+impl ToolsCommand {
+    fn run(self) -> anyhow::Result<()> {
+        match self {
+            #[cfg(feature = "loose_deserialization")]
+            Self::Binprot { binprot_command } => binprot_command.run(),
+            Self::Base58 { base58_command } => base58_command.run(),
+        }
+    }
+}
+
+impl Base58Command {
+    fn run(self) -> anyhow::Result<()> {
+        match self {
+            Self::Inspect { input, json } => {
+                let inspection = Base58Kind::inspect(input.trim())?;
+                if json {
+                    println!("{}", serde_json::to_string_pretty(&inspection)?)
+                } else {
+                    print!("{inspection}")
+                }
+
+                if !inspection.is_valid() {
+                    process::exit(1);
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(feature = "loose_deserialization")]
+impl BinprotCommand {
+    fn run(self) -> anyhow::Result<()> {
+        match self {
+            Self::Decode {
+                layout,
+                encoding,
+                payload,
+            } => {
+                let rule = read_layout(&layout)?;
+                let payload = match payload {
+                    Some(payload) => payload.into_bytes(),
+                    None => read_stdin()?,
+                };
+
+                let bytes = encoding.decode(&payload)?;
+                let value = json::decode(&bytes, &rule)?;
+                println!("{}", serde_json::to_string_pretty(&value)?);
+            }
+            Self::Encode {
+                layout,
+                encoding,
+                json,
+            } => {
+                let rule = read_layout(&layout)?;
+                let json = match json {
+                    Some(json) => json.into_bytes(),
+                    None => read_stdin()?,
+                };
+
+                let value = serde_json::from_slice(&json).context("Invalid JSON payload")?;
+                let bytes = json::encode(&value, &rule)?;
+                encoding.encode(&bytes)?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(feature = "loose_deserialization")]
+impl PayloadEncoding {
+    fn decode(&self, payload: &[u8]) -> anyhow::Result<Vec<u8>> {
+        if !self.hex && self.base58check.is_none() {
+            return Ok(payload.to_vec());
+        }
+
+        let payload = std::str::from_utf8(payload)
+            .context("Payload is not valid UTF-8")?
+            .trim();
+        if let Some(version) = self.base58check {
+            let bytes = bs58::decode(payload)
+                .with_check(Some(version))
+                .into_vec()
+                .context("Invalid base58check payload")?;
+
+            // drop the version byte
+            Ok(bytes[1..].to_vec())
+        } else {
+            hex::decode(payload.trim_start_matches("0x")).context("Invalid hex payload")
+        }
+    }
+
+    /// Writes the encoded bytes to stdout
+    fn encode(&self, bytes: &[u8]) -> anyhow::Result<()> {
+        if let Some(version) = self.base58check {
+            println!(
+                "{}",
+                bs58::encode(bytes)
+                    .with_check_version(version)
+                    .into_string()
+            );
+        } else if self.hex {
+            println!("{}", hex::encode(bytes));
+        } else {
+            io::stdout().write_all(bytes)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Reads a layout file, either a full layout or a bare bin_prot rule
+#[cfg(feature = "loose_deserialization")]
+fn read_layout(path: &Path) -> anyhow::Result<BinProtRule> {
+    let contents = fs::read(path).with_context(|| format!("Cannot read layout {path:#?}"))?;
+    match serde_json::from_slice::<Layout>(&contents) {
+        Ok(layout) => Ok(layout.bin_prot_rule),
+        Err(_) => {
+            serde_json::from_slice(&contents).with_context(|| format!("Invalid layout {path:#?}"))
+        }
+    }
+}
+
+#[cfg(feature = "loose_deserialization")]
+fn read_stdin() -> anyhow::Result<Vec<u8>> {
+    let mut buf = vec![];
+    io::stdin().read_to_end(&mut buf)?;
+    Ok(buf)
+}
+
 impl DatabaseCommand {
     async fn run(self, domain_socket_path: PathBuf) -> anyhow::Result<()> {
         // initialize logging
@@ -228,6 +517,30 @@ impl DatabaseCommand {
                     }
                 )
             }
+            Self::MigrateBlocks { database_dir } => {
+                if !database_dir.exists() {
+                    error!("Database dir {database_dir:#?} does not exist");
+                    process::exit(1);
+                }
+
+                info!("Migrating blocks in database dir {database_dir:#?}");
+                let db = IndexerStore::new(&database_dir)?;
+                let num_migrated = db.migrate_block_encoding()?;
+                info!("Migrated {num_migrated} blocks");
+            }
+            Self::VerifyBlocks { blocks_dir, json } => {
+                info!("Verifying block files in {blocks_dir:#?}");
+                let verification = verify_blocks_dir(&blocks_dir)?;
+                if json {
+                    println!("{}", serde_json::to_string_pretty(&verification)?)
+                } else {
+                    print!("{verification}")
+                }
+
+                if !verification.is_ok() {
+                    process::exit(1);
+                }
+            }
             Self::Snapshot {
                 output_path,
                 database_dir,
@@ -304,6 +617,61 @@ impl DatabaseCommand {
                     }
                 }
             }
+            Self::AddNetwork(args) => {
+                let database_dir = args.database_dir.clone();
+                if !database_dir.exists() {
+                    error!("Database dir {database_dir:#?} does not exist");
+                    process::exit(1);
+                }
+
+                debug!("Building mina indexer configuration");
+                let mut config = process_indexer_configuration(
+                    (*args).into(),
+                    InitializationMode::BuildDB,
+                    domain_socket_path,
+                )?;
+                let network = config.version.network.clone();
+
+                // the primary network is indexed via `database create`
+                {
+                    let db = IndexerStore::new(&database_dir)?;
+                    if db.database.get(IndexerStore::CHAIN_ID_KEY)?.is_none() {
+                        bail!("Create the primary network of {database_dir:#?} first");
+                    }
+                    if db.get_current_network()? == network {
+                        bail!("{network} is the primary network of {database_dir:#?}");
+                    }
+                }
+
+                info!("Adding network {network} to database dir {database_dir:#?}");
+                let db = Arc::new(IndexerStore::new_with_networks(
+                    &database_dir,
+                    &[network.clone()],
+                )?);
+                let store = db.network_store(&network)?;
+                if store
+                    .database
+                    .get(store.fixed_key(IndexerStore::INDEXER_CONFIG_KEY))?
+                    .is_some()
+                {
+                    config.initialization_mode = InitializationMode::Sync;
+                }
+
+                tokio::select! {
+                    // wait for SIGINT
+                    _ = tokio::signal::ctrl_c() => {
+                        info!("SIGINT received");
+                        store.database.cancel_all_background_work(true);
+                    }
+
+                    // build the network's part of the database
+                    res = config.initialize_indexer_database(&store) => {
+                        if let Err(e) = res {
+                            error!("Failed to initialize {network} indexer database: {e}");
+                        };
+                    }
+                }
+            }
         }
         Ok(())
     }
@@ -319,6 +687,7 @@ fn process_indexer_configuration(
     let genesis_hash = args.db.genesis_hash;
     let blocks_dir = args.db.blocks_dir;
     let staking_ledgers_dir = args.db.staking_ledgers_dir;
+    let block_sources = args.db.block_sources;
     let prune_interval = args.db.prune_interval;
     let canonical_threshold = args.db.canonical_threshold;
     let canonical_update_threshold = args.db.canonical_update_threshold;
@@ -349,41 +718,42 @@ fn process_indexer_configuration(
         }
     }
 
-    // pick up protocol constants from the given file or use defaults
-    // let genesis_constants = args.db.genesis_constants;
-    // let constraint_system_digests = args.db.constraint_system_digests;
-    // let protocol_txn_version_digest = args.db.protocol_txn_version_digest;
-    // let protocol_network_version_digest =
-    // args.db.protocol_network_version_digest; let genesis_constants =
-    // protocol_constants(genesis_constants)?; let constraint_system_digests =
-    // constraint_system_digests.unwrap_or(
-    //     MAINNET_CONSTRAINT_SYSTEM_DIGESTS
-    //         .iter()
-    //         .map(|x| x.to_string())
-    //         .collect(),
-    // );
+    // network profile, overridden by the genesis constants & digests
+    let mut profile =
+        NetworkProfile::from_path_or_genesis(args.db.network_profile.as_deref(), &genesis_hash)?;
+    if let Some(path) = args.db.genesis_constants {
+        info!("Overriding protocol constants with {path:#?}");
+        profile.override_constants(serde_json::from_slice(&fs::read(path)?)?);
+    }
+    if let Some(digests) = args.db.constraint_system_digests {
+        profile.indexer.constraint_system_digests = Some(digests);
+    }
+    if let Some(digest) = args.db.protocol_txn_version_digest {
+        profile.indexer.protocol_txn_version_digest = Some(digest);
+    }
+    if let Some(digest) = args.db.protocol_network_version_digest {
+        profile.indexer.protocol_network_version_digest = Some(digest);
+    }
+    profile
+        .indexer
+        .network
+        .get_or_insert_with(|| args.db.network.to_string());
 
     // indexer version
-    let network = args.db.network;
-    let (version, chain_id, genesis) = if genesis_hash == HARDFORK_GENESIS_HASH {
-        (PcbVersion::V2, ChainId::v2(), GenesisVersion::v2())
-    } else {
-        (PcbVersion::V1, ChainId::v1(), GenesisVersion::v1())
-    };
+    let version = IndexerVersion::from_profile(&profile)?;
+    info!(
+        "Indexing {} from genesis {} (chain id {})",
+        version.network, version.genesis.state_hash, version.chain_id
+    );
 
-    let genesis_ledger = parse_genesis_ledger(args.db.genesis_ledger, &version)?;
-    let version = IndexerVersion {
-        network,
-        version,
-        chain_id,
-        genesis,
-    };
+    let genesis_ledger = parse_genesis_ledger(args.db.genesis_ledger, &profile, &version.version)?;
 
     Ok(IndexerConfiguration {
         genesis_ledger,
         version,
         blocks_dir,
         staking_ledgers_dir,
+        block_sources,
         prune_interval,
         canonical_threshold,
         canonical_update_threshold,
@@ -402,6 +772,7 @@ fn process_indexer_configuration(
 
 fn parse_genesis_ledger(
     path: Option<PathBuf>,
+    profile: &NetworkProfile,
     version: &PcbVersion,
 ) -> anyhow::Result<GenesisLedger> {
     let genesis_ledger = if let Some(path) = path {
@@ -418,6 +789,9 @@ fn parse_genesis_ledger(
                 genesis
             }
         }
+    } else if let Some(genesis) = profile.genesis_ledger()? {
+        info!("Using the network profile's genesis ledger");
+        genesis
     } else {
         info!("Using default {} genesis ledger", version);
         match version {
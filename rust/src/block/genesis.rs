@@ -3,6 +3,7 @@ use crate::{
     block::precomputed::PrecomputedBlock,
     constants::{HARDFORK_GENESIS_BLOCKCHAIN_LENGTH, HARDFORK_GENESIS_HASH, MAINNET_GENESIS_HASH},
 };
+use std::path::Path;
 
 #[derive(Debug)]
 pub struct GenesisBlock(pub PrecomputedBlock, pub u64);
@@ -58,6 +59,12 @@ impl GenesisBlock {
 }
 
 impl GenesisBlock {
+    /// Parses a custom network's genesis block file
+    pub fn parse_file(path: &Path, version: PcbVersion) -> anyhow::Result<Self> {
+        let size = path.metadata()?.len();
+        Ok(Self(PrecomputedBlock::parse_file(path, version)?, size))
+    }
+
     pub fn to_precomputed(self) -> PrecomputedBlock {
         self.0
     }
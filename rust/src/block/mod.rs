@@ -7,7 +7,9 @@ pub mod genesis_state_hash;
 pub mod parser;
 pub mod precomputed;
 pub mod previous_state_hash;
+pub mod source;
 pub mod store;
+pub mod verify;
 pub mod vrf_output;
 
 mod post_hardfork;
@@ -225,9 +227,11 @@ impl std::cmp::Ord for Block {
     }
 }
 
+/// Well-formed placeholder whose all-ones hash exceeds the field modulus, so
+/// it's never a real state hash
 impl std::default::Default for StateHash {
     fn default() -> Self {
-        Self("3NLDEFAULTDEFAULTDEFAULTDEFAULTDEFAULTDEFAULTDEFAULT".into())
+        Self("3NLydwEHsAWZ3YrTG2UXjh9zXnSfnzkKMeFEAkqteeNQHHRdLxAT".into())
     }
 }
 
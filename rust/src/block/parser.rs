@@ -64,6 +64,12 @@ impl BlockParser {
         }
     }
 
+    /// Parse blocks of the chains in `chain_data`, e.g. a custom network's
+    pub fn with_chain_data(mut self, chain_data: ChainData) -> Self {
+        self.chain_data = chain_data;
+        self
+    }
+
     /// Returns a new block parser which employs canonical chain discovery
     pub async fn new_with_canonical_chain_discovery(
         blocks_dir: &Path,
@@ -235,7 +241,7 @@ impl BlockParser {
             .0
             .get(&genesis_state_hash)
             .cloned()
-            .expect("PCB version");
+            .ok_or_else(|| anyhow!("Unknown genesis state hash {genesis_state_hash}: {path:?}"))?;
 
         // if the PCB version changed, change block parser version
         if curr_pcb_version != new_pcb_version {
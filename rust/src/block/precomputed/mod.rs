@@ -9,19 +9,20 @@ use super::{
     post_hardfork::{
         account_accessed::AccountAccessed, account_created::AccountCreated, token_used::TokenUsed,
     },
+    verify::verify_block_file,
     Block, StateHash, VrfOutput,
 };
 use crate::{
     base::{blockchain_length::BlockchainLength, public_key::PublicKey},
     canonicity::Canonicity,
-    chain::Network,
+    chain::{profile::ProtocolParams, Network},
     command::{
         signed::{SignedCommand, TxnHash},
         UserCommandWithStatus, UserCommandWithStatusT,
     },
     constants::*,
     ledger::{
-        coinbase::{Coinbase, CoinbaseFeeTransfer, CoinbaseKind},
+        coinbase::{CoinbaseFeeTransfer, CoinbaseKind},
         token::TokenAddress,
         username::Username,
         LedgerHash,
@@ -30,6 +31,7 @@ use crate::{
     snark_work::SnarkWorkSummary,
     store::username::UsernameUpdate,
 };
+use anyhow::bail;
 use serde::{Deserialize, Serialize};
 use std::{
     collections::{BTreeMap, HashMap, HashSet},
@@ -74,48 +76,103 @@ impl PrecomputedBlock {
         let state_hash = block_file_contents.state_hash;
         let blockchain_length = block_file_contents.blockchain_length;
 
-        match version {
-            PcbVersion::V1 => {
-                let BlockFileV1 {
-                    scheduled_time,
-                    protocol_state,
-                    staged_ledger_diff,
-                } = serde_json::from_slice(&block_file_contents.contents)?;
-                Ok(Self::V1(Box::new(PrecomputedBlockV1 {
-                    state_hash,
-                    scheduled_time,
-                    blockchain_length,
-                    network: block_file_contents.network,
-                    protocol_state: protocol_state.into(),
-                    staged_ledger_diff: staged_ledger_diff.into(),
-                })))
-            }
-            PcbVersion::V2 => {
-                let BlockFileV2 {
-                    version: _,
-                    data:
-                        BlockFileDataV2 {
-                            scheduled_time,
-                            protocol_state,
-                            staged_ledger_diff,
-                            tokens_used,
-                            accounts_accessed,
-                            accounts_created,
-                        },
-                } = serde_json::from_slice(&block_file_contents.contents)?;
-                Ok(Self::V2(PrecomputedBlockV2 {
-                    state_hash,
+        Ok(match version {
+            PcbVersion::V1 => Self::from_block_file_v1(
+                block_file_contents.network,
+                state_hash,
+                blockchain_length,
+                serde_json::from_slice(&block_file_contents.contents)?,
+            ),
+            PcbVersion::V2 => Self::from_block_file_v2(
+                block_file_contents.network,
+                state_hash,
+                blockchain_length,
+                serde_json::from_slice(&block_file_contents.contents)?,
+            ),
+        })
+    }
+
+    /// Decodes the precomputed block from already parsed JSON, taking its
+    /// height from the protocol state
+    pub fn from_json_value(
+        network: Network,
+        state_hash: StateHash,
+        value: serde_json::Value,
+        version: PcbVersion,
+    ) -> anyhow::Result<Self> {
+        let mut block = match version {
+            PcbVersion::V1 => Self::from_block_file_v1(
+                network,
+                state_hash,
+                BlockchainLength::default(),
+                serde_json::from_value(value)?,
+            ),
+            PcbVersion::V2 => Self::from_block_file_v2(
+                network,
+                state_hash,
+                BlockchainLength::default(),
+                serde_json::from_value(value)?,
+            ),
+        };
+
+        let blockchain_length = block.consensus_blockchain_length().into();
+        match &mut block {
+            Self::V1(v1) => v1.blockchain_length = blockchain_length,
+            Self::V2(v2) => v2.blockchain_length = blockchain_length,
+        }
+        Ok(block)
+    }
+
+    fn from_block_file_v1(
+        network: Network,
+        state_hash: StateHash,
+        blockchain_length: BlockchainLength,
+        block_file: BlockFileV1,
+    ) -> Self {
+        let BlockFileV1 {
+            scheduled_time,
+            protocol_state,
+            staged_ledger_diff,
+        } = block_file;
+        Self::V1(Box::new(PrecomputedBlockV1 {
+            state_hash,
+            scheduled_time,
+            blockchain_length,
+            network,
+            protocol_state: protocol_state.into(),
+            staged_ledger_diff: staged_ledger_diff.into(),
+        }))
+    }
+
+    fn from_block_file_v2(
+        network: Network,
+        state_hash: StateHash,
+        blockchain_length: BlockchainLength,
+        block_file: BlockFileV2,
+    ) -> Self {
+        let BlockFileV2 {
+            version: _,
+            data:
+                BlockFileDataV2 {
                     scheduled_time,
-                    blockchain_length,
-                    network: block_file_contents.network,
                     protocol_state,
                     staged_ledger_diff,
                     tokens_used,
                     accounts_accessed,
                     accounts_created,
-                }))
-            }
-        }
+                },
+        } = block_file;
+        Self::V2(PrecomputedBlockV2 {
+            state_hash,
+            scheduled_time,
+            blockchain_length,
+            network,
+            protocol_state,
+            staged_ledger_diff,
+            tokens_used,
+            accounts_accessed,
+            accounts_created,
+        })
     }
 
     pub fn new(
@@ -137,8 +194,23 @@ impl PrecomputedBlock {
         Ok(precomputed_block)
     }
 
-    /// Parses the precomputed block if the path is a valid block file
+    /// Parses the precomputed block if the path is a valid block file whose
+    /// name agrees with its contents
     pub fn parse_file(path: &Path, version: PcbVersion) -> anyhow::Result<Self> {
+        let precomputed_block = Self::parse_file_unverified(path, version)?;
+        let issues = verify_block_file(path, &precomputed_block);
+        if !issues.is_empty() {
+            bail!(
+                "Block file name disagrees with its contents {}: {issues:?}",
+                path.display()
+            )
+        }
+        Ok(precomputed_block)
+    }
+
+    /// Parses the precomputed block, trusting the file name's network, height
+    /// & state hash
+    pub fn parse_file_unverified(path: &Path, version: PcbVersion) -> anyhow::Result<Self> {
         let (network, blockchain_length, state_hash) = extract_network_height_hash(path);
         let contents = std::fs::read(path)?;
         let precomputed_block = PrecomputedBlock::from_file_contents(
@@ -298,6 +370,7 @@ impl PrecomputedBlock {
     /// - new coinbase receiver option
     pub fn accounts_created(
         &self,
+        protocol: &ProtocolParams,
     ) -> (
         BTreeMap<PublicKey, BTreeMap<TokenAddress, u64>>,
         Option<PublicKey>,
@@ -307,12 +380,13 @@ impl PrecomputedBlock {
 
         // maybe coinbase receiver
         if let Some(bal) = self.coinbase_receiver_balance() {
-            if [
-                MAINNET_COINBASE_REWARD - MAINNET_ACCOUNT_CREATION_FEE.0,
-                // supercharged
-                2 * MAINNET_COINBASE_REWARD - MAINNET_ACCOUNT_CREATION_FEE.0,
-            ]
-            .contains(&bal)
+            if [false, true]
+                .map(|supercharged| {
+                    protocol
+                        .coinbase(supercharged)
+                        .saturating_sub(protocol.account_creation_fee)
+                })
+                .contains(&bal)
             {
                 account_balances.insert(
                     self.coinbase_receiver(),
@@ -387,9 +461,10 @@ impl PrecomputedBlock {
         }
     }
 
-    pub fn snarked_ledger_hash(&self) -> Option<LedgerHash> {
+    /// Hash of the ledger proven by the block's latest ledger proof
+    pub fn snarked_ledger_hash(&self) -> LedgerHash {
         match self {
-            Self::V1(v1) => Some(LedgerHash::from_hashv1(
+            Self::V1(v1) => LedgerHash::from_hashv1(
                 v1.protocol_state
                     .body
                     .t
@@ -399,8 +474,37 @@ impl PrecomputedBlock {
                     .t
                     .snarked_ledger_hash
                     .to_owned(),
-            )),
-            Self::V2(_v2) => None,
+            ),
+            Self::V2(v2) => v2
+                .protocol_state
+                .body
+                .blockchain_state
+                .ledger_proof_statement
+                .target
+                .first_pass_ledger
+                .to_owned(),
+        }
+    }
+
+    pub fn genesis_ledger_hash(&self) -> LedgerHash {
+        match self {
+            Self::V1(v1) => LedgerHash::from_hashv1(
+                v1.protocol_state
+                    .body
+                    .t
+                    .t
+                    .blockchain_state
+                    .t
+                    .t
+                    .genesis_ledger_hash
+                    .to_owned(),
+            ),
+            Self::V2(v2) => v2
+                .protocol_state
+                .body
+                .blockchain_state
+                .genesis_ledger_hash
+                .to_owned(),
         }
     }
 
@@ -648,7 +752,10 @@ impl PrecomputedBlock {
             HashSet::from([self.block_creator(), self.block_stake_winner()]);
 
         // coinbase receiver if coinbase is applied
-        if Coinbase::from_precomputed(self).is_coinbase_applied() {
+        if CoinbaseKind::from_precomputed(self)
+            .iter()
+            .any(|kind| *kind != CoinbaseKind::Zero)
+        {
             public_keys.insert(self.coinbase_receiver());
         }
 
@@ -690,6 +797,25 @@ impl PrecomputedBlock {
     // Consensus state //
     /////////////////////
 
+    /// Blockchain length from the protocol state, as opposed to the file name
+    pub fn consensus_blockchain_length(&self) -> u32 {
+        match self {
+            Self::V1(v1) => {
+                v1.protocol_state
+                    .body
+                    .t
+                    .t
+                    .consensus_state
+                    .t
+                    .t
+                    .blockchain_length
+                    .t
+                    .t
+            }
+            Self::V2(v2) => v2.protocol_state.body.consensus_state.blockchain_length.0,
+        }
+    }
+
     pub fn total_currency(&self) -> u64 {
         match self {
             Self::V1(v1) => {
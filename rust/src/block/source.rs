@@ -0,0 +1,615 @@
+//! Precomputed block sources other than a directory of canonically named
+//! block files
+//!
+//! - directories of block files with arbitrary names
+//! - `.tar` & `.tar.gz` bundles of block files
+//! - newline-delimited JSON logs, e.g. the daemon's
+//!   `--precomputed-blocks-file`, which can also be tailed as they grow
+//!
+//! Sources are read in batches of [BLOCK_SOURCE_BATCH_SIZE] blocks, each
+//! sorted by height, so a source never has to fit in memory.
+//!
+//! Heights are taken from the protocol state. State hashes are taken from the
+//! contents when present (`{"state_hash": .., "precomputed_block": ..}`,
+//! optionally nested in a structured log's `metadata`, or `{"3N..": block}`),
+//! otherwise from a state hash in the file name. Blocks without either are
+//! named by the `previous_state_hash` of their child, provided they are the
+//! only such block at their height.
+//!
+//! The indexer cannot compute a state hash from the block contents (see
+//! [super::verify]), so a block without a hash & without a child in the
+//! source, e.g. the tip of a bare NDJSON log, cannot be named. These blocks
+//! are reported & skipped; wrap them with their state hash to ingest them.
+
+use super::{
+    extract_block_height, is_valid_block_file,
+    precomputed::{PcbVersion, PrecomputedBlock},
+};
+use crate::{base::state_hash::StateHash, chain::Network};
+use anyhow::bail;
+use flate2::read::GzDecoder;
+use glob::glob;
+use log::{debug, trace, warn};
+use serde_json::Value;
+use std::{
+    collections::{HashMap, HashSet},
+    fs::File,
+    io::{BufRead, BufReader, Read, Seek, SeekFrom},
+    path::{Path, PathBuf},
+};
+
+/// Number of blocks read from a source before they're named & pipelined
+pub const BLOCK_SOURCE_BATCH_SIZE: usize = 1000;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BlockSource {
+    /// Directory of block files with arbitrary names
+    Dir(PathBuf),
+
+    /// Newline-delimited JSON log of precomputed blocks
+    Ndjson(PathBuf),
+
+    /// Tarball of block files, optionally gzipped
+    Tar { path: PathBuf, gzip: bool },
+}
+
+/// Block read from a source whose state hash may not be known yet
+#[derive(Debug, Clone)]
+struct SourceBlock {
+    state_hash: Option<StateHash>,
+    block: PrecomputedBlock,
+    bytes: u64,
+
+    /// Where the block was read from, for reporting
+    origin: String,
+}
+
+/// Names source blocks after their children's `previous_state_hash`, across
+/// batches
+#[derive(Debug)]
+struct BlockNamer {
+    /// Blocks waiting for a child to name them
+    pending: Vec<SourceBlock>,
+
+    /// Recently named state hashes & their heights
+    named: HashMap<StateHash, u32>,
+
+    /// Highest block seen
+    max_length: u32,
+
+    /// Network's transition frontier length
+    k: u32,
+}
+
+/// Follows a growing NDJSON block log
+pub struct NdjsonTail {
+    pub path: PathBuf,
+    offset: u64,
+
+    /// Number of complete lines read
+    num_lines: usize,
+
+    /// Trailing bytes of a partially written line
+    partial: Vec<u8>,
+
+    namer: BlockNamer,
+}
+
+impl BlockSource {
+    /// Block source corresponding to the path's type & extension
+    pub fn from_path(path: &Path) -> anyhow::Result<Self> {
+        if path.is_dir() {
+            return Ok(Self::Dir(path.to_path_buf()));
+        }
+
+        let file_name = path
+            .file_name()
+            .and_then(|name| name.to_str())
+            .unwrap_or_default();
+        if file_name.ends_with(".tar.gz") || file_name.ends_with(".tgz") {
+            return Ok(Self::Tar {
+                path: path.to_path_buf(),
+                gzip: true,
+            });
+        }
+
+        match path.extension().and_then(|ext| ext.to_str()) {
+            Some("tar") => Ok(Self::Tar {
+                path: path.to_path_buf(),
+                gzip: false,
+            }),
+            Some("ndjson" | "jsonl" | "log") => Ok(Self::Ndjson(path.to_path_buf())),
+            _ => bail!("Unknown block source {}", path.display()),
+        }
+    }
+
+    pub fn path(&self) -> &Path {
+        match self {
+            Self::Dir(path) | Self::Ndjson(path) | Self::Tar { path, .. } => path,
+        }
+    }
+
+    /// Reads the source's blocks in batches of `batch_size`, passing each
+    /// batch's named blocks, sorted by height, with their sizes (bytes) to
+    /// `add_batch`
+    ///
+    /// Blocks whose state hash cannot be determined within `k` blocks of the
+    /// highest block are reported & skipped
+    pub fn read_batches<F>(&self, batch_size: usize, k: u32, mut add_batch: F) -> anyhow::Result<()>
+    where
+        F: FnMut(Vec<(PrecomputedBlock, u64)>) -> anyhow::Result<()>,
+    {
+        let mut namer = BlockNamer::new(k);
+        let mut batch = Vec::with_capacity(batch_size);
+        let mut add_block = |block: SourceBlock| {
+            batch.push(block);
+            if batch.len() >= batch_size {
+                let named = namer.name(std::mem::take(&mut batch));
+                if !named.is_empty() {
+                    add_batch(named)?;
+                }
+            }
+            Ok(())
+        };
+
+        match self {
+            Self::Dir(dir) => read_dir_blocks(dir, &mut add_block)?,
+            Self::Ndjson(path) => {
+                read_ndjson_blocks(path, BufReader::new(File::open(path)?), 0, &mut add_block)?;
+            }
+            Self::Tar { path, gzip } => {
+                let file = BufReader::new(File::open(path)?);
+                if *gzip {
+                    read_tar_blocks(path, GzDecoder::new(file), &mut add_block)?
+                } else {
+                    read_tar_blocks(path, file, &mut add_block)?
+                }
+            }
+        };
+
+        let named = namer.name(batch);
+        if !named.is_empty() {
+            add_batch(named)?;
+        }
+
+        namer.report_unnamed();
+        Ok(())
+    }
+}
+
+impl NdjsonTail {
+    /// Tail the log from its current end, naming blocks within `k` of the
+    /// highest block
+    pub fn new(path: &Path, k: u32) -> anyhow::Result<Self> {
+        let offset = if path.exists() {
+            path.metadata()?.len()
+        } else {
+            0
+        };
+
+        Ok(Self {
+            offset,
+            ..Self::from_start(path, k)
+        })
+    }
+
+    /// Tail the log from the beginning
+    pub fn from_start(path: &Path, k: u32) -> Self {
+        Self {
+            path: path.to_path_buf(),
+            offset: 0,
+            num_lines: 0,
+            partial: vec![],
+            namer: BlockNamer::new(k),
+        }
+    }
+
+    /// Reads the blocks appended since the last poll, sorted by height
+    pub fn poll(&mut self) -> anyhow::Result<Vec<(PrecomputedBlock, u64)>> {
+        if !self.path.exists() {
+            return Ok(vec![]);
+        }
+
+        let mut file = File::open(&self.path)?;
+        let len = file.metadata()?.len();
+
+        // the log was truncated or rotated
+        if len < self.offset {
+            debug!(
+                "Block log {} truncated, reading from start",
+                self.path.display()
+            );
+            self.offset = 0;
+            self.num_lines = 0;
+            self.partial.clear();
+        }
+
+        file.seek(SeekFrom::Start(self.offset))?;
+        let mut bytes = std::mem::take(&mut self.partial);
+        let num_read = file.read_to_end(&mut bytes)? as u64;
+        self.offset += num_read;
+
+        // keep the incomplete last line for the next poll
+        let complete = match bytes.iter().rposition(|b| *b == b'\n') {
+            Some(n) => {
+                self.partial = bytes.split_off(n + 1);
+                bytes
+            }
+            None => {
+                self.partial = bytes;
+                return Ok(vec![]);
+            }
+        };
+
+        let mut blocks = vec![];
+        self.num_lines += read_ndjson_blocks(
+            &self.path,
+            complete.as_slice(),
+            self.num_lines,
+            &mut |block| {
+                blocks.push(block);
+                Ok(())
+            },
+        )?;
+
+        Ok(self.namer.name(blocks))
+    }
+}
+
+impl BlockNamer {
+    fn new(k: u32) -> Self {
+        Self {
+            pending: vec![],
+            named: HashMap::new(),
+            max_length: 0,
+            k,
+        }
+    }
+
+    /// Names what it can of the blocks & the pending blocks. Returns the named
+    /// blocks, sorted by height.
+    ///
+    /// Pending blocks more than `k` below the highest block seen are dropped
+    /// as their forks may never be named.
+    fn name(&mut self, blocks: Vec<SourceBlock>) -> Vec<(PrecomputedBlock, u64)> {
+        if let Some(max_length) = blocks
+            .iter()
+            .map(|block| block.block.blockchain_length())
+            .max()
+        {
+            self.max_length = self.max_length.max(max_length);
+        }
+
+        let pending = std::mem::take(&mut self.pending);
+        let named = name_blocks(
+            pending.into_iter().chain(blocks).collect(),
+            &self.named,
+            &mut self.pending,
+        );
+        for (block, _) in named.iter() {
+            self.named
+                .insert(block.state_hash(), block.blockchain_length());
+        }
+
+        let min_length = self.max_length.saturating_sub(self.k);
+        self.named.retain(|_, length| *length >= min_length);
+        self.pending.retain(|pending| {
+            let keep = pending.block.blockchain_length() >= min_length;
+            if !keep {
+                warn!(
+                    "Dropping block at height {} from {} whose state hash could not be determined",
+                    pending.block.blockchain_length(),
+                    pending.origin,
+                );
+            }
+            keep
+        });
+
+        named
+    }
+
+    /// Reports the blocks which were never named
+    fn report_unnamed(self) {
+        for pending in self.pending {
+            warn!(
+                "Skipping block at height {} from {} whose state hash could not be determined",
+                pending.block.blockchain_length(),
+                pending.origin,
+            );
+        }
+    }
+}
+
+/// Parses a precomputed block from JSON contents, returning `None` if the
+/// contents aren't a precomputed block
+///
+/// `name` is the file name, searched for a network prefix & state hash
+pub fn parse_block_contents(
+    name: Option<&str>,
+    contents: &[u8],
+) -> anyhow::Result<Option<(Option<StateHash>, PrecomputedBlock)>> {
+    let value: Value = serde_json::from_slice(contents)?;
+    let Some((state_hash, block)) = unwrap_block(value, None) else {
+        return Ok(None);
+    };
+
+    let version = if block.get("data").is_some() && block.get("version").is_some() {
+        PcbVersion::V2
+    } else {
+        PcbVersion::V1
+    };
+
+    // only canonically named files carry a network prefix
+    let network = name
+        .filter(|name| is_valid_block_file(name))
+        .and_then(|name| name.split_once('-'))
+        .map_or(Network::default(), |(network, _)| network.into());
+    let state_hash = state_hash.or_else(|| name.and_then(find_state_hash));
+
+    // decoded from the parsed JSON, the height comes from the protocol state
+    let block = PrecomputedBlock::from_json_value(
+        network,
+        state_hash.clone().unwrap_or_else(|| "".into()),
+        block,
+        version,
+    )?;
+
+    Ok(Some((state_hash, block)))
+}
+
+/// Strips state hash-carrying wrappers from the block JSON
+fn unwrap_block(value: Value, state_hash: Option<StateHash>) -> Option<(Option<StateHash>, Value)> {
+    let Value::Object(mut object) = value else {
+        return None;
+    };
+
+    let state_hash = object
+        .get("state_hash")
+        .and_then(Value::as_str)
+        .filter(|hash| StateHash::is_valid(hash))
+        .map(Into::into)
+        .or(state_hash);
+
+    // bare block
+    if object.contains_key("protocol_state")
+        || (object.contains_key("version") && object.contains_key("data"))
+    {
+        object.remove("state_hash");
+        return Some((state_hash, Value::Object(object)));
+    }
+
+    // {"state_hash": .., "precomputed_block": ..}
+    if let Some(block) = object.remove("precomputed_block") {
+        return unwrap_block(block, state_hash);
+    }
+
+    // structured daemon log line
+    if let Some(metadata) = object.remove("metadata") {
+        return unwrap_block(metadata, state_hash);
+    }
+
+    // {"3N..": block}
+    if object.len() == 1 {
+        let (key, block) = object.into_iter().next()?;
+        if StateHash::is_valid(&key) {
+            return unwrap_block(block, Some(key.into()));
+        }
+    }
+
+    None
+}
+
+/// Finds a state hash among the name's `-`, `_` & `.` separated parts
+fn find_state_hash(name: &str) -> Option<StateHash> {
+    name.split(['-', '_', '.'])
+        .find(|part| StateHash::is_valid(part))
+        .map(Into::into)
+}
+
+fn read_dir_blocks<F>(dir: &Path, add_block: &mut F) -> anyhow::Result<()>
+where
+    F: FnMut(SourceBlock) -> anyhow::Result<()>,
+{
+    // canonically named files first, in height order
+    let mut paths: Vec<_> = glob(&format!("{}/*.json", dir.display()))?
+        .flatten()
+        .collect();
+    paths.sort_by_cached_key(|path| {
+        is_valid_block_file(path)
+            .then(|| extract_block_height(path))
+            .unwrap_or(u32::MAX)
+    });
+
+    for path in paths {
+        let contents = std::fs::read(&path)?;
+        let name = path.file_name().and_then(|name| name.to_str());
+
+        match parse_block_contents(name, &contents) {
+            Ok(Some((state_hash, block))) => add_block(SourceBlock {
+                state_hash,
+                block,
+                bytes: contents.len() as u64,
+                origin: path.display().to_string(),
+            })?,
+            Ok(None) => trace!("Not a precomputed block {}", path.display()),
+            Err(e) => warn!("Failed to parse {}: {e}", path.display()),
+        }
+    }
+
+    Ok(())
+}
+
+fn read_tar_blocks<R, F>(tarball: &Path, reader: R, add_block: &mut F) -> anyhow::Result<()>
+where
+    R: Read,
+    F: FnMut(SourceBlock) -> anyhow::Result<()>,
+{
+    let mut archive = tar::Archive::new(reader);
+    for entry in archive.entries()? {
+        let mut entry = entry?;
+        if !entry.header().entry_type().is_file() {
+            continue;
+        }
+
+        let path = entry.path()?.to_path_buf();
+        if path.extension().and_then(|ext| ext.to_str()) != Some("json") {
+            continue;
+        }
+
+        let mut contents = vec![];
+        entry.read_to_end(&mut contents)?;
+
+        let name = path.file_name().and_then(|name| name.to_str());
+        match parse_block_contents(name, &contents) {
+            Ok(Some((state_hash, block))) => add_block(SourceBlock {
+                state_hash,
+                block,
+                bytes: contents.len() as u64,
+                origin: format!("{}:{}", tarball.display(), path.display()),
+            })?,
+            Ok(None) => trace!("Not a precomputed block {}", path.display()),
+            Err(e) => warn!("Failed to parse {}: {e}", path.display()),
+        }
+    }
+
+    Ok(())
+}
+
+/// Reads the log's blocks, numbering lines from `first_line`. Returns the
+/// number of lines read.
+fn read_ndjson_blocks<R, F>(
+    log: &Path,
+    reader: R,
+    first_line: usize,
+    add_block: &mut F,
+) -> anyhow::Result<usize>
+where
+    R: BufRead,
+    F: FnMut(SourceBlock) -> anyhow::Result<()>,
+{
+    let mut num_lines = 0;
+    for line in reader.lines() {
+        let line = line?;
+        num_lines += 1;
+
+        let line_number = first_line + num_lines;
+        if line.trim().is_empty() {
+            continue;
+        }
+
+        match parse_block_contents(None, line.as_bytes()) {
+            Ok(Some((state_hash, block))) => add_block(SourceBlock {
+                state_hash,
+                block,
+                bytes: line.len() as u64,
+                origin: format!("{}:{line_number}", log.display()),
+            })?,
+            Ok(None) => trace!("Line {line_number} is not a precomputed block"),
+            Err(e) => warn!("Failed to parse line {line_number}: {e}"),
+        }
+    }
+
+    Ok(num_lines)
+}
+
+/// Names the blocks without a state hash after their child's
+/// `previous_state_hash`, excluding hashes already named in the batch or in
+/// `named`. Returns the named blocks, sorted by height, & puts the rest in
+/// `pending`.
+fn name_blocks(
+    blocks: Vec<SourceBlock>,
+    named: &HashMap<StateHash, u32>,
+    pending: &mut Vec<SourceBlock>,
+) -> Vec<(PrecomputedBlock, u64)> {
+    let named_hashes: HashSet<_> = blocks
+        .iter()
+        .filter_map(|block| block.state_hash.clone())
+        .collect();
+
+    // parent hashes claimed by the blocks at each height
+    let mut parent_hashes: HashMap<u32, HashSet<StateHash>> = HashMap::new();
+    let mut num_unnamed: HashMap<u32, u32> = HashMap::new();
+    for block in blocks.iter() {
+        let blockchain_length = block.block.blockchain_length();
+        parent_hashes
+            .entry(blockchain_length.saturating_sub(1))
+            .or_default()
+            .insert(block.block.previous_state_hash());
+
+        if block.state_hash.is_none() {
+            *num_unnamed.entry(blockchain_length).or_default() += 1;
+        }
+    }
+
+    let mut named_blocks = vec![];
+    for mut block in blocks {
+        let blockchain_length = block.block.blockchain_length();
+        if block.state_hash.is_none() && num_unnamed.get(&blockchain_length) == Some(&1) {
+            let candidates: Vec<_> = parent_hashes
+                .get(&blockchain_length)
+                .into_iter()
+                .flatten()
+                .filter(|hash| !named_hashes.contains(*hash) && !named.contains_key(*hash))
+                .collect();
+
+            if let [state_hash] = candidates.as_slice() {
+                block.state_hash = Some((*state_hash).clone());
+            }
+        }
+
+        match block.state_hash {
+            Some(state_hash) => {
+                let mut precomputed = block.block;
+                match &mut precomputed {
+                    PrecomputedBlock::V1(v1) => v1.state_hash = state_hash,
+                    PrecomputedBlock::V2(v2) => v2.state_hash = state_hash,
+                }
+                named_blocks.push((precomputed, block.bytes));
+            }
+            None => pending.push(block),
+        }
+    }
+
+    named_blocks.sort_by_key(|(block, _)| block.blockchain_length());
+    named_blocks
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const BLOCK: &str = "./tests/data/sequential_blocks/mainnet-105490-3NKxEA9gztvEGxL4uk4eTncZAxuRmMsB8n81UkeAMevUjMbLHmkC.json";
+    const STATE_HASH: &str = "3NKxEA9gztvEGxL4uk4eTncZAxuRmMsB8n81UkeAMevUjMbLHmkC";
+
+    #[test]
+    fn block_contents_wrappers() -> anyhow::Result<()> {
+        let contents = std::fs::read(BLOCK)?;
+        let value: Value = serde_json::from_slice(&contents)?;
+
+        // bare block, state hash from the name
+        let (state_hash, block) =
+            parse_block_contents(Some(&format!("block_{STATE_HASH}.json")), &contents)?.unwrap();
+        assert_eq!(state_hash, Some(STATE_HASH.into()));
+        assert_eq!(block.blockchain_length(), 105490);
+
+        // bare block without a name
+        let (state_hash, _) = parse_block_contents(None, &contents)?.unwrap();
+        assert_eq!(state_hash, None);
+
+        // wrappers
+        for wrapped in [
+            serde_json::json!({ STATE_HASH: value }),
+            serde_json::json!({ "state_hash": STATE_HASH, "precomputed_block": value }),
+            serde_json::json!({ "metadata": { "state_hash": STATE_HASH, "precomputed_block": value } }),
+        ] {
+            let (state_hash, block) =
+                parse_block_contents(None, &serde_json::to_vec(&wrapped)?)?.unwrap();
+            assert_eq!(state_hash, Some(STATE_HASH.into()));
+            assert_eq!(block.blockchain_length(), 105490);
+        }
+
+        // not a block
+        assert!(parse_block_contents(None, br#"{"message": "hello"}"#)?.is_none());
+        Ok(())
+    }
+}
@@ -11,6 +11,8 @@ use speedb::{DBIterator, Direction, IteratorMode, WriteBatch};
 #[derive(Debug)]
 pub struct BlockUpdate {
     pub state_hash: StateHash,
+
+    /// The block's own height, which keys its balance & delegation history
     pub blockchain_length: u32,
     pub global_slot_since_genesis: u32,
 }
@@ -28,6 +30,10 @@ pub trait BlockStore {
     /// Get block from the store
     fn get_block(&self, state_hash: &StateHash) -> anyhow::Result<Option<(PrecomputedBlock, u64)>>;
 
+    /// Re-encode the blocks stored as JSON in the compact binary encoding,
+    /// returns the number of migrated blocks
+    fn migrate_block_encoding(&self) -> anyhow::Result<u32>;
+
     //////////////////////////
     // Best block functions //
     //////////////////////////
@@ -98,6 +104,17 @@ pub trait BlockStore {
     /// Get a block's creation date time
     fn get_block_date_time(&self, state_hash: &StateHash) -> anyhow::Result<Option<i64>>;
 
+    /// Index the block's min window density
+    fn set_block_min_window_density_batch(
+        &self,
+        state_hash: &StateHash,
+        min_window_density: u32,
+        batch: &mut WriteBatch,
+    ) -> anyhow::Result<()>;
+
+    /// Get a block's min window density without deserializing the PCB
+    fn get_block_min_window_density(&self, state_hash: &StateHash) -> anyhow::Result<Option<u32>>;
+
     /// Index the block's blockchain length
     fn set_block_height_batch(
         &self,
@@ -0,0 +1,241 @@
+//! Check precomputed block file names against the block contents
+//!
+//! Block files are named `{network}-{height}-{state hash}` & the indexer
+//! takes these values from the file name. The height is checked against the
+//! protocol state & the state hash against the `previous_state_hash` of the
+//! child blocks, i.e. a block's state hash is confirmed when a child links to
+//! it. Recomputing the state hash itself requires Mina's Poseidon parameters,
+//! which are not part of the indexer, so the state hash of a block without a
+//! child, e.g. the tip, is unconfirmed & taken from the file name.
+
+use super::{
+    extract_network_height_hash, genesis_state_hash::GenesisStateHash, is_valid_block_file,
+    precomputed::PrecomputedBlock,
+};
+use crate::{base::state_hash::StateHash, chain::ChainData};
+use glob::glob;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::{
+    collections::{HashMap, HashSet},
+    path::{Path, PathBuf},
+};
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum BlockFileIssue {
+    /// The file could not be parsed as a precomputed block
+    Unparsable { error: String },
+
+    /// The genesis state hash doesn't belong to a known chain
+    UnknownGenesisStateHash { genesis_state_hash: StateHash },
+
+    /// The file name height differs from the protocol state's
+    HeightMismatch { file_name: u32, protocol_state: u32 },
+
+    /// Another file contains the same protocol state
+    DuplicateProtocolState { path: PathBuf },
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BlockFileReport {
+    pub path: PathBuf,
+    pub state_hash: StateHash,
+    pub blockchain_length: u32,
+
+    /// A child block links to the file name's state hash
+    pub confirmed: bool,
+    pub issues: Vec<BlockFileIssue>,
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BlockFilesVerification {
+    pub num_files: u32,
+    pub num_confirmed: u32,
+
+    /// Reports of the files with issues
+    pub issues: Vec<BlockFileReport>,
+
+    /// Parent hashes which no file claims, either missing blocks or
+    /// mislabeled files at the previous height
+    pub missing_parents: Vec<(u32, StateHash)>,
+}
+
+impl BlockFileReport {
+    pub fn is_ok(&self) -> bool {
+        self.issues.is_empty()
+    }
+}
+
+impl BlockFilesVerification {
+    pub fn is_ok(&self) -> bool {
+        self.issues.is_empty()
+    }
+}
+
+/// Check the block file's name against its own contents
+pub fn verify_block_file(path: &Path, block: &PrecomputedBlock) -> Vec<BlockFileIssue> {
+    let (_, file_name, _) = extract_network_height_hash(path);
+    let protocol_state = block.consensus_blockchain_length();
+
+    if file_name != protocol_state {
+        return vec![BlockFileIssue::HeightMismatch {
+            file_name,
+            protocol_state,
+        }];
+    }
+
+    vec![]
+}
+
+/// Check the names of the block files in the directory
+pub fn verify_blocks_dir(blocks_dir: &Path) -> anyhow::Result<BlockFilesVerification> {
+    let mut paths: Vec<PathBuf> = glob(&format!("{}/*-*-*.json", blocks_dir.display()))?
+        .filter_map(|path| path.ok())
+        .filter(|path| is_valid_block_file(path))
+        .collect();
+    paths.sort();
+
+    Ok(verify_block_files(&paths))
+}
+
+/// Check the block files' names against their contents & each other
+pub fn verify_block_files(paths: &[PathBuf]) -> BlockFilesVerification {
+    let chain_data = ChainData::default();
+    let mut reports = vec![];
+    let mut parsed = vec![];
+
+    for path in paths {
+        let (_, blockchain_length, state_hash) = extract_network_height_hash(path);
+        let mut report = BlockFileReport {
+            path: path.clone(),
+            state_hash,
+            blockchain_length,
+            confirmed: false,
+            issues: vec![],
+        };
+
+        match parse_block_file(path, &chain_data) {
+            Ok(block) => {
+                report.issues = verify_block_file(path, &block);
+                parsed.push((reports.len(), block));
+            }
+            Err(issue) => report.issues.push(issue),
+        }
+        reports.push(report);
+    }
+
+    // identical protocol states under different names
+    let mut digests: HashMap<Vec<u8>, usize> = HashMap::new();
+    for (n, block) in parsed.iter() {
+        let Some(digest) = protocol_state_digest(block) else {
+            continue;
+        };
+
+        if let Some(other) = digests.get(&digest).copied() {
+            let other_path = reports[other].path.clone();
+            let path = reports[*n].path.clone();
+
+            reports[*n]
+                .issues
+                .push(BlockFileIssue::DuplicateProtocolState { path: other_path });
+            reports[other]
+                .issues
+                .push(BlockFileIssue::DuplicateProtocolState { path });
+        } else {
+            digests.insert(digest, *n);
+        }
+    }
+
+    // a child confirms its parent's state hash
+    let claimed: HashSet<_> = reports
+        .iter()
+        .map(|report| report.state_hash.clone())
+        .collect();
+    let parent_hashes: HashSet<_> = parsed
+        .iter()
+        .map(|(_, block)| block.previous_state_hash())
+        .collect();
+
+    for report in reports.iter_mut() {
+        report.confirmed = parent_hashes.contains(&report.state_hash);
+    }
+
+    // the lowest blocks' parents are expected to be missing
+    let min_length = parsed
+        .iter()
+        .map(|(_, block)| block.consensus_blockchain_length())
+        .min()
+        .unwrap_or_default();
+    let mut missing_parents: Vec<_> = parsed
+        .iter()
+        .filter(|(_, block)| block.consensus_blockchain_length() > min_length)
+        .map(|(_, block)| {
+            (
+                block.consensus_blockchain_length() - 1,
+                block.previous_state_hash(),
+            )
+        })
+        .filter(|(_, parent_hash)| !claimed.contains(parent_hash))
+        .collect();
+    missing_parents.sort();
+    missing_parents.dedup();
+
+    BlockFilesVerification {
+        num_files: reports.len() as u32,
+        num_confirmed: reports.iter().filter(|report| report.confirmed).count() as u32,
+        issues: reports
+            .into_iter()
+            .filter(|report| !report.is_ok())
+            .collect(),
+        missing_parents,
+    }
+}
+
+fn parse_block_file(
+    path: &Path,
+    chain_data: &ChainData,
+) -> Result<PrecomputedBlock, BlockFileIssue> {
+    let unparsable = |error: anyhow::Error| BlockFileIssue::Unparsable {
+        error: error.to_string(),
+    };
+
+    let genesis_state_hash = GenesisStateHash::from_path(path).map_err(unparsable)?;
+    let Some((version, _)) = chain_data.0.get(&genesis_state_hash) else {
+        return Err(BlockFileIssue::UnknownGenesisStateHash { genesis_state_hash });
+    };
+
+    PrecomputedBlock::parse_file_unverified(path, version.clone()).map_err(unparsable)
+}
+
+fn protocol_state_digest(block: &PrecomputedBlock) -> Option<Vec<u8>> {
+    let bytes = match block {
+        PrecomputedBlock::V1(v1) => serde_json::to_vec(&v1.protocol_state),
+        PrecomputedBlock::V2(v2) => serde_json::to_vec(&v2.protocol_state),
+    }
+    .ok()?;
+
+    Some(Sha256::digest(bytes).to_vec())
+}
+
+impl std::fmt::Display for BlockFilesVerification {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(
+            f,
+            "Checked {} block files: {} confirmed by a child, {} with issues",
+            self.num_files,
+            self.num_confirmed,
+            self.issues.len()
+        )?;
+
+        for report in self.issues.iter() {
+            for issue in report.issues.iter() {
+                writeln!(f, "{}: {issue:?}", report.path.display())?;
+            }
+        }
+
+        for (blockchain_length, parent_hash) in self.missing_parents.iter() {
+            writeln!(f, "Missing parent block {blockchain_length}-{parent_hash}")?;
+        }
+        Ok(())
+    }
+}
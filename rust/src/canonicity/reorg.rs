@@ -0,0 +1,212 @@
+//! Best chain reorganizations
+
+use crate::{
+    base::state_hash::StateHash, block::store::DbBlockUpdate, command::signed::TxnHash,
+    utility::deadline::Deadline,
+};
+use serde::{Deserialize, Serialize};
+use speedb::{DBIterator, IteratorMode};
+use std::collections::BTreeSet;
+use tokio::sync::broadcast;
+
+pub trait ReorgStore {
+    /// Record the best chain switch from `old_best_tip` to `new_best_tip`,
+    /// no-op if no block was unapplied
+    fn add_reorg(
+        &self,
+        old_best_tip: &StateHash,
+        new_best_tip: &StateHash,
+        blocks: &DbBlockUpdate,
+    ) -> anyhow::Result<Option<Reorg>>;
+
+    /// Receive the reorgs recorded after subscribing
+    fn subscribe_reorgs(&self) -> broadcast::Receiver<Reorg>;
+
+    /// Get the reorgs with at least `min_depth` orphaned blocks whose new
+    /// best tip height is at least `since`, most recent first
+    fn get_reorgs(
+        &self,
+        min_depth: u32,
+        since: u32,
+        limit: usize,
+        deadline: Option<&Deadline>,
+    ) -> anyhow::Result<Vec<Reorg>>;
+
+    ///////////////
+    // Iterators //
+    ///////////////
+
+    /// Iterator for reorgs
+    /// ```
+    /// {height}{state_hash} -> [Reorg] serde bytes
+    /// where
+    /// - height:     new best tip [u32] BE bytes
+    /// - state_hash: new best tip [StateHash] bytes
+    fn reorg_iterator(&self, mode: IteratorMode) -> DBIterator<'_>;
+}
+
+/// A switch of the best chain to another branch
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Reorg {
+    pub old_best_tip: StateHash,
+    pub new_best_tip: StateHash,
+    pub new_best_tip_height: u32,
+
+    /// Last block shared by the old & new best chains
+    pub common_ancestor: StateHash,
+    pub common_ancestor_height: u32,
+
+    /// Number of orphaned blocks
+    pub depth: u32,
+
+    /// Previously canonical blocks, in ascending height order
+    pub orphaned: Vec<StateHash>,
+
+    /// Newly canonical blocks, in ascending height order
+    pub canonical: Vec<StateHash>,
+
+    /// User commands which are no longer canonical
+    pub orphaned_txn_hashes: Vec<TxnHash>,
+
+    /// User commands which became canonical
+    pub canonical_txn_hashes: Vec<TxnHash>,
+
+    /// New best tip timestamp (millis)
+    pub date_time: i64,
+}
+
+impl Reorg {
+    /// Build the reorg from the best chain's block updates & each updated
+    /// block's user command hashes, `None` if no block was unapplied
+    pub fn new(
+        old_best_tip: StateHash,
+        new_best_tip: StateHash,
+        blocks: &DbBlockUpdate,
+        common_ancestor: StateHash,
+        date_time: i64,
+        txn_hashes: impl Fn(&StateHash) -> Vec<TxnHash>,
+    ) -> Option<Self> {
+        // unapplied blocks are ordered from the old best tip down
+        let deepest = blocks.unapply.last()?;
+        let new_best_tip_height = blocks
+            .apply
+            .last()
+            .map_or(deepest.blockchain_length - 1, |block| {
+                block.blockchain_length
+            });
+
+        let unapplied: Vec<_> = blocks
+            .unapply
+            .iter()
+            .rev()
+            .flat_map(|block| txn_hashes(&block.state_hash))
+            .collect();
+        let applied: Vec<_> = blocks
+            .apply
+            .iter()
+            .flat_map(|block| txn_hashes(&block.state_hash))
+            .collect();
+
+        // commands included on both branches stay canonical
+        let unapplied_set: BTreeSet<_> = unapplied.iter().collect();
+        let applied_set: BTreeSet<_> = applied.iter().collect();
+
+        Some(Self {
+            common_ancestor_height: deepest.blockchain_length - 1,
+            depth: blocks.unapply.len() as u32,
+            orphaned: blocks
+                .unapply
+                .iter()
+                .rev()
+                .map(|block| block.state_hash.clone())
+                .collect(),
+            canonical: blocks
+                .apply
+                .iter()
+                .map(|block| block.state_hash.clone())
+                .collect(),
+            orphaned_txn_hashes: unapplied
+                .iter()
+                .filter(|hash| !applied_set.contains(hash))
+                .cloned()
+                .collect(),
+            canonical_txn_hashes: applied
+                .iter()
+                .filter(|hash| !unapplied_set.contains(hash))
+                .cloned()
+                .collect(),
+            old_best_tip,
+            new_best_tip,
+            new_best_tip_height,
+            common_ancestor,
+            date_time,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{block::store::BlockUpdate, store::DbUpdate};
+
+    const TXN0: &str = "CkpZirFuoLVVab6x2ry4j8Ld5gMmQdak7VHW6f5C7VJYE34WAEWqa";
+    const TXN1: &str = "CkpYeG32dVJUjs6iq3oroXWitXar1eBtV3GVFyH5agw7HPp9bG4yQ";
+    const TXN2: &str = "CkpYfTKJyVjWmM5Lb5SdzRL6GuEbJf2q3yXAamRMnKN7p3GyNLTw8";
+
+    fn update(state_hash: &str, blockchain_length: u32) -> BlockUpdate {
+        BlockUpdate {
+            state_hash: state_hash.into(),
+            blockchain_length,
+            global_slot_since_genesis: blockchain_length,
+        }
+    }
+
+    #[test]
+    fn reorg_from_block_updates() {
+        let blocks = DbUpdate {
+            apply: vec![update("a10", 10), update("a11", 11), update("a12", 12)],
+            unapply: vec![update("u11", 11), update("u10", 10)],
+        };
+        let txn_hashes = |state_hash: &StateHash| match state_hash.0.as_str() {
+            "u10" => vec![TxnHash::V1(TXN0.into()), TxnHash::V1(TXN1.into())],
+            "a11" => vec![TxnHash::V1(TXN1.into())],
+            "a12" => vec![TxnHash::V1(TXN2.into())],
+            _ => vec![],
+        };
+
+        let reorg = Reorg::new(
+            "u11".into(),
+            "a12".into(),
+            &blocks,
+            "c9".into(),
+            0,
+            txn_hashes,
+        )
+        .unwrap();
+
+        assert_eq!(reorg.depth, 2);
+        assert_eq!(reorg.common_ancestor_height, 9);
+        assert_eq!(reorg.new_best_tip_height, 12);
+        assert_eq!(reorg.orphaned, vec!["u10".into(), "u11".into()]);
+        assert_eq!(reorg.canonical.len(), 3);
+
+        // TXN1 was included on both branches
+        assert_eq!(reorg.orphaned_txn_hashes, vec![TxnHash::V1(TXN0.into())]);
+        assert_eq!(reorg.canonical_txn_hashes, vec![TxnHash::V1(TXN2.into())]);
+
+        // extending the best chain is not a reorg
+        let extension = DbUpdate {
+            apply: vec![update("a13", 13)],
+            unapply: vec![],
+        };
+        assert!(Reorg::new(
+            "a12".into(),
+            "a13".into(),
+            &extension,
+            "a12".into(),
+            0,
+            |_| { vec![] }
+        )
+        .is_none());
+    }
+}
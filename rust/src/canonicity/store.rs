@@ -29,6 +29,13 @@ pub trait CanonicityStore {
     /// Get block canonicity
     fn get_block_canonicity(&self, state_hash: &StateHash) -> anyhow::Result<Option<Canonicity>>;
 
+    /// Record the genesis state hash & prev state hash pair
+    fn add_known_genesis(
+        &self,
+        genesis_state_hash: &StateHash,
+        genesis_prev_state_hash: &StateHash,
+    ) -> anyhow::Result<()>;
+
     /// Get the list of all known genesis state hashes
     fn get_known_genesis_state_hashes(&self) -> anyhow::Result<Vec<StateHash>>;
 
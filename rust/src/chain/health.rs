@@ -0,0 +1,313 @@
+//! Chain health statistics: slot fill, empty slot runs, window density,
+//! orphan rate & block times
+
+use crate::utility::deadline::Deadline;
+use serde::{Deserialize, Serialize};
+
+pub trait ChainHealthStore {
+    /// Get the chain health between the global slots (inclusive), the end
+    /// slot is capped at the best tip's global slot
+    fn get_chain_health(
+        &self,
+        start_slot: u32,
+        end_slot: u32,
+        deadline: Option<&Deadline>,
+    ) -> anyhow::Result<ChainHealth>;
+
+    /// Get the chain health of the epoch's elapsed slots
+    fn get_epoch_chain_health(
+        &self,
+        epoch: u32,
+        deadline: Option<&Deadline>,
+    ) -> anyhow::Result<ChainHealth>;
+
+    /// Get the chain health of sliding windows of `window` slots, starting
+    /// every `step` slots, between the global slots (inclusive)
+    fn get_chain_health_time_series(
+        &self,
+        start_slot: u32,
+        end_slot: u32,
+        window: u32,
+        step: u32,
+        limit: usize,
+        deadline: Option<&Deadline>,
+    ) -> anyhow::Result<Vec<ChainHealth>>;
+}
+
+/// Block data needed for chain health
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HealthBlock {
+    pub global_slot: u32,
+
+    /// Whether the block is on the best chain
+    pub canonical: bool,
+
+    /// Block timestamp (millis)
+    pub date_time: i64,
+    pub min_window_density: Option<u32>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ChainHealth {
+    /// Inclusive global slot bounds
+    pub start_slot: u32,
+    pub end_slot: u32,
+    pub num_slots: u32,
+
+    /// Number of slots with a canonical block
+    pub num_slots_filled: u32,
+    pub slot_fill_rate: f64,
+
+    pub num_blocks: u32,
+    pub num_canonical_blocks: u32,
+    pub num_orphaned_blocks: u32,
+
+    /// Fraction of blocks which are not canonical
+    pub orphan_rate: f64,
+
+    /// Maximal runs of consecutive slots without a canonical block
+    pub num_empty_slot_runs: u32,
+
+    /// Earliest of the longest empty slot runs
+    pub longest_empty_slot_run: Option<EmptySlotRun>,
+    pub mean_empty_slot_run: f64,
+
+    /// Min window density of the canonical blocks
+    pub min_window_density: Option<WindowDensityStats>,
+
+    /// Time between consecutive canonical blocks
+    pub block_time: Option<BlockTimeStats>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct EmptySlotRun {
+    pub start_slot: u32,
+    pub num_slots: u32,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct WindowDensityStats {
+    /// Density of the first & last canonical blocks
+    pub first: u32,
+    pub last: u32,
+    pub min: u32,
+    pub max: u32,
+}
+
+/// Inter-block time distribution (millis)
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct BlockTimeStats {
+    pub num_intervals: u32,
+    pub mean: f64,
+    pub min: i64,
+    pub median: i64,
+    pub p90: i64,
+    pub max: i64,
+}
+
+//////////
+// impl //
+//////////
+
+impl ChainHealth {
+    /// Compute the chain health between the global slots (inclusive) from
+    /// the `blocks` sorted by global slot
+    ///
+    /// Blocks outside the slot bounds are ignored
+    pub fn new(start_slot: u32, end_slot: u32, blocks: &[HealthBlock]) -> Self {
+        let lo = blocks.partition_point(|block| block.global_slot < start_slot);
+        let hi = blocks.partition_point(|block| block.global_slot <= end_slot);
+        let blocks = &blocks[lo..hi.max(lo)];
+
+        let canonical: Vec<_> = blocks.iter().filter(|block| block.canonical).collect();
+        let num_slots = end_slot.saturating_add(1).saturating_sub(start_slot);
+
+        let mut filled: Vec<_> = canonical.iter().map(|block| block.global_slot).collect();
+        filled.dedup();
+
+        let empty_runs = empty_slot_runs(start_slot, end_slot, &filled);
+        let num_empty_slots = num_slots - filled.len() as u32;
+
+        let num_blocks = blocks.len() as u32;
+        let num_canonical_blocks = canonical.len() as u32;
+        let num_orphaned_blocks = num_blocks - num_canonical_blocks;
+
+        Self {
+            start_slot,
+            end_slot,
+            num_slots,
+            num_slots_filled: filled.len() as u32,
+            slot_fill_rate: ratio(filled.len() as f64, num_slots as f64),
+            num_blocks,
+            num_canonical_blocks,
+            num_orphaned_blocks,
+            orphan_rate: ratio(num_orphaned_blocks as f64, num_blocks as f64),
+            num_empty_slot_runs: empty_runs.len() as u32,
+            longest_empty_slot_run: empty_runs.iter().copied().max_by(|a, b| {
+                a.num_slots
+                    .cmp(&b.num_slots)
+                    .then(b.start_slot.cmp(&a.start_slot))
+            }),
+            mean_empty_slot_run: ratio(num_empty_slots as f64, empty_runs.len() as f64),
+            min_window_density: WindowDensityStats::new(&canonical),
+            block_time: BlockTimeStats::new(&canonical),
+        }
+    }
+}
+
+impl WindowDensityStats {
+    fn new(canonical: &[&HealthBlock]) -> Option<Self> {
+        let densities: Vec<_> = canonical
+            .iter()
+            .filter_map(|block| block.min_window_density)
+            .collect();
+
+        Some(Self {
+            first: *densities.first()?,
+            last: *densities.last()?,
+            min: *densities.iter().min()?,
+            max: *densities.iter().max()?,
+        })
+    }
+}
+
+impl BlockTimeStats {
+    fn new(canonical: &[&HealthBlock]) -> Option<Self> {
+        let mut intervals: Vec<_> = canonical
+            .windows(2)
+            .map(|pair| pair[1].date_time - pair[0].date_time)
+            .collect();
+        if intervals.is_empty() {
+            return None;
+        }
+
+        intervals.sort_unstable();
+        let n = intervals.len();
+        let percentile = |p: usize| intervals[(n * p).div_ceil(100).saturating_sub(1)];
+
+        Some(Self {
+            num_intervals: n as u32,
+            mean: intervals.iter().sum::<i64>() as f64 / n as f64,
+            min: intervals[0],
+            median: percentile(50),
+            p90: percentile(90),
+            max: intervals[n - 1],
+        })
+    }
+}
+
+/// Maximal runs of slots in `start..=end` which are not `filled`
+///
+/// `filled` slots are sorted & deduplicated
+fn empty_slot_runs(start: u32, end: u32, filled: &[u32]) -> Vec<EmptySlotRun> {
+    let mut runs = vec![];
+    let mut next = start;
+
+    for slot in filled
+        .iter()
+        .copied()
+        .chain(std::iter::once(end.saturating_add(1)))
+    {
+        if slot > next {
+            runs.push(EmptySlotRun {
+                start_slot: next,
+                num_slots: slot - next,
+            });
+        }
+        next = slot.saturating_add(1);
+    }
+
+    runs
+}
+
+fn ratio(num: f64, denom: f64) -> f64 {
+    if denom > 0.0 {
+        num / denom
+    } else {
+        0.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn block(global_slot: u32, canonical: bool, date_time: i64, density: u32) -> HealthBlock {
+        HealthBlock {
+            global_slot,
+            canonical,
+            date_time,
+            min_window_density: Some(density),
+        }
+    }
+
+    #[test]
+    fn empty_runs() {
+        let runs = empty_slot_runs(10, 19, &[10, 13, 14, 19]);
+        assert_eq!(
+            runs,
+            vec![
+                EmptySlotRun {
+                    start_slot: 11,
+                    num_slots: 2
+                },
+                EmptySlotRun {
+                    start_slot: 15,
+                    num_slots: 4
+                },
+            ]
+        );
+
+        // leading & trailing runs
+        let runs = empty_slot_runs(0, 9, &[5]);
+        assert_eq!(runs.len(), 2);
+        assert_eq!(runs[1].num_slots, 4);
+        assert_eq!(empty_slot_runs(0, 9, &[]).len(), 1);
+    }
+
+    #[test]
+    fn chain_health() {
+        let blocks = vec![
+            block(0, true, 0, 77),
+            block(1, true, 180_000, 76),
+            block(1, false, 181_000, 76),
+            block(4, true, 720_000, 75),
+            block(5, false, 900_000, 75),
+            block(7, true, 1_260_000, 74),
+            block(12, true, 2_160_000, 73),
+        ];
+        let health = ChainHealth::new(0, 9, &blocks);
+
+        assert_eq!(health.num_slots, 10);
+        assert_eq!(health.num_slots_filled, 4);
+        assert_eq!(health.slot_fill_rate, 0.4);
+        assert_eq!(health.num_blocks, 6);
+        assert_eq!(health.num_orphaned_blocks, 2);
+        assert_eq!(
+            health.longest_empty_slot_run,
+            Some(EmptySlotRun {
+                start_slot: 2,
+                num_slots: 2
+            })
+        );
+        assert_eq!(health.num_empty_slot_runs, 3);
+        assert_eq!(health.mean_empty_slot_run, 2.0);
+
+        let density = health.min_window_density.unwrap();
+        assert_eq!((density.first, density.last, density.min), (77, 74, 74));
+
+        // 1, 3 & 3 slot intervals
+        let block_time = health.block_time.unwrap();
+        assert_eq!(block_time.num_intervals, 3);
+        assert_eq!(block_time.min, 180_000);
+        assert_eq!(block_time.median, 540_000);
+        assert_eq!(block_time.max, 540_000);
+        assert_eq!(block_time.mean, 420_000.0);
+
+        // empty range
+        let health = ChainHealth::new(20, 29, &blocks);
+        assert_eq!(health.num_slots_filled, 0);
+        assert_eq!(health.block_time, None);
+        assert_eq!(health.min_window_density, None);
+    }
+}
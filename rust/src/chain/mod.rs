@@ -1,5 +1,7 @@
 //! Chain data
 
+pub mod health;
+pub mod profile;
 pub mod store;
 
 mod id;
@@ -0,0 +1,539 @@
+//! Network profiles
+//!
+//! A network profile is a daemon runtime config (`--config-file`) with an
+//! additional `indexer` section, so a lightnet or private devnet can be
+//! indexed from the same file its nodes were started with. Missing values
+//! default to those of mainnet.
+
+use super::{ChainId, Network};
+use crate::{
+    base::{amount::Amount, state_hash::StateHash},
+    block::{precomputed::PcbVersion, vrf_output::VrfOutput},
+    constants::*,
+    ledger::{
+        account::vesting::SlotClock,
+        genesis::{GenesisAccounts, GenesisForkProof, GenesisLedger, ProtocolConstants},
+    },
+    utility::compression::decompress_gzip,
+};
+use anyhow::{anyhow, Context};
+use serde::{Deserialize, Serialize};
+use std::{
+    path::{Path, PathBuf},
+    str::FromStr,
+};
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct NetworkProfile {
+    #[serde(default)]
+    pub indexer: ProfileIndexer,
+
+    #[serde(default)]
+    pub genesis: ProfileGenesis,
+
+    #[serde(default)]
+    pub proof: ProfileProof,
+
+    #[serde(default)]
+    pub daemon: ProfileDaemon,
+
+    /// Genesis ledger, only its accounts are used
+    pub ledger: Option<GenesisAccounts>,
+}
+
+/// Indexer-specific values, ignored by the daemon
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ProfileIndexer {
+    /// Network name
+    pub network: Option<String>,
+
+    /// Precomputed block version
+    pub version: Option<PcbVersion>,
+
+    pub genesis_state_hash: Option<StateHash>,
+
+    /// Defaults to `proof.fork.state_hash`
+    pub genesis_prev_state_hash: Option<StateHash>,
+    pub genesis_last_vrf_output: Option<String>,
+
+    /// Precomputed genesis block, required unless the genesis is mainnet's
+    pub genesis_block: Option<PathBuf>,
+
+    // chain id inputs
+    pub constraint_system_digests: Option<Vec<String>>,
+    pub protocol_txn_version_digest: Option<String>,
+    pub protocol_network_version_digest: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ProfileGenesis {
+    pub k: Option<u32>,
+    pub delta: Option<u32>,
+    pub slots_per_epoch: Option<u32>,
+    pub slots_per_sub_window: Option<u32>,
+
+    /// RFC 3339 date time
+    pub genesis_state_timestamp: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ProfileProof {
+    pub block_window_duration_ms: Option<u64>,
+
+    /// MINA amounts
+    pub coinbase_amount: Option<String>,
+    pub account_creation_fee: Option<String>,
+
+    /// Supercharged coinbase multiplier
+    pub supercharged_coinbase_factor: Option<u64>,
+
+    /// Parent of the genesis block if the network is a hardfork
+    pub fork: Option<GenesisForkProof>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ProfileDaemon {
+    pub txpool_max_size: Option<u32>,
+}
+
+/// Protocol parameters the indexer needs after startup
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ProtocolParams {
+    pub k: u32,
+    pub slots_per_epoch: u32,
+    pub slots_per_sub_window: u32,
+    pub delta: u32,
+    pub txpool_max_size: u32,
+
+    /// Slot duration (millis)
+    pub block_window_duration_ms: u64,
+
+    /// Genesis block timestamp (millis)
+    pub genesis_timestamp: u64,
+
+    /// Genesis block global slot since genesis, non-zero after a hardfork
+    pub genesis_global_slot: u32,
+
+    /// Nanomina
+    pub coinbase_amount: u64,
+    pub supercharged_coinbase_factor: u64,
+    pub account_creation_fee: u64,
+}
+
+///////////
+// impls //
+///////////
+
+impl NetworkProfile {
+    /// Pre-hardfork mainnet profile
+    pub fn v1() -> Self {
+        Self {
+            indexer: ProfileIndexer {
+                version: Some(PcbVersion::V1),
+                genesis_state_hash: Some(MAINNET_GENESIS_HASH.into()),
+                genesis_prev_state_hash: Some(MAINNET_GENESIS_PREV_STATE_HASH.into()),
+                genesis_last_vrf_output: Some(MAINNET_GENESIS_LAST_VRF_OUTPUT.to_string()),
+                ..Default::default()
+            },
+            ..Default::default()
+        }
+    }
+
+    /// Post-hardfork mainnet profile
+    pub fn v2() -> Self {
+        Self {
+            indexer: ProfileIndexer {
+                version: Some(PcbVersion::V2),
+                genesis_state_hash: Some(HARDFORK_GENESIS_HASH.into()),
+                genesis_last_vrf_output: Some(HARDFORK_GENESIS_LAST_VRF_OUTPUT.to_string()),
+                ..Default::default()
+            },
+            genesis: ProfileGenesis {
+                genesis_state_timestamp: Some(millis_to_iso_date_string(
+                    HARDFORK_GENESIS_TIMESTAMP as i64,
+                )),
+                ..Default::default()
+            },
+            proof: ProfileProof {
+                fork: Some(GenesisForkProof {
+                    state_hash: HARDFORK_GENESIS_PREV_STATE_HASH.into(),
+                    blockchain_length: HARDFORK_GENESIS_BLOCKCHAIN_LENGTH - 1,
+                    global_slot_since_genesis: HARDFORK_GENESIS_GLOBAL_SLOT,
+                }),
+                ..Default::default()
+            },
+            ..Default::default()
+        }
+    }
+
+    /// Parses the profile at `path` (JSON, optionally gzip'd)
+    pub fn parse_file<P: AsRef<Path>>(path: P) -> anyhow::Result<Self> {
+        let path = path.as_ref();
+        let mut bytes = std::fs::read(path)
+            .with_context(|| format!("Failed to read network profile {}", path.display()))?;
+
+        // decompress if gzip'd
+        if path.extension().map_or(false, |ext| ext == "gz") {
+            bytes = decompress_gzip(&bytes[..])?;
+        }
+
+        serde_json::from_slice(&bytes)
+            .with_context(|| format!("Invalid network profile {}", path.display()))
+    }
+
+    /// Profile at `path` if given, otherwise the mainnet profile for the
+    /// genesis state hash
+    pub fn from_path_or_genesis(path: Option<&Path>, genesis_hash: &str) -> anyhow::Result<Self> {
+        match path {
+            Some(path) => Self::parse_file(path),
+            None if genesis_hash == HARDFORK_GENESIS_HASH => Ok(Self::v2()),
+            None => Ok(Self::v1()),
+        }
+    }
+
+    /// Replaces the given protocol constants
+    pub fn override_constants(&mut self, constants: ProtocolConstants) {
+        let ProtocolConstants {
+            k,
+            slots_per_epoch,
+            slots_per_sub_window,
+            delta,
+            txpool_max_size,
+        } = constants;
+
+        self.genesis.k = k.or(self.genesis.k);
+        self.genesis.slots_per_epoch = slots_per_epoch.or(self.genesis.slots_per_epoch);
+        self.genesis.slots_per_sub_window =
+            slots_per_sub_window.or(self.genesis.slots_per_sub_window);
+        self.genesis.delta = delta.or(self.genesis.delta);
+        self.daemon.txpool_max_size = txpool_max_size.or(self.daemon.txpool_max_size);
+    }
+
+    /// Defaults to mainnet
+    pub fn network(&self) -> Option<Network> {
+        self.indexer.network.as_deref().map(Into::into)
+    }
+
+    /// Defaults to v2 unless the profile is pre-hardfork mainnet
+    pub fn version(&self) -> PcbVersion {
+        self.indexer.version.clone().unwrap_or_else(|| {
+            match self.indexer.genesis_state_hash.as_ref() {
+                Some(hash) if hash.0 == MAINNET_GENESIS_HASH => PcbVersion::V1,
+                _ => PcbVersion::V2,
+            }
+        })
+    }
+
+    pub fn genesis_state_hash(&self) -> anyhow::Result<StateHash> {
+        self.indexer
+            .genesis_state_hash
+            .clone()
+            .ok_or_else(|| anyhow!("Network profile is missing indexer.genesis_state_hash"))
+    }
+
+    pub fn genesis_prev_state_hash(&self) -> anyhow::Result<StateHash> {
+        self.indexer
+            .genesis_prev_state_hash
+            .clone()
+            .or_else(|| self.proof.fork.as_ref().map(|fork| fork.state_hash.clone()))
+            .ok_or_else(|| {
+                anyhow!(
+                    "Network profile is missing indexer.genesis_prev_state_hash or proof.fork.state_hash"
+                )
+            })
+    }
+
+    pub fn genesis_last_vrf_output(&self) -> anyhow::Result<VrfOutput> {
+        let vrf_output = self
+            .indexer
+            .genesis_last_vrf_output
+            .as_deref()
+            .unwrap_or(MAINNET_GENESIS_LAST_VRF_OUTPUT);
+        VrfOutput::from_str(vrf_output)
+            .with_context(|| format!("Invalid genesis last vrf output {vrf_output}"))
+    }
+
+    /// Genesis block length & global slot, continuing the forked chain
+    pub fn genesis_height_and_slot(&self) -> (u32, u32) {
+        match self.proof.fork.as_ref() {
+            Some(fork) => (fork.blockchain_length + 1, fork.global_slot_since_genesis),
+            None => (1, 0),
+        }
+    }
+
+    /// The profile's constants, defaulting to mainnet
+    pub fn protocol_constants(&self) -> ProtocolConstants {
+        let mut constants = ProtocolConstants::default();
+        constants.override_with(ProtocolConstants {
+            k: self.genesis.k,
+            slots_per_epoch: self.genesis.slots_per_epoch,
+            slots_per_sub_window: self.genesis.slots_per_sub_window,
+            delta: self.genesis.delta,
+            txpool_max_size: self.daemon.txpool_max_size,
+        });
+        constants
+    }
+
+    pub fn protocol_params(&self) -> anyhow::Result<ProtocolParams> {
+        let constants = self.protocol_constants();
+        let parse_amount = |amount: &Option<String>, default: u64| match amount {
+            Some(amount) => amount
+                .parse::<Amount>()
+                .map(|amount| amount.0)
+                .with_context(|| format!("Invalid amount {amount}")),
+            None => Ok(default),
+        };
+
+        let genesis_timestamp = match self.genesis.genesis_state_timestamp.as_ref() {
+            Some(timestamp) => chrono::DateTime::parse_from_rfc3339(timestamp)
+                .with_context(|| format!("Invalid genesis state timestamp {timestamp}"))?
+                .timestamp_millis() as u64,
+            None => MAINNET_GENESIS_TIMESTAMP,
+        };
+
+        Ok(ProtocolParams {
+            k: constants.k.expect("k"),
+            slots_per_epoch: constants.slots_per_epoch.expect("slots per epoch"),
+            slots_per_sub_window: constants
+                .slots_per_sub_window
+                .expect("slots per sub window"),
+            delta: constants.delta.expect("delta"),
+            txpool_max_size: constants.txpool_max_size.expect("txpool max size"),
+            block_window_duration_ms: self
+                .proof
+                .block_window_duration_ms
+                .unwrap_or(MAINNET_BLOCK_SLOT_TIME_MILLIS),
+            genesis_timestamp,
+            genesis_global_slot: self.genesis_height_and_slot().1,
+            coinbase_amount: parse_amount(&self.proof.coinbase_amount, MAINNET_COINBASE_REWARD)?,
+            supercharged_coinbase_factor: self
+                .proof
+                .supercharged_coinbase_factor
+                .unwrap_or(MAINNET_SUPERCHARGED_COINBASE_FACTOR),
+            account_creation_fee: parse_amount(
+                &self.proof.account_creation_fee,
+                MAINNET_ACCOUNT_CREATION_FEE.0,
+            )?,
+        })
+    }
+
+    /// Chain id the network's nodes compute from the profile
+    pub fn chain_id(&self) -> anyhow::Result<ChainId> {
+        let params = self.protocol_params()?;
+        let constants = [
+            params.k,
+            params.slots_per_epoch,
+            params.slots_per_sub_window,
+            params.delta,
+            params.txpool_max_size,
+        ];
+
+        // pre-hardfork chain ids don't include the protocol version digests
+        let (default_digests, default_txn_digest, default_network_digest) = match self.version() {
+            PcbVersion::V1 => (MAINNET_CONSTRAINT_SYSTEM_DIGESTS, None, None),
+            PcbVersion::V2 => (
+                HARDFORK_CONSTRAINT_SYSTEM_DIGESTS,
+                Some(HARDFORK_PROTOCOL_TXN_VERSION_DIGEST),
+                Some(HARDFORK_PROTOCOL_NETWORK_VERSION_DIGEST),
+            ),
+        };
+        let digests: Vec<&str> = match self.indexer.constraint_system_digests.as_ref() {
+            Some(digests) => digests.iter().map(String::as_str).collect(),
+            None => default_digests.to_vec(),
+        };
+
+        Ok(ChainId::new(
+            &self.genesis_state_hash()?.0,
+            &constants,
+            &digests,
+            params.genesis_timestamp as i64,
+            self.indexer
+                .protocol_txn_version_digest
+                .as_deref()
+                .or(default_txn_digest),
+            self.indexer
+                .protocol_network_version_digest
+                .as_deref()
+                .or(default_network_digest),
+        ))
+    }
+
+    /// Genesis ledger of the profile's accounts, if any
+    pub fn genesis_ledger(&self) -> anyhow::Result<Option<GenesisLedger>> {
+        self.ledger
+            .as_ref()
+            .filter(|ledger| !ledger.accounts.is_empty())
+            .cloned()
+            .map(GenesisLedger::new)
+            .transpose()
+    }
+}
+
+impl ProtocolParams {
+    /// Coinbase reward (nanomina)
+    pub fn coinbase(&self, supercharged: bool) -> u64 {
+        if supercharged {
+            self.supercharged_coinbase_factor * self.coinbase_amount
+        } else {
+            self.coinbase_amount
+        }
+    }
+
+    pub fn epoch(&self, global_slot: u32) -> u32 {
+        global_slot / self.slots_per_epoch
+    }
+
+    /// First & last global slots of the epoch, `None` past the max global
+    /// slot
+    pub fn epoch_global_slots(&self, epoch: u32) -> Option<(u32, u32)> {
+        let start = epoch.checked_mul(self.slots_per_epoch)?;
+        Some((start, start.checked_add(self.slots_per_epoch - 1)?))
+    }
+
+    /// Slot within the epoch
+    pub fn epoch_slot(&self, global_slot: u32) -> u32 {
+        global_slot % self.slots_per_epoch
+    }
+
+    pub fn slot_clock(&self) -> SlotClock {
+        SlotClock {
+            genesis_timestamp: self.genesis_timestamp,
+            genesis_global_slot: self.genesis_global_slot,
+            slot_duration: self.block_window_duration_ms,
+        }
+    }
+
+    /// Global slot containing the epoch millis, zero before genesis
+    pub fn millis_to_global_slot(&self, millis: i64) -> u32 {
+        let slots = (millis - self.genesis_timestamp as i64)
+            .div_euclid(self.block_window_duration_ms as i64);
+        (self.genesis_global_slot as i64 + slots).max(0) as u32
+    }
+}
+
+//////////////
+// defaults //
+//////////////
+
+impl std::default::Default for ProtocolParams {
+    fn default() -> Self {
+        NetworkProfile::v1()
+            .protocol_params()
+            .expect("v1 protocol params")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ledger::token::TokenAddress;
+
+    const PROFILES: &str = "./tests/data/network_profiles";
+
+    #[test]
+    fn mainnet_chain_ids() -> anyhow::Result<()> {
+        assert_eq!(NetworkProfile::v1().chain_id()?.0, MAINNET_CHAIN_ID);
+        assert_eq!(NetworkProfile::v2().chain_id()?.0, HARDFORK_CHAIN_ID);
+
+        // runtime config equivalent of the built-in profile
+        let hardfork = NetworkProfile::parse_file(format!("{PROFILES}/hardfork.json"))?;
+        assert_eq!(hardfork.chain_id()?.0, HARDFORK_CHAIN_ID);
+        assert_eq!(
+            hardfork.protocol_params()?,
+            NetworkProfile::v2().protocol_params()?
+        );
+        assert_eq!(
+            hardfork.genesis_prev_state_hash()?.0,
+            HARDFORK_GENESIS_PREV_STATE_HASH
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn mainnet_indexer_versions() -> anyhow::Result<()> {
+        use crate::server::IndexerVersion;
+
+        assert_eq!(
+            IndexerVersion::from_profile(&NetworkProfile::v1())?,
+            IndexerVersion::v1()
+        );
+        assert_eq!(
+            IndexerVersion::from_profile(&NetworkProfile::v2())?,
+            IndexerVersion::v2()
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn lightnet() -> anyhow::Result<()> {
+        let mut profile = NetworkProfile::parse_file(format!("{PROFILES}/lightnet.json"))?;
+        let params = profile.protocol_params()?;
+
+        assert_eq!(profile.network(), Some(Network::Custom("lightnet".into())));
+        assert_eq!(profile.version(), PcbVersion::V2);
+        assert_eq!(profile.genesis_height_and_slot(), (1, 0));
+        assert_eq!(
+            params,
+            ProtocolParams {
+                k: 30,
+                slots_per_epoch: 720,
+                slots_per_sub_window: 7,
+                delta: 0,
+                txpool_max_size: 3000,
+                block_window_duration_ms: 20000,
+                genesis_timestamp: 1717286400000,
+                genesis_global_slot: 0,
+                coinbase_amount: 720 * MINA_SCALE,
+                supercharged_coinbase_factor: 1,
+                account_creation_fee: MINA_SCALE / 10,
+            }
+        );
+        assert!(ChainId::is_valid(profile.chain_id()?));
+        assert_ne!(profile.chain_id()?.0, HARDFORK_CHAIN_ID);
+
+        // slots & rewards
+        assert_eq!(params.epoch(1441), 2);
+        assert_eq!(params.epoch_slot(1441), 1);
+        assert_eq!(params.epoch_global_slots(2), Some((1440, 2159)));
+        assert_eq!(params.epoch_global_slots(u32::MAX), None);
+        assert_eq!(params.coinbase(true), params.coinbase(false));
+        assert_eq!(params.millis_to_global_slot(1717286400000 + 45000), 2);
+        assert_eq!(params.millis_to_global_slot(0), 0);
+
+        // genesis constants override the profile's
+        profile.override_constants(serde_json::from_str(r#"{"k": 10}"#)?);
+        assert_eq!(profile.protocol_params()?.k, 10);
+        assert_eq!(profile.protocol_params()?.slots_per_epoch, 720);
+
+        // genesis ledger
+        let ledger = profile.genesis_ledger()?.expect("genesis ledger");
+        let ledger = ledger.into_ledger(&profile.protocol_params()?);
+        assert_eq!(
+            ledger.tokens[&TokenAddress::default()].accounts.len(),
+            // including the genesis block winner
+            3
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn mainnet_slots() {
+        let v1 = ProtocolParams::default();
+        let v2 = NetworkProfile::v2().protocol_params().unwrap();
+
+        for millis in [
+            MAINNET_GENESIS_TIMESTAMP,
+            HARDFORK_GENESIS_TIMESTAMP,
+            HARDFORK_GENESIS_TIMESTAMP + 10 * MAINNET_BLOCK_SLOT_TIME_MILLIS + 1,
+        ] {
+            assert_eq!(
+                v1.millis_to_global_slot(millis as i64),
+                millis_to_global_slot(millis as i64)
+            );
+            assert_eq!(
+                v2.millis_to_global_slot(millis as i64),
+                millis_to_global_slot(millis as i64)
+            );
+        }
+    }
+}
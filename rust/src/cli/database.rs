@@ -15,6 +15,12 @@ pub struct DatabaseArgs {
     )]
     pub genesis_hash: String,
 
+    /// Path to the network profile, a runtime config (JSON) with an `indexer`
+    /// section defining the genesis, chain id inputs & protocol constants of a
+    /// custom network
+    #[arg(long, value_name = "FILE")]
+    pub network_profile: Option<PathBuf>,
+
     /// Path to the genesis constants (JSON)
     #[arg(long)]
     pub genesis_constants: Option<PathBuf>,
@@ -39,6 +45,12 @@ pub struct DatabaseArgs {
     #[arg(long)]
     pub staking_ledgers_dir: Option<PathBuf>,
 
+    /// Additional precomputed block source: a directory of arbitrarily named
+    /// block files, a `.tar`/`.tar.gz` bundle or an NDJSON block log
+    /// (`.ndjson`/`.jsonl`/`.log`), which is tailed. May be repeated.
+    #[arg(long = "block-source", value_name = "PATH")]
+    pub block_sources: Vec<PathBuf>,
+
     /// Path to directory for speedb
     #[arg(long, default_value = "/var/log/mina-indexer/database")]
     pub database_dir: PathBuf,
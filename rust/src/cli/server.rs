@@ -1,5 +1,6 @@
 use super::{database::DatabaseArgs, LogLevelFilter};
-use crate::constants::*;
+use crate::{constants::*, web::graphql::limits::GraphQLLimits};
+use anyhow::{bail, Context};
 use std::{path::PathBuf, str::FromStr};
 
 #[derive(clap::Parser, Debug, Clone, Default)]
@@ -16,6 +17,32 @@ pub struct ServerArgs {
     #[arg(long, default_value_t = DEFAULT_WEB_PORT)]
     pub web_port: u16,
 
+    /// Maximum GraphQL query depth
+    #[arg(long, default_value_t = DEFAULT_GRAPHQL_MAX_DEPTH)]
+    pub graphql_max_depth: usize,
+
+    /// Maximum GraphQL query complexity (list fields cost their `limit` times
+    /// their selection set)
+    #[arg(long, default_value_t = DEFAULT_GRAPHQL_MAX_COMPLEXITY)]
+    pub graphql_max_complexity: usize,
+
+    /// GraphQL request timeout (sec)
+    #[arg(long, default_value_t = DEFAULT_GRAPHQL_TIMEOUT)]
+    pub graphql_timeout: u64,
+
+    /// Path to the persisted GraphQL queries JSON file (sha256 hash -> query)
+    #[arg(long)]
+    pub graphql_persisted_queries: Option<PathBuf>,
+
+    /// Only execute persisted GraphQL queries
+    #[arg(long, default_value_t = false)]
+    pub graphql_production: bool,
+
+    /// Path to the file containing the bearer token for block & staking ledger
+    /// uploads (uploads are disabled without one)
+    #[arg(long)]
+    pub upload_token_file: Option<PathBuf>,
+
     /// Start with data consistency checks
     #[arg(long, default_value_t = false)]
     pub self_check: bool,
@@ -49,12 +76,16 @@ pub struct ServerArgs {
 pub struct ServerArgsJson {
     pub genesis_ledger: Option<String>,
     pub genesis_hash: String,
+    #[serde(default)]
+    pub network_profile: Option<String>,
     pub genesis_constants: Option<String>,
     pub constraint_system_digests: Option<Vec<String>>,
     pub protocol_txn_version_digest: Option<String>,
     pub protocol_network_version_digest: Option<String>,
     pub blocks_dir: Option<String>,
     pub staking_ledgers_dir: Option<String>,
+    #[serde(default)]
+    pub block_sources: Vec<String>,
     pub database_dir: String,
     pub log_level: String,
     pub ledger_cadence: u32,
@@ -64,6 +95,18 @@ pub struct ServerArgsJson {
     pub canonical_update_threshold: u32,
     pub web_hostname: String,
     pub web_port: u16,
+    #[serde(default = "default_graphql_max_depth")]
+    pub graphql_max_depth: usize,
+    #[serde(default = "default_graphql_max_complexity")]
+    pub graphql_max_complexity: usize,
+    #[serde(default = "default_graphql_timeout")]
+    pub graphql_timeout: u64,
+    #[serde(default)]
+    pub graphql_persisted_queries: Option<String>,
+    #[serde(default)]
+    pub graphql_production: bool,
+    #[serde(default)]
+    pub upload_token_file: Option<String>,
     pub pid: Option<u32>,
     pub do_not_ingest_orphan_blocks: bool,
     pub fetch_new_blocks_exe: Option<String>,
@@ -83,6 +126,43 @@ impl ServerArgs {
         self.pid = Some(pid);
         self
     }
+
+    pub fn graphql_limits(&self) -> anyhow::Result<GraphQLLimits> {
+        GraphQLLimits::new(
+            self.graphql_max_depth,
+            self.graphql_max_complexity,
+            self.graphql_timeout,
+            self.graphql_persisted_queries.as_deref(),
+            self.graphql_production,
+        )
+    }
+
+    /// Bearer token authorizing uploads, if configured
+    pub fn upload_token(&self) -> anyhow::Result<Option<String>> {
+        let Some(path) = self.upload_token_file.as_ref() else {
+            return Ok(None);
+        };
+
+        let token = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed reading upload token file {}", path.display()))?;
+        let token = token.trim();
+        if token.is_empty() {
+            bail!("Empty upload token file {}", path.display());
+        }
+        Ok(Some(token.to_string()))
+    }
+}
+
+fn default_graphql_max_depth() -> usize {
+    DEFAULT_GRAPHQL_MAX_DEPTH
+}
+
+fn default_graphql_max_complexity() -> usize {
+    DEFAULT_GRAPHQL_MAX_COMPLEXITY
+}
+
+fn default_graphql_timeout() -> u64 {
+    DEFAULT_GRAPHQL_TIMEOUT
 }
 
 /////////////////
@@ -99,6 +179,10 @@ impl From<ServerArgs> for ServerArgsJson {
                 .genesis_ledger
                 .map(|path| path.display().to_string()),
             genesis_hash: value.db.genesis_hash,
+            network_profile: value
+                .db
+                .network_profile
+                .map(|path| path.display().to_string()),
             genesis_constants: value.db.genesis_constants.map(|g| g.display().to_string()),
             constraint_system_digests: value.db.constraint_system_digests,
             protocol_txn_version_digest: value.db.protocol_txn_version_digest,
@@ -108,6 +192,12 @@ impl From<ServerArgs> for ServerArgsJson {
                 .db
                 .staking_ledgers_dir
                 .map(|d| d.display().to_string()),
+            block_sources: value
+                .db
+                .block_sources
+                .iter()
+                .map(|p| p.display().to_string())
+                .collect(),
             database_dir: value.db.database_dir.display().to_string(),
             log_level: value.db.log_level.to_string(),
             ledger_cadence: value.db.ledger_cadence,
@@ -117,6 +207,14 @@ impl From<ServerArgs> for ServerArgsJson {
             canonical_update_threshold: value.db.canonical_update_threshold,
             web_hostname: value.web_hostname,
             web_port: value.web_port,
+            graphql_max_depth: value.graphql_max_depth,
+            graphql_max_complexity: value.graphql_max_complexity,
+            graphql_timeout: value.graphql_timeout,
+            graphql_persisted_queries: value
+                .graphql_persisted_queries
+                .map(|p| p.display().to_string()),
+            graphql_production: value.graphql_production,
+            upload_token_file: value.upload_token_file.map(|p| p.display().to_string()),
             pid: value.pid,
             fetch_new_blocks_delay: value.fetch_new_blocks_delay,
             fetch_new_blocks_exe: value.fetch_new_blocks_exe.map(|p| p.display().to_string()),
@@ -136,12 +234,14 @@ impl From<ServerArgsJson> for ServerArgs {
         let db = DatabaseArgs {
             genesis_ledger: value.genesis_ledger.and_then(|path| path.parse().ok()),
             genesis_hash: value.genesis_hash,
+            network_profile: value.network_profile.map(Into::into),
             genesis_constants: value.genesis_constants.map(Into::into),
             protocol_txn_version_digest: value.protocol_txn_version_digest,
             protocol_network_version_digest: value.protocol_network_version_digest,
             constraint_system_digests: value.constraint_system_digests,
             blocks_dir: value.blocks_dir.map(Into::into),
             staking_ledgers_dir: value.staking_ledgers_dir.map(Into::into),
+            block_sources: value.block_sources.into_iter().map(Into::into).collect(),
             database_dir: value.database_dir.into(),
             log_level: LogLevelFilter::from_str(&value.log_level).expect("log level"),
             ledger_cadence: value.ledger_cadence,
@@ -157,6 +257,12 @@ impl From<ServerArgsJson> for ServerArgs {
             db,
             web_hostname: value.web_hostname,
             web_port: value.web_port,
+            graphql_max_depth: value.graphql_max_depth,
+            graphql_max_complexity: value.graphql_max_complexity,
+            graphql_timeout: value.graphql_timeout,
+            graphql_persisted_queries: value.graphql_persisted_queries.map(Into::into),
+            graphql_production: value.graphql_production,
+            upload_token_file: value.upload_token_file.map(Into::into),
             self_check: false,
             pid: value.pid,
             fetch_new_blocks_delay: value.fetch_new_blocks_delay,
@@ -174,6 +280,9 @@ impl From<DatabaseArgs> for ServerArgs {
             db: value,
             web_hostname: DEFAULT_WEB_HOSTNAME.to_string(),
             web_port: DEFAULT_WEB_PORT,
+            graphql_max_depth: DEFAULT_GRAPHQL_MAX_DEPTH,
+            graphql_max_complexity: DEFAULT_GRAPHQL_MAX_COMPLEXITY,
+            graphql_timeout: DEFAULT_GRAPHQL_TIMEOUT,
             ..Default::default()
         }
     }
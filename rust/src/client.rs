@@ -1,4 +1,7 @@
-use crate::constants::MAINNET_GENESIS_HASH;
+use crate::{
+    chain::Network,
+    constants::{DEFAULT_VESTING_SCHEDULE_INTERVAL, MAINNET_GENESIS_HASH},
+};
 use bincode::{config, Decode, Encode};
 use clap::{Parser, Subcommand};
 use std::{path::PathBuf, process};
@@ -84,6 +87,30 @@ pub enum Accounts {
         #[arg(long)]
         public_key: String,
     },
+
+    /// Project the unlock schedule of a timed account
+    Vesting {
+        /// Timed account's public key
+        #[arg(long)]
+        public_key: String,
+
+        /// Project the combined schedule of the timed accounts delegating to
+        /// the public key instead
+        #[arg(long, default_value_t = false)]
+        delegators: bool,
+
+        /// Number of slots between schedule entries
+        #[arg(long, default_value_t = DEFAULT_VESTING_SCHEDULE_INTERVAL)]
+        interval: u32,
+
+        /// Format output as CSV
+        #[arg(long, default_value_t = false)]
+        csv: bool,
+
+        /// Path to write the vesting schedule [default: stdout]
+        #[arg(long)]
+        path: Option<PathBuf>,
+    },
 }
 
 #[derive(Subcommand, Debug, Encode, Decode)]
@@ -201,6 +228,17 @@ pub enum Chain {
         #[arg(long, default_value_t = false)]
         verbose: bool,
     },
+
+    /// Query the witness tree, i.e. the root & dangling branches
+    Tree {
+        /// Path to write the witness tree [default: stdout]
+        #[arg(long)]
+        path: Option<PathBuf>,
+
+        /// Output Graphviz DOT instead of JSON
+        #[arg(long, default_value_t = false)]
+        dot: bool,
+    },
 }
 
 #[derive(Subcommand, Debug, Encode, Decode)]
@@ -316,6 +354,68 @@ pub enum StakingLedgers {
         #[arg(long)]
         public_key: String,
     },
+
+    /// Compute a block producer's delegator payouts by epoch
+    Payouts {
+        /// Epoch of the staking ledger & won blocks
+        #[arg(long)]
+        epoch: u32,
+
+        /// Genesis state hash
+        #[arg(long, default_value = MAINNET_GENESIS_HASH)]
+        genesis_state_hash: String,
+
+        /// Block producer's public key
+        #[arg(long)]
+        public_key: String,
+
+        /// Percentage of each delegator's share kept by the pool
+        #[arg(long, default_value_t = 0.0)]
+        pool_fee: f64,
+
+        /// Exclude user command fees from the distributed rewards
+        #[arg(long, default_value_t = false)]
+        exclude_tx_fees: bool,
+
+        /// Distribute supercharged coinbase bonuses to locked delegators too
+        #[arg(long, default_value_t = false)]
+        share_supercharge: bool,
+
+        /// Format output as CSV
+        #[arg(long, default_value_t = false)]
+        csv: bool,
+
+        /// Path to write the payouts [default: stdout]
+        #[arg(long)]
+        path: Option<PathBuf>,
+    },
+
+    /// Query block producer performance relative to stake by epoch
+    Performance {
+        /// Epoch of the staking ledger & produced blocks
+        #[arg(long)]
+        epoch: u32,
+
+        /// Genesis state hash
+        #[arg(long, default_value = MAINNET_GENESIS_HASH)]
+        genesis_state_hash: String,
+
+        /// Block producer's public key [default: all producers with stake]
+        #[arg(long)]
+        public_key: Option<String>,
+
+        /// Rank producers by stake, luck, reliability, or canonical-blocks
+        #[arg(long, default_value = "stake")]
+        sort_by: String,
+
+        /// Number of producers to report
+        #[arg(long, default_value_t = 100)]
+        limit: u32,
+
+        /// Path to write the report [default: stdout]
+        #[arg(long)]
+        path: Option<PathBuf>,
+    },
 }
 
 #[derive(Subcommand, Debug, Encode, Decode)]
@@ -439,7 +539,17 @@ pub enum InternalCommands {
 }
 
 impl ClientCli {
+    /// Queries the primary network
     pub async fn run(&self, domain_socket_path: PathBuf) -> anyhow::Result<()> {
+        self.run_on_network(domain_socket_path, None).await
+    }
+
+    /// Queries the given network, the primary network by default
+    pub async fn run_on_network(
+        &self,
+        domain_socket_path: PathBuf,
+        network: Option<Network>,
+    ) -> anyhow::Result<()> {
         let conn = UnixStream::connect(domain_socket_path)
             .await
             .unwrap_or_else(|e| {
@@ -449,7 +559,7 @@ impl ClientCli {
         let (reader, mut writer) = conn.into_split();
         let mut reader = BufReader::new(reader);
         let mut buffer = Vec::with_capacity(BUFFER_SIZE);
-        let encoded = bincode::encode_to_vec(self, BIN_CODE_CONFIG)?;
+        let encoded = bincode::encode_to_vec((network, self), BIN_CODE_CONFIG)?;
 
         writer.write_all(&encoded).await?;
         reader.read_to_end(&mut buffer).await?;
@@ -3,6 +3,7 @@ pub mod store;
 use crate::{
     base::{public_key::PublicKey, state_hash::StateHash},
     block::precomputed::PrecomputedBlock,
+    chain::profile::ProtocolParams,
     ledger::{coinbase::Coinbase, diff::account::*},
 };
 use serde::{Deserialize, Serialize};
@@ -43,11 +44,11 @@ impl InternalCommand {
     /// Compute the internal commands for the given precomputed block
     ///
     /// See [crate::ledger::diff::LedgerDiff::from_precomputed]
-    pub fn from_precomputed(block: &PrecomputedBlock) -> Vec<Self> {
+    pub fn from_precomputed(block: &PrecomputedBlock, protocol: &ProtocolParams) -> Vec<Self> {
         let mut all_account_diff_fees: Vec<Vec<AccountDiff>> = AccountDiff::from_block_fees(block);
 
         // replace Fee_transfer with Fee_transfer_via_coinbase, if any
-        let coinbase = Coinbase::from_precomputed(block);
+        let coinbase = Coinbase::from_precomputed(block, protocol);
         if coinbase.has_fee_transfer() {
             coinbase.account_diffs_coinbase_mut(&mut all_account_diff_fees);
         }
@@ -197,8 +198,8 @@ impl DbInternalCommandWithData {
         }
     }
 
-    pub fn from_precomputed(block: &PrecomputedBlock) -> Vec<Self> {
-        DbInternalCommand::from_precomputed(block)
+    pub fn from_precomputed(block: &PrecomputedBlock, protocol: &ProtocolParams) -> Vec<Self> {
+        DbInternalCommand::from_precomputed(block, protocol)
             .into_iter()
             .map(|cmd| {
                 Self::from_internal_cmd(
@@ -252,8 +253,8 @@ impl std::fmt::Display for InternalCommandKind {
 }
 
 impl DbInternalCommand {
-    pub fn from_precomputed(block: &PrecomputedBlock) -> Vec<Self> {
-        let internal_cmd_parts = InternalCommand::from_precomputed(block);
+    pub fn from_precomputed(block: &PrecomputedBlock, protocol: &ProtocolParams) -> Vec<Self> {
+        let internal_cmd_parts = InternalCommand::from_precomputed(block, protocol);
         let mut coinbase: Option<Self> = None;
         let mut fee_transfers = <HashMap<PublicKey, Self>>::new();
         let mut fee_transfers_via_coinbase = <HashMap<PublicKey, Self>>::new();
@@ -340,7 +341,7 @@ mod tests {
         let block = PrecomputedBlock::parse_file(&path, PcbVersion::V1)?;
 
         assert_eq!(
-            InternalCommand::from_precomputed(&block),
+            InternalCommand::from_precomputed(&block, &ProtocolParams::default()),
             vec![
                 InternalCommand::Coinbase {
                     receiver: "B62qs2YyNuo1LbNo5sbhPByDDAB7NZiejFM6H1ctND5ui7wH4PWa7qm".into(),
@@ -354,7 +355,7 @@ mod tests {
             ]
         );
 
-        let internal_cmds = DbInternalCommand::from_precomputed(&block);
+        let internal_cmds = DbInternalCommand::from_precomputed(&block, &ProtocolParams::default());
         assert_eq!(
             internal_cmds,
             vec![
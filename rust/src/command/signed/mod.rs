@@ -1,16 +1,11 @@
 mod txn_hash;
 
 use crate::{
+    base::base58::Base58Kind,
     command::*,
     mina_blocks::v2::{self, staged_ledger_diff::UserCommandData},
     proof_systems::signer::signature::Signature,
-    protocol::{
-        bin_prot,
-        serialization_types::{
-            staged_ledger_diff as mina_rs,
-            version_bytes::{USER_COMMAND, V1_TXN_HASH},
-        },
-    },
+    protocol::{bin_prot, serialization_types::staged_ledger_diff as mina_rs},
 };
 use anyhow::bail;
 use blake2::digest::VariableOutput;
@@ -304,9 +299,7 @@ impl SignedCommand {
                 bin_prot::to_writer(&mut binprot_bytes, v1)?;
 
                 // base58 encode + Blake2b hash
-                let binprot_bytes_bs58 = bs58::encode(&binprot_bytes[..])
-                    .with_check_version(USER_COMMAND)
-                    .into_string();
+                let binprot_bytes_bs58 = Base58Kind::UserCommand.encode(&binprot_bytes);
                 let mut hasher = blake2::Blake2bVar::new(32)?;
                 hasher.write_all(binprot_bytes_bs58.as_bytes())?;
 
@@ -317,11 +310,7 @@ impl SignedCommand {
                 hash.insert(0, VERSION_BYTE);
 
                 // base58 encode txn hash
-                Ok(TxnHash::V1(
-                    bs58::encode(hash)
-                        .with_check_version(V1_TXN_HASH)
-                        .into_string(),
-                ))
+                Ok(TxnHash::V1(Base58Kind::V1TxnHash.encode(&hash)))
             }
             Self::V2(data) => {
                 let json: serde_json::Value = data.to_owned().to_mina_json();
@@ -1,5 +1,5 @@
 use crate::{
-    base::{public_key::PublicKey, state_hash::StateHash},
+    base::{nonce::Nonce, public_key::PublicKey, state_hash::StateHash},
     block::{precomputed::PrecomputedBlock, store::DbBlockUpdate},
     command::{
         signed::{SignedCommandWithData, TxnHash},
@@ -7,7 +7,7 @@ use crate::{
     },
 };
 use speedb::{DBIterator, IteratorMode, WriteBatch};
-use std::path::PathBuf;
+use std::{collections::HashMap, path::PathBuf};
 
 /// Store for user commands
 pub trait UserCommandStore {
@@ -32,6 +32,13 @@ pub trait UserCommandStore {
         state_hash: &StateHash,
     ) -> anyhow::Result<Option<Vec<UserCommandWithStatus>>>;
 
+    /// Get the hashes of the given block's user commands, keyed by
+    /// `(sender, nonce)`
+    fn get_block_user_command_hashes(
+        &self,
+        state_hash: &StateHash,
+    ) -> anyhow::Result<HashMap<(PublicKey, Nonce), TxnHash>>;
+
     /// Get user command by its hash & index
     fn get_user_command(
         &self,
@@ -13,6 +13,7 @@ pub const VERSION: &str = concat!(env!("CARGO_PKG_VERSION"), "-", env!("GIT_COMM
 pub const BLOCK_REPORTING_FREQ_NUM: u32 = 1000;
 pub const BLOCK_REPORTING_FREQ_SEC: u64 = 180;
 pub const LEDGER_CADENCE: u32 = 100;
+pub const STAGED_LEDGER_CACHE_SIZE: usize = 4;
 pub const CANONICAL_UPDATE_THRESHOLD: u32 = PRUNE_INTERVAL_DEFAULT / 5;
 pub const MAINNET_CANONICAL_THRESHOLD: u32 = 10;
 pub const PRUNE_INTERVAL_DEFAULT: u32 = 10;
@@ -25,10 +26,44 @@ pub const MINA_SCALE_DEC: Decimal = dec!(1_000_000_000);
 pub const MINA_TOKEN_ADDRESS: &str = "wSHV2S4qX9jFsLjQo8r1BsMLH2ZRKsZx6EJd1sbozGPieEC4Jf";
 pub const MINA_TOKEN_ID: u64 = 1;
 
+pub const EMPTY_RECEIPT_CHAIN_HASH: &str = "2mzbV7WevxLuchs2dAMY4vQBS6XttnCUF8Hvks4XNBQ5qiSGGBQe";
+pub const ZERO_STATE_HASH: &str = "3NK2tkzqqK5spR2sZ7tujjqPksL45M3UUrcA4WhCkeiPtnugyE2x";
+
 pub const MAINNET_BLOCK_SLOT_TIME_MILLIS: u64 = 180000;
+
+/// One day of slots
+pub const DEFAULT_VESTING_SCHEDULE_INTERVAL: u32 = 480;
+
+/// One day of slots
+pub const DEFAULT_CHAIN_HEALTH_WINDOW: u32 = 480;
+
+/// Number of reorgs buffered for slow reorg subscribers
+pub const REORG_SUBSCRIPTION_CAPACITY: usize = 64;
+
+/// Interval between NDJSON block log polls (secs)
+pub const BLOCK_LOG_POLL_SECS: u64 = 5;
+
+/// Number of uploads waiting for the indexer before uploaders are blocked
+pub const UPLOAD_CHANNEL_CAPACITY: usize = 64;
+
+/// Maximum (compressed) size of an uploaded block or staking ledger body
+pub const UPLOAD_PAYLOAD_LIMIT: usize = 512 * 1024 * 1024;
+
+/// Number of blocks re-encoded per block migration write batch
+pub const BLOCK_MIGRATION_BATCH_SIZE: usize = 1000;
+
+/// Max zstd dictionary size for the blocks column family
+pub const BLOCKS_ZSTD_MAX_DICT_BYTES: i32 = 64 * 1024;
+
+/// Max sampled bytes for training the blocks zstd dictionary
+pub const BLOCKS_ZSTD_MAX_TRAIN_BYTES: i32 = 100 * BLOCKS_ZSTD_MAX_DICT_BYTES;
+
+/// Numbers of largest producers whose stake concentration is reported
+pub const WEALTH_DISTRIBUTION_TOP_N: [usize; 4] = [10, 25, 50, 100];
 pub const MAINNET_TRANSITION_FRONTIER_K: u32 = 290;
 pub const MAINNET_ACCOUNT_CREATION_FEE: Amount = Amount(1e9 as u64);
 pub const MAINNET_COINBASE_REWARD: u64 = 720000000000;
+pub const MAINNET_SUPERCHARGED_COINBASE_FACTOR: u64 = 2;
 
 pub const MAINNET_GENESIS_HASH: &str = "3NKeMoncuHab5ScarV5ViyF16cJPT4taWNSaTLS64Dp67wuXigPZ";
 pub const MAINNET_GENESIS_PREV_STATE_HASH: &str =
@@ -87,6 +122,8 @@ pub const HARDFORK_DIGEST_TXN_MERGE: &str = "b8879f677f622a1d86648030701f43e1";
 pub const HARDFORK_DIGEST_TXN_BASE: &str = "d31948e661cc662675b0c079458f714a";
 pub const HARDFORK_DIGEST_BLOCKCHAIN_STEP: &str = "14ab5562ed292de7a3deb9e12f00aec0";
 
+pub const HARDFORK_PROTOCOL_TXN_VERSION: u32 = 3;
+
 pub const HARDFORK_PROTOCOL_NETWORK_VERSION_DIGEST: &str = "eccbc87e4b5ce2fe28308fd9f2a7baf3";
 pub const HARDFORK_PROTOCOL_TXN_VERSION_DIGEST: &str = "eccbc87e4b5ce2fe28308fd9f2a7baf3";
 
@@ -126,3 +163,8 @@ pub mod berkeley {
 
 pub const DEFAULT_WEB_HOSTNAME: &str = "localhost";
 pub const DEFAULT_WEB_PORT: u16 = 8080;
+
+// GraphQL limits
+pub const DEFAULT_GRAPHQL_MAX_DEPTH: usize = 16;
+pub const DEFAULT_GRAPHQL_MAX_COMPLEXITY: usize = 100_000;
+pub const DEFAULT_GRAPHQL_TIMEOUT: u64 = 30;
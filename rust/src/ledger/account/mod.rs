@@ -1,6 +1,8 @@
 mod receipt_chain_hash;
 mod timing;
 
+pub mod vesting;
+
 use super::{
     diff::{
         account::{
@@ -17,7 +19,7 @@ use super::{
 use crate::{
     base::{amount::Amount, nonce::Nonce, public_key::PublicKey, state_hash::StateHash},
     block::genesis::GenesisBlock,
-    constants::MAINNET_ACCOUNT_CREATION_FEE,
+    chain::profile::ProtocolParams,
     ledger::diff::account::PaymentDiff,
     mina_blocks::v2::{self, ZkappAccount},
 };
@@ -94,10 +96,10 @@ impl Account {
         self.zkapp.is_some()
     }
 
-    /// Display view of account, removes non-genesis account creation fee
-    pub fn display(self) -> Self {
+    /// Display view of account, removes the network's account creation fee
+    pub fn display(self, protocol: &ProtocolParams) -> Self {
         Self {
-            balance: self.balance - MAINNET_ACCOUNT_CREATION_FEE,
+            balance: self.balance - protocol.account_creation_fee,
             ..self
         }
     }
@@ -105,16 +107,9 @@ impl Account {
     /// Time-locked balance (subtracted from circulating supply)
     /// as per https://docs.minaprotocol.com/mina-protocol/time-locked-accounts
     pub fn current_minimum_balance(&self, curr_global_slot: u32) -> u64 {
-        self.timing.as_ref().map_or(0, |t| {
-            if curr_global_slot < t.cliff_time.0 {
-                t.initial_minimum_balance.0
-            } else {
-                t.initial_minimum_balance.0.saturating_sub(
-                    ((curr_global_slot - t.cliff_time.0) / t.vesting_period.0) as u64
-                        * t.vesting_increment.0,
-                )
-            }
-        })
+        self.timing
+            .as_ref()
+            .map_or(0, |timing| timing.minimum_balance(curr_global_slot))
     }
 
     /// Creates a new empty account with the specified public key.
@@ -480,7 +475,6 @@ impl Account {
     /// Apply zkapp account creation fee
     pub fn zkapp_account_creation(self, diff: &ZkappAccountCreationFee) -> Self {
         self.checks(&diff.public_key, &diff.token);
-        assert_eq!(diff.amount, MAINNET_ACCOUNT_CREATION_FEE);
 
         Self {
             balance: self.balance + diff.amount,
@@ -628,15 +622,10 @@ impl From<GenesisBlock> for Account {
 // debug/display //
 ///////////////////
 
-/// Deduct account creation fee
+/// Ledger view, see [Account::display] for the display view
 impl std::fmt::Display for Account {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        let deducted = Self {
-            balance: self.balance - MAINNET_ACCOUNT_CREATION_FEE,
-            ..self.clone()
-        };
-
-        match serde_json::to_string_pretty(&deducted) {
+        match serde_json::to_string_pretty(self) {
             Ok(s) => write!(f, "{s}"),
             Err(_) => Err(std::fmt::Error),
         }
@@ -655,6 +644,7 @@ mod tests {
     use super::{Account, Amount};
     use crate::{
         base::{nonce::Nonce, public_key::PublicKey},
+        chain::profile::ProtocolParams,
         constants::ZKAPP_STATE_FIELD_ELEMENTS_NUM,
         ledger::{
             account::{Permission, Permissions, Timing},
@@ -670,19 +660,29 @@ mod tests {
     #[test]
     fn test_account_display() -> anyhow::Result<()> {
         let ledger_account = Account {
-            balance: Amount::new(100),
+            balance: Amount(2_000_000_000),
             ..Default::default()
         };
-        let deduct_account = ledger_account.clone().display();
 
-        // account display & debug => deduct "creation fee"
+        // account display view => deduct the network's creation fee
+        let mainnet = ProtocolParams::default();
+        let lightnet = ProtocolParams {
+            account_creation_fee: 100_000_000,
+            ..mainnet
+        };
         assert_eq!(
-            format!("{ledger_account}"),
-            serde_json::to_string_pretty(&deduct_account)?
+            ledger_account.clone().display(&mainnet).balance,
+            Amount(1_000_000_000)
         );
         assert_eq!(
-            format!("{ledger_account:?}"),
-            serde_json::to_string_pretty(&deduct_account)?
+            ledger_account.clone().display(&lightnet).balance,
+            Amount(1_900_000_000)
+        );
+
+        // account display & debug => ledger view
+        assert_eq!(
+            format!("{ledger_account}"),
+            serde_json::to_string_pretty(&ledger_account)?
         );
 
         // same account display & debug
@@ -690,6 +690,76 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn current_minimum_balance() {
+        let timing = Timing {
+            cliff_time: 100.into(),
+            vesting_period: 10.into(),
+            cliff_amount: 300.into(),
+            vesting_increment: 50.into(),
+            initial_minimum_balance: 1000.into(),
+        };
+        let account = Account {
+            timing: Some(timing.clone()),
+            ..Default::default()
+        };
+
+        // untimed accounts aren't locked
+        assert_eq!(Account::default().current_minimum_balance(0), 0);
+
+        // fully locked before the cliff
+        assert_eq!(account.current_minimum_balance(0), 1000);
+        assert_eq!(account.current_minimum_balance(99), 1000);
+
+        // the cliff amount unlocks at the cliff time
+        assert_eq!(account.current_minimum_balance(100), 700);
+        assert_eq!(account.current_minimum_balance(109), 700);
+
+        // then one vesting increment per vesting period
+        assert_eq!(account.current_minimum_balance(110), 650);
+        assert_eq!(account.current_minimum_balance(235), 50);
+        assert_eq!(account.current_minimum_balance(240), 0);
+        assert_eq!(account.current_minimum_balance(u32::MAX), 0);
+
+        // a zero vesting period unlocks everything at the cliff
+        let account = Account {
+            timing: Some(Timing {
+                vesting_period: 0.into(),
+                ..timing
+            }),
+            ..Default::default()
+        };
+        assert_eq!(account.current_minimum_balance(99), 1000);
+        assert_eq!(account.current_minimum_balance(100), 0);
+    }
+
+    #[test]
+    fn next_unlock_slot() {
+        let timing = Timing {
+            cliff_time: 100.into(),
+            vesting_period: 10.into(),
+            cliff_amount: 300.into(),
+            vesting_increment: 50.into(),
+            initial_minimum_balance: 1000.into(),
+        };
+
+        assert_eq!(timing.next_unlock_slot(0), Some(100));
+        assert_eq!(timing.next_unlock_slot(100), Some(110));
+        assert_eq!(timing.next_unlock_slot(119), Some(120));
+        assert_eq!(timing.next_unlock_slot(230), Some(240));
+
+        // fully unlocked
+        assert_eq!(timing.next_unlock_slot(240), None);
+
+        // never unlocks past the cliff
+        let timing = Timing {
+            vesting_increment: 0.into(),
+            ..timing
+        };
+        assert_eq!(timing.next_unlock_slot(0), Some(100));
+        assert_eq!(timing.next_unlock_slot(100), None);
+    }
+
     #[test]
     fn zkapp_account_diff_payment() {
         let amount = Amount(2000000000);
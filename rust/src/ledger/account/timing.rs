@@ -16,3 +16,49 @@ pub struct Timing {
     pub vesting_increment: Balance,
     pub initial_minimum_balance: Balance,
 }
+
+impl Timing {
+    /// Minimum balance of the timed account at the given global slot
+    ///
+    /// The cliff amount unlocks at the cliff time, then one vesting increment
+    /// unlocks every vesting period. A zero vesting period unlocks the whole
+    /// balance at the cliff.
+    pub fn minimum_balance(&self, global_slot: u32) -> u64 {
+        if global_slot < self.cliff_time.0 {
+            return self.initial_minimum_balance.0;
+        }
+
+        if self.vesting_period.0 == 0 {
+            return 0;
+        }
+
+        let num_periods = ((global_slot - self.cliff_time.0) / self.vesting_period.0) as u64;
+        self.initial_minimum_balance
+            .0
+            .saturating_sub(self.cliff_amount.0)
+            .saturating_sub(num_periods.saturating_mul(self.vesting_increment.0))
+    }
+
+    /// First global slot after the given one at which the minimum balance
+    /// may decrease, `None` if the account never unlocks any more
+    pub fn next_unlock_slot(&self, global_slot: u32) -> Option<u32> {
+        if self.minimum_balance(global_slot) == 0 {
+            return None;
+        }
+
+        if global_slot < self.cliff_time.0 {
+            return Some(self.cliff_time.0);
+        }
+
+        if self.vesting_increment.0 == 0 {
+            return None;
+        }
+
+        // past the cliff with a non-zero minimum balance => non-zero period
+        let num_periods = (global_slot - self.cliff_time.0) / self.vesting_period.0;
+        num_periods
+            .checked_add(1)
+            .and_then(|n| n.checked_mul(self.vesting_period.0))
+            .and_then(|n| n.checked_add(self.cliff_time.0))
+    }
+}
@@ -0,0 +1,339 @@
+//! Projected unlock schedules of timed accounts
+
+use super::Timing;
+use crate::{
+    base::public_key::PublicKey,
+    constants::{MAINNET_BLOCK_SLOT_TIME_MILLIS, MAINNET_GENESIS_TIMESTAMP},
+};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
+
+/// Maps global slots to dates
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SlotClock {
+    /// Genesis block timestamp (millis)
+    pub genesis_timestamp: u64,
+
+    /// Genesis block global slot since genesis, non-zero after a hardfork
+    pub genesis_global_slot: u32,
+
+    /// Slot duration (millis)
+    pub slot_duration: u64,
+}
+
+/// Balance unlocked by a global slot
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct VestingUnlock {
+    pub global_slot: u32,
+
+    /// Slot start time (millis)
+    pub date_time: i64,
+
+    /// Balance unlocked since the previous unlock
+    pub unlocked: u64,
+    pub cumulative_unlocked: u64,
+    pub locked: u64,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct VestingSchedule {
+    pub public_key: PublicKey,
+    pub timing: Timing,
+
+    /// First global slot without a minimum balance, if any
+    pub fully_vested_slot: Option<u32>,
+    pub unlocks: Vec<VestingUnlock>,
+}
+
+/// Unlock schedules of the accounts delegating to `delegate`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DelegatorVestingSchedules {
+    pub delegate: PublicKey,
+
+    /// Combined unlocks of all delegators
+    pub unlocks: Vec<VestingUnlock>,
+    pub schedules: Vec<VestingSchedule>,
+}
+
+#[derive(Serialize)]
+struct VestingUnlockRecord<'a> {
+    public_key: &'a str,
+    global_slot: u32,
+    date_time: i64,
+    unlocked: u64,
+    cumulative_unlocked: u64,
+    locked: u64,
+}
+
+//////////
+// impl //
+//////////
+
+impl Default for SlotClock {
+    fn default() -> Self {
+        Self {
+            genesis_timestamp: MAINNET_GENESIS_TIMESTAMP,
+            genesis_global_slot: 0,
+            slot_duration: MAINNET_BLOCK_SLOT_TIME_MILLIS,
+        }
+    }
+}
+
+impl SlotClock {
+    /// Start time of the global slot (millis)
+    pub fn slot_millis(&self, global_slot: u32) -> i64 {
+        let slots = global_slot as i64 - self.genesis_global_slot as i64;
+        self.genesis_timestamp as i64 + slots * self.slot_duration as i64
+    }
+}
+
+impl Timing {
+    /// First global slot without a minimum balance, `None` if the balance
+    /// never fully vests
+    pub fn fully_vested_slot(&self) -> Option<u32> {
+        let vesting = self
+            .initial_minimum_balance
+            .0
+            .saturating_sub(self.cliff_amount.0);
+        if vesting == 0 {
+            return Some(self.cliff_time.0);
+        }
+
+        if self.vesting_increment.0 == 0 || self.vesting_period.0 == 0 {
+            return None;
+        }
+
+        let num_periods = vesting.div_ceil(self.vesting_increment.0);
+        u32::try_from(num_periods)
+            .ok()
+            .and_then(|n| n.checked_mul(self.vesting_period.0))
+            .and_then(|slots| slots.checked_add(self.cliff_time.0))
+    }
+
+    /// Global slots at which the minimum balance decreases, sampled every
+    /// `interval` slots (rounded up to a multiple of the vesting period)
+    fn unlock_slots(&self, interval: u32) -> BTreeSet<u32> {
+        let mut slots = BTreeSet::new();
+        if self.initial_minimum_balance.0 == 0 {
+            return slots;
+        }
+
+        let cliff = self.cliff_time.0;
+        slots.insert(cliff);
+
+        if let Some(end) = self.fully_vested_slot() {
+            let period = self.vesting_period.0.max(1);
+            let step = interval.max(1).div_ceil(period) * period;
+
+            let mut slot = cliff;
+            while let Some(next) = slot.checked_add(step).filter(|next| *next < end) {
+                slots.insert(next);
+                slot = next;
+            }
+            slots.insert(end);
+        }
+
+        slots
+    }
+}
+
+impl VestingSchedule {
+    /// Project the unlock schedule of the timed account
+    pub fn project(
+        public_key: PublicKey,
+        timing: Timing,
+        clock: &SlotClock,
+        interval: u32,
+    ) -> Self {
+        let timings = [&timing];
+        let unlocks = unlocks(&timings, timing.unlock_slots(interval), clock);
+
+        Self {
+            public_key,
+            fully_vested_slot: timing.fully_vested_slot(),
+            timing,
+            unlocks,
+        }
+    }
+
+    /// CSV of the unlocks
+    pub fn to_csv(&self) -> anyhow::Result<String> {
+        to_csv([(&self.public_key, &self.unlocks)])
+    }
+}
+
+impl DelegatorVestingSchedules {
+    /// Project & combine the unlock schedules of the delegators' timed
+    /// accounts
+    pub fn project(
+        delegate: PublicKey,
+        delegators: Vec<(PublicKey, Timing)>,
+        clock: &SlotClock,
+        interval: u32,
+    ) -> Self {
+        let timings: Vec<_> = delegators.iter().map(|(_, timing)| timing).collect();
+        let slots = timings
+            .iter()
+            .flat_map(|timing| timing.unlock_slots(interval))
+            .collect();
+
+        let mut schedules: Vec<_> = delegators
+            .iter()
+            .map(|(pk, timing)| {
+                VestingSchedule::project(pk.clone(), timing.clone(), clock, interval)
+            })
+            .collect();
+        schedules.sort_by(|a, b| a.public_key.cmp(&b.public_key));
+
+        Self {
+            unlocks: unlocks(&timings, slots, clock),
+            delegate,
+            schedules,
+        }
+    }
+
+    /// CSV of the combined unlocks followed by each delegator's unlocks
+    pub fn to_csv(&self) -> anyhow::Result<String> {
+        to_csv(
+            std::iter::once((&self.delegate, &self.unlocks)).chain(
+                self.schedules
+                    .iter()
+                    .map(|schedule| (&schedule.public_key, &schedule.unlocks)),
+            ),
+        )
+    }
+}
+
+impl<'a> VestingUnlockRecord<'a> {
+    fn new(pk: &'a PublicKey, unlock: &VestingUnlock) -> Self {
+        Self {
+            public_key: &pk.0,
+            global_slot: unlock.global_slot,
+            date_time: unlock.date_time,
+            unlocked: unlock.unlocked,
+            cumulative_unlocked: unlock.cumulative_unlocked,
+            locked: unlock.locked,
+        }
+    }
+}
+
+/// Evaluate the combined minimum balance of the timings at each slot
+fn unlocks(timings: &[&Timing], slots: BTreeSet<u32>, clock: &SlotClock) -> Vec<VestingUnlock> {
+    let initial: u64 = timings
+        .iter()
+        .map(|timing| timing.initial_minimum_balance.0)
+        .sum();
+
+    let mut prev = 0;
+    slots
+        .into_iter()
+        .map(|global_slot| {
+            let locked: u64 = timings
+                .iter()
+                .map(|timing| timing.minimum_balance(global_slot))
+                .sum();
+            let cumulative_unlocked = initial - locked;
+            let unlock = VestingUnlock {
+                global_slot,
+                date_time: clock.slot_millis(global_slot),
+                unlocked: cumulative_unlocked - prev,
+                cumulative_unlocked,
+                locked,
+            };
+
+            prev = cumulative_unlocked;
+            unlock
+        })
+        .collect()
+}
+
+fn to_csv<'a>(
+    schedules: impl IntoIterator<Item = (&'a PublicKey, &'a Vec<VestingUnlock>)>,
+) -> anyhow::Result<String> {
+    let mut csv_writer = csv::WriterBuilder::new()
+        .has_headers(true)
+        .from_writer(vec![]);
+    for (pk, unlocks) in schedules {
+        for unlock in unlocks {
+            csv_writer.serialize(VestingUnlockRecord::new(pk, unlock))?;
+        }
+    }
+    Ok(String::from_utf8(csv_writer.into_inner()?)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::base::numeric::Numeric;
+
+    const PK: &str = "B62qrecVjpoZ4Re3a5arN6gXZ6orhmj1enUtA887XdG5mtZfdUbBUh4";
+
+    fn timing(initial: u64, cliff_time: u32, cliff_amount: u64, period: u32, inc: u64) -> Timing {
+        Timing {
+            initial_minimum_balance: Numeric(initial),
+            cliff_time: Numeric(cliff_time),
+            cliff_amount: Numeric(cliff_amount),
+            vesting_period: Numeric(period),
+            vesting_increment: Numeric(inc),
+        }
+    }
+
+    #[test]
+    fn project_schedule() -> anyhow::Result<()> {
+        let clock = SlotClock::default();
+        let schedule =
+            VestingSchedule::project(PK.into(), timing(1000, 10, 400, 5, 100), &clock, 1);
+
+        // cliff unlock, then 100 every 5 slots until fully vested
+        assert_eq!(schedule.fully_vested_slot, Some(40));
+        let slots: Vec<_> = schedule.unlocks.iter().map(|u| u.global_slot).collect();
+        assert_eq!(slots, vec![10, 15, 20, 25, 30, 35, 40]);
+        assert_eq!(schedule.unlocks[0].unlocked, 400);
+        assert!(schedule.unlocks[1..].iter().all(|u| u.unlocked == 100));
+        assert_eq!(schedule.unlocks.last().unwrap().locked, 0);
+        assert_eq!(
+            schedule.unlocks[1].date_time,
+            (MAINNET_GENESIS_TIMESTAMP + 15 * MAINNET_BLOCK_SLOT_TIME_MILLIS) as i64
+        );
+
+        // coarser intervals keep the cumulative amounts exact
+        let coarse = VestingSchedule::project(PK.into(), timing(1000, 10, 400, 5, 100), &clock, 12);
+        let slots: Vec<_> = coarse.unlocks.iter().map(|u| u.global_slot).collect();
+        assert_eq!(slots, vec![10, 25, 40]);
+        assert_eq!(coarse.unlocks[1].cumulative_unlocked, 700);
+
+        assert_eq!(schedule.to_csv()?.lines().count(), 8);
+        Ok(())
+    }
+
+    #[test]
+    fn combine_delegator_schedules() {
+        let clock = SlotClock::default();
+        let schedules = DelegatorVestingSchedules::project(
+            PK.into(),
+            vec![
+                (PK.into(), timing(100, 10, 100, 1, 0)),
+                (
+                    "B62qmCwouxG2UzH6zEYGFWFFzUuSv9sbLnr96VJWDX3paSSucX7jAJN".into(),
+                    timing(50, 20, 0, 10, 25),
+                ),
+            ],
+            &clock,
+            1,
+        );
+
+        let unlocks: Vec<_> = schedules
+            .unlocks
+            .iter()
+            .map(|u| (u.global_slot, u.unlocked, u.locked))
+            .collect();
+        assert_eq!(
+            unlocks,
+            vec![(10, 100, 50), (20, 0, 50), (30, 25, 25), (40, 25, 0)]
+        );
+        assert_eq!(schedules.schedules.len(), 2);
+
+        // never vests without an increment
+        assert_eq!(timing(100, 10, 50, 1, 0).fully_vested_slot(), None);
+    }
+}
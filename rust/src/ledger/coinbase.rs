@@ -1,7 +1,7 @@
 use crate::{
     block::precomputed::PrecomputedBlock,
+    chain::profile::ProtocolParams,
     command::internal::InternalCommand,
-    constants::*,
     ledger::{
         diff::account::{AccountDiff, PaymentDiff, UpdateType},
         token::TokenAddress,
@@ -16,6 +16,9 @@ pub struct Coinbase {
     pub kind: CoinbaseKind,
     pub receiver: PublicKey,
     pub supercharge: bool,
+
+    /// Reward (nanomina) per the network's protocol params
+    pub amount: u64,
     pub is_new_account: bool,
     pub receiver_balance: Option<u64>,
 }
@@ -84,23 +87,17 @@ impl CoinbaseKind {
 }
 
 impl Coinbase {
-    pub fn amount(&self) -> u64 {
-        if self.supercharge {
-            2 * MAINNET_COINBASE_REWARD
-        } else {
-            MAINNET_COINBASE_REWARD
-        }
-    }
-
-    pub fn from_precomputed(block: &PrecomputedBlock) -> Self {
+    pub fn from_precomputed(block: &PrecomputedBlock, protocol: &ProtocolParams) -> Self {
         let kind = CoinbaseKind::from_precomputed(block);
         let kind = kind.iter().max().expect("max coinbase").clone();
+        let supercharge = block.supercharge_coinbase();
         Self {
             kind,
             receiver: block.coinbase_receiver(),
             receiver_balance: block.coinbase_receiver_balance(),
-            is_new_account: block.accounts_created().1.is_some(),
-            supercharge: block.supercharge_coinbase(),
+            is_new_account: block.accounts_created(protocol).1.is_some(),
+            amount: protocol.coinbase(supercharge),
+            supercharge,
         }
     }
 
@@ -175,11 +172,7 @@ impl Coinbase {
     pub fn as_internal_cmd(&self) -> InternalCommand {
         InternalCommand::Coinbase {
             receiver: self.receiver.clone(),
-            amount: if self.supercharge {
-                2 * MAINNET_COINBASE_REWARD
-            } else {
-                MAINNET_COINBASE_REWARD
-            },
+            amount: self.amount,
         }
     }
 }
@@ -187,6 +180,7 @@ impl Coinbase {
 #[cfg(test)]
 mod coinbase_tests {
     use super::*;
+    use crate::constants::MAINNET_COINBASE_REWARD;
 
     #[test]
     fn test_coinbase_fee_transfer() {
@@ -198,6 +192,7 @@ mod coinbase_tests {
             kind: CoinbaseKind::One(Some(transfer.clone())),
             receiver: PublicKey::default(),
             supercharge: false,
+            amount: MAINNET_COINBASE_REWARD,
             is_new_account: false,
             receiver_balance: Some(0),
         };
@@ -222,6 +217,7 @@ mod coinbase_tests {
             kind: CoinbaseKind::Zero,
             receiver: PublicKey::default(),
             supercharge: false,
+            amount: MAINNET_COINBASE_REWARD,
             is_new_account: false,
             receiver_balance: Some(0),
         };
@@ -244,6 +240,7 @@ mod coinbase_tests {
             kind: CoinbaseKind::One(Some(transfer.clone())),
             receiver: PublicKey::default(),
             supercharge: false,
+            amount: MAINNET_COINBASE_REWARD,
             is_new_account: false,
             receiver_balance: Some(0),
         };
@@ -269,6 +266,7 @@ mod coinbase_tests {
             kind: CoinbaseKind::Zero,
             receiver: PublicKey::default(),
             supercharge: false,
+            amount: MAINNET_COINBASE_REWARD,
             is_new_account: false,
             receiver_balance: Some(0),
         };
@@ -294,11 +292,42 @@ mod coinbase_tests {
             kind: CoinbaseKind::One(None),
             receiver: PublicKey::from("B62qjHdYUPTHQkwDWUbDYscteT2LFj3ro1vz9fnxMyHTACe6C2fLbSd"),
             supercharge: false,
+            amount: MAINNET_COINBASE_REWARD,
             is_new_account: false,
             receiver_balance: Some(16790466359034),
         };
 
-        assert_eq!(Coinbase::from_precomputed(&block), expect);
+        assert_eq!(
+            Coinbase::from_precomputed(&block, &ProtocolParams::default()),
+            expect
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn coinbase_amount_from_protocol_params() -> anyhow::Result<()> {
+        use crate::block::precomputed::PcbVersion;
+
+        let path = std::path::PathBuf::from("./tests/data/misc_blocks/mainnet-278424-3NLbUZF8568pK56NJuSpCkfLTQTKpoiNiruju1Hpr6qpoAbuN9Yr.json");
+        let block = PrecomputedBlock::parse_file(&path, PcbVersion::V1)?;
+
+        // lightnet doesn't supercharge & charges 0.1 MINA to create accounts
+        let protocol = ProtocolParams {
+            coinbase_amount: 2 * MAINNET_COINBASE_REWARD,
+            supercharged_coinbase_factor: 1,
+            account_creation_fee: 100_000_000,
+            ..ProtocolParams::default()
+        };
+        let coinbase = Coinbase::from_precomputed(&block, &protocol);
+        assert_eq!(coinbase.amount, 2 * MAINNET_COINBASE_REWARD);
+        assert_eq!(
+            coinbase.as_internal_cmd(),
+            InternalCommand::Coinbase {
+                receiver: coinbase.receiver.clone(),
+                amount: 2 * MAINNET_COINBASE_REWARD,
+            }
+        );
+        assert_eq!(protocol.coinbase(true), protocol.coinbase(false));
         Ok(())
     }
 
@@ -312,11 +341,15 @@ mod coinbase_tests {
             kind: CoinbaseKind::Zero,
             receiver: PublicKey::from("B62qiy32p8kAKnny8ZFwoMhYpBppM1DWVCqAPBYNcXnsAHhnfAAuXgg"),
             supercharge: false,
+            amount: MAINNET_COINBASE_REWARD,
             is_new_account: false,
             receiver_balance: None,
         };
 
-        assert_eq!(Coinbase::from_precomputed(&block), expect);
+        assert_eq!(
+            Coinbase::from_precomputed(&block, &ProtocolParams::default()),
+            expect
+        );
         Ok(())
     }
 }
@@ -332,7 +332,7 @@ impl AccountDiff {
     pub fn from_coinbase(coinbase: Coinbase) -> Vec<Vec<Self>> {
         let mut res = vec![vec![Self::Coinbase(CoinbaseDiff {
             public_key: coinbase.receiver.clone(),
-            amount: coinbase.amount().into(),
+            amount: coinbase.amount.into(),
         })]];
 
         res.append(
@@ -1203,6 +1203,7 @@ mod tests {
     use crate::{
         base::nonce::Nonce,
         block::precomputed::{PcbVersion, PrecomputedBlock},
+        chain::profile::ProtocolParams,
         command::{Command, Delegation, Payment},
         constants::MAINNET_COINBASE_REWARD,
         ledger::{
@@ -1290,6 +1291,7 @@ mod tests {
         let snarker: PublicKey = "B62qospDjUj43x2yMKiNehojWWRUsE1wpdUDVpfxH8V3n5Y1QgJKFfw".into();
         let account_diff = AccountDiff::from_coinbase(Coinbase {
             supercharge: true,
+            amount: 2 * MAINNET_COINBASE_REWARD,
             is_new_account: true,
             receiver: receiver.clone(),
             receiver_balance: Some(1440 * (1e9 as u64)),
@@ -1382,6 +1384,7 @@ mod tests {
         let receiver = PublicKey::from("B62qospDjUj43x2yMKiNehojWWRUsE1wpdUDVpfxH8V3n5Y1QgJKFfw");
         let account_diff = AccountDiff::from_coinbase(Coinbase {
             supercharge: true,
+            amount: 2 * MAINNET_COINBASE_REWARD,
             is_new_account: false,
             receiver_balance: None,
             receiver: receiver.clone(),
@@ -1427,7 +1430,7 @@ mod tests {
         use crate::ledger::diff::AccountDiffType::*;
         let path = PathBuf::from("./tests/data/misc_blocks/mainnet-128743-3NLmYZD9eaV58opgC5RzQXaoPbyC15McNxw1CuCNatj7F9vGBbNz.json");
         let block = PrecomputedBlock::parse_file(&path, PcbVersion::V1)?;
-        let mut ledger_diff = LedgerDiff::from_precomputed(&block);
+        let mut ledger_diff = LedgerDiff::from_precomputed(&block, &ProtocolParams::default());
         let mut expect_diffs = LedgerDiff::from(&[
             (
                 "B62qre3erTHfzQckNuibViWQGyyKwZseztqrjPZBv6SQF384Rg6ESAy",
@@ -1481,7 +1484,7 @@ mod tests {
         let pcb = PrecomputedBlock::parse_file(&path, PcbVersion::V2)?;
 
         // all ledger diffs
-        let diffs = LedgerDiff::from_precomputed_unexpanded(&pcb);
+        let diffs = LedgerDiff::from_precomputed_unexpanded(&pcb, &ProtocolParams::default());
 
         // filter out non-zkapp account diffs
         let zkapp_diffs = diffs
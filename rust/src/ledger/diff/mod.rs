@@ -5,6 +5,7 @@ use super::{coinbase::Coinbase, token::TokenAddress, LedgerHash, PublicKey};
 use crate::{
     base::state_hash::StateHash,
     block::{precomputed::PrecomputedBlock, AccountCreated},
+    chain::profile::ProtocolParams,
     command::UserCommandWithStatusT,
 };
 use account::ZkappAccountCreationFee;
@@ -38,8 +39,8 @@ pub struct LedgerDiff {
 
 impl LedgerDiff {
     /// Compute a ledger diff from the given precomputed block
-    pub fn from_precomputed(block: &PrecomputedBlock) -> Self {
-        let unexpanded = Self::from_precomputed_unexpanded(block);
+    pub fn from_precomputed(block: &PrecomputedBlock, protocol: &ProtocolParams) -> Self {
+        let unexpanded = Self::from_precomputed_unexpanded(block, protocol);
         let mut account_diffs = AccountDiff::expand(unexpanded.account_diffs);
 
         // v2 account creation fees (via payments & zkapps)
@@ -104,7 +105,10 @@ impl LedgerDiff {
 
     /// Compute a ledger diff from the given precomputed block, without
     /// expanding zkapp diffs
-    pub fn from_precomputed_unexpanded(block: &PrecomputedBlock) -> Self {
+    pub fn from_precomputed_unexpanded(
+        block: &PrecomputedBlock,
+        protocol: &ProtocolParams,
+    ) -> Self {
         let mut account_diffs = vec![];
 
         // transaction fees
@@ -129,7 +133,7 @@ impl LedgerDiff {
             .collect::<Vec<_>>();
 
         // replace fee_transfer with fee_transfer_via_coinbase, if any
-        let coinbase = Coinbase::from_precomputed(block);
+        let coinbase = Coinbase::from_precomputed(block, protocol);
         if coinbase.has_fee_transfer() {
             coinbase.account_diffs_coinbase_mut(&mut account_diff_fees);
         }
@@ -141,7 +145,7 @@ impl LedgerDiff {
         }
         account_diffs.append(&mut account_diff_fees);
 
-        let accounts_created = block.accounts_created();
+        let accounts_created = block.accounts_created(protocol);
         Self {
             account_diffs,
             new_pk_balances: accounts_created.0,
@@ -195,6 +199,7 @@ mod tests {
     use crate::{
         base::nonce::Nonce,
         block::precomputed::{PcbVersion, PrecomputedBlock},
+        chain::profile::ProtocolParams,
         ledger::diff::{account::AccountDiffType::*, LedgerDiff},
     };
     use std::path::PathBuf;
@@ -203,7 +208,7 @@ mod tests {
     fn fees_from_precomputed_111() -> anyhow::Result<()> {
         let path = PathBuf::from("./tests/data/non_sequential_blocks/mainnet-111-3NL33j16AWm3Jhjj1Ud25E54hu7HpUq4WBQcAiijEKMfXqwFJwzK.json");
         let block = PrecomputedBlock::parse_file(&path, PcbVersion::V1)?;
-        let ledger_diff = LedgerDiff::from_precomputed(&block);
+        let ledger_diff = LedgerDiff::from_precomputed(&block, &ProtocolParams::default());
         let expect_diffs = LedgerDiff::from(&[
             (
                 "B62qre3erTHfzQckNuibViWQGyyKwZseztqrjPZBv6SQF384Rg6ESAy",
@@ -252,7 +257,7 @@ mod tests {
     fn fees_from_precomputed_320081() -> anyhow::Result<()> {
         let path = PathBuf::from("./tests/data/non_sequential_blocks/mainnet-320081-3NK3bLM3eMyCum34ovAGCUw2GWUqDxkNwiti8XtKBYrocinp8oZM.json");
         let block = PrecomputedBlock::parse_file(&path, PcbVersion::V1)?;
-        let mut ledger_diff = LedgerDiff::from_precomputed(&block);
+        let mut ledger_diff = LedgerDiff::from_precomputed(&block, &ProtocolParams::default());
         let mut expect_diffs = LedgerDiff::from(&[
             (
                 "B62qjBMMMbvj17vc5n6y7839mJr28QLLx8RC3QpKLDbsagtTgQA5sAW",
@@ -0,0 +1,231 @@
+//! Wealth distribution & decentralization statistics of a ledger
+
+use crate::{
+    base::public_key::PublicKey,
+    constants::{MINA_SCALE, WEALTH_DISTRIBUTION_TOP_N},
+};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// Number of balance histogram buckets, powers of 10 MINA from 1 MINA to
+/// 10M MINA with a final unbounded bucket
+const NUM_BALANCE_BUCKETS: usize = 9;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct WealthDistribution {
+    pub num_accounts: u32,
+
+    /// Number of accounts with delegated stake
+    pub num_producers: u32,
+    pub total_balance: u64,
+
+    /// Gini coefficient of the account balances
+    pub balance_gini: f64,
+
+    /// Gini coefficient of the producers' stakes
+    pub stake_gini: f64,
+
+    /// Minimum number of producers controlling more than 1/3 of the stake
+    pub nakamoto_one_third: u32,
+
+    /// Minimum number of producers controlling more than 1/2 of the stake
+    pub nakamoto_one_half: u32,
+
+    /// Stake held by the largest producers
+    pub top_stake: Vec<StakeConcentration>,
+
+    /// Account counts by balance bucket, in ascending balance order
+    pub balance_histogram: Vec<BalanceBucket>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct StakeConcentration {
+    /// Number of largest producers
+    pub top: u32,
+    pub stake: u64,
+    pub share: f64,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BalanceBucket {
+    /// Inclusive lower bound (nanomina)
+    pub min_balance: u64,
+
+    /// Exclusive upper bound (nanomina), `None` for the last bucket
+    pub max_balance: Option<u64>,
+    pub num_accounts: u32,
+    pub total_balance: u64,
+}
+
+//////////
+// impl //
+//////////
+
+impl WealthDistribution {
+    /// Compute the distribution of the accounts' `(balance, delegate)` pairs
+    ///
+    /// A producer's stake is the sum of the balances delegated to it
+    pub fn new<'a>(accounts: impl IntoIterator<Item = (u64, &'a PublicKey)>) -> Self {
+        let mut balances = vec![];
+        let mut stakes = <HashMap<&PublicKey, u64>>::new();
+
+        for (balance, delegate) in accounts {
+            balances.push(balance);
+            *stakes.entry(delegate).or_default() += balance;
+        }
+
+        let mut stakes: Vec<_> = stakes.into_values().filter(|stake| *stake > 0).collect();
+        stakes.sort_unstable_by(|a, b| b.cmp(a));
+
+        let total_balance = balances.iter().sum();
+        let total_stake: u64 = stakes.iter().sum();
+
+        Self {
+            num_accounts: balances.len() as u32,
+            num_producers: stakes.len() as u32,
+            total_balance,
+            balance_gini: gini(&balances),
+            stake_gini: gini(&stakes),
+            nakamoto_one_third: nakamoto(&stakes, total_stake, 3),
+            nakamoto_one_half: nakamoto(&stakes, total_stake, 2),
+            top_stake: WEALTH_DISTRIBUTION_TOP_N
+                .iter()
+                .map(|top| {
+                    let stake = stakes.iter().take(*top).sum();
+                    StakeConcentration {
+                        top: *top as u32,
+                        stake,
+                        share: ratio(stake, total_stake),
+                    }
+                })
+                .collect(),
+            balance_histogram: balance_histogram(&balances),
+        }
+    }
+}
+
+/// Gini coefficient of the values, 0 for perfect equality
+fn gini(values: &[u64]) -> f64 {
+    let mut values = values.to_vec();
+    values.sort_unstable();
+
+    let n = values.len() as f64;
+    let total: f64 = values.iter().map(|value| *value as f64).sum();
+    if total == 0.0 {
+        return 0.0;
+    }
+
+    let weighted: f64 = values
+        .iter()
+        .enumerate()
+        .map(|(idx, value)| (idx + 1) as f64 * *value as f64)
+        .sum();
+    2.0 * weighted / (n * total) - (n + 1.0) / n
+}
+
+/// Minimum number of the descending `stakes` whose sum exceeds
+/// `total / denom`
+fn nakamoto(stakes: &[u64], total: u64, denom: u64) -> u32 {
+    let mut sum = 0;
+    for (idx, stake) in stakes.iter().enumerate() {
+        sum += stake;
+        if sum as u128 * denom as u128 > total as u128 {
+            return idx as u32 + 1;
+        }
+    }
+    0
+}
+
+fn balance_histogram(balances: &[u64]) -> Vec<BalanceBucket> {
+    let mut buckets: Vec<_> = (0..NUM_BALANCE_BUCKETS)
+        .map(|idx| BalanceBucket {
+            min_balance: bucket_bound(idx).unwrap_or_default(),
+            max_balance: bucket_bound(idx + 1),
+            num_accounts: 0,
+            total_balance: 0,
+        })
+        .collect();
+
+    for balance in balances {
+        let bucket = buckets
+            .iter_mut()
+            .find(|bucket| bucket.max_balance.map_or(true, |max| *balance < max))
+            .expect("last bucket is unbounded");
+        bucket.num_accounts += 1;
+        bucket.total_balance += balance;
+    }
+
+    buckets
+}
+
+/// Lower bound of the bucket: 0, 1 MINA, 10 MINA, ...
+fn bucket_bound(idx: usize) -> Option<u64> {
+    match idx {
+        0 => Some(0),
+        idx if idx < NUM_BALANCE_BUCKETS => Some(10u64.pow(idx as u32 - 1) * MINA_SCALE),
+        _ => None,
+    }
+}
+
+fn ratio(num: u64, denom: u64) -> f64 {
+    if denom > 0 {
+        num as f64 / denom as f64
+    } else {
+        0.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PK0: &str = "B62qrecVjpoZ4Re3a5arN6gXZ6orhmj1enUtA887XdG5mtZfdUbBUh4";
+    const PK1: &str = "B62qmCwouxG2UzH6zEYGFWFFzUuSv9sbLnr96VJWDX3paSSucX7jAJN";
+    const PK2: &str = "B62qjYanmV7y9njVeH5UHkz3GYBm7xKir1rAnoY4KsEYUGLMiU45FSM";
+
+    #[test]
+    fn gini_coefficient() {
+        assert_eq!(gini(&[]), 0.0);
+        assert_eq!(gini(&[5, 5, 5, 5]), 0.0);
+        assert_eq!(gini(&[0, 0, 0, 8]), 0.75);
+        assert!((gini(&[1, 2, 3, 4]) - 0.25).abs() < 1e-12);
+    }
+
+    #[test]
+    fn wealth_distribution() {
+        let pk0 = PublicKey::from(PK0);
+        let pk1 = PublicKey::from(PK1);
+        let pk2 = PublicKey::from(PK2);
+
+        // pk0: 60 stake, pk1: 30 stake, pk2: 10 stake
+        let dist = WealthDistribution::new([
+            (40 * MINA_SCALE, &pk0),
+            (20 * MINA_SCALE, &pk0),
+            (30 * MINA_SCALE, &pk1),
+            (10 * MINA_SCALE, &pk2),
+            (0, &pk2),
+        ]);
+
+        assert_eq!(dist.num_accounts, 5);
+        assert_eq!(dist.num_producers, 3);
+        assert_eq!(dist.total_balance, 100 * MINA_SCALE);
+        assert_eq!(dist.nakamoto_one_third, 1);
+        assert_eq!(dist.nakamoto_one_half, 1);
+        assert_eq!(dist.top_stake[0].top, 10);
+        assert_eq!(dist.top_stake[0].share, 1.0);
+
+        // 0 | 10, 20, 30, 40 MINA
+        let counts: Vec<_> = dist
+            .balance_histogram
+            .iter()
+            .map(|bucket| bucket.num_accounts)
+            .collect();
+        assert_eq!(counts, vec![1, 0, 4, 0, 0, 0, 0, 0, 0]);
+        assert_eq!(dist.balance_histogram[8].max_balance, None);
+
+        // exactly half the stake is not a majority
+        let dist = WealthDistribution::new([(50, &pk0), (50, &pk1)]);
+        assert_eq!(dist.nakamoto_one_third, 1);
+        assert_eq!(dist.nakamoto_one_half, 2);
+    }
+}
@@ -1,22 +1,25 @@
 use super::{
-    account::{Account, ReceiptChainHash, Timing},
-    token::{TokenAddress, TokenId},
-    Ledger, TokenLedger,
+    account::{self, Account, ReceiptChainHash, Timing},
+    token::{TokenAddress, TokenId, TokenSymbol},
+    Ledger,
 };
 use crate::{
     base::{amount::Amount, nonce::Nonce, public_key::PublicKey, state_hash::StateHash},
     block::genesis::GenesisBlock,
+    chain::profile::ProtocolParams,
     constants::*,
+    mina_blocks::v2::{VerificationKey, ZkappAccount},
     utility::compression::decompress_gzip,
 };
-use anyhow::anyhow;
-use log::error;
+use anyhow::{anyhow, bail, Context};
+use log::{error, warn};
+use num::{BigUint, Num};
 use serde::{Deserialize, Serialize};
-use std::{collections::HashMap, path::Path, str::FromStr};
+use std::{path::Path, str::FromStr};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct GenesisLedger {
-    ledger: TokenLedger,
+    ledger: Ledger,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -50,6 +53,8 @@ pub struct GenesisAccounts {
     pub seed: Option<String>,
 }
 
+/// Runtime config account, see
+/// https://github.com/MinaProtocol/mina/blob/berkeley/src/lib/runtime_config/runtime_config.ml
 #[derive(Default, Debug, Clone, Serialize, Deserialize)]
 pub struct GenesisAccount {
     pub pk: String,
@@ -65,29 +70,107 @@ pub struct GenesisAccount {
     pub nonce: Option<Nonce>,
 
     #[serde(default)]
-    pub token: Option<TokenId>,
+    pub token: Option<GenesisToken>,
+
+    #[serde(default)]
+    pub token_symbol: Option<TokenSymbol>,
+
+    #[serde(default)]
+    pub zkapp: Option<GenesisZkappAccount>,
 }
 
+/// Pre-berkeley token permissions
 #[derive(Default, Debug, Clone, Serialize, Deserialize)]
 pub struct TokenPermissions {}
 
-#[derive(Default, Debug, Clone, Serialize, Deserialize)]
+/// Token ids are numeric pre-berkeley & base58check encoded after
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum GenesisToken {
+    Address(TokenAddress),
+    Id(TokenId),
+}
+
+/// Missing permissions default to those of a new account
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
 pub struct Permissions {
+    /// Pre-berkeley only
     pub stake: bool,
     pub edit_state: Permission,
+    pub access: Permission,
     pub send: Permission,
+    pub receive: Permission,
     pub set_delegate: Permission,
     pub set_permissions: Permission,
-    pub set_verification_key: Permission,
+    pub set_verification_key: VerificationKeyPermission,
+    pub set_zkapp_uri: Permission,
+    pub edit_action_state: Permission,
+    pub set_token_symbol: Permission,
+    pub increment_nonce: Permission,
+    pub set_voting_for: Permission,
+    pub set_timing: Permission,
 }
 
 #[derive(Default, Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "lowercase")]
 pub enum Permission {
+    None,
+    Either,
+    Proof,
     #[default]
     Signature,
+    Impossible,
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum VerificationKeyPermission {
+    Versioned {
+        auth: Permission,
+        txn_version: String,
+    },
+
+    /// Pre-berkeley
+    Unversioned(Permission),
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GenesisZkappAccount {
+    pub app_state: [GenesisField; ZKAPP_STATE_FIELD_ELEMENTS_NUM],
+    pub verification_key: Option<GenesisVerificationKey>,
+
+    #[serde(deserialize_with = "crate::utility::serde::from_num_or_str")]
+    pub zkapp_version: u32,
+
+    pub action_state: [GenesisField; 5],
+
+    #[serde(deserialize_with = "crate::utility::serde::from_num_or_str")]
+    pub last_action_slot: u32,
+
+    pub proved_state: bool,
+    pub zkapp_uri: String,
+
+    /// Hash of a bare `verification_key`, which the indexer cannot compute.
+    /// Daemon runtime configs omit it, the hash is then left unset
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub verification_key_hash: Option<GenesisField>,
+}
+
+/// The runtime config only includes the verification key data, its hash may
+/// be given alongside in `verification_key_hash`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum GenesisVerificationKey {
+    Data(String),
+    WithHash(VerificationKey),
+}
+
+/// Field element, decimal in the runtime config & normalized to the
+/// 0x-prefixed hex of the precomputed blocks
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct GenesisField(pub String);
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct GenesisAccountTiming {
     pub initial_minimum_balance: String,
@@ -174,16 +257,29 @@ impl GenesisLedger {
         let bytes = include_bytes!("../../data/genesis_ledgers/hardfork.json.gz");
         let root = decompress_gzip(bytes)?;
         let root: GenesisRoot = serde_json::from_slice(&root)?;
-        Ok(root.into())
+        root.try_into()
+    }
+
+    /// Ledger with the network's display account creation fee added to
+    /// each genesis account, see [Account::display]
+    pub fn into_ledger(self, protocol: &ProtocolParams) -> Ledger {
+        let mut ledger = self.ledger;
+        for token_ledger in ledger.tokens.values_mut() {
+            for account in token_ledger.accounts.values_mut() {
+                account.balance += Amount(protocol.account_creation_fee);
+            }
+        }
+
+        ledger
     }
 
     /// This is the only way to construct a genesis ledger
-    pub fn new(genesis: GenesisAccounts) -> GenesisLedger {
-        let mut accounts = HashMap::new();
+    pub fn new(genesis: GenesisAccounts) -> anyhow::Result<GenesisLedger> {
+        let mut ledger = Ledger::new();
 
         // Add genesis block winner
         let block_creator = Account::from(GenesisBlock::new_v1().unwrap());
-        accounts.insert(block_creator.public_key.clone(), block_creator);
+        ledger.insert_account(block_creator, &TokenAddress::default());
 
         for account in genesis.accounts {
             let balance = account
@@ -195,34 +291,48 @@ impl GenesisLedger {
             let delegate = account
                 .delegate
                 .map_or_else(|| public_key.to_owned(), PublicKey);
+            let token = account.token.map(TokenAddress::from);
+            if account
+                .zkapp
+                .as_ref()
+                .is_some_and(GenesisZkappAccount::is_verification_key_unhashed)
+            {
+                warn!(
+                    "Genesis zkapp account {public_key} has no `verification_key_hash`, its verification key hash is unset"
+                );
+            }
+
+            let zkapp = account
+                .zkapp
+                .map(ZkappAccount::try_from)
+                .transpose()
+                .with_context(|| format!("Invalid genesis zkapp account {public_key}"))?;
 
-            accounts.insert(
-                public_key.clone(),
+            ledger.insert_account(
                 Account {
                     public_key,
                     balance,
                     delegate,
                     nonce: account.nonce.map(Into::into),
-                    token: account.token.map(TokenAddress::from),
+                    token: token.clone(),
                     receipt_chain_hash: account.receipt_chain_hash,
                     voting_for: account.voting_for.map(Into::into),
                     timing: account.timing.map(Into::into),
                     genesis_account: true,
-                    token_symbol: None,
-                    permissions: None,
+                    token_symbol: account.token_symbol,
+                    permissions: account.permissions.map(Into::into),
                     username: None,
-                    zkapp: None,
+                    zkapp,
                 },
+                &token.unwrap_or_default(),
             );
         }
 
-        Self {
-            ledger: TokenLedger { accounts },
-        }
+        Ok(Self { ledger })
     }
 
     pub fn parse_file<P: AsRef<Path>>(path: P) -> anyhow::Result<Self> {
-        GenesisRoot::parse_file(path).map(Into::into)
+        GenesisRoot::parse_file(path)?.try_into()
     }
 }
 
@@ -240,10 +350,43 @@ impl GenesisRoot {
     }
 }
 
+impl GenesisZkappAccount {
+    /// Bare verification key without a `verification_key_hash`
+    pub fn is_verification_key_unhashed(&self) -> bool {
+        matches!(self.verification_key, Some(GenesisVerificationKey::Data(_)))
+            && self.verification_key_hash.is_none()
+    }
+}
+
 //////////////
 // defaults //
 //////////////
 
+impl std::default::Default for Permissions {
+    /// New account permissions
+    fn default() -> Self {
+        Self {
+            stake: true,
+            edit_state: Permission::Signature,
+            access: Permission::None,
+            send: Permission::Signature,
+            receive: Permission::None,
+            set_delegate: Permission::Signature,
+            set_permissions: Permission::Signature,
+            set_verification_key: VerificationKeyPermission::Versioned {
+                auth: Permission::Signature,
+                txn_version: HARDFORK_PROTOCOL_TXN_VERSION.to_string(),
+            },
+            set_zkapp_uri: Permission::Signature,
+            edit_action_state: Permission::Signature,
+            set_token_symbol: Permission::Signature,
+            increment_nonce: Permission::Signature,
+            set_voting_for: Permission::Signature,
+            set_timing: Permission::Signature,
+        }
+    }
+}
+
 impl std::default::Default for ProtocolConstants {
     fn default() -> Self {
         Self {
@@ -272,44 +415,128 @@ impl FromStr for GenesisLedger {
     type Err = anyhow::Error;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        GenesisRoot::from_str(s).map(Into::into)
+        GenesisRoot::from_str(s)?.try_into()
     }
 }
 
-impl From<GenesisRoot> for GenesisLedger {
-    fn from(value: GenesisRoot) -> Self {
+impl TryFrom<GenesisRoot> for GenesisLedger {
+    type Error = anyhow::Error;
+
+    fn try_from(value: GenesisRoot) -> anyhow::Result<Self> {
         Self::new(value.ledger)
     }
 }
 
-impl From<GenesisLedger> for Ledger {
-    fn from(value: GenesisLedger) -> Self {
-        Ledger::from_mina_ledger(value.into())
+impl From<GenesisToken> for TokenAddress {
+    fn from(value: GenesisToken) -> Self {
+        match value {
+            GenesisToken::Address(token) => token,
+            GenesisToken::Id(id) => id.into(),
+        }
+    }
+}
+
+impl From<Permission> for account::Permission {
+    fn from(value: Permission) -> Self {
+        match value {
+            Permission::None => Self::None,
+            Permission::Either => Self::Either,
+            Permission::Proof => Self::Proof,
+            Permission::Signature => Self::Signature,
+            Permission::Impossible => Self::Impossible,
+        }
+    }
+}
+
+impl From<VerificationKeyPermission> for (account::Permission, String) {
+    fn from(value: VerificationKeyPermission) -> Self {
+        match value {
+            VerificationKeyPermission::Versioned { auth, txn_version } => {
+                (auth.into(), txn_version)
+            }
+            VerificationKeyPermission::Unversioned(auth) => {
+                (auth.into(), HARDFORK_PROTOCOL_TXN_VERSION.to_string())
+            }
+        }
     }
 }
 
-impl From<GenesisLedger> for TokenLedger {
-    fn from(value: GenesisLedger) -> Self {
+impl From<Permissions> for account::Permissions {
+    fn from(value: Permissions) -> Self {
         Self {
-            accounts: value
-                .ledger
-                .accounts
-                .into_iter()
-                .map(|(pk, acct)| {
-                    (
-                        pk,
-                        Account {
-                            // add display fee
-                            balance: acct.balance + MAINNET_ACCOUNT_CREATION_FEE,
-                            ..acct
-                        },
-                    )
-                })
-                .collect(),
+            edit_state: value.edit_state.into(),
+            access: value.access.into(),
+            send: value.send.into(),
+            receive: value.receive.into(),
+            set_delegate: value.set_delegate.into(),
+            set_permissions: value.set_permissions.into(),
+            set_verification_key: value.set_verification_key.into(),
+            set_zkapp_uri: value.set_zkapp_uri.into(),
+            edit_action_state: value.edit_action_state.into(),
+            set_token_symbol: value.set_token_symbol.into(),
+            increment_nonce: value.increment_nonce.into(),
+            set_voting_for: value.set_voting_for.into(),
+            set_timing: value.set_timing.into(),
         }
     }
 }
 
+impl TryFrom<GenesisZkappAccount> for ZkappAccount {
+    type Error = anyhow::Error;
+
+    fn try_from(value: GenesisZkappAccount) -> anyhow::Result<Self> {
+        let verification_key = match (value.verification_key, value.verification_key_hash) {
+            // 32 bytes = 64 hex + 2 prefix chars
+            (Some(GenesisVerificationKey::Data(_)), Some(hash)) if hash.0.len() != 66 => {
+                bail!("Verification key hash {} is not 32 bytes", hash.0)
+            }
+            (Some(GenesisVerificationKey::Data(data)), Some(hash)) => VerificationKey {
+                data: data.into(),
+                hash: hash.0.into(),
+            },
+            (Some(GenesisVerificationKey::Data(data)), None) => VerificationKey {
+                data: data.into(),
+                ..Default::default()
+            },
+            (Some(GenesisVerificationKey::WithHash(vk)), _) => vk,
+            (None, _) => VerificationKey::default(),
+        };
+
+        Ok(Self {
+            app_state: value.app_state.map(|field| field.0.into()),
+            action_state: value.action_state.map(|field| field.0.into()),
+            verification_key,
+            proved_state: value.proved_state,
+            zkapp_uri: value.zkapp_uri.into(),
+            zkapp_version: value.zkapp_version.into(),
+            last_action_slot: value.last_action_slot.into(),
+        })
+    }
+}
+
+impl FromStr for GenesisField {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let field = match s.strip_prefix("0x") {
+            Some(hex) => BigUint::from_str_radix(hex, 16),
+            None => BigUint::from_str_radix(s, 10),
+        }
+        .map_err(|e| anyhow!("Invalid field element {s}: {e}"))?;
+
+        Ok(Self(format!("0x{field:064X}")))
+    }
+}
+
+impl<'de> Deserialize<'de> for GenesisField {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        crate::utility::serde::from_str(deserializer)
+    }
+}
+
 impl From<GenesisAccountTiming> for Timing {
     fn from(value: GenesisAccountTiming) -> Self {
         Self {
@@ -377,11 +604,13 @@ mod tests {
         assert_eq!(None, root.ledger.accounts.first().unwrap().delegate);
 
         // after turning into a [Ledger]
-        let ledger = GenesisLedger::new(root.ledger);
+        let ledger = GenesisLedger::new(root.ledger)?;
         let account = ledger
             .ledger
-            .accounts
-            .get(&"B62qqdcf6K9HyBSaxqH5JVFJkc1SUEe1VzDc5kYZFQZXWSQyGHoino1".into())
+            .get_account(
+                &"B62qqdcf6K9HyBSaxqH5JVFJkc1SUEe1VzDc5kYZFQZXWSQyGHoino1".into(),
+                &TokenAddress::default(),
+            )
             .unwrap();
 
         // The delete should be the same as the public key
@@ -397,6 +626,102 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn parse_runtime_config() -> anyhow::Result<()> {
+        let root = GenesisRoot::parse_file("./tests/data/genesis_ledgers/berkeley.json")?;
+        let protocol = ProtocolParams::default();
+        let ledger = GenesisLedger::try_from(root)?.into_ledger(&protocol);
+
+        let mina = TokenAddress::default();
+        let token =
+            TokenAddress::new("xBxjFpJkbWpbGua7Lf36S1NLhffFoEChyP3pz6SYKnx7dFCTwg").unwrap();
+        assert_eq!(ledger.len(), 2);
+
+        // MINA account
+        let pk = "B62qmqMrgPshhHKLJ7DqWn1KeizEgga5MuGmWb2bXajUnyivfeMW6JE".into();
+        let account = ledger.get_account(&pk, &mina).unwrap();
+        assert_eq!(
+            account.clone().display(&protocol).balance,
+            Amount(1_000_000_000_000)
+        );
+        assert_eq!(account.nonce, Some(2.into()));
+        assert_eq!(account.token, Some(mina.clone()));
+        assert!(account.timing.is_some());
+        assert!(account.permissions.is_none());
+        assert!(!account.is_zkapp_account());
+
+        // zkapp account
+        let pk = "B62qmVHmj3mNhouDf1hyQFCSt3ATuttrxozMunxYMLctMvnk5y7nas1".into();
+        let account = ledger.get_account(&pk, &mina).unwrap();
+        let permissions = account.permissions.as_ref().unwrap();
+        assert_eq!(permissions.edit_state, account::Permission::Proof);
+        assert_eq!(
+            permissions.set_verification_key,
+            (account::Permission::Signature, "3".to_string())
+        );
+
+        let zkapp = account.zkapp.as_ref().unwrap();
+        assert_eq!(
+            zkapp.app_state[0].0,
+            "0x0000000000000000000000000000000000000000000000000000000000000001"
+        );
+        assert_eq!(
+            zkapp.action_state[0].0,
+            "0x3772BC5435B957F81F86F752E93F2E29E886AC24580B3D1EC879C1DAD26965F9"
+        );
+        assert_eq!(
+            zkapp.verification_key.data.0,
+            "zCJGb9ZhnBm1bokDgzy2JoK5iJSgRhT5cZzKbNnM"
+        );
+        assert_eq!(
+            zkapp.verification_key.hash.0,
+            "0x053A4846C8CD7608462E276140924454CAD36B7E7412AC054C78640D6D6157E5"
+        );
+        assert_eq!(zkapp.zkapp_uri.0, "https://minaprotocol.com");
+        assert!(zkapp.proved_state);
+
+        // custom token account
+        let pk = "B62qjX1zTYtJqCg6c7VHYjTzGTEgzzYxE1ArGZMZQpoukrGXaDFq5aW".into();
+        assert!(ledger.get_account(&pk, &mina).is_none());
+
+        let account = ledger.get_account(&pk, &token).unwrap();
+        let permissions = account.permissions.as_ref().unwrap();
+        assert_eq!(account.token_symbol, Some("TOKEN".into()));
+        assert_eq!(permissions.send, account::Permission::Either);
+        assert_eq!(permissions.receive, account::Permission::None);
+        assert_eq!(
+            permissions.set_verification_key,
+            (account::Permission::Proof, "3".to_string())
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn zkapp_verification_key_without_hash() -> anyhow::Result<()> {
+        let contents = std::fs::read_to_string("./tests/data/genesis_ledgers/berkeley.json")?;
+        let mut root: serde_json::Value = serde_json::from_str(&contents)?;
+        for account in root["ledger"]["accounts"].as_array_mut().unwrap() {
+            if let Some(zkapp) = account["zkapp"].as_object_mut() {
+                zkapp.remove("verification_key_hash");
+            }
+        }
+
+        // daemon runtime configs omit the hash, the account is kept
+        let ledger =
+            GenesisLedger::from_str(&root.to_string())?.into_ledger(&ProtocolParams::default());
+        let pk = "B62qmVHmj3mNhouDf1hyQFCSt3ATuttrxozMunxYMLctMvnk5y7nas1".into();
+        let account = ledger.get_account(&pk, &TokenAddress::default()).unwrap();
+        let verification_key = &account.zkapp.as_ref().unwrap().verification_key;
+
+        assert_eq!(
+            verification_key.data.0,
+            "zCJGb9ZhnBm1bokDgzy2JoK5iJSgRhT5cZzKbNnM"
+        );
+        assert!(verification_key.hash.0.is_empty());
+        Ok(())
+    }
+
     #[test]
     fn override_genesis_constants() -> anyhow::Result<()> {
         // no override
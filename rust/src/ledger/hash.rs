@@ -1,6 +1,9 @@
-use crate::protocol::serialization_types::{
-    common::{Base58EncodableVersionedType, HashV1},
-    version_bytes,
+use crate::{
+    base::base58::Base58Kind,
+    protocol::serialization_types::{
+        common::{Base58EncodableVersionedType, HashV1},
+        version_bytes,
+    },
 };
 use anyhow::bail;
 use serde::{Deserialize, Serialize};
@@ -44,9 +47,9 @@ impl LedgerHash {
         Self::from_bytes(bytes).expect("ledger hash bytes")
     }
 
+    /// Checks length, version byte, payload length & checksum
     pub fn is_valid(input: &str) -> bool {
-        let prefix: String = input.chars().take(2).collect();
-        input.len() == LedgerHash::LEN && LedgerHash::PREFIX.contains(&prefix.as_str())
+        input.len() == LedgerHash::LEN && Base58Kind::LedgerHash.validate(input).is_ok()
     }
 }
 
@@ -83,9 +86,11 @@ impl std::str::FromStr for LedgerHash {
 // default //
 /////////////
 
+/// Well-formed placeholder whose all-ones hash exceeds the field modulus, so
+/// it's never a real ledger hash
 impl std::default::Default for LedgerHash {
     fn default() -> Self {
-        Self("jxDEFAULTDEFAULTDEFAULTDEFAULTDEFAULTDEFAULTDEFAULT".into())
+        Self("jy3MAGPwUS3Kri1BC8hyn7do1EVcPmKZnPo1m2rFP5aBe9dDZbN".into())
     }
 }
 
@@ -1,6 +1,7 @@
 pub mod account;
 pub mod coinbase;
 pub mod diff;
+pub mod distribution;
 pub mod genesis;
 pub mod hash;
 pub mod staking;
@@ -11,7 +12,7 @@ pub mod username;
 use crate::{
     base::{amount::Amount, nonce::Nonce, public_key::PublicKey},
     block::precomputed::PrecomputedBlock,
-    constants::MAINNET_ACCOUNT_CREATION_FEE,
+    chain::profile::ProtocolParams,
     ledger::{
         account::Account,
         diff::{account::AccountDiff, LedgerDiff},
@@ -111,8 +112,12 @@ impl Ledger {
     }
 
     /// Apply the ledger diff from a PCB
-    pub fn apply_diff_from_precomputed(self, block: &PrecomputedBlock) -> anyhow::Result<Self> {
-        let diff = LedgerDiff::from_precomputed(block);
+    pub fn apply_diff_from_precomputed(
+        self,
+        block: &PrecomputedBlock,
+        protocol: &ProtocolParams,
+    ) -> anyhow::Result<Self> {
+        let diff = LedgerDiff::from_precomputed(block, protocol);
         self.apply_diff(&diff)
     }
 
@@ -216,20 +221,62 @@ impl Ledger {
         Ok(Self::from_mina_ledger(ledger))
     }
 
-    pub fn to_string_pretty(&self) -> String {
-        let mut tokens = HashMap::new();
+    pub fn to_string_pretty(&self, protocol: &ProtocolParams) -> String {
+        let mut bytes = vec![];
+        self.write_pretty(&mut bytes, protocol).unwrap();
 
-        for (token, token_ledger) in self.tokens.iter() {
-            let mut accounts = HashMap::new();
+        String::from_utf8(bytes).unwrap()
+    }
 
-            for (pk, acct) in token_ledger.accounts.iter() {
-                accounts.insert(pk.to_address(), acct.clone().display());
-            }
+    /// Stream the pretty JSON display view of the ledger to `writer`
+    /// one account at a time
+    pub fn write_pretty<W: std::io::Write>(
+        &self,
+        writer: W,
+        protocol: &ProtocolParams,
+    ) -> anyhow::Result<()> {
+        Ok(serde_json::to_writer_pretty(
+            writer,
+            &LedgerDisplay(self, protocol),
+        )?)
+    }
+}
 
-            tokens.insert(token.0.to_owned(), accounts);
-        }
+/// Display view of a ledger, serialized without materializing the
+/// display accounts
+struct LedgerDisplay<'a>(&'a Ledger, &'a ProtocolParams);
+
+struct TokenLedgerDisplay<'a>(&'a TokenLedger, &'a ProtocolParams);
+
+impl Serialize for LedgerDisplay<'_> {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_map(
+            self.0
+                .tokens
+                .iter()
+                .map(|(token, token_ledger)| (&token.0, TokenLedgerDisplay(token_ledger, self.1))),
+        )
+    }
+}
+
+impl std::fmt::Display for TokenLedgerDisplay<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}",
+            serde_json::to_string(self).map_err(|_| std::fmt::Error)?
+        )
+    }
+}
 
-        serde_json::to_string_pretty(&tokens).unwrap()
+impl Serialize for TokenLedgerDisplay<'_> {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_map(
+            self.0
+                .accounts
+                .iter()
+                .map(|(pk, acct)| (pk.to_address(), acct.clone().display(self.1))),
+        )
     }
 }
 
@@ -257,11 +304,13 @@ impl Eq for Ledger {}
 
 impl std::fmt::Debug for Ledger {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let protocol = ProtocolParams::default();
+
         for (token, token_ledger) in self.tokens.iter() {
             writeln!(f, "{token}:")?;
 
             for (pk, acct) in token_ledger.accounts.iter() {
-                writeln!(f, "  {pk} -> {}", acct.clone().display().balance.0)?;
+                writeln!(f, "  {pk} -> {}", acct.clone().display(&protocol).balance.0)?;
             }
         }
 
@@ -288,8 +337,12 @@ impl TokenLedger {
         }
     }
 
-    pub fn apply_diff_from_precomputed(self, block: &PrecomputedBlock) -> anyhow::Result<Self> {
-        let diff = LedgerDiff::from_precomputed(block);
+    pub fn apply_diff_from_precomputed(
+        self,
+        block: &PrecomputedBlock,
+        protocol: &ProtocolParams,
+    ) -> anyhow::Result<Self> {
+        let diff = LedgerDiff::from_precomputed(block, protocol);
         self.apply_diff(&diff)
     }
 
@@ -379,29 +432,19 @@ impl TokenLedger {
         Ok(ledger)
     }
 
-    pub fn to_string_pretty(&self) -> String {
-        let mut accounts = HashMap::new();
-        for (pk, acct) in self.accounts.iter() {
-            accounts.insert(pk.to_address(), acct.clone().display());
-        }
-        serde_json::to_string_pretty(&accounts).unwrap()
+    pub fn to_string_pretty(&self, protocol: &ProtocolParams) -> String {
+        serde_json::to_string_pretty(&TokenLedgerDisplay(self, protocol)).unwrap()
     }
-}
 
-impl std::fmt::Display for TokenLedger {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        let mut accounts = HashMap::new();
-        for (pk, acct) in self.accounts.iter() {
-            accounts.insert(pk.to_address(), acct.clone().display());
-        }
-        write!(f, "{}", serde_json::to_string(&accounts).unwrap())
+    /// Display view of the token ledger, with balances net of the
+    /// network's account creation fee
+    pub fn display<'a>(&'a self, protocol: &'a ProtocolParams) -> impl std::fmt::Display + 'a {
+        TokenLedgerDisplay(self, protocol)
     }
-}
-
-impl FromStr for TokenLedger {
-    type Err = anyhow::Error;
 
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
+    /// Parse the display view of a token ledger, compensating for the
+    /// network's account creation fee deduction
+    pub fn from_display_str(s: &str, protocol: &ProtocolParams) -> anyhow::Result<Self> {
         let deser: HashMap<String, Account> = serde_json::from_str(s)?;
         let mut accounts = HashMap::new();
 
@@ -410,8 +453,8 @@ impl FromStr for TokenLedger {
                 pk.into(),
                 Account {
                     // compensate for display deduction
-                    balance: acct.balance + MAINNET_ACCOUNT_CREATION_FEE,
-                    ..acct.clone()
+                    balance: acct.balance + protocol.account_creation_fee,
+                    ..acct
                 },
             );
         }
@@ -420,14 +463,39 @@ impl FromStr for TokenLedger {
     }
 }
 
+/// Mainnet display view, see [TokenLedger::display]
+impl std::fmt::Display for TokenLedger {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.display(&ProtocolParams::default()))
+    }
+}
+
+/// Parses the mainnet display view, see [TokenLedger::from_display_str]
+impl FromStr for TokenLedger {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::from_display_str(s, &ProtocolParams::default())
+    }
+}
+
 impl PartialEq for TokenLedger {
     fn eq(&self, other: &Self) -> bool {
+        let protocol = ProtocolParams::default();
+
         for pk in self.accounts.keys() {
             if self.accounts.get(pk) != other.accounts.get(pk) {
                 println!(
                     "[TokenLedger.eq mismatch] {pk:?} | {:?} | {:?}",
-                    self.accounts.get(pk).cloned().map(Account::display),
-                    other.accounts.get(pk).cloned().map(Account::display),
+                    self.accounts
+                        .get(pk)
+                        .cloned()
+                        .map(|acct| acct.display(&protocol)),
+                    other
+                        .accounts
+                        .get(pk)
+                        .cloned()
+                        .map(|acct| acct.display(&protocol)),
                 );
 
                 return false;
@@ -438,8 +506,15 @@ impl PartialEq for TokenLedger {
             if self.accounts.get(pk) != other.accounts.get(pk) {
                 println!(
                     "[TokenLedger.eq mismatch] {pk:?} | {:?} | {:?}",
-                    self.accounts.get(pk).cloned().map(Account::display),
-                    other.accounts.get(pk).cloned().map(Account::display),
+                    self.accounts
+                        .get(pk)
+                        .cloned()
+                        .map(|acct| acct.display(&protocol)),
+                    other
+                        .accounts
+                        .get(pk)
+                        .cloned()
+                        .map(|acct| acct.display(&protocol)),
                 );
 
                 return false;
@@ -454,8 +529,10 @@ impl Eq for TokenLedger {}
 
 impl std::fmt::Debug for TokenLedger {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let protocol = ProtocolParams::default();
+
         for (pk, acct) in &self.accounts {
-            writeln!(f, "{pk} -> {}", acct.clone().display().balance.0)?;
+            writeln!(f, "{pk} -> {}", acct.clone().display(&protocol).balance.0)?;
         }
         writeln!(f)?;
         Ok(())
@@ -474,6 +551,7 @@ mod tests {
     };
     use crate::{
         base::{nonce::Nonce, public_key::PublicKey, state_hash::StateHash},
+        chain::profile::ProtocolParams,
         constants::MINA_SCALE,
         ledger::{token::TokenAddress, TokenLedger},
     };
@@ -571,4 +649,31 @@ mod tests {
             }
         );
     }
+
+    #[test]
+    fn token_ledger_display_roundtrip() -> anyhow::Result<()> {
+        let public_key = PublicKey::new("B62qre3erTHfzQckNuibViWQGyyKwZseztqrjPZBv6SQF384Rg6ESAy");
+        let mut accounts = HashMap::new();
+        accounts.insert(
+            public_key.clone(),
+            Account {
+                balance: Amount(42 * MINA_SCALE),
+                ..Account::empty(public_key, TokenAddress::default())
+            },
+        );
+        let ledger = TokenLedger { accounts };
+
+        // mainnet params via Display/FromStr
+        assert_eq!(ledger.to_string().parse::<TokenLedger>()?, ledger);
+
+        // explicit params
+        let protocol = ProtocolParams {
+            account_creation_fee: MINA_SCALE / 10,
+            ..Default::default()
+        };
+        let display = ledger.display(&protocol).to_string();
+        assert_ne!(display, ledger.to_string());
+        assert_eq!(TokenLedger::from_display_str(&display, &protocol)?, ledger);
+        Ok(())
+    }
 }
@@ -1,14 +1,17 @@
 pub mod parser;
+pub mod payouts;
+pub mod performance;
 pub mod permissions;
 
 use crate::{
     base::{nonce::Nonce, public_key::PublicKey, state_hash::StateHash},
     block::{extract_height_and_hash, extract_network},
-    chain::Network,
-    constants::MINA_SCALE_DEC,
+    chain::{profile::ProtocolParams, Network},
+    constants::{EMPTY_RECEIPT_CHAIN_HASH, MINA_SCALE_DEC, MINA_TOKEN_ID, ZERO_STATE_HASH},
     ledger::{
-        account::{ReceiptChainHash, Timing},
-        LedgerHash,
+        account::{Account, ReceiptChainHash, Timing},
+        token::TokenAddress,
+        Ledger, LedgerHash,
     },
     mina_blocks::v2::ZkappAccount,
 };
@@ -30,6 +33,21 @@ pub struct StakingLedger {
     pub total_currency: u64,
     pub genesis_state_hash: StateHash,
     pub staking_ledger: HashMap<PublicKey, StakingAccount>,
+
+    #[serde(default)]
+    pub source: StakingLedgerSource,
+}
+
+/// Where the indexer got a staking ledger from
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum StakingLedgerSource {
+    /// Parsed from a staking ledger file
+    #[default]
+    Imported,
+
+    /// Derived from the indexer's own canonical ledger
+    Derived,
 }
 
 #[derive(Debug, PartialEq, Eq, Serialize, Deserialize)]
@@ -144,6 +162,29 @@ impl From<StakingAccountJson> for StakingAccount {
     }
 }
 
+impl StakingAccount {
+    /// Staking account of the indexer's ledger `account`
+    pub fn from_ledger_account(account: Account, protocol: &ProtocolParams) -> Self {
+        // ledger balances include the account creation fee
+        let value = account.display(protocol);
+        Self {
+            pk: value.public_key,
+            balance: value.balance.0,
+            delegate: value.delegate,
+            username: value.username.map(|username| username.0),
+            token: Some(MINA_TOKEN_ID),
+            permissions: value.permissions.map(Into::into).unwrap_or_default(),
+            receipt_chain_hash: value
+                .receipt_chain_hash
+                .unwrap_or_else(|| EMPTY_RECEIPT_CHAIN_HASH.into()),
+            voting_for: value.voting_for.unwrap_or_else(|| ZERO_STATE_HASH.into()),
+            nonce: value.nonce,
+            timing: value.timing,
+            zkapp: value.zkapp,
+        }
+    }
+}
+
 pub fn is_valid_ledger_file(path: &Path) -> bool {
     crate::utility::functions::is_valid_file_name(path, &LedgerHash::is_valid)
 }
@@ -164,13 +205,24 @@ impl StakingLedger {
             path.file_stem().unwrap().to_str().unwrap_or_default()
         );
         let bytes = std::fs::read(path)?;
-        let staking_ledger: Vec<StakingAccountJson> = serde_json::from_slice(&bytes)
-            .with_context(|| format!("Failed reading staking ledger {}", path.display()))?;
+        let (network, epoch, ledger_hash) = split_ledger_path(path);
+        Self::from_bytes(&bytes, network, epoch, ledger_hash, genesis_state_hash)
+            .with_context(|| format!("Failed reading staking ledger {}", path.display()))
+    }
+
+    /// Parse the staking ledger JSON accounts
+    pub fn from_bytes(
+        bytes: &[u8],
+        network: Network,
+        epoch: u32,
+        ledger_hash: LedgerHash,
+        genesis_state_hash: StateHash,
+    ) -> anyhow::Result<StakingLedger> {
+        let staking_ledger: Vec<StakingAccountJson> = serde_json::from_slice(bytes)?;
         let staking_ledger: HashMap<PublicKey, StakingAccount> = staking_ledger
             .into_iter()
             .map(|acct| (acct.pk.clone(), acct.into()))
             .collect();
-        let (network, epoch, ledger_hash) = split_ledger_path(path);
         let total_currency: u64 = staking_ledger.values().map(|account| account.balance).sum();
         Ok(Self {
             epoch,
@@ -179,9 +231,53 @@ impl StakingLedger {
             ledger_hash,
             staking_ledger,
             genesis_state_hash,
+            source: StakingLedgerSource::Imported,
         })
     }
 
+    /// Derive the staking ledger from the MINA accounts of the indexer's
+    /// ledger whose hash is `ledger_hash`
+    pub fn derive(
+        ledger: &Ledger,
+        epoch: u32,
+        network: Network,
+        ledger_hash: LedgerHash,
+        genesis_state_hash: StateHash,
+        protocol: &ProtocolParams,
+    ) -> Self {
+        let staking_ledger: HashMap<PublicKey, StakingAccount> = ledger
+            .tokens
+            .get(&TokenAddress::default())
+            .into_iter()
+            .flat_map(|token_ledger| token_ledger.accounts.iter())
+            .map(|(pk, account)| {
+                (
+                    pk.clone(),
+                    StakingAccount::from_ledger_account(account.clone(), protocol),
+                )
+            })
+            .collect();
+        let total_currency = staking_ledger.values().map(|account| account.balance).sum();
+        Self {
+            epoch,
+            network,
+            total_currency,
+            ledger_hash,
+            staking_ledger,
+            genesis_state_hash,
+            source: StakingLedgerSource::Derived,
+        }
+    }
+
+    /// Total balance delegated to another account
+    pub fn staked_supply(&self) -> u64 {
+        self.staking_ledger
+            .values()
+            .filter(|account| account.pk != account.delegate)
+            .map(|account| account.balance)
+            .sum()
+    }
+
     /// Aggregate each public key's staking delegations and total delegations
     /// If the public key has delegated, they cannot be delegated to
     pub fn aggregate_delegations(&self) -> anyhow::Result<AggregatedEpochStakeDelegations> {
@@ -252,12 +348,21 @@ impl StakingLedger {
 
     pub fn summary(&self) -> String {
         format!(
-            "{} (epoch {}): {}",
-            self.network, self.epoch, self.ledger_hash
+            "{} (epoch {}, {}): {}",
+            self.network, self.epoch, self.source, self.ledger_hash
         )
     }
 }
 
+impl std::fmt::Display for StakingLedgerSource {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Imported => write!(f, "imported"),
+            Self::Derived => write!(f, "derived"),
+        }
+    }
+}
+
 impl From<String> for LedgerHash {
     fn from(value: String) -> Self {
         Self(value)
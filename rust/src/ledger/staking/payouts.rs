@@ -0,0 +1,355 @@
+//! Staking pool delegator payouts
+
+use super::StakingAccount;
+use crate::{
+    base::{public_key::PublicKey, state_hash::StateHash},
+    block::precomputed::PrecomputedBlock,
+    chain::profile::ProtocolParams,
+    ledger::{coinbase::Coinbase, LedgerHash},
+};
+use anyhow::bail;
+use serde::{Deserialize, Serialize};
+
+/// Pool fee precision (parts per million)
+const POOL_FEE_SCALE: u128 = 1_000_000;
+
+/// Rules used to split a producer's epoch rewards among its delegators
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PayoutConfig {
+    /// Percentage of each delegator's share kept by the pool
+    pub pool_fee: f64,
+
+    /// Distribute the user command fees of won blocks
+    pub include_tx_fees: bool,
+
+    /// Only distribute supercharged coinbase bonuses to unlocked delegators
+    pub supercharge_unlocked_only: bool,
+}
+
+/// Rewards of a canonical block won by the producer
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PayoutBlock {
+    pub state_hash: StateHash,
+    pub blockchain_length: u32,
+    pub global_slot: u32,
+    pub coinbase: u64,
+    pub supercharged: bool,
+
+    /// Extra coinbase paid because the winning stake was unlocked
+    pub supercharge_bonus: u64,
+    pub tx_fees: u64,
+    pub snark_fees: u64,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DelegatorPayout {
+    pub public_key: PublicKey,
+    pub stake: u64,
+
+    /// Share of the block rewards before the pool fee
+    pub gross: u64,
+    pub pool_fee: u64,
+    pub payout: u64,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct EpochPayouts {
+    pub producer: PublicKey,
+    pub epoch: u32,
+    pub ledger_hash: LedgerHash,
+    pub config: PayoutConfig,
+    pub total_stake: u64,
+    pub total_rewards: u64,
+    pub total_pool_fees: u64,
+    pub blocks: Vec<PayoutBlock>,
+    pub payouts: Vec<DelegatorPayout>,
+}
+
+//////////
+// impl //
+//////////
+
+impl Default for PayoutConfig {
+    fn default() -> Self {
+        Self {
+            pool_fee: 0.0,
+            include_tx_fees: true,
+            supercharge_unlocked_only: true,
+        }
+    }
+}
+
+impl PayoutConfig {
+    pub fn validate(&self) -> anyhow::Result<()> {
+        if !(0.0..=100.0).contains(&self.pool_fee) {
+            bail!("Pool fee must be a percentage, got {}", self.pool_fee)
+        }
+        Ok(())
+    }
+
+    /// Pool fee in parts per million
+    fn pool_fee_ppm(&self) -> u128 {
+        (self.pool_fee * 10_000.0).round() as u128
+    }
+}
+
+impl PayoutBlock {
+    /// Total rewards distributed to the delegators, the producer pays for
+    /// the block's SNARK work
+    pub fn reward(&self, config: &PayoutConfig) -> u64 {
+        let tx_fees = if config.include_tx_fees {
+            self.tx_fees
+        } else {
+            0
+        };
+        (self.coinbase + tx_fees).saturating_sub(self.snark_fees)
+    }
+
+    pub fn from_precomputed(block: &PrecomputedBlock, protocol: &ProtocolParams) -> Self {
+        let coinbase = Coinbase::from_precomputed(block, protocol);
+        let (coinbase_amount, supercharge_bonus) = if coinbase.is_coinbase_applied() {
+            (coinbase.amount, coinbase.amount - protocol.coinbase(false))
+        } else {
+            (0, 0)
+        };
+
+        Self {
+            state_hash: block.state_hash(),
+            blockchain_length: block.blockchain_length(),
+            global_slot: block.global_slot_since_genesis(),
+            coinbase: coinbase_amount,
+            supercharged: coinbase.supercharge,
+            supercharge_bonus,
+            tx_fees: block.tx_fees(),
+            snark_fees: block.snark_fees(),
+        }
+    }
+}
+
+impl EpochPayouts {
+    /// Split the rewards of the producer's won `blocks` among its `epoch`
+    /// delegators, pro rata by stake
+    pub fn compute(
+        producer: PublicKey,
+        epoch: u32,
+        ledger_hash: LedgerHash,
+        delegators: &[StakingAccount],
+        blocks: Vec<PayoutBlock>,
+        config: PayoutConfig,
+    ) -> Self {
+        let stakes: Vec<u64> = delegators.iter().map(|account| account.balance).collect();
+        let total_stake: u64 = stakes.iter().sum();
+
+        let mut gross = vec![0; delegators.len()];
+        let mut total_rewards = 0;
+        for block in blocks.iter() {
+            let reward = block.reward(&config);
+            let bonus = if config.supercharge_unlocked_only {
+                block.supercharge_bonus.min(reward)
+            } else {
+                0
+            };
+            total_rewards += reward;
+            distribute(reward - bonus, &stakes, &mut gross);
+
+            if bonus > 0 {
+                // only unlocked stake earns the supercharged bonus
+                let unlocked: Vec<u64> = delegators
+                    .iter()
+                    .map(|account| match account.timing.as_ref() {
+                        Some(timing) if timing.minimum_balance(block.global_slot) > 0 => 0,
+                        _ => account.balance,
+                    })
+                    .collect();
+
+                if unlocked.iter().any(|stake| *stake > 0) {
+                    distribute(bonus, &unlocked, &mut gross);
+                } else {
+                    distribute(bonus, &stakes, &mut gross);
+                }
+            }
+        }
+
+        let fee_ppm = config.pool_fee_ppm();
+        let mut payouts: Vec<DelegatorPayout> = delegators
+            .iter()
+            .zip(gross)
+            .map(|(account, gross)| {
+                // the producer does not pay itself a fee
+                let pool_fee = if account.pk == producer {
+                    0
+                } else {
+                    (gross as u128 * fee_ppm / POOL_FEE_SCALE) as u64
+                };
+                DelegatorPayout {
+                    public_key: account.pk.clone(),
+                    stake: account.balance,
+                    gross,
+                    pool_fee,
+                    payout: gross - pool_fee,
+                }
+            })
+            .collect();
+        payouts.sort_by(|a, b| {
+            b.stake
+                .cmp(&a.stake)
+                .then_with(|| a.public_key.cmp(&b.public_key))
+        });
+
+        Self {
+            producer,
+            epoch,
+            ledger_hash,
+            config,
+            total_stake,
+            total_rewards,
+            total_pool_fees: payouts.iter().map(|payout| payout.pool_fee).sum(),
+            blocks,
+            payouts,
+        }
+    }
+
+    /// CSV of the delegator payouts
+    pub fn to_csv(&self) -> anyhow::Result<String> {
+        let mut csv_writer = csv::WriterBuilder::new()
+            .has_headers(true)
+            .from_writer(vec![]);
+        for payout in self.payouts.iter() {
+            csv_writer.serialize(payout)?;
+        }
+        Ok(String::from_utf8(csv_writer.into_inner()?)?)
+    }
+}
+
+/// Add each weight's pro rata share of `amount` to `acc`, rounding down
+fn distribute(amount: u64, weights: &[u64], acc: &mut [u64]) {
+    let total: u128 = weights.iter().map(|weight| *weight as u128).sum();
+    if total == 0 {
+        return;
+    }
+
+    for (weight, acc) in weights.iter().zip(acc.iter_mut()) {
+        *acc += (amount as u128 * *weight as u128 / total) as u64;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        constants::{MAINNET_COINBASE_REWARD, MINA_SCALE},
+        ledger::staking::StakingAccountJson,
+    };
+    use serde_json::json;
+
+    fn account(
+        pk: &str,
+        balance: &str,
+        delegate: &str,
+        timing: Option<serde_json::Value>,
+    ) -> StakingAccount {
+        let mut account = json!({
+            "pk": pk,
+            "balance": balance,
+            "delegate": delegate,
+            "token": "1",
+            "receipt_chain_hash": "2mzbV7WevxLuchs2dAMY4vQBS6XttnCUF8Hvks4XNBQ5qiSGGBQe",
+            "voting_for": "3NK2tkzqqK5spR2sZ7tujjqPksL45M3UUrcA4WhCkeiPtnugyE2x",
+            "permissions": {
+                "stake": true,
+                "edit_state": "signature",
+                "send": "signature",
+                "set_delegate": "signature",
+                "set_permissions": "signature",
+                "set_verification_key": "signature"
+            }
+        });
+        if let Some(timing) = timing {
+            account["timing"] = timing;
+        }
+        serde_json::from_value::<StakingAccountJson>(account)
+            .unwrap()
+            .into()
+    }
+
+    fn block(supercharged: bool) -> PayoutBlock {
+        PayoutBlock {
+            state_hash: StateHash::default(),
+            blockchain_length: 1,
+            global_slot: 1,
+            coinbase: MAINNET_COINBASE_REWARD * if supercharged { 2 } else { 1 },
+            supercharged,
+            supercharge_bonus: if supercharged {
+                MAINNET_COINBASE_REWARD
+            } else {
+                0
+            },
+            tx_fees: 100,
+            snark_fees: 40,
+        }
+    }
+
+    #[test]
+    fn split_supercharged_rewards() {
+        let producer = "B62qrecVjpoZ4Re3a5arN6gXZ6orhmj1enUtA887XdG5mtZfdUbBUh4";
+        let locked = "B62qmCwouxG2UzH6zEYGFWFFzUuSv9sbLnr96VJWDX3paSSucX7jAJN";
+        let delegators = [
+            account(producer, "1000", producer, None),
+            account(
+                locked,
+                "3000",
+                producer,
+                Some(json!({
+                    "initial_minimum_balance": "3000",
+                    "cliff_time": "100",
+                    "cliff_amount": "0",
+                    "vesting_period": "1",
+                    "vesting_increment": "0"
+                })),
+            ),
+        ];
+        let config = PayoutConfig {
+            pool_fee: 10.0,
+            ..Default::default()
+        };
+        let payouts = EpochPayouts::compute(
+            producer.into(),
+            0,
+            LedgerHash::default(),
+            &delegators,
+            vec![block(true), block(false)],
+            config,
+        );
+
+        // the supercharged bonus only goes to the unlocked producer
+        let base = 2 * MAINNET_COINBASE_REWARD + 2 * 60;
+        assert_eq!(payouts.total_stake, 4_000 * MINA_SCALE);
+        assert_eq!(payouts.total_rewards, base + MAINNET_COINBASE_REWARD);
+
+        let locked_payout = &payouts.payouts[0];
+        assert_eq!(locked_payout.public_key.0, locked);
+        assert_eq!(locked_payout.gross, base * 3 / 4);
+        assert_eq!(locked_payout.pool_fee, base * 3 / 40);
+        assert_eq!(locked_payout.payout, base * 3 / 4 - base * 3 / 40);
+
+        let producer_payout = &payouts.payouts[1];
+        assert_eq!(producer_payout.gross, base / 4 + MAINNET_COINBASE_REWARD);
+        assert_eq!(producer_payout.pool_fee, 0);
+        assert_eq!(payouts.total_pool_fees, locked_payout.pool_fee);
+    }
+
+    #[test]
+    fn exclude_tx_fees() {
+        let config = PayoutConfig {
+            include_tx_fees: false,
+            ..Default::default()
+        };
+        assert_eq!(block(false).reward(&config), MAINNET_COINBASE_REWARD - 40);
+        assert!(PayoutConfig {
+            pool_fee: 101.0,
+            ..Default::default()
+        }
+        .validate()
+        .is_err());
+    }
+}
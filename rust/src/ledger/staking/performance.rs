@@ -0,0 +1,208 @@
+//! Block producer performance relative to stake
+
+use crate::base::public_key::PublicKey;
+use anyhow::bail;
+use serde::{Deserialize, Serialize};
+use std::{cmp::Ordering, str::FromStr};
+
+/// Producer's epoch block production counts
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ProducerBlockCounts {
+    pub num_blocks_produced: u32,
+    pub num_canonical_blocks_produced: u32,
+    pub num_supercharged_blocks_produced: u32,
+    pub num_slots_produced: u32,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ProducerPerformance {
+    pub public_key: PublicKey,
+    pub epoch: u32,
+
+    /// Total stake delegated to the producer
+    pub stake: u64,
+
+    /// Staking ledger total currency
+    pub total_currency: u64,
+    pub stake_share: f64,
+
+    pub num_blocks_produced: u32,
+    pub num_canonical_blocks_produced: u32,
+    pub num_orphaned_blocks: u32,
+    pub num_supercharged_blocks_produced: u32,
+    pub num_slots_produced: u32,
+
+    /// Number of epoch slots filled by any producer
+    pub epoch_slots_produced: u32,
+
+    /// Number of epoch slots which have passed
+    pub epoch_slots_elapsed: u32,
+    pub slot_fill_rate: f64,
+
+    /// Statistically expected number of canonical blocks given the stake
+    /// share & slot fill rate
+    pub expected_blocks: f64,
+
+    /// Canonical blocks produced per expected block
+    pub luck: Option<f64>,
+
+    /// Fraction of produced blocks which are canonical
+    pub reliability: Option<f64>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ProducerPerformanceSort {
+    Stake,
+    Luck,
+    Reliability,
+    CanonicalBlocks,
+}
+
+//////////
+// impl //
+//////////
+
+impl ProducerPerformance {
+    pub fn new(
+        public_key: PublicKey,
+        epoch: u32,
+        stake: u64,
+        total_currency: u64,
+        counts: ProducerBlockCounts,
+        epoch_slots_produced: u32,
+        epoch_slots_elapsed: u32,
+    ) -> Self {
+        let stake_share = ratio(stake as f64, total_currency as f64).unwrap_or_default();
+        let slot_fill_rate =
+            ratio(epoch_slots_produced as f64, epoch_slots_elapsed as f64).unwrap_or_default();
+        let expected_blocks = stake_share * epoch_slots_elapsed as f64 * slot_fill_rate;
+
+        Self {
+            public_key,
+            epoch,
+            stake,
+            total_currency,
+            stake_share,
+            num_blocks_produced: counts.num_blocks_produced,
+            num_canonical_blocks_produced: counts.num_canonical_blocks_produced,
+            num_orphaned_blocks: counts
+                .num_blocks_produced
+                .saturating_sub(counts.num_canonical_blocks_produced),
+            num_supercharged_blocks_produced: counts.num_supercharged_blocks_produced,
+            num_slots_produced: counts.num_slots_produced,
+            epoch_slots_produced,
+            epoch_slots_elapsed,
+            slot_fill_rate,
+            expected_blocks,
+            luck: ratio(counts.num_canonical_blocks_produced as f64, expected_blocks),
+            reliability: ratio(
+                counts.num_canonical_blocks_produced as f64,
+                counts.num_blocks_produced as f64,
+            ),
+        }
+    }
+
+    /// Sort the producers in descending order, ties are broken by stake
+    pub fn sort(performances: &mut [Self], sort: ProducerPerformanceSort) {
+        use ProducerPerformanceSort::*;
+
+        fn cmp_opt(a: Option<f64>, b: Option<f64>) -> Ordering {
+            a.unwrap_or(f64::NEG_INFINITY)
+                .total_cmp(&b.unwrap_or(f64::NEG_INFINITY))
+        }
+
+        performances.sort_by(|a, b| {
+            let ord = match sort {
+                Stake => Ordering::Equal,
+                Luck => cmp_opt(a.luck, b.luck),
+                Reliability => cmp_opt(a.reliability, b.reliability),
+                CanonicalBlocks => a
+                    .num_canonical_blocks_produced
+                    .cmp(&b.num_canonical_blocks_produced),
+            };
+            ord.then_with(|| a.stake.cmp(&b.stake))
+                .then_with(|| b.public_key.cmp(&a.public_key))
+                .reverse()
+        });
+    }
+}
+
+fn ratio(num: f64, denom: f64) -> Option<f64> {
+    if denom > 0.0 {
+        Some(num / denom)
+    } else {
+        None
+    }
+}
+
+impl FromStr for ProducerPerformanceSort {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "stake" => Ok(Self::Stake),
+            "luck" => Ok(Self::Luck),
+            "reliability" => Ok(Self::Reliability),
+            "canonical-blocks" => Ok(Self::CanonicalBlocks),
+            _ => bail!(
+                "Invalid producer performance sort {s}, expected one of: stake, luck, reliability, canonical-blocks"
+            ),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PK0: &str = "B62qrecVjpoZ4Re3a5arN6gXZ6orhmj1enUtA887XdG5mtZfdUbBUh4";
+    const PK1: &str = "B62qmCwouxG2UzH6zEYGFWFFzUuSv9sbLnr96VJWDX3paSSucX7jAJN";
+
+    fn performance(pk: &str, stake: u64, produced: u32, canonical: u32) -> ProducerPerformance {
+        ProducerPerformance::new(
+            pk.into(),
+            0,
+            stake,
+            1_000,
+            ProducerBlockCounts {
+                num_blocks_produced: produced,
+                num_canonical_blocks_produced: canonical,
+                num_supercharged_blocks_produced: 0,
+                num_slots_produced: produced,
+            },
+            100,
+            200,
+        )
+    }
+
+    #[test]
+    fn expected_blocks() {
+        let perf = performance(PK0, 100, 12, 10);
+
+        assert_eq!(perf.stake_share, 0.1);
+        assert_eq!(perf.slot_fill_rate, 0.5);
+        assert_eq!(perf.expected_blocks, 10.0);
+        assert_eq!(perf.num_orphaned_blocks, 2);
+        assert_eq!(perf.luck, Some(1.0));
+        assert_eq!(perf.reliability, Some(10.0 / 12.0));
+
+        // no stake, no expected blocks
+        let perf = performance(PK1, 0, 0, 0);
+        assert_eq!(perf.expected_blocks, 0.0);
+        assert_eq!(perf.luck, None);
+        assert_eq!(perf.reliability, None);
+    }
+
+    #[test]
+    fn sort_by_luck() -> anyhow::Result<()> {
+        let mut performances = vec![performance(PK0, 100, 5, 5), performance(PK1, 100, 20, 15)];
+
+        ProducerPerformance::sort(&mut performances, "luck".parse()?);
+        assert_eq!(performances[0].public_key.0, PK1);
+
+        ProducerPerformance::sort(&mut performances, "reliability".parse()?);
+        assert_eq!(performances[0].public_key.0, PK0);
+        assert!("fastest".parse::<ProducerPerformanceSort>().is_err());
+        Ok(())
+    }
+}
@@ -1,3 +1,4 @@
+use crate::ledger::account::{self, Permissions};
 use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
@@ -17,3 +18,38 @@ pub enum Permission {
     Signature,
     Proof,
 }
+
+impl Default for StakingPermissions {
+    fn default() -> Self {
+        Self {
+            stake: true,
+            edit_state: Permission::Signature,
+            send: Permission::Signature,
+            set_delegate: Permission::Signature,
+            set_permissions: Permission::Signature,
+            set_verification_key: Permission::Signature,
+        }
+    }
+}
+
+impl From<Permissions> for StakingPermissions {
+    fn from(value: Permissions) -> Self {
+        Self {
+            stake: true,
+            edit_state: value.edit_state.into(),
+            send: value.send.into(),
+            set_delegate: value.set_delegate.into(),
+            set_permissions: value.set_permissions.into(),
+            set_verification_key: value.set_verification_key.0.into(),
+        }
+    }
+}
+
+impl From<account::Permission> for Permission {
+    fn from(value: account::Permission) -> Self {
+        match value {
+            account::Permission::Proof => Self::Proof,
+            _ => Self::Signature,
+        }
+    }
+}
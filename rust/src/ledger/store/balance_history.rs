@@ -0,0 +1,322 @@
+//! Store of best chain account balance changes
+
+use crate::{
+    base::{nonce::Nonce, public_key::PublicKey, state_hash::StateHash},
+    block::store::BlockUpdate,
+    command::signed::TxnHash,
+    ledger::{
+        diff::{
+            account::{AccountDiff, UpdateType},
+            LedgerDiff,
+        },
+        token::TokenAddress,
+        Ledger,
+    },
+    store::Result,
+    utility::deadline::Deadline,
+};
+use serde::{Deserialize, Serialize};
+use speedb::{DBIterator, Direction, IteratorMode};
+use std::collections::HashMap;
+
+pub trait BalanceHistoryStore {
+    /// Record the balance changes caused by applying the block's ledger diff
+    /// to the best ledger. `balances` maps each token account touched by the
+    /// diff to its (display) balance before & after the diff was applied
+    fn add_block_balance_changes(
+        &self,
+        block: &BlockUpdate,
+        diff: &LedgerDiff,
+        balances: &HashMap<(PublicKey, TokenAddress), (u64, u64)>,
+    ) -> Result<()>;
+
+    /// Record the initial balances of the genesis ledger accounts
+    fn add_genesis_balance_changes(
+        &self,
+        state_hash: &StateHash,
+        block_height: u32,
+        genesis_ledger: &Ledger,
+    ) -> Result<()>;
+
+    /// Remove the balance changes recorded for an unapplied block
+    fn remove_block_balance_changes(&self, block: &BlockUpdate, diff: &LedgerDiff) -> Result<()>;
+
+    /// Get the token account's balance change at the given block height
+    fn get_balance_change(
+        &self,
+        pk: &PublicKey,
+        token: &TokenAddress,
+        block_height: u32,
+    ) -> Result<Option<BalanceChange>>;
+
+    /// Get the first `limit` of the token account's balance changes between
+    /// the given block heights (inclusive), in the given height direction
+    #[allow(clippy::too_many_arguments)]
+    fn get_balance_history(
+        &self,
+        pk: &PublicKey,
+        token: &TokenAddress,
+        start_height: u32,
+        end_height: u32,
+        limit: usize,
+        direction: Direction,
+        deadline: Option<&Deadline>,
+    ) -> Result<Vec<BalanceChange>>;
+
+    /// Get the token account's best chain balance at the given block height
+    fn get_balance_at_height(
+        &self,
+        pk: &PublicKey,
+        token: &TokenAddress,
+        block_height: u32,
+    ) -> Result<Option<u64>>;
+
+    /// Get the token account's best chain balance at the given global slot
+    fn get_balance_at_slot(
+        &self,
+        pk: &PublicKey,
+        token: &TokenAddress,
+        global_slot: u32,
+        deadline: Option<&Deadline>,
+    ) -> Result<Option<u64>>;
+
+    ///////////////
+    // Iterators //
+    ///////////////
+
+    /// Iterator for token account balance changes
+    /// ```
+    /// {token}{pk}{height} -> [BalanceChange] serde bytes
+    /// where
+    /// - token:  [TokenAddress] bytes
+    /// - pk:     [PublicKey] bytes
+    /// - height: [u32] BE bytes
+    fn balance_history_iterator(&self, mode: IteratorMode) -> DBIterator<'_>;
+}
+
+/// A token account's balance change in a best chain block
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BalanceChange {
+    pub public_key: PublicKey,
+    pub token: TokenAddress,
+    pub state_hash: StateHash,
+    pub blockchain_length: u32,
+    pub global_slot_since_genesis: u32,
+    pub date_time: i64,
+    pub balance_before: u64,
+    pub balance_after: u64,
+    pub causes: Vec<BalanceChangeCause>,
+}
+
+/// The account diff responsible for (part of) a balance change
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BalanceChangeCause {
+    pub kind: BalanceChangeKind,
+
+    /// Signed nanomina amount
+    pub amount: i64,
+
+    /// Hash of the causing user command, if any
+    pub txn_hash: Option<TxnHash>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum BalanceChangeKind {
+    Genesis,
+    Payment,
+    Coinbase,
+    FeeTransfer,
+    FeeTransferViaCoinbase,
+    AccountCreationFee,
+}
+
+impl BalanceChange {
+    /// Compute the balance changes of the token accounts touched by the
+    /// block's ledger diff
+    ///
+    /// `txn_hashes` maps each of the block's user commands `(sender, nonce)`
+    /// to the command's hash
+    pub fn from_ledger_diff(
+        block: &BlockUpdate,
+        date_time: i64,
+        diff: &LedgerDiff,
+        balances: &HashMap<(PublicKey, TokenAddress), (u64, u64)>,
+        txn_hashes: &HashMap<(PublicKey, Nonce), TxnHash>,
+    ) -> Vec<Self> {
+        let mut causes = <HashMap<(PublicKey, TokenAddress), Vec<_>>>::new();
+
+        for diffs in diff.account_diffs.iter() {
+            // all diffs in the group are caused by the same command
+            let txn_hash = diffs.iter().find_map(|diff| match diff {
+                AccountDiff::Payment(payment) => match payment.update_type {
+                    UpdateType::Debit(Some(nonce)) => {
+                        txn_hashes.get(&(payment.public_key.clone(), nonce - 1))
+                    }
+                    _ => None,
+                },
+                _ => None,
+            });
+
+            for diff in diffs {
+                if let Some(cause) = BalanceChangeCause::from_account_diff(diff, txn_hash) {
+                    causes
+                        .entry((diff.public_key(), diff.token_address()))
+                        .or_default()
+                        .push(cause);
+                }
+            }
+        }
+
+        let mut changes: Vec<_> = balances
+            .iter()
+            .filter(|(_, (before, after))| before != after)
+            .map(|((pk, token), (before, after))| Self {
+                public_key: pk.clone(),
+                token: token.clone(),
+                state_hash: block.state_hash.clone(),
+                blockchain_length: block.blockchain_length,
+                global_slot_since_genesis: block.global_slot_since_genesis,
+                date_time,
+                balance_before: *before,
+                balance_after: *after,
+                causes: causes
+                    .remove(&(pk.clone(), token.clone()))
+                    .unwrap_or_default(),
+            })
+            .collect();
+
+        // sort for determinism
+        changes.sort_by(|a, b| (&a.token, &a.public_key).cmp(&(&b.token, &b.public_key)));
+        changes
+    }
+
+    /// Net nanomina change
+    pub fn delta(&self) -> i64 {
+        self.balance_after as i64 - self.balance_before as i64
+    }
+}
+
+impl BalanceChangeCause {
+    fn from_account_diff(diff: &AccountDiff, txn_hash: Option<&TxnHash>) -> Option<Self> {
+        use AccountDiff::*;
+
+        let signed = |update_type: &UpdateType, amount: u64| match update_type {
+            UpdateType::Credit => amount as i64,
+            UpdateType::Debit(_) => 0 - amount as i64,
+        };
+        let (kind, amount) = match diff {
+            Payment(diff) => (
+                BalanceChangeKind::Payment,
+                signed(&diff.update_type, diff.amount.0),
+            ),
+            FeeTransfer(diff) => (
+                BalanceChangeKind::FeeTransfer,
+                signed(&diff.update_type, diff.amount.0),
+            ),
+            FeeTransferViaCoinbase(diff) => (
+                BalanceChangeKind::FeeTransferViaCoinbase,
+                signed(&diff.update_type, diff.amount.0),
+            ),
+            Coinbase(diff) => (BalanceChangeKind::Coinbase, diff.amount.0 as i64),
+            ZkappAccountCreationFee(diff) => (
+                BalanceChangeKind::AccountCreationFee,
+                0 - diff.amount.0 as i64,
+            ),
+            _ => return None,
+        };
+
+        Some(Self {
+            kind,
+            amount,
+            txn_hash: match kind {
+                BalanceChangeKind::Payment => txn_hash.cloned(),
+                _ => None,
+            },
+        })
+    }
+}
+
+impl std::fmt::Display for BalanceChangeKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Genesis => write!(f, "Genesis"),
+            Self::Payment => write!(f, "Payment"),
+            Self::Coinbase => write!(f, "Coinbase"),
+            Self::FeeTransfer => write!(f, "FeeTransfer"),
+            Self::FeeTransferViaCoinbase => write!(f, "FeeTransferViaCoinbase"),
+            Self::AccountCreationFee => write!(f, "AccountCreationFee"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        base::amount::Amount,
+        ledger::diff::account::{CoinbaseDiff, PaymentDiff},
+    };
+
+    #[test]
+    fn balance_changes_from_ledger_diff() {
+        let sender = PublicKey::from("B62qre3erTHfzQckNuibViWQGyyKwZseztqrjPZBv6SQF384Rg6ESAy");
+        let receiver = PublicKey::from("B62qjYanmV7y9njVeH5UHkz3GYBm7xKir1rAnoY4KsEYUGLMiU45FSM");
+        let producer = PublicKey::from("B62qoaMj7u1JzuqXaBByQBL5jzqLguK8e7LHVPdY9LcvvLXK7HPsusD");
+        let txn_hash = TxnHash::V1("CkpZirFuoLVVab6x2ry4j8Ld5gMmQdak7VHW6f5C7VJYE34WAEWqa".into());
+        let token = TokenAddress::default();
+        let block = BlockUpdate {
+            state_hash: StateHash::default(),
+            blockchain_length: 111,
+            global_slot_since_genesis: 143,
+        };
+
+        let diff = LedgerDiff {
+            account_diffs: vec![
+                vec![
+                    AccountDiff::Payment(PaymentDiff {
+                        update_type: UpdateType::Credit,
+                        public_key: receiver.clone(),
+                        amount: Amount(1000),
+                        token: token.clone(),
+                    }),
+                    AccountDiff::Payment(PaymentDiff {
+                        update_type: UpdateType::Debit(Some(Nonce(166))),
+                        public_key: sender.clone(),
+                        amount: Amount(1000),
+                        token: token.clone(),
+                    }),
+                ],
+                vec![AccountDiff::Coinbase(CoinbaseDiff {
+                    public_key: producer.clone(),
+                    amount: Amount(720000000000),
+                })],
+            ],
+            ..Default::default()
+        };
+        let balances = HashMap::from([
+            ((sender.clone(), token.clone()), (5000, 4000)),
+            ((receiver.clone(), token.clone()), (0, 1000)),
+            ((producer.clone(), token.clone()), (10, 720000000010)),
+        ]);
+        let txn_hashes = HashMap::from([((sender.clone(), Nonce(165)), txn_hash.clone())]);
+
+        let changes = BalanceChange::from_ledger_diff(&block, 0, &diff, &balances, &txn_hashes);
+        assert_eq!(changes.len(), 3);
+
+        for change in changes {
+            assert_eq!(change.blockchain_length, 111);
+            assert_eq!(change.global_slot_since_genesis, 143);
+
+            if change.public_key == producer {
+                assert_eq!(change.delta(), 720000000000);
+                assert_eq!(change.causes[0].kind, BalanceChangeKind::Coinbase);
+                assert_eq!(change.causes[0].txn_hash, None);
+            } else {
+                // both sides of the payment are linked to the command
+                assert_eq!(change.causes[0].kind, BalanceChangeKind::Payment);
+                assert_eq!(change.causes[0].amount, change.delta());
+                assert_eq!(change.causes[0].txn_hash, Some(txn_hash.clone()));
+            }
+        }
+    }
+}
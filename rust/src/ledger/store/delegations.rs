@@ -0,0 +1,247 @@
+//! Store of best chain delegation changes & staking ledger delegation flows
+
+use crate::{
+    base::{nonce::Nonce, public_key::PublicKey, state_hash::StateHash},
+    block::store::BlockUpdate,
+    command::signed::TxnHash,
+    ledger::diff::{account::AccountDiff, LedgerDiff},
+    store::Result,
+    utility::deadline::Deadline,
+};
+use serde::{Deserialize, Serialize};
+use speedb::{DBIterator, IteratorMode};
+use std::collections::HashMap;
+
+pub trait DelegationStore {
+    /// Record the delegation changes caused by applying the block's ledger
+    /// diff to the best ledger. `delegates` maps each delegator in the diff
+    /// to its delegate before the diff was applied
+    fn add_block_delegation_changes(
+        &self,
+        block: &BlockUpdate,
+        diff: &LedgerDiff,
+        delegates: &HashMap<PublicKey, PublicKey>,
+    ) -> Result<()>;
+
+    /// Remove the delegation changes recorded for an unapplied block
+    fn remove_block_delegation_changes(&self, block: &BlockUpdate, diff: &LedgerDiff)
+        -> Result<()>;
+
+    /// Get the delegator's best chain delegation changes, most recent first
+    fn get_delegation_history(
+        &self,
+        pk: &PublicKey,
+        limit: usize,
+        deadline: Option<&Deadline>,
+    ) -> Result<Vec<DelegationChange>>;
+
+    /// Get the delegators who joined & left the delegate between the
+    /// `epoch - 1` & `epoch` staking ledgers
+    fn get_delegation_flows(
+        &self,
+        delegate: &PublicKey,
+        epoch: u32,
+        genesis_state_hash: Option<&StateHash>,
+        deadline: Option<&Deadline>,
+    ) -> Result<Option<DelegationFlows>>;
+
+    ///////////////
+    // Iterators //
+    ///////////////
+
+    /// Iterator for best chain delegation changes
+    /// ```
+    /// {pk}{height}{nonce} -> [DelegationChange] serde bytes
+    /// where
+    /// - pk:     [PublicKey] bytes
+    /// - height: [u32] BE bytes
+    /// - nonce:  [u32] BE bytes
+    fn delegation_history_iterator(&self, mode: IteratorMode) -> DBIterator<'_>;
+}
+
+/// A delegator's change of delegate in a best chain block
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DelegationChange {
+    pub delegator: PublicKey,
+    pub delegate: PublicKey,
+
+    /// Delegate before the change, if the account existed
+    pub previous_delegate: Option<PublicKey>,
+
+    /// Delegator's nonce after the change
+    pub nonce: Nonce,
+
+    /// Hash of the delegating user command, if any
+    pub txn_hash: Option<TxnHash>,
+    pub state_hash: StateHash,
+    pub blockchain_length: u32,
+    pub global_slot_since_genesis: u32,
+    pub date_time: i64,
+}
+
+/// Delegators who joined & left a delegate between consecutive epochs'
+/// staking ledgers
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DelegationFlows {
+    pub delegate: PublicKey,
+    pub epoch: u32,
+
+    /// Delegating in `epoch`, but not in `epoch - 1`
+    pub joined: Vec<DelegatorFlow>,
+
+    /// Delegating in `epoch - 1`, but not in `epoch`
+    pub left: Vec<DelegatorFlow>,
+    pub joined_balance: u64,
+    pub left_balance: u64,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DelegatorFlow {
+    pub public_key: PublicKey,
+
+    /// Balance in the staking ledger the delegator delegates to the delegate
+    pub balance: u64,
+
+    /// Delegate in the `epoch - 1` staking ledger, if the account existed
+    pub from_delegate: Option<PublicKey>,
+
+    /// Delegate in the `epoch` staking ledger, if the account exists
+    pub to_delegate: Option<PublicKey>,
+}
+
+impl DelegationChange {
+    /// Compute the delegation changes of the block's ledger diff, in order
+    ///
+    /// `txn_hashes` maps each of the block's user commands `(sender, nonce)`
+    /// to the command's hash
+    pub fn from_ledger_diff(
+        block: &BlockUpdate,
+        date_time: i64,
+        diff: &LedgerDiff,
+        delegates: &HashMap<PublicKey, PublicKey>,
+        txn_hashes: &HashMap<(PublicKey, Nonce), TxnHash>,
+    ) -> Vec<Self> {
+        let mut delegates = delegates.clone();
+        diff.account_diffs
+            .iter()
+            .flatten()
+            .filter_map(|diff| match diff {
+                AccountDiff::Delegation(delegation) => Some(delegation),
+                _ => None,
+            })
+            .map(|delegation| Self {
+                delegator: delegation.delegator.clone(),
+                delegate: delegation.delegate.clone(),
+                previous_delegate: delegates
+                    .insert(delegation.delegator.clone(), delegation.delegate.clone()),
+                nonce: delegation.nonce,
+                txn_hash: txn_hashes
+                    .get(&(delegation.delegator.clone(), delegation.nonce - 1))
+                    .cloned(),
+                state_hash: block.state_hash.clone(),
+                blockchain_length: block.blockchain_length,
+                global_slot_since_genesis: block.global_slot_since_genesis,
+                date_time,
+            })
+            .collect()
+    }
+}
+
+impl DelegationFlows {
+    pub fn new(
+        delegate: PublicKey,
+        epoch: u32,
+        mut joined: Vec<DelegatorFlow>,
+        mut left: Vec<DelegatorFlow>,
+    ) -> Self {
+        // largest flows first
+        joined.sort_by(|a, b| (b.balance, &a.public_key).cmp(&(a.balance, &b.public_key)));
+        left.sort_by(|a, b| (b.balance, &a.public_key).cmp(&(a.balance, &b.public_key)));
+
+        Self {
+            delegate,
+            epoch,
+            joined_balance: joined.iter().map(|flow| flow.balance).sum(),
+            left_balance: left.iter().map(|flow| flow.balance).sum(),
+            joined,
+            left,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ledger::diff::account::DelegationDiff;
+
+    #[test]
+    fn delegation_changes_from_ledger_diff() {
+        let delegator = PublicKey::from("B62qre3erTHfzQckNuibViWQGyyKwZseztqrjPZBv6SQF384Rg6ESAy");
+        let first = PublicKey::from("B62qjYanmV7y9njVeH5UHkz3GYBm7xKir1rAnoY4KsEYUGLMiU45FSM");
+        let second = PublicKey::from("B62qoaMj7u1JzuqXaBByQBL5jzqLguK8e7LHVPdY9LcvvLXK7HPsusD");
+        let txn_hash = TxnHash::V1("CkpZirFuoLVVab6x2ry4j8Ld5gMmQdak7VHW6f5C7VJYE34WAEWqa".into());
+        let block = BlockUpdate {
+            state_hash: StateHash::default(),
+            blockchain_length: 111,
+            global_slot_since_genesis: 143,
+        };
+
+        let delegation = |delegate: &PublicKey, nonce| {
+            vec![AccountDiff::Delegation(DelegationDiff {
+                nonce: Nonce(nonce),
+                delegator: delegator.clone(),
+                delegate: delegate.clone(),
+            })]
+        };
+        let diff = LedgerDiff {
+            account_diffs: vec![delegation(&first, 6), delegation(&second, 7)],
+            ..Default::default()
+        };
+        let delegates = HashMap::from([(delegator.clone(), delegator.clone())]);
+        let txn_hashes = HashMap::from([((delegator.clone(), Nonce(5)), txn_hash.clone())]);
+
+        let changes = DelegationChange::from_ledger_diff(&block, 0, &diff, &delegates, &txn_hashes);
+        assert_eq!(changes.len(), 2);
+
+        // the second change's previous delegate is the first change's
+        assert_eq!(changes[0].previous_delegate, Some(delegator.clone()));
+        assert_eq!(changes[0].delegate, first);
+        assert_eq!(changes[0].txn_hash, Some(txn_hash));
+        assert_eq!(changes[1].previous_delegate, Some(first));
+        assert_eq!(changes[1].delegate, second);
+        assert_eq!(changes[1].txn_hash, None);
+        assert!(changes.iter().all(|change| change.blockchain_length == 111));
+    }
+
+    #[test]
+    fn delegation_flows_totals() {
+        let flow = |pk: &str, balance| DelegatorFlow {
+            public_key: pk.into(),
+            balance,
+            from_delegate: None,
+            to_delegate: None,
+        };
+        let flows = DelegationFlows::new(
+            "B62qre3erTHfzQckNuibViWQGyyKwZseztqrjPZBv6SQF384Rg6ESAy".into(),
+            1,
+            vec![
+                flow(
+                    "B62qjYanmV7y9njVeH5UHkz3GYBm7xKir1rAnoY4KsEYUGLMiU45FSM",
+                    10,
+                ),
+                flow(
+                    "B62qoaMj7u1JzuqXaBByQBL5jzqLguK8e7LHVPdY9LcvvLXK7HPsusD",
+                    30,
+                ),
+            ],
+            vec![flow(
+                "B62qmCwouxG2UzH6zEYGFWFFzUuSv9sbLnr96VJWDX3paSSucX7jAJN",
+                5,
+            )],
+        );
+
+        assert_eq!(flows.joined_balance, 40);
+        assert_eq!(flows.left_balance, 5);
+        assert_eq!(flows.joined[0].balance, 30);
+    }
+}
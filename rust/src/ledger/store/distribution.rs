@@ -0,0 +1,39 @@
+//! Store of ledger wealth distribution statistics
+
+use crate::{
+    base::state_hash::StateHash,
+    ledger::{distribution::WealthDistribution, LedgerHash},
+    store::Result,
+};
+
+pub trait DistributionStore {
+    /// Set the staking ledger's wealth distribution
+    fn set_staking_ledger_distribution(
+        &self,
+        ledger_hash: &LedgerHash,
+        distribution: &WealthDistribution,
+    ) -> Result<()>;
+
+    /// Get the staking ledger's wealth distribution
+    fn get_staking_ledger_distribution(
+        &self,
+        ledger_hash: &LedgerHash,
+    ) -> Result<Option<WealthDistribution>>;
+
+    /// Get the wealth distribution of the epoch's staking ledger
+    ///
+    /// If no genesis state hash is provided, default to current network
+    fn get_epoch_distribution(
+        &self,
+        epoch: u32,
+        genesis_state_hash: Option<&StateHash>,
+    ) -> Result<Option<WealthDistribution>>;
+
+    /// Compute the wealth distribution of the best ledger & replace the
+    /// previous best tip's
+    fn update_best_ledger_distribution(&self) -> Result<()>;
+
+    /// Get the wealth distribution of the best ledger as of the latest
+    /// [DistributionStore::update_best_ledger_distribution]
+    fn get_best_ledger_distribution(&self) -> Result<Option<WealthDistribution>>;
+}
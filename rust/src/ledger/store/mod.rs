@@ -1,3 +1,7 @@
+pub mod balance_history;
 pub mod best;
+pub mod delegations;
+pub mod distribution;
 pub mod staged;
 pub mod staking;
+pub mod supply;
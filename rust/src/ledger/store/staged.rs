@@ -5,6 +5,7 @@ use crate::{
     ledger::{account::Account, diff::LedgerDiff, token::TokenAddress, Ledger, LedgerHash},
 };
 use speedb::{DBIterator, Direction, WriteBatch};
+use std::sync::Arc;
 
 pub trait StagedLedgerStore {
     // Get `pk`'s `state_hash` staged ledger account
@@ -39,22 +40,32 @@ pub trait StagedLedgerStore {
         &self,
         ledger_hash: &LedgerHash,
         memoize: bool,
-    ) -> anyhow::Result<Option<Ledger>>;
+    ) -> anyhow::Result<Option<Arc<Ledger>>>;
 
     /// Get a ledger associated with an arbitrary block
     fn get_staged_ledger_at_state_hash(
         &self,
         state_hash: &StateHash,
         memoize: bool,
-    ) -> anyhow::Result<Option<Ledger>>;
+    ) -> anyhow::Result<Option<Arc<Ledger>>>;
 
     /// Get a (canonical) ledger at a specified block height
     /// (i.e. blockchain_length)
+    ///
+    /// Materialized from the nearest persisted staged ledger by applying or
+    /// unapplying the intermediate canonical block ledger diffs
     fn get_staged_ledger_at_block_height(
         &self,
         height: u32,
         memoize: bool,
-    ) -> anyhow::Result<Option<Ledger>>;
+    ) -> anyhow::Result<Option<Arc<Ledger>>>;
+
+    /// Get the canonical block height & state hash of the persisted staged
+    /// ledger nearest to `block_height` (above or below, preferring below)
+    fn get_nearest_staged_ledger_height(
+        &self,
+        block_height: u32,
+    ) -> anyhow::Result<Option<(u32, StateHash)>>;
 
     /// Set `pk`'s `state_hash` staged ledger `account` & balance-sort data
     fn set_staged_account(
@@ -100,7 +111,7 @@ pub trait StagedLedgerStore {
     fn add_staged_ledger_at_state_hash(
         &self,
         state_hash: &StateHash,
-        ledger: Ledger,
+        ledger: &Ledger,
         block_height: u32,
     ) -> anyhow::Result<()>;
 
@@ -2,12 +2,17 @@
 
 use crate::{
     base::{public_key::PublicKey, state_hash::StateHash},
+    block::precomputed::PrecomputedBlock,
     ledger::{
         staking::{
+            payouts::{EpochPayouts, PayoutConfig},
+            performance::ProducerPerformance,
             AggregatedEpochStakeDelegations, EpochStakeDelegation, StakingAccount, StakingLedger,
+            StakingLedgerSource,
         },
         LedgerHash,
     },
+    utility::deadline::Deadline,
 };
 use speedb::{DBIterator, Direction, IteratorMode};
 
@@ -95,6 +100,26 @@ pub trait StakingLedgerStore {
     /// Get a staking ledger's total currency
     fn get_total_currency(&self, ledger_hash: &LedgerHash) -> anyhow::Result<Option<u64>>;
 
+    /// Set whether the staking ledger was imported or derived
+    fn set_staking_ledger_source(
+        &self,
+        ledger_hash: &LedgerHash,
+        source: StakingLedgerSource,
+    ) -> anyhow::Result<()>;
+
+    /// Get whether the staking ledger was imported or derived
+    fn get_staking_ledger_source(
+        &self,
+        ledger_hash: &LedgerHash,
+    ) -> anyhow::Result<Option<StakingLedgerSource>>;
+
+    /// Derive & add the missing staking ledgers of the block's current &
+    /// next epochs from the indexer's own staged ledgers
+    ///
+    /// A staking ledger is only derived from a staged ledger whose hash is
+    /// the one the block commits to, returns the derived ledgers' epochs
+    fn derive_epoch_staking_ledgers(&self, block: &PrecomputedBlock) -> anyhow::Result<Vec<u32>>;
+
     /// Get the total number of accounts per staking ledger
     fn get_staking_ledger_accounts_count_epoch(
         &self,
@@ -124,6 +149,34 @@ pub trait StakingLedgerStore {
         genesis_state_hash: Option<&StateHash>,
     ) -> anyhow::Result<Option<AggregatedEpochStakeDelegations>>;
 
+    /// Split the producer's canonical `epoch` block rewards among its
+    /// delegators according to the payout config
+    fn get_epoch_payouts(
+        &self,
+        producer: &PublicKey,
+        epoch: u32,
+        genesis_state_hash: Option<&StateHash>,
+        config: PayoutConfig,
+        deadline: Option<&Deadline>,
+    ) -> anyhow::Result<Option<EpochPayouts>>;
+
+    /// Get the producer's `epoch` block production relative to its stake
+    fn get_producer_performance(
+        &self,
+        pk: &PublicKey,
+        epoch: u32,
+        genesis_state_hash: Option<&StateHash>,
+    ) -> anyhow::Result<Option<ProducerPerformance>>;
+
+    /// Get the `epoch` block production of every producer with stake, in
+    /// descending stake order
+    fn get_epoch_producer_performances(
+        &self,
+        epoch: u32,
+        genesis_state_hash: Option<&StateHash>,
+        deadline: Option<&Deadline>,
+    ) -> anyhow::Result<Option<Vec<ProducerPerformance>>>;
+
     ///////////////
     // Iterators //
     ///////////////
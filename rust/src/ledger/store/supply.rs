@@ -0,0 +1,146 @@
+//! Store of MINA supply computed from the best ledger's timed accounts
+
+use crate::{
+    base::{public_key::PublicKey, state_hash::StateHash},
+    block::precomputed::PrecomputedBlock,
+    ledger::{
+        account::{
+            vesting::{DelegatorVestingSchedules, SlotClock, VestingSchedule},
+            Timing,
+        },
+        LedgerHash,
+    },
+    store::Result,
+    utility::deadline::Deadline,
+};
+use serde::{Deserialize, Serialize};
+use speedb::{DBIterator, IteratorMode};
+
+pub trait SupplyStore {
+    /// Track the timing of the best ledger MINA account, `None` removes
+    /// the account from the timed accounts
+    fn update_timed_account(&self, pk: &PublicKey, timing: Option<&Timing>) -> Result<()>;
+
+    /// Get the timing of the best ledger MINA account
+    fn get_timed_account(&self, pk: &PublicKey) -> Result<Option<Timing>>;
+
+    /// Get the locked supply of the best ledger's timed accounts at the
+    /// given global slot
+    fn get_locked_supply(&self, global_slot: u32, deadline: Option<&Deadline>) -> Result<u64>;
+
+    /// Advance the best ledger's locked supply aggregate to the best tip's
+    /// global slot, only revisiting the accounts which unlock in between
+    fn update_locked_supply(&self, global_slot: u32) -> Result<u64>;
+
+    /// Compute & cache the supply of the new best tip
+    fn add_block_supply(&self, block: &PrecomputedBlock) -> Result<BlockSupply>;
+
+    /// Get the cached supply of the block
+    fn get_block_supply(&self, state_hash: &StateHash) -> Result<Option<BlockSupply>>;
+
+    /// Get the cached supplies of canonical blocks between the given global
+    /// slots (inclusive)
+    fn get_supply_time_series(
+        &self,
+        start_slot: u32,
+        end_slot: u32,
+        limit: usize,
+        deadline: Option<&Deadline>,
+    ) -> Result<Vec<BlockSupply>>;
+
+    /// Get the clock of the best tip's network
+    fn get_slot_clock(&self) -> Result<SlotClock>;
+
+    /// Project the unlock schedule of the best ledger timed account,
+    /// sampled every `interval` slots
+    fn get_vesting_schedule(
+        &self,
+        pk: &PublicKey,
+        interval: u32,
+    ) -> Result<Option<VestingSchedule>>;
+
+    /// Project the unlock schedules of the best ledger timed accounts
+    /// delegating to `delegate`, sampled every `interval` slots
+    fn get_delegator_vesting_schedules(
+        &self,
+        delegate: &PublicKey,
+        interval: u32,
+        deadline: Option<&Deadline>,
+    ) -> Result<DelegatorVestingSchedules>;
+
+    /// Set the staking ledger's staked supply
+    fn set_staked_supply(&self, ledger_hash: &LedgerHash, staked: u64) -> Result<()>;
+
+    /// Get the staking ledger's staked supply
+    fn get_staked_supply(&self, ledger_hash: &LedgerHash) -> Result<Option<u64>>;
+
+    ///////////////
+    // Iterators //
+    ///////////////
+
+    /// Iterator for best ledger timed MINA accounts
+    /// ```
+    /// - key: [PublicKey] bytes
+    /// - val: [Timing] serde bytes
+    fn timed_accounts_iterator(&self, mode: IteratorMode) -> DBIterator<'_>;
+
+    /// Iterator for best ledger timed MINA accounts by next unlock slot
+    /// ```
+    /// {next_unlock_slot}{pk} -> _
+    /// where
+    /// - next_unlock_slot: [u32] BE bytes
+    /// - pk:               [PublicKey] bytes
+    fn timed_accounts_unlocks_iterator(&self, mode: IteratorMode) -> DBIterator<'_>;
+
+    /// Iterator for best tip supplies
+    /// ```
+    /// {global_slot}{state_hash} -> [BlockSupply] serde bytes
+    /// where
+    /// - global_slot: [u32] BE bytes
+    /// - state_hash:  [StateHash] bytes
+    fn block_supply_iterator(&self, mode: IteratorMode) -> DBIterator<'_>;
+}
+
+/// MINA supply at a best tip
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BlockSupply {
+    pub state_hash: StateHash,
+    pub blockchain_length: u32,
+    pub global_slot_since_genesis: u32,
+    pub date_time: i64,
+    pub total_currency: u64,
+
+    /// Sum of the timed accounts' minimum balances
+    ///
+    /// Off-chain time locks aren't recorded in the ledger, so they can't be
+    /// verified by the indexer & aren't included. Mainnet has off-chain
+    /// locks, so its circulating supply is higher than totals including them
+    pub locked: u64,
+
+    /// Total currency minus the locked supply
+    pub circulating: u64,
+
+    /// Staking ledger used by the block
+    pub staking_ledger_hash: LedgerHash,
+
+    /// Balance delegated to another account in the staking ledger, if it
+    /// has been ingested
+    pub staked: Option<u64>,
+}
+
+impl BlockSupply {
+    pub fn new(block: &PrecomputedBlock, locked: u64, staked: Option<u64>) -> Self {
+        let total_currency = block.total_currency();
+        Self {
+            state_hash: block.state_hash(),
+            blockchain_length: block.blockchain_length(),
+            global_slot_since_genesis: block.global_slot_since_genesis(),
+            date_time: block.timestamp() as i64,
+            total_currency,
+            locked,
+            circulating: total_currency.saturating_sub(locked),
+            staking_ledger_hash: block.staking_epoch_ledger_hash(),
+            staked,
+        }
+    }
+}
@@ -2,8 +2,7 @@
 
 use super::TokenId;
 use crate::{
-    constants::MINA_TOKEN_ADDRESS, protocol::serialization_types::version_bytes::TOKEN_ID_KEY,
-    utility::store::common::U64_LEN,
+    base::base58::Base58Kind, constants::MINA_TOKEN_ADDRESS, utility::store::common::U64_LEN,
 };
 use anyhow::bail;
 use serde::{Deserialize, Serialize};
@@ -78,11 +77,7 @@ impl From<TokenId> for TokenAddress {
 
         // big int LE bytes
         big_int[..U64_LEN].copy_from_slice(&le_bytes);
-        Self(
-            bs58::encode(&big_int[..])
-                .with_check_version(TOKEN_ID_KEY)
-                .into_string(),
-        )
+        Self(Base58Kind::TokenId.encode(&big_int))
     }
 }
 
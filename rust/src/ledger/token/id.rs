@@ -1,10 +1,7 @@
 //! Token id
 
 use super::TokenAddress;
-use crate::{
-    constants::MINA_TOKEN_ID, protocol::serialization_types::version_bytes::TOKEN_ID_KEY,
-    utility::store::common::U64_LEN,
-};
+use crate::{base::base58::Base58Kind, constants::MINA_TOKEN_ID, utility::store::common::U64_LEN};
 use serde::{Deserialize, Serialize};
 use std::{fmt::Display, str::FromStr};
 
@@ -26,15 +23,12 @@ impl FromStr for TokenId {
 
 impl From<TokenAddress> for TokenId {
     fn from(value: TokenAddress) -> Self {
-        let bs58_bytes = value.0.as_bytes();
-        let big_int = bs58::decode(bs58_bytes)
-            .with_check(Some(TOKEN_ID_KEY))
-            .into_vec()
+        let big_int = Base58Kind::TokenId
+            .decode(&value.0)
             .expect("valid base58 check");
 
-        // drop version byte
         let mut le_bytes = [0; U64_LEN];
-        le_bytes.copy_from_slice(&big_int[1..=U64_LEN]);
+        le_bytes.copy_from_slice(&big_int[..U64_LEN]);
 
         Self(u64::from_le_bytes(le_bytes))
     }
@@ -116,6 +116,17 @@ pub enum Error {
     #[error("Input stream has {0} unconsumed bytes")]
     StreamNotFullyConsumed(usize),
 
+    /// A json value does not match the layout rule it is converted with
+    #[error("Expected {expected} at {path}, got {got}")]
+    LayoutMismatch {
+        /// What the layout rule expects
+        expected: String,
+        /// Path of the mismatched value from the root
+        path: String,
+        /// The mismatched value
+        got: String,
+    },
+
     //////////////////////////////////
     /// Some user-defined error occurred.
     #[error("{message}")]
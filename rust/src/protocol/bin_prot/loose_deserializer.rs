@@ -125,43 +125,20 @@ impl<'de, R: Read> DS<R, LooselyTyped> {
                     }
                     BinProtRule::CustomForPath(path, rules) => {
                         // here is where custom deser methods can be looked up by path
-                        match path.as_str() {
+                        if let Some(len) = vector_len(&path) {
                             // These vector types will be handled like any other sequence
-                            "Pickles_type.Vector.Vector2" // the missing 's' on 'types' here is intention due to a bug in layout producing code
-                            | "Pickles_types.Vector.Vector2" // in case it gets fixed :P
-                            | "Pickles_types.Vector.Vector4"
-                            | "Pickles_types.Vector.Vector8"
-                            | "Pickles_types.Vector.Vector17"
-                            | "Pickles_types.Vector.Vector18" => {
-                                let element_rule = rules.first().unwrap();
-                                let len = match path.as_str() {
-                                    "Pickles_type.Vector.Vector2"
-                                    | "Pickles_types.Vector.Vector2" => 2,
-                                    "Pickles_types.Vector.Vector4" => 4,
-                                    "Pickles_types.Vector.Vector8" => 8,
-                                    "Pickles_types.Vector.Vector17" => 17,
-                                    "Pickles_types.Vector.Vector18" => 18,
-                                    _ => unreachable!()
-                                };
-                                self.mode.layout_iter.push(vec![BinProtRule::Unit]); // zero byte terminator, will be read last
-                                self.mode.layout_iter.push_n(element_rule.clone(), len);
-                                visitor.visit_seq(SeqAccess::new(self, len + 1))
-                            }
-                            "Ledger_hash0" // these are all BigInt (32 bytes)
-                            | "State_hash"
-                            | "Pending_coinbase.Stack_hash"
-                            | "State_body_hash"
-                            | "Pending_coinbase.Hash_builder"
-                            | "Snark_params.Make_inner_curve_scalar"
-                            | "Snark_params.Tick"
-                            | "Epoch_seed"
-                            | "Zexe_backend.Zexe_backend_common.Stable.Field"
-                            | "Pending_coinbase.Coinbase_stack" => {
+                            let element_rule = rules.first().unwrap();
+                            self.mode.layout_iter.push(vec![BinProtRule::Unit]); // zero byte terminator, will be read last
+                            self.mode.layout_iter.push_n(element_rule.clone(), len);
+                            return visitor.visit_seq(SeqAccess::new(self, len + 1));
+                        }
+                        match path.as_str() {
+                            path if BIG_INT_PATHS.contains(&path) => {
                                 // force it to read a 32 element long tuple of u8/chars
-                                self.mode.layout_iter.push_n(BinProtRule::Char, 32);
-                                visitor.visit_seq(SeqAccess::new(self, 32))
+                                self.mode.layout_iter.push_n(BinProtRule::Char, BIG_INT_LEN);
+                                visitor.visit_seq(SeqAccess::new(self, BIG_INT_LEN))
                             }
-                            _ => Err(Error::UnknownCustomType { typ: path })
+                            _ => Err(Error::UnknownCustomType { typ: path }),
                         }
                     }
                 }
@@ -171,6 +148,32 @@ impl<'de, R: Read> DS<R, LooselyTyped> {
     }
 }
 
+/// Custom types which are BigInts (32 bytes)
+pub(crate) const BIG_INT_PATHS: &[&str] = &[
+    "Ledger_hash0",
+    "State_hash",
+    "Pending_coinbase.Stack_hash",
+    "State_body_hash",
+    "Pending_coinbase.Hash_builder",
+    "Snark_params.Make_inner_curve_scalar",
+    "Snark_params.Tick",
+    "Epoch_seed",
+    "Zexe_backend.Zexe_backend_common.Stable.Field",
+    "Pending_coinbase.Coinbase_stack",
+    "Pasta_bindings.Fp", // v2 field elements
+    "Pasta_bindings.Fq",
+];
+
+/// Number of bytes in a BigInt custom type
+pub(crate) const BIG_INT_LEN: usize = 32;
+
+/// Pickles vectors have their length in the module name
+pub(crate) fn vector_len(path: &str) -> Option<usize> {
+    path.strip_prefix("Pickles_types.Vector.Vector")
+        .or_else(|| path.strip_prefix("Pickles_type.Vector.Vector")) // the missing 's' on 'types' here is intention due to a bug in layout producing code
+        .and_then(|n| n.parse().ok())
+}
+
 pub enum VariantType {
     Sum(Summand),
     Polyvar(u8, TaggedPolyvar),
@@ -0,0 +1,399 @@
+//! Conversion between loosely typed BinProt values and json, guided by a
+//! layout
+//!
+//! - records are json objects, tuples, lists & vectors are arrays
+//! - sum types & polyvars are list tagged, i.e. `["Ctor", args..]`
+//! - options are `null` or the value
+//! - strings are json strings if they're valid utf-8, otherwise arrays of bytes
+//! - BigInt custom types (hashes, field elements) are hex strings of the
+//!   serialized bytes
+
+use crate::protocol::bin_prot::{
+    error::{Error, Result},
+    loose_deserializer::{vector_len, BIG_INT_LEN, BIG_INT_PATHS},
+    to_writer,
+    value::layout::{BinProtRule, Polyvar, RuleRef},
+    Deserializer, Value,
+};
+use serde::Deserialize;
+use serde_json::{json, Map, Value as JsonValue};
+use std::io::{Cursor, Read};
+
+/// Loosely deserializes the bytes according to the layout rule & converts the
+/// result to json
+pub fn decode(bytes: &[u8], rule: &BinProtRule) -> Result<JsonValue> {
+    let mut de = Deserializer::from_reader(Cursor::new(bytes)).with_layout(rule);
+    let value = Value::deserialize(&mut de)?;
+
+    match de.rdr.bytes().count() {
+        0 => to_json(&value, rule),
+        unconsumed => Err(Error::StreamNotFullyConsumed(unconsumed)),
+    }
+}
+
+/// Converts the json to a value according to the layout rule & serializes it
+pub fn encode(json: &JsonValue, rule: &BinProtRule) -> Result<Vec<u8>> {
+    let value = from_json(json, rule)?;
+    let mut bytes = vec![];
+    to_writer(&mut bytes, &value)?;
+    Ok(bytes)
+}
+
+/// Converts a loosely deserialized value to json
+pub fn to_json(value: &Value, rule: &BinProtRule) -> Result<JsonValue> {
+    value_to_json(value, rule, None, "$")
+}
+
+/// Converts json to a value which serializes according to the layout rule
+pub fn from_json(json: &JsonValue, rule: &BinProtRule) -> Result<Value> {
+    json_to_value(json, rule, None, "$")
+}
+
+fn value_to_json(
+    value: &Value,
+    rule: &BinProtRule,
+    module_path: Option<&str>,
+    path: &str,
+) -> Result<JsonValue> {
+    let err = |expected: &str| mismatch(expected, path, format!("{value:?}"));
+
+    Ok(match (rule, value) {
+        (BinProtRule::Unit, Value::Unit) => JsonValue::Null,
+        (BinProtRule::Bool, Value::Bool(b)) => json!(b),
+        (BinProtRule::Char, Value::Char(c)) => json!(c),
+        (
+            BinProtRule::Int | BinProtRule::Int32 | BinProtRule::Int64 | BinProtRule::NativeInt,
+            Value::Int(n),
+        ) => json!(n),
+        (BinProtRule::Float, Value::Float(f)) => json!(f),
+        (BinProtRule::String, Value::String(bytes)) => string_to_json(bytes.clone()),
+        (BinProtRule::String, Value::List(chars)) => {
+            string_to_json(chars_to_bytes(chars).ok_or_else(|| err("a string"))?)
+        }
+        (BinProtRule::Option(_), Value::Option(None)) => JsonValue::Null,
+        (BinProtRule::Option(rule), Value::Option(Some(value))) => {
+            value_to_json(value, rule, module_path, path)?
+        }
+        (BinProtRule::Record(fields), Value::Record(values)) if fields.len() == values.len() => {
+            let mut map = Map::new();
+            for (field, (_, value)) in fields.iter().zip(values) {
+                let path = format!("{path}.{}", field.field_name);
+                map.insert(
+                    field.field_name.clone(),
+                    value_to_json(value, &field.field_rule, module_path, &path)?,
+                );
+            }
+            JsonValue::Object(map)
+        }
+        (BinProtRule::Tuple(rules), Value::Tuple(values) | Value::List(values))
+            if rules.len() == values.len() =>
+        {
+            seq_to_json(values, rules.iter(), module_path, path)?
+        }
+        (BinProtRule::List(rule), Value::List(values) | Value::Tuple(values)) => {
+            seq_to_json(values, std::iter::repeat(rule.as_ref()), module_path, path)?
+        }
+        (BinProtRule::Sum(summands), Value::Sum { index, value, .. }) => {
+            let summand = summands
+                .iter()
+                .find(|s| s.index == *index as i32)
+                .ok_or_else(|| err("a known variant index"))?;
+            list_tagged_to_json(
+                &summand.ctor_name,
+                value,
+                &summand.ctor_args,
+                module_path,
+                path,
+            )?
+        }
+        (BinProtRule::Polyvar(polyvars), Value::Polyvar { tag, value, .. }) => {
+            let polyvar = polyvars
+                .iter()
+                .find_map(|p| match p {
+                    Polyvar::Tagged(t) if t.hash == *tag => Some(t),
+                    _ => None,
+                })
+                .ok_or_else(|| err("a known polyvar tag"))?;
+            list_tagged_to_json(
+                &polyvar.polyvar_name,
+                value,
+                &polyvar.polyvar_args,
+                module_path,
+                path,
+            )?
+        }
+        (BinProtRule::Reference(RuleRef::Resolved(payload)), value) => value_to_json(
+            value,
+            &payload.ref_rule,
+            Some(&payload.source_module_path),
+            path,
+        )?,
+        (BinProtRule::Custom(rules), Value::Tuple(values) | Value::List(values)) => {
+            let module_path = module_path.unwrap_or_default();
+            if let Some(len) = vector_len(module_path) {
+                // drop the unit terminator
+                match (rules.first(), values.split_last()) {
+                    (Some(rule), Some((Value::Unit, values))) if values.len() == len => {
+                        seq_to_json(values, std::iter::repeat(rule), None, path)?
+                    }
+                    _ => return Err(err(&format!("a vector of length {len}"))),
+                }
+            } else if BIG_INT_PATHS.contains(&module_path) {
+                match chars_to_bytes(values) {
+                    Some(bytes) if bytes.len() == BIG_INT_LEN => json!(hex::encode(bytes)),
+                    _ => return Err(err("a big int")),
+                }
+            } else {
+                return Err(Error::UnknownCustomType {
+                    typ: module_path.to_string(),
+                });
+            }
+        }
+        (BinProtRule::Nat0, _)
+        | (BinProtRule::Vec(_, _), _)
+        | (BinProtRule::Hashtable(_), _)
+        | (BinProtRule::TypeVar(_), _)
+        | (BinProtRule::Bigstring, _)
+        | (BinProtRule::SelfReference(_), _)
+        | (BinProtRule::TypeClosure(_, _), _)
+        | (BinProtRule::TypeAbstraction(_, _), _)
+        | (BinProtRule::Reference(RuleRef::Unresolved(_)), _)
+        | (BinProtRule::CustomForPath(_, _), _) => return Err(Error::UnimplementedRule),
+        (rule, _) => return Err(err(&format!("a value for rule {rule:?}"))),
+    })
+}
+
+fn json_to_value(
+    json: &JsonValue,
+    rule: &BinProtRule,
+    module_path: Option<&str>,
+    path: &str,
+) -> Result<Value> {
+    let err = |expected: &str| mismatch(expected, path, json.to_string());
+
+    Ok(match rule {
+        BinProtRule::Unit if json.is_null() => Value::Unit,
+        BinProtRule::Bool => Value::Bool(json.as_bool().ok_or_else(|| err("a bool"))?),
+        BinProtRule::Char => Value::Char(
+            json.as_u64()
+                .and_then(|c| u8::try_from(c).ok())
+                .ok_or_else(|| err("a byte"))?,
+        ),
+        BinProtRule::Int | BinProtRule::Int32 | BinProtRule::Int64 | BinProtRule::NativeInt => {
+            Value::Int(json.as_i64().ok_or_else(|| err("an integer"))?)
+        }
+        BinProtRule::Float => Value::Float(json.as_f64().ok_or_else(|| err("a number"))?),
+        BinProtRule::String => Value::String(match json {
+            JsonValue::String(s) => s.as_bytes().to_vec(),
+            JsonValue::Array(bytes) => bytes
+                .iter()
+                .map(|b| b.as_u64().and_then(|b| u8::try_from(b).ok()))
+                .collect::<Option<_>>()
+                .ok_or_else(|| err("a string or an array of bytes"))?,
+            _ => return Err(err("a string or an array of bytes")),
+        }),
+        BinProtRule::Option(_) if json.is_null() => Value::Option(None),
+        BinProtRule::Option(rule) => Value::Option(Some(Box::new(json_to_value(
+            json,
+            rule,
+            module_path,
+            path,
+        )?))),
+        BinProtRule::Record(fields) => {
+            let map = json.as_object().ok_or_else(|| err("an object"))?;
+            let mut values = Vec::with_capacity(fields.len());
+            for field in fields {
+                let path = format!("{path}.{}", field.field_name);
+                let json = map.get(&field.field_name).unwrap_or(&JsonValue::Null);
+                values.push((
+                    field.field_name.clone(),
+                    json_to_value(json, &field.field_rule, module_path, &path)?,
+                ));
+            }
+            Value::Record(values)
+        }
+        BinProtRule::Tuple(rules) => match json.as_array() {
+            Some(jsons) if jsons.len() == rules.len() => {
+                Value::Tuple(json_to_seq(jsons, rules.iter(), module_path, path)?)
+            }
+            _ => return Err(err(&format!("an array of length {}", rules.len()))),
+        },
+        BinProtRule::List(rule) => {
+            let jsons = json.as_array().ok_or_else(|| err("an array"))?;
+            Value::List(json_to_seq(
+                jsons,
+                std::iter::repeat(rule.as_ref()),
+                module_path,
+                path,
+            )?)
+        }
+        BinProtRule::Sum(summands) => {
+            let (ctor, args) = list_tagged(json).ok_or_else(|| err("a list tagged variant"))?;
+            let summand = summands
+                .iter()
+                .find(|s| s.ctor_name == ctor)
+                .ok_or_else(|| err("a known variant"))?;
+            Value::Sum {
+                name: summand.ctor_name.clone(),
+                index: summand.index as u8,
+                value: Box::new(json_to_args(args, &summand.ctor_args, module_path, path)?),
+            }
+        }
+        BinProtRule::Polyvar(polyvars) => {
+            let (ctor, args) = list_tagged(json).ok_or_else(|| err("a list tagged polyvar"))?;
+            let polyvar = polyvars
+                .iter()
+                .find_map(|p| match p {
+                    Polyvar::Tagged(t) if t.polyvar_name == ctor => Some(t),
+                    _ => None,
+                })
+                .ok_or_else(|| err("a known polyvar"))?;
+            Value::Polyvar {
+                name: polyvar.polyvar_name.clone(),
+                tag: polyvar.hash,
+                value: Box::new(json_to_args(
+                    args,
+                    &polyvar.polyvar_args,
+                    module_path,
+                    path,
+                )?),
+            }
+        }
+        BinProtRule::Reference(RuleRef::Resolved(payload)) => json_to_value(
+            json,
+            &payload.ref_rule,
+            Some(&payload.source_module_path),
+            path,
+        )?,
+        BinProtRule::Custom(rules) => {
+            let module_path = module_path.unwrap_or_default();
+            if let Some(len) = vector_len(module_path) {
+                match (rules.first(), json.as_array()) {
+                    (Some(rule), Some(jsons)) if jsons.len() == len => {
+                        let mut values = json_to_seq(jsons, std::iter::repeat(rule), None, path)?;
+                        values.push(Value::Unit);
+                        Value::Tuple(values)
+                    }
+                    _ => return Err(err(&format!("an array of length {len}"))),
+                }
+            } else if BIG_INT_PATHS.contains(&module_path) {
+                match json.as_str().map(hex::decode) {
+                    Some(Ok(bytes)) if bytes.len() == BIG_INT_LEN => {
+                        Value::Tuple(bytes.into_iter().map(Value::Char).collect())
+                    }
+                    _ => return Err(err("a 32 byte hex string")),
+                }
+            } else {
+                return Err(Error::UnknownCustomType {
+                    typ: module_path.to_string(),
+                });
+            }
+        }
+        BinProtRule::Unit => return Err(err("null")),
+        BinProtRule::Nat0
+        | BinProtRule::Vec(_, _)
+        | BinProtRule::Hashtable(_)
+        | BinProtRule::TypeVar(_)
+        | BinProtRule::Bigstring
+        | BinProtRule::SelfReference(_)
+        | BinProtRule::TypeClosure(_, _)
+        | BinProtRule::TypeAbstraction(_, _)
+        | BinProtRule::Reference(RuleRef::Unresolved(_))
+        | BinProtRule::CustomForPath(_, _) => return Err(Error::UnimplementedRule),
+    })
+}
+
+fn seq_to_json<'a>(
+    values: &[Value],
+    rules: impl Iterator<Item = &'a BinProtRule>,
+    module_path: Option<&str>,
+    path: &str,
+) -> Result<JsonValue> {
+    values
+        .iter()
+        .zip(rules)
+        .enumerate()
+        .map(|(i, (value, rule))| value_to_json(value, rule, module_path, &format!("{path}[{i}]")))
+        .collect()
+}
+
+fn json_to_seq<'a>(
+    jsons: &[JsonValue],
+    rules: impl Iterator<Item = &'a BinProtRule>,
+    module_path: Option<&str>,
+    path: &str,
+) -> Result<Vec<Value>> {
+    jsons
+        .iter()
+        .zip(rules)
+        .enumerate()
+        .map(|(i, (json, rule))| json_to_value(json, rule, module_path, &format!("{path}[{i}]")))
+        .collect()
+}
+
+/// Variant args are deserialized as a tuple
+fn list_tagged_to_json(
+    ctor: &str,
+    value: &Value,
+    args: &[BinProtRule],
+    module_path: Option<&str>,
+    path: &str,
+) -> Result<JsonValue> {
+    let mut tagged = vec![json!(ctor)];
+    match value_to_json(value, &BinProtRule::Tuple(args.to_vec()), module_path, path)? {
+        JsonValue::Array(args) => tagged.extend(args),
+        _ => unreachable!("tuples are arrays"),
+    }
+    Ok(JsonValue::Array(tagged))
+}
+
+fn json_to_args(
+    args: &[JsonValue],
+    rules: &[BinProtRule],
+    module_path: Option<&str>,
+    path: &str,
+) -> Result<Value> {
+    if args.len() != rules.len() {
+        return Err(mismatch(
+            &format!("{} variant args", rules.len()),
+            path,
+            JsonValue::Array(args.to_vec()).to_string(),
+        ));
+    }
+    Ok(Value::Tuple(json_to_seq(
+        args,
+        rules.iter(),
+        module_path,
+        path,
+    )?))
+}
+
+fn list_tagged(json: &JsonValue) -> Option<(&str, &[JsonValue])> {
+    let (ctor, args) = json.as_array()?.split_first()?;
+    Some((ctor.as_str()?, args))
+}
+
+fn chars_to_bytes(chars: &[Value]) -> Option<Vec<u8>> {
+    chars
+        .iter()
+        .map(|c| match c {
+            Value::Char(c) => Some(*c),
+            _ => None,
+        })
+        .collect()
+}
+
+fn string_to_json(bytes: Vec<u8>) -> JsonValue {
+    match String::from_utf8(bytes) {
+        Ok(s) => json!(s),
+        Err(e) => json!(e.into_bytes()),
+    }
+}
+
+fn mismatch(expected: &str, path: &str, got: String) -> Error {
+    Error::LayoutMismatch {
+        expected: expected.to_string(),
+        path: path.to_string(),
+        got,
+    }
+}
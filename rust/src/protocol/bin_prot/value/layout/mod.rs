@@ -161,7 +161,7 @@ impl TryFrom<ListTaggedEnum> for BinProtRule {
 /// Field of a BinProt record with a name and a value
 pub struct RecordField {
     pub(crate) field_name: String,
-    field_rule: BinProtRule,
+    pub(crate) field_rule: BinProtRule,
 }
 
 #[derive(Clone, Serialize, Deserialize, Debug)]
@@ -237,11 +237,11 @@ pub struct UnresolvedPayload {
 pub struct ResolvedPayload {
     source_type_decl: String,
     #[serde(default)]
-    source_module_path: String,
+    pub(crate) source_module_path: String,
     #[serde(default)]
     bin_io_derived: bool,
     // This is present only in later versions of the type layouts. Probably don't trust its value
-    ref_rule: Box<BinProtRule>,
+    pub(crate) ref_rule: Box<BinProtRule>,
 }
 
 impl TryFrom<ListTaggedEnum> for RuleRef {
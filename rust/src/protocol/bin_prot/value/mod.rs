@@ -12,6 +12,8 @@ use serde::Deserialize;
 
 mod index;
 #[cfg(feature = "loose_deserialization")]
+pub mod json;
+#[cfg(feature = "loose_deserialization")]
 pub mod layout;
 pub mod ser;
 mod visitor;
@@ -35,7 +35,7 @@ pub type BlockTimeV1 = Versioned2<u64, 1, 1>;
 
 /// u32 wrapper (json)
 /// Note that integers are represented as string in mina json
-#[derive(Clone, Debug, Eq, PartialEq, derive_more::From)]
+#[derive(Clone, Debug, Eq, PartialEq, derive_more::From, derive_more::Into)]
 pub struct U32Json(pub u32);
 
 impl Serialize for U32Json {
@@ -63,7 +63,7 @@ impl<'de> Deserialize<'de> for U32Json {
 
 /// u64 wrapper (json)
 /// Note that integers are represented as string in mina json
-#[derive(Clone, Debug, Eq, PartialEq, derive_more::From)]
+#[derive(Clone, Debug, Eq, PartialEq, derive_more::From, derive_more::Into)]
 pub struct U64Json(pub u64);
 
 impl Serialize for U64Json {
@@ -97,7 +97,7 @@ pub struct I64(pub i64);
 
 /// u64 wrapper (json)
 /// Note that integers are represented as string in mina json
-#[derive(Clone, Debug, Eq, PartialEq, derive_more::From)]
+#[derive(Clone, Debug, Eq, PartialEq, derive_more::From, derive_more::Into)]
 pub struct DecimalJson(pub u64);
 
 const MINA_PRECISION: u64 = 1000000000;
@@ -28,4 +28,5 @@ pub mod protocol_version;
 pub mod signatures;
 pub mod snark_work;
 pub mod staged_ledger_diff;
+pub mod v2;
 pub mod version_bytes;
@@ -0,0 +1,528 @@
+//! Basic types shared by the v2 (post-hardfork) protocol structures
+//!
+//! Berkeley only adds version tags to top level types, so unlike their v1
+//! counterparts these are not wrapped in [Versioned].
+
+use crate::protocol::serialization_types::{
+    common::{
+        Base58EncodableType, Base58EncodableVersionedType, BigInt256, ByteVec, HashV1, U32Json,
+    },
+    field_and_curve_elements::{FieldElement, InnerCurveScalar},
+    signatures::SignatureJson,
+    version_bytes,
+};
+use derive_more::{From, Into};
+use mina_serialization_versioned::Versioned;
+use num::BigUint;
+use serde::{de::DeserializeOwned, Deserialize, Deserializer, Serialize, Serializer};
+use std::marker::PhantomData;
+
+/// Mina's `Unsigned_extended.UInt32`
+///
+/// bin_prot encodes it as an OCaml `Int32`, so values above [i32::MAX] are
+/// written as negative numbers
+#[derive(Clone, Copy, Debug, Default, Serialize, Deserialize, Eq, PartialEq)]
+pub struct UInt32(pub i32);
+
+impl From<u32> for UInt32 {
+    fn from(t: u32) -> Self {
+        Self(t as i32)
+    }
+}
+
+impl From<UInt32> for u32 {
+    fn from(t: UInt32) -> Self {
+        t.0 as u32
+    }
+}
+
+impl From<U32Json> for UInt32 {
+    fn from(t: U32Json) -> Self {
+        t.0.into()
+    }
+}
+
+impl From<UInt32> for U32Json {
+    fn from(t: UInt32) -> Self {
+        Self(t.into())
+    }
+}
+
+/// Global slot number, either since genesis or since the hard fork, or a span
+/// of slots. Each is a single constructor sum over [UInt32]
+#[derive(Clone, Copy, Debug, Serialize, Deserialize, Eq, PartialEq)]
+pub enum GlobalSlot {
+    /// Slot number
+    Slot(UInt32),
+}
+
+impl From<U32Json> for GlobalSlot {
+    fn from(t: U32Json) -> Self {
+        Self::Slot(t.into())
+    }
+}
+
+impl From<GlobalSlot> for U32Json {
+    fn from(t: GlobalSlot) -> Self {
+        let GlobalSlot::Slot(slot) = t;
+        slot.into()
+    }
+}
+
+/// Pickles fixed length vector of `N` elements
+///
+/// The elements are written without a length prefix and followed by a unit
+/// byte
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Vector<T, const N: usize>(pub Vec<T>);
+
+impl<T, const N: usize> IntoIterator for Vector<T, N> {
+    type Item = T;
+    type IntoIter = std::vec::IntoIter<T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.0.into_iter()
+    }
+}
+
+impl<T, const N: usize> FromIterator<T> for Vector<T, N> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        Self(iter.into_iter().collect())
+    }
+}
+
+impl<T: Serialize, const N: usize> Serialize for Vector<T, N> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        use serde::ser::SerializeTuple;
+
+        if self.0.len() != N {
+            return Err(<S::Error as serde::ser::Error>::custom(format!(
+                "Expected {N} vector elements, got {}",
+                self.0.len()
+            )));
+        }
+
+        let mut tuple = serializer.serialize_tuple(N + 1)?;
+        for elem in &self.0 {
+            tuple.serialize_element(elem)?;
+        }
+        tuple.serialize_element(&())?;
+        tuple.end()
+    }
+}
+
+impl<'de, T: Deserialize<'de>, const N: usize> Deserialize<'de> for Vector<T, N> {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        struct VectorVisitor<T, const N: usize>(PhantomData<T>);
+
+        impl<'de, T: Deserialize<'de>, const N: usize> serde::de::Visitor<'de> for VectorVisitor<T, N> {
+            type Value = Vector<T, N>;
+
+            fn expecting(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+                write!(f, "{N} elements followed by a unit")
+            }
+
+            fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
+            where
+                A: serde::de::SeqAccess<'de>,
+            {
+                let mut elems = Vec::with_capacity(N);
+                for i in 0..N {
+                    elems.push(
+                        seq.next_element()?
+                            .ok_or_else(|| serde::de::Error::invalid_length(i, &self))?,
+                    );
+                }
+                seq.next_element::<()>()?
+                    .ok_or_else(|| serde::de::Error::invalid_length(N, &self))?;
+                Ok(Vector(elems))
+            }
+        }
+
+        deserializer.deserialize_tuple(N + 1, VectorVisitor::<T, N>(PhantomData))
+    }
+}
+
+/// Signature (field, scalar) pair
+#[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq)]
+pub struct Signature(pub FieldElement, pub InnerCurveScalar);
+
+impl From<SignatureJson> for Signature {
+    fn from(t: SignatureJson) -> Self {
+        let ((field, scalar),) = t.0.into();
+        Self(field, scalar)
+    }
+}
+
+impl From<Signature> for SignatureJson {
+    fn from(t: Signature) -> Self {
+        let sig: Versioned<_, 1> = (t.0, t.1).into();
+        Self(sig)
+    }
+}
+
+/// Field element (json)
+///
+/// Berkeley renders fields as `0x` prefixed, big endian, upper case hex,
+/// unlike [FieldElementJson](crate::protocol::serialization_types::field_and_curve_elements::FieldElementJson)
+#[derive(Clone, Debug, Eq, PartialEq, From, Into)]
+pub struct FieldJson(pub FieldElement);
+
+impl Serialize for FieldJson {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let mut bytes = self.0;
+        bytes.reverse();
+        serializer.serialize_str(&format!("0x{}", hex::encode_upper(bytes)))
+    }
+}
+
+impl<'de> Deserialize<'de> for FieldJson {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let s = String::deserialize(deserializer)?;
+        let mut bytes: FieldElement = hex::decode(s.trim_start_matches("0x"))
+            .map_err(<D::Error as serde::de::Error>::custom)?
+            .try_into()
+            .map_err(|_| <D::Error as serde::de::Error>::custom(format!("Invalid field: {s}")))?;
+        bytes.reverse();
+        Ok(Self(bytes))
+    }
+}
+
+/// Field element (json) rendered as a decimal integer, as in zkapp
+/// authorization kinds
+#[derive(Clone, Debug, Eq, PartialEq, From, Into)]
+pub struct DecimalFieldJson(pub FieldElement);
+
+impl Serialize for DecimalFieldJson {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_str(&BigUint::from_bytes_le(&self.0).to_str_radix(10))
+    }
+}
+
+impl<'de> Deserialize<'de> for DecimalFieldJson {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let s = String::deserialize(deserializer)?;
+        let mut bytes = BigUint::parse_bytes(s.as_bytes(), 10)
+            .ok_or_else(|| <D::Error as serde::de::Error>::custom(format!("Invalid field: {s}")))?
+            .to_bytes_le();
+        if bytes.len() > 32 {
+            return Err(<D::Error as serde::de::Error>::custom(format!(
+                "Invalid field: {s}"
+            )));
+        }
+        bytes.resize(32, 0);
+        Ok(Self(bytes.try_into().expect("32 bytes")))
+    }
+}
+
+impl<const VERSION_BYTE: u8> From<BigInt256>
+    for Base58EncodableVersionedType<VERSION_BYTE, HashV1>
+{
+    fn from(t: BigInt256) -> Self {
+        Self(t.into())
+    }
+}
+
+impl<const VERSION_BYTE: u8> From<Base58EncodableVersionedType<VERSION_BYTE, HashV1>>
+    for BigInt256
+{
+    fn from(t: Base58EncodableVersionedType<VERSION_BYTE, HashV1>) -> Self {
+        let (hash,) = t.0.into();
+        hash
+    }
+}
+
+impl<const VERSION_BYTE: u8> From<ByteVec> for Base58EncodableType<VERSION_BYTE, Vec<u8>> {
+    fn from(t: ByteVec) -> Self {
+        Self(t.0)
+    }
+}
+
+impl<const VERSION_BYTE: u8> From<Base58EncodableType<VERSION_BYTE, Vec<u8>>> for ByteVec {
+    fn from(t: Base58EncodableType<VERSION_BYTE, Vec<u8>>) -> Self {
+        Self(t.0)
+    }
+}
+
+/// base58 string representation of a receipt chain hash
+pub type ReceiptChainHashJson =
+    Base58EncodableVersionedType<{ version_bytes::RECEIPT_CHAIN_HASH }, HashV1>;
+
+/// base58 string representation of a user command memo
+pub type MemoJson = Base58EncodableType<{ version_bytes::USER_COMMAND_MEMO }, Vec<u8>>;
+
+/// Token id (json), the base58 encoded field without a version tag
+#[derive(Clone, Debug, Eq, PartialEq, From, Into)]
+pub struct TokenIdJson(pub FieldElement);
+
+impl Serialize for TokenIdJson {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let s = bs58::encode(self.0)
+            .with_check_version(version_bytes::TOKEN_ID_KEY)
+            .into_string();
+        serializer.serialize_str(&s)
+    }
+}
+
+impl<'de> Deserialize<'de> for TokenIdJson {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let s = String::deserialize(deserializer)?;
+        let decoded = bs58::decode(&s)
+            .with_check(Some(version_bytes::TOKEN_ID_KEY))
+            .into_vec()
+            .map_err(<D::Error as serde::de::Error>::custom)?;
+        // skip the version check byte
+        Ok(Self(decoded[1..].try_into().map_err(|_| {
+            <D::Error as serde::de::Error>::custom(format!("Invalid token id: {s}"))
+        })?))
+    }
+}
+
+/// Body reference (json), a lower case hex encoded blake2 digest
+#[derive(Clone, Debug, Eq, PartialEq, From, Into)]
+pub struct BodyReferenceJson(pub Vec<u8>);
+
+impl From<ByteVec> for BodyReferenceJson {
+    fn from(t: ByteVec) -> Self {
+        Self(t.0)
+    }
+}
+
+impl From<BodyReferenceJson> for ByteVec {
+    fn from(t: BodyReferenceJson) -> Self {
+        Self(t.0)
+    }
+}
+
+impl Serialize for BodyReferenceJson {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_str(&hex::encode(&self.0))
+    }
+}
+
+impl<'de> Deserialize<'de> for BodyReferenceJson {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let s = String::deserialize(deserializer)?;
+        Ok(Self(
+            hex::decode(s).map_err(<D::Error as serde::de::Error>::custom)?,
+        ))
+    }
+}
+
+/// Zkapp update that either sets a new value or keeps the current one
+#[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq)]
+pub enum SetOrKeep<T> {
+    /// Set a new value
+    Set(T),
+    /// Keep the current value
+    Keep,
+}
+
+/// Zkapp update that either sets a new value or keeps the current one (json)
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum SetOrKeepJson<T> {
+    /// Set a new value
+    Set(T),
+    /// Keep the current value
+    Keep,
+}
+
+impl<T, J: From<T>> From<SetOrKeep<T>> for SetOrKeepJson<J> {
+    fn from(t: SetOrKeep<T>) -> Self {
+        match t {
+            SetOrKeep::Set(v) => Self::Set(v.into()),
+            SetOrKeep::Keep => Self::Keep,
+        }
+    }
+}
+
+impl<T: From<J>, J> From<SetOrKeepJson<J>> for SetOrKeep<T> {
+    fn from(t: SetOrKeepJson<J>) -> Self {
+        match t {
+            SetOrKeepJson::Set(v) => Self::Set(v.into()),
+            SetOrKeepJson::Keep => Self::Keep,
+        }
+    }
+}
+
+impl<T: Serialize> Serialize for SetOrKeepJson<T> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        match self {
+            Self::Set(v) => ("Set", v).serialize(serializer),
+            Self::Keep => ("Keep",).serialize(serializer),
+        }
+    }
+}
+
+impl<'de, T: DeserializeOwned> Deserialize<'de> for SetOrKeepJson<T> {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        Ok(
+            match deserialize_list_tagged(deserializer, "Set", "Keep")? {
+                Some(v) => Self::Set(v),
+                None => Self::Keep,
+            },
+        )
+    }
+}
+
+/// Zkapp precondition that is either checked or ignored
+#[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq)]
+pub enum OrIgnore<T> {
+    /// Check the precondition
+    Check(T),
+    /// Ignore the precondition
+    Ignore,
+}
+
+/// Zkapp precondition that is either checked or ignored (json)
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum OrIgnoreJson<T> {
+    /// Check the precondition
+    Check(T),
+    /// Ignore the precondition
+    Ignore,
+}
+
+impl<T, J: From<T>> From<OrIgnore<T>> for OrIgnoreJson<J> {
+    fn from(t: OrIgnore<T>) -> Self {
+        match t {
+            OrIgnore::Check(v) => Self::Check(v.into()),
+            OrIgnore::Ignore => Self::Ignore,
+        }
+    }
+}
+
+impl<T: From<J>, J> From<OrIgnoreJson<J>> for OrIgnore<T> {
+    fn from(t: OrIgnoreJson<J>) -> Self {
+        match t {
+            OrIgnoreJson::Check(v) => Self::Check(v.into()),
+            OrIgnoreJson::Ignore => Self::Ignore,
+        }
+    }
+}
+
+impl<T: Serialize> Serialize for OrIgnoreJson<T> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        match self {
+            Self::Check(v) => ("Check", v).serialize(serializer),
+            Self::Ignore => ("Ignore",).serialize(serializer),
+        }
+    }
+}
+
+impl<'de, T: DeserializeOwned> Deserialize<'de> for OrIgnoreJson<T> {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        Ok(
+            match deserialize_list_tagged(deserializer, "Check", "Ignore")? {
+                Some(v) => Self::Check(v),
+                None => Self::Ignore,
+            },
+        )
+    }
+}
+
+/// Closed interval of a numeric zkapp precondition
+#[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq)]
+pub struct ClosedInterval<T> {
+    /// Lower bound
+    pub lower: T,
+    /// Upper bound
+    pub upper: T,
+}
+
+/// Closed interval of a numeric zkapp precondition (json)
+#[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq)]
+pub struct ClosedIntervalJson<T> {
+    /// Lower bound
+    pub lower: T,
+    /// Upper bound
+    pub upper: T,
+}
+
+impl<T, J: From<T>> From<ClosedInterval<T>> for ClosedIntervalJson<J> {
+    fn from(t: ClosedInterval<T>) -> Self {
+        Self {
+            lower: t.lower.into(),
+            upper: t.upper.into(),
+        }
+    }
+}
+
+impl<T: From<J>, J> From<ClosedIntervalJson<J>> for ClosedInterval<T> {
+    fn from(t: ClosedIntervalJson<J>) -> Self {
+        Self {
+            lower: t.lower.into(),
+            upper: t.upper.into(),
+        }
+    }
+}
+
+/// Deserializes a list tagged `[some_tag, value]` or `[none_tag]` option-like
+/// enum
+fn deserialize_list_tagged<'de, D, T>(
+    deserializer: D,
+    some_tag: &str,
+    none_tag: &str,
+) -> Result<Option<T>, D::Error>
+where
+    D: Deserializer<'de>,
+    T: DeserializeOwned,
+{
+    let v = Vec::<serde_json::Value>::deserialize(deserializer)?;
+    match (
+        v.first().and_then(serde_json::Value::as_str),
+        v.get(1),
+        v.len(),
+    ) {
+        (Some(tag), Some(value), 2) if tag == some_tag => Ok(Some(
+            serde_json::from_value(value.clone())
+                .map_err(<D::Error as serde::de::Error>::custom)?,
+        )),
+        (Some(tag), None, 1) if tag == none_tag => Ok(None),
+        _ => Err(<D::Error as serde::de::Error>::custom(format!(
+            "Expected [\"{some_tag}\", _] or [\"{none_tag}\"], got {v:?}"
+        ))),
+    }
+}
@@ -0,0 +1,29 @@
+//! Types capturing the v2 (post-hardfork) protocol structures
+//!
+//! Unlike their v1 counterparts these carry no version tags, so the binary
+//! types serialize to exactly the bytes the berkeley daemon produces. Each
+//! binary type has a json counterpart (suffixed `Json`) matching the
+//! daemon's json encoding, e.g. the precomputed blocks.
+//!
+//! The layout files describe the same types for use with
+//! [crate::protocol::bin_prot::Deserializer::with_layout]. The recursive zkapp
+//! call forest is unrolled to a depth of 5 in the zkapp command layouts.
+
+pub mod common;
+pub mod pickles;
+pub mod protocol_state;
+pub mod signed_command;
+pub mod staged_ledger_diff;
+pub mod zkapp_command;
+
+/// Layout of [protocol_state::ProtocolState]
+pub const PROTOCOL_STATE_LAYOUT: &str = include_str!("layouts/protocol_state.json");
+
+/// Layout of [signed_command::SignedCommand]
+pub const SIGNED_COMMAND_LAYOUT: &str = include_str!("layouts/signed_command.json");
+
+/// Layout of [zkapp_command::ZkappCommand]
+pub const ZKAPP_COMMAND_LAYOUT: &str = include_str!("layouts/zkapp_command.json");
+
+/// Layout of [staged_ledger_diff::StagedLedgerDiff]
+pub const STAGED_LEDGER_DIFF_LAYOUT: &str = include_str!("layouts/staged_ledger_diff.json");
@@ -0,0 +1,258 @@
+//! Pickles proofs and side loaded verification keys
+//!
+//! See <https://github.com/MinaProtocol/mina/tree/berkeley/src/lib/pickles>
+
+#![allow(missing_docs)] // Fields mirror the pickles types of the same name
+
+use super::common::Vector;
+use crate::protocol::{
+    bin_prot,
+    serialization_types::{
+        common::Base58EncodableVersionedType,
+        field_and_curve_elements::{FieldElement, FiniteECPoint},
+        version_bytes,
+    },
+};
+use base64::{prelude::BASE64_URL_SAFE, Engine};
+use derive_more::{From, Into};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+/// 128 bit challenge as two 64 bit limbs
+pub type Challenge = Vector<i64, 2>;
+
+#[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq)]
+pub struct ScalarChallenge {
+    pub inner: Challenge,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq)]
+pub struct BulletproofChallenge {
+    pub prechallenge: ScalarChallenge,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq)]
+pub struct FeatureFlags {
+    pub range_check0: bool,
+    pub range_check1: bool,
+    pub foreign_field_add: bool,
+    pub foreign_field_mul: bool,
+    pub xor: bool,
+    pub rot: bool,
+    pub lookup: bool,
+    pub runtime_tables: bool,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq)]
+pub struct Plonk {
+    pub alpha: ScalarChallenge,
+    pub beta: Challenge,
+    pub gamma: Challenge,
+    pub zeta: ScalarChallenge,
+    pub joint_combiner: Option<ScalarChallenge>,
+    pub feature_flags: FeatureFlags,
+}
+
+/// Number of proofs verified by a pickles proof (`Pickles_base.Proofs_verified`)
+#[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq)]
+pub enum ProofsVerified {
+    N0,
+    N1,
+    N2,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq)]
+pub struct BranchData {
+    pub proofs_verified: ProofsVerified,
+    pub domain_log2: u8,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq)]
+pub struct DeferredValues {
+    pub plonk: Plonk,
+    pub bulletproof_challenges: Vector<BulletproofChallenge, 16>,
+    pub branch_data: BranchData,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq)]
+pub struct MessagesForNextWrapProof {
+    pub challenge_polynomial_commitment: FiniteECPoint,
+    pub old_bulletproof_challenges: Vector<Vector<BulletproofChallenge, 15>, 2>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq)]
+pub struct ProofState {
+    pub deferred_values: DeferredValues,
+    pub sponge_digest_before_evaluations: Vector<i64, 4>,
+    pub messages_for_next_wrap_proof: MessagesForNextWrapProof,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq)]
+pub struct MessagesForNextStepProof {
+    pub app_state: (),
+    pub challenge_polynomial_commitments: Vec<FiniteECPoint>,
+    pub old_bulletproof_challenges: Vec<Vector<BulletproofChallenge, 16>>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq)]
+pub struct Statement {
+    pub proof_state: ProofState,
+    pub messages_for_next_step_proof: MessagesForNextStepProof,
+}
+
+/// Evaluations of a polynomial at zeta and zeta * omega, one per chunk
+#[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq)]
+pub struct PointEvaluations(pub Vec<FieldElement>, pub Vec<FieldElement>);
+
+#[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq)]
+pub struct Evals {
+    pub w: Vector<PointEvaluations, 15>,
+    pub coefficients: Vector<PointEvaluations, 15>,
+    pub z: PointEvaluations,
+    pub s: Vector<PointEvaluations, 6>,
+    pub generic_selector: PointEvaluations,
+    pub poseidon_selector: PointEvaluations,
+    pub complete_add_selector: PointEvaluations,
+    pub mul_selector: PointEvaluations,
+    pub emul_selector: PointEvaluations,
+    pub endomul_scalar_selector: PointEvaluations,
+    pub range_check0_selector: Option<PointEvaluations>,
+    pub range_check1_selector: Option<PointEvaluations>,
+    pub foreign_field_add_selector: Option<PointEvaluations>,
+    pub foreign_field_mul_selector: Option<PointEvaluations>,
+    pub xor_selector: Option<PointEvaluations>,
+    pub rot_selector: Option<PointEvaluations>,
+    pub lookup_aggregation: Option<PointEvaluations>,
+    pub lookup_table: Option<PointEvaluations>,
+    pub lookup_sorted: Vector<Option<PointEvaluations>, 5>,
+    pub runtime_lookup_table: Option<PointEvaluations>,
+    pub runtime_lookup_table_selector: Option<PointEvaluations>,
+    pub xor_lookup_selector: Option<PointEvaluations>,
+    pub lookup_gate_lookup_selector: Option<PointEvaluations>,
+    pub range_check_lookup_selector: Option<PointEvaluations>,
+    pub foreign_field_mul_lookup_selector: Option<PointEvaluations>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq)]
+pub struct EvalsWithPublicInput {
+    pub public_input: (FieldElement, FieldElement),
+    pub evals: Evals,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq)]
+pub struct AllEvals {
+    pub evals: EvalsWithPublicInput,
+    pub ft_eval1: FieldElement,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq)]
+pub struct WireCommitments {
+    pub w_comm: Vector<FiniteECPoint, 15>,
+    pub z_comm: FiniteECPoint,
+    pub t_comm: Vector<FiniteECPoint, 7>,
+}
+
+/// Evaluations at zeta and zeta * omega
+pub type FieldPair = (FieldElement, FieldElement);
+
+#[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq)]
+pub struct WireEvaluations {
+    pub w: Vector<FieldPair, 15>,
+    pub coefficients: Vector<FieldPair, 15>,
+    pub z: FieldPair,
+    pub s: Vector<FieldPair, 6>,
+    pub generic_selector: FieldPair,
+    pub poseidon_selector: FieldPair,
+    pub complete_add_selector: FieldPair,
+    pub mul_selector: FieldPair,
+    pub emul_selector: FieldPair,
+    pub endomul_scalar_selector: FieldPair,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq)]
+pub struct OpeningProof {
+    pub lr: Vec<(FiniteECPoint, FiniteECPoint)>,
+    pub z_1: FieldElement,
+    pub z_2: FieldElement,
+    pub delta: FiniteECPoint,
+    pub challenge_polynomial_commitment: FiniteECPoint,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq)]
+pub struct WireProof {
+    pub commitments: WireCommitments,
+    pub evaluations: WireEvaluations,
+    pub ft_eval1: FieldElement,
+    pub bulletproof: OpeningProof,
+}
+
+/// Pickles proof verifying at most two proofs
+/// (`Pickles.Proof.Proofs_verified_2.Stable.V2`), used for blockchain,
+/// transaction snark and zkapp proofs
+#[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq)]
+pub struct Proof {
+    pub statement: Statement,
+    pub prev_evals: AllEvals,
+    pub proof: WireProof,
+}
+
+/// Pickles proof (json), the base64 (url safe) encoded bin_prot bytes
+#[derive(Clone, Debug, Eq, PartialEq, From, Into)]
+pub struct ProofJson(pub Box<Proof>);
+
+impl Serialize for ProofJson {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let mut buf = Vec::new();
+        bin_prot::to_writer(&mut buf, &self.0).map_err(<S::Error as serde::ser::Error>::custom)?;
+        serializer.serialize_str(&BASE64_URL_SAFE.encode(buf))
+    }
+}
+
+impl<'de> Deserialize<'de> for ProofJson {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let s = String::deserialize(deserializer)?;
+        let bytes = BASE64_URL_SAFE
+            .decode(s)
+            .map_err(<D::Error as serde::de::Error>::custom)?;
+        Ok(Self(
+            bin_prot::from_reader_strict(bytes.as_slice())
+                .map_err(<D::Error as serde::de::Error>::custom)?,
+        ))
+    }
+}
+
+/// Commitments to the wrap circuit's polynomials
+#[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq)]
+pub struct WrapIndex {
+    pub sigma_comm: Vector<FiniteECPoint, 7>,
+    pub coefficients_comm: Vector<FiniteECPoint, 15>,
+    pub generic_comm: FiniteECPoint,
+    pub psm_comm: FiniteECPoint,
+    pub complete_add_comm: FiniteECPoint,
+    pub mul_comm: FiniteECPoint,
+    pub emul_comm: FiniteECPoint,
+    pub endomul_scalar_comm: FiniteECPoint,
+}
+
+/// Side loaded verification key (`Pickles.Side_loaded.Verification_key.Stable.V2`)
+#[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq)]
+pub struct VerificationKey {
+    pub max_proofs_verified: ProofsVerified,
+    pub actual_wrap_domain_size: ProofsVerified,
+    pub wrap_index: WrapIndex,
+}
+
+/// base58 string representation of a side loaded verification key
+pub type VerificationKeyJson =
+    Base58EncodableVersionedType<{ version_bytes::VERIFICATION_KEY }, VerificationKey>;
+
+impl From<VerificationKeyJson> for VerificationKey {
+    fn from(t: VerificationKeyJson) -> Self {
+        t.0
+    }
+}
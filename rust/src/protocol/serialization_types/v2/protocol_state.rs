@@ -0,0 +1,457 @@
+//! Types related to the v2 protocol state
+//!
+//! See <https://github.com/MinaProtocol/mina/blob/berkeley/src/lib/mina_state/protocol_state.ml>
+
+#![allow(missing_docs)] // Fields mirror the mina_state types of the same name
+
+use super::common::{BodyReferenceJson, FieldJson, GlobalSlot, TokenIdJson, UInt32};
+use crate::protocol::serialization_types::{
+    common::{
+        AuxHashJson, BigInt256, ByteVec, ByteVecJson, CoinBaseHashV1Json, CoinBaseStackDataV1Json,
+        CoinBaseStackHashV1Json, DecimalJson, EpochSeedHashV1Json, LedgerHashV1Json,
+        PendingCoinbaseAuxHashJson, StateHashV1Json, U32Json, U64Json,
+    },
+    consensus_state::{VrfOutputTruncated, VrfOutputTruncatedBase64Json},
+    field_and_curve_elements::FieldElement,
+    signatures::{CompressedCurvePoint, PublicKeyJson},
+    snark_work::{SgnType, SgnTypeJson},
+    staged_ledger_diff::{TransactionStatusFailedType, TransactionStatusFailedTypeJson},
+};
+use mina_serialization_proc_macros::AutoFrom;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+/// Mina v2 protocol state (`Mina_state.Protocol_state.Value.Stable.V2`)
+#[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq)]
+pub struct ProtocolState {
+    /// Hash of the previous protocol state
+    pub previous_state_hash: BigInt256,
+    /// Body of the protocol state
+    pub body: ProtocolStateBody,
+}
+
+/// Mina v2 protocol state (json)
+#[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq, AutoFrom)]
+#[auto_from(ProtocolState)]
+pub struct ProtocolStateJson {
+    /// Hash of the previous protocol state
+    pub previous_state_hash: StateHashV1Json,
+    /// Body of the protocol state
+    pub body: ProtocolStateBodyJson,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq)]
+pub struct ProtocolStateBody {
+    pub genesis_state_hash: BigInt256,
+    pub blockchain_state: BlockchainState,
+    pub consensus_state: ConsensusState,
+    pub constants: Constants,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq, AutoFrom)]
+#[auto_from(ProtocolStateBody)]
+pub struct ProtocolStateBodyJson {
+    pub genesis_state_hash: StateHashV1Json,
+    pub blockchain_state: BlockchainStateJson,
+    pub consensus_state: ConsensusStateJson,
+    pub constants: ConstantsJson,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq)]
+pub struct BlockchainState {
+    pub staged_ledger_hash: StagedLedgerHash,
+    pub genesis_ledger_hash: BigInt256,
+    pub ledger_proof_statement: SnarkedLedgerState,
+    pub timestamp: u64,
+    pub body_reference: ByteVec,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq, AutoFrom)]
+#[auto_from(BlockchainState)]
+pub struct BlockchainStateJson {
+    pub staged_ledger_hash: StagedLedgerHashJson,
+    pub genesis_ledger_hash: LedgerHashV1Json,
+    pub ledger_proof_statement: SnarkedLedgerStateJson,
+    pub timestamp: U64Json,
+    pub body_reference: BodyReferenceJson,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq)]
+pub struct StagedLedgerHash {
+    pub non_snark: NonSnarkStagedLedgerHash,
+    pub pending_coinbase_hash: BigInt256,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq, AutoFrom)]
+#[auto_from(StagedLedgerHash)]
+pub struct StagedLedgerHashJson {
+    pub non_snark: NonSnarkStagedLedgerHashJson,
+    pub pending_coinbase_hash: CoinBaseHashV1Json,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq)]
+pub struct NonSnarkStagedLedgerHash {
+    pub ledger_hash: BigInt256,
+    pub aux_hash: ByteVec,
+    pub pending_coinbase_aux: ByteVec,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq, AutoFrom)]
+#[auto_from(NonSnarkStagedLedgerHash)]
+pub struct NonSnarkStagedLedgerHashJson {
+    pub ledger_hash: LedgerHashV1Json,
+    pub aux_hash: AuxHashJson,
+    pub pending_coinbase_aux: PendingCoinbaseAuxHashJson,
+}
+
+/// Signed amount or fee
+#[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq)]
+pub struct Signed {
+    pub magnitude: u64,
+    pub sgn: SgnType,
+}
+
+/// Signed amount (json)
+#[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq, AutoFrom)]
+#[auto_from(Signed)]
+pub struct SignedAmountJson {
+    pub magnitude: U64Json,
+    pub sgn: SgnTypeJson,
+}
+
+/// Signed fee (json), the magnitude is rendered in decimal mina
+#[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq, AutoFrom)]
+#[auto_from(Signed)]
+pub struct SignedFeeJson {
+    pub magnitude: DecimalJson,
+    pub sgn: SgnTypeJson,
+}
+
+/// Statement of a snarked ledger transition (`Mina_state.Snarked_ledger_state`)
+///
+/// Blocks carry it without the sok digest
+#[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq)]
+pub struct SnarkedLedgerState {
+    pub source: Registers,
+    pub target: Registers,
+    pub connecting_ledger_left: BigInt256,
+    pub connecting_ledger_right: BigInt256,
+    pub supply_increase: Signed,
+    pub fee_excess: FeeExcess,
+    pub sok_digest: (),
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq, AutoFrom)]
+#[auto_from(SnarkedLedgerState)]
+pub struct SnarkedLedgerStateJson {
+    pub source: RegistersJson,
+    pub target: RegistersJson,
+    pub connecting_ledger_left: LedgerHashV1Json,
+    pub connecting_ledger_right: LedgerHashV1Json,
+    pub supply_increase: SignedAmountJson,
+    pub fee_excess: FeeExcessJson,
+    pub sok_digest: (),
+}
+
+/// Statement of a snarked ledger transition with the digest of the snark
+/// worker's sok message
+#[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq)]
+pub struct SnarkedLedgerStateWithSok {
+    pub source: Registers,
+    pub target: Registers,
+    pub connecting_ledger_left: BigInt256,
+    pub connecting_ledger_right: BigInt256,
+    pub supply_increase: Signed,
+    pub fee_excess: FeeExcess,
+    pub sok_digest: ByteVec,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq, AutoFrom)]
+#[auto_from(SnarkedLedgerStateWithSok)]
+pub struct SnarkedLedgerStateWithSokJson {
+    pub source: RegistersJson,
+    pub target: RegistersJson,
+    pub connecting_ledger_left: LedgerHashV1Json,
+    pub connecting_ledger_right: LedgerHashV1Json,
+    pub supply_increase: SignedAmountJson,
+    pub fee_excess: FeeExcessJson,
+    pub sok_digest: ByteVecJson,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq)]
+pub struct Registers {
+    pub first_pass_ledger: BigInt256,
+    pub second_pass_ledger: BigInt256,
+    pub pending_coinbase_stack: PendingCoinbaseStack,
+    pub local_state: LocalState,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq, AutoFrom)]
+#[auto_from(Registers)]
+pub struct RegistersJson {
+    pub first_pass_ledger: LedgerHashV1Json,
+    pub second_pass_ledger: LedgerHashV1Json,
+    pub pending_coinbase_stack: PendingCoinbaseStackJson,
+    pub local_state: LocalStateJson,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq)]
+pub struct PendingCoinbaseStack {
+    pub data: BigInt256,
+    pub state: PendingCoinbaseStackState,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq, AutoFrom)]
+#[auto_from(PendingCoinbaseStack)]
+pub struct PendingCoinbaseStackJson {
+    pub data: CoinBaseStackDataV1Json,
+    pub state: PendingCoinbaseStackStateJson,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq)]
+pub struct PendingCoinbaseStackState {
+    pub init: BigInt256,
+    pub curr: BigInt256,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq, AutoFrom)]
+#[auto_from(PendingCoinbaseStackState)]
+pub struct PendingCoinbaseStackStateJson {
+    pub init: CoinBaseStackHashV1Json,
+    pub curr: CoinBaseStackHashV1Json,
+}
+
+/// Zkapp command application state between account updates
+#[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq)]
+pub struct LocalState {
+    pub stack_frame: FieldElement,
+    pub call_stack: FieldElement,
+    pub transaction_commitment: FieldElement,
+    pub full_transaction_commitment: FieldElement,
+    pub excess: Signed,
+    pub supply_increase: Signed,
+    pub ledger: BigInt256,
+    pub success: bool,
+    pub account_update_index: UInt32,
+    pub failure_status_tbl: Vec<FailureList>,
+    pub will_succeed: bool,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq, AutoFrom)]
+#[auto_from(LocalState)]
+pub struct LocalStateJson {
+    pub stack_frame: FieldJson,
+    pub call_stack: FieldJson,
+    pub transaction_commitment: FieldJson,
+    pub full_transaction_commitment: FieldJson,
+    pub excess: SignedAmountJson,
+    pub supply_increase: SignedAmountJson,
+    pub ledger: LedgerHashV1Json,
+    pub success: bool,
+    pub account_update_index: U32Json,
+    pub failure_status_tbl: Vec<FailureListJson>,
+    pub will_succeed: bool,
+}
+
+/// Failures of a single account update
+#[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq)]
+pub struct FailureList(pub Vec<TransactionStatusFailedType>);
+
+/// Failures of a single account update (json), each failure is list tagged
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct FailureListJson(pub Vec<TransactionStatusFailedTypeJson>);
+
+impl Serialize for FailureListJson {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let mut failures = Vec::with_capacity(self.0.len());
+        for failure in &self.0 {
+            let v =
+                serde_json::to_value(failure).map_err(<S::Error as serde::ser::Error>::custom)?;
+            failures.push(match v {
+                serde_json::Value::Object(m) => m
+                    .into_iter()
+                    .flat_map(|(k, v)| [serde_json::Value::String(k), v])
+                    .collect(),
+                v => vec![v],
+            });
+        }
+        failures.serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for FailureListJson {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let failures = Vec::<Vec<serde_json::Value>>::deserialize(deserializer)?;
+        let mut res = Vec::with_capacity(failures.len());
+        for failure in failures {
+            let v = match failure.as_slice() {
+                [tag] => tag.clone(),
+                [serde_json::Value::String(tag), arg] => serde_json::json!({ tag: arg }),
+                _ => {
+                    return Err(<D::Error as serde::de::Error>::custom(format!(
+                        "Expected a list tagged failure, got {failure:?}"
+                    )))
+                }
+            };
+            res.push(serde_json::from_value(v).map_err(<D::Error as serde::de::Error>::custom)?);
+        }
+        Ok(Self(res))
+    }
+}
+
+impl From<FailureList> for FailureListJson {
+    fn from(t: FailureList) -> Self {
+        Self(t.0.into_iter().map(Into::into).collect())
+    }
+}
+
+impl From<FailureListJson> for FailureList {
+    fn from(t: FailureListJson) -> Self {
+        Self(t.0.into_iter().map(Into::into).collect())
+    }
+}
+
+/// Fee excess of the two tokens a transaction touches
+#[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq)]
+pub struct FeeExcess(pub FeeExcessPart, pub FeeExcessPart);
+
+/// Fee excess of the two tokens a transaction touches (json)
+#[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq)]
+pub struct FeeExcessJson(pub FeeExcessPartJson, pub FeeExcessPartJson);
+
+impl From<FeeExcess> for FeeExcessJson {
+    fn from(t: FeeExcess) -> Self {
+        Self(t.0.into(), t.1.into())
+    }
+}
+
+impl From<FeeExcessJson> for FeeExcess {
+    fn from(t: FeeExcessJson) -> Self {
+        Self(t.0.into(), t.1.into())
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq)]
+pub struct FeeExcessPart {
+    pub token: FieldElement,
+    pub amount: Signed,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq, AutoFrom)]
+#[auto_from(FeeExcessPart)]
+pub struct FeeExcessPartJson {
+    pub token: TokenIdJson,
+    pub amount: SignedFeeJson,
+}
+
+/// Mina v2 consensus state (`Consensus.Data.Consensus_state.Value.Stable.V2`)
+#[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq)]
+pub struct ConsensusState {
+    pub blockchain_length: UInt32,
+    pub epoch_count: UInt32,
+    pub min_window_density: UInt32,
+    pub sub_window_densities: Vec<UInt32>,
+    pub last_vrf_output: VrfOutputTruncated,
+    pub total_currency: u64,
+    pub curr_global_slot_since_hard_fork: ConsensusGlobalSlot,
+    pub global_slot_since_genesis: GlobalSlot,
+    pub staking_epoch_data: EpochData,
+    pub next_epoch_data: EpochData,
+    pub has_ancestor_in_same_checkpoint_window: bool,
+    pub block_stake_winner: CompressedCurvePoint,
+    pub block_creator: CompressedCurvePoint,
+    pub coinbase_receiver: CompressedCurvePoint,
+    pub supercharge_coinbase: bool,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq, AutoFrom)]
+#[auto_from(ConsensusState)]
+pub struct ConsensusStateJson {
+    pub blockchain_length: U32Json,
+    pub epoch_count: U32Json,
+    pub min_window_density: U32Json,
+    pub sub_window_densities: Vec<U32Json>,
+    pub last_vrf_output: VrfOutputTruncatedBase64Json,
+    pub total_currency: U64Json,
+    pub curr_global_slot_since_hard_fork: ConsensusGlobalSlotJson,
+    pub global_slot_since_genesis: U32Json,
+    pub staking_epoch_data: EpochDataJson,
+    pub next_epoch_data: EpochDataJson,
+    pub has_ancestor_in_same_checkpoint_window: bool,
+    pub block_stake_winner: PublicKeyJson,
+    pub block_creator: PublicKeyJson,
+    pub coinbase_receiver: PublicKeyJson,
+    pub supercharge_coinbase: bool,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq)]
+pub struct ConsensusGlobalSlot {
+    pub slot_number: GlobalSlot,
+    pub slots_per_epoch: UInt32,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq, AutoFrom)]
+#[auto_from(ConsensusGlobalSlot)]
+pub struct ConsensusGlobalSlotJson {
+    pub slot_number: U32Json,
+    pub slots_per_epoch: U32Json,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq)]
+pub struct EpochData {
+    pub ledger: EpochLedger,
+    pub seed: BigInt256,
+    pub start_checkpoint: BigInt256,
+    pub lock_checkpoint: BigInt256,
+    pub epoch_length: UInt32,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq, AutoFrom)]
+#[auto_from(EpochData)]
+pub struct EpochDataJson {
+    pub ledger: EpochLedgerJson,
+    pub seed: EpochSeedHashV1Json,
+    pub start_checkpoint: StateHashV1Json,
+    pub lock_checkpoint: StateHashV1Json,
+    pub epoch_length: U32Json,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq)]
+pub struct EpochLedger {
+    pub hash: BigInt256,
+    pub total_currency: u64,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq, AutoFrom)]
+#[auto_from(EpochLedger)]
+pub struct EpochLedgerJson {
+    pub hash: LedgerHashV1Json,
+    pub total_currency: U64Json,
+}
+
+/// Consensus constants of the protocol state
+#[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq)]
+pub struct Constants {
+    pub k: UInt32,
+    pub slots_per_epoch: UInt32,
+    pub slots_per_sub_window: UInt32,
+    pub grace_period_slots: UInt32,
+    pub delta: UInt32,
+    pub genesis_state_timestamp: u64,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq, AutoFrom)]
+#[auto_from(Constants)]
+pub struct ConstantsJson {
+    pub k: U32Json,
+    pub slots_per_epoch: U32Json,
+    pub slots_per_sub_window: U32Json,
+    pub grace_period_slots: U32Json,
+    pub delta: U32Json,
+    pub genesis_state_timestamp: U64Json,
+}
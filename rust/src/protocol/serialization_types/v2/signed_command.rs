@@ -0,0 +1,126 @@
+//! Types related to v2 signed commands
+//!
+//! See <https://github.com/MinaProtocol/mina/blob/berkeley/src/lib/mina_base/signed_command.ml>
+
+#![allow(missing_docs)] // Fields mirror the mina_base types of the same name
+
+use super::common::{GlobalSlot, MemoJson, Signature, UInt32};
+use crate::protocol::serialization_types::{
+    common::{ByteVec, DecimalJson, U32Json, U64Json},
+    signatures::{CompressedCurvePoint, PublicKeyJson, SignatureJson},
+};
+use mina_serialization_proc_macros::AutoFrom;
+use mina_serialization_versioned::{
+    impl_mina_enum_json_serde, impl_mina_enum_json_serde_with_option,
+};
+use serde::{Deserialize, Serialize};
+
+/// Mina v2 signed command (`Mina_base.Signed_command.Stable.V2`)
+#[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq)]
+pub struct SignedCommand {
+    pub payload: SignedCommandPayload,
+    pub signer: CompressedCurvePoint,
+    pub signature: Signature,
+}
+
+/// Mina v2 signed command (json)
+#[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq, AutoFrom)]
+#[auto_from(SignedCommand)]
+pub struct SignedCommandJson {
+    pub payload: SignedCommandPayloadJson,
+    pub signer: PublicKeyJson,
+    pub signature: SignatureJson,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq)]
+pub struct SignedCommandPayload {
+    pub common: SignedCommandPayloadCommon,
+    pub body: SignedCommandPayloadBody,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq, AutoFrom)]
+#[auto_from(SignedCommandPayload)]
+pub struct SignedCommandPayloadJson {
+    pub common: SignedCommandPayloadCommonJson,
+    pub body: SignedCommandPayloadBodyJson,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq)]
+pub struct SignedCommandPayloadCommon {
+    pub fee: u64,
+    pub fee_payer_pk: CompressedCurvePoint,
+    pub nonce: UInt32,
+    pub valid_until: GlobalSlot,
+    pub memo: ByteVec,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq, AutoFrom)]
+#[auto_from(SignedCommandPayloadCommon)]
+pub struct SignedCommandPayloadCommonJson {
+    pub fee: DecimalJson,
+    pub fee_payer_pk: PublicKeyJson,
+    pub nonce: U32Json,
+    pub valid_until: U32Json,
+    pub memo: MemoJson,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq)]
+pub enum SignedCommandPayloadBody {
+    PaymentPayload(PaymentPayload),
+    StakeDelegation(StakeDelegation),
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq)]
+enum SignedCommandPayloadBodyJsonProxy {
+    #[serde(rename = "Payment")]
+    PaymentPayload(PaymentPayloadJson),
+    #[serde(rename = "Stake_delegation")]
+    StakeDelegation(StakeDelegationJson),
+}
+
+#[derive(Clone, Debug, Eq, PartialEq, AutoFrom)]
+#[auto_from(SignedCommandPayloadBody)]
+#[auto_from(SignedCommandPayloadBodyJsonProxy)]
+pub enum SignedCommandPayloadBodyJson {
+    PaymentPayload(PaymentPayloadJson),
+    StakeDelegation(StakeDelegationJson),
+}
+
+impl_mina_enum_json_serde_with_option!(
+    SignedCommandPayloadBodyJson,
+    SignedCommandPayloadBodyJsonProxy,
+    false
+);
+
+#[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq)]
+pub struct PaymentPayload {
+    pub receiver_pk: CompressedCurvePoint,
+    pub amount: u64,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq, AutoFrom)]
+#[auto_from(PaymentPayload)]
+pub struct PaymentPayloadJson {
+    pub receiver_pk: PublicKeyJson,
+    pub amount: U64Json,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq)]
+pub enum StakeDelegation {
+    SetDelegate { new_delegate: CompressedCurvePoint },
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq)]
+enum StakeDelegationJsonProxy {
+    #[serde(rename = "Set_delegate")]
+    SetDelegate { new_delegate: PublicKeyJson },
+}
+
+#[derive(Clone, Debug, Eq, PartialEq, AutoFrom)]
+#[auto_from(StakeDelegation)]
+#[auto_from(StakeDelegationJsonProxy)]
+pub enum StakeDelegationJson {
+    SetDelegate { new_delegate: PublicKeyJson },
+}
+
+impl_mina_enum_json_serde!(StakeDelegationJson, StakeDelegationJsonProxy);
@@ -0,0 +1,299 @@
+//! Types related to the v2 staged ledger diff
+//!
+//! See <https://github.com/MinaProtocol/mina/blob/berkeley/src/lib/staged_ledger_diff/diff.ml>
+
+#![allow(missing_docs)] // Fields mirror the staged_ledger_diff types of the same name
+
+use super::{
+    pickles::{Proof, ProofJson},
+    protocol_state::{
+        FailureList, FailureListJson, SnarkedLedgerStateWithSok, SnarkedLedgerStateWithSokJson,
+    },
+    signed_command::{SignedCommand, SignedCommandJson},
+    zkapp_command::{ZkappCommand, ZkappCommandJson},
+};
+use crate::protocol::serialization_types::{
+    common::DecimalJson,
+    signatures::{CompressedCurvePoint, PublicKeyJson},
+};
+use mina_serialization_proc_macros::AutoFrom;
+use mina_serialization_versioned::{
+    impl_mina_enum_json_serde, impl_mina_enum_json_serde_with_option,
+};
+use serde::{Deserialize, Serialize};
+
+/// Mina v2 staged ledger diff (`Staged_ledger_diff.Stable.V2`)
+#[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq)]
+pub struct StagedLedgerDiff {
+    pub diff: Diff,
+}
+
+/// Mina v2 staged ledger diff (json)
+#[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq, AutoFrom)]
+#[auto_from(StagedLedgerDiff)]
+pub struct StagedLedgerDiffJson {
+    pub diff: DiffJson,
+}
+
+/// Pre-diff with at most two coinbase parts, optionally followed by one with
+/// at most one coinbase part
+#[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq)]
+pub struct Diff(pub PreDiffTwo, pub Option<PreDiffOne>);
+
+#[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq)]
+pub struct DiffJson(pub PreDiffTwoJson, pub Option<PreDiffOneJson>);
+
+impl From<Diff> for DiffJson {
+    fn from(t: Diff) -> Self {
+        Self(t.0.into(), t.1.map(Into::into))
+    }
+}
+
+impl From<DiffJson> for Diff {
+    fn from(t: DiffJson) -> Self {
+        Self(t.0.into(), t.1.map(Into::into))
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq)]
+pub struct PreDiffTwo {
+    pub completed_works: Vec<TransactionSnarkWork>,
+    pub commands: Vec<UserCommandWithStatus>,
+    pub coinbase: CoinbaseAtMostTwo,
+    pub internal_command_statuses: Vec<TransactionStatus>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq, AutoFrom)]
+#[auto_from(PreDiffTwo)]
+pub struct PreDiffTwoJson {
+    pub completed_works: Vec<TransactionSnarkWorkJson>,
+    pub commands: Vec<UserCommandWithStatusJson>,
+    pub coinbase: CoinbaseAtMostTwoJson,
+    pub internal_command_statuses: Vec<TransactionStatusJson>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq)]
+pub struct PreDiffOne {
+    pub completed_works: Vec<TransactionSnarkWork>,
+    pub commands: Vec<UserCommandWithStatus>,
+    pub coinbase: CoinbaseAtMostOne,
+    pub internal_command_statuses: Vec<TransactionStatus>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq, AutoFrom)]
+#[auto_from(PreDiffOne)]
+pub struct PreDiffOneJson {
+    pub completed_works: Vec<TransactionSnarkWorkJson>,
+    pub commands: Vec<UserCommandWithStatusJson>,
+    pub coinbase: CoinbaseAtMostOneJson,
+    pub internal_command_statuses: Vec<TransactionStatusJson>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq)]
+pub struct UserCommandWithStatus {
+    pub data: UserCommand,
+    pub status: TransactionStatus,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq, AutoFrom)]
+#[auto_from(UserCommandWithStatus)]
+pub struct UserCommandWithStatusJson {
+    pub data: UserCommandJson,
+    pub status: TransactionStatusJson,
+}
+
+/// Mina v2 user command (`Mina_base.User_command.Stable.V2`)
+#[allow(clippy::large_enum_variant)]
+#[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq)]
+pub enum UserCommand {
+    SignedCommand(SignedCommand),
+    ZkappCommand(ZkappCommand),
+}
+
+#[allow(clippy::large_enum_variant)]
+#[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq)]
+enum UserCommandJsonProxy {
+    #[serde(rename = "Signed_command")]
+    SignedCommand(SignedCommandJson),
+    #[serde(rename = "Zkapp_command")]
+    ZkappCommand(ZkappCommandJson),
+}
+
+#[allow(clippy::large_enum_variant)]
+#[derive(Clone, Debug, Eq, PartialEq, AutoFrom)]
+#[auto_from(UserCommand)]
+#[auto_from(UserCommandJsonProxy)]
+pub enum UserCommandJson {
+    SignedCommand(SignedCommandJson),
+    ZkappCommand(ZkappCommandJson),
+}
+
+impl_mina_enum_json_serde!(UserCommandJson, UserCommandJsonProxy);
+
+/// Mina v2 transaction status (`Mina_base.Transaction_status.Stable.V2`)
+#[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq)]
+pub enum TransactionStatus {
+    Applied,
+    /// Failures of each account update
+    Failed(Vec<FailureList>),
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq)]
+enum TransactionStatusJsonProxy {
+    Applied,
+    Failed(Vec<FailureListJson>),
+}
+
+#[derive(Clone, Debug, Eq, PartialEq, AutoFrom)]
+#[auto_from(TransactionStatus)]
+#[auto_from(TransactionStatusJsonProxy)]
+pub enum TransactionStatusJson {
+    Applied,
+    Failed(Vec<FailureListJson>),
+}
+
+impl_mina_enum_json_serde_with_option!(TransactionStatusJson, TransactionStatusJsonProxy, false);
+
+/// Coinbase with at most two fee transfers
+#[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq)]
+pub enum CoinbaseAtMostTwo {
+    Zero,
+    One(Option<CoinbaseFeeTransfer>),
+    Two(Option<CoinbaseFeeTransferPair>),
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq)]
+enum CoinbaseAtMostTwoJsonProxy {
+    Zero,
+    One(Option<CoinbaseFeeTransferJson>),
+    Two(Option<CoinbaseFeeTransferPairJson>),
+}
+
+#[derive(Clone, Debug, Eq, PartialEq, AutoFrom)]
+#[auto_from(CoinbaseAtMostTwo)]
+#[auto_from(CoinbaseAtMostTwoJsonProxy)]
+pub enum CoinbaseAtMostTwoJson {
+    Zero,
+    One(Option<CoinbaseFeeTransferJson>),
+    Two(Option<CoinbaseFeeTransferPairJson>),
+}
+
+impl_mina_enum_json_serde_with_option!(CoinbaseAtMostTwoJson, CoinbaseAtMostTwoJsonProxy, false);
+
+/// Coinbase with at most one fee transfer
+#[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq)]
+pub enum CoinbaseAtMostOne {
+    Zero,
+    One(Option<CoinbaseFeeTransfer>),
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq)]
+enum CoinbaseAtMostOneJsonProxy {
+    Zero,
+    One(Option<CoinbaseFeeTransferJson>),
+}
+
+#[derive(Clone, Debug, Eq, PartialEq, AutoFrom)]
+#[auto_from(CoinbaseAtMostOne)]
+#[auto_from(CoinbaseAtMostOneJsonProxy)]
+pub enum CoinbaseAtMostOneJson {
+    Zero,
+    One(Option<CoinbaseFeeTransferJson>),
+}
+
+impl_mina_enum_json_serde_with_option!(CoinbaseAtMostOneJson, CoinbaseAtMostOneJsonProxy, false);
+
+#[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq)]
+pub struct CoinbaseFeeTransfer {
+    pub receiver_pk: CompressedCurvePoint,
+    pub fee: u64,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq, AutoFrom)]
+#[auto_from(CoinbaseFeeTransfer)]
+pub struct CoinbaseFeeTransferJson {
+    pub receiver_pk: PublicKeyJson,
+    pub fee: DecimalJson,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq)]
+pub struct CoinbaseFeeTransferPair(pub CoinbaseFeeTransfer, pub Option<CoinbaseFeeTransfer>);
+
+#[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq)]
+pub struct CoinbaseFeeTransferPairJson(
+    pub CoinbaseFeeTransferJson,
+    pub Option<CoinbaseFeeTransferJson>,
+);
+
+impl From<CoinbaseFeeTransferPair> for CoinbaseFeeTransferPairJson {
+    fn from(t: CoinbaseFeeTransferPair) -> Self {
+        Self(t.0.into(), t.1.map(Into::into))
+    }
+}
+
+impl From<CoinbaseFeeTransferPairJson> for CoinbaseFeeTransferPair {
+    fn from(t: CoinbaseFeeTransferPairJson) -> Self {
+        Self(t.0.into(), t.1.map(Into::into))
+    }
+}
+
+/// Mina v2 snark work (`Transaction_snark_work.Stable.V2`)
+#[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq)]
+pub struct TransactionSnarkWork {
+    pub fee: u64,
+    pub proofs: OneOrTwo,
+    pub prover: CompressedCurvePoint,
+}
+
+/// Mina v2 snark work (json)
+///
+/// The daemon renders ledger proofs as base64 encoded s-expressions, which
+/// can't be read back, so only binary proofs are supported
+#[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq, AutoFrom)]
+#[auto_from(TransactionSnarkWork)]
+pub struct TransactionSnarkWorkJson {
+    pub fee: DecimalJson,
+    pub proofs: OneOrTwoJson,
+    pub prover: PublicKeyJson,
+}
+
+/// One or two ledger proofs
+#[allow(clippy::large_enum_variant)]
+#[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq)]
+#[serde(rename = "Polyvar")]
+pub enum OneOrTwo {
+    One(LedgerProof),
+    Two(LedgerProof, LedgerProof),
+}
+
+#[allow(clippy::large_enum_variant)]
+#[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq)]
+enum OneOrTwoJsonProxy {
+    One(LedgerProofJson),
+    Two(LedgerProofJson, LedgerProofJson),
+}
+
+#[allow(clippy::large_enum_variant)]
+#[derive(Clone, Debug, Eq, PartialEq, AutoFrom)]
+#[auto_from(OneOrTwo)]
+#[auto_from(OneOrTwoJsonProxy)]
+pub enum OneOrTwoJson {
+    One(LedgerProofJson),
+    Two(LedgerProofJson, LedgerProofJson),
+}
+
+impl_mina_enum_json_serde!(OneOrTwoJson, OneOrTwoJsonProxy);
+
+/// Transaction snark (`Ledger_proof.Stable.V2`)
+#[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq)]
+pub struct LedgerProof {
+    pub statement: SnarkedLedgerStateWithSok,
+    pub proof: Box<Proof>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq, AutoFrom)]
+#[auto_from(LedgerProof)]
+pub struct LedgerProofJson {
+    pub statement: SnarkedLedgerStateWithSokJson,
+    pub proof: ProofJson,
+}
@@ -0,0 +1,542 @@
+//! Types related to zkapp commands
+//!
+//! See <https://github.com/MinaProtocol/mina/blob/berkeley/src/lib/mina_base/zkapp_command.ml>
+
+#![allow(missing_docs)] // Fields mirror the mina_base types of the same name
+
+use super::{
+    common::{
+        ClosedInterval, ClosedIntervalJson, DecimalFieldJson, FieldJson, GlobalSlot, MemoJson,
+        OrIgnore, OrIgnoreJson, ReceiptChainHashJson, SetOrKeep, SetOrKeepJson, Signature,
+        TokenIdJson, UInt32, Vector,
+    },
+    pickles::{Proof, ProofJson, VerificationKey, VerificationKeyJson},
+    protocol_state::{Signed, SignedAmountJson},
+};
+use crate::protocol::serialization_types::{
+    common::{
+        BigInt256, ByteVec, DecimalJson, EpochSeedHashV1Json, LedgerHashV1Json, StateHashV1Json,
+        U32Json, U64Json,
+    },
+    field_and_curve_elements::FieldElement,
+    signatures::{CompressedCurvePoint, PublicKeyJson, SignatureJson},
+};
+use mina_serialization_proc_macros::AutoFrom;
+use mina_serialization_versioned::{
+    impl_mina_enum_json_serde, impl_mina_enum_json_serde_with_option,
+};
+use serde::{Deserialize, Serialize};
+
+/// Mina zkapp command (`Mina_base.Zkapp_command.Stable.V1`)
+#[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq)]
+pub struct ZkappCommand {
+    pub fee_payer: FeePayer,
+    pub account_updates: Vec<CallForestElem>,
+    pub memo: ByteVec,
+}
+
+/// Mina zkapp command (json)
+#[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq, AutoFrom)]
+#[auto_from(ZkappCommand)]
+pub struct ZkappCommandJson {
+    pub fee_payer: FeePayerJson,
+    pub account_updates: Vec<CallForestElemJson>,
+    pub memo: MemoJson,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq)]
+pub struct FeePayer {
+    pub body: FeePayerBody,
+    pub authorization: Signature,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq, AutoFrom)]
+#[auto_from(FeePayer)]
+pub struct FeePayerJson {
+    pub body: FeePayerBodyJson,
+    pub authorization: SignatureJson,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq)]
+pub struct FeePayerBody {
+    pub public_key: CompressedCurvePoint,
+    pub fee: u64,
+    pub valid_until: Option<GlobalSlot>,
+    pub nonce: UInt32,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq, AutoFrom)]
+#[auto_from(FeePayerBody)]
+pub struct FeePayerBodyJson {
+    pub public_key: PublicKeyJson,
+    pub fee: DecimalJson,
+    pub valid_until: Option<U32Json>,
+    pub nonce: U32Json,
+}
+
+/// Element of the account update forest
+///
+/// The stack hash is not serialized, it is recomputed when the command is
+/// read
+#[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq)]
+pub struct CallForestElem {
+    pub elt: CallTree,
+    pub stack_hash: (),
+}
+
+/// Element of the account update forest (json)
+///
+/// The daemon includes the recomputed stack hash, which is dropped when
+/// converting to the binary type
+#[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq)]
+pub struct CallForestElemJson {
+    pub elt: CallTreeJson,
+    pub stack_hash: Option<FieldJson>,
+}
+
+impl From<CallForestElem> for CallForestElemJson {
+    fn from(t: CallForestElem) -> Self {
+        Self {
+            elt: t.elt.into(),
+            stack_hash: None,
+        }
+    }
+}
+
+impl From<CallForestElemJson> for CallForestElem {
+    fn from(t: CallForestElemJson) -> Self {
+        Self {
+            elt: t.elt.into(),
+            stack_hash: (),
+        }
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq)]
+pub struct CallTree {
+    pub account_update: AccountUpdate,
+    pub account_update_digest: (),
+    pub calls: Vec<CallForestElem>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq)]
+pub struct CallTreeJson {
+    pub account_update: AccountUpdateJson,
+    pub account_update_digest: Option<FieldJson>,
+    pub calls: Vec<CallForestElemJson>,
+}
+
+impl From<CallTree> for CallTreeJson {
+    fn from(t: CallTree) -> Self {
+        Self {
+            account_update: t.account_update.into(),
+            account_update_digest: None,
+            calls: t.calls.into_iter().map(Into::into).collect(),
+        }
+    }
+}
+
+impl From<CallTreeJson> for CallTree {
+    fn from(t: CallTreeJson) -> Self {
+        Self {
+            account_update: t.account_update.into(),
+            account_update_digest: (),
+            calls: t.calls.into_iter().map(Into::into).collect(),
+        }
+    }
+}
+
+/// Mina account update (`Mina_base.Account_update.Stable.V1`)
+#[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq)]
+pub struct AccountUpdate {
+    pub body: AccountUpdateBody,
+    pub authorization: Control,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq, AutoFrom)]
+#[auto_from(AccountUpdate)]
+pub struct AccountUpdateJson {
+    pub body: AccountUpdateBodyJson,
+    pub authorization: ControlJson,
+}
+
+/// Authorization of an account update
+#[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq)]
+pub enum Control {
+    Proof(Box<Proof>),
+    Signature(Signature),
+    NoneGiven,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq)]
+enum ControlJsonProxy {
+    Proof(ProofJson),
+    Signature(SignatureJson),
+    #[serde(rename = "None_given")]
+    NoneGiven,
+}
+
+/// Authorization of an account update (json)
+///
+/// Proofs are the base64 encoded bin_prot bytes
+#[derive(Clone, Debug, Eq, PartialEq, AutoFrom)]
+#[auto_from(Control)]
+#[auto_from(ControlJsonProxy)]
+pub enum ControlJson {
+    Proof(ProofJson),
+    Signature(SignatureJson),
+    NoneGiven,
+}
+
+impl_mina_enum_json_serde!(ControlJson, ControlJsonProxy);
+
+#[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq)]
+pub struct AccountUpdateBody {
+    pub public_key: CompressedCurvePoint,
+    pub token_id: FieldElement,
+    pub update: Update,
+    pub balance_change: Signed,
+    pub increment_nonce: bool,
+    pub events: Vec<Event>,
+    pub actions: Vec<Event>,
+    pub call_data: FieldElement,
+    pub preconditions: Preconditions,
+    pub use_full_commitment: bool,
+    pub implicit_account_creation_fee: bool,
+    pub may_use_token: MayUseToken,
+    pub authorization_kind: AuthorizationKind,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq, AutoFrom)]
+#[auto_from(AccountUpdateBody)]
+pub struct AccountUpdateBodyJson {
+    pub public_key: PublicKeyJson,
+    pub token_id: TokenIdJson,
+    pub update: UpdateJson,
+    pub balance_change: SignedAmountJson,
+    pub increment_nonce: bool,
+    pub events: Vec<EventJson>,
+    pub actions: Vec<EventJson>,
+    pub call_data: FieldJson,
+    pub preconditions: PreconditionsJson,
+    pub use_full_commitment: bool,
+    pub implicit_account_creation_fee: bool,
+    pub may_use_token: MayUseTokenJson,
+    pub authorization_kind: AuthorizationKindJson,
+}
+
+/// Zkapp event or action, a list of fields
+#[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq)]
+pub struct Event(pub Vec<FieldElement>);
+
+/// Zkapp event or action (json)
+#[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq)]
+pub struct EventJson(pub Vec<FieldJson>);
+
+impl From<Event> for EventJson {
+    fn from(t: Event) -> Self {
+        Self(t.0.into_iter().map(Into::into).collect())
+    }
+}
+
+impl From<EventJson> for Event {
+    fn from(t: EventJson) -> Self {
+        Self(t.0.into_iter().map(Into::into).collect())
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq)]
+pub enum MayUseToken {
+    No,
+    ParentsOwnToken,
+    InheritFromParent,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq)]
+enum MayUseTokenJsonProxy {
+    No,
+    #[serde(rename = "Parents_own_token")]
+    ParentsOwnToken,
+    #[serde(rename = "Inherit_from_parent")]
+    InheritFromParent,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq, AutoFrom)]
+#[auto_from(MayUseToken)]
+#[auto_from(MayUseTokenJsonProxy)]
+pub enum MayUseTokenJson {
+    No,
+    ParentsOwnToken,
+    InheritFromParent,
+}
+
+impl_mina_enum_json_serde!(MayUseTokenJson, MayUseTokenJsonProxy);
+
+#[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq)]
+pub enum AuthorizationKind {
+    Signature,
+    /// Hash of the verification key
+    Proof(FieldElement),
+    NoneGiven,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq)]
+enum AuthorizationKindJsonProxy {
+    Signature,
+    Proof(DecimalFieldJson),
+    #[serde(rename = "None_given")]
+    NoneGiven,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq, AutoFrom)]
+#[auto_from(AuthorizationKind)]
+#[auto_from(AuthorizationKindJsonProxy)]
+pub enum AuthorizationKindJson {
+    Signature,
+    Proof(DecimalFieldJson),
+    NoneGiven,
+}
+
+impl_mina_enum_json_serde!(AuthorizationKindJson, AuthorizationKindJsonProxy);
+
+/// Account fields an update sets
+#[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq)]
+pub struct Update {
+    pub app_state: Vector<SetOrKeep<FieldElement>, 8>,
+    pub delegate: SetOrKeep<CompressedCurvePoint>,
+    pub verification_key: SetOrKeep<VerificationKeyWire>,
+    pub permissions: SetOrKeep<Permissions>,
+    pub zkapp_uri: SetOrKeep<String>,
+    pub token_symbol: SetOrKeep<String>,
+    pub timing: SetOrKeep<Timing>,
+    pub voting_for: SetOrKeep<BigInt256>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq, AutoFrom)]
+#[auto_from(Update)]
+pub struct UpdateJson {
+    pub app_state: Vec<SetOrKeepJson<FieldJson>>,
+    pub delegate: SetOrKeepJson<PublicKeyJson>,
+    pub verification_key: SetOrKeepJson<VerificationKeyWireJson>,
+    pub permissions: SetOrKeepJson<PermissionsJson>,
+    pub zkapp_uri: SetOrKeepJson<String>,
+    pub token_symbol: SetOrKeepJson<String>,
+    pub timing: SetOrKeepJson<TimingJson>,
+    pub voting_for: SetOrKeepJson<StateHashV1Json>,
+}
+
+/// Verification key with its hash
+#[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq)]
+pub struct VerificationKeyWire {
+    pub data: VerificationKey,
+    pub hash: FieldElement,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq, AutoFrom)]
+#[auto_from(VerificationKeyWire)]
+pub struct VerificationKeyWireJson {
+    pub data: VerificationKeyJson,
+    pub hash: FieldJson,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq)]
+pub struct Timing {
+    pub initial_minimum_balance: u64,
+    pub cliff_time: GlobalSlot,
+    pub cliff_amount: u64,
+    pub vesting_period: GlobalSlot,
+    pub vesting_increment: u64,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq, AutoFrom)]
+#[auto_from(Timing)]
+pub struct TimingJson {
+    pub initial_minimum_balance: U64Json,
+    pub cliff_time: U32Json,
+    pub cliff_amount: U64Json,
+    pub vesting_period: U32Json,
+    pub vesting_increment: U64Json,
+}
+
+/// Account permissions (`Mina_base.Permissions.Stable.V2`)
+#[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq)]
+pub struct Permissions {
+    pub edit_state: AuthRequired,
+    pub access: AuthRequired,
+    pub send: AuthRequired,
+    pub receive: AuthRequired,
+    pub set_delegate: AuthRequired,
+    pub set_permissions: AuthRequired,
+    pub set_verification_key: SetVerificationKeyPermission,
+    pub set_zkapp_uri: AuthRequired,
+    pub edit_action_state: AuthRequired,
+    pub set_token_symbol: AuthRequired,
+    pub increment_nonce: AuthRequired,
+    pub set_voting_for: AuthRequired,
+    pub set_timing: AuthRequired,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq, AutoFrom)]
+#[auto_from(Permissions)]
+pub struct PermissionsJson {
+    pub edit_state: AuthRequiredJson,
+    pub access: AuthRequiredJson,
+    pub send: AuthRequiredJson,
+    pub receive: AuthRequiredJson,
+    pub set_delegate: AuthRequiredJson,
+    pub set_permissions: AuthRequiredJson,
+    pub set_verification_key: SetVerificationKeyPermissionJson,
+    pub set_zkapp_uri: AuthRequiredJson,
+    pub edit_action_state: AuthRequiredJson,
+    pub set_token_symbol: AuthRequiredJson,
+    pub increment_nonce: AuthRequiredJson,
+    pub set_voting_for: AuthRequiredJson,
+    pub set_timing: AuthRequiredJson,
+}
+
+/// Verification key permission & the transaction version it was set in
+#[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq)]
+pub struct SetVerificationKeyPermission(pub AuthRequired, pub UInt32);
+
+#[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq)]
+pub struct SetVerificationKeyPermissionJson(pub AuthRequiredJson, pub U32Json);
+
+impl From<SetVerificationKeyPermission> for SetVerificationKeyPermissionJson {
+    fn from(t: SetVerificationKeyPermission) -> Self {
+        Self(t.0.into(), t.1.into())
+    }
+}
+
+impl From<SetVerificationKeyPermissionJson> for SetVerificationKeyPermission {
+    fn from(t: SetVerificationKeyPermissionJson) -> Self {
+        Self(t.0.into(), t.1.into())
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq)]
+pub enum AuthRequired {
+    None,
+    Either,
+    Proof,
+    Signature,
+    Impossible,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq)]
+enum AuthRequiredJsonProxy {
+    None,
+    Either,
+    Proof,
+    Signature,
+    Impossible,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq, AutoFrom)]
+#[auto_from(AuthRequired)]
+#[auto_from(AuthRequiredJsonProxy)]
+pub enum AuthRequiredJson {
+    None,
+    Either,
+    Proof,
+    Signature,
+    Impossible,
+}
+
+impl_mina_enum_json_serde!(AuthRequiredJson, AuthRequiredJsonProxy);
+
+#[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq)]
+pub struct Preconditions {
+    pub network: NetworkPreconditions,
+    pub account: AccountPreconditions,
+    pub valid_while: OrIgnore<ClosedInterval<GlobalSlot>>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq, AutoFrom)]
+#[auto_from(Preconditions)]
+pub struct PreconditionsJson {
+    pub network: NetworkPreconditionsJson,
+    pub account: AccountPreconditionsJson,
+    pub valid_while: OrIgnoreJson<ClosedIntervalJson<U32Json>>,
+}
+
+/// Protocol state precondition (`Mina_base.Zkapp_precondition.Protocol_state`)
+#[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq)]
+pub struct NetworkPreconditions {
+    pub snarked_ledger_hash: OrIgnore<BigInt256>,
+    pub blockchain_length: OrIgnore<ClosedInterval<UInt32>>,
+    pub min_window_density: OrIgnore<ClosedInterval<UInt32>>,
+    pub total_currency: OrIgnore<ClosedInterval<u64>>,
+    pub global_slot_since_genesis: OrIgnore<ClosedInterval<GlobalSlot>>,
+    pub staking_epoch_data: EpochDataPreconditions,
+    pub next_epoch_data: EpochDataPreconditions,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq, AutoFrom)]
+#[auto_from(NetworkPreconditions)]
+pub struct NetworkPreconditionsJson {
+    pub snarked_ledger_hash: OrIgnoreJson<LedgerHashV1Json>,
+    pub blockchain_length: OrIgnoreJson<ClosedIntervalJson<U32Json>>,
+    pub min_window_density: OrIgnoreJson<ClosedIntervalJson<U32Json>>,
+    pub total_currency: OrIgnoreJson<ClosedIntervalJson<U64Json>>,
+    pub global_slot_since_genesis: OrIgnoreJson<ClosedIntervalJson<U32Json>>,
+    pub staking_epoch_data: EpochDataPreconditionsJson,
+    pub next_epoch_data: EpochDataPreconditionsJson,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq)]
+pub struct EpochDataPreconditions {
+    pub ledger: EpochLedgerPreconditions,
+    pub seed: OrIgnore<BigInt256>,
+    pub start_checkpoint: OrIgnore<BigInt256>,
+    pub lock_checkpoint: OrIgnore<BigInt256>,
+    pub epoch_length: OrIgnore<ClosedInterval<UInt32>>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq, AutoFrom)]
+#[auto_from(EpochDataPreconditions)]
+pub struct EpochDataPreconditionsJson {
+    pub ledger: EpochLedgerPreconditionsJson,
+    pub seed: OrIgnoreJson<EpochSeedHashV1Json>,
+    pub start_checkpoint: OrIgnoreJson<StateHashV1Json>,
+    pub lock_checkpoint: OrIgnoreJson<StateHashV1Json>,
+    pub epoch_length: OrIgnoreJson<ClosedIntervalJson<U32Json>>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq)]
+pub struct EpochLedgerPreconditions {
+    pub hash: OrIgnore<BigInt256>,
+    pub total_currency: OrIgnore<ClosedInterval<u64>>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq, AutoFrom)]
+#[auto_from(EpochLedgerPreconditions)]
+pub struct EpochLedgerPreconditionsJson {
+    pub hash: OrIgnoreJson<LedgerHashV1Json>,
+    pub total_currency: OrIgnoreJson<ClosedIntervalJson<U64Json>>,
+}
+
+/// Account precondition (`Mina_base.Zkapp_precondition.Account`)
+#[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq)]
+pub struct AccountPreconditions {
+    pub balance: OrIgnore<ClosedInterval<u64>>,
+    pub nonce: OrIgnore<ClosedInterval<UInt32>>,
+    pub receipt_chain_hash: OrIgnore<BigInt256>,
+    pub delegate: OrIgnore<CompressedCurvePoint>,
+    pub state: Vector<OrIgnore<FieldElement>, 8>,
+    pub action_state: OrIgnore<FieldElement>,
+    pub proved_state: OrIgnore<bool>,
+    pub is_new: OrIgnore<bool>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq, AutoFrom)]
+#[auto_from(AccountPreconditions)]
+pub struct AccountPreconditionsJson {
+    pub balance: OrIgnoreJson<ClosedIntervalJson<U64Json>>,
+    pub nonce: OrIgnoreJson<ClosedIntervalJson<U32Json>>,
+    pub receipt_chain_hash: OrIgnoreJson<ReceiptChainHashJson>,
+    pub delegate: OrIgnoreJson<PublicKeyJson>,
+    pub state: Vec<OrIgnoreJson<FieldJson>>,
+    pub action_state: OrIgnoreJson<FieldJson>,
+    pub proved_state: OrIgnoreJson<bool>,
+    pub is_new: OrIgnoreJson<bool>,
+}
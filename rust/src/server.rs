@@ -2,8 +2,17 @@
 
 use crate::{
     base::state_hash::StateHash,
-    block::{self, parser::BlockParser, precomputed::PcbVersion, vrf_output::VrfOutput},
-    chain::{ChainId, Network},
+    block::{
+        self,
+        parser::BlockParser,
+        precomputed::{PcbVersion, PrecomputedBlock},
+        source::{BlockSource, NdjsonTail},
+        vrf_output::VrfOutput,
+    },
+    chain::{
+        profile::{NetworkProfile, ProtocolParams},
+        ChainData, ChainId, Network,
+    },
     cli::server::ServerArgsJson,
     constants::*,
     ledger::{
@@ -11,16 +20,20 @@ use crate::{
         staking::{self, StakingLedger},
         store::staking::StakingLedgerStore,
     },
-    state::{IndexerState, IndexerStateConfig},
+    state::{
+        upload::{BlockUploadStatus, StakingLedgerUploadStatus},
+        IndexerState, IndexerStateConfig,
+    },
     store::{fixed_keys::FixedKeys, IndexerStore},
     unix_socket_server::{create_socket_listener, handle_connection},
 };
+use anyhow::Context;
 use log::{debug, error, info, trace, warn};
 use notify::{Config, Event, EventKind, RecommendedWatcher, RecursiveMode, Watcher};
 use serde::{Deserialize, Serialize};
 use speedb::checkpoint::Checkpoint;
 use std::{
-    collections::HashSet,
+    collections::{HashMap, HashSet},
     fs,
     os::unix::fs::MetadataExt,
     path::{Path, PathBuf},
@@ -30,25 +43,32 @@ use std::{
 };
 use tokio::{
     runtime::Handle,
-    sync::{mpsc, RwLock},
+    sync::{mpsc, oneshot, RwLock},
 };
 use tokio_graceful_shutdown::{SubsystemBuilder, SubsystemHandle};
 
-#[derive(Clone, Debug, Deserialize, Serialize)]
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
 pub struct IndexerVersion {
     pub network: Network,
     pub version: PcbVersion,
     pub chain_id: ChainId,
     pub genesis: GenesisVersion,
+
+    #[serde(default)]
+    pub protocol: ProtocolParams,
 }
 
-#[derive(Clone, Debug, Deserialize, Serialize)]
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
 pub struct GenesisVersion {
     pub state_hash: StateHash,
     pub prev_hash: StateHash,
     pub blockchain_lenth: u32,
     pub global_slot: u32,
     pub last_vrf_output: VrfOutput,
+
+    /// Precomputed genesis block of a custom network
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub block: Option<PathBuf>,
 }
 
 #[derive(Clone, Debug, Deserialize, Serialize)]
@@ -57,6 +77,8 @@ pub struct IndexerConfiguration {
     pub version: IndexerVersion,
     pub blocks_dir: Option<PathBuf>,
     pub staking_ledgers_dir: Option<PathBuf>,
+    #[serde(default)]
+    pub block_sources: Vec<PathBuf>,
     pub prune_interval: u32,
     pub canonical_threshold: u32,
     pub canonical_update_threshold: u32,
@@ -72,6 +94,37 @@ pub struct IndexerConfiguration {
     pub missing_block_recovery_batch: bool,
 }
 
+/// Indexer states of the networks followed in the same database
+#[derive(Clone)]
+pub struct NetworkStates {
+    primary: Arc<RwLock<IndexerState>>,
+    networks: HashMap<Network, Arc<RwLock<IndexerState>>>,
+}
+
+/// Block or staking ledger uploaded to the web server
+#[derive(Debug)]
+pub enum Upload {
+    Block {
+        block: PrecomputedBlock,
+        block_bytes: u64,
+    },
+    StakingLedger(StakingLedger),
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(untagged)]
+pub enum UploadStatus {
+    Block(BlockUploadStatus),
+    StakingLedger(StakingLedgerUploadStatus),
+}
+
+/// Upload & the channel to reply on once the indexer has processed it
+#[derive(Debug)]
+pub struct UploadRequest {
+    pub upload: Upload,
+    pub reply: oneshot::Sender<anyhow::Result<UploadStatus>>,
+}
+
 #[derive(Debug, Clone, Deserialize, Serialize)]
 pub enum InitializationMode {
     BuildDB,
@@ -119,6 +172,7 @@ impl IndexerConfiguration {
             genesis_ledger,
             blocks_dir,
             staking_ledgers_dir,
+            block_sources,
             prune_interval,
             canonical_threshold,
             canonical_update_threshold,
@@ -132,9 +186,10 @@ impl IndexerConfiguration {
             self
         } else {
             debug!("Persisting mina indexer config");
-            store
-                .database
-                .put(IndexerStore::INDEXER_CONFIG_KEY, serde_json::to_vec(&self)?)?;
+            store.database.put(
+                store.fixed_key(IndexerStore::INDEXER_CONFIG_KEY),
+                serde_json::to_vec(&self)?,
+            )?;
 
             self
         };
@@ -157,31 +212,13 @@ impl IndexerConfiguration {
             }
         }
 
-        // let chain_id = chain_id(
-        //     &version.genesis.state_hash.0,
-        //     &[
-        //         genesis_constants.k.unwrap(),
-        //         genesis_constants.slots_per_epoch.unwrap(),
-        //         genesis_constants.slots_per_sub_window.unwrap(),
-        //         genesis_constants.delta.unwrap(),
-        //         genesis_constants.txpool_max_size.unwrap(),
-        //     ],
-        //     constraint_system_digests
-        //         .iter()
-        //         .map(|x| x.as_str())
-        //         .collect::<Vec<&str>>()
-        //         .as_slice(),
-        //     MAINNET_GENESIS_TIMESTAMP as i64,
-        //     protocol_txn_version_digest.as_ref().map(|d| d as &str),
-        //     protocol_network_version_digest.as_ref().map(|d| d as &str),
-        // );
-
         let pcb_version = version.version.to_owned();
+        let chain_data = version.chain_data();
         let state_config = IndexerStateConfig {
             indexer_store: store.clone(),
             version: version.clone(),
             genesis_ledger: genesis_ledger.clone(),
-            transition_frontier_length: MAINNET_TRANSITION_FRONTIER_K,
+            transition_frontier_length: version.protocol.k,
             do_not_ingest_orphan_blocks,
             prune_interval,
             canonical_threshold,
@@ -227,7 +264,8 @@ impl IndexerConfiguration {
                         reporting_freq,
                     )
                     .await
-                    .unwrap_or_else(|e| panic!("Obtaining block parser failed: {e}"));
+                    .unwrap_or_else(|e| panic!("Obtaining block parser failed: {e}"))
+                    .with_chain_data(chain_data);
                     state
                         .initialize_with_canonical_chain_discovery(&mut block_parser)
                         .await?;
@@ -237,9 +275,9 @@ impl IndexerConfiguration {
                 if let Ok(ref replay_state) =
                     IndexerState::new_without_genesis_events(IndexerStateConfig {
                         indexer_store: store.clone(),
+                        transition_frontier_length: version.protocol.k,
                         version,
                         genesis_ledger,
-                        transition_frontier_length: MAINNET_TRANSITION_FRONTIER_K,
                         prune_interval,
                         canonical_threshold,
                         canonical_update_threshold,
@@ -254,7 +292,8 @@ impl IndexerConfiguration {
                             blocks_dir,
                             pcb_version,
                             min_length_filter,
-                        )?;
+                        )?
+                        .with_chain_data(chain_data);
 
                         if block_parser.total_num_blocks > 0 {
                             info!("Adding new blocks from {blocks_dir:#?}");
@@ -270,7 +309,8 @@ impl IndexerConfiguration {
                         blocks_dir,
                         pcb_version,
                         min_length_filter,
-                    )?;
+                    )?
+                    .with_chain_data(chain_data);
 
                     if block_parser.total_num_blocks > 0 {
                         info!("Adding new blocks from {blocks_dir:#?}");
@@ -280,6 +320,14 @@ impl IndexerConfiguration {
             }
         }
 
+        // ingest additional block sources
+        for path in block_sources.iter() {
+            match BlockSource::from_path(path).and_then(|source| state.add_block_source(&source)) {
+                Ok(num_added) => info!("Added {num_added} blocks from {path:#?}"),
+                Err(e) => error!("Failed to ingest block source {path:#?}: {e}"),
+            }
+        }
+
         // flush/compress database
         let store = state.indexer_store.as_ref().unwrap();
         let temp_checkpoint_dir = store.db_path.join("tmp-checkpoint");
@@ -290,49 +338,75 @@ impl IndexerConfiguration {
         Ok(state)
     }
 
-    /// Initializes witness tree, connects database, starts UDS server & runs
-    /// the indexer
+    /// Initializes witness trees, connects database, starts UDS server & runs
+    /// the indexer for the primary & each additional network
     pub async fn start_indexer(
         self,
         subsys: SubsystemHandle,
         store: Arc<IndexerStore>,
+        networks: Vec<(Arc<IndexerStore>, IndexerConfiguration)>,
+        uploads: mpsc::Receiver<UploadRequest>,
     ) -> anyhow::Result<()> {
-        let blocks_dir = self.blocks_dir.clone();
-        let staking_ledgers_dir = self.staking_ledgers_dir.clone();
-        let fetch_new_blocks_delay = self.fetch_new_blocks_delay;
-        let fetch_new_blocks_exe = self.fetch_new_blocks_exe.clone();
-        let missing_block_recovery_delay = self.missing_block_recovery_delay;
-        let missing_block_recovery_exe = self.missing_block_recovery_exe.clone();
-        let missing_block_recovery_batch = self.missing_block_recovery_batch;
+        let network = self.version.network.clone();
         let domain_socket_path = self.domain_socket_path.clone();
+        let missing_block_recovery =
+            self.missing_block_recovery_exe
+                .clone()
+                .map(|exe| MissingBlockRecoveryOptions {
+                    exe,
+                    batch: self.missing_block_recovery_batch,
+                    delay: self.missing_block_recovery_delay.unwrap_or(180),
+                });
+        let fetch_new_blocks = self
+            .fetch_new_blocks_exe
+            .clone()
+            .map(|exe| FetchNewBlocksOptions {
+                exe,
+                delay: self.fetch_new_blocks_delay.unwrap_or(180),
+            });
 
         // initialize witness tree & connect database
-        let state = Arc::new(RwLock::new(
-            self.initialize(&store, true).await.unwrap_or_else(|e| {
-                error!("Failed to initialize mina indexer state: {e}");
-                std::process::exit(1);
-            }),
-        ));
+        let (state, indexer) = self.initialize_network(&store).await?;
+        let mut states = NetworkStates::new(network, state.clone());
+
+        // additional networks only watch their own directories & block logs
+        for (store, config) in networks {
+            let network = config.version.network.clone();
+            info!("Following additional network {network}");
+
+            let (state, indexer) = config.initialize_network(&store).await?;
+            states.insert(network.clone(), state.clone());
+
+            subsys.start(SubsystemBuilder::new(
+                format!("Indexer ({network})"),
+                move |s| async move {
+                    // uploads go to the primary network
+                    let (_, uploads) = mpsc::channel(1);
+                    run_indexer(
+                        &s,
+                        indexer.blocks_dir,
+                        indexer.staking_ledgers_dir,
+                        indexer.block_logs,
+                        uploads,
+                        None,
+                        None,
+                        state,
+                    )
+                    .await
+                },
+            ));
+        }
 
         // read-only state
-        start_uds_server(&subsys, state.clone(), &domain_socket_path).await?;
+        start_uds_server(&subsys, states, &domain_socket_path).await?;
 
         // modifies the state
-        let missing_block_recovery =
-            missing_block_recovery_exe.map(|exe| MissingBlockRecoveryOptions {
-                exe,
-                batch: missing_block_recovery_batch,
-                delay: missing_block_recovery_delay.unwrap_or(180),
-            });
-        let fetch_new_blocks = fetch_new_blocks_exe.map(|exe| FetchNewBlocksOptions {
-            exe,
-            delay: fetch_new_blocks_delay.unwrap_or(180),
-        });
-
         run_indexer(
             &subsys,
-            blocks_dir,
-            staking_ledgers_dir,
+            indexer.blocks_dir,
+            indexer.staking_ledgers_dir,
+            indexer.block_logs,
+            uploads,
             missing_block_recovery,
             fetch_new_blocks,
             state.clone(),
@@ -342,9 +416,49 @@ impl IndexerConfiguration {
         Ok(())
     }
 
+    /// Initializes the network's witness tree & collects what its indexer
+    /// watches
+    async fn initialize_network(
+        self,
+        store: &Arc<IndexerStore>,
+    ) -> anyhow::Result<(Arc<RwLock<IndexerState>>, NetworkIndexer)> {
+        let network = self.version.network.clone();
+        let blocks_dir = self.blocks_dir.clone();
+        let staking_ledgers_dir = self.staking_ledgers_dir.clone();
+        let block_sources = self.block_sources.clone();
+        let k = self.version.protocol.k;
+
+        let state = Arc::new(RwLock::new(
+            self.initialize(store, true).await.unwrap_or_else(|e| {
+                error!("Failed to initialize mina indexer state ({network}): {e}");
+                std::process::exit(1);
+            }),
+        ));
+        store.set_witness_tree_source(&state);
+
+        // NDJSON block logs were read during initialization
+        let block_logs = block_sources
+            .iter()
+            .filter(|path| matches!(BlockSource::from_path(path), Ok(BlockSource::Ndjson(_))))
+            .map(|path| NdjsonTail::new(path, k))
+            .collect::<anyhow::Result<Vec<_>>>()?;
+
+        Ok((
+            state,
+            NetworkIndexer {
+                blocks_dir,
+                staking_ledgers_dir,
+                block_logs,
+            },
+        ))
+    }
+
     /// Read the indexer config from the given store or panic
     pub fn read_indexer_config(store: &Arc<IndexerStore>) -> anyhow::Result<Self> {
-        if let Some(config_bytes) = store.database.get(IndexerStore::INDEXER_CONFIG_KEY)? {
+        if let Some(config_bytes) = store
+            .database
+            .get(store.fixed_key(IndexerStore::INDEXER_CONFIG_KEY))?
+        {
             debug!("Reading mina indexer config from store");
             Ok(serde_json::from_slice(&config_bytes)?)
         } else {
@@ -353,16 +467,16 @@ impl IndexerConfiguration {
     }
 }
 
-/// Starts UDS server with read-only state for summary
+/// Starts UDS server with the read-only network states
 async fn start_uds_server(
     subsys: &SubsystemHandle,
-    state: Arc<RwLock<IndexerState>>,
+    states: NetworkStates,
     domain_socket_path: &Path,
 ) -> anyhow::Result<()> {
     let listener = create_socket_listener(domain_socket_path);
 
     subsys.start(SubsystemBuilder::new("Socket Listener", {
-        move |subsys| handle_connection(listener, state, subsys)
+        move |subsys| handle_connection(listener, states, subsys)
     }));
 
     Ok(())
@@ -408,11 +522,21 @@ struct FetchNewBlocksOptions {
     pub exe: PathBuf,
 }
 
+/// Directories & block logs a network's indexer watches
+struct NetworkIndexer {
+    blocks_dir: Option<PathBuf>,
+    staking_ledgers_dir: Option<PathBuf>,
+    block_logs: Vec<NdjsonTail>,
+}
+
 /// Starts filesystem watchers & runs the mina indexer
+#[allow(clippy::too_many_arguments)]
 async fn run_indexer<P: AsRef<Path>>(
     subsys: &SubsystemHandle,
     blocks_dir: Option<P>,
     staking_ledgers_dir: Option<P>,
+    mut block_logs: Vec<NdjsonTail>,
+    mut uploads: mpsc::Receiver<UploadRequest>,
     missing_block_recovery: Option<MissingBlockRecoveryOptions>,
     fetch_new_blocks_opts: Option<FetchNewBlocksOptions>,
     state: Arc<RwLock<IndexerState>>,
@@ -449,6 +573,10 @@ async fn run_indexer<P: AsRef<Path>>(
         );
     }
 
+    for block_log in block_logs.iter() {
+        info!("Tailing precomputed block log: {:#?}", block_log.path);
+    }
+
     let fetch_new_blocks_delay = fetch_new_blocks_opts.as_ref().map(|f| f.delay);
     let fetch_new_blocks_exe = fetch_new_blocks_opts.as_ref().map(|f| f.exe.clone());
     let missing_block_recovery_delay = missing_block_recovery.as_ref().map(|m| m.delay);
@@ -472,6 +600,16 @@ async fn run_indexer<P: AsRef<Path>>(
                 }
             }
 
+            // pipeline uploaded blocks & staking ledgers
+            Some(request) = uploads.recv() => {
+                process_upload(request, &state).await
+            }
+
+            // tail NDJSON block logs
+            _ = tokio::time::sleep(std::time::Duration::from_secs(BLOCK_LOG_POLL_SECS)), if !block_logs.is_empty() => {
+                tail_block_logs(&state, &mut block_logs).await
+            }
+
             // fetch new blocks
             _ = tokio::time::sleep(std::time::Duration::from_secs(fetch_new_blocks_delay.unwrap_or(180))) => {
                 if let Some(ref blocks_dir) = blocks_dir {
@@ -555,7 +693,7 @@ async fn process_event(event: Event, state: &Arc<RwLock<IndexerState>>) -> anyho
                         match state.block_pipeline(&block, path.metadata()?.len()) {
                             Ok(is_added) => {
                                 if is_added {
-                                    info!("Added block {}", block.summary())
+                                    info!("Added block {}", block.summary());
                                 }
                             }
                             Err(e) => error!("Error adding block: {e}"),
@@ -602,6 +740,46 @@ async fn process_event(event: Event, state: &Arc<RwLock<IndexerState>>) -> anyho
     Ok(())
 }
 
+/// Uploaded block & staking ledger handler
+async fn process_upload(request: UploadRequest, state: &Arc<RwLock<IndexerState>>) {
+    let mut state = state.write().await;
+    let status = match request.upload {
+        Upload::Block { block, block_bytes } => state
+            .add_uploaded_block(&block, block_bytes)
+            .map(UploadStatus::Block),
+        Upload::StakingLedger(staking_ledger) => state
+            .add_uploaded_staking_ledger(staking_ledger)
+            .map(UploadStatus::StakingLedger),
+    };
+
+    if let Err(ref e) = status {
+        error!("Error adding upload: {e}");
+    }
+    if request.reply.send(status).is_err() {
+        warn!("Upload client disconnected before the reply");
+    }
+}
+
+/// Pipelines the blocks appended to the NDJSON block logs
+async fn tail_block_logs(state: &Arc<RwLock<IndexerState>>, block_logs: &mut [NdjsonTail]) {
+    for block_log in block_logs.iter_mut() {
+        match block_log.poll() {
+            Ok(blocks) if blocks.is_empty() => (),
+            Ok(blocks) => {
+                let mut state = state.write().await;
+                match state.add_source_blocks(blocks) {
+                    Ok(0) => (),
+                    Ok(num_added) => {
+                        info!("Added {num_added} blocks from {:#?}", block_log.path);
+                    }
+                    Err(e) => error!("Error adding blocks from {:#?}: {e}", block_log.path),
+                }
+            }
+            Err(e) => error!("Error reading block log {:#?}: {e}", block_log.path),
+        }
+    }
+}
+
 /// Fetch new blocks
 async fn fetch_new_blocks(
     state: &Arc<RwLock<IndexerState>>,
@@ -692,6 +870,30 @@ async fn recover_missing_blocks(
     }
 }
 
+impl NetworkStates {
+    pub fn new(network: Network, primary: Arc<RwLock<IndexerState>>) -> Self {
+        let networks = HashMap::from([(network, primary.clone())]);
+        Self { primary, networks }
+    }
+
+    pub fn insert(&mut self, network: Network, state: Arc<RwLock<IndexerState>>) {
+        self.networks.insert(network, state);
+    }
+
+    /// State of the network, the primary network's by default
+    ///
+    /// Error if the network isn't followed
+    pub fn get(&self, network: Option<&Network>) -> anyhow::Result<&Arc<RwLock<IndexerState>>> {
+        match network {
+            None => Ok(&self.primary),
+            Some(network) => self
+                .networks
+                .get(network)
+                .with_context(|| format!("Network {network} is not indexed")),
+        }
+    }
+}
+
 impl GenesisVersion {
     pub fn v1() -> Self {
         use std::str::FromStr;
@@ -704,6 +906,7 @@ impl GenesisVersion {
             last_vrf_output,
             blockchain_lenth: 1,
             global_slot: 0,
+            block: None,
         }
     }
 
@@ -718,6 +921,7 @@ impl GenesisVersion {
             prev_hash: HARDFORK_GENESIS_PREV_STATE_HASH.into(),
             blockchain_lenth: HARDFORK_GENESIS_BLOCKCHAIN_LENGTH,
             global_slot: HARDFORK_GENESIS_GLOBAL_SLOT,
+            block: None,
         }
     }
 }
@@ -729,6 +933,7 @@ impl IndexerVersion {
             version: PcbVersion::V1,
             chain_id: ChainId::v1(),
             genesis: GenesisVersion::v1(),
+            protocol: ProtocolParams::default(),
         }
     }
 
@@ -738,21 +943,67 @@ impl IndexerVersion {
             version: PcbVersion::V2,
             chain_id: ChainId::v2(),
             genesis: GenesisVersion::v2(),
+            protocol: NetworkProfile::v2()
+                .protocol_params()
+                .expect("v2 protocol params"),
         }
     }
+
+    /// Indexer version of the network defined by the profile, the genesis
+    /// block defaults to the mainnet one for its version
+    pub fn from_profile(profile: &NetworkProfile) -> anyhow::Result<Self> {
+        let (blockchain_lenth, global_slot) = profile.genesis_height_and_slot();
+        let genesis = GenesisVersion {
+            state_hash: profile.genesis_state_hash()?,
+            prev_hash: profile.genesis_prev_state_hash()?,
+            blockchain_lenth,
+            global_slot,
+            last_vrf_output: profile.genesis_last_vrf_output()?,
+            block: profile.indexer.genesis_block.clone(),
+        };
+
+        Ok(Self {
+            network: profile.network().unwrap_or_default(),
+            version: profile.version(),
+            chain_id: profile.chain_id()?,
+            genesis,
+            protocol: profile.protocol_params()?,
+        })
+    }
+
+    /// Known chains & the indexed one
+    pub fn chain_data(&self) -> ChainData {
+        let mut chain_data = ChainData::default();
+        chain_data.0.insert(
+            self.genesis.state_hash.clone(),
+            (self.version.clone(), self.chain_id.clone()),
+        );
+        chain_data
+    }
 }
 
 impl From<(ServerArgsJson, PathBuf)> for IndexerConfiguration {
     fn from(value: (ServerArgsJson, PathBuf)) -> Self {
-        let genesis_ledger = if value.0.genesis_hash == HARDFORK_GENESIS_HASH {
-            GenesisLedger::new_v2().expect("v2 genesis ledger")
-        } else {
-            GenesisLedger::new_v1().expect("v1 genesis ledger")
-        };
-        let version = if value.0.genesis_hash == HARDFORK_GENESIS_HASH {
-            IndexerVersion::v2()
-        } else {
-            IndexerVersion::v1()
+        let mut profile = NetworkProfile::from_path_or_genesis(
+            value.0.network_profile.as_deref().map(Path::new),
+            &value.0.genesis_hash,
+        )
+        .expect("network profile");
+        profile
+            .indexer
+            .network
+            .get_or_insert_with(|| value.0.network.clone());
+
+        let version = IndexerVersion::from_profile(&profile).expect("indexer version");
+        let genesis_ledger = match profile
+            .genesis_ledger()
+            .expect("network profile genesis ledger")
+        {
+            Some(genesis_ledger) => genesis_ledger,
+            None if version.version == PcbVersion::V2 => {
+                GenesisLedger::new_v2().expect("v2 genesis ledger")
+            }
+            None => GenesisLedger::new_v1().expect("v1 genesis ledger"),
         };
 
         Self {
@@ -761,6 +1012,7 @@ impl From<(ServerArgsJson, PathBuf)> for IndexerConfiguration {
             domain_socket_path: value.1,
             blocks_dir: value.0.blocks_dir.map(Into::into),
             staking_ledgers_dir: value.0.staking_ledgers_dir.map(Into::into),
+            block_sources: value.0.block_sources.into_iter().map(Into::into).collect(),
             prune_interval: value.0.prune_interval,
             canonical_threshold: value.0.canonical_threshold,
             canonical_update_threshold: value.0.canonical_update_threshold,
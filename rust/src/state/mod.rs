@@ -1,5 +1,7 @@
 pub mod branch;
 pub mod summary;
+pub mod upload;
+pub mod witness_tree;
 
 use crate::{
     base::{public_key::PublicKey, state_hash::StateHash},
@@ -7,7 +9,8 @@ use crate::{
         genesis::GenesisBlock,
         genesis_state_hash::GenesisStateHash,
         parser::{BlockParser, ParsedBlock},
-        precomputed::{PcbVersion, PrecomputedBlock},
+        precomputed::PrecomputedBlock,
+        source::{BlockSource, BLOCK_SOURCE_BATCH_SIZE},
         store::BlockStore,
         Block, BlockWithoutHeight,
     },
@@ -22,7 +25,9 @@ use crate::{
             parser::{extract_epoch_hash, StakingLedgerParser},
             StakingLedger,
         },
-        store::{staged::StagedLedgerStore, staking::StakingLedgerStore},
+        store::{
+            distribution::DistributionStore, staged::StagedLedgerStore, staking::StakingLedgerStore,
+        },
         token::TokenAddress,
         username::Username,
         Ledger, LedgerHash,
@@ -34,6 +39,8 @@ use crate::{
             DbStats, SummaryShort, SummaryVerbose, WitnessTreeSummaryShort,
             WitnessTreeSummaryVerbose,
         },
+        upload::{BlockUploadStatus, StakingLedgerUploadStatus},
+        witness_tree::{WitnessBranch, WitnessTree},
     },
     store::{fixed_keys::FixedKeys, username::UsernameStore, IndexerStore},
     utility::{
@@ -44,7 +51,7 @@ use crate::{
         },
     },
 };
-use anyhow::bail;
+use anyhow::{anyhow, bail};
 use id_tree::NodeId;
 use log::{debug, error, info, trace};
 use std::{
@@ -265,7 +272,7 @@ impl IndexerState {
             .0
             .get(&genesis_state_hash)
             .cloned()
-            .expect("PCB version");
+            .ok_or_else(|| anyhow!("Unknown genesis state hash {genesis_state_hash}: {path:?}"))?;
 
         // if the PCB version changed, change state version quantities
         if curr_pcb_version != new_pcb_version {
@@ -284,19 +291,31 @@ impl IndexerState {
 
     /// Creates a new indexer state from the genesis ledger
     pub fn new_from_config(config: IndexerStateConfig) -> anyhow::Result<Self> {
-        // set chain id
+        // set chain id & protocol params
         config
             .indexer_store
             .set_chain_id_for_network(&config.version.chain_id, &config.version.network)?;
-
-        let genesis_block = match config.version.version {
-            PcbVersion::V1 => GenesisBlock::new_v1()?,
-            PcbVersion::V2 => GenesisBlock::new_v2()?,
+        config
+            .indexer_store
+            .set_protocol_params(&config.version.protocol)?;
+
+        let genesis = &config.version.genesis;
+        let genesis_block = match genesis.block.as_ref() {
+            Some(path) => GenesisBlock::parse_file(path, config.version.version.clone())?,
+            None if genesis.state_hash.0 == MAINNET_GENESIS_HASH => GenesisBlock::new_v1()?,
+            None if genesis.state_hash.0 == HARDFORK_GENESIS_HASH => GenesisBlock::new_v2()?,
+            None => bail!(
+                "Network profile for genesis {} is missing indexer.genesis_block",
+                genesis.state_hash
+            ),
         };
         let genesis_bytes = genesis_block.1;
         let genesis_block = genesis_block.0;
 
         // add genesis block and ledger to indexer store
+        config
+            .indexer_store
+            .add_known_genesis(&genesis.state_hash, &genesis.prev_hash)?;
         config
             .indexer_store
             .add_block(&genesis_block, genesis_bytes)?;
@@ -314,7 +333,8 @@ impl IndexerState {
             Some(&genesis_block.previous_state_hash()),
         )?;
 
-        let genesis_ledger: Ledger = config.genesis_ledger.into();
+        let protocol = config.version.protocol;
+        let genesis_ledger = config.genesis_ledger.into_ledger(&protocol);
         config.indexer_store.add_genesis_ledger(
             &genesis_block.previous_state_hash(),
             genesis_ledger.clone(),
@@ -335,15 +355,14 @@ impl IndexerState {
         };
 
         Ok(Self {
-            ledger: genesis_ledger.apply_diff_from_precomputed(&genesis_block)?,
+            ledger: genesis_ledger.apply_diff_from_precomputed(&genesis_block, &protocol)?,
             diffs_map: HashMap::from([(
                 genesis_block.state_hash(),
-                LedgerDiff::from_precomputed(&genesis_block),
+                LedgerDiff::from_precomputed(&genesis_block, &protocol),
             )]),
             canonical_root: tip.clone(),
             best_tip: tip,
             root_branch,
-            version: config.version,
             dangling_branches: Vec::new(),
             indexer_store: Some(config.indexer_store),
             transition_frontier_length: config.transition_frontier_length,
@@ -357,12 +376,21 @@ impl IndexerState {
             ledger_cadence: config.ledger_cadence,
             reporting_freq: config.reporting_freq,
             staking_ledgers: Arc::new(Mutex::new(HashMap::new())),
-            chain_data: ChainData::default(),
+            chain_data: config.version.chain_data(),
+            version: config.version,
         })
     }
 
     /// Creates a new indexer state without genesis events
     pub fn new_without_genesis_events(config: IndexerStateConfig) -> anyhow::Result<Self> {
+        config
+            .indexer_store
+            .set_protocol_params(&config.version.protocol)?;
+        config.indexer_store.add_known_genesis(
+            &config.version.genesis.state_hash,
+            &config.version.genesis.prev_hash,
+        )?;
+
         let root_branch = Branch::new_genesis(
             config.version.genesis.state_hash.to_owned(),
             config.version.genesis.prev_hash.to_owned(),
@@ -376,12 +404,11 @@ impl IndexerState {
         };
 
         Ok(Self {
-            ledger: config.genesis_ledger.into(),
+            ledger: config.genesis_ledger.into_ledger(&config.version.protocol),
             diffs_map: HashMap::new(),
             canonical_root: tip.clone(),
             best_tip: tip,
             root_branch,
-            version: config.version,
             dangling_branches: Vec::new(),
             indexer_store: Some(config.indexer_store),
             transition_frontier_length: config.transition_frontier_length,
@@ -395,7 +422,8 @@ impl IndexerState {
             ledger_cadence: config.ledger_cadence,
             reporting_freq: config.reporting_freq,
             staking_ledgers: Arc::new(Mutex::new(HashMap::new())),
-            chain_data: ChainData::default(),
+            chain_data: config.version.chain_data(),
+            version: config.version,
         })
     }
 
@@ -409,6 +437,7 @@ impl IndexerState {
         ledger_cadence: Option<u32>,
         reporting_freq: Option<u32>,
     ) -> anyhow::Result<Self> {
+        let version = IndexerVersion::default();
         let root_branch = Branch::new_testing(root_block);
         let indexer_store = speedb_path.map(|path| {
             let store = IndexerStore::new(path).unwrap();
@@ -416,7 +445,7 @@ impl IndexerState {
                 store
                     .add_staged_ledger_at_state_hash(
                         &root_block.state_hash(),
-                        ledger,
+                        &ledger,
                         root_block.blockchain_length(),
                     )
                     .expect("ledger add succeeds");
@@ -435,11 +464,14 @@ impl IndexerState {
         // apply root block to root ledger and keep its ledger diff
         Ok(Self {
             ledger: root_ledger
-                .and_then(|x| x.apply_diff_from_precomputed(root_block).ok())
+                .and_then(|x| {
+                    x.apply_diff_from_precomputed(root_block, &version.protocol)
+                        .ok()
+                })
                 .unwrap_or_default(),
             diffs_map: HashMap::from([(
                 root_block.state_hash(),
-                LedgerDiff::from_precomputed(root_block),
+                LedgerDiff::from_precomputed(root_block, &version.protocol),
             )]),
             canonical_root: tip.clone(),
             best_tip: tip,
@@ -458,7 +490,7 @@ impl IndexerState {
             ledger_cadence: ledger_cadence.unwrap_or(LEDGER_CADENCE),
             reporting_freq: reporting_freq.unwrap_or(BLOCK_REPORTING_FREQ_NUM),
             staking_ledgers: Arc::new(Mutex::new(HashMap::new())),
-            version: IndexerVersion::default(),
+            version,
             chain_data: ChainData::default(),
         })
     }
@@ -497,7 +529,7 @@ impl IndexerState {
                     self.bytes_processed += block_bytes;
 
                     // apply diff + add to db
-                    let diff = LedgerDiff::from_precomputed(&block);
+                    let diff = LedgerDiff::from_precomputed(&block, &self.version.protocol);
                     ledger_diffs.push(diff.clone());
 
                     indexer_store.add_block(&block, block_bytes)?;
@@ -519,7 +551,7 @@ impl IndexerState {
                         ledger_diffs.clear();
                         indexer_store.add_staged_ledger_at_state_hash(
                             &state_hash,
-                            self.ledger.clone(),
+                            &self.ledger,
                             block.blockchain_length(),
                         )?;
                     }
@@ -543,6 +575,9 @@ impl IndexerState {
                 self.blocks_processed,
                 block_parser.num_deep_canonical_blocks + 1
             ); // +1 genesis
+
+            // deep canonical best tips skip the per best tip distribution
+            indexer_store.update_best_ledger_distribution()?;
         }
 
         self.report_from_block_count(block_parser, total_time);
@@ -560,6 +595,114 @@ impl IndexerState {
         self.add_blocks_with_time(block_parser, None).await
     }
 
+    /// Adds the source's blocks, in height-sorted batches, via the block
+    /// pipeline. Returns the number of blocks added.
+    pub fn add_block_source(&mut self, source: &BlockSource) -> anyhow::Result<u32> {
+        info!("Adding blocks from {}", source.path().display());
+
+        let mut num_added = 0;
+        let k = self.version.protocol.k;
+        source.read_batches(BLOCK_SOURCE_BATCH_SIZE, k, |blocks| {
+            num_added += self.add_source_blocks(blocks)?;
+            Ok(())
+        })?;
+        Ok(num_added)
+    }
+
+    /// Adds the blocks, skipping those already present, via the block
+    /// pipeline. Returns the number of blocks added.
+    pub fn add_source_blocks(
+        &mut self,
+        blocks: Vec<(PrecomputedBlock, u64)>,
+    ) -> anyhow::Result<u32> {
+        let mut num_added = 0;
+        for (block, block_bytes) in blocks {
+            if self.diffs_map.contains_key(&block.state_hash()) {
+                debug!(
+                    "Block is already present in the witness tree {}",
+                    block.summary()
+                );
+                continue;
+            }
+
+            if self.block_pipeline(&block, block_bytes)? {
+                debug!("Added block {}", block.summary());
+                num_added += 1;
+            }
+        }
+
+        Ok(num_added)
+    }
+
+    /// Pipeline an uploaded block & report its canonicity relative to the
+    /// resulting best tip
+    pub fn add_uploaded_block(
+        &mut self,
+        block: &PrecomputedBlock,
+        block_bytes: u64,
+    ) -> anyhow::Result<BlockUploadStatus> {
+        let state_hash = block.state_hash();
+        let added =
+            !self.diffs_map.contains_key(&state_hash) && self.block_pipeline(block, block_bytes)?;
+
+        if added {
+            info!("Added uploaded block {}", block.summary());
+        }
+
+        let canonicity = self.get_block_status(&state_hash)?;
+        let on_best_chain = self
+            .best_chain()
+            .iter()
+            .any(|block| block.state_hash == state_hash);
+        let best_tip = self.best_tip_block();
+
+        Ok(BlockUploadStatus {
+            blockchain_length: block.blockchain_length(),
+            canonicity: canonicity.unwrap_or(Canonicity::Pending),
+            on_best_chain,
+            best_tip_state_hash: best_tip.state_hash.clone(),
+            best_tip_blockchain_length: best_tip.blockchain_length,
+            state_hash,
+            added,
+        })
+    }
+
+    /// Add an uploaded staking ledger unless the epoch's ledger is already
+    /// present
+    pub fn add_uploaded_staking_ledger(
+        &self,
+        staking_ledger: StakingLedger,
+    ) -> anyhow::Result<StakingLedgerUploadStatus> {
+        let Some(store) = self.indexer_store.as_ref() else {
+            bail!("Indexer store unavailable");
+        };
+
+        let epoch = staking_ledger.epoch;
+        let ledger_hash = staking_ledger.ledger_hash.clone();
+        let added = store
+            .get_staking_ledger_hash_by_epoch(epoch, Some(&staking_ledger.genesis_state_hash))?
+            .as_ref()
+            != Some(&ledger_hash);
+
+        if added {
+            let summary = staking_ledger.summary();
+            let genesis_state_hash = staking_ledger.genesis_state_hash.clone();
+
+            store.add_staking_ledger(staking_ledger, &genesis_state_hash)?;
+            self.staking_ledgers
+                .lock()
+                .unwrap()
+                .insert(epoch, ledger_hash.clone());
+            info!("Added uploaded staking ledger {summary}");
+        }
+
+        Ok(StakingLedgerUploadStatus {
+            epoch,
+            ledger_hash,
+            added,
+        })
+    }
+
     async fn add_blocks_with_time(
         &mut self,
         block_parser: &mut BlockParser,
@@ -691,7 +834,7 @@ impl IndexerState {
         if insert_diff {
             self.diffs_map.insert(
                 precomputed_block.state_hash(),
-                LedgerDiff::from_precomputed(precomputed_block),
+                LedgerDiff::from_precomputed(precomputed_block, &self.version.protocol),
             );
         }
 
@@ -1154,7 +1297,12 @@ impl IndexerState {
         state_hash: &StateHash,
     ) -> anyhow::Result<Option<HashMap<PublicKey, Username>>> {
         if let Some(indexer_store) = self.indexer_store.as_ref() {
+            let old_best_tip = indexer_store.get_best_block_hash()?;
             indexer_store.set_best_block(state_hash)?;
+
+            if old_best_tip.as_ref() != Some(state_hash) {
+                indexer_store.update_best_ledger_distribution()?;
+            }
             return indexer_store.get_block_username_updates(state_hash);
         }
         Ok(None)
@@ -1210,7 +1358,7 @@ impl IndexerState {
                     };
                     self.diffs_map.insert(
                         tip.state_hash.clone(),
-                        LedgerDiff::from_precomputed(&root_block),
+                        LedgerDiff::from_precomputed(&root_block, &self.version.protocol),
                     );
                     self.canonical_root = tip.clone();
                     self.best_tip = tip;
@@ -1279,7 +1427,7 @@ impl IndexerState {
             self.blocks_processed = indexer_store.get_block_production_total_count()?;
             self.bytes_processed = indexer_store
                 .database
-                .get(IndexerStore::NUM_BLOCK_BYTES_PROCESSED)?
+                .get(indexer_store.fixed_key(IndexerStore::NUM_BLOCK_BYTES_PROCESSED))?
                 .map_or(self.genesis_bytes, |bytes| {
                     u64_from_be_bytes(&bytes).expect("num blocks processed u64 BE bytes")
                 });
@@ -1559,7 +1707,7 @@ impl IndexerState {
                 if canonical_block.blockchain_length % self.ledger_cadence == 0 {
                     indexer_store.add_staged_ledger_at_state_hash(
                         &canonical_block.state_hash,
-                        self.ledger.clone(),
+                        &self.ledger,
                         canonical_block.blockchain_length,
                     )?;
                 }
@@ -1682,6 +1830,33 @@ impl IndexerState {
         }
     }
 
+    /// Snapshot of the root & dangling branches
+    pub fn witness_tree(&self) -> WitnessTree {
+        let node_info = |block: &Block| {
+            let store = self.indexer_store.as_ref();
+            let creator = store
+                .and_then(|store| store.get_block_creator(&block.state_hash).ok())
+                .flatten();
+            let canonicity = store
+                .and_then(|store| store.get_block_canonicity(&block.state_hash).ok())
+                .flatten()
+                .unwrap_or(Canonicity::Pending);
+
+            (creator, canonicity)
+        };
+
+        WitnessTree {
+            best_tip: self.best_tip_block().state_hash.clone(),
+            canonical_root: self.canonical_root_block().state_hash.clone(),
+            root_branch: WitnessBranch::new(&self.root_branch, node_info),
+            dangling_branches: self
+                .dangling_branches
+                .iter()
+                .map(|branch| WitnessBranch::new(branch, node_info))
+                .collect(),
+        }
+    }
+
     fn should_report_from_block_count(&self, block_parser: &BlockParser) -> bool {
         self.blocks_processed > 0 && self.blocks_processed % self.reporting_freq == 0
             || self.blocks_processed == block_parser.num_deep_canonical_blocks + 1
@@ -0,0 +1,31 @@
+//! Statuses of blocks & staking ledgers uploaded to the web server
+
+use crate::{base::state_hash::StateHash, canonicity::Canonicity, ledger::LedgerHash};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BlockUploadStatus {
+    pub state_hash: StateHash,
+    pub blockchain_length: u32,
+
+    /// The block was not yet in the witness tree
+    pub added: bool,
+
+    /// Pending until the block is canonical or orphaned in the store
+    pub canonicity: Canonicity,
+
+    /// The block is an ancestor of (or is) the best tip
+    pub on_best_chain: bool,
+
+    pub best_tip_state_hash: StateHash,
+    pub best_tip_blockchain_length: u32,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StakingLedgerUploadStatus {
+    pub epoch: u32,
+    pub ledger_hash: LedgerHash,
+
+    /// The epoch's staking ledger was not yet in the store
+    pub added: bool,
+}
@@ -0,0 +1,223 @@
+//! Snapshot of the witness tree for inspection & export
+
+use crate::{
+    base::{public_key::PublicKey, state_hash::StateHash},
+    block::{vrf_output::VrfOutput, Block},
+    canonicity::Canonicity,
+    state::branch::Branch,
+};
+use serde::{Deserialize, Serialize};
+use std::fmt::Write;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct WitnessTree {
+    pub best_tip: StateHash,
+    pub canonical_root: StateHash,
+
+    /// Tree of blocks connecting back to a known ledger
+    pub root_branch: WitnessBranch,
+
+    /// Trees of blocks waiting for their missing ancestors
+    pub dangling_branches: Vec<WitnessBranch>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct WitnessBranch {
+    pub root: StateHash,
+
+    /// Parent of the branch root, i.e. the block needed to merge a dangling
+    /// branch
+    pub root_parent: StateHash,
+
+    /// Branch blocks in level order
+    pub nodes: Vec<WitnessTreeNode>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct WitnessTreeNode {
+    pub state_hash: StateHash,
+    pub parent_hash: StateHash,
+    pub blockchain_length: u32,
+    pub global_slot_since_genesis: u32,
+    pub creator: Option<PublicKey>,
+    pub last_vrf_output: VrfOutput,
+    pub canonicity: Canonicity,
+}
+
+impl WitnessBranch {
+    /// Snapshot the branch, looking up each block's creator & canonicity
+    pub fn new<F>(branch: &Branch, node_info: F) -> Self
+    where
+        F: Fn(&Block) -> (Option<PublicKey>, Canonicity),
+    {
+        let root = branch.root_block();
+        let nodes = branch
+            .branches
+            .traverse_level_order(&branch.root)
+            .expect("traverse level order")
+            .map(|node| {
+                let block = node.data();
+                let (creator, canonicity) = node_info(block);
+
+                WitnessTreeNode {
+                    state_hash: block.state_hash.clone(),
+                    parent_hash: block.parent_hash.clone(),
+                    blockchain_length: block.blockchain_length,
+                    global_slot_since_genesis: block.global_slot_since_genesis,
+                    last_vrf_output: block.hash_last_vrf_output.clone(),
+                    creator,
+                    canonicity,
+                }
+            })
+            .collect();
+
+        Self {
+            root: root.state_hash.clone(),
+            root_parent: root.parent_hash.clone(),
+            nodes,
+        }
+    }
+}
+
+impl WitnessTree {
+    /// Number of blocks in the root & dangling branches
+    pub fn num_blocks(&self) -> usize {
+        self.root_branch.nodes.len()
+            + self
+                .dangling_branches
+                .iter()
+                .map(|branch| branch.nodes.len())
+                .sum::<usize>()
+    }
+
+    /// Graphviz DOT representation, one cluster per branch
+    pub fn to_dot(&self) -> String {
+        let mut dot = String::from("digraph witness_tree {\n");
+        dot.push_str("  rankdir=LR;\n");
+        dot.push_str("  node [shape=box, style=filled, fontname=monospace];\n");
+
+        self.write_branch_dot(&mut dot, "root_branch", "root branch", &self.root_branch);
+        for (n, branch) in self.dangling_branches.iter().enumerate() {
+            self.write_branch_dot(
+                &mut dot,
+                &format!("dangling_branch_{n}"),
+                &format!("dangling branch {n}"),
+                branch,
+            );
+
+            // the missing parent each dangling branch is waiting for
+            writeln!(
+                dot,
+                "  \"{}\" [label=\"missing\\n{}\", style=dashed];",
+                branch.root_parent,
+                short_hash(&branch.root_parent),
+            )
+            .unwrap();
+            writeln!(
+                dot,
+                "  \"{}\" -> \"{}\" [style=dashed];",
+                branch.root_parent, branch.root
+            )
+            .unwrap();
+        }
+
+        dot.push_str("}\n");
+        dot
+    }
+
+    fn write_branch_dot(&self, dot: &mut String, name: &str, label: &str, branch: &WitnessBranch) {
+        writeln!(dot, "  subgraph cluster_{name} {{").unwrap();
+        writeln!(dot, "    label=\"{label}\";").unwrap();
+
+        for node in branch.nodes.iter() {
+            let color = match node.canonicity {
+                Canonicity::Canonical => "palegreen",
+                Canonicity::Orphaned => "lightpink",
+                Canonicity::Pending => "lightgray",
+            };
+            let mut attrs = format!(
+                "label=\"{}\\n{}\\nslot {}\", fillcolor={color}",
+                node.blockchain_length,
+                short_hash(&node.state_hash),
+                node.global_slot_since_genesis,
+            );
+
+            if node.state_hash == self.best_tip {
+                attrs.push_str(", penwidth=3");
+            }
+            if node.state_hash == self.canonical_root {
+                attrs.push_str(", peripheries=2");
+            }
+
+            writeln!(dot, "    \"{}\" [{attrs}];", node.state_hash).unwrap();
+        }
+
+        for node in branch.nodes.iter().skip(1) {
+            writeln!(
+                dot,
+                "    \"{}\" -> \"{}\";",
+                node.parent_hash, node.state_hash
+            )
+            .unwrap();
+        }
+
+        dot.push_str("  }\n");
+    }
+}
+
+/// First & last characters of the state hash
+fn short_hash(state_hash: &StateHash) -> String {
+    let hash = &state_hash.0;
+    if hash.len() <= 12 {
+        return hash.clone();
+    }
+
+    format!("{}..{}", &hash[..6], &hash[hash.len() - 4..])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn node(state_hash: &str, parent_hash: &str, blockchain_length: u32) -> WitnessTreeNode {
+        WitnessTreeNode {
+            state_hash: state_hash.into(),
+            parent_hash: parent_hash.into(),
+            blockchain_length,
+            global_slot_since_genesis: blockchain_length,
+            creator: None,
+            last_vrf_output: VrfOutput::default(),
+            canonicity: Canonicity::Pending,
+        }
+    }
+
+    #[test]
+    fn witness_tree_dot() {
+        let tree = WitnessTree {
+            best_tip: "b".into(),
+            canonical_root: "a".into(),
+            root_branch: WitnessBranch {
+                root: "a".into(),
+                root_parent: "p".into(),
+                nodes: vec![node("a", "p", 1), node("b", "a", 2), node("c", "a", 2)],
+            },
+            dangling_branches: vec![WitnessBranch {
+                root: "e".into(),
+                root_parent: "d".into(),
+                nodes: vec![node("e", "d", 4)],
+            }],
+        };
+        let dot = tree.to_dot();
+
+        assert_eq!(tree.num_blocks(), 4);
+        assert!(dot.starts_with("digraph witness_tree {\n"));
+        assert!(dot.contains("subgraph cluster_root_branch {"));
+        assert!(dot.contains("subgraph cluster_dangling_branch_0 {"));
+        assert!(dot.contains("\"a\" -> \"b\";"));
+        assert!(dot.contains("\"a\" -> \"c\";"));
+        assert!(dot.contains("\"d\" -> \"e\" [style=dashed];"));
+
+        // the branch root's parent is not part of the branch
+        assert!(!dot.contains("\"p\" -> \"a\""));
+    }
+}
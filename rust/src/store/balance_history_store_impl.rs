@@ -0,0 +1,243 @@
+use super::{column_families::ColumnFamilyHelpers, IndexerStore};
+use crate::{
+    base::{public_key::PublicKey, state_hash::StateHash},
+    block::store::{BlockStore, BlockUpdate},
+    command::store::UserCommandStore,
+    ledger::{
+        diff::LedgerDiff,
+        store::balance_history::{
+            BalanceChange, BalanceChangeCause, BalanceChangeKind, BalanceHistoryStore,
+        },
+        token::TokenAddress,
+        Ledger,
+    },
+    store::Result,
+    utility::{
+        deadline::{check_scan, Deadline},
+        store::common::{token_pk_index_key, u32_from_be_bytes},
+    },
+};
+use log::trace;
+use speedb::{DBIterator, Direction, IteratorMode, WriteBatch};
+use std::collections::HashMap;
+
+impl BalanceHistoryStore for IndexerStore {
+    fn add_block_balance_changes(
+        &self,
+        block: &BlockUpdate,
+        diff: &LedgerDiff,
+        balances: &HashMap<(PublicKey, TokenAddress), (u64, u64)>,
+    ) -> Result<()> {
+        trace!("Adding block balance changes {}", block.state_hash);
+
+        // link payments to their user commands
+        let txn_hashes = self.get_block_user_command_hashes(&block.state_hash)?;
+        let date_time = self
+            .get_block_date_time(&block.state_hash)?
+            .unwrap_or_default();
+
+        let mut batch = WriteBatch::default();
+        for change in BalanceChange::from_ledger_diff(block, date_time, diff, balances, &txn_hashes)
+        {
+            batch.put_cf(
+                self.best_ledger_account_balance_history_cf(),
+                token_pk_index_key(&change.token, &change.public_key, block.blockchain_length),
+                serde_json::to_vec(&change)?,
+            );
+        }
+
+        Ok(self.database.write(batch)?)
+    }
+
+    fn add_genesis_balance_changes(
+        &self,
+        state_hash: &StateHash,
+        block_height: u32,
+        genesis_ledger: &Ledger,
+    ) -> Result<()> {
+        trace!("Adding genesis balance changes {state_hash}");
+        let global_slot_since_genesis = self
+            .get_block_global_slots_from_height(block_height)?
+            .and_then(|slots| slots.first().cloned())
+            .unwrap_or_default();
+        let protocol = self.protocol_params();
+
+        let mut batch = WriteBatch::default();
+        for (token, token_ledger) in genesis_ledger.tokens.iter() {
+            for (pk, account) in token_ledger.accounts.iter() {
+                let balance = account.clone().display(&protocol).balance.0;
+                let change = BalanceChange {
+                    public_key: pk.clone(),
+                    token: token.clone(),
+                    state_hash: state_hash.clone(),
+                    blockchain_length: block_height,
+                    global_slot_since_genesis,
+                    date_time: protocol.genesis_timestamp as i64,
+                    balance_before: 0,
+                    balance_after: balance,
+                    causes: vec![BalanceChangeCause {
+                        kind: BalanceChangeKind::Genesis,
+                        amount: balance as i64,
+                        txn_hash: None,
+                    }],
+                };
+
+                batch.put_cf(
+                    self.best_ledger_account_balance_history_cf(),
+                    token_pk_index_key(token, pk, block_height),
+                    serde_json::to_vec(&change)?,
+                );
+            }
+        }
+
+        Ok(self.database.write(batch)?)
+    }
+
+    fn remove_block_balance_changes(&self, block: &BlockUpdate, diff: &LedgerDiff) -> Result<()> {
+        trace!("Removing block balance changes {}", block.state_hash);
+        let mut batch = WriteBatch::default();
+
+        for account_diff in diff.account_diffs.iter().flatten() {
+            let pk = account_diff.public_key();
+            let token = account_diff.token_address();
+
+            // only remove the unapplied block's changes
+            if self
+                .get_balance_change(&pk, &token, block.blockchain_length)?
+                .is_some_and(|change| change.state_hash == block.state_hash)
+            {
+                batch.delete_cf(
+                    self.best_ledger_account_balance_history_cf(),
+                    token_pk_index_key(&token, &pk, block.blockchain_length),
+                );
+            }
+        }
+
+        Ok(self.database.write(batch)?)
+    }
+
+    fn get_balance_change(
+        &self,
+        pk: &PublicKey,
+        token: &TokenAddress,
+        block_height: u32,
+    ) -> Result<Option<BalanceChange>> {
+        trace!("Getting {pk} token {token} balance change at height {block_height}");
+        Ok(self
+            .database
+            .get_cf(
+                self.best_ledger_account_balance_history_cf(),
+                token_pk_index_key(token, pk, block_height),
+            )?
+            .and_then(|bytes| serde_json::from_slice(&bytes).ok()))
+    }
+
+    fn get_balance_history(
+        &self,
+        pk: &PublicKey,
+        token: &TokenAddress,
+        start_height: u32,
+        end_height: u32,
+        limit: usize,
+        direction: Direction,
+        deadline: Option<&Deadline>,
+    ) -> Result<Vec<BalanceChange>> {
+        trace!("Getting {pk} token {token} balance history ({start_height}, {end_height})");
+        let mut history = vec![];
+        let prefix = token_pk_index_key(token, pk, 0);
+        let start = match direction {
+            Direction::Forward => token_pk_index_key(token, pk, start_height),
+            Direction::Reverse => token_pk_index_key(token, pk, end_height),
+        };
+
+        for (key, value) in self
+            .balance_history_iterator(IteratorMode::From(&start, direction))
+            .flatten()
+        {
+            check_scan(deadline)?;
+            if history.len() >= limit
+                || key[..TokenAddress::LEN + PublicKey::LEN]
+                    != prefix[..TokenAddress::LEN + PublicKey::LEN]
+            {
+                break;
+            }
+
+            let height = u32_from_be_bytes(&key[TokenAddress::LEN + PublicKey::LEN..])?;
+            if height < start_height || height > end_height {
+                break;
+            }
+
+            history.push(serde_json::from_slice(&value)?);
+        }
+
+        Ok(history)
+    }
+
+    fn get_balance_at_height(
+        &self,
+        pk: &PublicKey,
+        token: &TokenAddress,
+        block_height: u32,
+    ) -> Result<Option<u64>> {
+        trace!("Getting {pk} token {token} balance at height {block_height}");
+        let prefix = token_pk_index_key(token, pk, 0);
+        let start = token_pk_index_key(token, pk, block_height);
+
+        // the most recent change at or below the block height
+        if let Some((key, value)) = self
+            .balance_history_iterator(IteratorMode::From(&start, Direction::Reverse))
+            .flatten()
+            .next()
+        {
+            if key[..TokenAddress::LEN + PublicKey::LEN]
+                == prefix[..TokenAddress::LEN + PublicKey::LEN]
+            {
+                let change: BalanceChange = serde_json::from_slice(&value)?;
+                return Ok(Some(change.balance_after));
+            }
+        }
+
+        Ok(None)
+    }
+
+    fn get_balance_at_slot(
+        &self,
+        pk: &PublicKey,
+        token: &TokenAddress,
+        global_slot: u32,
+        deadline: Option<&Deadline>,
+    ) -> Result<Option<u64>> {
+        trace!("Getting {pk} token {token} balance at global slot {global_slot}");
+        let prefix = token_pk_index_key(token, pk, 0);
+        let start = token_pk_index_key(token, pk, u32::MAX);
+
+        // the most recent change at or below the global slot
+        for (key, value) in self
+            .balance_history_iterator(IteratorMode::From(&start, Direction::Reverse))
+            .flatten()
+        {
+            check_scan(deadline)?;
+            if key[..TokenAddress::LEN + PublicKey::LEN]
+                != prefix[..TokenAddress::LEN + PublicKey::LEN]
+            {
+                break;
+            }
+
+            let change: BalanceChange = serde_json::from_slice(&value)?;
+            if change.global_slot_since_genesis <= global_slot {
+                return Ok(Some(change.balance_after));
+            }
+        }
+
+        Ok(None)
+    }
+
+    ///////////////
+    // Iterators //
+    ///////////////
+
+    fn balance_history_iterator(&self, mode: IteratorMode) -> DBIterator<'_> {
+        self.database
+            .iterator_cf(self.best_ledger_account_balance_history_cf(), mode)
+    }
+}
@@ -1,13 +1,16 @@
 use super::{column_families::ColumnFamilyHelpers, fixed_keys::FixedKeys, DbUpdate, IndexerStore};
 use crate::{
     base::{public_key::PublicKey, state_hash::StateHash},
-    block::store::{BlockStore, BlockUpdate, DbBlockUpdate},
+    block::store::{BlockStore, DbBlockUpdate},
     ledger::{
         account::Account,
         diff::account::AccountDiff,
         store::{
+            balance_history::BalanceHistoryStore,
             best::{BestLedgerStore, DbAccountUpdate},
+            delegations::DelegationStore,
             staged::StagedLedgerStore,
+            supply::SupplyStore,
         },
         token::TokenAddress,
         Ledger, TokenLedger,
@@ -23,7 +26,7 @@ use crate::{
 };
 use log::trace;
 use speedb::{DBIterator, IteratorMode};
-use std::collections::HashSet;
+use std::collections::{hash_map::Entry, HashSet};
 
 impl BestLedgerStore for IndexerStore {
     fn get_best_account(&self, pk: &PublicKey, token: &TokenAddress) -> Result<Option<Account>> {
@@ -44,7 +47,7 @@ impl BestLedgerStore for IndexerStore {
     ) -> Result<Option<Account>> {
         trace!("Display best ledger account {pk}");
         if let Some(best_acct) = self.get_best_account(pk, token)? {
-            return Ok(Some(best_acct.display()));
+            return Ok(Some(best_acct.display(&self.protocol_params())));
         }
         Ok(None)
     }
@@ -56,12 +59,8 @@ impl BestLedgerStore for IndexerStore {
             {
                 if memoize {
                     trace!("Memoizing best ledger (state hash {state_hash})");
-                    self.add_staged_ledger_at_state_hash(
-                        &state_hash,
-                        best_ledger.clone(),
-                        block_height,
-                    )
-                    .ok();
+                    self.add_staged_ledger_at_state_hash(&state_hash, best_ledger, block_height)
+                        .ok();
                 }
             }
         }))
@@ -97,6 +96,9 @@ impl BestLedgerStore for IndexerStore {
                 }
             }
 
+            if *token == TokenAddress::default() {
+                self.update_timed_account(pk, None)?;
+            }
             return Ok(());
         }
 
@@ -122,6 +124,11 @@ impl BestLedgerStore for IndexerStore {
         let account_key = best_account_key(token, pk);
         let sort_key = best_account_sort_key(token, balance, pk);
 
+        // track timed MINA accounts for the locked supply
+        if *token == TokenAddress::default() {
+            self.update_timed_account(pk, after.timing.as_ref())?;
+        }
+
         // store the new account
         self.database.put_cf(
             self.best_ledger_accounts_cf(),
@@ -162,35 +169,75 @@ impl BestLedgerStore for IndexerStore {
         state_hash: &StateHash,
         blocks: &DbBlockUpdate,
     ) -> Result<()> {
-        let account_updates = DbUpdate {
-            apply: blocks
-                .apply
-                .iter()
-                .flat_map(|BlockUpdate { state_hash: a, .. }| {
-                    let diff = self.get_block_ledger_diff(a).unwrap();
-                    diff.map(|d| {
-                        (
-                            d.account_diffs.into_iter().flatten().collect(),
-                            update_token_accounts(d.new_pk_balances),
-                        )
-                    })
-                })
-                .collect(),
-            unapply: blocks
-                .unapply
-                .iter()
-                .flat_map(|BlockUpdate { state_hash: u, .. }| {
-                    let diff = self.get_block_ledger_diff(u).unwrap();
-                    diff.map(|d| {
-                        (
-                            d.account_diffs.into_iter().flatten().collect(),
-                            update_token_accounts(d.new_pk_balances),
-                        )
-                    })
-                })
-                .collect(),
-        };
-        self.update_best_accounts(state_hash, account_updates)
+        // unapply
+        let mut unapply = vec![];
+        for block in blocks.unapply.iter() {
+            if let Some(diff) = self.get_block_ledger_diff(&block.state_hash)? {
+                self.remove_block_balance_changes(block, &diff)?;
+                self.remove_block_delegation_changes(block, &diff)?;
+                unapply.push((
+                    diff.account_diffs.into_iter().flatten().collect(),
+                    update_token_accounts(diff.new_pk_balances),
+                ));
+            }
+        }
+        self.update_best_accounts(
+            state_hash,
+            DbUpdate {
+                apply: vec![],
+                unapply,
+            },
+        )?;
+
+        // apply each block, recording the resulting balance & delegation changes
+        for block in blocks.apply.iter() {
+            if let Some(diff) = self.get_block_ledger_diff(&block.state_hash)? {
+                let mut balances = HashMap::new();
+                let mut delegates = HashMap::new();
+                for account_diff in diff.account_diffs.iter().flatten() {
+                    let (pk, token) = (account_diff.public_key(), account_diff.token_address());
+                    if let Entry::Vacant(entry) = balances.entry((pk.clone(), token.clone())) {
+                        let before = self
+                            .get_best_account_display(&pk, &token)?
+                            .map_or(0, |acct| acct.balance.0);
+                        entry.insert((before, before));
+                    }
+
+                    if let AccountDiff::Delegation(delegation) = account_diff {
+                        if let Entry::Vacant(entry) = delegates.entry(delegation.delegator.clone())
+                        {
+                            if let Some(account) =
+                                self.get_best_account(&delegation.delegator, &token)?
+                            {
+                                entry.insert(account.delegate);
+                            }
+                        }
+                    }
+                }
+
+                self.update_best_accounts(
+                    state_hash,
+                    DbUpdate {
+                        apply: vec![(
+                            diff.account_diffs.iter().flatten().cloned().collect(),
+                            update_token_accounts(diff.new_pk_balances.clone()),
+                        )],
+                        unapply: vec![],
+                    },
+                )?;
+
+                for ((pk, token), (_, after)) in balances.iter_mut() {
+                    *after = self
+                        .get_best_account_display(pk, token)?
+                        .map_or(0, |acct| acct.balance.0);
+                }
+
+                self.add_block_balance_changes(block, &diff, &balances)?;
+                self.add_block_delegation_changes(block, &diff, &delegates)?;
+            }
+        }
+
+        Ok(())
     }
 
     fn update_best_accounts(&self, state_hash: &StateHash, updates: DbAccountUpdate) -> Result<()> {
@@ -381,14 +428,14 @@ impl BestLedgerStore for IndexerStore {
             Greater => {
                 let old = self.get_num_accounts().ok().flatten().unwrap_or(0);
                 self.database.put(
-                    Self::TOTAL_NUM_ACCOUNTS_KEY,
+                    self.fixed_key(Self::TOTAL_NUM_ACCOUNTS_KEY),
                     old.saturating_add(adjust.unsigned_abs()).to_be_bytes(),
                 )?;
             }
             Less => {
                 let old = self.get_num_accounts().ok().flatten().unwrap_or(0);
                 self.database.put(
-                    Self::TOTAL_NUM_ACCOUNTS_KEY,
+                    self.fixed_key(Self::TOTAL_NUM_ACCOUNTS_KEY),
                     old.saturating_sub(adjust.unsigned_abs()).to_be_bytes(),
                 )?;
             }
@@ -399,7 +446,7 @@ impl BestLedgerStore for IndexerStore {
     fn get_num_accounts(&self) -> Result<Option<u32>> {
         Ok(self
             .database
-            .get(Self::TOTAL_NUM_ACCOUNTS_KEY)?
+            .get(self.fixed_key(Self::TOTAL_NUM_ACCOUNTS_KEY))?
             .map(from_be_bytes))
     }
 
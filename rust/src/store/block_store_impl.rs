@@ -9,14 +9,16 @@ use crate::{
         store::{BlockStore, BlockUpdate, DbBlockUpdate},
         BlockComparison,
     },
-    canonicity::{store::CanonicityStore, Canonicity},
+    canonicity::{reorg::ReorgStore, store::CanonicityStore, Canonicity},
     command::{internal::store::InternalCommandStore, store::UserCommandStore},
     constants::*,
     event::{db::*, store::EventStore, IndexerEvent},
     ledger::{
-        coinbase::Coinbase,
         diff::{account::AccountDiff, LedgerDiff},
-        store::{best::BestLedgerStore, staged::StagedLedgerStore},
+        store::{
+            best::BestLedgerStore, staged::StagedLedgerStore, staking::StakingLedgerStore,
+            supply::SupplyStore,
+        },
     },
     snark_work::store::SnarkStore,
     utility::store::{
@@ -24,12 +26,11 @@ use crate::{
         common::{
             block_u32_prefix_from_key, from_be_bytes, i64_from_be_bytes, pk_index_key,
             state_hash_suffix, u32_from_be_bytes, u32_prefix_key, u64_from_be_bytes, U32_LEN,
-            U64_LEN,
         },
     },
 };
 use anyhow::{bail, Context};
-use log::{error, trace};
+use log::{error, info, trace};
 use speedb::{DBIterator, Direction, IteratorMode, WriteBatch};
 
 impl BlockStore for IndexerStore {
@@ -43,9 +44,6 @@ impl BlockStore for IndexerStore {
 
         // add block to db - prefix with num bytes (u64) BE bytes
         let state_hash = block.state_hash();
-        let mut value = num_block_bytes.to_be_bytes().to_vec();
-        value.append(&mut serde_json::to_vec(block)?);
-
         if matches!(
             self.database
                 .get_cf(self.blocks_state_hash_cf(), state_hash.0.as_bytes()),
@@ -55,6 +53,8 @@ impl BlockStore for IndexerStore {
             return Ok(None);
         }
 
+        let value = block_value(block, num_block_bytes)?;
+
         let mut batch = WriteBatch::default();
         batch.put_cf(self.blocks_state_hash_cf(), state_hash.0.as_bytes(), b"");
         batch.put_cf(self.blocks_cf(), state_hash.0.as_bytes(), value);
@@ -62,7 +62,7 @@ impl BlockStore for IndexerStore {
         // add to ledger diff index
         self.set_block_ledger_diff_batch(
             &state_hash,
-            &LedgerDiff::from_precomputed(block),
+            &LedgerDiff::from_precomputed(block, &self.protocol_params()),
             &mut batch,
         )?;
 
@@ -91,6 +91,13 @@ impl BlockStore for IndexerStore {
         // add to date time index
         self.set_block_date_time_batch(&state_hash, block.timestamp() as i64, &mut batch)?;
 
+        // add to min window density index
+        self.set_block_min_window_density_batch(
+            &state_hash,
+            block.min_window_density(),
+            &mut batch,
+        )?;
+
         // add to staged ledger hash index
         self.set_block_staged_ledger_hash_batch(
             &state_hash,
@@ -99,7 +106,7 @@ impl BlockStore for IndexerStore {
         )?;
 
         // add to genesis state hash index
-        if state_hash.0 == MAINNET_GENESIS_HASH || state_hash.0 == HARDFORK_GENESIS_HASH {
+        if self.get_known_genesis_state_hashes()?.contains(&state_hash) {
             self.set_block_genesis_state_hash_batch(&state_hash, &state_hash, &mut batch)?;
         } else {
             let genesis_state_hash = block.genesis_state_hash();
@@ -157,7 +164,8 @@ impl BlockStore for IndexerStore {
         // add epoch produced slot
         self.add_epoch_slots_produced(
             block.epoch_count(),
-            block.global_slot_since_genesis() % MAINNET_EPOCH_SLOT_COUNT,
+            self.protocol_params()
+                .epoch_slot(block.global_slot_since_genesis()),
             &block.block_creator(),
         )?;
 
@@ -167,12 +175,12 @@ impl BlockStore for IndexerStore {
         // increment bytes processed
         let bytes_processed = self
             .database
-            .get(Self::NUM_BLOCK_BYTES_PROCESSED)?
+            .get(self.fixed_key(Self::NUM_BLOCK_BYTES_PROCESSED))?
             .map_or(0, |bytes| {
                 u64_from_be_bytes(&bytes).expect("bytes processed u64 BE bytes")
             });
         self.database.put(
-            Self::NUM_BLOCK_BYTES_PROCESSED,
+            self.fixed_key(Self::NUM_BLOCK_BYTES_PROCESSED),
             (bytes_processed + num_block_bytes).to_be_bytes(),
         )?;
 
@@ -191,18 +199,50 @@ impl BlockStore for IndexerStore {
             .database
             .get_pinned_cf(self.blocks_cf(), state_hash.0.as_bytes())?
             .and_then(|bytes| {
-                serde_json::from_slice::<PrecomputedBlock>(&bytes[U64_LEN..])
+                block_from_value(&bytes)
                     .with_context(|| format!("{:?}", bytes.to_vec()))
                     .ok()
-                    .map(|block| {
-                        (
-                            block,
-                            u64_from_be_bytes(&bytes[..U64_LEN]).expect("block bytes u64 BE bytes"),
-                        )
-                    })
             }))
     }
 
+    fn migrate_block_encoding(&self) -> anyhow::Result<u32> {
+        trace!("Migrating JSON encoded blocks");
+        let mut num_migrated = 0;
+        let mut batch = WriteBatch::default();
+
+        for (key, value) in self
+            .database
+            .iterator_cf(self.blocks_cf(), IteratorMode::Start)
+            .flatten()
+        {
+            // skip the block creator index keys
+            if key.len() != StateHash::LEN || !is_json_block_value(&value) {
+                continue;
+            }
+
+            let (block, num_block_bytes) = block_from_value(&value)?;
+            let value = block_value(&block, num_block_bytes)?;
+            if is_json_block_value(&value) {
+                error!(
+                    "Block {} does not round trip, keeping JSON",
+                    block.summary()
+                );
+                continue;
+            }
+
+            batch.put_cf(self.blocks_cf(), key, value);
+            num_migrated += 1;
+
+            if batch.len() >= BLOCK_MIGRATION_BATCH_SIZE {
+                self.database.write(std::mem::take(&mut batch))?;
+                info!("Migrated {num_migrated} blocks");
+            }
+        }
+
+        self.database.write(batch)?;
+        Ok(num_migrated)
+    }
+
     //////////////////////////
     // Best block functions //
     //////////////////////////
@@ -219,7 +259,7 @@ impl BlockStore for IndexerStore {
         trace!("Getting best block state hash");
         Ok(self
             .database
-            .get(Self::BEST_TIP_STATE_HASH_KEY)?
+            .get(self.fixed_key(Self::BEST_TIP_STATE_HASH_KEY))?
             .and_then(|bytes| StateHash::from_bytes(&bytes).ok()))
     }
 
@@ -248,24 +288,42 @@ impl BlockStore for IndexerStore {
 
     fn set_best_block(&self, state_hash: &StateHash) -> anyhow::Result<()> {
         trace!("Setting best block {state_hash}");
-        if let Some(old) = self.get_best_block_hash()? {
-            if old == *state_hash {
+        let old_best_tip = self.get_best_block_hash()?;
+        if let Some(old) = old_best_tip.as_ref() {
+            if old == state_hash {
                 return Ok(());
             }
 
             // reorg updates
-            let reorg_blocks = self.reorg_blocks(&old, state_hash)?;
+            let reorg_blocks = self.reorg_blocks(old, state_hash)?;
             self.update_block_canonicities(&reorg_blocks)?;
             self.update_block_best_accounts(state_hash, &reorg_blocks)?;
             self.update_block_snarks(&reorg_blocks)?;
             self.update_block_usernames(&reorg_blocks)?;
             self.update_internal_commands(&reorg_blocks)?;
             self.update_user_commands(&reorg_blocks)?;
+            self.add_reorg(old, state_hash, &reorg_blocks)?;
         }
 
         // set new best tip
-        self.database
-            .put(Self::BEST_TIP_STATE_HASH_KEY, state_hash.0.as_bytes())?;
+        self.database.put(
+            self.fixed_key(Self::BEST_TIP_STATE_HASH_KEY),
+            state_hash.0.as_bytes(),
+        )?;
+
+        // cache the new best tip's supply & derive its missing staking ledgers
+        // once per epoch transition, when the next epoch ledger is chosen
+        if let Some((block, _)) = self.get_block(state_hash)? {
+            self.add_block_supply(&block)?;
+
+            let old_epoch = match old_best_tip.as_ref() {
+                Some(old) => self.get_block_epoch(old)?,
+                None => None,
+            };
+            if old_epoch != Some(block.epoch_count()) {
+                self.derive_epoch_staking_ledgers(&block)?;
+            }
+        }
 
         // record new best tip event
         match self.get_block_height(state_hash)? {
@@ -305,13 +363,13 @@ impl BlockStore for IndexerStore {
         // bring b back to the same height as a
         for _ in 0..b_length.saturating_sub(self.get_block_height(&a)?.expect("a has length")) {
             // check if there's a previous block
-            if b.0 == MAINNET_GENESIS_HASH || b.0 == HARDFORK_GENESIS_HASH {
+            if self.is_genesis_block(&b)? {
                 break;
             }
 
             apply.push(BlockUpdate {
                 state_hash: b.clone(),
-                blockchain_length: b_length,
+                blockchain_length: self.get_block_height(&b)?.expect("b has length"),
                 global_slot_since_genesis: self
                     .get_block_global_slot(&b)?
                     .expect("b has global slot"),
@@ -324,7 +382,7 @@ impl BlockStore for IndexerStore {
         let mut a_prev = self.get_block_parent_hash(&a)?.expect("a has a parent");
         let mut b_prev = self.get_block_parent_hash(&b)?.expect("b has a parent");
 
-        while a != b && (a.0 != MAINNET_GENESIS_HASH && a.0 != HARDFORK_GENESIS_HASH) {
+        while a != b && !self.is_genesis_block(&a)? {
             // add blocks to appropriate collection
             let a_length = self.get_block_height(&a)?.expect("a has length");
             let b_length = self.get_block_height(&b)?.expect("b has length");
@@ -437,6 +495,29 @@ impl BlockStore for IndexerStore {
         Ok(())
     }
 
+    fn set_block_min_window_density_batch(
+        &self,
+        state_hash: &StateHash,
+        min_window_density: u32,
+        batch: &mut WriteBatch,
+    ) -> anyhow::Result<()> {
+        trace!("Setting block min window density {state_hash}");
+        batch.put_cf(
+            self.block_min_window_density_cf(),
+            state_hash.0.as_bytes(),
+            min_window_density.to_be_bytes(),
+        );
+        Ok(())
+    }
+
+    fn get_block_min_window_density(&self, state_hash: &StateHash) -> anyhow::Result<Option<u32>> {
+        trace!("Getting block min window density {state_hash}");
+        Ok(self
+            .database
+            .get_cf(self.block_min_window_density_cf(), state_hash.0.as_bytes())?
+            .and_then(|bytes| u32_from_be_bytes(&bytes).ok()))
+    }
+
     fn get_block_height(&self, state_hash: &StateHash) -> anyhow::Result<Option<u32>> {
         trace!("Getting block height {state_hash}");
 
@@ -942,8 +1023,9 @@ impl BlockStore for IndexerStore {
 
     fn get_next_global_slot_produced(&self, global_slot: u32) -> anyhow::Result<Option<u32>> {
         trace!("Getting next slot produced at or above {global_slot}");
-        let epoch = global_slot / MAINNET_EPOCH_SLOT_COUNT;
-        let epoch_slot = global_slot % MAINNET_EPOCH_SLOT_COUNT;
+        let protocol = self.protocol_params();
+        let epoch = protocol.epoch(global_slot);
+        let epoch_slot = protocol.epoch_slot(global_slot);
 
         if let Some((key, _)) = self
             .database
@@ -956,15 +1038,16 @@ impl BlockStore for IndexerStore {
         {
             let epoch = u32_from_be_bytes(&key[..U32_LEN]).expect("epoch u32 bytes");
             let epoch_slot = u32_from_be_bytes(&key[U32_LEN..]).expect("epoch slot u32 bytes");
-            return Ok(Some(epoch * MAINNET_EPOCH_SLOT_COUNT + epoch_slot));
+            return Ok(Some(epoch * protocol.slots_per_epoch + epoch_slot));
         }
         Ok(None)
     }
 
     fn get_prev_global_slot_produced(&self, global_slot: u32) -> anyhow::Result<u32> {
         trace!("Getting previous slot produced at or below {global_slot}");
-        let epoch = global_slot / MAINNET_EPOCH_SLOT_COUNT;
-        let epoch_slot = global_slot % MAINNET_EPOCH_SLOT_COUNT;
+        let protocol = self.protocol_params();
+        let epoch = protocol.epoch(global_slot);
+        let epoch_slot = protocol.epoch_slot(global_slot);
 
         if let Some((key, _)) = self
             .database
@@ -977,7 +1060,7 @@ impl BlockStore for IndexerStore {
         {
             let epoch = u32_from_be_bytes(&key[..U32_LEN]).expect("epoch u32 bytes");
             let epoch_slot = u32_from_be_bytes(&key[U32_LEN..]).expect("epoch slot u32 bytes");
-            return Ok(epoch * MAINNET_EPOCH_SLOT_COUNT + epoch_slot);
+            return Ok(epoch * protocol.slots_per_epoch + epoch_slot);
         }
         Ok(0)
     }
@@ -1083,10 +1166,13 @@ impl BlockStore for IndexerStore {
 
         // increment total count
         let acc = self.get_block_production_total_count()?;
-        batch.put(Self::TOTAL_NUM_BLOCKS_KEY, (acc + 1).to_be_bytes());
+        batch.put(
+            self.fixed_key(Self::TOTAL_NUM_BLOCKS_KEY),
+            (acc + 1).to_be_bytes(),
+        );
 
         // supercharged counts
-        if Coinbase::from_precomputed(block).supercharge {
+        if block.supercharge_coinbase() {
             // pk epoch supercharged
             let acc =
                 self.get_block_production_pk_supercharged_epoch_count(&creator, Some(epoch))?;
@@ -1115,7 +1201,7 @@ impl BlockStore for IndexerStore {
             // total supercharged
             let acc = self.get_block_production_supercharged_total_count()?;
             batch.put(
-                Self::TOTAL_NUM_BLOCKS_SUPERCHARGED_KEY,
+                self.fixed_key(Self::TOTAL_NUM_BLOCKS_SUPERCHARGED_KEY),
                 (acc + 1).to_be_bytes(),
             );
         }
@@ -1156,8 +1242,10 @@ impl BlockStore for IndexerStore {
 
         // increment total count
         let acc = self.get_block_production_total_count()?;
-        self.database
-            .put(Self::TOTAL_NUM_BLOCKS_KEY, (acc + 1).to_be_bytes())?;
+        self.database.put(
+            self.fixed_key(Self::TOTAL_NUM_BLOCKS_KEY),
+            (acc + 1).to_be_bytes(),
+        )?;
 
         // supercharged counts
         if supercharged {
@@ -1188,7 +1276,7 @@ impl BlockStore for IndexerStore {
             // total supercharged
             let acc = self.get_block_production_supercharged_total_count()?;
             self.database.put(
-                Self::TOTAL_NUM_BLOCKS_SUPERCHARGED_KEY,
+                self.fixed_key(Self::TOTAL_NUM_BLOCKS_SUPERCHARGED_KEY),
                 (acc + 1).to_be_bytes(),
             )?;
         }
@@ -1437,7 +1525,7 @@ impl BlockStore for IndexerStore {
         trace!("Getting total block production count");
         Ok(self
             .database
-            .get(Self::TOTAL_NUM_BLOCKS_KEY)?
+            .get(self.fixed_key(Self::TOTAL_NUM_BLOCKS_KEY))?
             .map_or(0, from_be_bytes))
     }
 
@@ -1451,7 +1539,7 @@ impl BlockStore for IndexerStore {
         trace!("Getting total supercharged block production count");
         Ok(self
             .database
-            .get(Self::TOTAL_NUM_BLOCKS_SUPERCHARGED_KEY)?
+            .get(self.fixed_key(Self::TOTAL_NUM_BLOCKS_SUPERCHARGED_KEY))?
             .map_or(0, from_be_bytes))
     }
 
@@ -1599,6 +1687,14 @@ impl BlockStore for IndexerStore {
     }
 }
 
+impl IndexerStore {
+    /// Genesis blocks, mainnet's & the configured network's, are indexed
+    /// as their own genesis
+    fn is_genesis_block(&self, state_hash: &StateHash) -> anyhow::Result<bool> {
+        Ok(self.get_block_genesis_state_hash(state_hash)?.as_ref() == Some(state_hash))
+    }
+}
+
 fn block_cmp(db: &IndexerStore, a: &StateHash, b: &StateHash) -> std::cmp::Ordering {
     use std::cmp::Ordering;
     let a_canonicity = db.get_block_canonicity(a).ok().flatten();
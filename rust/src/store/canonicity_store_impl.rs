@@ -4,7 +4,6 @@ use crate::{
     block::store::{BlockStore, BlockUpdate, DbBlockUpdate},
     canonicity::{store::CanonicityStore, Canonicity, CanonicityDiff, CanonicityUpdate},
     command::internal::{store::InternalCommandStore, DbInternalCommandWithData},
-    constants::MAINNET_COINBASE_REWARD,
     event::{db::*, store::EventStore, IndexerEvent},
 };
 use log::trace;
@@ -31,7 +30,7 @@ impl CanonicityStore for IndexerStore {
                     self.increment_block_production_count(
                         state_hash,
                         receiver,
-                        *amount > MAINNET_COINBASE_REWARD,
+                        *amount > self.protocol_params().coinbase(false),
                     )?;
                 }
             }
@@ -55,28 +54,7 @@ impl CanonicityStore for IndexerStore {
 
         // record new genesis/prev state hashes
         if let Some(genesis_prev_state_hash) = genesis_prev_state_hash {
-            let (mut genesis_state_hashes, mut genesis_prev_state_hashes) = (
-                self.get_known_genesis_state_hashes()?,
-                self.get_known_genesis_prev_state_hashes()?,
-            );
-
-            // check if genesis hash is present
-            if !genesis_state_hashes.contains(genesis_state_hash) {
-                // if not
-                // add genesis state hash
-                genesis_state_hashes.push(genesis_state_hash.clone());
-                self.database.put(
-                    Self::KNOWN_GENESIS_STATE_HASHES_KEY,
-                    serde_json::to_vec(&genesis_state_hashes)?,
-                )?;
-
-                // add genesis prev state hash
-                genesis_prev_state_hashes.push(genesis_prev_state_hash.clone());
-                self.database.put(
-                    Self::KNOWN_GENESIS_PREV_STATE_HASHES_KEY,
-                    serde_json::to_vec(&genesis_prev_state_hashes)?,
-                )?;
-            }
+            self.add_known_genesis(genesis_state_hash, genesis_prev_state_hash)?;
         }
 
         // record new canonical block event
@@ -89,11 +67,42 @@ impl CanonicityStore for IndexerStore {
         Ok(())
     }
 
+    fn add_known_genesis(
+        &self,
+        genesis_state_hash: &StateHash,
+        genesis_prev_state_hash: &StateHash,
+    ) -> anyhow::Result<()> {
+        let (mut genesis_state_hashes, mut genesis_prev_state_hashes) = (
+            self.get_known_genesis_state_hashes()?,
+            self.get_known_genesis_prev_state_hashes()?,
+        );
+
+        // check if genesis hash is present
+        if !genesis_state_hashes.contains(genesis_state_hash) {
+            trace!("Adding known genesis {genesis_state_hash}");
+
+            // add genesis state hash
+            genesis_state_hashes.push(genesis_state_hash.clone());
+            self.database.put(
+                self.fixed_key(Self::KNOWN_GENESIS_STATE_HASHES_KEY),
+                serde_json::to_vec(&genesis_state_hashes)?,
+            )?;
+
+            // add genesis prev state hash
+            genesis_prev_state_hashes.push(genesis_prev_state_hash.clone());
+            self.database.put(
+                self.fixed_key(Self::KNOWN_GENESIS_PREV_STATE_HASHES_KEY),
+                serde_json::to_vec(&genesis_prev_state_hashes)?,
+            )?;
+        }
+        Ok(())
+    }
+
     fn get_known_genesis_state_hashes(&self) -> anyhow::Result<Vec<StateHash>> {
         trace!("Getting known genesis state hashes");
         Ok(self
             .database
-            .get_pinned(Self::KNOWN_GENESIS_STATE_HASHES_KEY)?
+            .get_pinned(self.fixed_key(Self::KNOWN_GENESIS_STATE_HASHES_KEY))?
             .map_or(vec![], |bytes| {
                 serde_json::from_slice(&bytes).expect("known genesis state hashes")
             }))
@@ -103,7 +112,7 @@ impl CanonicityStore for IndexerStore {
         trace!("Getting known genesis prev state hashes");
         Ok(self
             .database
-            .get_pinned(Self::KNOWN_GENESIS_PREV_STATE_HASHES_KEY)?
+            .get_pinned(self.fixed_key(Self::KNOWN_GENESIS_PREV_STATE_HASHES_KEY))?
             .map_or(vec![], |bytes| {
                 serde_json::from_slice(&bytes).expect("known genesis prev state hashes")
             }))
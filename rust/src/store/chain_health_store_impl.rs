@@ -0,0 +1,148 @@
+use super::{column_families::ColumnFamilyHelpers, IndexerStore};
+use crate::{
+    block::store::BlockStore,
+    chain::health::{ChainHealth, ChainHealthStore, HealthBlock},
+    utility::{
+        deadline::{check_scan, Deadline},
+        store::common::{
+            block_u32_prefix_from_key, i64_from_be_bytes, state_hash_suffix, u32_from_be_bytes,
+        },
+    },
+};
+use anyhow::bail;
+use log::trace;
+use speedb::{Direction, IteratorMode};
+
+impl ChainHealthStore for IndexerStore {
+    fn get_chain_health(
+        &self,
+        start_slot: u32,
+        end_slot: u32,
+        deadline: Option<&Deadline>,
+    ) -> anyhow::Result<ChainHealth> {
+        trace!("Getting chain health between global slots {start_slot} & {end_slot}");
+        let end_slot = self.cap_best_global_slot(end_slot)?;
+        let blocks = self.health_blocks(start_slot, end_slot, deadline)?;
+
+        Ok(ChainHealth::new(start_slot, end_slot, &blocks))
+    }
+
+    fn get_epoch_chain_health(
+        &self,
+        epoch: u32,
+        deadline: Option<&Deadline>,
+    ) -> anyhow::Result<ChainHealth> {
+        trace!("Getting epoch {epoch} chain health");
+        let Some((start_slot, end_slot)) = self.protocol_params().epoch_global_slots(epoch) else {
+            bail!("Epoch {epoch} ends after the max global slot")
+        };
+        self.get_chain_health(start_slot, end_slot, deadline)
+    }
+
+    fn get_chain_health_time_series(
+        &self,
+        start_slot: u32,
+        end_slot: u32,
+        window: u32,
+        step: u32,
+        limit: usize,
+        deadline: Option<&Deadline>,
+    ) -> anyhow::Result<Vec<ChainHealth>> {
+        trace!("Getting chain health time series between global slots {start_slot} & {end_slot}");
+        let end_slot = self.cap_best_global_slot(end_slot)?;
+
+        // only fetch the blocks of the first `limit` windows
+        let last_window_end = (limit.saturating_sub(1) as u32)
+            .saturating_mul(step.max(1))
+            .saturating_add(window.max(1) - 1);
+        let blocks = self.health_blocks(
+            start_slot,
+            start_slot.saturating_add(last_window_end).min(end_slot),
+            deadline,
+        )?;
+
+        let mut series = vec![];
+        let mut window_start = start_slot;
+        while window_start <= end_slot && series.len() < limit {
+            let window_end = window_start.saturating_add(window.max(1) - 1).min(end_slot);
+            series.push(ChainHealth::new(window_start, window_end, &blocks));
+
+            match window_start.checked_add(step.max(1)) {
+                Some(next) => window_start = next,
+                None => break,
+            }
+        }
+
+        Ok(series)
+    }
+}
+
+impl IndexerStore {
+    fn cap_best_global_slot(&self, global_slot: u32) -> anyhow::Result<u32> {
+        Ok(self
+            .get_best_block_global_slot()?
+            .map_or(global_slot, |best| global_slot.min(best)))
+    }
+
+    /// Blocks between the global slots (inclusive), sorted by global slot
+    ///
+    /// The canonical state hash at each block's global slot, date time & min
+    /// window density are read in a single `multi_get_cf`
+    fn health_blocks(
+        &self,
+        start_slot: u32,
+        end_slot: u32,
+        deadline: Option<&Deadline>,
+    ) -> anyhow::Result<Vec<HealthBlock>> {
+        let mut slot_blocks = vec![];
+        let start = start_slot.to_be_bytes();
+
+        for (key, _) in self
+            .blocks_global_slot_iterator(IteratorMode::From(&start, Direction::Forward))
+            .flatten()
+        {
+            check_scan(deadline)?;
+            let global_slot = block_u32_prefix_from_key(&key)?;
+            if global_slot > end_slot {
+                break;
+            }
+            slot_blocks.push((global_slot, state_hash_suffix(&key)?));
+        }
+
+        let keys = slot_blocks.iter().flat_map(|(global_slot, state_hash)| {
+            [
+                (
+                    self.canonicity_slot_cf(),
+                    global_slot.to_be_bytes().to_vec(),
+                ),
+                (self.block_date_time_cf(), state_hash.0.as_bytes().to_vec()),
+                (
+                    self.block_min_window_density_cf(),
+                    state_hash.0.as_bytes().to_vec(),
+                ),
+            ]
+        });
+        let values = self
+            .database
+            .multi_get_cf(keys)
+            .into_iter()
+            .collect::<Result<Vec<_>, _>>()?;
+
+        check_scan(deadline)?;
+        Ok(slot_blocks
+            .into_iter()
+            .zip(values.chunks_exact(3))
+            .map(|((global_slot, state_hash), values)| HealthBlock {
+                global_slot,
+                canonical: values[0].as_deref() == Some(state_hash.0.as_bytes()),
+                date_time: values[1]
+                    .as_deref()
+                    .and_then(|bytes| i64_from_be_bytes(bytes).ok())
+                    .unwrap_or_default(),
+                min_window_density: values[2]
+                    .as_deref()
+                    .and_then(|bytes| u32_from_be_bytes(bytes).ok()),
+            })
+            .collect())
+    }
+}
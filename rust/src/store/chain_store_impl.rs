@@ -24,7 +24,8 @@ impl ChainStore for IndexerStore {
         )?;
 
         // update current chain_id
-        self.database.put(Self::CHAIN_ID_KEY, chain_bytes)?;
+        self.database
+            .put(self.fixed_key(Self::CHAIN_ID_KEY), chain_bytes)?;
         Ok(())
     }
 
@@ -47,7 +48,7 @@ impl ChainStore for IndexerStore {
         trace!("Getting chain id");
         Ok(ChainId::from(
             self.database
-                .get(Self::CHAIN_ID_KEY)?
+                .get(self.fixed_key(Self::CHAIN_ID_KEY))?
                 .expect("chain id should exist in database"),
         ))
     }
@@ -28,6 +28,9 @@ pub trait ColumnFamilyHelpers {
     /// CF for storing block date times
     fn block_date_time_cf(&self) -> &ColumnFamily;
 
+    /// CF for storing block min window densities
+    fn block_min_window_density_cf(&self) -> &ColumnFamily;
+
     /// CF for storing block genesis state hashes
     fn block_genesis_state_hash_cf(&self) -> &ColumnFamily;
 
@@ -97,6 +100,9 @@ pub trait ColumnFamilyHelpers {
     /// CF for storing canonical state hashes by global slot
     fn canonicity_slot_cf(&self) -> &ColumnFamily;
 
+    /// CF for storing best chain reorgs by new best tip height
+    fn canonicity_reorgs_cf(&self) -> &ColumnFamily;
+
     ////////////////////////////
     // User command store CFs //
     ////////////////////////////
@@ -212,6 +218,24 @@ pub trait ColumnFamilyHelpers {
     /// CF for storing best ledger account delegations
     fn best_ledger_accounts_delegations_cf(&self) -> &ColumnFamily;
 
+    /// CF for storing best chain token account balance changes
+    fn best_ledger_account_balance_history_cf(&self) -> &ColumnFamily;
+
+    /// CF for storing best chain account delegation changes
+    fn best_ledger_account_delegation_history_cf(&self) -> &ColumnFamily;
+
+    /// CF for storing the timing of best ledger timed MINA accounts
+    fn best_ledger_timed_accounts_cf(&self) -> &ColumnFamily;
+
+    /// CF for scheduling the next unlock of best ledger timed MINA accounts
+    fn best_ledger_timed_accounts_unlocks_cf(&self) -> &ColumnFamily;
+
+    /// CF for storing best tip supplies sorted by global slot
+    fn best_ledger_block_supply_cf(&self) -> &ColumnFamily;
+
+    /// CF for storing the latest best tip's best ledger wealth distribution
+    fn best_ledger_wealth_distribution_cf(&self) -> &ColumnFamily;
+
     /////////////////////////////
     // Staged ledger store CFs //
     /////////////////////////////
@@ -234,6 +258,9 @@ pub trait ColumnFamilyHelpers {
     /// CF for storing which staged ledgers have been persisted
     fn staged_ledgers_persisted_cf(&self) -> &ColumnFamily;
 
+    /// CF for storing the heights of persisted staged ledgers
+    fn staged_ledgers_persisted_heights_cf(&self) -> &ColumnFamily;
+
     /// CF for tracking when an account was added to the staged ledger
     fn staged_ledger_accounts_min_block_cf(&self) -> &ColumnFamily;
 
@@ -266,6 +293,13 @@ pub trait ColumnFamilyHelpers {
     /// CF for storing staking ledger total currencies
     fn staking_ledger_total_currency_cf(&self) -> &ColumnFamily;
 
+    /// CF for storing staking ledger staked supplies
+    fn staking_ledger_staked_supply_cf(&self) -> &ColumnFamily;
+    fn staking_ledger_source_cf(&self) -> &ColumnFamily;
+
+    /// CF for storing staking ledger wealth distributions
+    fn staking_ledger_wealth_distribution_cf(&self) -> &ColumnFamily;
+
     /// CF for sorting staking ledger accounts by balance
     fn staking_ledger_balance_sort_cf(&self) -> &ColumnFamily;
 
@@ -9,10 +9,11 @@ impl ColumnFamilyHelpers for IndexerStore {
     /// CF for storing blocks
     /// ```
     /// key: [StateHash] bytes
-    /// val: {num block bytes BE u64 bytes}{serde_json block bytes}
+    /// val: {num block bytes BE u64 bytes}{encoding u8}{MessagePack block bytes}
+    ///
+    /// See [crate::utility::store::block::block_value]
     fn blocks_cf(&self) -> &ColumnFamily {
-        self.database
-            .cf_handle("blocks")
+        self.cf_handle("blocks")
             .expect("blocks column family exists")
     }
 
@@ -21,8 +22,7 @@ impl ColumnFamilyHelpers for IndexerStore {
     /// key: [StateHash] bytes
     /// val: {num block bytes BE u64 bytes}{serde_json block bytes}
     fn blocks_state_hash_cf(&self) -> &ColumnFamily {
-        self.database
-            .cf_handle("blocks-state-hash")
+        self.cf_handle("blocks-state-hash")
             .expect("blocks-state-hash column family exists")
     }
 
@@ -31,8 +31,7 @@ impl ColumnFamilyHelpers for IndexerStore {
     /// key: [StateHash] bytes
     /// val: [PcbVersion] serde bytes
     fn block_version_cf(&self) -> &ColumnFamily {
-        self.database
-            .cf_handle("blocks-version")
+        self.cf_handle("blocks-version")
             .expect("blocks-version column family exists")
     }
 
@@ -44,8 +43,7 @@ impl ColumnFamilyHelpers for IndexerStore {
     /// - global_slot: [u32] BE bytes
     /// - state_hash:  [StateHash] bytes
     fn blocks_global_slot_sort_cf(&self) -> &ColumnFamily {
-        self.database
-            .cf_handle("blocks-global-slot-sort")
+        self.cf_handle("blocks-global-slot-sort")
             .expect("blocks-global-slot-sort column family exists")
     }
 
@@ -57,92 +55,86 @@ impl ColumnFamilyHelpers for IndexerStore {
     /// - block_height: [u32] BE bytes
     /// - state_hash:   [StateHash] bytes
     fn blocks_height_sort_cf(&self) -> &ColumnFamily {
-        self.database
-            .cf_handle("blocks-height-sort")
+        self.cf_handle("blocks-height-sort")
             .expect("blocks-height-sort column family exists")
     }
 
     fn block_height_to_global_slots_cf(&self) -> &ColumnFamily {
-        self.database
-            .cf_handle("blocks-height-to-slots")
+        self.cf_handle("blocks-height-to-slots")
             .expect("blocks-height-to-slots column family exists")
     }
 
     fn block_global_slot_to_heights_cf(&self) -> &ColumnFamily {
-        self.database
-            .cf_handle("blocks-slot-to-heights")
+        self.cf_handle("blocks-slot-to-heights")
             .expect("blocks-slot-to-heights column family exists")
     }
 
     fn block_date_time_cf(&self) -> &ColumnFamily {
-        self.database
-            .cf_handle("blocks-date-time")
+        self.cf_handle("blocks-date-time")
             .expect("blocks-date-time column family exists")
     }
 
+    /// CF for storing block min window densities
+    /// ```
+    /// - key: [StateHash] bytes
+    /// - val: [u32] BE bytes
+    fn block_min_window_density_cf(&self) -> &ColumnFamily {
+        self.cf_handle("blocks-min-window-density")
+            .expect("blocks-min-window-density column family exists")
+    }
+
     fn block_parent_hash_cf(&self) -> &ColumnFamily {
-        self.database
-            .cf_handle("blocks-parent-hash")
+        self.cf_handle("blocks-parent-hash")
             .expect("blocks-parent-hash column family exists")
     }
 
     fn block_height_cf(&self) -> &ColumnFamily {
-        self.database
-            .cf_handle("blocks-height")
+        self.cf_handle("blocks-height")
             .expect("blocks-height column family exists")
     }
 
     fn block_global_slot_cf(&self) -> &ColumnFamily {
-        self.database
-            .cf_handle("blocks-global-slot")
+        self.cf_handle("blocks-global-slot")
             .expect("blocks-global-slot column family exists")
     }
 
     fn block_epoch_cf(&self) -> &ColumnFamily {
-        self.database
-            .cf_handle("blocks-epoch")
+        self.cf_handle("blocks-epoch")
             .expect("blocks-epoch column family exists")
     }
 
     fn block_genesis_state_hash_cf(&self) -> &ColumnFamily {
-        self.database
-            .cf_handle("blocks-genesis-hash")
+        self.cf_handle("blocks-genesis-hash")
             .expect("blocks-genesis-hash column family exists")
     }
 
     fn block_creator_cf(&self) -> &ColumnFamily {
-        self.database
-            .cf_handle("blocks-creator")
+        self.cf_handle("blocks-creator")
             .expect("blocks-creator column family exists")
     }
 
     fn block_coinbase_receiver_cf(&self) -> &ColumnFamily {
-        self.database
-            .cf_handle("blocks-coinbase-receiver")
+        self.cf_handle("blocks-coinbase-receiver")
             .expect("blocks-coinbase-receiver column family exists")
     }
 
     fn block_coinbase_height_sort_cf(&self) -> &ColumnFamily {
-        self.database
-            .cf_handle("coinbase-receiver-height-sort")
+        self.cf_handle("coinbase-receiver-height-sort")
             .expect("coinbase-receiver-height-sort column family exists")
     }
 
     fn block_coinbase_slot_sort_cf(&self) -> &ColumnFamily {
-        self.database
-            .cf_handle("coinbase-receiver-slot-sort")
+        self.cf_handle("coinbase-receiver-slot-sort")
             .expect("coinbase-receiver-slot-sort column family exists")
     }
 
     fn block_creator_height_sort_cf(&self) -> &ColumnFamily {
-        self.database
-            .cf_handle("block-creator-height-sort")
+        self.cf_handle("block-creator-height-sort")
             .expect("block-creator-height-sort column family exists")
     }
 
     fn block_creator_slot_sort_cf(&self) -> &ColumnFamily {
-        self.database
-            .cf_handle("block-creator-slot-sort")
+        self.cf_handle("block-creator-slot-sort")
             .expect("block-creator-slot-sort column family exists")
     }
 
@@ -151,8 +143,7 @@ impl ColumnFamilyHelpers for IndexerStore {
     ///
     /// - `list of state hashes at height`: sorted from best to worst
     fn blocks_at_height_cf(&self) -> &ColumnFamily {
-        self.database
-            .cf_handle("blocks-at-length")
+        self.cf_handle("blocks-at-length")
             .expect("blocks-at-length column family exists")
     }
 
@@ -161,14 +152,12 @@ impl ColumnFamilyHelpers for IndexerStore {
     ///
     /// - `list of state hashes at slot`: sorted from best to worst
     fn blocks_at_global_slot_cf(&self) -> &ColumnFamily {
-        self.database
-            .cf_handle("blocks-at-slot")
+        self.cf_handle("blocks-at-slot")
             .expect("blocks-at-slot column family exists")
     }
 
     fn block_comparison_cf(&self) -> &ColumnFamily {
-        self.database
-            .cf_handle("blocks-comparison")
+        self.cf_handle("blocks-comparison")
             .expect("blocks-comparison column family exists")
     }
 
@@ -180,8 +169,7 @@ impl ColumnFamilyHelpers for IndexerStore {
     /// - epoch: [u32] BE bytes
     /// - slot:  [u32] BE bytes
     fn block_epoch_slots_produced_cf(&self) -> &ColumnFamily {
-        self.database
-            .cf_handle("block-epoch-slots-produced")
+        self.cf_handle("block-epoch-slots-produced")
             .expect("block-epoch-slots-produced column family exists")
     }
 
@@ -196,8 +184,7 @@ impl ColumnFamilyHelpers for IndexerStore {
     /// ```
     /// Use [epoch_pk_num_key]
     fn block_pk_epoch_slots_produced_cf(&self) -> &ColumnFamily {
-        self.database
-            .cf_handle("block-pk-epoch-slots-produced")
+        self.cf_handle("block-pk-epoch-slots-produced")
             .expect("block-pk-epoch-slots-produced column family exists")
     }
 
@@ -209,8 +196,7 @@ impl ColumnFamilyHelpers for IndexerStore {
     /// - pk:    [PublicKey] bytes
     /// - count: [u32] BE bytes
     fn blocks_pk_count_cf(&self) -> &ColumnFamily {
-        self.database
-            .cf_handle("blocks-pk-count")
+        self.cf_handle("blocks-pk-count")
             .expect("blocks-pk-count column family exists")
     }
 
@@ -219,44 +205,37 @@ impl ColumnFamilyHelpers for IndexerStore {
     ////////////////////////////
 
     fn user_commands_pk_cf(&self) -> &ColumnFamily {
-        self.database
-            .cf_handle("user-commands-pk")
+        self.cf_handle("user-commands-pk")
             .expect("user-commands-pk column family exists")
     }
 
     fn user_commands_pk_num_cf(&self) -> &ColumnFamily {
-        self.database
-            .cf_handle("user-commands-pk-num")
+        self.cf_handle("user-commands-pk-num")
             .expect("user-commands-pk-num column family exists")
     }
 
     fn user_command_state_hashes_cf(&self) -> &ColumnFamily {
-        self.database
-            .cf_handle("user-command-state-hashes")
+        self.cf_handle("user-command-state-hashes")
             .expect("user-command-state-hashes column family exists")
     }
 
     fn user_commands_cf(&self) -> &ColumnFamily {
-        self.database
-            .cf_handle("user-commands")
+        self.cf_handle("user-commands")
             .expect("user-commands column family exists")
     }
 
     fn user_commands_per_block_cf(&self) -> &ColumnFamily {
-        self.database
-            .cf_handle("user-commands-block")
+        self.cf_handle("user-commands-block")
             .expect("user-commands-block column family exists")
     }
 
     fn user_commands_block_order_cf(&self) -> &ColumnFamily {
-        self.database
-            .cf_handle("user-commands-block-order")
+        self.cf_handle("user-commands-block-order")
             .expect("user-commands-block-order column family exists")
     }
 
     fn user_commands_num_containing_blocks_cf(&self) -> &ColumnFamily {
-        self.database
-            .cf_handle("user-commands-num-blocks")
+        self.cf_handle("user-commands-num-blocks")
             .expect("user-commands-num-blocks column family exists")
     }
 
@@ -269,8 +248,7 @@ impl ColumnFamilyHelpers for IndexerStore {
     /// - txn_hash:   [TxnHash::V1_LEN] bytes
     /// - state_hash: [StateHash] bytes
     fn user_commands_height_sort_cf(&self) -> &ColumnFamily {
-        self.database
-            .cf_handle("user-commands-height-sort")
+        self.cf_handle("user-commands-height-sort")
             .expect("user-commands-height-sort column family exists")
     }
 
@@ -283,8 +261,7 @@ impl ColumnFamilyHelpers for IndexerStore {
     /// - txn_hash:   [TxnHash::V1_LEN] bytes
     /// - state_hash: [StateHash] bytes
     fn user_commands_slot_sort_cf(&self) -> &ColumnFamily {
-        self.database
-            .cf_handle("user-commands-slot-sort")
+        self.cf_handle("user-commands-slot-sort")
             .expect("user-commands-slot-sort column family exists")
     }
 
@@ -296,8 +273,7 @@ impl ColumnFamilyHelpers for IndexerStore {
     /// - txn_hash:     [TxnHash::V1_LEN] bytes
     /// - block_height: [u32] BE bytes
     fn user_commands_txn_hash_to_block_height_cf(&self) -> &ColumnFamily {
-        self.database
-            .cf_handle("user-commands-to-block-height")
+        self.cf_handle("user-commands-to-block-height")
             .expect("user-commands-to-block-height column family exists")
     }
 
@@ -309,8 +285,7 @@ impl ColumnFamilyHelpers for IndexerStore {
     /// - txn_hash:    [TxnHash::V1_LEN] bytes
     /// - global_slot: [u32] BE bytes
     fn user_commands_txn_hash_to_global_slot_cf(&self) -> &ColumnFamily {
-        self.database
-            .cf_handle("user-commands-to-global-slot")
+        self.cf_handle("user-commands-to-global-slot")
             .expect("user-commands-to-global-slot column family exists")
     }
 
@@ -325,8 +300,7 @@ impl ColumnFamilyHelpers for IndexerStore {
     /// - state_hash:  [StateHash] bytes
     /// - amount:      [u64] BE bytes
     fn txn_from_slot_sort_cf(&self) -> &ColumnFamily {
-        self.database
-            .cf_handle("txn-from-slot-sort")
+        self.cf_handle("txn-from-slot-sort")
             .expect("txn-from-slot-sort column family exists")
     }
 
@@ -342,8 +316,7 @@ impl ColumnFamilyHelpers for IndexerStore {
     /// - state_hash:   [StateHash] bytes
     /// - amount:       [u64] BE bytes
     fn txn_from_height_sort_cf(&self) -> &ColumnFamily {
-        self.database
-            .cf_handle("txn-from-height-sort")
+        self.cf_handle("txn-from-height-sort")
             .expect("txn-from-height-sort column family exists")
     }
 
@@ -358,8 +331,7 @@ impl ColumnFamilyHelpers for IndexerStore {
     /// - state_hash:  [StateHash] bytes
     /// - amount:      [u64] BE bytes
     fn txn_to_slot_sort_cf(&self) -> &ColumnFamily {
-        self.database
-            .cf_handle("txn-to-slot-sort")
+        self.cf_handle("txn-to-slot-sort")
             .expect("txn-to-slot-sort column family exists")
     }
 
@@ -374,8 +346,7 @@ impl ColumnFamilyHelpers for IndexerStore {
     /// - state_hash:   [StateHash] bytes
     /// - amount:       [u64] BE bytes
     fn txn_to_height_sort_cf(&self) -> &ColumnFamily {
-        self.database
-            .cf_handle("txn-to-height-sort")
+        self.cf_handle("txn-to-height-sort")
             .expect("txn-to-height-sort column family exists")
     }
 
@@ -392,8 +363,7 @@ impl ColumnFamilyHelpers for IndexerStore {
     /// - pk:    [PublicKey] bytes
     /// - num:   [u32] BE bytes
     fn zkapp_actions_cf(&self) -> &ColumnFamily {
-        self.database
-            .cf_handle("zkapp-actions")
+        self.cf_handle("zkapp-actions")
             .expect("zkapp-actions column family exists")
     }
 
@@ -405,8 +375,7 @@ impl ColumnFamilyHelpers for IndexerStore {
     /// - token: [TokenAddress] bytes
     /// - pk:    [PublicKey] bytes
     fn zkapp_actions_pk_num_cf(&self) -> &ColumnFamily {
-        self.database
-            .cf_handle("zkapp-actions-pk-num")
+        self.cf_handle("zkapp-actions-pk-num")
             .expect("zkapp-actions-pk-num column family exists")
     }
 
@@ -419,8 +388,7 @@ impl ColumnFamilyHelpers for IndexerStore {
     /// - pk:    [PublicKey] bytes
     /// - num:   [u32] BE bytes
     fn zkapp_events_cf(&self) -> &ColumnFamily {
-        self.database
-            .cf_handle("zkapp-events")
+        self.cf_handle("zkapp-events")
             .expect("zkapp-events column family exists")
     }
 
@@ -432,8 +400,7 @@ impl ColumnFamilyHelpers for IndexerStore {
     /// - token: [TokenAddress] bytes
     /// - pk:    [PublicKey] bytes
     fn zkapp_events_pk_num_cf(&self) -> &ColumnFamily {
-        self.database
-            .cf_handle("zkapp-events-pk-num")
+        self.cf_handle("zkapp-events-pk-num")
             .expect("zkapp-events-pk-num column family exists")
     }
 
@@ -449,8 +416,7 @@ impl ColumnFamilyHelpers for IndexerStore {
     /// - state_hash: [StateHash] bytes
     /// - index:      [u32] BE bytes
     fn internal_commands_cf(&self) -> &ColumnFamily {
-        self.database
-            .cf_handle("internal-commands")
+        self.cf_handle("internal-commands")
             .expect("internal-commands column family exists")
     }
 
@@ -459,8 +425,7 @@ impl ColumnFamilyHelpers for IndexerStore {
     /// - key: [StateHash] bytes
     /// - val: [u32] BE bytes
     fn internal_commands_block_num_cf(&self) -> &ColumnFamily {
-        self.database
-            .cf_handle("internal-commands-block-num")
+        self.cf_handle("internal-commands-block-num")
             .expect("internal-commands-block-num column family exists")
     }
 
@@ -472,8 +437,7 @@ impl ColumnFamilyHelpers for IndexerStore {
     /// - receiver: [PublicKey] bytes
     /// - index:    [u32] BE bytes
     fn internal_commands_pk_cf(&self) -> &ColumnFamily {
-        self.database
-            .cf_handle("internal-commands-pk")
+        self.cf_handle("internal-commands-pk")
             .expect("internal-commands-pk column family exists")
     }
 
@@ -482,8 +446,7 @@ impl ColumnFamilyHelpers for IndexerStore {
     /// - key: [PublicKey] bytes
     /// - val: [u32] BE bytes
     fn internal_commands_pk_num_cf(&self) -> &ColumnFamily {
-        self.database
-            .cf_handle("internal-commands-pk-num")
+        self.cf_handle("internal-commands-pk-num")
             .expect("internal-commands-pk-num column family exists")
     }
 
@@ -497,8 +460,7 @@ impl ColumnFamilyHelpers for IndexerStore {
     /// - index:        [u32] BE bytes
     /// - kind:         0, 1, or 2
     fn internal_commands_block_height_sort_cf(&self) -> &ColumnFamily {
-        self.database
-            .cf_handle("internal-commands-block-height-sort")
+        self.cf_handle("internal-commands-block-height-sort")
             .expect("internal-commands-block-height-sort column family exists")
     }
 
@@ -512,8 +474,7 @@ impl ColumnFamilyHelpers for IndexerStore {
     /// - index:       [u32] BE bytes
     /// - kind:        0, 1, or 2
     fn internal_commands_global_slot_sort_cf(&self) -> &ColumnFamily {
-        self.database
-            .cf_handle("internal-commands-global-slot-sort")
+        self.cf_handle("internal-commands-global-slot-sort")
             .expect("internal-commands-global-slot-sort column family exists")
     }
 
@@ -527,8 +488,7 @@ impl ColumnFamilyHelpers for IndexerStore {
     /// - state_hash:   [StateHash] bytes
     /// - index:        [u32] BE bytes
     fn internal_commands_pk_block_height_sort_cf(&self) -> &ColumnFamily {
-        self.database
-            .cf_handle("internal-commands-pk-block-height-sort")
+        self.cf_handle("internal-commands-pk-block-height-sort")
             .expect("internal-commands-pk-block-height-sort column family exists")
     }
 
@@ -542,8 +502,7 @@ impl ColumnFamilyHelpers for IndexerStore {
     /// - state_hash:  [StateHash] bytes
     /// - index:       [u32] BE bytes
     fn internal_commands_pk_global_slot_sort_cf(&self) -> &ColumnFamily {
-        self.database
-            .cf_handle("internal-commands-pk-global-slot-sort")
+        self.cf_handle("internal-commands-pk-global-slot-sort")
             .expect("internal-commands-pk-global-slot-sort column family exists")
     }
 
@@ -552,17 +511,27 @@ impl ColumnFamilyHelpers for IndexerStore {
     //////////////////////////
 
     fn canonicity_length_cf(&self) -> &ColumnFamily {
-        self.database
-            .cf_handle("canonicity-length")
+        self.cf_handle("canonicity-length")
             .expect("canonicity-length column family exists")
     }
 
     fn canonicity_slot_cf(&self) -> &ColumnFamily {
-        self.database
-            .cf_handle("canonicity-slot")
+        self.cf_handle("canonicity-slot")
             .expect("canonicity-slot column family exists")
     }
 
+    /// CF for storing best chain reorgs by new best tip height
+    /// ```
+    /// - key: {height}{state_hash}
+    /// - val: [Reorg] serde bytes
+    /// where
+    /// - height:     new best tip [u32] BE bytes
+    /// - state_hash: new best tip [StateHash] bytes
+    fn canonicity_reorgs_cf(&self) -> &ColumnFamily {
+        self.cf_handle("canonicity-reorgs")
+            .expect("canonicity-reorgs column family exists")
+    }
+
     ///////////////////////////
     // Best ledger store CFs //
     ///////////////////////////
@@ -575,8 +544,7 @@ impl ColumnFamilyHelpers for IndexerStore {
     /// - token:   [TokenAddress] bytes
     /// - pk:      [PublicKey] bytes
     fn best_ledger_accounts_cf(&self) -> &ColumnFamily {
-        self.database
-            .cf_handle("best-ledger-accounts")
+        self.cf_handle("best-ledger-accounts")
             .expect("best-ledger-accounts column family exists")
     }
 
@@ -589,8 +557,7 @@ impl ColumnFamilyHelpers for IndexerStore {
     /// - balance: [u64] BE bytes
     /// - pk:      [PublicKey] bytes
     fn best_ledger_accounts_balance_sort_cf(&self) -> &ColumnFamily {
-        self.database
-            .cf_handle("best-ledger-account-balance-sort")
+        self.cf_handle("best-ledger-account-balance-sort")
             .expect("best-ledger-account-balance-sort column family exists")
     }
 
@@ -602,8 +569,7 @@ impl ColumnFamilyHelpers for IndexerStore {
     /// - token:   [TokenAddress] bytes
     /// - pk:      [PublicKey] bytes
     fn zkapp_best_ledger_accounts_cf(&self) -> &ColumnFamily {
-        self.database
-            .cf_handle("zkapp-best-ledger-accounts")
+        self.cf_handle("zkapp-best-ledger-accounts")
             .expect("zkapp-best-ledger-accounts column family exists")
     }
 
@@ -616,8 +582,7 @@ impl ColumnFamilyHelpers for IndexerStore {
     /// - balance: [u64] BE bytes
     /// - pk:      [PublicKey] bytes
     fn zkapp_best_ledger_accounts_balance_sort_cf(&self) -> &ColumnFamily {
-        self.database
-            .cf_handle("zkapp-best-ledger-account-balance-sort")
+        self.cf_handle("zkapp-best-ledger-account-balance-sort")
             .expect("zkapp-best-ledger-account-balance-sort column family exists")
     }
 
@@ -628,8 +593,7 @@ impl ColumnFamilyHelpers for IndexerStore {
     /// - pk:  [PublicKey] bytes
     /// - num: [u32] BE bytes
     fn best_ledger_accounts_num_delegations_cf(&self) -> &ColumnFamily {
-        self.database
-            .cf_handle("best-ledger-account-num-delegations")
+        self.cf_handle("best-ledger-account-num-delegations")
             .expect("best-ledger-account-num-delegations column family exists")
     }
 
@@ -640,11 +604,71 @@ impl ColumnFamilyHelpers for IndexerStore {
     /// - pk:  [PublicKey] bytes
     /// - num: [u32] BE bytes
     fn best_ledger_accounts_delegations_cf(&self) -> &ColumnFamily {
-        self.database
-            .cf_handle("best-ledger-account-delegations")
+        self.cf_handle("best-ledger-account-delegations")
             .expect("best-ledger-account-delegations column family exists")
     }
 
+    /// CF for storing best chain token account balance changes
+    /// ```
+    /// {token}{pk}{height} -> [BalanceChange] serde bytes
+    /// where
+    /// - token:  [TokenAddress] bytes
+    /// - pk:     [PublicKey] bytes
+    /// - height: [u32] BE bytes
+    fn best_ledger_account_balance_history_cf(&self) -> &ColumnFamily {
+        self.cf_handle("best-ledger-account-balance-history")
+            .expect("best-ledger-account-balance-history column family exists")
+    }
+
+    /// CF for storing best chain account delegation changes
+    /// ```
+    /// - key: [delegation_history_key]
+    /// - val: [DelegationChange] serde bytes
+    fn best_ledger_account_delegation_history_cf(&self) -> &ColumnFamily {
+        self.cf_handle("best-ledger-account-delegation-history")
+            .expect("best-ledger-account-delegation-history column family exists")
+    }
+
+    /// CF for storing the timing of best ledger timed MINA accounts
+    /// ```
+    /// - key: [PublicKey] bytes
+    /// - val: [Timing] serde bytes
+    fn best_ledger_timed_accounts_cf(&self) -> &ColumnFamily {
+        self.cf_handle("best-ledger-timed-accounts")
+            .expect("best-ledger-timed-accounts column family exists")
+    }
+
+    /// CF for scheduling the next unlock of best ledger timed MINA accounts
+    /// ```
+    /// {next_unlock_slot}{pk} -> _
+    /// where
+    /// - next_unlock_slot: [u32] BE bytes
+    /// - pk:               [PublicKey] bytes
+    fn best_ledger_timed_accounts_unlocks_cf(&self) -> &ColumnFamily {
+        self.cf_handle("best-ledger-timed-accounts-unlocks")
+            .expect("best-ledger-timed-accounts-unlocks column family exists")
+    }
+
+    /// CF for storing best tip supplies sorted by global slot
+    /// ```
+    /// {global_slot}{state_hash} -> [BlockSupply] serde bytes
+    /// where
+    /// - global_slot: [u32] BE bytes
+    /// - state_hash:  [StateHash] bytes
+    fn best_ledger_block_supply_cf(&self) -> &ColumnFamily {
+        self.cf_handle("best-ledger-block-supply")
+            .expect("best-ledger-block-supply column family exists")
+    }
+
+    /// CF for storing the latest best tip's best ledger wealth distribution
+    /// ```
+    /// - key: [StateHash] bytes
+    /// - val: [WealthDistribution] serde bytes
+    fn best_ledger_wealth_distribution_cf(&self) -> &ColumnFamily {
+        self.cf_handle("best-ledger-wealth-distribution")
+            .expect("best-ledger-wealth-distribution column family exists")
+    }
+
     /////////////////////////////
     // Staged ledger store CFs //
     /////////////////////////////
@@ -658,8 +682,7 @@ impl ColumnFamilyHelpers for IndexerStore {
     /// - pk:         [PublicKey] bytes
     /// - account:    [Account] serde bytes
     fn staged_ledger_accounts_cf(&self) -> &ColumnFamily {
-        self.database
-            .cf_handle("staged-ledger-accounts")
+        self.cf_handle("staged-ledger-accounts")
             .expect("staged-ledger-accounts column family exists")
     }
 
@@ -672,8 +695,7 @@ impl ColumnFamilyHelpers for IndexerStore {
     /// - balance:    [u64] BE bytes
     /// - pk:         [PublicKey] bytes
     fn staged_ledger_account_balance_sort_cf(&self) -> &ColumnFamily {
-        self.database
-            .cf_handle("staged-ledger-account-balance-sort")
+        self.cf_handle("staged-ledger-account-balance-sort")
             .expect("staged-ledger-account-balance-sort column family exists")
     }
 
@@ -685,8 +707,7 @@ impl ColumnFamilyHelpers for IndexerStore {
     /// - pk:         [PublicKey] bytes
     /// - num:        [u32] BE bytes
     fn staged_ledger_account_num_delegations_cf(&self) -> &ColumnFamily {
-        self.database
-            .cf_handle("staged-ledger-account-num-delegations")
+        self.cf_handle("staged-ledger-account-num-delegations")
             .expect("staged-ledger-account-num-delegations column family exists")
     }
 
@@ -699,29 +720,34 @@ impl ColumnFamilyHelpers for IndexerStore {
     /// - num:        [u32] BE bytes
     /// - delegate:   [PublicKey] bytes
     fn staged_ledger_account_delegations_cf(&self) -> &ColumnFamily {
-        self.database
-            .cf_handle("staged-ledger-account-delegations")
+        self.cf_handle("staged-ledger-account-delegations")
             .expect("staged-ledger-account-delegations column family exists")
     }
 
     /// CF for storing staged ledger hash -> block state hash
     fn staged_ledger_hash_to_block_cf(&self) -> &ColumnFamily {
-        self.database
-            .cf_handle("staged-ledger-hash-to-block")
+        self.cf_handle("staged-ledger-hash-to-block")
             .expect("staged-ledger-hash-to-block column family exists")
     }
 
     /// CF for keeping track of which staged ledgers have been persisted
     fn staged_ledgers_persisted_cf(&self) -> &ColumnFamily {
-        self.database
-            .cf_handle("staged-ledger-persisted")
+        self.cf_handle("staged-ledger-persisted")
             .expect("staged-ledger-persisted column family exists")
     }
 
+    /// CF for sorting persisted staged ledgers by block height
+    /// ```
+    /// key: {height BE}{state hash}
+    /// val: b""
+    fn staged_ledgers_persisted_heights_cf(&self) -> &ColumnFamily {
+        self.cf_handle("staged-ledger-persisted-heights")
+            .expect("staged-ledger-persisted-heights column family exists")
+    }
+
     /// CF for tracking when an account was added to the staged ledger
     fn staged_ledger_accounts_min_block_cf(&self) -> &ColumnFamily {
-        self.database
-            .cf_handle("staged-ledger-accounts-min-block")
+        self.cf_handle("staged-ledger-accounts-min-block")
             .expect("staged-ledger-accounts-min-block column family exists")
     }
 
@@ -730,14 +756,12 @@ impl ColumnFamilyHelpers for IndexerStore {
     /// key: [StateHash] bytes
     /// val: [LedgerDiff] serde bytes
     fn block_ledger_diff_cf(&self) -> &ColumnFamily {
-        self.database
-            .cf_handle("blocks-ledger-diff")
+        self.cf_handle("blocks-ledger-diff")
             .expect("blocks-ledger-diff column family exists")
     }
 
     fn block_staged_ledger_hash_cf(&self) -> &ColumnFamily {
-        self.database
-            .cf_handle("blocks-staged-ledger-hash")
+        self.cf_handle("blocks-staged-ledger-hash")
             .expect("blocks-staged-ledger-hash column family exists")
     }
 
@@ -750,8 +774,7 @@ impl ColumnFamilyHelpers for IndexerStore {
     /// - key: [staking_ledger_account_key]
     /// - val: [Account] serde bytes
     fn staking_ledger_accounts_cf(&self) -> &ColumnFamily {
-        self.database
-            .cf_handle("staking-ledger-accounts")
+        self.cf_handle("staking-ledger-accounts")
             .expect("staking-ledger-accounts column family exists")
     }
 
@@ -760,8 +783,7 @@ impl ColumnFamilyHelpers for IndexerStore {
     /// - key: [staking_ledger_account_key]
     /// - val: aggregated epoch delegations serde bytes
     fn staking_delegations_cf(&self) -> &ColumnFamily {
-        self.database
-            .cf_handle("staking-ledger-delegations")
+        self.cf_handle("staking-ledger-delegations")
             .expect("staking-ledger-delegations column family exists")
     }
 
@@ -770,8 +792,7 @@ impl ColumnFamilyHelpers for IndexerStore {
     /// - key: [staking_ledger_epoch_key]
     /// - val: b""
     fn staking_ledger_persisted_cf(&self) -> &ColumnFamily {
-        self.database
-            .cf_handle("staking-ledger-persisted")
+        self.cf_handle("staking-ledger-persisted")
             .expect("staking-ledger-persisted column family exists")
     }
 
@@ -780,8 +801,7 @@ impl ColumnFamilyHelpers for IndexerStore {
     /// - key: [staking_ledger_epoch_key_prefix]
     /// - val: [LedgerHash] bytes
     fn staking_ledger_epoch_to_hash_cf(&self) -> &ColumnFamily {
-        self.database
-            .cf_handle("staking-ledger-epoch-to-hash")
+        self.cf_handle("staking-ledger-epoch-to-hash")
             .expect("staking-ledger-epoch-to-hash column family exists")
     }
 
@@ -790,8 +810,7 @@ impl ColumnFamilyHelpers for IndexerStore {
     /// - key: [LedgerHash] bytes
     /// - val: epoch ([u32] BE bytes)
     fn staking_ledger_hash_to_epoch_cf(&self) -> &ColumnFamily {
-        self.database
-            .cf_handle("staking-ledger-hash-to-epoch")
+        self.cf_handle("staking-ledger-hash-to-epoch")
             .expect("staking-ledger-hash-to-epoch column family exists")
     }
 
@@ -800,8 +819,7 @@ impl ColumnFamilyHelpers for IndexerStore {
     /// - key: [LedgerHash] bytes
     /// - val: [StateHash] bytes
     fn staking_ledger_genesis_hash_cf(&self) -> &ColumnFamily {
-        self.database
-            .cf_handle("staking-ledger-genesis-hash")
+        self.cf_handle("staking-ledger-genesis-hash")
             .expect("staking-ledger-genesis-hash column family exists")
     }
 
@@ -810,18 +828,43 @@ impl ColumnFamilyHelpers for IndexerStore {
     /// - key: [LedgerHash] bytes
     /// - val: [u64] BE bytes
     fn staking_ledger_total_currency_cf(&self) -> &ColumnFamily {
-        self.database
-            .cf_handle("staking-ledger-total-currency")
+        self.cf_handle("staking-ledger-total-currency")
             .expect("staking-ledger-total-currency column family exists")
     }
 
+    /// CF for storing staking ledger staked supplies
+    /// ```
+    /// - key: [LedgerHash] bytes
+    /// - val: [u64] BE bytes
+    fn staking_ledger_staked_supply_cf(&self) -> &ColumnFamily {
+        self.cf_handle("staking-ledger-staked-supply")
+            .expect("staking-ledger-staked-supply column family exists")
+    }
+
+    /// CF for storing staking ledger sources (imported or derived)
+    /// ```
+    /// - key: [LedgerHash] bytes
+    /// - val: [StakingLedgerSource] serde bytes
+    fn staking_ledger_source_cf(&self) -> &ColumnFamily {
+        self.cf_handle("staking-ledger-source")
+            .expect("staking-ledger-source column family exists")
+    }
+
+    /// CF for storing staking ledger wealth distributions
+    /// ```
+    /// - key: [LedgerHash] bytes
+    /// - val: [WealthDistribution] serde bytes
+    fn staking_ledger_wealth_distribution_cf(&self) -> &ColumnFamily {
+        self.cf_handle("staking-ledger-wealth-distribution")
+            .expect("staking-ledger-wealth-distribution column family exists")
+    }
+
     /// CF for sorting staking ledger accounts by balance
     /// ```
     /// - key: [staking_ledger_sort_key]
     /// - val: b""
     fn staking_ledger_balance_sort_cf(&self) -> &ColumnFamily {
-        self.database
-            .cf_handle("staking-ledger-balance-sort")
+        self.cf_handle("staking-ledger-balance-sort")
             .expect("staking-ledger-balance-sort column family exists")
     }
 
@@ -830,8 +873,7 @@ impl ColumnFamilyHelpers for IndexerStore {
     /// - key: [staking_ledger_sort_key]
     /// - val: b""
     fn staking_ledger_stake_sort_cf(&self) -> &ColumnFamily {
-        self.database
-            .cf_handle("staking-ledger-stake-sort")
+        self.cf_handle("staking-ledger-stake-sort")
             .expect("staking-ledger-stake-sort column family exists")
     }
 
@@ -840,8 +882,7 @@ impl ColumnFamilyHelpers for IndexerStore {
     /// - key: epoch ([u32] BE bytes)
     /// - value: number of staking ledger accounts in epoch ([u32] BE bytes)
     fn staking_ledger_accounts_count_epoch_cf(&self) -> &ColumnFamily {
-        self.database
-            .cf_handle("staking-ledger-accounts-count-epoch")
+        self.cf_handle("staking-ledger-accounts-count-epoch")
             .expect("staking-ledger-accounts-count-epoch column family exists")
     }
 
@@ -858,8 +899,7 @@ impl ColumnFamilyHelpers for IndexerStore {
     /// - index: [u32] BE bytes
     /// - snark: [SnarkWorkSummary] serde bytes
     fn snarks_cf(&self) -> &ColumnFamily {
-        self.database
-            .cf_handle("snarks")
+        self.cf_handle("snarks")
             .expect("snarks column family exists")
     }
 
@@ -872,8 +912,7 @@ impl ColumnFamilyHelpers for IndexerStore {
     /// - index:  [u32] BE bytes
     /// - snark:  [SnarkWorkSummaryWithStateHash] serde bytes
     fn snarks_prover_cf(&self) -> &ColumnFamily {
-        self.database
-            .cf_handle("snarks-prover")
+        self.cf_handle("snarks-prover")
             .expect("snarks-prover column family exists")
     }
 
@@ -885,8 +924,7 @@ impl ColumnFamilyHelpers for IndexerStore {
     /// - prover: [PublicKey] bytes
     /// - fees:   [u64] BE bytes
     fn snark_prover_fees_cf(&self) -> &ColumnFamily {
-        self.database
-            .cf_handle("snark-prover-fees")
+        self.cf_handle("snark-prover-fees")
             .expect("snark-prover-fees column family exists")
     }
 
@@ -899,8 +937,7 @@ impl ColumnFamilyHelpers for IndexerStore {
     /// - prover: [PublicKey] bytes
     /// - fees:   [u64] BE bytes
     fn snark_prover_fees_epoch_cf(&self) -> &ColumnFamily {
-        self.database
-            .cf_handle("snark-prover-fees-epoch")
+        self.cf_handle("snark-prover-fees-epoch")
             .expect("snark-prover-fees-epoch column family exists")
     }
 
@@ -912,8 +949,7 @@ impl ColumnFamilyHelpers for IndexerStore {
     /// - prover: [PublicKey] bytes
     /// - height: [u32] BE bytes
     fn snark_prover_fees_historical_cf(&self) -> &ColumnFamily {
-        self.database
-            .cf_handle("snark-prover-fees-historical")
+        self.cf_handle("snark-prover-fees-historical")
             .expect("snark-prover-fees-historical column family exists")
     }
 
@@ -925,8 +961,7 @@ impl ColumnFamilyHelpers for IndexerStore {
     /// - prover: [PublicKey] bytes
     /// - height: [u32] BE bytes
     fn snark_prover_fees_epoch_historical_cf(&self) -> &ColumnFamily {
-        self.database
-            .cf_handle("snark-prover-fees-epoch-historical")
+        self.cf_handle("snark-prover-fees-epoch-historical")
             .expect("snark-prover-fees-epoch-historical column family exists")
     }
 
@@ -938,8 +973,7 @@ impl ColumnFamilyHelpers for IndexerStore {
     /// - fees:   [u64] BE bytes
     /// - prover: [PublicKey] bytes
     fn snark_prover_total_fees_sort_cf(&self) -> &ColumnFamily {
-        self.database
-            .cf_handle("snark-prover-total-fees-sort")
+        self.cf_handle("snark-prover-total-fees-sort")
             .expect("snark-prover-total-fees-sort column family exists")
     }
 
@@ -952,8 +986,7 @@ impl ColumnFamilyHelpers for IndexerStore {
     /// - fees:   [u64] BE bytes
     /// - prover: [PublicKey] bytes
     fn snark_prover_total_fees_epoch_sort_cf(&self) -> &ColumnFamily {
-        self.database
-            .cf_handle("snark-prover-total-fees-epoch-sort")
+        self.cf_handle("snark-prover-total-fees-epoch-sort")
             .expect("snark-prover-total-fees-epoch-sort column family exists")
     }
 
@@ -965,8 +998,7 @@ impl ColumnFamilyHelpers for IndexerStore {
     /// - prover: [PublicKey] bytes
     /// - fee:    [u64] BE bytes
     fn snark_prover_max_fee_cf(&self) -> &ColumnFamily {
-        self.database
-            .cf_handle("snark-prover-max-fee")
+        self.cf_handle("snark-prover-max-fee")
             .expect("snark-prover-max-fee column family exists")
     }
 
@@ -979,8 +1011,7 @@ impl ColumnFamilyHelpers for IndexerStore {
     /// - prover: [PublicKey] bytes
     /// - fee:    [u64] BE bytes
     fn snark_prover_max_fee_epoch_cf(&self) -> &ColumnFamily {
-        self.database
-            .cf_handle("snark-prover-max-fee-epoch")
+        self.cf_handle("snark-prover-max-fee-epoch")
             .expect("snark-prover-max-fee-epoch column family exists")
     }
 
@@ -994,8 +1025,7 @@ impl ColumnFamilyHelpers for IndexerStore {
     /// ```
     /// Use [snark_fee_sort_key]
     fn snark_prover_max_fee_sort_cf(&self) -> &ColumnFamily {
-        self.database
-            .cf_handle("snark-prover-max-fee-sort")
+        self.cf_handle("snark-prover-max-fee-sort")
             .expect("snark-prover-max-fee-sort column family exists")
     }
 
@@ -1010,8 +1040,7 @@ impl ColumnFamilyHelpers for IndexerStore {
     /// ```
     /// Use [snark_fee_epoch_sort_key]
     fn snark_prover_max_fee_epoch_sort_cf(&self) -> &ColumnFamily {
-        self.database
-            .cf_handle("snark-prover-max-fee-epoch-sort")
+        self.cf_handle("snark-prover-max-fee-epoch-sort")
             .expect("snark-prover-max-fee-epoch-sort column family exists")
     }
 
@@ -1023,8 +1052,7 @@ impl ColumnFamilyHelpers for IndexerStore {
     /// - prover: [PublicKey] bytes
     /// - fee:    [u64] BE bytes
     fn snark_prover_min_fee_cf(&self) -> &ColumnFamily {
-        self.database
-            .cf_handle("snark-prover-min-fee")
+        self.cf_handle("snark-prover-min-fee")
             .expect("snark-prover-min-fee column family exists")
     }
 
@@ -1037,8 +1065,7 @@ impl ColumnFamilyHelpers for IndexerStore {
     /// - prover: [PublicKey] bytes
     /// - fee:    [u64] BE bytes
     fn snark_prover_min_fee_epoch_cf(&self) -> &ColumnFamily {
-        self.database
-            .cf_handle("snark-prover-min-fee-epoch")
+        self.cf_handle("snark-prover-min-fee-epoch")
             .expect("snark-prover-min-fee-epoch column family exists")
     }
 
@@ -1052,8 +1079,7 @@ impl ColumnFamilyHelpers for IndexerStore {
     /// ```
     /// Use [snark_fee_sort_key]
     fn snark_prover_min_fee_sort_cf(&self) -> &ColumnFamily {
-        self.database
-            .cf_handle("snark-prover-min-fee-sort")
+        self.cf_handle("snark-prover-min-fee-sort")
             .expect("snark-prover-min-fee-sort column family exists")
     }
 
@@ -1068,8 +1094,7 @@ impl ColumnFamilyHelpers for IndexerStore {
     /// ```
     /// Use [snark_fee_epoch_sort_key]
     fn snark_prover_min_fee_epoch_sort_cf(&self) -> &ColumnFamily {
-        self.database
-            .cf_handle("snark-prover-min-fee-epoch-sort")
+        self.cf_handle("snark-prover-min-fee-epoch-sort")
             .expect("snark-prover-min-fee-epoch-sort column family exists")
     }
 
@@ -1083,8 +1108,7 @@ impl ColumnFamilyHelpers for IndexerStore {
     /// - index:        [u32] BE bytes
     /// - snark:        [SnarkWorkSummary] serde bytes
     fn snark_prover_block_height_sort_cf(&self) -> &ColumnFamily {
-        self.database
-            .cf_handle("snark-prover-block-height-sort")
+        self.cf_handle("snark-prover-block-height-sort")
             .expect("snark-prover-block-height-sort column family exists")
     }
 
@@ -1098,8 +1122,7 @@ impl ColumnFamilyHelpers for IndexerStore {
     /// - index:       [u32] BE bytes
     /// - snark:       [SnarkWorkSummary] serde bytes
     fn snark_prover_global_slot_sort_cf(&self) -> &ColumnFamily {
-        self.database
-            .cf_handle("snark-prover-global-slot-sort")
+        self.cf_handle("snark-prover-global-slot-sort")
             .expect("snark-prover-global-slot-sort column family exists")
     }
 
@@ -1115,8 +1138,7 @@ impl ColumnFamilyHelpers for IndexerStore {
     /// ```
     /// Use [snark_fee_sort_key]
     fn snark_work_fees_block_height_sort_cf(&self) -> &ColumnFamily {
-        self.database
-            .cf_handle("snark-work-fees-block-height-sort")
+        self.cf_handle("snark-work-fees-block-height-sort")
             .expect("snark-work-fees-block-height-sort column family exists")
     }
 
@@ -1132,8 +1154,7 @@ impl ColumnFamilyHelpers for IndexerStore {
     /// ```
     /// Use [snark_fee_sort_key]
     fn snark_work_fees_global_slot_sort_cf(&self) -> &ColumnFamily {
-        self.database
-            .cf_handle("snark-work-fees-global-slot-sort")
+        self.cf_handle("snark-work-fees-global-slot-sort")
             .expect("snark-work-fees-global-slot-sort column family exists")
     }
 
@@ -1142,21 +1163,18 @@ impl ColumnFamilyHelpers for IndexerStore {
     ////////////////////////
 
     fn username_pk_num_cf(&self) -> &ColumnFamily {
-        self.database
-            .cf_handle("username-pk-num")
+        self.cf_handle("username-pk-num")
             .expect("username-pk-num column family exists")
     }
 
     fn username_pk_index_cf(&self) -> &ColumnFamily {
-        self.database
-            .cf_handle("username-pk-index")
+        self.cf_handle("username-pk-index")
             .expect("username-pk-index column family exists")
     }
 
     /// CF for storing state hash -> usernames
     fn usernames_per_block_cf(&self) -> &ColumnFamily {
-        self.database
-            .cf_handle("usernames-per-block")
+        self.cf_handle("usernames-per-block")
             .expect("usernames-per-block column family exists")
     }
 
@@ -1175,8 +1193,7 @@ impl ColumnFamilyHelpers for IndexerStore {
     /////////////////////
 
     fn events_cf(&self) -> &ColumnFamily {
-        self.database
-            .cf_handle("events")
+        self.cf_handle("events")
             .expect("events column family exists")
     }
 
@@ -1189,8 +1206,7 @@ impl ColumnFamilyHelpers for IndexerStore {
     /// - key: {epoch BE bytes}{pk}
     /// - value: number of blocks produced by pk in epoch
     fn block_production_pk_epoch_cf(&self) -> &ColumnFamily {
-        self.database
-            .cf_handle("block-production-pk-epoch")
+        self.cf_handle("block-production-pk-epoch")
             .expect("block-production-pk-epoch column family exists")
     }
 
@@ -1199,8 +1215,7 @@ impl ColumnFamilyHelpers for IndexerStore {
     /// - key: {epoch BE bytes}{pk}
     /// - value: number of canonical blocks produced by pk in epoch
     fn block_production_pk_canonical_epoch_cf(&self) -> &ColumnFamily {
-        self.database
-            .cf_handle("block-production-pk-canonical-epoch")
+        self.cf_handle("block-production-pk-canonical-epoch")
             .expect("block-production-pk-canonical-epoch column family exists")
     }
 
@@ -1211,8 +1226,7 @@ impl ColumnFamilyHelpers for IndexerStore {
     /// ```
     /// Use [epoch_block_num_key]
     fn block_production_pk_canonical_epoch_sort_cf(&self) -> &ColumnFamily {
-        self.database
-            .cf_handle("block-production-pk-canonical-epoch-sort")
+        self.cf_handle("block-production-pk-canonical-epoch-sort")
             .expect("block-production-pk-canonical-epoch-sort column family exists")
     }
 
@@ -1221,8 +1235,7 @@ impl ColumnFamilyHelpers for IndexerStore {
     /// - key: {epoch BE bytes}{pk}
     /// - value: number of supercharged blocks produced by pk in epoch
     fn block_production_pk_supercharged_epoch_cf(&self) -> &ColumnFamily {
-        self.database
-            .cf_handle("block-production-pk-supercharged-epoch")
+        self.cf_handle("block-production-pk-supercharged-epoch")
             .expect("block-production-pk-supercharged-epoch column family exists")
     }
 
@@ -1231,8 +1244,7 @@ impl ColumnFamilyHelpers for IndexerStore {
     /// - key: pk
     /// - value: total number of blocks produced by pk
     fn block_production_pk_total_cf(&self) -> &ColumnFamily {
-        self.database
-            .cf_handle("block-production-pk-total")
+        self.cf_handle("block-production-pk-total")
             .expect("block-production-pk-total column family exists")
     }
 
@@ -1241,8 +1253,7 @@ impl ColumnFamilyHelpers for IndexerStore {
     /// - key: pk
     /// - value: total number of canonical blocks produced by pk
     fn block_production_pk_canonical_total_cf(&self) -> &ColumnFamily {
-        self.database
-            .cf_handle("block-production-pk-canonical-total")
+        self.cf_handle("block-production-pk-canonical-total")
             .expect("block-production-pk-canonical-total column family exists")
     }
 
@@ -1251,8 +1262,7 @@ impl ColumnFamilyHelpers for IndexerStore {
     /// - key: pk
     /// - value: total number of supercharged blocks produced by pk
     fn block_production_pk_supercharged_total_cf(&self) -> &ColumnFamily {
-        self.database
-            .cf_handle("block-production-pk-supercharged-total")
+        self.cf_handle("block-production-pk-supercharged-total")
             .expect("block-production-pk-supercharged-total column family exists")
     }
 
@@ -1261,8 +1271,7 @@ impl ColumnFamilyHelpers for IndexerStore {
     /// - key: epoch
     /// - value: number of blocks produced in epoch
     fn block_production_epoch_cf(&self) -> &ColumnFamily {
-        self.database
-            .cf_handle("block-production-epoch")
+        self.cf_handle("block-production-epoch")
             .expect("block-production-epoch column family exists")
     }
 
@@ -1271,8 +1280,7 @@ impl ColumnFamilyHelpers for IndexerStore {
     /// - key: epoch
     /// - value: number of canonical blocks produced in epoch
     fn block_production_canonical_epoch_cf(&self) -> &ColumnFamily {
-        self.database
-            .cf_handle("block-production-canonical-epoch")
+        self.cf_handle("block-production-canonical-epoch")
             .expect("block-production-canonical-epoch column family exists")
     }
 
@@ -1281,8 +1289,7 @@ impl ColumnFamilyHelpers for IndexerStore {
     /// - key: epoch
     /// - value: number of supercharged blocks produced in epoch
     fn block_production_supercharged_epoch_cf(&self) -> &ColumnFamily {
-        self.database
-            .cf_handle("block-production-supercharged-epoch")
+        self.cf_handle("block-production-supercharged-epoch")
             .expect("block-production-supercharged-epoch column family exists")
     }
 
@@ -1290,8 +1297,7 @@ impl ColumnFamilyHelpers for IndexerStore {
     /// - key: state hash
     /// - value: number of SNARKs in block
     fn block_snark_counts_cf(&self) -> &ColumnFamily {
-        self.database
-            .cf_handle("block-snark-counts")
+        self.cf_handle("block-snark-counts")
             .expect("block-snark-counts column family exists")
     }
 
@@ -1300,8 +1306,7 @@ impl ColumnFamilyHelpers for IndexerStore {
     /// - key: state hash
     /// - value: number of user commands in block
     fn block_user_command_counts_cf(&self) -> &ColumnFamily {
-        self.database
-            .cf_handle("block-user-command-counts")
+        self.cf_handle("block-user-command-counts")
             .expect("block-user-command-counts column family exists")
     }
 
@@ -1310,8 +1315,7 @@ impl ColumnFamilyHelpers for IndexerStore {
     /// - key: state hash
     /// - value: number of internal commands in block
     fn block_internal_command_counts_cf(&self) -> &ColumnFamily {
-        self.database
-            .cf_handle("block-internal-command-counts")
+        self.cf_handle("block-internal-command-counts")
             .expect("block-internal-command-counts column family exists")
     }
 
@@ -1320,8 +1324,7 @@ impl ColumnFamilyHelpers for IndexerStore {
     /// key: epoch ([u32] BE bytes)
     /// val: number of slots produced in epoch ([u32] BE bytes)
     fn block_epoch_slots_produced_count_cf(&self) -> &ColumnFamily {
-        self.database
-            .cf_handle("block-epoch-slots-produced-count")
+        self.cf_handle("block-epoch-slots-produced-count")
             .expect("block-epoch-slots-produced-count column family exists")
     }
 
@@ -1336,8 +1339,7 @@ impl ColumnFamilyHelpers for IndexerStore {
     /// ```
     /// Use [epoch_pk_key]
     fn block_pk_epoch_slots_produced_count_cf(&self) -> &ColumnFamily {
-        self.database
-            .cf_handle("block-pk-epoch-slots-produced-count")
+        self.cf_handle("block-pk-epoch-slots-produced-count")
             .expect("block-pk-epoch-slots-produced-count column family exists")
     }
 
@@ -1351,8 +1353,7 @@ impl ColumnFamilyHelpers for IndexerStore {
     /// ```
     /// Use [epoch_block_num_key]
     fn block_pk_epoch_slots_produced_count_sort_cf(&self) -> &ColumnFamily {
-        self.database
-            .cf_handle("block-pk-epoch-slots-produced-count-sort")
+        self.cf_handle("block-pk-epoch-slots-produced-count-sort")
             .expect("block-pk-epoch-slots-produced-count-sort column family exists")
     }
 
@@ -1361,8 +1362,7 @@ impl ColumnFamilyHelpers for IndexerStore {
     /// - key: {epoch BE bytes}{pk}
     /// - value: number of pk user commands in epoch
     fn user_commands_pk_epoch_cf(&self) -> &ColumnFamily {
-        self.database
-            .cf_handle("user-commands-pk-epoch")
+        self.cf_handle("user-commands-pk-epoch")
             .expect("user-commands-pk-epoch column family exists")
     }
 
@@ -1371,8 +1371,7 @@ impl ColumnFamilyHelpers for IndexerStore {
     /// - key: pk
     /// - value: total number of pk user commands
     fn user_commands_pk_total_cf(&self) -> &ColumnFamily {
-        self.database
-            .cf_handle("user-commands-pk-total")
+        self.cf_handle("user-commands-pk-total")
             .expect("user-commands-pk-total column family exists")
     }
 
@@ -1381,8 +1380,7 @@ impl ColumnFamilyHelpers for IndexerStore {
     /// - key: epoch
     /// - value: number of user commands in epoch
     fn user_commands_epoch_cf(&self) -> &ColumnFamily {
-        self.database
-            .cf_handle("user-commands-epoch")
+        self.cf_handle("user-commands-epoch")
             .expect("user-commands-epoch column family exists")
     }
 
@@ -1391,8 +1389,7 @@ impl ColumnFamilyHelpers for IndexerStore {
     /// - key: {epoch BE bytes}{pk}
     /// - value: number of pk internal commands in epoch
     fn internal_commands_pk_epoch_cf(&self) -> &ColumnFamily {
-        self.database
-            .cf_handle("internal-commands-pk-epoch")
+        self.cf_handle("internal-commands-pk-epoch")
             .expect("internal-commands-pk-epoch column family exists")
     }
 
@@ -1401,8 +1398,7 @@ impl ColumnFamilyHelpers for IndexerStore {
     /// - key: pk
     /// - value: total number of pk internal commands
     fn internal_commands_pk_total_cf(&self) -> &ColumnFamily {
-        self.database
-            .cf_handle("internal-commands-pk-total")
+        self.cf_handle("internal-commands-pk-total")
             .expect("internal-commands-pk-total column family exists")
     }
 
@@ -1411,8 +1407,7 @@ impl ColumnFamilyHelpers for IndexerStore {
     /// - key: epoch
     /// - value: number of internal commands in epoch
     fn internal_commands_epoch_cf(&self) -> &ColumnFamily {
-        self.database
-            .cf_handle("internal-commands-epoch")
+        self.cf_handle("internal-commands-epoch")
             .expect("internal-commands-epoch column family exists")
     }
 
@@ -1421,8 +1416,7 @@ impl ColumnFamilyHelpers for IndexerStore {
     /// - key: {epoch BE bytes}{pk}
     /// - value: number of pk SNARKs in epoch
     fn snarks_pk_epoch_cf(&self) -> &ColumnFamily {
-        self.database
-            .cf_handle("snarks-pk-epoch")
+        self.cf_handle("snarks-pk-epoch")
             .expect("snarks-pk-epoch column family exists")
     }
 
@@ -1431,8 +1425,7 @@ impl ColumnFamilyHelpers for IndexerStore {
     /// - key: pk
     /// - value: total number of pk SNARKs
     fn snarks_pk_total_cf(&self) -> &ColumnFamily {
-        self.database
-            .cf_handle("snarks-pk-total")
+        self.cf_handle("snarks-pk-total")
             .expect("snarks-pk-total column family exists")
     }
 
@@ -1441,8 +1434,7 @@ impl ColumnFamilyHelpers for IndexerStore {
     /// - key: epoch
     /// - value: number of SNARKs in epoch
     fn snarks_epoch_cf(&self) -> &ColumnFamily {
-        self.database
-            .cf_handle("snarks-epoch")
+        self.cf_handle("snarks-epoch")
             .expect("snarks-epoch column family exists")
     }
 }
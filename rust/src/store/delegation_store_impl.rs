@@ -0,0 +1,180 @@
+use super::{column_families::ColumnFamilyHelpers, IndexerStore};
+use crate::{
+    base::{public_key::PublicKey, state_hash::StateHash},
+    block::store::{BlockStore, BlockUpdate},
+    command::store::UserCommandStore,
+    ledger::{
+        diff::{account::AccountDiff, LedgerDiff},
+        store::{
+            delegations::{DelegationChange, DelegationFlows, DelegationStore, DelegatorFlow},
+            staking::StakingLedgerStore,
+        },
+    },
+    store::Result,
+    utility::{
+        deadline::{check_scan, Deadline},
+        store::ledger::best::delegation_history_key,
+    },
+};
+use log::trace;
+use speedb::{DBIterator, Direction, IteratorMode, WriteBatch};
+use std::collections::{HashMap, HashSet};
+
+impl DelegationStore for IndexerStore {
+    fn add_block_delegation_changes(
+        &self,
+        block: &BlockUpdate,
+        diff: &LedgerDiff,
+        delegates: &HashMap<PublicKey, PublicKey>,
+    ) -> Result<()> {
+        trace!("Adding block delegation changes {}", block.state_hash);
+
+        // link delegations to their user commands
+        let txn_hashes = self.get_block_user_command_hashes(&block.state_hash)?;
+        let date_time = self
+            .get_block_date_time(&block.state_hash)?
+            .unwrap_or_default();
+
+        let mut batch = WriteBatch::default();
+        for change in
+            DelegationChange::from_ledger_diff(block, date_time, diff, delegates, &txn_hashes)
+        {
+            batch.put_cf(
+                self.best_ledger_account_delegation_history_cf(),
+                delegation_history_key(&change.delegator, block.blockchain_length, change.nonce),
+                serde_json::to_vec(&change)?,
+            );
+        }
+
+        Ok(self.database.write(batch)?)
+    }
+
+    fn remove_block_delegation_changes(
+        &self,
+        block: &BlockUpdate,
+        diff: &LedgerDiff,
+    ) -> Result<()> {
+        trace!("Removing block delegation changes {}", block.state_hash);
+        let mut batch = WriteBatch::default();
+
+        for account_diff in diff.account_diffs.iter().flatten() {
+            if let AccountDiff::Delegation(delegation) = account_diff {
+                let key = delegation_history_key(
+                    &delegation.delegator,
+                    block.blockchain_length,
+                    delegation.nonce,
+                );
+
+                // only remove the unapplied block's changes
+                if self
+                    .database
+                    .get_cf(self.best_ledger_account_delegation_history_cf(), key)?
+                    .and_then(|bytes| serde_json::from_slice::<DelegationChange>(&bytes).ok())
+                    .is_some_and(|change| change.state_hash == block.state_hash)
+                {
+                    batch.delete_cf(self.best_ledger_account_delegation_history_cf(), key);
+                }
+            }
+        }
+
+        Ok(self.database.write(batch)?)
+    }
+
+    fn get_delegation_history(
+        &self,
+        pk: &PublicKey,
+        limit: usize,
+        deadline: Option<&Deadline>,
+    ) -> Result<Vec<DelegationChange>> {
+        trace!("Getting {pk} delegation history");
+        let mut history = vec![];
+        let start = delegation_history_key(pk, u32::MAX, u32::MAX.into());
+
+        for (key, value) in self
+            .delegation_history_iterator(IteratorMode::From(&start, Direction::Reverse))
+            .flatten()
+        {
+            check_scan(deadline)?;
+            if key[..PublicKey::LEN] != *pk.0.as_bytes() || history.len() >= limit {
+                break;
+            }
+
+            history.push(serde_json::from_slice(&value)?);
+        }
+
+        Ok(history)
+    }
+
+    fn get_delegation_flows(
+        &self,
+        delegate: &PublicKey,
+        epoch: u32,
+        genesis_state_hash: Option<&StateHash>,
+        deadline: Option<&Deadline>,
+    ) -> Result<Option<DelegationFlows>> {
+        trace!("Getting {delegate} delegation flows epoch {epoch}");
+        let Some(prev_epoch) = epoch.checked_sub(1) else {
+            return Ok(None);
+        };
+
+        // both staking ledgers are needed
+        for epoch in [prev_epoch, epoch] {
+            if self
+                .get_staking_ledger_hash_by_epoch(epoch, genesis_state_hash)?
+                .is_none()
+            {
+                return Ok(None);
+            }
+        }
+
+        let delegators = |epoch| -> Result<HashSet<PublicKey>> {
+            Ok(self
+                .get_epoch_delegations(delegate, epoch, genesis_state_hash)?
+                .map(|delegation| delegation.delegates)
+                .unwrap_or_default())
+        };
+        let prev_delegators = delegators(prev_epoch)?;
+        let curr_delegators = delegators(epoch)?;
+
+        let flow = |pk: &PublicKey, balance_epoch| -> Result<DelegatorFlow> {
+            check_scan(deadline)?;
+            let from = self.get_staking_account(pk, prev_epoch, genesis_state_hash)?;
+            let to = self.get_staking_account(pk, epoch, genesis_state_hash)?;
+            let balance_account = if balance_epoch == epoch { &to } else { &from };
+
+            Ok(DelegatorFlow {
+                public_key: pk.clone(),
+                balance: balance_account
+                    .as_ref()
+                    .map_or(0, |account| account.balance),
+                from_delegate: from.map(|account| account.delegate),
+                to_delegate: to.map(|account| account.delegate),
+            })
+        };
+
+        let joined = curr_delegators
+            .difference(&prev_delegators)
+            .map(|pk| flow(pk, epoch))
+            .collect::<Result<_>>()?;
+        let left = prev_delegators
+            .difference(&curr_delegators)
+            .map(|pk| flow(pk, prev_epoch))
+            .collect::<Result<_>>()?;
+
+        Ok(Some(DelegationFlows::new(
+            delegate.clone(),
+            epoch,
+            joined,
+            left,
+        )))
+    }
+
+    ///////////////
+    // Iterators //
+    ///////////////
+
+    fn delegation_history_iterator(&self, mode: IteratorMode) -> DBIterator<'_> {
+        self.database
+            .iterator_cf(self.best_ledger_account_delegation_history_cf(), mode)
+    }
+}
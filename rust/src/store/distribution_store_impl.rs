@@ -0,0 +1,125 @@
+use super::{column_families::ColumnFamilyHelpers, IndexerStore};
+use crate::{
+    base::state_hash::StateHash,
+    block::store::BlockStore,
+    ledger::{
+        account::Account,
+        distribution::WealthDistribution,
+        store::{
+            best::BestLedgerStore, distribution::DistributionStore, staking::StakingLedgerStore,
+        },
+        token::TokenAddress,
+        LedgerHash,
+    },
+    store::Result,
+};
+use log::trace;
+use speedb::{Direction, IteratorMode, WriteBatch};
+
+impl DistributionStore for IndexerStore {
+    fn set_staking_ledger_distribution(
+        &self,
+        ledger_hash: &LedgerHash,
+        distribution: &WealthDistribution,
+    ) -> Result<()> {
+        trace!("Setting wealth distribution for staking ledger {ledger_hash}");
+        Ok(self.database.put_cf(
+            self.staking_ledger_wealth_distribution_cf(),
+            ledger_hash.0.as_bytes(),
+            serde_json::to_vec(distribution)?,
+        )?)
+    }
+
+    fn get_staking_ledger_distribution(
+        &self,
+        ledger_hash: &LedgerHash,
+    ) -> Result<Option<WealthDistribution>> {
+        trace!("Getting wealth distribution for staking ledger {ledger_hash}");
+        Ok(self
+            .database
+            .get_cf(
+                self.staking_ledger_wealth_distribution_cf(),
+                ledger_hash.0.as_bytes(),
+            )?
+            .map(|bytes| serde_json::from_slice(&bytes))
+            .transpose()?)
+    }
+
+    fn get_epoch_distribution(
+        &self,
+        epoch: u32,
+        genesis_state_hash: Option<&StateHash>,
+    ) -> Result<Option<WealthDistribution>> {
+        trace!("Getting epoch {epoch} wealth distribution");
+        match self.get_staking_ledger_hash_by_epoch(epoch, genesis_state_hash)? {
+            Some(ledger_hash) => self.get_staking_ledger_distribution(&ledger_hash),
+            None => Ok(None),
+        }
+    }
+
+    fn update_best_ledger_distribution(&self) -> Result<()> {
+        let Some(state_hash) = self.get_best_block_hash()? else {
+            return Ok(());
+        };
+        trace!("Updating best ledger wealth distribution {state_hash}");
+
+        // compute from the MINA accounts
+        let protocol = self.protocol_params();
+        let mina = TokenAddress::default();
+        let mut accounts = vec![];
+        for (key, value) in self
+            .best_ledger_account_balance_iterator(IteratorMode::From(
+                mina.0.as_bytes(),
+                Direction::Forward,
+            ))
+            .flatten()
+        {
+            if key[..TokenAddress::LEN] != *mina.0.as_bytes() {
+                break;
+            }
+
+            let account: Account = serde_json::from_slice(&value)?;
+            accounts.push(account.display(&protocol));
+        }
+
+        let distribution = WealthDistribution::new(
+            accounts
+                .iter()
+                .map(|account| (account.balance.0, &account.delegate)),
+        );
+
+        // only keep the latest best tip's distribution
+        let mut batch = WriteBatch::default();
+        for (key, _) in self
+            .database
+            .iterator_cf(
+                self.best_ledger_wealth_distribution_cf(),
+                IteratorMode::Start,
+            )
+            .flatten()
+        {
+            batch.delete_cf(self.best_ledger_wealth_distribution_cf(), key);
+        }
+        batch.put_cf(
+            self.best_ledger_wealth_distribution_cf(),
+            state_hash.0.as_bytes(),
+            serde_json::to_vec(&distribution)?,
+        );
+
+        Ok(self.database.write(batch)?)
+    }
+
+    fn get_best_ledger_distribution(&self) -> Result<Option<WealthDistribution>> {
+        trace!("Getting best ledger wealth distribution");
+        Ok(self
+            .database
+            .iterator_cf(
+                self.best_ledger_wealth_distribution_cf(),
+                IteratorMode::Start,
+            )
+            .flatten()
+            .next()
+            .map(|(_, bytes)| serde_json::from_slice(&bytes))
+            .transpose()?)
+    }
+}
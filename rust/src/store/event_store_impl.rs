@@ -42,8 +42,10 @@ impl EventStore for IndexerStore {
 
         // increment event sequence number
         let next_seq_num = seq_num + 1;
-        self.database
-            .put(Self::NEXT_EVENT_SEQ_NUM_KEY, next_seq_num.to_be_bytes())?;
+        self.database.put(
+            self.fixed_key(Self::NEXT_EVENT_SEQ_NUM_KEY),
+            next_seq_num.to_be_bytes(),
+        )?;
 
         // return next event sequence number
         Ok(next_seq_num)
@@ -61,7 +63,7 @@ impl EventStore for IndexerStore {
         trace!("Getting next event sequence number");
         Ok(self
             .database
-            .get(Self::NEXT_EVENT_SEQ_NUM_KEY)?
+            .get(self.fixed_key(Self::NEXT_EVENT_SEQ_NUM_KEY))?
             .map_or(0, from_be_bytes))
     }
 
@@ -7,10 +7,12 @@ pub trait FixedKeys {
     const KNOWN_GENESIS_PREV_STATE_HASHES_KEY: &'static [u8] =
         "genesis_prev_state_hashes".as_bytes();
     const NUM_BLOCK_BYTES_PROCESSED: &'static [u8] = "num_block_bytes_processed".as_bytes();
+    const LOCKED_SUPPLY_KEY: &'static [u8] = "locked_supply".as_bytes();
 
     // version info
     const INDEXER_STORE_VERSION_KEY: &'static [u8] = "indexer_store_version".as_bytes();
     const INDEXER_CONFIG_KEY: &'static [u8] = "indexer_config".as_bytes();
+    const PROTOCOL_PARAMS_KEY: &'static [u8] = "protocol_params".as_bytes();
 
     // indexed totals
     const TOTAL_NUM_ACCOUNTS_KEY: &'static [u8] = "total_num_accounts".as_bytes();
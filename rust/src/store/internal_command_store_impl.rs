@@ -35,7 +35,7 @@ impl InternalCommandStore for IndexerStore {
 
         // add cmds with data to public keys
         let internal_cmds_with_data: Vec<DbInternalCommandWithData> =
-            DbInternalCommand::from_precomputed(block)
+            DbInternalCommand::from_precomputed(block, &self.protocol_params())
                 .into_iter()
                 .map(|c| {
                     DbInternalCommandWithData::from_internal_cmd(
@@ -358,7 +358,7 @@ impl InternalCommandStore for IndexerStore {
         trace!("Getting internal command total");
         Ok(self
             .database
-            .get(Self::TOTAL_NUM_FEE_TRANSFERS_KEY)?
+            .get(self.fixed_key(Self::TOTAL_NUM_FEE_TRANSFERS_KEY))?
             .map_or(0, from_be_bytes))
     }
 
@@ -366,7 +366,7 @@ impl InternalCommandStore for IndexerStore {
         trace!("Incrementing internal command total");
         let old = self.get_internal_commands_total_count()?;
         Ok(self.database.put(
-            Self::TOTAL_NUM_FEE_TRANSFERS_KEY,
+            self.fixed_key(Self::TOTAL_NUM_FEE_TRANSFERS_KEY),
             (old + incr).to_be_bytes(),
         )?)
     }
@@ -472,7 +472,7 @@ impl InternalCommandStore for IndexerStore {
         trace!("Getting canonical internal command count");
         Ok(self
             .database
-            .get(Self::TOTAL_NUM_CANONICAL_FEE_TRANSFERS_KEY)?
+            .get(self.fixed_key(Self::TOTAL_NUM_CANONICAL_FEE_TRANSFERS_KEY))?
             .map_or(0, from_be_bytes))
     }
 
@@ -481,7 +481,7 @@ impl InternalCommandStore for IndexerStore {
         trace!("Increment canonical internal commands count");
         let old = self.get_canonical_internal_commands_count()?;
         Ok(self.database.put(
-            Self::TOTAL_NUM_CANONICAL_FEE_TRANSFERS_KEY,
+            self.fixed_key(Self::TOTAL_NUM_CANONICAL_FEE_TRANSFERS_KEY),
             (old + incr).to_be_bytes(),
         )?)
     }
@@ -491,7 +491,7 @@ impl InternalCommandStore for IndexerStore {
         trace!("Decrement canonical internal commands count");
         let old = self.get_canonical_internal_commands_count()?;
         Ok(self.database.put(
-            Self::TOTAL_NUM_CANONICAL_FEE_TRANSFERS_KEY,
+            self.fixed_key(Self::TOTAL_NUM_CANONICAL_FEE_TRANSFERS_KEY),
             (old.saturating_sub(incr)).to_be_bytes(),
         )?)
     }
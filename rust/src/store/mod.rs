@@ -4,45 +4,104 @@
 // traits
 pub mod column_families;
 pub mod fixed_keys;
+pub mod multi_get;
 pub mod username;
 pub mod version;
 pub mod zkapp;
 
 // impls
+pub mod balance_history_store_impl;
 pub mod best_ledger_store_impl;
 pub mod block_store_impl;
 pub mod canonicity_store_impl;
+pub mod chain_health_store_impl;
 pub mod chain_store_impl;
 pub mod column_families_impl;
+pub mod delegation_store_impl;
+pub mod distribution_store_impl;
 pub mod event_store_impl;
 pub mod internal_command_store_impl;
+pub mod multi_get_store_impl;
+pub mod reorg_store_impl;
 pub mod snark_store_impl;
 pub mod staged_ledger_store_impl;
 pub mod staking_ledger_store_impl;
+pub mod supply_store_impl;
 pub mod user_command_store_impl;
 pub mod username_store_impl;
 pub mod version_store_impl;
 pub mod zkapp_store_impl;
 
-use self::fixed_keys::FixedKeys;
+use self::{column_families::ColumnFamilyHelpers, fixed_keys::FixedKeys};
+use crate::{
+    base::state_hash::StateHash,
+    canonicity::reorg::Reorg,
+    chain::{profile::ProtocolParams, store::ChainStore, ChainId, Network},
+    constants::*,
+    ledger::Ledger,
+    state::{witness_tree::WitnessTree, IndexerState},
+};
 use anyhow::{anyhow, bail, Context};
 use log::{debug, info};
+use lru::LruCache;
 use serde::{Deserialize, Serialize};
-use speedb::{ColumnFamilyDescriptor, DBCompressionType, DB};
+use speedb::{ColumnFamily, ColumnFamilyDescriptor, DBCompressionType, DB};
 use std::{
+    borrow::Cow,
+    collections::HashMap,
     fs::{self, read_dir, File},
     io::{self, BufReader, BufWriter, Write},
+    num::NonZeroUsize,
     path::{Path, PathBuf},
+    sync::{Arc, Mutex, OnceLock, Weak},
 };
+use tokio::sync::{broadcast, RwLock};
 use version::{IndexerStoreVersion, VersionStore};
 
 pub(crate) type Result<T> = anyhow::Result<T>;
 
+fn staged_ledger_cache() -> Mutex<LruCache<StateHash, Arc<Ledger>>> {
+    Mutex::new(LruCache::new(
+        NonZeroUsize::new(STAGED_LEDGER_CACHE_SIZE).expect("nonzero staged ledger cache size"),
+    ))
+}
+
+fn reorg_sender() -> broadcast::Sender<Reorg> {
+    broadcast::channel(REORG_SUBSCRIPTION_CAPACITY).0
+}
+
 #[derive(Debug)]
 pub struct IndexerStore {
     pub db_path: PathBuf,
-    pub database: DB,
+    pub database: Arc<DB>,
     pub is_primary: bool,
+
+    /// Network whose column families & fixed keys the store is scoped to,
+    /// `None` for the primary network
+    ///
+    /// Scopes are keyed by network rather than chain id: a hardfork changes
+    /// the chain id but continues the same network's ledgers & canonical
+    /// chain, so each of its chain ids resolves to the network's scope via
+    /// `chain-id-to-network` (see [IndexerStore::chain_id_store])
+    pub network_scope: Option<Network>,
+
+    /// Networks indexed alongside the primary network
+    pub(crate) networks: Vec<Network>,
+
+    /// Scoped stores of the networks indexed alongside the primary network
+    pub(crate) network_stores: Mutex<HashMap<Network, Arc<IndexerStore>>>,
+
+    /// Recently materialized staged ledgers
+    pub(crate) staged_ledger_cache: Mutex<LruCache<StateHash, Arc<Ledger>>>,
+
+    /// Running indexer state, snapshot when the witness tree is queried
+    pub(crate) witness_tree_source: OnceLock<Weak<RwLock<IndexerState>>>,
+
+    /// Protocol parameters of the indexed network
+    pub(crate) protocol_params: Mutex<ProtocolParams>,
+
+    /// Reorgs recorded by the store, sent to each reorg subscriber
+    pub(crate) reorgs: broadcast::Sender<Reorg>,
 }
 
 #[derive(Default, Clone, PartialEq, Serialize, Deserialize)]
@@ -54,7 +113,7 @@ pub struct DbUpdate<T> {
 impl IndexerStore {
     /// Add the corresponding CF helper to [ColumnFamilyHelpers]
     /// & modify [IndexerStoreVersion] as needed!
-    const COLUMN_FAMILIES: [&'static str; 129] = [
+    const COLUMN_FAMILIES: [&'static str; 141] = [
         //////////////////////
         // Blocks store CFs //
         //////////////////////
@@ -67,6 +126,7 @@ impl IndexerStore {
         "blocks-global-slot",
         "blocks-parent-hash",
         "blocks-date-time",
+        "blocks-min-window-density",
         "blocks-epoch",
         "blocks-genesis-hash",
         "blocks-height-to-slots",
@@ -88,6 +148,7 @@ impl IndexerStore {
         //////////////////////////
         "canonicity-length",
         "canonicity-slot",
+        "canonicity-reorgs",
         ////////////////////////////
         // User command store CFs //
         ////////////////////////////
@@ -159,6 +220,12 @@ impl IndexerStore {
         "best-ledger-account-balance-sort",
         "best-ledger-account-num-delegations",
         "best-ledger-account-delegations",
+        "best-ledger-account-balance-history",
+        "best-ledger-account-delegation-history",
+        "best-ledger-timed-accounts",
+        "best-ledger-timed-accounts-unlocks",
+        "best-ledger-block-supply",
+        "best-ledger-wealth-distribution",
         "zkapp-best-ledger-accounts",
         "zkapp-best-ledger-account-balance-sort",
         /////////////////////////////
@@ -170,6 +237,7 @@ impl IndexerStore {
         "staged-ledger-account-delegations",
         "staged-ledger-hash-to-block",
         "staged-ledger-persisted",
+        "staged-ledger-persisted-heights",
         "staged-ledger-accounts-min-block",
         "blocks-ledger-diff",
         "blocks-staged-ledger-hash",
@@ -183,6 +251,9 @@ impl IndexerStore {
         "staking-ledger-hash-to-epoch",
         "staking-ledger-genesis-hash",
         "staking-ledger-total-currency",
+        "staking-ledger-staked-supply",
+        "staking-ledger-source",
+        "staking-ledger-wealth-distribution",
         "staking-ledger-balance-sort",
         "staking-ledger-stake-sort",
         "staking-ledger-accounts-count-epoch",
@@ -228,8 +299,17 @@ impl IndexerStore {
         "snarks-pk-total",
     ];
 
+    /// Column families shared by all networks, i.e. not scoped
+    const UNSCOPED_COLUMN_FAMILIES: [&'static str; 1] = ["chain-id-to-network"];
+
     /// Creates a new _primary_ indexer store
     pub fn new(path: &Path) -> Result<Self> {
+        Self::new_with_networks(path, &[])
+    }
+
+    /// Creates a new _primary_ indexer store with the column families of the
+    /// given networks, in addition to those already in the database
+    pub fn new_with_networks(path: &Path, networks: &[Network]) -> Result<Self> {
         let mut cf_opts = speedb::Options::default();
         cf_opts.set_write_buffer_size(256 * 1024 * 1024); // 256MB
         cf_opts.set_max_write_buffer_number(16); // 256Mb * 16 ~= 4GB
@@ -241,18 +321,29 @@ impl IndexerStore {
         database_opts.create_missing_column_families(true);
         database_opts.create_if_missing(true);
 
-        let column_families: Vec<ColumnFamilyDescriptor> = Self::COLUMN_FAMILIES
-            .iter()
-            .map(|cf| ColumnFamilyDescriptor::new(*cf, cf_opts.clone()))
-            .collect();
+        let mut indexed_networks = Self::existing_networks(path);
+        for network in networks {
+            if !indexed_networks.contains(network) {
+                indexed_networks.push(network.clone());
+            }
+        }
+
+        let column_families = Self::column_family_descriptors(&cf_opts, &indexed_networks);
         let primary = Self {
             is_primary: true,
+            network_scope: None,
+            networks: indexed_networks,
+            network_stores: Mutex::default(),
+            staged_ledger_cache: staged_ledger_cache(),
+            witness_tree_source: OnceLock::new(),
+            protocol_params: Mutex::new(ProtocolParams::default()),
+            reorgs: reorg_sender(),
             db_path: path.into(),
-            database: speedb::DBWithThreadMode::open_cf_descriptors(
+            database: Arc::new(speedb::DBWithThreadMode::open_cf_descriptors(
                 &database_opts,
                 path,
                 column_families,
-            )?,
+            )?),
         };
 
         // set db version
@@ -263,9 +354,166 @@ impl IndexerStore {
         )?;
         let version = primary.get_db_version().expect("db version exists");
         persist_indexer_version(&version, path)?;
+        primary.load_protocol_params()?;
         Ok(primary)
     }
 
+    /// Column family descriptors of the primary & given networks, the blocks
+    /// CFs compress with a zstd dictionary trained on the stored blocks
+    fn column_family_descriptors(
+        cf_opts: &speedb::Options,
+        networks: &[Network],
+    ) -> Vec<ColumnFamilyDescriptor> {
+        let mut blocks_cf_opts = cf_opts.clone();
+        blocks_cf_opts.set_compression_options(-14, 3, 0, BLOCKS_ZSTD_MAX_DICT_BYTES);
+        blocks_cf_opts.set_zstd_max_train_bytes(BLOCKS_ZSTD_MAX_TRAIN_BYTES);
+
+        let mut descriptors = vec![];
+        for network in std::iter::once(None).chain(networks.iter().map(Some)) {
+            for cf in Self::COLUMN_FAMILIES {
+                if network.is_some() && Self::UNSCOPED_COLUMN_FAMILIES.contains(&cf) {
+                    continue;
+                }
+
+                let opts = if cf == "blocks" {
+                    blocks_cf_opts.clone()
+                } else {
+                    cf_opts.clone()
+                };
+                descriptors.push(ColumnFamilyDescriptor::new(scoped_name(network, cf), opts));
+            }
+        }
+        descriptors
+    }
+
+    /// Networks with scoped column families in the database at the path
+    fn existing_networks(path: &Path) -> Vec<Network> {
+        let mut networks = vec![];
+        for cf in DB::list_cf(&speedb::Options::default(), path).unwrap_or_default() {
+            if let Some((network, _)) = cf.rsplit_once('/') {
+                let network = Network::from(network);
+                if !networks.contains(&network) {
+                    networks.push(network);
+                }
+            }
+        }
+        networks
+    }
+
+    /// Handle of the column family in the store's network scope
+    pub(crate) fn cf_handle(&self, name: &str) -> Option<&ColumnFamily> {
+        self.database
+            .cf_handle(&scoped_name(self.network_scope.as_ref(), name))
+    }
+
+    /// Fixed key in the store's network scope
+    pub fn fixed_key(&self, key: &'static [u8]) -> Cow<'static, [u8]> {
+        match self.network_scope.as_ref() {
+            Some(network) => Cow::Owned([format!("{network}/").as_bytes(), key].concat()),
+            None => Cow::Borrowed(key),
+        }
+    }
+
+    /// Networks indexed alongside the primary network
+    pub fn networks(&self) -> &[Network] {
+        &self.networks
+    }
+
+    /// Store of the network, sharing the primary store's database. The
+    /// primary network's store is the primary store itself.
+    ///
+    /// Error if the network isn't indexed
+    pub fn network_store(self: &Arc<Self>, network: &Network) -> Result<Arc<Self>> {
+        if self.network_scope.as_ref() == Some(network) {
+            return Ok(self.clone());
+        }
+
+        let mut network_stores = self.network_stores.lock().unwrap();
+        if let Some(store) = network_stores.get(network) {
+            return Ok(store.clone());
+        }
+
+        if !self.networks.contains(network) {
+            let has_chain_id = self
+                .database
+                .get(self.fixed_key(Self::CHAIN_ID_KEY))?
+                .is_some();
+            if has_chain_id && self.get_current_network()? == *network {
+                return Ok(self.clone());
+            }
+            bail!("Network {network} is not indexed");
+        }
+
+        let store = Arc::new(Self {
+            db_path: self.db_path.clone(),
+            database: self.database.clone(),
+            is_primary: self.is_primary,
+            network_scope: Some(network.clone()),
+            networks: vec![],
+            network_stores: Mutex::default(),
+            staged_ledger_cache: staged_ledger_cache(),
+            witness_tree_source: OnceLock::new(),
+            protocol_params: Mutex::new(ProtocolParams::default()),
+            reorgs: reorg_sender(),
+        });
+
+        store.load_protocol_params()?;
+        network_stores.insert(network.clone(), store.clone());
+        Ok(store)
+    }
+
+    /// Store of the network the chain id belongs to
+    ///
+    /// Error if the chain id isn't indexed
+    pub fn chain_id_store(self: &Arc<Self>, chain_id: &ChainId) -> Result<Arc<Self>> {
+        match self
+            .database
+            .get_pinned_cf(self.chain_id_to_network_cf(), chain_id.0.as_bytes())?
+        {
+            Some(network) => self.network_store(&Network::from(network.to_vec())),
+            None => bail!("Chain id {chain_id} is not indexed"),
+        }
+    }
+
+    /// Register the running indexer state whose witness tree is served
+    pub fn set_witness_tree_source(&self, state: &Arc<RwLock<IndexerState>>) {
+        let _ = self.witness_tree_source.set(Arc::downgrade(state));
+    }
+
+    /// Snapshot of the indexer state's current witness tree, built on
+    /// request. `None` if the indexer state isn't running
+    pub async fn get_witness_tree(&self) -> Option<WitnessTree> {
+        let state = self.witness_tree_source.get()?.upgrade()?;
+        let state = state.read().await;
+        Some(state.witness_tree())
+    }
+
+    /// Persist the indexed network's protocol parameters
+    pub fn set_protocol_params(&self, protocol_params: &ProtocolParams) -> Result<()> {
+        self.database.put(
+            self.fixed_key(Self::PROTOCOL_PARAMS_KEY),
+            serde_json::to_vec(protocol_params)?,
+        )?;
+
+        *self.protocol_params.lock().unwrap() = *protocol_params;
+        Ok(())
+    }
+
+    /// Protocol parameters of the indexed network, mainnet's until set
+    pub fn protocol_params(&self) -> ProtocolParams {
+        *self.protocol_params.lock().unwrap()
+    }
+
+    fn load_protocol_params(&self) -> Result<()> {
+        if let Some(bytes) = self
+            .database
+            .get(self.fixed_key(Self::PROTOCOL_PARAMS_KEY))?
+        {
+            *self.protocol_params.lock().unwrap() = serde_json::from_slice(&bytes)?;
+        }
+        Ok(())
+    }
+
     /// Create a snapshot of the Indexer store
     pub fn create_snapshot(&self, output_file: &Path) -> Result<String> {
         use speedb::checkpoint::Checkpoint;
@@ -298,24 +546,39 @@ impl IndexerStore {
         database_opts.create_missing_column_families(true);
         database_opts.create_if_missing(true);
 
-        let column_families: Vec<ColumnFamilyDescriptor> = Self::COLUMN_FAMILIES
-            .iter()
-            .map(|cf| ColumnFamilyDescriptor::new(*cf, cf_opts.clone()))
-            .collect();
+        let networks = Self::existing_networks(primary);
+        let column_families = Self::column_family_descriptors(&cf_opts, &networks);
         let read_only = Self {
             is_primary: false,
+            network_scope: None,
+            networks,
+            network_stores: Mutex::default(),
+            staged_ledger_cache: staged_ledger_cache(),
+            witness_tree_source: OnceLock::new(),
+            protocol_params: Mutex::new(ProtocolParams::default()),
+            reorgs: reorg_sender(),
             db_path: secondary.into(),
-            database: speedb::DBWithThreadMode::open_cf_descriptors_as_secondary(
+            database: Arc::new(speedb::DBWithThreadMode::open_cf_descriptors_as_secondary(
                 &database_opts,
                 primary,
                 secondary,
                 column_families,
-            )?,
+            )?),
         };
+
+        read_only.load_protocol_params()?;
         Ok(read_only)
     }
 }
 
+/// Column family name in the network's scope, see [IndexerStore::network_scope]
+fn scoped_name<'a>(network: Option<&Network>, cf: &'a str) -> Cow<'a, str> {
+    match network {
+        Some(network) => Cow::Owned(format!("{network}/{cf}")),
+        None => Cow::Borrowed(cf),
+    }
+}
+
 /// Restore a snapshot of the Indexer store
 pub fn restore_snapshot(snapshot_file: &PathBuf, restore_dir: &PathBuf) -> Result<()> {
     if !snapshot_file.exists() {
@@ -441,3 +704,78 @@ impl IndexerStore {
             .unwrap()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        chain::{store::ChainStore, ChainId},
+        snark_work::store::SnarkStore,
+    };
+    use tempfile::TempDir;
+
+    #[test]
+    fn network_scoped_stores() -> Result<()> {
+        let temp_dir = TempDir::new()?;
+        let primary = Arc::new(IndexerStore::new_with_networks(
+            temp_dir.path(),
+            &[Network::Devnet],
+        )?);
+        let devnet = primary.network_store(&Network::Devnet)?;
+        assert!(Arc::ptr_eq(
+            &devnet,
+            &primary.network_store(&Network::Devnet)?
+        ));
+
+        // fixed keys
+        primary.set_chain_id_for_network(&ChainId::v1(), &Network::Mainnet)?;
+        devnet.set_chain_id_for_network(&ChainId::v2(), &Network::Devnet)?;
+        devnet.increment_snarks_total_canonical_count(2)?;
+
+        assert_eq!(primary.get_current_network()?, Network::Mainnet);
+        assert_eq!(devnet.get_current_network()?, Network::Devnet);
+        assert_eq!(primary.get_snarks_total_canonical_count()?, 0);
+        assert_eq!(devnet.get_snarks_total_canonical_count()?, 2);
+
+        // column families
+        devnet
+            .database
+            .put_cf(devnet.blocks_state_hash_cf(), b"key", b"value")?;
+        assert!(primary
+            .database
+            .get_cf(primary.blocks_state_hash_cf(), b"key")?
+            .is_none());
+
+        // the primary network's store is the primary store
+        assert!(Arc::ptr_eq(
+            &primary,
+            &primary.network_store(&Network::Mainnet)?
+        ));
+        assert!(primary.network_store(&Network::Berkeley).is_err());
+
+        // chain ids resolve to their network's store
+        assert!(Arc::ptr_eq(
+            &devnet,
+            &primary.chain_id_store(&ChainId::v2())?
+        ));
+        assert!(Arc::ptr_eq(
+            &primary,
+            &primary.chain_id_store(&ChainId::v1())?
+        ));
+        assert!(primary.chain_id_store(&"0".repeat(64).parse()?).is_err());
+        drop(devnet);
+        drop(primary);
+
+        // the scoped column families are reopened
+        let reopened = Arc::new(IndexerStore::new(temp_dir.path())?);
+        assert_eq!(reopened.networks(), &[Network::Devnet]);
+        assert_eq!(
+            reopened
+                .network_store(&Network::Devnet)?
+                .get_snarks_total_canonical_count()?,
+            2
+        );
+
+        Ok(())
+    }
+}
@@ -0,0 +1,38 @@
+use crate::{
+    base::{public_key::PublicKey, state_hash::StateHash},
+    block::precomputed::PrecomputedBlock,
+    ledger::username::Username,
+};
+
+/// Public key block production, SNARK, user command & internal command counts
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct PkCounts {
+    pub epoch_num_blocks: u32,
+    pub total_num_blocks: u32,
+    pub epoch_num_snarks: u32,
+    pub total_num_snarks: u32,
+    pub epoch_num_user_commands: u32,
+    pub total_num_user_commands: u32,
+    pub epoch_num_internal_commands: u32,
+    pub total_num_internal_commands: u32,
+}
+
+/// Batched point lookups, each backed by a single `multi_get_cf` per column
+/// family
+///
+/// Results are returned in the same order as the input keys
+pub trait MultiGetStore {
+    /// Get the current usernames of the given public keys
+    fn get_usernames(&self, pks: &[PublicKey]) -> anyhow::Result<Vec<Option<Username>>>;
+
+    /// Get the counts of the given public keys in the given epoch
+    /// (default: current epoch)
+    fn get_pk_counts(&self, pks: &[PublicKey], epoch: Option<u32>)
+        -> anyhow::Result<Vec<PkCounts>>;
+
+    /// Get the blocks with the given state hashes
+    fn get_blocks(
+        &self,
+        state_hashes: &[StateHash],
+    ) -> anyhow::Result<Vec<Option<PrecomputedBlock>>>;
+}
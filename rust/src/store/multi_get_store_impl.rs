@@ -0,0 +1,130 @@
+use super::{
+    column_families::ColumnFamilyHelpers,
+    multi_get::{MultiGetStore, PkCounts},
+    IndexerStore,
+};
+use crate::{
+    base::{public_key::PublicKey, state_hash::StateHash},
+    block::{precomputed::PrecomputedBlock, store::BlockStore},
+    ledger::username::Username,
+    utility::store::{
+        block::block_from_value,
+        common::{from_be_bytes, pk_index_key, u32_prefix_key},
+    },
+};
+use log::trace;
+
+impl MultiGetStore for IndexerStore {
+    fn get_usernames(&self, pks: &[PublicKey]) -> anyhow::Result<Vec<Option<Username>>> {
+        trace!("Getting {} usernames", pks.len());
+
+        // number of username updates => index of the current username
+        let indices = self
+            .database
+            .multi_get_cf(
+                pks.iter()
+                    .map(|pk| (self.username_pk_num_cf(), pk.0.as_bytes())),
+            )
+            .into_iter()
+            .map(|index| Ok(index?.map(from_be_bytes)))
+            .collect::<anyhow::Result<Vec<Option<u32>>>>()?;
+
+        let mut usernames = self
+            .database
+            .multi_get_cf(
+                pks.iter()
+                    .zip(indices.iter())
+                    .filter_map(|(pk, index)| index.map(|index| pk_index_key(pk, index)))
+                    .map(|key| (self.username_pk_index_cf(), key)),
+            )
+            .into_iter();
+
+        indices
+            .iter()
+            .map(|index| match index {
+                None => Ok(None),
+                Some(_) => Ok(usernames
+                    .next()
+                    .transpose()?
+                    .flatten()
+                    .and_then(|bytes| Username::from_bytes(bytes).ok())),
+            })
+            .collect()
+    }
+
+    fn get_pk_counts(
+        &self,
+        pks: &[PublicKey],
+        epoch: Option<u32>,
+    ) -> anyhow::Result<Vec<PkCounts>> {
+        let epoch = epoch.unwrap_or(self.get_current_epoch()?);
+        trace!("Getting {} pk epoch {epoch} counts", pks.len());
+
+        // (epoch, total) count column families
+        let cfs = [
+            (
+                self.block_production_pk_epoch_cf(),
+                self.block_production_pk_total_cf(),
+            ),
+            (self.snarks_pk_epoch_cf(), self.snarks_pk_total_cf()),
+            (
+                self.user_commands_pk_epoch_cf(),
+                self.user_commands_pk_total_cf(),
+            ),
+            (
+                self.internal_commands_pk_epoch_cf(),
+                self.internal_commands_pk_total_cf(),
+            ),
+        ];
+        let keys = pks.iter().flat_map(|pk| {
+            cfs.iter().flat_map(move |(epoch_cf, total_cf)| {
+                [
+                    (*epoch_cf, u32_prefix_key(epoch, pk).to_vec()),
+                    (*total_cf, pk.0.as_bytes().to_vec()),
+                ]
+            })
+        });
+
+        let counts = self
+            .database
+            .multi_get_cf(keys)
+            .into_iter()
+            .map(|count| Ok(count?.map_or(0, from_be_bytes)))
+            .collect::<anyhow::Result<Vec<u32>>>()?;
+
+        Ok(counts
+            .chunks_exact(2 * cfs.len())
+            .map(|counts| PkCounts {
+                epoch_num_blocks: counts[0],
+                total_num_blocks: counts[1],
+                epoch_num_snarks: counts[2],
+                total_num_snarks: counts[3],
+                epoch_num_user_commands: counts[4],
+                total_num_user_commands: counts[5],
+                epoch_num_internal_commands: counts[6],
+                total_num_internal_commands: counts[7],
+            })
+            .collect())
+    }
+
+    fn get_blocks(
+        &self,
+        state_hashes: &[StateHash],
+    ) -> anyhow::Result<Vec<Option<PrecomputedBlock>>> {
+        trace!("Getting {} blocks", state_hashes.len());
+        self.database
+            .multi_get_cf(
+                state_hashes
+                    .iter()
+                    .map(|state_hash| (self.blocks_cf(), state_hash.0.as_bytes())),
+            )
+            .into_iter()
+            .map(|bytes| {
+                // drop the num bytes
+                bytes?
+                    .map(|bytes| block_from_value(&bytes).map(|(block, _)| block))
+                    .transpose()
+            })
+            .collect()
+    }
+}
@@ -0,0 +1,111 @@
+use super::{column_families::ColumnFamilyHelpers, IndexerStore};
+use crate::{
+    base::state_hash::StateHash,
+    block::store::{BlockStore, DbBlockUpdate},
+    canonicity::reorg::{Reorg, ReorgStore},
+    command::store::UserCommandStore,
+    utility::{
+        deadline::{check_scan, Deadline},
+        store::{block::global_slot_state_hash_key, common::block_u32_prefix_from_key},
+    },
+};
+use log::{info, trace};
+use speedb::{DBIterator, IteratorMode};
+use std::collections::HashMap;
+use tokio::sync::broadcast;
+
+impl ReorgStore for IndexerStore {
+    fn add_reorg(
+        &self,
+        old_best_tip: &StateHash,
+        new_best_tip: &StateHash,
+        blocks: &DbBlockUpdate,
+    ) -> anyhow::Result<Option<Reorg>> {
+        let Some(deepest) = blocks.unapply.last() else {
+            return Ok(None);
+        };
+        trace!("Adding reorg {old_best_tip} -> {new_best_tip}");
+
+        let common_ancestor = self
+            .get_block_parent_hash(&deepest.state_hash)?
+            .expect("orphaned block has a parent");
+        let date_time = self.get_block_date_time(new_best_tip)?.unwrap_or_default();
+
+        // updated blocks' user command hashes, in (sender, nonce) order
+        let mut txn_hashes = HashMap::new();
+        for block in blocks.apply.iter().chain(blocks.unapply.iter()) {
+            let mut hashes: Vec<_> = self
+                .get_block_user_command_hashes(&block.state_hash)?
+                .into_iter()
+                .collect();
+            hashes.sort();
+            txn_hashes.insert(
+                block.state_hash.clone(),
+                hashes.into_iter().map(|(_, hash)| hash).collect(),
+            );
+        }
+
+        let Some(reorg) = Reorg::new(
+            old_best_tip.clone(),
+            new_best_tip.clone(),
+            blocks,
+            common_ancestor,
+            date_time,
+            |state_hash| txn_hashes.get(state_hash).cloned().unwrap_or_default(),
+        ) else {
+            return Ok(None);
+        };
+        info!(
+            "Reorg of depth {} at height {}: {old_best_tip} -> {new_best_tip}",
+            reorg.depth, reorg.common_ancestor_height,
+        );
+
+        // same {u32}{state_hash} key format, keyed by new best tip height
+        self.database.put_cf(
+            self.canonicity_reorgs_cf(),
+            global_slot_state_hash_key(reorg.new_best_tip_height, new_best_tip),
+            serde_json::to_vec(&reorg)?,
+        )?;
+
+        // no subscribers isn't an error
+        let _ = self.reorgs.send(reorg.clone());
+        Ok(Some(reorg))
+    }
+
+    fn subscribe_reorgs(&self) -> broadcast::Receiver<Reorg> {
+        self.reorgs.subscribe()
+    }
+
+    fn get_reorgs(
+        &self,
+        min_depth: u32,
+        since: u32,
+        limit: usize,
+        deadline: Option<&Deadline>,
+    ) -> anyhow::Result<Vec<Reorg>> {
+        trace!("Getting reorgs of depth at least {min_depth} since height {since}");
+        let mut reorgs = vec![];
+
+        for (key, value) in self.reorg_iterator(IteratorMode::End).flatten() {
+            check_scan(deadline)?;
+            if reorgs.len() >= limit || block_u32_prefix_from_key(&key)? < since {
+                break;
+            }
+
+            let reorg: Reorg = serde_json::from_slice(&value)?;
+            if reorg.depth >= min_depth {
+                reorgs.push(reorg);
+            }
+        }
+
+        Ok(reorgs)
+    }
+
+    ///////////////
+    // Iterators //
+    ///////////////
+
+    fn reorg_iterator(&self, mode: IteratorMode) -> DBIterator<'_> {
+        self.database.iterator_cf(self.canonicity_reorgs_cf(), mode)
+    }
+}
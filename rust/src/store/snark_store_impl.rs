@@ -6,7 +6,6 @@ use crate::{
         store::{BlockStore, BlockUpdate, DbBlockUpdate},
     },
     canonicity::store::CanonicityStore,
-    constants::MAINNET_EPOCH_SLOT_COUNT,
     snark_work::{
         store::{DbSnarkUpdate, SnarkApplication, SnarkProverFees, SnarkStore, SnarkUpdate},
         SnarkWorkSummary, SnarkWorkSummaryWithStateHash, SnarkWorkTotal,
@@ -214,7 +213,7 @@ impl SnarkStore for IndexerStore {
         apply: SnarkApplication,
     ) -> anyhow::Result<()> {
         trace!("Updating SNARK prover fees");
-        let epoch = global_slot / MAINNET_EPOCH_SLOT_COUNT;
+        let epoch = self.protocol_params().epoch(global_slot);
         let block_height_opt = match apply {
             SnarkApplication::Apply => None,
             SnarkApplication::Unapply => Some(block_height),
@@ -951,7 +950,7 @@ impl SnarkStore for IndexerStore {
         trace!("Getting total SNARKs count");
         Ok(self
             .database
-            .get_pinned(Self::TOTAL_NUM_SNARKS_KEY)?
+            .get_pinned(self.fixed_key(Self::TOTAL_NUM_SNARKS_KEY))?
             .map_or(0, |bytes| {
                 u32_from_be_bytes(&bytes).expect("total SNARK count")
             }))
@@ -961,7 +960,7 @@ impl SnarkStore for IndexerStore {
         trace!("Getting total canonical SNARKs count");
         Ok(self
             .database
-            .get_pinned(Self::TOTAL_NUM_CANONICAL_SNARKS_KEY)?
+            .get_pinned(self.fixed_key(Self::TOTAL_NUM_CANONICAL_SNARKS_KEY))?
             .map_or(0, |bytes| {
                 u32_from_be_bytes(&bytes).expect("total canonical SNARK count")
             }))
@@ -974,7 +973,7 @@ impl SnarkStore for IndexerStore {
             .ok()
             .unwrap_or_default();
         Ok(self.database.put(
-            Self::TOTAL_NUM_CANONICAL_SNARKS_KEY,
+            self.fixed_key(Self::TOTAL_NUM_CANONICAL_SNARKS_KEY),
             (old + incr).to_be_bytes(),
         )?)
     }
@@ -986,7 +985,7 @@ impl SnarkStore for IndexerStore {
             .ok()
             .unwrap_or_default();
         Ok(self.database.put(
-            Self::TOTAL_NUM_CANONICAL_SNARKS_KEY,
+            self.fixed_key(Self::TOTAL_NUM_CANONICAL_SNARKS_KEY),
             (old.saturating_sub(decr)).to_be_bytes(),
         )?)
     }
@@ -994,9 +993,10 @@ impl SnarkStore for IndexerStore {
     fn increment_snarks_total_count(&self) -> anyhow::Result<()> {
         trace!("Incrementing total SNARKs count");
         let old = self.get_snarks_total_count()?;
-        Ok(self
-            .database
-            .put(Self::TOTAL_NUM_SNARKS_KEY, (old + 1).to_be_bytes())?)
+        Ok(self.database.put(
+            self.fixed_key(Self::TOTAL_NUM_SNARKS_KEY),
+            (old + 1).to_be_bytes(),
+        )?)
     }
 
     fn get_snarks_pk_epoch_count(&self, pk: &PublicKey, epoch: Option<u32>) -> anyhow::Result<u32> {
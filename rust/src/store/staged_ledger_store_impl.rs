@@ -8,15 +8,21 @@ use crate::{
     ledger::{
         account::Account,
         diff::LedgerDiff,
-        store::{best::BestLedgerStore, staged::StagedLedgerStore},
+        store::{
+            balance_history::BalanceHistoryStore, best::BestLedgerStore, staged::StagedLedgerStore,
+        },
         token::TokenAddress,
         Ledger, LedgerHash,
     },
-    utility::store::{common::from_be_bytes, ledger::staged::*},
+    utility::store::{
+        common::{block_u32_prefix_from_key, from_be_bytes, state_hash_suffix},
+        ledger::staged::*,
+    },
 };
 use anyhow::{bail, Context};
 use log::{error, trace};
 use speedb::{DBIterator, Direction, IteratorMode, WriteBatch};
+use std::sync::Arc;
 
 impl StagedLedgerStore for IndexerStore {
     fn get_staged_account(
@@ -88,7 +94,7 @@ impl StagedLedgerStore for IndexerStore {
     ) -> anyhow::Result<Option<Account>> {
         trace!("Display {pk} staged ledger {state_hash} account");
         if let Some(staged_acct) = self.get_staged_account(pk, token, state_hash)? {
-            return Ok(Some(staged_acct.display()));
+            return Ok(Some(staged_acct.display(&self.protocol_params())));
         }
         Ok(None)
     }
@@ -212,7 +218,7 @@ impl StagedLedgerStore for IndexerStore {
     fn add_staged_ledger_at_state_hash(
         &self,
         state_hash: &StateHash,
-        ledger: Ledger,
+        ledger: &Ledger,
         block_height: u32,
     ) -> anyhow::Result<()> {
         trace!("Adding staged ledger at state hash {state_hash}");
@@ -289,6 +295,11 @@ impl StagedLedgerStore for IndexerStore {
             state_hash.0.as_bytes(),
             b"",
         )?;
+        self.database.put_cf(
+            self.staged_ledgers_persisted_heights_cf(),
+            staged_ledger_height_key(block_height, state_hash),
+            b"",
+        )?;
 
         Ok(())
     }
@@ -307,7 +318,7 @@ impl StagedLedgerStore for IndexerStore {
         if !known_prev.contains(state_hash) {
             known_prev.push(state_hash.clone());
             self.database.put(
-                Self::KNOWN_GENESIS_PREV_STATE_HASHES_KEY,
+                self.fixed_key(Self::KNOWN_GENESIS_PREV_STATE_HASHES_KEY),
                 serde_json::to_vec(&known_prev)?,
             )?;
         }
@@ -319,7 +330,10 @@ impl StagedLedgerStore for IndexerStore {
             }
         }
 
-        self.add_staged_ledger_at_state_hash(state_hash, genesis_ledger, height)?;
+        // initial balance history
+        self.add_genesis_balance_changes(state_hash, height, &genesis_ledger)?;
+
+        self.add_staged_ledger_at_state_hash(state_hash, &genesis_ledger, height)?;
         Ok(())
     }
 
@@ -327,73 +341,15 @@ impl StagedLedgerStore for IndexerStore {
         &self,
         state_hash: &StateHash,
         memoize: bool,
-    ) -> anyhow::Result<Option<Ledger>> {
-        trace!("Getting staged ledger state hash {state_hash}");
-
-        let mut curr_state_hash = state_hash.clone();
-        let mut diffs = vec![];
-
-        // walk chain back to a stored ledger
-        // collect diffs to compute the current ledger
-        while self
-            .database
-            .get_cf(
-                self.staged_ledgers_persisted_cf(),
-                curr_state_hash.0.as_bytes(),
-            )?
-            .is_none()
-        {
-            trace!("No staged ledger found for state hash {curr_state_hash}");
-            if let Some(diff) = self.get_block_ledger_diff(&curr_state_hash)? {
-                diffs.push(diff);
-
-                if let Ok(Some(parent_hash)) = self.get_block_parent_hash(&curr_state_hash) {
-                    trace!("Checking for staged ledger state hash {parent_hash}");
-                    curr_state_hash = parent_hash;
-                }
-            } else {
-                if curr_state_hash.0 != MAINNET_GENESIS_PREV_STATE_HASH
-                    || curr_state_hash.0 != HARDFORK_GENESIS_PREV_STATE_HASH
-                {
-                    error!("Block missing from store: {curr_state_hash}");
-                }
-
-                return Ok(None);
-            }
-        }
-
-        trace!("Found staged ledger state hash {curr_state_hash}");
-        if let Ok(Some(mut ledger)) = self.build_staged_ledger(&curr_state_hash) {
-            // apply diffs
-            diffs.reverse();
-
-            let diff = LedgerDiff::append_vec(diffs);
-            ledger._apply_diff(&diff)?;
-
-            if memoize {
-                trace!("Memoizing ledger for block {state_hash}");
-
-                match self.get_block_height(state_hash)? {
-                    Some(block_height) => self.add_staged_ledger_at_state_hash(
-                        state_hash,
-                        ledger.clone(),
-                        block_height,
-                    )?,
-                    None => bail!("Block missing from store {state_hash}"),
-                }
-            }
-
-            return Ok(Some(ledger));
-        }
-
-        Ok(None)
+    ) -> anyhow::Result<Option<Arc<Ledger>>> {
+        self.staged_ledger_at_state_hash(state_hash, memoize, true)
     }
 
     fn get_staged_ledger_at_ledger_hash(
         &self,
         ledger_hash: &LedgerHash,
         memoize: bool,
-    ) -> anyhow::Result<Option<Ledger>> {
+    ) -> anyhow::Result<Option<Arc<Ledger>>> {
         trace!("Getting staged ledger hash {ledger_hash}");
         let key = ledger_hash.0.as_bytes();
         if let Some(state_hash) = self
@@ -412,12 +368,107 @@ impl StagedLedgerStore for IndexerStore {
         &self,
         height: u32,
         memoize: bool,
-    ) -> anyhow::Result<Option<Ledger>> {
+    ) -> anyhow::Result<Option<Arc<Ledger>>> {
         trace!("Getting staged ledger at height {height}");
-        self.get_canonical_hash_at_height(height)?
-            .map_or(Ok(None), |state_hash| {
-                self.get_staged_ledger_at_state_hash(&state_hash, memoize)
-            })
+        let state_hash = match self.get_canonical_hash_at_height(height)? {
+            Some(state_hash) => state_hash,
+            None => return Ok(None),
+        };
+
+        let cached = self
+            .staged_ledger_cache
+            .lock()
+            .unwrap()
+            .get(&state_hash)
+            .cloned();
+        if let Some(ledger) = cached {
+            trace!("Staged ledger cache hit {state_hash}");
+            if memoize && !self.is_staged_ledger_persisted(&state_hash)? {
+                self.add_staged_ledger_at_state_hash(&state_hash, &ledger, height)?;
+            }
+            return Ok(Some(ledger));
+        }
+
+        // materialize from the nearest persisted staged ledger
+        let (snapshot_height, snapshot_state_hash) =
+            match self.get_nearest_staged_ledger_height(height)? {
+                Some(snapshot) => snapshot,
+                None => return self.get_staged_ledger_at_state_hash(&state_hash, memoize),
+            };
+        let mut ledger = match self.build_staged_ledger(&snapshot_state_hash)? {
+            Some(ledger) => ledger,
+            None => return Ok(None),
+        };
+
+        trace!("Materializing staged ledger at height {height} from height {snapshot_height}");
+        if snapshot_height <= height {
+            for diff_height in snapshot_height + 1..=height {
+                ledger._apply_diff(&self.get_canonical_block_ledger_diff(diff_height)?)?;
+            }
+        } else {
+            for diff_height in (height + 1..=snapshot_height).rev() {
+                ledger._unapply_diff(&self.get_canonical_block_ledger_diff(diff_height)?)?;
+            }
+        }
+
+        if memoize {
+            trace!("Memoizing ledger for block {state_hash}");
+            self.add_staged_ledger_at_state_hash(&state_hash, &ledger, height)?;
+        }
+
+        let ledger = Arc::new(ledger);
+        self.staged_ledger_cache
+            .lock()
+            .unwrap()
+            .put(state_hash, ledger.clone());
+        Ok(Some(ledger))
+    }
+
+    fn get_nearest_staged_ledger_height(
+        &self,
+        block_height: u32,
+    ) -> anyhow::Result<Option<(u32, StateHash)>> {
+        trace!("Getting nearest staged ledger to height {block_height}");
+
+        // first persisted canonical staged ledger in the iterator's direction
+        let nearest = |mode: IteratorMode| -> anyhow::Result<Option<(u32, StateHash)>> {
+            for (key, _) in self
+                .database
+                .iterator_cf(self.staged_ledgers_persisted_heights_cf(), mode)
+                .flatten()
+            {
+                let height = block_u32_prefix_from_key(&key)?;
+                let state_hash = state_hash_suffix(&key)?;
+
+                if height > 0
+                    && self.get_canonical_hash_at_height(height)?.as_ref() == Some(&state_hash)
+                {
+                    return Ok(Some((height, state_hash)));
+                }
+            }
+            Ok(None)
+        };
+
+        // keys are `{height BE}{state hash}`, so seeking to `{height + 1 BE}`
+        // lands between the heights at or below & above the block height
+        let next_height = block_height.saturating_add(1).to_be_bytes();
+        let below = nearest(IteratorMode::From(&next_height, Direction::Reverse))?;
+        if below
+            .as_ref()
+            .is_some_and(|(height, _)| *height == block_height)
+        {
+            return Ok(below);
+        }
+        let above = nearest(IteratorMode::From(&next_height, Direction::Forward))?;
+
+        // prefer the lower height when equidistant
+        Ok(match (below, above) {
+            (Some(below), Some(above)) if above.0 - block_height < block_height - below.0 => {
+                Some(above)
+            }
+            (None, above) => above,
+            (below, _) => below,
+        })
     }
 
     fn set_block_ledger_diff_batch(
@@ -535,3 +586,108 @@ impl StagedLedgerStore for IndexerStore {
             .iterator_cf(self.staged_ledger_account_balance_sort_cf(), mode)
     }
 }
+
+impl IndexerStore {
+    /// Compute the staged ledger of the block, only caching it if `cache`
+    /// (one-off reads shouldn't evict the block processing ledgers)
+    pub(crate) fn staged_ledger_at_state_hash(
+        &self,
+        state_hash: &StateHash,
+        memoize: bool,
+        cache: bool,
+    ) -> anyhow::Result<Option<Arc<Ledger>>> {
+        trace!("Getting staged ledger state hash {state_hash}");
+
+        let cached = self
+            .staged_ledger_cache
+            .lock()
+            .unwrap()
+            .get(state_hash)
+            .cloned();
+        if let Some(ledger) = cached {
+            trace!("Staged ledger cache hit {state_hash}");
+            if memoize && !self.is_staged_ledger_persisted(state_hash)? {
+                match self.get_block_height(state_hash)? {
+                    Some(block_height) => {
+                        self.add_staged_ledger_at_state_hash(state_hash, &ledger, block_height)?
+                    }
+                    None => bail!("Block missing from store {state_hash}"),
+                }
+            }
+            return Ok(Some(ledger));
+        }
+
+        let mut curr_state_hash = state_hash.clone();
+        let mut diffs = vec![];
+
+        // walk chain back to a stored ledger
+        // collect diffs to compute the current ledger
+        while !self.is_staged_ledger_persisted(&curr_state_hash)? {
+            trace!("No staged ledger found for state hash {curr_state_hash}");
+            if let Some(diff) = self.get_block_ledger_diff(&curr_state_hash)? {
+                diffs.push(diff);
+
+                if let Ok(Some(parent_hash)) = self.get_block_parent_hash(&curr_state_hash) {
+                    trace!("Checking for staged ledger state hash {parent_hash}");
+                    curr_state_hash = parent_hash;
+                }
+            } else {
+                if curr_state_hash.0 != MAINNET_GENESIS_PREV_STATE_HASH
+                    || curr_state_hash.0 != HARDFORK_GENESIS_PREV_STATE_HASH
+                {
+                    error!("Block missing from store: {curr_state_hash}");
+                }
+
+                return Ok(None);
+            }
+        }
+
+        trace!("Found staged ledger state hash {curr_state_hash}");
+        if let Ok(Some(mut ledger)) = self.build_staged_ledger(&curr_state_hash) {
+            // apply diffs
+            diffs.reverse();
+
+            let diff = LedgerDiff::append_vec(diffs);
+            ledger._apply_diff(&diff)?;
+
+            if memoize {
+                trace!("Memoizing ledger for block {state_hash}");
+
+                match self.get_block_height(state_hash)? {
+                    Some(block_height) => {
+                        self.add_staged_ledger_at_state_hash(state_hash, &ledger, block_height)?
+                    }
+                    None => bail!("Block missing from store {state_hash}"),
+                }
+            }
+
+            let ledger = Arc::new(ledger);
+            if cache {
+                self.staged_ledger_cache
+                    .lock()
+                    .unwrap()
+                    .put(state_hash.clone(), ledger.clone());
+            }
+            return Ok(Some(ledger));
+        }
+
+        Ok(None)
+    }
+
+    /// Check whether the block's staged ledger has been persisted
+    fn is_staged_ledger_persisted(&self, state_hash: &StateHash) -> anyhow::Result<bool> {
+        Ok(self
+            .database
+            .get_cf(self.staged_ledgers_persisted_cf(), state_hash.0.as_bytes())?
+            .is_some())
+    }
+
+    /// Get the ledger diff of the canonical block at the given height
+    fn get_canonical_block_ledger_diff(&self, height: u32) -> anyhow::Result<LedgerDiff> {
+        let state_hash = self
+            .get_canonical_hash_at_height(height)?
+            .with_context(|| format!("Canonical block missing at height {height}"))?;
+        self.get_block_ledger_diff(&state_hash)?
+            .with_context(|| format!("Block ledger diff missing {state_hash}"))
+    }
+}
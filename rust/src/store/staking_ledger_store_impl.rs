@@ -1,25 +1,41 @@
 use super::{column_families::ColumnFamilyHelpers, IndexerStore};
 use crate::{
     base::{public_key::PublicKey, state_hash::StateHash},
-    block::store::BlockStore,
+    block::{precomputed::PrecomputedBlock, store::BlockStore},
+    canonicity::{store::CanonicityStore, Canonicity},
     chain::store::ChainStore,
     event::{db::*, store::EventStore, IndexerEvent},
     ledger::{
+        distribution::WealthDistribution,
         staking::{
+            payouts::{EpochPayouts, PayoutBlock, PayoutConfig},
+            performance::{ProducerBlockCounts, ProducerPerformance},
             AggregatedEpochStakeDelegations, EpochStakeDelegation, StakingAccount, StakingLedger,
+            StakingLedgerSource,
+        },
+        store::{
+            distribution::DistributionStore,
+            staged::StagedLedgerStore,
+            staking::{StakingAccountWithEpochDelegation, StakingLedgerStore},
+            supply::SupplyStore,
         },
-        store::staking::{StakingAccountWithEpochDelegation, StakingLedgerStore},
         LedgerHash,
     },
-    utility::store::{
-        common::{from_be_bytes, u64_from_be_bytes},
-        ledger::staking::*,
+    utility::{
+        deadline::{check_scan, Deadline},
+        store::{
+            common::{
+                from_be_bytes, pk_index_key, state_hash_suffix, u32_from_be_bytes,
+                u64_from_be_bytes, U32_LEN,
+            },
+            ledger::staking::*,
+        },
     },
 };
-use anyhow::Context;
-use log::{error, trace};
+use anyhow::{bail, Context};
+use log::{error, info, trace};
 use speedb::{DBIterator, Direction, IteratorMode};
-use std::collections::HashMap;
+use std::{cmp::Ordering, collections::HashMap};
 
 impl StakingLedgerStore for IndexerStore {
     fn get_staking_account(
@@ -162,6 +178,17 @@ impl StakingLedgerStore for IndexerStore {
         self.set_staking_ledger_hash_epoch_pair(&ledger_hash, epoch, Some(genesis_state_hash))?;
         self.set_staking_ledger_hash_genesis_pair(&ledger_hash, genesis_state_hash)?;
         self.set_total_currency(&ledger_hash, staking_ledger.total_currency)?;
+        self.set_staked_supply(&ledger_hash, staking_ledger.staked_supply())?;
+        self.set_staking_ledger_source(&ledger_hash, staking_ledger.source)?;
+        self.set_staking_ledger_distribution(
+            &ledger_hash,
+            &WealthDistribution::new(
+                staking_ledger
+                    .staking_ledger
+                    .values()
+                    .map(|account| (account.balance, &account.delegate)),
+            ),
+        )?;
 
         // add staking ledger count at epoch
         let count = staking_ledger.staking_ledger.len();
@@ -347,6 +374,66 @@ impl StakingLedgerStore for IndexerStore {
             .and_then(|bytes| u64_from_be_bytes(&bytes).ok()))
     }
 
+    fn set_staking_ledger_source(
+        &self,
+        ledger_hash: &LedgerHash,
+        source: StakingLedgerSource,
+    ) -> anyhow::Result<()> {
+        trace!("Setting source {source} for staking ledger {ledger_hash}");
+        Ok(self.database.put_cf(
+            self.staking_ledger_source_cf(),
+            ledger_hash.0.as_bytes(),
+            serde_json::to_vec(&source)?,
+        )?)
+    }
+
+    fn get_staking_ledger_source(
+        &self,
+        ledger_hash: &LedgerHash,
+    ) -> anyhow::Result<Option<StakingLedgerSource>> {
+        trace!("Getting source for staking ledger {ledger_hash}");
+        Ok(self
+            .database
+            .get_cf(self.staking_ledger_source_cf(), ledger_hash.0.as_bytes())?
+            .map(|bytes| serde_json::from_slice(&bytes))
+            .transpose()?)
+    }
+
+    fn derive_epoch_staking_ledgers(&self, block: &PrecomputedBlock) -> anyhow::Result<Vec<u32>> {
+        let epoch = block.epoch_count();
+        let genesis_state_hash = self
+            .get_block_genesis_state_hash(&block.state_hash())?
+            .unwrap_or_else(|| block.genesis_state_hash());
+        let genesis_ledger_hash = block.genesis_ledger_hash();
+        let candidates = [
+            (
+                epoch,
+                block.staking_epoch_ledger_hash(),
+                block.staking_epoch_start_checkpoint(),
+            ),
+            (
+                epoch + 1,
+                block.next_epoch_ledger_hash(),
+                block.next_epoch_start_checkpoint(),
+            ),
+        ];
+
+        let mut derived = vec![];
+        for (epoch, ledger_hash, start_checkpoint) in candidates {
+            // the genesis ledger is used until the first snarked ledger
+            let checkpoint = if ledger_hash == genesis_ledger_hash {
+                genesis_state_hash.clone()
+            } else {
+                start_checkpoint
+            };
+
+            if self.derive_staking_ledger(epoch, &ledger_hash, &checkpoint, &genesis_state_hash)? {
+                derived.push(epoch);
+            }
+        }
+        Ok(derived)
+    }
+
     fn get_staking_ledger_accounts_count_epoch(
         &self,
         epoch: u32,
@@ -415,6 +502,9 @@ impl StakingLedgerStore for IndexerStore {
                     staking_ledger.insert(pk, account);
                 }
 
+                let source = self
+                    .get_staking_ledger_source(&ledger_hash)?
+                    .unwrap_or_default();
                 return Ok(Some(StakingLedger {
                     epoch,
                     network,
@@ -422,6 +512,7 @@ impl StakingLedgerStore for IndexerStore {
                     total_currency,
                     staking_ledger,
                     genesis_state_hash: genesis_hash.clone(),
+                    source,
                 }));
             }
         }
@@ -477,6 +568,133 @@ impl StakingLedgerStore for IndexerStore {
         Ok(None)
     }
 
+    fn get_epoch_payouts(
+        &self,
+        producer: &PublicKey,
+        epoch: u32,
+        genesis_state_hash: Option<&StateHash>,
+        config: PayoutConfig,
+        deadline: Option<&Deadline>,
+    ) -> anyhow::Result<Option<EpochPayouts>> {
+        trace!("Getting epoch {epoch} payouts for {producer}");
+        config.validate()?;
+
+        let best_block_genesis_hash = self.get_best_block_genesis_hash()?;
+        let genesis_state_hash = match genesis_state_hash.or(best_block_genesis_hash.as_ref()) {
+            Some(genesis_state_hash) => genesis_state_hash,
+            None => return Ok(None),
+        };
+        let ledger_hash =
+            match self.get_staking_ledger_hash_by_epoch(epoch, Some(genesis_state_hash))? {
+                Some(ledger_hash) => ledger_hash,
+                None => return Ok(None),
+            };
+
+        // producer's delegators
+        let mut delegators = vec![];
+        if let Some(delegations) =
+            self.get_epoch_delegations(producer, epoch, Some(genesis_state_hash))?
+        {
+            for pk in delegations.delegates.iter() {
+                check_scan(deadline)?;
+                delegators.push(
+                    self.get_staking_account(pk, epoch, Some(genesis_state_hash))?
+                        .with_context(|| format!("epoch {epoch}, account {pk}"))?,
+                );
+            }
+        }
+
+        // producer's canonical epoch blocks, from the epoch's first global slot
+        let protocol = self.protocol_params();
+        let Some((start_slot, end_slot)) = protocol.epoch_global_slots(epoch) else {
+            bail!("Epoch {epoch} ends after the max global slot")
+        };
+        let start = pk_index_key(producer, start_slot);
+
+        let mut blocks = vec![];
+        for (key, _) in self
+            .block_creator_global_slot_iterator(IteratorMode::From(&start, Direction::Forward))
+            .flatten()
+        {
+            check_scan(deadline)?;
+            if key[..PublicKey::LEN] != *producer.0.as_bytes()
+                || u32_from_be_bytes(&key[PublicKey::LEN..][..U32_LEN])? > end_slot
+            {
+                break;
+            }
+
+            let state_hash = state_hash_suffix(&key)?;
+            if self.get_block_genesis_state_hash(&state_hash)?.as_ref() != Some(genesis_state_hash)
+                || !matches!(
+                    self.get_block_canonicity(&state_hash)?,
+                    Some(Canonicity::Canonical)
+                )
+            {
+                continue;
+            }
+
+            let (block, _) = self
+                .get_block(&state_hash)?
+                .with_context(|| format!("block missing from store {state_hash}"))?;
+            blocks.push(PayoutBlock::from_precomputed(&block, &protocol));
+        }
+
+        Ok(Some(EpochPayouts::compute(
+            producer.clone(),
+            epoch,
+            ledger_hash,
+            &delegators,
+            blocks,
+            config,
+        )))
+    }
+
+    fn get_producer_performance(
+        &self,
+        pk: &PublicKey,
+        epoch: u32,
+        genesis_state_hash: Option<&StateHash>,
+    ) -> anyhow::Result<Option<ProducerPerformance>> {
+        trace!("Getting epoch {epoch} producer performance for {pk}");
+        let Some(totals) = self.epoch_production_totals(epoch, genesis_state_hash)? else {
+            return Ok(None);
+        };
+
+        let stake = self
+            .get_epoch_delegations(pk, epoch, Some(&totals.genesis_state_hash))?
+            .and_then(|delegations| delegations.total_delegated)
+            .unwrap_or_default();
+        self.producer_performance(pk.clone(), epoch, stake, &totals)
+            .map(Some)
+    }
+
+    fn get_epoch_producer_performances(
+        &self,
+        epoch: u32,
+        genesis_state_hash: Option<&StateHash>,
+        deadline: Option<&Deadline>,
+    ) -> anyhow::Result<Option<Vec<ProducerPerformance>>> {
+        trace!("Getting epoch {epoch} producer performances");
+        let Some(totals) = self.epoch_production_totals(epoch, genesis_state_hash)? else {
+            return Ok(None);
+        };
+
+        let mut performances = vec![];
+        for (key, _) in self
+            .staking_ledger_account_stake_iterator(epoch, Direction::Reverse)
+            .flatten()
+        {
+            check_scan(deadline)?;
+            let (key_epoch, stake, pk) = split_staking_ledger_sort_key(&key)?;
+            if key_epoch != epoch || stake == 0 {
+                break;
+            }
+
+            performances.push(self.producer_performance(pk, epoch, stake, &totals)?);
+        }
+        Ok(Some(performances))
+    }
+
     ///////////////
     // Iterators //
     ///////////////
@@ -516,3 +734,147 @@ impl StakingLedgerStore for IndexerStore {
             .iterator_cf(self.staking_ledger_persisted_cf(), mode)
     }
 }
+
+/// Network wide epoch values shared by each producer's performance
+struct EpochProductionTotals {
+    genesis_state_hash: StateHash,
+    total_currency: u64,
+    epoch_slots_produced: u32,
+    epoch_slots_elapsed: u32,
+}
+
+impl IndexerStore {
+    /// Derive & add the `epoch` staking ledger if it's missing & the indexer
+    /// has a staged ledger with hash `ledger_hash`
+    ///
+    /// An epoch ledger is the snarked ledger of the epoch data's start
+    /// checkpoint, the last block before the epoch transition which chose
+    /// the ledger (the lock checkpoint only fixes the epoch seed). That
+    /// snarked ledger is the staged ledger of an earlier block, all of whose
+    /// transactions have been proven, found by its staged ledger hash.
+    fn derive_staking_ledger(
+        &self,
+        epoch: u32,
+        ledger_hash: &LedgerHash,
+        checkpoint: &StateHash,
+        genesis_state_hash: &StateHash,
+    ) -> anyhow::Result<bool> {
+        if self
+            .get_staking_ledger_hash_by_epoch(epoch, Some(genesis_state_hash))?
+            .is_some()
+        {
+            return Ok(false);
+        }
+
+        // verify the checkpoint's snarked ledger is the epoch ledger
+        match self.get_block(checkpoint)? {
+            Some((block, _)) if block.snarked_ledger_hash() == *ledger_hash => (),
+            Some((block, _)) => {
+                error!(
+                    "Snarked ledger hash mismatch for checkpoint {checkpoint}, expected {ledger_hash} got {}",
+                    block.snarked_ledger_hash()
+                );
+                return Ok(false);
+            }
+            None => {
+                trace!("Missing checkpoint {checkpoint} to derive staking ledger (epoch {epoch})");
+                return Ok(false);
+            }
+        }
+
+        let Some(state_hash) = self.get_staged_ledger_block_state_hash(ledger_hash)? else {
+            trace!("No staged ledger {ledger_hash} to derive staking ledger (epoch {epoch})");
+            return Ok(false);
+        };
+
+        // verify the staged ledger against the block's staged ledger hash
+        if self.get_block_staged_ledger_hash(&state_hash)?.as_ref() != Some(ledger_hash) {
+            error!("Staged ledger hash mismatch for block {state_hash}, expected {ledger_hash}");
+            return Ok(false);
+        }
+        let Some(ledger) = self.staged_ledger_at_state_hash(&state_hash, false, false)? else {
+            trace!("Staged ledger missing for block {state_hash}");
+            return Ok(false);
+        };
+
+        let staking_ledger = StakingLedger::derive(
+            &ledger,
+            epoch,
+            self.get_current_network()?,
+            ledger_hash.clone(),
+            genesis_state_hash.clone(),
+            &self.protocol_params(),
+        );
+        info!(
+            "Derived staking ledger {} from block {state_hash}",
+            staking_ledger.summary()
+        );
+
+        self.add_staking_ledger(staking_ledger, genesis_state_hash)?;
+        Ok(true)
+    }
+
+    fn epoch_production_totals(
+        &self,
+        epoch: u32,
+        genesis_state_hash: Option<&StateHash>,
+    ) -> anyhow::Result<Option<EpochProductionTotals>> {
+        let best_block_genesis_hash = self.get_best_block_genesis_hash()?;
+        let Some(genesis_state_hash) = genesis_state_hash.or(best_block_genesis_hash.as_ref())
+        else {
+            return Ok(None);
+        };
+        let Some(ledger_hash) =
+            self.get_staking_ledger_hash_by_epoch(epoch, Some(genesis_state_hash))?
+        else {
+            return Ok(None);
+        };
+
+        let total_currency = self.get_total_currency(&ledger_hash)?.unwrap_or_default();
+        let epoch_slots_produced = self.get_epoch_slots_produced_count(Some(epoch))?;
+        let epoch_slots_elapsed = {
+            let current_epoch = self.get_current_epoch()?;
+            let protocol = self.protocol_params();
+            match epoch.cmp(&current_epoch) {
+                Ordering::Less => protocol.slots_per_epoch,
+                Ordering::Greater => 0,
+                Ordering::Equal => {
+                    protocol.epoch_slot(self.get_best_block_global_slot()?.unwrap_or_default()) + 1
+                }
+            }
+        };
+
+        Ok(Some(EpochProductionTotals {
+            genesis_state_hash: genesis_state_hash.clone(),
+            total_currency,
+            epoch_slots_produced,
+            epoch_slots_elapsed,
+        }))
+    }
+
+    fn producer_performance(
+        &self,
+        pk: PublicKey,
+        epoch: u32,
+        stake: u64,
+        totals: &EpochProductionTotals,
+    ) -> anyhow::Result<ProducerPerformance> {
+        let counts = ProducerBlockCounts {
+            num_blocks_produced: self.get_block_production_pk_epoch_count(&pk, Some(epoch))?,
+            num_canonical_blocks_produced: self
+                .get_block_production_pk_canonical_epoch_count(&pk, Some(epoch))?,
+            num_supercharged_blocks_produced: self
+                .get_block_production_pk_supercharged_epoch_count(&pk, Some(epoch))?,
+            num_slots_produced: self.get_pk_epoch_slots_produced_count(&pk, Some(epoch))?,
+        };
+        Ok(ProducerPerformance::new(
+            pk,
+            epoch,
+            stake,
+            totals.total_currency,
+            counts,
+            totals.epoch_slots_produced,
+            totals.epoch_slots_elapsed,
+        ))
+    }
+}
@@ -0,0 +1,412 @@
+use super::{column_families::ColumnFamilyHelpers, IndexerStore};
+use crate::{
+    base::{public_key::PublicKey, state_hash::StateHash},
+    block::{precomputed::PrecomputedBlock, store::BlockStore},
+    canonicity::{store::CanonicityStore, Canonicity},
+    ledger::{
+        account::{
+            vesting::{DelegatorVestingSchedules, SlotClock, VestingSchedule},
+            Timing,
+        },
+        store::{
+            best::BestLedgerStore,
+            supply::{BlockSupply, SupplyStore},
+        },
+        token::TokenAddress,
+        LedgerHash,
+    },
+    store::{fixed_keys::FixedKeys, Result},
+    utility::{
+        deadline::{check_scan, Deadline},
+        store::{
+            block::global_slot_state_hash_key,
+            common::{
+                block_u32_prefix_from_key, state_hash_suffix, u32_from_be_bytes, u32_prefix_key,
+                u64_from_be_bytes, U32_LEN,
+            },
+        },
+    },
+};
+use anyhow::Context;
+use log::trace;
+use speedb::{DBIterator, Direction, IteratorMode};
+
+impl SupplyStore for IndexerStore {
+    fn update_timed_account(&self, pk: &PublicKey, timing: Option<&Timing>) -> Result<()> {
+        let before = self.get_timed_account(pk)?;
+        if before.as_ref() == timing {
+            return Ok(());
+        }
+
+        // swap the account's contribution to the locked supply aggregate
+        let (global_slot, mut locked) = self.get_locked_supply_aggregate()?;
+        if let Some(before) = before.as_ref() {
+            locked = unlock(locked, before.minimum_balance(global_slot), pk)?;
+            if let Some(unlock_slot) = before.next_unlock_slot(global_slot) {
+                self.database.delete_cf(
+                    self.best_ledger_timed_accounts_unlocks_cf(),
+                    u32_prefix_key(unlock_slot, pk),
+                )?;
+            }
+        }
+        if let Some(timing) = timing {
+            locked += timing.minimum_balance(global_slot);
+            if let Some(unlock_slot) = timing.next_unlock_slot(global_slot) {
+                self.database.put_cf(
+                    self.best_ledger_timed_accounts_unlocks_cf(),
+                    u32_prefix_key(unlock_slot, pk),
+                    b"",
+                )?;
+            }
+        }
+        self.set_locked_supply_aggregate(global_slot, locked)?;
+
+        match timing {
+            Some(timing) => {
+                trace!("Updating timed account {pk}");
+                self.database.put_cf(
+                    self.best_ledger_timed_accounts_cf(),
+                    pk.0.as_bytes(),
+                    serde_json::to_vec(timing)?,
+                )?;
+            }
+            None => {
+                // untimed accounts returned early, avoiding tombstones
+                trace!("Removing timed account {pk}");
+                self.database
+                    .delete_cf(self.best_ledger_timed_accounts_cf(), pk.0.as_bytes())?;
+            }
+        }
+
+        Ok(())
+    }
+
+    fn get_timed_account(&self, pk: &PublicKey) -> Result<Option<Timing>> {
+        trace!("Getting timed account {pk}");
+        Ok(self
+            .database
+            .get_cf(self.best_ledger_timed_accounts_cf(), pk.0.as_bytes())?
+            .and_then(|bytes| serde_json::from_slice(&bytes).ok()))
+    }
+
+    fn get_locked_supply(&self, global_slot: u32, deadline: Option<&Deadline>) -> Result<u64> {
+        trace!("Getting locked supply at global slot {global_slot}");
+
+        let (aggregate_slot, mut locked) = self.get_locked_supply_aggregate()?;
+        if global_slot < aggregate_slot {
+            return self.compute_locked_supply(global_slot, deadline);
+        }
+
+        // only the accounts scheduled to unlock by the global slot change
+        for (key, _) in self
+            .timed_accounts_unlocks_iterator(IteratorMode::Start)
+            .flatten()
+        {
+            check_scan(deadline)?;
+            if block_u32_prefix_from_key(&key)? > global_slot {
+                break;
+            }
+
+            let pk = PublicKey::from_bytes(&key[U32_LEN..])?;
+            if let Some(timing) = self.get_timed_account(&pk)? {
+                locked = unlock(locked, timing.minimum_balance(aggregate_slot), &pk)?
+                    + timing.minimum_balance(global_slot);
+            }
+        }
+
+        Ok(locked)
+    }
+
+    fn update_locked_supply(&self, global_slot: u32) -> Result<u64> {
+        trace!("Updating locked supply to global slot {global_slot}");
+
+        let (aggregate_slot, mut locked) = self.get_locked_supply_aggregate()?;
+        if global_slot < aggregate_slot {
+            return self.rebuild_locked_supply(global_slot);
+        }
+
+        let mut unlocks = vec![];
+        for (key, _) in self
+            .timed_accounts_unlocks_iterator(IteratorMode::Start)
+            .flatten()
+        {
+            if block_u32_prefix_from_key(&key)? > global_slot {
+                break;
+            }
+            unlocks.push(key);
+        }
+
+        for key in unlocks {
+            self.database
+                .delete_cf(self.best_ledger_timed_accounts_unlocks_cf(), &key)?;
+
+            let pk = PublicKey::from_bytes(&key[U32_LEN..])?;
+            if let Some(timing) = self.get_timed_account(&pk)? {
+                locked = unlock(locked, timing.minimum_balance(aggregate_slot), &pk)?
+                    + timing.minimum_balance(global_slot);
+
+                // reschedule the account's next unlock
+                if let Some(unlock_slot) = timing.next_unlock_slot(global_slot) {
+                    self.database.put_cf(
+                        self.best_ledger_timed_accounts_unlocks_cf(),
+                        u32_prefix_key(unlock_slot, &pk),
+                        b"",
+                    )?;
+                }
+            }
+        }
+
+        self.set_locked_supply_aggregate(global_slot, locked)?;
+        Ok(locked)
+    }
+
+    fn add_block_supply(&self, block: &PrecomputedBlock) -> Result<BlockSupply> {
+        let state_hash = block.state_hash();
+        trace!("Adding block supply {state_hash}");
+
+        let locked = self.update_locked_supply(block.global_slot_since_genesis())?;
+        let staked = self.get_staked_supply(&block.staking_epoch_ledger_hash())?;
+        let supply = BlockSupply::new(block, locked, staked);
+
+        self.database.put_cf(
+            self.best_ledger_block_supply_cf(),
+            global_slot_state_hash_key(block.global_slot_since_genesis(), &state_hash),
+            serde_json::to_vec(&supply)?,
+        )?;
+
+        Ok(supply)
+    }
+
+    fn get_block_supply(&self, state_hash: &StateHash) -> Result<Option<BlockSupply>> {
+        trace!("Getting block supply {state_hash}");
+
+        let supply = match self.get_block_global_slot(state_hash)? {
+            Some(global_slot) => self
+                .database
+                .get_cf(
+                    self.best_ledger_block_supply_cf(),
+                    global_slot_state_hash_key(global_slot, state_hash),
+                )?
+                .map(|bytes| serde_json::from_slice(&bytes))
+                .transpose()?,
+            None => None,
+        };
+
+        supply
+            .map(|supply| self.with_staked_supply(supply))
+            .transpose()
+    }
+
+    fn get_supply_time_series(
+        &self,
+        start_slot: u32,
+        end_slot: u32,
+        limit: usize,
+        deadline: Option<&Deadline>,
+    ) -> Result<Vec<BlockSupply>> {
+        trace!("Getting supply time series between global slots {start_slot} & {end_slot}");
+
+        let mut supplies = vec![];
+        let start = start_slot.to_be_bytes();
+        for (key, value) in self
+            .block_supply_iterator(IteratorMode::From(&start, Direction::Forward))
+            .flatten()
+        {
+            check_scan(deadline)?;
+            if supplies.len() >= limit || block_u32_prefix_from_key(&key)? > end_slot {
+                break;
+            }
+
+            // best tips may have been orphaned by a reorg
+            let state_hash = state_hash_suffix(&key)?;
+            if self.get_block_canonicity(&state_hash)? != Some(Canonicity::Canonical) {
+                continue;
+            }
+
+            supplies.push(self.with_staked_supply(serde_json::from_slice(&value)?)?);
+        }
+
+        Ok(supplies)
+    }
+
+    fn get_slot_clock(&self) -> Result<SlotClock> {
+        trace!("Getting slot clock");
+
+        let genesis = match self.get_best_block_genesis_hash()? {
+            Some(genesis_state_hash) => self.get_block(&genesis_state_hash)?,
+            None => None,
+        };
+        let protocol = self.protocol_params();
+        Ok(match genesis {
+            Some((block, _)) => SlotClock {
+                genesis_timestamp: block.timestamp(),
+                genesis_global_slot: block.global_slot_since_genesis(),
+                slot_duration: protocol.block_window_duration_ms,
+            },
+            None => protocol.slot_clock(),
+        })
+    }
+
+    fn get_vesting_schedule(
+        &self,
+        pk: &PublicKey,
+        interval: u32,
+    ) -> Result<Option<VestingSchedule>> {
+        trace!("Getting vesting schedule {pk}");
+
+        let clock = self.get_slot_clock()?;
+        Ok(self
+            .get_timed_account(pk)?
+            .map(|timing| VestingSchedule::project(pk.clone(), timing, &clock, interval)))
+    }
+
+    fn get_delegator_vesting_schedules(
+        &self,
+        delegate: &PublicKey,
+        interval: u32,
+        deadline: Option<&Deadline>,
+    ) -> Result<DelegatorVestingSchedules> {
+        trace!("Getting delegator vesting schedules {delegate}");
+
+        let mut delegators = vec![];
+        for (key, value) in self.timed_accounts_iterator(IteratorMode::Start).flatten() {
+            check_scan(deadline)?;
+            let pk = PublicKey::from_bytes(&key)?;
+            if let Some(account) = self.get_best_account(&pk, &TokenAddress::default())? {
+                if account.delegate == *delegate {
+                    delegators.push((pk, serde_json::from_slice(&value)?));
+                }
+            }
+        }
+
+        let clock = self.get_slot_clock()?;
+        Ok(DelegatorVestingSchedules::project(
+            delegate.clone(),
+            delegators,
+            &clock,
+            interval,
+        ))
+    }
+
+    fn set_staked_supply(&self, ledger_hash: &LedgerHash, staked: u64) -> Result<()> {
+        trace!("Setting staked supply {staked} for staking ledger {ledger_hash}");
+        Ok(self.database.put_cf(
+            self.staking_ledger_staked_supply_cf(),
+            ledger_hash.0.as_bytes(),
+            staked.to_be_bytes(),
+        )?)
+    }
+
+    fn get_staked_supply(&self, ledger_hash: &LedgerHash) -> Result<Option<u64>> {
+        trace!("Getting staked supply for staking ledger {ledger_hash}");
+        Ok(self
+            .database
+            .get_cf(
+                self.staking_ledger_staked_supply_cf(),
+                ledger_hash.0.as_bytes(),
+            )?
+            .and_then(|bytes| u64_from_be_bytes(&bytes).ok()))
+    }
+
+    ///////////////
+    // Iterators //
+    ///////////////
+
+    fn timed_accounts_iterator(&self, mode: IteratorMode) -> DBIterator<'_> {
+        self.database
+            .iterator_cf(self.best_ledger_timed_accounts_cf(), mode)
+    }
+
+    fn timed_accounts_unlocks_iterator(&self, mode: IteratorMode) -> DBIterator<'_> {
+        self.database
+            .iterator_cf(self.best_ledger_timed_accounts_unlocks_cf(), mode)
+    }
+
+    fn block_supply_iterator(&self, mode: IteratorMode) -> DBIterator<'_> {
+        self.database
+            .iterator_cf(self.best_ledger_block_supply_cf(), mode)
+    }
+}
+
+impl IndexerStore {
+    /// Global slot & locked supply of the best ledger's timed accounts at
+    /// that slot, maintained as the timed accounts & best tip change
+    fn get_locked_supply_aggregate(&self) -> Result<(u32, u64)> {
+        Ok(
+            match self.database.get(self.fixed_key(Self::LOCKED_SUPPLY_KEY))? {
+                Some(bytes) => (
+                    u32_from_be_bytes(&bytes[..U32_LEN])?,
+                    u64_from_be_bytes(&bytes[U32_LEN..])?,
+                ),
+                None => (0, 0),
+            },
+        )
+    }
+
+    fn set_locked_supply_aggregate(&self, global_slot: u32, locked: u64) -> Result<()> {
+        Ok(self.database.put(
+            self.fixed_key(Self::LOCKED_SUPPLY_KEY),
+            [global_slot.to_be_bytes().as_slice(), &locked.to_be_bytes()].concat(),
+        )?)
+    }
+
+    /// Sum the minimum balances of all timed accounts at the global slot
+    fn compute_locked_supply(&self, global_slot: u32, deadline: Option<&Deadline>) -> Result<u64> {
+        let mut locked = 0;
+        for (_, value) in self.timed_accounts_iterator(IteratorMode::Start).flatten() {
+            check_scan(deadline)?;
+            let timing: Timing = serde_json::from_slice(&value)?;
+            locked += timing.minimum_balance(global_slot);
+        }
+
+        Ok(locked)
+    }
+
+    /// The best tip's global slot decreased in a reorg, reschedule all
+    /// timed accounts' unlocks from the new global slot
+    fn rebuild_locked_supply(&self, global_slot: u32) -> Result<u64> {
+        for (key, _) in self
+            .timed_accounts_unlocks_iterator(IteratorMode::Start)
+            .flatten()
+        {
+            self.database
+                .delete_cf(self.best_ledger_timed_accounts_unlocks_cf(), key)?;
+        }
+
+        let mut locked = 0;
+        for (key, value) in self.timed_accounts_iterator(IteratorMode::Start).flatten() {
+            let pk = PublicKey::from_bytes(&key)?;
+            let timing: Timing = serde_json::from_slice(&value)?;
+            locked += timing.minimum_balance(global_slot);
+
+            if let Some(unlock_slot) = timing.next_unlock_slot(global_slot) {
+                self.database.put_cf(
+                    self.best_ledger_timed_accounts_unlocks_cf(),
+                    u32_prefix_key(unlock_slot, &pk),
+                    b"",
+                )?;
+            }
+        }
+
+        self.set_locked_supply_aggregate(global_slot, locked)?;
+        Ok(locked)
+    }
+
+    /// The staking ledger may be ingested after the block's supply is cached
+    fn with_staked_supply(&self, mut supply: BlockSupply) -> Result<BlockSupply> {
+        if supply.staked.is_none() {
+            supply.staked = self.get_staked_supply(&supply.staking_ledger_hash)?;
+        }
+        Ok(supply)
+    }
+}
+
+/// Remove the timed account's minimum balance from the locked supply
+/// aggregate, which can't underflow unless the aggregate is corrupt
+fn unlock(locked: u64, minimum_balance: u64, pk: &PublicKey) -> Result<u64> {
+    locked.checked_sub(minimum_balance).with_context(|| {
+        format!(
+            "Locked supply {locked} less than timed account {pk} minimum balance {minimum_balance}"
+        )
+    })
+}
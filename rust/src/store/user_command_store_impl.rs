@@ -3,7 +3,7 @@ use super::{
     username::UsernameStore, IndexerStore,
 };
 use crate::{
-    base::{public_key::PublicKey, state_hash::StateHash},
+    base::{nonce::Nonce, public_key::PublicKey, state_hash::StateHash},
     block::{
         precomputed::PrecomputedBlock,
         store::{BlockStore, DbBlockUpdate},
@@ -23,7 +23,7 @@ use crate::{
 use anyhow::bail;
 use log::{trace, warn};
 use speedb::{DBIterator, IteratorMode, WriteBatch};
-use std::path::PathBuf;
+use std::{collections::HashMap, path::PathBuf};
 
 impl UserCommandStore for IndexerStore {
     fn add_user_commands_batch(
@@ -289,6 +289,25 @@ impl UserCommandStore for IndexerStore {
             .and_then(|bytes| serde_json::from_slice(&bytes).ok()))
     }
 
+    fn get_block_user_command_hashes(
+        &self,
+        state_hash: &StateHash,
+    ) -> anyhow::Result<HashMap<(PublicKey, Nonce), TxnHash>> {
+        trace!("Getting block user command hashes {state_hash}");
+        Ok(self
+            .get_block_user_commands(state_hash)?
+            .unwrap_or_default()
+            .into_iter()
+            .filter_map(|cmd| {
+                let key = (cmd.sender(), cmd.nonce());
+                SignedCommand::from(cmd)
+                    .hash_signed_command()
+                    .ok()
+                    .map(|txn_hash| (key, txn_hash))
+            })
+            .collect())
+    }
+
     fn get_user_commands_for_public_key(
         &self,
         pk: &PublicKey,
@@ -511,16 +530,17 @@ impl UserCommandStore for IndexerStore {
         trace!("Getting user command total");
         Ok(self
             .database
-            .get(Self::TOTAL_NUM_USER_COMMANDS_KEY)?
+            .get(self.fixed_key(Self::TOTAL_NUM_USER_COMMANDS_KEY))?
             .map_or(0, from_be_bytes))
     }
 
     fn increment_user_commands_total_count(&self) -> anyhow::Result<()> {
         trace!("Incrementing user command total");
         let old = self.get_user_commands_total_count()?;
-        Ok(self
-            .database
-            .put(Self::TOTAL_NUM_USER_COMMANDS_KEY, (old + 1).to_be_bytes())?)
+        Ok(self.database.put(
+            self.fixed_key(Self::TOTAL_NUM_USER_COMMANDS_KEY),
+            (old + 1).to_be_bytes(),
+        )?)
     }
 
     fn get_user_commands_pk_epoch_count(
@@ -630,7 +650,7 @@ impl UserCommandStore for IndexerStore {
         trace!("Getting applied user command count");
         Ok(self
             .database
-            .get(Self::TOTAL_NUM_APPLIED_USER_COMMANDS_KEY)?
+            .get(self.fixed_key(Self::TOTAL_NUM_APPLIED_USER_COMMANDS_KEY))?
             .map_or(0, from_be_bytes))
     }
 
@@ -639,7 +659,7 @@ impl UserCommandStore for IndexerStore {
         trace!("Getting failed user command count");
         Ok(self
             .database
-            .get(Self::TOTAL_NUM_FAILED_USER_COMMANDS_KEY)?
+            .get(self.fixed_key(Self::TOTAL_NUM_FAILED_USER_COMMANDS_KEY))?
             .map_or(0, from_be_bytes))
     }
 
@@ -648,7 +668,7 @@ impl UserCommandStore for IndexerStore {
         trace!("Incrementing applied user command count");
         let old = self.get_applied_user_commands_count()?;
         Ok(self.database.put(
-            Self::TOTAL_NUM_APPLIED_USER_COMMANDS_KEY,
+            self.fixed_key(Self::TOTAL_NUM_APPLIED_USER_COMMANDS_KEY),
             (old + incr).to_be_bytes(),
         )?)
     }
@@ -658,7 +678,7 @@ impl UserCommandStore for IndexerStore {
         trace!("Incrementing failed user command count");
         let old = self.get_failed_user_commands_count()?;
         Ok(self.database.put(
-            Self::TOTAL_NUM_FAILED_USER_COMMANDS_KEY,
+            self.fixed_key(Self::TOTAL_NUM_FAILED_USER_COMMANDS_KEY),
             (old + incr).to_be_bytes(),
         )?)
     }
@@ -668,7 +688,7 @@ impl UserCommandStore for IndexerStore {
         trace!("Decrementing applied user command count");
         let old = self.get_applied_user_commands_count()?;
         Ok(self.database.put(
-            Self::TOTAL_NUM_APPLIED_USER_COMMANDS_KEY,
+            self.fixed_key(Self::TOTAL_NUM_APPLIED_USER_COMMANDS_KEY),
             (old.saturating_sub(incr)).to_be_bytes(),
         )?)
     }
@@ -678,7 +698,7 @@ impl UserCommandStore for IndexerStore {
         trace!("Decrementing failed user command count");
         let old = self.get_failed_user_commands_count()?;
         Ok(self.database.put(
-            Self::TOTAL_NUM_FAILED_USER_COMMANDS_KEY,
+            self.fixed_key(Self::TOTAL_NUM_FAILED_USER_COMMANDS_KEY),
             (old.saturating_sub(incr)).to_be_bytes(),
         )?)
     }
@@ -688,7 +708,7 @@ impl UserCommandStore for IndexerStore {
         trace!("Getting canonical user command count");
         Ok(self
             .database
-            .get(Self::TOTAL_NUM_CANONICAL_USER_COMMANDS_KEY)?
+            .get(self.fixed_key(Self::TOTAL_NUM_CANONICAL_USER_COMMANDS_KEY))?
             .map_or(0, from_be_bytes))
     }
 
@@ -697,7 +717,7 @@ impl UserCommandStore for IndexerStore {
         trace!("Incrementing canonical user command count");
         let old = self.get_canonical_user_commands_count()?;
         Ok(self.database.put(
-            Self::TOTAL_NUM_CANONICAL_USER_COMMANDS_KEY,
+            self.fixed_key(Self::TOTAL_NUM_CANONICAL_USER_COMMANDS_KEY),
             (old + incr).to_be_bytes(),
         )?)
     }
@@ -707,7 +727,7 @@ impl UserCommandStore for IndexerStore {
         trace!("Decrementing canonical user command count");
         let old = self.get_canonical_user_commands_count()?;
         Ok(self.database.put(
-            Self::TOTAL_NUM_CANONICAL_USER_COMMANDS_KEY,
+            self.fixed_key(Self::TOTAL_NUM_CANONICAL_USER_COMMANDS_KEY),
             (old.saturating_sub(incr)).to_be_bytes(),
         )?)
     }
@@ -717,7 +737,7 @@ impl UserCommandStore for IndexerStore {
         trace!("Getting applied canonical user command count");
         Ok(self
             .database
-            .get(Self::TOTAL_NUM_APPLIED_CANONICAL_USER_COMMANDS_KEY)?
+            .get(self.fixed_key(Self::TOTAL_NUM_APPLIED_CANONICAL_USER_COMMANDS_KEY))?
             .map_or(0, from_be_bytes))
     }
 
@@ -726,7 +746,7 @@ impl UserCommandStore for IndexerStore {
         trace!("Incrementing applied canonical user command count");
         let old = self.get_applied_canonical_user_commands_count()?;
         Ok(self.database.put(
-            Self::TOTAL_NUM_APPLIED_CANONICAL_USER_COMMANDS_KEY,
+            self.fixed_key(Self::TOTAL_NUM_APPLIED_CANONICAL_USER_COMMANDS_KEY),
             (old + incr).to_be_bytes(),
         )?)
     }
@@ -736,7 +756,7 @@ impl UserCommandStore for IndexerStore {
         trace!("Decrementing applied canonical user command count");
         let old = self.get_applied_canonical_user_commands_count()?;
         Ok(self.database.put(
-            Self::TOTAL_NUM_APPLIED_CANONICAL_USER_COMMANDS_KEY,
+            self.fixed_key(Self::TOTAL_NUM_APPLIED_CANONICAL_USER_COMMANDS_KEY),
             (old.saturating_sub(incr)).to_be_bytes(),
         )?)
     }
@@ -746,7 +766,7 @@ impl UserCommandStore for IndexerStore {
         trace!("Getting failed canonical user command count");
         Ok(self
             .database
-            .get(Self::TOTAL_NUM_FAILED_CANONICAL_USER_COMMANDS_KEY)?
+            .get(self.fixed_key(Self::TOTAL_NUM_FAILED_CANONICAL_USER_COMMANDS_KEY))?
             .map_or(0, from_be_bytes))
     }
 
@@ -755,7 +775,7 @@ impl UserCommandStore for IndexerStore {
         trace!("Incrementing failed canonical user command count");
         let old = self.get_failed_canonical_user_commands_count()?;
         Ok(self.database.put(
-            Self::TOTAL_NUM_FAILED_CANONICAL_USER_COMMANDS_KEY,
+            self.fixed_key(Self::TOTAL_NUM_FAILED_CANONICAL_USER_COMMANDS_KEY),
             (old + incr).to_be_bytes(),
         )?)
     }
@@ -765,7 +785,7 @@ impl UserCommandStore for IndexerStore {
         trace!("Decrementing failed canonical user command count");
         let old = self.get_failed_canonical_user_commands_count()?;
         Ok(self.database.put(
-            Self::TOTAL_NUM_FAILED_CANONICAL_USER_COMMANDS_KEY,
+            self.fixed_key(Self::TOTAL_NUM_FAILED_CANONICAL_USER_COMMANDS_KEY),
             (old.saturating_sub(incr)).to_be_bytes(),
         )?)
     }
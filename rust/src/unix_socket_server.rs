@@ -2,18 +2,27 @@ use crate::{
     base::{public_key::PublicKey, state_hash::StateHash},
     block::{precomputed::PrecomputedBlockWithCanonicity, store::BlockStore, BlockWithoutHeight},
     canonicity::store::CanonicityStore,
+    chain::{profile::ProtocolParams, Network},
     client::*,
     command::{
         internal::store::InternalCommandStore, signed::TxnHash, store::UserCommandStore, Command,
     },
     ledger::{
-        staking::AggregatedEpochStakeDelegation,
-        store::{best::BestLedgerStore, staged::StagedLedgerStore, staking::StakingLedgerStore},
+        staking::{
+            payouts::PayoutConfig,
+            performance::{ProducerPerformance, ProducerPerformanceSort},
+            AggregatedEpochStakeDelegation,
+        },
+        store::{
+            best::BestLedgerStore, staged::StagedLedgerStore, staking::StakingLedgerStore,
+            supply::SupplyStore,
+        },
         token::TokenAddress,
         Ledger, LedgerHash,
     },
+    server::NetworkStates,
     snark_work::store::SnarkStore,
-    state::{summary::SummaryShort, IndexerState},
+    state::summary::SummaryShort,
     store::version::VersionStore,
 };
 use anyhow::{bail, Context};
@@ -21,12 +30,10 @@ use log::{debug, error, info, trace, warn};
 use std::{
     io::{self, ErrorKind},
     path::Path,
-    sync::Arc,
 };
 use tokio::{
     io::AsyncWriteExt,
     net::{UnixListener, UnixStream},
-    sync::RwLock,
 };
 use tokio_graceful_shutdown::{FutureExt, SubsystemHandle};
 
@@ -41,7 +48,8 @@ pub fn create_socket_listener(domain_socket_path: &Path) -> UnixListener {
     listener
 }
 
-async fn parse_conn_to_cli(stream: &UnixStream) -> anyhow::Result<ClientCli> {
+/// Reads the queried network & client command from the stream
+async fn parse_conn_to_cli(stream: &UnixStream) -> anyhow::Result<(Option<Network>, ClientCli)> {
     loop {
         stream.readable().await?;
 
@@ -58,9 +66,9 @@ async fn parse_conn_to_cli(stream: &UnixStream) -> anyhow::Result<ClientCli> {
                 return Err(e.into());
             }
         }
-        let (command, _): (ClientCli, usize) =
+        let (request, _): ((Option<Network>, ClientCli), usize) =
             bincode::decode_from_slice(&buffer, BIN_CODE_CONFIG)?;
-        return Ok(command);
+        return Ok(request);
     }
     bail!("Unexpected Unix domain socket read error");
 }
@@ -69,7 +77,7 @@ async fn parse_conn_to_cli(stream: &UnixStream) -> anyhow::Result<ClientCli> {
 #[allow(clippy::too_many_lines)]
 pub async fn handle_connection(
     listener: UnixListener,
-    state: Arc<RwLock<IndexerState>>,
+    states: NetworkStates,
     subsys: SubsystemHandle,
 ) -> anyhow::Result<()> {
     use helpers::*;
@@ -80,16 +88,23 @@ pub async fn handle_connection(
             Err(_) => break,
         }?;
 
-        let state = state.read().await;
+        let (network, command) = parse_conn_to_cli(&connection).await?;
+        let (_, mut writer) = connection.into_split();
+
+        let state = match states.get(network.as_ref()) {
+            Ok(state) => state.read().await,
+            Err(e) => {
+                error!("{e}");
+                writer.write_all(e.to_string().as_bytes()).await?;
+                continue;
+            }
+        };
         let db = if let Some(store) = state.indexer_store.as_ref() {
             store
         } else {
             bail!("Unable to get a handle on indexer store...");
         };
 
-        let command = parse_conn_to_cli(&connection).await?;
-        let (_, mut writer) = connection.into_split();
-
         let response_json = match command {
             ClientCli::Accounts(__) => match __ {
                 Accounts::PublicKey { public_key: pk } => {
@@ -100,12 +115,62 @@ pub async fn handle_connection(
                         let pk: PublicKey = pk.into();
                         if let Some(account) = db.get_best_account(&pk, &TokenAddress::default())? {
                             info!("Writing account {pk} to client");
-                            Some(format!("{account}"))
+                            Some(serde_json::to_string_pretty(
+                                &account.display(&db.protocol_params()),
+                            )?)
                         } else {
                             account_missing_from_db(&pk)
                         }
                     }
                 }
+                Accounts::Vesting {
+                    public_key: pk,
+                    delegators,
+                    interval,
+                    csv,
+                    path,
+                } => {
+                    info!("Received account vesting command for {pk}");
+                    if !PublicKey::is_valid(&pk) {
+                        invalid_public_key(&pk)
+                    } else {
+                        let pk: PublicKey = pk.into();
+                        let schedule_str = if delegators {
+                            let schedules =
+                                db.get_delegator_vesting_schedules(&pk, interval, None)?;
+                            Some(if csv {
+                                schedules.to_csv()?
+                            } else {
+                                serde_json::to_string_pretty(&schedules)?
+                            })
+                        } else if let Some(schedule) = db.get_vesting_schedule(&pk, interval)? {
+                            Some(if csv {
+                                schedule.to_csv()?
+                            } else {
+                                serde_json::to_string_pretty(&schedule)?
+                            })
+                        } else {
+                            None
+                        };
+
+                        match (schedule_str, path) {
+                            (None, _) => Some(format!("Account {pk} is not a timed account")),
+                            (Some(schedule_str), None) => {
+                                debug!("Writing vesting schedule for {pk} to stdout");
+                                Some(schedule_str)
+                            }
+                            (Some(schedule_str), Some(path)) => {
+                                if !path.is_dir() {
+                                    debug!("Writing vesting schedule for {pk} to {path:?}");
+                                    std::fs::write(&path, schedule_str)?;
+                                    Some(format!("Vesting schedule for {pk} written to {path:?}"))
+                                } else {
+                                    file_must_not_be_a_directory(&path)
+                                }
+                            }
+                        }
+                    }
+                }
             },
             ClientCli::Blocks(__) => match __ {
                 Blocks::Best { verbose, path } => {
@@ -552,6 +617,29 @@ pub async fn handle_connection(
                         best_tip_missing_from_db()
                     }
                 }
+                Chain::Tree { path, dot } => {
+                    info!("Received witness tree command");
+                    let witness_tree = state.witness_tree();
+                    let witness_tree_str = if dot {
+                        witness_tree.to_dot()
+                    } else {
+                        serde_json::to_string_pretty(&witness_tree)?
+                    };
+
+                    if path.is_none() {
+                        info!("Writing witness tree to stdout");
+                        Some(witness_tree_str)
+                    } else {
+                        let path = path.unwrap();
+                        if !path.is_dir() {
+                            info!("Writing witness tree to {path:?}");
+                            std::fs::write(&path, witness_tree_str)?;
+                            Some(format!("Witness tree written to {path:?}"))
+                        } else {
+                            file_must_not_be_a_directory(&path)
+                        }
+                    }
+                }
             },
             ClientCli::CreateSnapshot { output_path } => {
                 info!("Received create-snapshot command");
@@ -564,7 +652,7 @@ pub async fn handle_connection(
                 Ledgers::Best { path, memoize } => {
                     info!("Received best-ledger command");
                     if let Some(ledger) = db.get_best_ledger(memoize)? {
-                        let ledger = ledger.to_string_pretty();
+                        let ledger = ledger.to_string_pretty(&db.protocol_params());
                         if path.is_none() {
                             debug!("Writing best ledger to stdout");
                             Some(ledger)
@@ -591,18 +679,23 @@ pub async fn handle_connection(
                     info!("Received staged ledger command for {hash}");
                     fn write_ledger(
                         path: Option<std::path::PathBuf>,
-                        ledger: Ledger,
+                        ledger: &Ledger,
                         hash: &str,
+                        protocol: &ProtocolParams,
                     ) -> Option<String> {
-                        let ledger = ledger.to_string_pretty();
                         if path.is_none() {
                             debug!("Writing staged ledger at hash {hash} to stdout");
-                            Some(ledger)
+                            Some(ledger.to_string_pretty(protocol))
                         } else {
                             let path = path.unwrap();
                             if !path.is_dir() {
                                 debug!("Writing staged ledger at {hash} to {path:?}");
-                                std::fs::write(path.clone(), ledger).ok();
+                                std::fs::File::create(&path)
+                                    .map_err(anyhow::Error::from)
+                                    .and_then(|file| {
+                                        ledger.write_pretty(std::io::BufWriter::new(file), protocol)
+                                    })
+                                    .ok();
                                 Some(format!("Ledger at hash {hash} written to {path:?}"))
                             } else {
                                 file_must_not_be_a_directory(&path)
@@ -616,7 +709,7 @@ pub async fn handle_connection(
                         if let Some(ledger) =
                             db.get_staged_ledger_at_state_hash(&hash.clone().into(), memoize)?
                         {
-                            write_ledger(path, ledger, &hash)
+                            write_ledger(path, &ledger, &hash, &db.protocol_params())
                         } else {
                             error!("Ledger at state hash {hash} is not in the store");
                             Some(format!("Ledger at state hash {hash} is not in the store"))
@@ -627,7 +720,7 @@ pub async fn handle_connection(
                             &LedgerHash::new_or_panic(hash.clone()),
                             memoize,
                         )? {
-                            write_ledger(path, ledger, &hash)
+                            write_ledger(path, &ledger, &hash, &db.protocol_params())
                         } else {
                             error!("Ledger at ledger hash {hash} is not in the store");
                             Some(format!("Ledger at ledger hash {hash} is not in the store"))
@@ -647,24 +740,29 @@ pub async fn handle_connection(
                         if height > best_tip_height {
                             // ahead of witness tree - cannot compute
                             Some(format!("Invalid query: ledger at height {height} cannot be determined from a chain of length {best_tip_height}"))
-                        } else {
-                            let ledger_str = db
-                                .get_staged_ledger_at_block_height(height, memoize)?
-                                .unwrap()
-                                .to_string_pretty();
+                        } else if let Some(ledger) =
+                            db.get_staged_ledger_at_block_height(height, memoize)?
+                        {
                             if path.is_none() {
                                 debug!("Writing ledger at height {height} to stdout");
-                                Some(ledger_str)
+                                Some(ledger.to_string_pretty(&db.protocol_params()))
                             } else {
                                 let path = path.unwrap();
                                 if !path.is_dir() {
                                     debug!("Writing ledger at height {height} to {path:?}");
-                                    std::fs::write(&path, ledger_str)?;
+                                    let file = std::fs::File::create(&path)?;
+                                    ledger.write_pretty(
+                                        std::io::BufWriter::new(file),
+                                        &db.protocol_params(),
+                                    )?;
                                     Some(format!("Ledger at height {height} written to {path:?}"))
                                 } else {
                                     file_must_not_be_a_directory(&path)
                                 }
                             }
+                        } else {
+                            error!("Ledger at height {height} is not in the store");
+                            Some(format!("Ledger at height {height} is not in the store"))
                         }
                     } else {
                         best_tip_missing_from_db()
@@ -788,6 +886,129 @@ pub async fn handle_connection(
                         ))
                     }
                 }
+                StakingLedgers::Payouts {
+                    epoch,
+                    genesis_state_hash,
+                    public_key: pk,
+                    pool_fee,
+                    exclude_tx_fees,
+                    share_supercharge,
+                    csv,
+                    path,
+                } => {
+                    info!("Received staking-ledgers-payouts command for pk {pk} epoch {epoch}");
+                    let config = PayoutConfig {
+                        pool_fee,
+                        include_tx_fees: !exclude_tx_fees,
+                        supercharge_unlocked_only: !share_supercharge,
+                    };
+                    if !StateHash::is_valid(&genesis_state_hash) {
+                        invalid_state_hash(&genesis_state_hash)
+                    } else if !PublicKey::is_valid(&pk) {
+                        invalid_public_key(&pk)
+                    } else if let Err(e) = config.validate() {
+                        Some(e.to_string())
+                    } else if let Some(payouts) = db.get_epoch_payouts(
+                        &pk.clone().into(),
+                        epoch,
+                        Some(&genesis_state_hash.into()),
+                        config,
+                        None,
+                    )? {
+                        let payouts_str = if csv {
+                            payouts.to_csv()?
+                        } else {
+                            serde_json::to_string_pretty(&payouts)?
+                        };
+                        if path.is_none() {
+                            debug!("Writing epoch {epoch} payouts for {pk} to stdout");
+                            Some(payouts_str)
+                        } else {
+                            let path = path.unwrap();
+                            if !path.is_dir() {
+                                debug!("Writing epoch {epoch} payouts for {pk} to {path:?}");
+                                std::fs::write(&path, payouts_str)?;
+                                Some(format!(
+                                    "Epoch {epoch} payouts for {pk} written to {path:?}"
+                                ))
+                            } else {
+                                file_must_not_be_a_directory(&path)
+                            }
+                        }
+                    } else {
+                        error!("Staking ledger at epoch {epoch} is not in the store");
+                        Some(format!(
+                            "Staking ledger at epoch {epoch} is not in the store"
+                        ))
+                    }
+                }
+                StakingLedgers::Performance {
+                    epoch,
+                    genesis_state_hash,
+                    public_key,
+                    sort_by,
+                    limit,
+                    path,
+                } => {
+                    info!("Received staking-ledgers-performance command for epoch {epoch}");
+                    let genesis_state_hash_valid = StateHash::is_valid(&genesis_state_hash);
+                    let genesis_state_hash: StateHash = genesis_state_hash.into();
+                    let performances = match (
+                        genesis_state_hash_valid,
+                        public_key,
+                        sort_by.parse::<ProducerPerformanceSort>(),
+                    ) {
+                        (false, _, _) => Err(invalid_state_hash(&genesis_state_hash.0)),
+                        (_, _, Err(e)) => Err(Some(e.to_string())),
+                        (_, Some(pk), _) if !PublicKey::is_valid(&pk) => {
+                            Err(invalid_public_key(&pk))
+                        }
+                        (_, Some(pk), _) => Ok(db
+                            .get_producer_performance(&pk.into(), epoch, Some(&genesis_state_hash))?
+                            .map(|performance| vec![performance])),
+                        (_, None, Ok(sort)) => Ok(db
+                            .get_epoch_producer_performances(
+                                epoch,
+                                Some(&genesis_state_hash),
+                                None,
+                            )?
+                            .map(|mut performances| {
+                                ProducerPerformance::sort(&mut performances, sort);
+                                performances.truncate(limit as usize);
+                                performances
+                            })),
+                    };
+
+                    match performances {
+                        Err(msg) => msg,
+                        Ok(None) => {
+                            error!("Staking ledger at epoch {epoch} is not in the store");
+                            Some(format!(
+                                "Staking ledger at epoch {epoch} is not in the store"
+                            ))
+                        }
+                        Ok(Some(performances)) => {
+                            let performances_str = serde_json::to_string_pretty(&performances)?;
+                            if path.is_none() {
+                                debug!("Writing epoch {epoch} producer performance to stdout");
+                                Some(performances_str)
+                            } else {
+                                let path = path.unwrap();
+                                if !path.is_dir() {
+                                    debug!(
+                                        "Writing epoch {epoch} producer performance to {path:?}"
+                                    );
+                                    std::fs::write(&path, performances_str)?;
+                                    Some(format!(
+                                        "Epoch {epoch} producer performance written to {path:?}"
+                                    ))
+                                } else {
+                                    file_must_not_be_a_directory(&path)
+                                }
+                            }
+                        }
+                    }
+                }
                 StakingLedgers::Delegations {
                     epoch,
                     genesis_state_hash,
@@ -0,0 +1,57 @@
+//! Deadlines of long running store scans
+
+use anyhow::bail;
+use std::time::{Duration, Instant};
+
+/// Request deadline, e.g. added to each GraphQL request's data
+///
+/// Store scans run synchronously, so they must check the deadline per row
+/// for a slow request to stop
+#[derive(Debug, Clone, Copy)]
+pub struct Deadline {
+    timeout: Duration,
+    deadline: Instant,
+}
+
+impl Deadline {
+    pub fn new(timeout: Duration) -> Self {
+        Self {
+            timeout,
+            deadline: Instant::now() + timeout,
+        }
+    }
+
+    /// Errors if the deadline has passed
+    pub fn check(&self) -> anyhow::Result<()> {
+        if Instant::now() >= self.deadline {
+            bail!(timed_out(self.timeout))
+        }
+        Ok(())
+    }
+}
+
+/// Errors if the scan has a deadline & it has passed
+pub fn check_scan(deadline: Option<&Deadline>) -> anyhow::Result<()> {
+    deadline.map_or(Ok(()), Deadline::check)
+}
+
+pub fn timed_out(timeout: Duration) -> String {
+    format!("Query timed out after {}s", timeout.as_secs_f64())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn deadline_passes() {
+        assert!(check_scan(None).is_ok());
+        assert!(check_scan(Some(&Deadline::new(Duration::from_secs(60)))).is_ok());
+
+        let deadline = Deadline::new(Duration::ZERO);
+        assert_eq!(
+            check_scan(Some(&deadline)).unwrap_err().to_string(),
+            "Query timed out after 0s"
+        );
+    }
+}
@@ -179,11 +179,16 @@ mod utility_function_tests {
     fn test_is_valid_file_name() {
         // Valid cases
         assert!(is_valid_block_file(
-            "mainnet-42-3Nabcdef12345678901234567890123456789012345678901234.json"
+            "mainnet-42-3NKxEA9gztvEGxL4uk4eTncZAxuRmMsB8n81UkeAMevUjMbLHmkC.json"
         ));
 
         assert!(!is_valid_block_file(
-            "mainnet-3Nabcdef12345678901234567890123456789012345678901234.json"
+            "mainnet-3NKxEA9gztvEGxL4uk4eTncZAxuRmMsB8n81UkeAMevUjMbLHmkC.json"
+        ));
+
+        // Invalid checksum
+        assert!(!is_valid_block_file(
+            "mainnet-42-3Nabcdef12345678901234567890123456789012345678901234.json"
         ));
 
         ///////////////////
@@ -203,12 +208,12 @@ mod utility_function_tests {
 
         // Invalid extension
         assert!(!is_valid_block_file(
-            "mainnet-42-3Nabcdef12345678901234567890123456789012345678901234.txt"
+            "mainnet-42-3NKxEA9gztvEGxL4uk4eTncZAxuRmMsB8n81UkeAMevUjMbLHmkC.txt"
         ));
 
         // Too many parts
         assert!(!is_valid_block_file(
-            "mainnet-42-3Nabcdef12345678901234567890123456789012345678901234-123.json"
+            "mainnet-42-3NKxEA9gztvEGxL4uk4eTncZAxuRmMsB8n81UkeAMevUjMbLHmkC-123.json"
         ));
     }
 }
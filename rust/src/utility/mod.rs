@@ -1,4 +1,5 @@
 pub mod compression;
+pub mod deadline;
 pub mod functions;
 pub mod serde;
 pub mod store;
@@ -21,6 +21,26 @@ where
         .map_err(serde::de::Error::custom)
 }
 
+/// Deserialize from either a number or `str`
+pub fn from_num_or_str<'de, T, D>(de: D) -> Result<T, D::Error>
+where
+    D: Deserializer<'de>,
+    T: FromStr + Deserialize<'de>,
+    <T as FromStr>::Err: std::fmt::Display,
+{
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum NumOrStr<T> {
+        Num(T),
+        Str(String),
+    }
+
+    match NumOrStr::<T>::deserialize(de)? {
+        NumOrStr::Num(num) => Ok(num),
+        NumOrStr::Str(s) => s.parse().map_err(serde::de::Error::custom),
+    }
+}
+
 /// Serialize to `str`
 pub(crate) fn to_str<T, S>(value: T, ser: S) -> Result<S::Ok, S::Error>
 where
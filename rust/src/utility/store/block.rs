@@ -1,8 +1,13 @@
 use crate::{
     base::{public_key::PublicKey, state_hash::StateHash},
     block::precomputed::PrecomputedBlock,
-    utility::store::common::U32_LEN,
+    utility::store::common::{u64_from_be_bytes, U32_LEN, U64_LEN},
 };
+use anyhow::bail;
+
+/// Block value encoding tags
+const MSGPACK_V1: u8 = 1;
+const MSGPACK_V2: u8 = 2;
 
 /// `{block height BE}{state hash}`
 pub fn block_height_key(block: &PrecomputedBlock) -> [u8; U32_LEN + StateHash::LEN] {
@@ -20,6 +25,17 @@ pub fn block_global_slot_key(block: &PrecomputedBlock) -> [u8; U32_LEN + StateHa
     key
 }
 
+/// `{global slot BE}{state hash}`
+pub fn global_slot_state_hash_key(
+    global_slot: u32,
+    state_hash: &StateHash,
+) -> [u8; U32_LEN + StateHash::LEN] {
+    let mut key = [0; U32_LEN + StateHash::LEN];
+    key[..U32_LEN].copy_from_slice(&global_slot.to_be_bytes());
+    key[U32_LEN..].copy_from_slice(state_hash.0.as_bytes());
+    key
+}
+
 /// Key format
 /// ```
 /// {pk}{sort_value}{state_hash}
@@ -103,6 +119,74 @@ pub fn epoch_block_num_key(
     key
 }
 
+/// Value format
+/// ```
+/// {num_bytes}{encoding}{block}
+/// where
+/// - num_bytes: block file size u64 BE bytes
+/// - encoding:  u8 tag, PCB version
+/// - block:     MessagePack [PrecomputedBlock] bytes
+///
+/// Blocks which fail to encode, or whose encoding doesn't decode back to the
+/// same block, are stored in the legacy `{num_bytes}{serde_json block}`
+/// format
+pub fn block_value(block: &PrecomputedBlock, num_block_bytes: u64) -> anyhow::Result<Vec<u8>> {
+    match encode_block(block) {
+        Ok((tag, bytes)) if decode_block(tag, &bytes).is_ok_and(|decoded| decoded == *block) => {
+            Ok(compact_block_value(tag, bytes, num_block_bytes))
+        }
+        _ => json_block_value(block, num_block_bytes),
+    }
+}
+
+/// Decodes the block & its file size from either value format
+pub fn block_from_value(value: &[u8]) -> anyhow::Result<(PrecomputedBlock, u64)> {
+    if value.len() <= U64_LEN {
+        bail!("Invalid block value length {}", value.len())
+    }
+
+    let num_block_bytes = u64_from_be_bytes(&value[..U64_LEN])?;
+    let block = match value[U64_LEN] {
+        b'{' => serde_json::from_slice(&value[U64_LEN..])?,
+        tag => decode_block(tag, &value[U64_LEN + 1..])?,
+    };
+
+    Ok((block, num_block_bytes))
+}
+
+/// Whether the value is in the legacy serde_json format
+pub fn is_json_block_value(value: &[u8]) -> bool {
+    value.get(U64_LEN) == Some(&b'{')
+}
+
+fn encode_block(block: &PrecomputedBlock) -> anyhow::Result<(u8, Vec<u8>)> {
+    Ok(match block {
+        PrecomputedBlock::V1(v1) => (MSGPACK_V1, rmp_serde::to_vec(v1)?),
+        PrecomputedBlock::V2(v2) => (MSGPACK_V2, rmp_serde::to_vec(v2)?),
+    })
+}
+
+fn compact_block_value(tag: u8, mut bytes: Vec<u8>, num_block_bytes: u64) -> Vec<u8> {
+    let mut value = num_block_bytes.to_be_bytes().to_vec();
+    value.push(tag);
+    value.append(&mut bytes);
+    value
+}
+
+fn json_block_value(block: &PrecomputedBlock, num_block_bytes: u64) -> anyhow::Result<Vec<u8>> {
+    let mut value = num_block_bytes.to_be_bytes().to_vec();
+    value.append(&mut serde_json::to_vec(block)?);
+    Ok(value)
+}
+
+fn decode_block(tag: u8, bytes: &[u8]) -> anyhow::Result<PrecomputedBlock> {
+    Ok(match tag {
+        MSGPACK_V1 => PrecomputedBlock::V1(rmp_serde::from_slice(bytes)?),
+        MSGPACK_V2 => PrecomputedBlock::V2(rmp_serde::from_slice(bytes)?),
+        tag => bail!("Unknown block encoding {tag}"),
+    })
+}
+
 #[cfg(test)]
 mod block_store_impl_tests {
     use super::*;
@@ -149,4 +233,29 @@ mod block_store_impl_tests {
         assert_eq!(&key[PublicKey::LEN..][..U32_LEN], &sort_value.to_be_bytes());
         assert_eq!(&key[PublicKey::LEN..][U32_LEN..], state_hash.0.as_bytes());
     }
+
+    #[test]
+    fn block_value_roundtrip() -> anyhow::Result<()> {
+        for (path, version) in [
+            ("./tests/data/sequential_blocks/mainnet-105489-3NLFXtdzaFW2WX6KgrxMjL4enE4pCa9hAsVUPm47PT6337SXgBGh.json", PcbVersion::V1),
+            ("./tests/data/hardfork/mainnet-359606-3NKvvtFwjEtQLswWJzXBSxxiKuYVbLJrKXCnmhp6jctYMqAWcftg.json", PcbVersion::V2),
+        ] {
+            let path = PathBuf::from(path);
+            let block = PrecomputedBlock::parse_file(&path, version)?;
+            let num_block_bytes = path.metadata()?.len();
+
+            // compact encoding
+            let value = block_value(&block, num_block_bytes)?;
+            assert!(!is_json_block_value(&value));
+            assert_eq!(block_from_value(&value)?, (block.clone(), num_block_bytes));
+
+            // legacy encoding
+            let mut legacy = num_block_bytes.to_be_bytes().to_vec();
+            legacy.append(&mut serde_json::to_vec(&block)?);
+            assert!(is_json_block_value(&legacy));
+            assert!(legacy.len() > value.len());
+            assert_eq!(block_from_value(&legacy)?, (block, num_block_bytes));
+        }
+        Ok(())
+    }
 }
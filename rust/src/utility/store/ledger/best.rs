@@ -1,7 +1,7 @@
 use crate::{
-    base::public_key::PublicKey,
+    base::{nonce::Nonce, public_key::PublicKey},
     ledger::token::TokenAddress,
-    utility::store::common::{balance_key_prefix, pk_key_prefix, U64_LEN},
+    utility::store::common::{balance_key_prefix, pk_key_prefix, U32_LEN, U64_LEN},
 };
 
 /// Key format for storing best ledger accounts
@@ -56,6 +56,26 @@ pub fn split_best_account_sort_key(key: &[u8]) -> Option<(TokenAddress, u64, Pub
     None
 }
 
+/// Key format for storing best chain delegation changes
+/// ```
+/// {pk}{height}{nonce}
+/// where
+/// - pk:     [PublicKey::LEN] bytes
+/// - height: [u32] BE bytes
+/// - nonce:  [u32] BE bytes
+pub fn delegation_history_key(
+    pk: &PublicKey,
+    height: u32,
+    nonce: Nonce,
+) -> [u8; PublicKey::LEN + U32_LEN + U32_LEN] {
+    let mut key = [0; PublicKey::LEN + U32_LEN + U32_LEN];
+
+    key[..PublicKey::LEN].copy_from_slice(pk.0.as_bytes());
+    key[PublicKey::LEN..][..U32_LEN].copy_from_slice(&height.to_be_bytes());
+    key[PublicKey::LEN..][U32_LEN..].copy_from_slice(&nonce.0.to_be_bytes());
+    key
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -1,9 +1,26 @@
 use crate::{
     base::{public_key::PublicKey, state_hash::StateHash},
     ledger::token::TokenAddress,
-    utility::store::common::{balance_key_prefix, pk_key_prefix, U64_LEN},
+    utility::store::common::{balance_key_prefix, pk_key_prefix, U32_LEN, U64_LEN},
 };
 
+/// Key format for sorting persisted staged ledgers by block height
+/// ```
+/// {height}{state_hash}
+/// where
+/// - height:     [u32] BE bytes
+/// - state_hash: [StateHash::LEN] bytes
+pub fn staged_ledger_height_key(
+    block_height: u32,
+    state_hash: &StateHash,
+) -> [u8; U32_LEN + StateHash::LEN] {
+    let mut key = [0; U32_LEN + StateHash::LEN];
+
+    key[..U32_LEN].copy_from_slice(&block_height.to_be_bytes());
+    key[U32_LEN..].copy_from_slice(state_hash.0.as_bytes());
+    key
+}
+
 /// Key format for storing staged ledger accounts by state hash
 /// ```
 /// {state_hash}{pk}
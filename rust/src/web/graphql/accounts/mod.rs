@@ -1,17 +1,21 @@
-use super::db;
+use super::{db, validate_optional_base58};
 use crate::{
-    base::public_key::PublicKey,
-    block::store::BlockStore,
-    command::{internal::store::InternalCommandStore, store::UserCommandStore},
+    base::{base58::Base58Kind, public_key::PublicKey},
     ledger::{account, store::best::BestLedgerStore, token::TokenAddress},
-    snark_work::store::SnarkStore,
-    store::username::UsernameStore,
-    web::graphql::Timing,
+    store::{multi_get::PkCounts, username::UsernameStore, IndexerStore},
+    utility::deadline::check_scan,
+    web::graphql::{
+        limits::{check_deadline, deadline},
+        loaders::{loader, PkCountsKey, UsernameKey},
+        pagination::{paginate, SortKeyConnection, SortKeyCursor},
+        Timing,
+    },
 };
-use async_graphql::{Context, Enum, InputObject, Object, Result, SimpleObject};
-use speedb::IteratorMode;
+use async_graphql::{ComplexObject, Context, Enum, InputObject, Object, Result, SimpleObject};
+use speedb::{Direction, IteratorMode};
+use std::sync::Arc;
 
-#[derive(InputObject)]
+#[derive(InputObject, Default)]
 pub struct AccountQueryInput {
     public_key: Option<String>,
     delegate: Option<String>,
@@ -46,9 +50,12 @@ pub enum AccountSortByInput {
 pub struct AccountQueryRoot;
 
 #[derive(SimpleObject)]
+#[graphql(complex)]
 pub struct Account {
+    #[graphql(skip)]
+    pk: PublicKey,
+
     public_key: String,
-    username: Option<String>,
     delegate: String,
     balance: u64,
     nonce: u32,
@@ -57,34 +64,59 @@ pub struct Account {
 
     #[graphql(name = "is_genesis_account")]
     is_genesis_account: bool,
+}
+
+#[ComplexObject]
+impl Account {
+    async fn username(&self, ctx: &Context<'_>) -> Result<Option<String>> {
+        let username = loader(ctx).load_one(UsernameKey(self.pk.clone())).await?;
+        Ok(username.or(Some("Unknown".to_string())))
+    }
 
     #[graphql(name = "pk_epoch_num_blocks")]
-    pk_epoch_num_blocks: u32,
+    async fn pk_epoch_num_blocks(&self, ctx: &Context<'_>) -> Result<u32> {
+        Ok(self.pk_counts(ctx).await?.epoch_num_blocks)
+    }
 
     #[graphql(name = "pk_total_num_blocks")]
-    pk_total_num_blocks: u32,
+    async fn pk_total_num_blocks(&self, ctx: &Context<'_>) -> Result<u32> {
+        Ok(self.pk_counts(ctx).await?.total_num_blocks)
+    }
 
     #[graphql(name = "pk_epoch_num_snarks")]
-    pk_epoch_num_snarks: u32,
+    async fn pk_epoch_num_snarks(&self, ctx: &Context<'_>) -> Result<u32> {
+        Ok(self.pk_counts(ctx).await?.epoch_num_snarks)
+    }
 
     #[graphql(name = "pk_total_num_snarks")]
-    pk_total_num_snarks: u32,
+    async fn pk_total_num_snarks(&self, ctx: &Context<'_>) -> Result<u32> {
+        Ok(self.pk_counts(ctx).await?.total_num_snarks)
+    }
 
     #[graphql(name = "pk_epoch_num_user_commands")]
-    pk_epoch_num_user_commands: u32,
+    async fn pk_epoch_num_user_commands(&self, ctx: &Context<'_>) -> Result<u32> {
+        Ok(self.pk_counts(ctx).await?.epoch_num_user_commands)
+    }
 
     #[graphql(name = "pk_total_num_user_commands")]
-    pk_total_num_user_commands: u32,
+    async fn pk_total_num_user_commands(&self, ctx: &Context<'_>) -> Result<u32> {
+        Ok(self.pk_counts(ctx).await?.total_num_user_commands)
+    }
 
     #[graphql(name = "pk_epoch_num_internal_commands")]
-    pk_epoch_num_internal_commands: u32,
+    async fn pk_epoch_num_internal_commands(&self, ctx: &Context<'_>) -> Result<u32> {
+        Ok(self.pk_counts(ctx).await?.epoch_num_internal_commands)
+    }
 
     #[graphql(name = "pk_total_num_internal_commands")]
-    pk_total_num_internal_commands: u32,
+    async fn pk_total_num_internal_commands(&self, ctx: &Context<'_>) -> Result<u32> {
+        Ok(self.pk_counts(ctx).await?.total_num_internal_commands)
+    }
 }
 
 #[Object]
 impl AccountQueryRoot {
+    #[graphql(complexity = "limit * child_complexity")]
     async fn accounts<'ctx>(
         &self,
         ctx: &Context<'ctx>,
@@ -95,6 +127,9 @@ impl AccountQueryRoot {
         use AccountSortByInput::*;
 
         let db = db(ctx);
+        if let Some(query) = query.as_ref() {
+            query.validate()?;
+        }
         let token = query
             .as_ref()
             .map_or(TokenAddress::default(), |q| match q.token.to_owned() {
@@ -109,31 +144,9 @@ impl AccountQueryRoot {
                 .get_best_account_display(&pk, &token)?
                 .iter()
                 .filter_map(|acct| {
-                    let username = match db.get_username(&pk) {
-                        Ok(None) | Err(_) => None,
-                        Ok(Some(username)) => Some(username.0),
-                    };
+                    let username = query_username(db, query.as_ref(), &pk);
                     if query.as_ref().unwrap().matches(acct, username.as_ref()) {
-                        Some(Account::from((
-                            acct.clone(),
-                            db.get_block_production_pk_epoch_count(&pk, None)
-                                .expect("pk epoch block count"),
-                            db.get_block_production_pk_total_count(&pk)
-                                .expect("pk total block count"),
-                            db.get_snarks_pk_epoch_count(&pk, None)
-                                .expect("pk epoch snark count"),
-                            db.get_snarks_pk_total_count(&pk)
-                                .expect("pk total snark count"),
-                            db.get_user_commands_pk_epoch_count(&pk, None)
-                                .expect("pk epoch user command count"),
-                            db.get_user_commands_pk_total_count(&pk)
-                                .expect("pk total user command count"),
-                            db.get_internal_commands_pk_epoch_count(&pk, None)
-                                .expect("pk epoch internal command count"),
-                            db.get_internal_commands_pk_total_count(&pk)
-                                .expect("pk total internal command count"),
-                            username,
-                        )))
+                        Some(Account::from(acct.clone()))
                     } else {
                         None
                     }
@@ -142,56 +155,31 @@ impl AccountQueryRoot {
         }
 
         // default query handler use balance-sorted accounts
+        let protocol = db.protocol_params();
         let mut accounts = Vec::with_capacity(limit);
         let mode = match sort_by {
             Some(BalanceAsc) => IteratorMode::Start,
             Some(BalanceDesc) | None => IteratorMode::End,
         };
         let iter = match query.as_ref().and_then(|q| q.zkapp) {
-            // all account types
-            None => db.best_ledger_account_balance_iterator(mode).flatten(),
+            // all account types, non-zkapp accounts are filtered by the query
+            None | Some(false) => db.best_ledger_account_balance_iterator(mode).flatten(),
             // zkapp accounts only
             Some(true) => db
                 .zkapp_best_ledger_account_balance_iterator(mode)
                 .flatten(),
-            // non-zkapp account only
-            Some(false) => todo!("non-zkapp account"),
         };
 
         for (_, value) in iter {
-            let account = serde_json::from_slice::<account::Account>(&value)?.display();
-            let pk = account.public_key.clone();
-            let username = match db.get_username(&pk) {
-                Ok(None) | Err(_) => None,
-                Ok(Some(username)) => Some(username.0),
-            };
+            check_deadline(ctx)?;
+            let account = serde_json::from_slice::<account::Account>(&value)?.display(&protocol);
+            let username = query_username(db, query.as_ref(), &account.public_key);
 
             if query
                 .as_ref()
                 .map_or(true, |q| q.matches(&account, username.as_ref()))
             {
-                let account = Account::from((
-                    account,
-                    db.get_block_production_pk_epoch_count(&pk, None)
-                        .expect("pk epoch block count"),
-                    db.get_block_production_pk_total_count(&pk)
-                        .expect("pk total block count"),
-                    db.get_snarks_pk_epoch_count(&pk, None)
-                        .expect("pk epoch snark count"),
-                    db.get_snarks_pk_total_count(&pk)
-                        .expect("pk total snark count"),
-                    db.get_user_commands_pk_epoch_count(&pk, None)
-                        .expect("pk epoch user command count"),
-                    db.get_user_commands_pk_total_count(&pk)
-                        .expect("pk total user command count"),
-                    db.get_internal_commands_pk_epoch_count(&pk, None)
-                        .expect("pk epoch internal command count"),
-                    db.get_internal_commands_pk_total_count(&pk)
-                        .expect("pk total internal command count"),
-                    username,
-                ));
-
-                accounts.push(account);
+                accounts.push(Account::from(account));
                 if accounts.len() >= limit {
                     break;
                 }
@@ -200,9 +188,100 @@ impl AccountQueryRoot {
 
         Ok(accounts)
     }
+
+    /// Cursor paginated balance-sorted accounts
+    ///
+    /// `totalCount` is only provided for unfiltered queries
+    #[graphql(complexity = "first * child_complexity")]
+    async fn accounts_connection<'ctx>(
+        &self,
+        ctx: &Context<'ctx>,
+        query: Option<AccountQueryInput>,
+        sort_by: Option<AccountSortByInput>,
+        #[graphql(default = 100)] first: usize,
+        after: Option<String>,
+    ) -> Result<SortKeyConnection<Account>> {
+        use AccountSortByInput::*;
+
+        let db = db(ctx);
+        if let Some(query) = query.as_ref() {
+            query.validate()?;
+        }
+        let after = SortKeyCursor::decode(after.as_deref())?;
+        let (mode, direction) = match sort_by {
+            Some(BalanceAsc) => (IteratorMode::Start, Direction::Forward),
+            Some(BalanceDesc) | None => (IteratorMode::End, Direction::Reverse),
+        };
+        let mode = match after.as_ref() {
+            Some(after) => IteratorMode::From(&after.0, direction),
+            None => mode,
+        };
+        let iter = match query.as_ref().and_then(|q| q.zkapp) {
+            // all account types, non-zkapp accounts are filtered by the query
+            None | Some(false) => db.best_ledger_account_balance_iterator(mode),
+            // zkapp accounts only
+            Some(true) => db.zkapp_best_ledger_account_balance_iterator(mode),
+        };
+
+        let protocol = db.protocol_params();
+
+        let deadline = deadline(ctx);
+        let nodes = iter
+            .flatten()
+            .filter(|(key, _)| SortKeyCursor::precedes(after.as_ref(), key, direction))
+            .map(|(key, value)| {
+                check_scan(deadline)?;
+
+                let account =
+                    serde_json::from_slice::<account::Account>(&value)?.display(&protocol);
+                let username = query_username(db, query.as_ref(), &account.public_key);
+
+                Ok(query
+                    .as_ref()
+                    .map_or(true, |q| q.matches(&account, username.as_ref()))
+                    .then(|| (key.to_vec(), Account::from(account))))
+            })
+            .filter_map(anyhow::Result::transpose);
+
+        let total_count = match query {
+            None => db.get_num_accounts()?,
+            Some(_) => None,
+        };
+        paginate(deadline, nodes, after.as_ref(), first, total_count)
+    }
+}
+
+impl Account {
+    async fn pk_counts(&self, ctx: &Context<'_>) -> Result<PkCounts> {
+        Ok(loader(ctx)
+            .load_one(PkCountsKey(self.pk.clone()))
+            .await?
+            .unwrap_or_default())
+    }
+}
+
+/// Only look up the username if the query filters on it, otherwise it's
+/// batch loaded with the rest of the page
+fn query_username(
+    db: &Arc<IndexerStore>,
+    query: Option<&AccountQueryInput>,
+    pk: &PublicKey,
+) -> Option<String> {
+    query.and_then(|q| q.username.as_ref())?;
+    match db.get_username(pk) {
+        Ok(None) | Err(_) => None,
+        Ok(Some(username)) => Some(username.0),
+    }
 }
 
 impl AccountQueryInput {
+    /// Checks the base58 inputs are of the expected kinds
+    fn validate(&self) -> Result<()> {
+        validate_optional_base58(Base58Kind::PublicKey, self.public_key.as_ref())?;
+        validate_optional_base58(Base58Kind::PublicKey, self.delegate.as_ref())?;
+        validate_optional_base58(Base58Kind::TokenId, self.token.as_ref())
+    }
+
     fn matches(&self, account: &account::Account, username: Option<&String>) -> bool {
         let AccountQueryInput {
             public_key,
@@ -291,51 +370,17 @@ impl AccountQueryInput {
     }
 }
 
-impl
-    From<(
-        account::Account,
-        u32,
-        u32,
-        u32,
-        u32,
-        u32,
-        u32,
-        u32,
-        u32,
-        Option<String>,
-    )> for Account
-{
-    fn from(
-        account: (
-            account::Account,
-            u32,
-            u32,
-            u32,
-            u32,
-            u32,
-            u32,
-            u32,
-            u32,
-            Option<String>,
-        ),
-    ) -> Self {
+impl From<account::Account> for Account {
+    fn from(account: account::Account) -> Self {
         Self {
-            public_key: account.0.public_key.0,
-            delegate: account.0.delegate.0,
-            nonce: account.0.nonce.map_or(0, |n| n.0),
-            balance: account.0.balance.0,
-            time_locked: account.0.timing.is_some(),
-            timing: account.0.timing.map(|t| t.into()),
-            is_genesis_account: account.0.genesis_account,
-            pk_epoch_num_blocks: account.1,
-            pk_total_num_blocks: account.2,
-            pk_epoch_num_snarks: account.3,
-            pk_total_num_snarks: account.4,
-            pk_epoch_num_user_commands: account.5,
-            pk_total_num_user_commands: account.6,
-            pk_epoch_num_internal_commands: account.7,
-            pk_total_num_internal_commands: account.8,
-            username: account.9.or(Some("Unknown".to_string())),
+            pk: account.public_key.clone(),
+            public_key: account.public_key.0,
+            delegate: account.delegate.0,
+            nonce: account.nonce.map_or(0, |n| n.0),
+            balance: account.balance.0,
+            time_locked: account.timing.is_some(),
+            timing: account.timing.map(|t| t.into()),
+            is_genesis_account: account.genesis_account,
         }
     }
 }
@@ -351,3 +396,32 @@ impl From<account::Timing> for Timing {
         }
     }
 }
+
+#[cfg(test)]
+mod web_graphql_accounts_tests {
+    use super::*;
+    use crate::mina_blocks::v2::ZkappAccount;
+
+    #[test]
+    fn matches_zkapp_filter() {
+        let account = account::Account::default();
+        let zkapp_account = account::Account {
+            zkapp: Some(ZkappAccount::default()),
+            ..Default::default()
+        };
+
+        let zkapp_only = AccountQueryInput {
+            zkapp: Some(true),
+            ..Default::default()
+        };
+        assert!(!zkapp_only.matches(&account, None));
+        assert!(zkapp_only.matches(&zkapp_account, None));
+
+        let non_zkapp_only = AccountQueryInput {
+            zkapp: Some(false),
+            ..Default::default()
+        };
+        assert!(non_zkapp_only.matches(&account, None));
+        assert!(!non_zkapp_only.matches(&zkapp_account, None));
+    }
+}
@@ -0,0 +1,186 @@
+use super::{db, validate_base58, DateTime};
+use crate::{
+    base::{base58::Base58Kind, public_key::PublicKey},
+    block::store::BlockStore,
+    constants::millis_to_iso_date_string,
+    ledger::{
+        store::balance_history::{BalanceChange, BalanceChangeCause, BalanceHistoryStore},
+        token::TokenAddress,
+    },
+    web::graphql::limits::deadline,
+};
+use async_graphql::{Context, Enum, Object, Result, SimpleObject};
+use speedb::Direction;
+
+#[derive(Default)]
+pub struct BalanceHistoryQueryRoot;
+
+#[derive(Enum, Copy, Clone, Eq, PartialEq)]
+pub enum BalanceHistorySortByInput {
+    BlockHeightAsc,
+    BlockHeightDesc,
+}
+
+#[derive(SimpleObject)]
+pub struct AccountBalanceChange {
+    #[graphql(name = "public_key")]
+    public_key: String,
+
+    token: String,
+
+    #[graphql(name = "state_hash")]
+    state_hash: String,
+
+    #[graphql(name = "block_height")]
+    block_height: u32,
+
+    #[graphql(name = "global_slot_since_genesis")]
+    global_slot_since_genesis: u32,
+
+    /// Value date_time as ISO 8601 string
+    #[graphql(name = "date_time")]
+    date_time: String,
+
+    #[graphql(name = "balance_before")]
+    balance_before: u64,
+
+    #[graphql(name = "balance_after")]
+    balance_after: u64,
+
+    /// Signed nanomina balance change
+    delta: i64,
+
+    causes: Vec<AccountBalanceChangeCause>,
+}
+
+#[derive(SimpleObject)]
+pub struct AccountBalanceChangeCause {
+    kind: String,
+
+    /// Signed nanomina amount
+    amount: i64,
+
+    #[graphql(name = "txn_hash")]
+    txn_hash: Option<String>,
+}
+
+#[Object]
+impl BalanceHistoryQueryRoot {
+    /// Best chain balance changes of the token account between the given
+    /// block heights (inclusive), ascending by default
+    #[graphql(
+        name = "accountBalanceHistory",
+        complexity = "limit * child_complexity"
+    )]
+    #[allow(clippy::too_many_arguments)]
+    async fn account_balance_history<'ctx>(
+        &self,
+        ctx: &Context<'ctx>,
+        public_key: String,
+        token: Option<String>,
+        from: Option<u32>,
+        to: Option<u32>,
+        sort_by: Option<BalanceHistorySortByInput>,
+        #[graphql(default = 100)] limit: usize,
+    ) -> Result<Vec<AccountBalanceChange>> {
+        let db = db(ctx);
+        let (pk, token) = pk_token(public_key, token)?;
+
+        let end_height = match to {
+            Some(to) => to,
+            None => db.get_best_block_height()?.unwrap_or_default(),
+        };
+        let direction = match sort_by {
+            Some(BalanceHistorySortByInput::BlockHeightAsc) | None => Direction::Forward,
+            Some(BalanceHistorySortByInput::BlockHeightDesc) => Direction::Reverse,
+        };
+        Ok(db
+            .get_balance_history(
+                &pk,
+                &token,
+                from.unwrap_or_default(),
+                end_height,
+                limit,
+                direction,
+                deadline(ctx),
+            )?
+            .into_iter()
+            .map(Into::into)
+            .collect())
+    }
+
+    /// Best chain balance of the token account at the given block height or
+    /// date time (defaults to the best tip)
+    #[graphql(name = "balanceAt")]
+    async fn balance_at<'ctx>(
+        &self,
+        ctx: &Context<'ctx>,
+        public_key: String,
+        token: Option<String>,
+        height: Option<u32>,
+        date_time: Option<DateTime>,
+    ) -> Result<Option<u64>> {
+        let db = db(ctx);
+        let (pk, token) = pk_token(public_key, token)?;
+
+        match (height, date_time) {
+            (Some(_), Some(_)) => Err(async_graphql::Error::new(
+                "Specify at most one of height & dateTime",
+            )),
+            (_, Some(date_time)) => {
+                let global_slot = db
+                    .protocol_params()
+                    .millis_to_global_slot(date_time.timestamp_millis());
+                Ok(db.get_balance_at_slot(&pk, &token, global_slot, deadline(ctx))?)
+            }
+            (height, None) => {
+                let height = match height {
+                    Some(height) => height,
+                    None => db.get_best_block_height()?.unwrap_or_default(),
+                };
+                Ok(db.get_balance_at_height(&pk, &token, height)?)
+            }
+        }
+    }
+}
+
+fn pk_token(public_key: String, token: Option<String>) -> Result<(PublicKey, TokenAddress)> {
+    validate_base58(Base58Kind::PublicKey, &public_key)?;
+
+    let token = match token {
+        Some(token) => {
+            validate_base58(Base58Kind::TokenId, &token)?;
+            token.parse()?
+        }
+        None => TokenAddress::default(),
+    };
+
+    Ok((public_key.into(), token))
+}
+
+impl From<BalanceChange> for AccountBalanceChange {
+    fn from(change: BalanceChange) -> Self {
+        Self {
+            delta: change.delta(),
+            public_key: change.public_key.0,
+            token: change.token.0,
+            state_hash: change.state_hash.0,
+            block_height: change.blockchain_length,
+            global_slot_since_genesis: change.global_slot_since_genesis,
+            date_time: millis_to_iso_date_string(change.date_time),
+            balance_before: change.balance_before,
+            balance_after: change.balance_after,
+            causes: change.causes.into_iter().map(Into::into).collect(),
+        }
+    }
+}
+
+impl From<BalanceChangeCause> for AccountBalanceChangeCause {
+    fn from(cause: BalanceChangeCause) -> Self {
+        Self {
+            kind: cause.kind.to_string(),
+            amount: cause.amount,
+            txn_hash: cause.txn_hash.map(|hash| hash.ref_inner().to_owned()),
+        }
+    }
+}
@@ -1,10 +1,11 @@
 use super::{
     db, get_block_canonicity, millis_to_iso_date_string, transactions::TransactionWithoutBlock,
-    MAINNET_COINBASE_REWARD, MAINNET_EPOCH_SLOT_COUNT, PK,
+    validate_optional_base58, PK,
 };
 use crate::{
-    base::{public_key::PublicKey, state_hash::StateHash},
+    base::{base58::Base58Kind, public_key::PublicKey},
     block::{precomputed::PrecomputedBlock, store::BlockStore},
+    chain::profile::ProtocolParams,
     command::{
         internal::{store::InternalCommandStore, DbInternalCommand, DbInternalCommandWithData},
         signed::SignedCommandWithData,
@@ -12,12 +13,15 @@ use crate::{
     },
     snark_work::{store::SnarkStore, SnarkWorkSummary},
     store::IndexerStore,
-    utility::store::common::{
-        block_u32_prefix_from_key, from_be_bytes, state_hash_suffix, U32_LEN,
+    utility::{
+        deadline::check_scan,
+        store::common::{block_u32_prefix_from_key, from_be_bytes, state_hash_suffix, U32_LEN},
     },
     web::graphql::{
         gen::{BlockProtocolStateConsensusStateQueryInput, BlockQueryInput},
         get_block,
+        limits::{check_deadline, deadline},
+        pagination::{paginate, SortKeyConnection, SortKeyCursor},
     },
 };
 use async_graphql::{self, Enum, Object, Result, SimpleObject};
@@ -37,6 +41,9 @@ impl BlocksQueryRoot {
         query: Option<BlockQueryInput>,
     ) -> Result<Option<Block>> {
         let db = db(ctx);
+        if let Some(query) = query.as_ref() {
+            query.validate()?;
+        }
 
         // no query filters => get the best block
         if query.is_none() {
@@ -48,10 +55,6 @@ impl BlocksQueryRoot {
 
         // Use constant time access if we have the state hash
         if let Some(state_hash) = query.as_ref().and_then(|input| input.state_hash.clone()) {
-            if !StateHash::is_valid(&state_hash) {
-                return Ok(None);
-            }
-
             let pcb = match db.get_block(&state_hash.into())? {
                 Some((pcb, _)) => pcb,
                 None => return Ok(None),
@@ -68,6 +71,7 @@ impl BlocksQueryRoot {
             .blocks_height_iterator(speedb::IteratorMode::End)
             .flatten()
         {
+            check_deadline(ctx)?;
             let state_hash = state_hash_suffix(&key)?;
             let pcb = get_block(db, &state_hash);
 
@@ -81,6 +85,7 @@ impl BlocksQueryRoot {
     }
 
     #[allow(clippy::too_many_lines)]
+    #[graphql(complexity = "limit * child_complexity")]
     async fn blocks<'ctx>(
         &self,
         ctx: &async_graphql::Context<'ctx>,
@@ -91,6 +96,9 @@ impl BlocksQueryRoot {
         use speedb::{Direction::*, IteratorMode::*};
         use BlockSortByInput::*;
         let db = db(ctx);
+        if let Some(query) = query.as_ref() {
+            query.validate()?;
+        }
 
         // unique block producer query
         if let Some(mut num_blocks) = query
@@ -110,6 +118,7 @@ impl BlocksQueryRoot {
                     ))
                     .flatten()
                 {
+                    check_deadline(ctx)?;
                     let height = from_be_bytes(key[..U32_LEN].to_vec());
                     if height <= start_height {
                         break;
@@ -194,6 +203,7 @@ impl BlocksQueryRoot {
                 GlobalSlotDesc => db.coinbase_receiver_global_slot_iterator(From(&end, Reverse)),
             };
             for (key, _) in iter.flatten() {
+                check_deadline(ctx)?;
                 if key[..PublicKey::LEN] != *coinbase_receiver.as_bytes() {
                     break;
                 }
@@ -245,6 +255,7 @@ impl BlocksQueryRoot {
                 GlobalSlotDesc => db.block_creator_global_slot_iterator(From(&end, Reverse)),
             };
             for (key, _) in iter.flatten() {
+                check_deadline(ctx)?;
                 if key[..PublicKey::LEN] != *creator_account.as_bytes() {
                     break;
                 }
@@ -306,6 +317,7 @@ impl BlocksQueryRoot {
                 _ => From(&end, Reverse),
             };
             for (key, _) in db.blocks_height_iterator(mode).flatten() {
+                check_deadline(ctx)?;
                 let height = block_u32_prefix_from_key(&key)?;
                 if height < min || height > max {
                     break;
@@ -377,6 +389,7 @@ impl BlocksQueryRoot {
                 _ => From(&end, Reverse),
             };
             for (key, _) in db.blocks_global_slot_iterator(mode).flatten() {
+                check_deadline(ctx)?;
                 let slot = block_u32_prefix_from_key(&key)?;
                 if slot < min || slot > max {
                     break;
@@ -414,6 +427,7 @@ impl BlocksQueryRoot {
             GlobalSlotDesc => db.blocks_global_slot_iterator(From(&end, Reverse)),
         };
         for (key, _) in iter.flatten() {
+            check_deadline(ctx)?;
             // avoid deserializing PCB if possible
             let state_hash = state_hash_suffix(&key)?;
             if let Some(query_canonicity) = query.as_ref().and_then(|q| q.canonical) {
@@ -433,6 +447,95 @@ impl BlocksQueryRoot {
         }
         Ok(blocks)
     }
+
+    /// Cursor paginated blocks
+    ///
+    /// `totalCount` is only provided for unfiltered queries
+    #[graphql(complexity = "first * child_complexity")]
+    async fn blocks_connection<'ctx>(
+        &self,
+        ctx: &async_graphql::Context<'ctx>,
+        query: Option<BlockQueryInput>,
+        sort_by: Option<BlockSortByInput>,
+        #[graphql(default = 100)] first: usize,
+        after: Option<String>,
+    ) -> Result<SortKeyConnection<Block>> {
+        use BlockSortByInput::*;
+
+        let db = db(ctx);
+        if let Some(query) = query.as_ref() {
+            query.validate()?;
+        }
+        let after = SortKeyCursor::decode(after.as_deref())?;
+        let counts = get_counts(db).await?;
+        let sort_by = sort_by.unwrap_or(BlockHeightDesc);
+
+        let (start, direction) = match sort_by {
+            BlockHeightAsc | GlobalSlotAsc => (0, Direction::Forward),
+            BlockHeightDesc | GlobalSlotDesc => (u32::MAX, Direction::Reverse),
+        };
+
+        // creator/coinbase receiver keys are prefixed by the public key
+        let creator = query
+            .as_ref()
+            .and_then(|q| q.creator_account.as_ref())
+            .and_then(|creator| creator.public_key.clone());
+        let coinbase_receiver = query
+            .as_ref()
+            .and_then(|q| q.coinbase_receiver.as_ref())
+            .and_then(|receiver| receiver.public_key.clone());
+        let pk = creator.as_ref().or(coinbase_receiver.as_ref());
+
+        let mut start = start.to_be_bytes().to_vec();
+        if let Some(pk) = pk {
+            start = [pk.as_bytes(), &start].concat();
+        }
+        let mode = SortKeyCursor::iterator_mode(after.as_ref(), &start, direction);
+        let iter = match sort_by {
+            BlockHeightAsc | BlockHeightDesc if creator.is_some() => {
+                db.block_creator_block_height_iterator(mode)
+            }
+            GlobalSlotAsc | GlobalSlotDesc if creator.is_some() => {
+                db.block_creator_global_slot_iterator(mode)
+            }
+            BlockHeightAsc | BlockHeightDesc if coinbase_receiver.is_some() => {
+                db.coinbase_receiver_block_height_iterator(mode)
+            }
+            GlobalSlotAsc | GlobalSlotDesc if coinbase_receiver.is_some() => {
+                db.coinbase_receiver_global_slot_iterator(mode)
+            }
+            BlockHeightAsc | BlockHeightDesc => db.blocks_height_iterator(mode),
+            GlobalSlotAsc | GlobalSlotDesc => db.blocks_global_slot_iterator(mode),
+        };
+
+        let deadline = deadline(ctx);
+        let nodes = iter
+            .flatten()
+            .take_while(|(key, _)| pk.map_or(true, |pk| key[..PublicKey::LEN] == *pk.as_bytes()))
+            .filter(|(key, _)| SortKeyCursor::precedes(after.as_ref(), key, direction))
+            .map(|(key, _)| {
+                check_scan(deadline)?;
+
+                // avoid deserializing PCB if possible
+                let state_hash = state_hash_suffix(&key)?;
+                if let Some(query_canonicity) = query.as_ref().and_then(|q| q.canonical) {
+                    if get_block_canonicity(db, &state_hash) != query_canonicity {
+                        return Ok(None);
+                    }
+                }
+
+                let pcb = get_block(db, &state_hash);
+                Ok(precomputed_matches_query(db, &query, &pcb, counts)
+                    .map(|block| (key.to_vec(), block)))
+            })
+            .filter_map(anyhow::Result::transpose);
+
+        let total_count = match query {
+            None => Some(db.get_block_production_total_count()?),
+            Some(_) => None,
+        };
+        paginate(deadline, nodes, after.as_ref(), first, total_count)
+    }
 }
 
 fn precomputed_matches_query(
@@ -747,6 +850,7 @@ impl BlockWithoutCanonicity {
         canonical: bool,
         epoch_num_user_commands: u32,
         total_num_user_commands: u32,
+        protocol: &ProtocolParams,
     ) -> Self {
         let winner_account = block.block_creator().0;
         let date_time = millis_to_iso_date_string(block.timestamp() as i64);
@@ -759,7 +863,7 @@ impl BlockWithoutCanonicity {
         let utc_date = block.timestamp().to_string();
 
         // blockchain state
-        let snarked_ledger_hash = block.snarked_ledger_hash().map(|hash| hash.0);
+        let snarked_ledger_hash = Some(block.snarked_ledger_hash().0);
         let staged_ledger_hash = block.staged_ledger_hash().0;
 
         // consensus state
@@ -772,7 +876,7 @@ impl BlockWithoutCanonicity {
         let last_vrf_output = block.last_vrf_output();
         let min_window_density = block.min_window_density();
         let slot_since_genesis = block.global_slot_since_genesis();
-        let slot = slot_since_genesis % MAINNET_EPOCH_SLOT_COUNT;
+        let slot = protocol.epoch_slot(slot_since_genesis);
 
         // next epoch data
         let next_epoch_seed = block.next_epoch_seed();
@@ -792,25 +896,22 @@ impl BlockWithoutCanonicity {
 
         let coinbase_receiver_account = block.coinbase_receiver().0;
         let supercharged = block.supercharge_coinbase();
-        let coinbase: u64 = if supercharged {
-            2 * MAINNET_COINBASE_REWARD
-        } else {
-            MAINNET_COINBASE_REWARD
-        };
+        let coinbase = protocol.coinbase(supercharged);
 
-        let fee_transfers: Vec<BlockFeetransfer> = DbInternalCommand::from_precomputed(block)
-            .into_iter()
-            .map(|cmd| {
-                DbInternalCommandWithData::from_internal_cmd(
-                    cmd,
-                    block.state_hash(),
-                    block.blockchain_length(),
-                    block.timestamp() as i64,
-                )
-            })
-            .filter(|x| matches!(x, DbInternalCommandWithData::FeeTransfer { .. }))
-            .map(|ft| ft.into())
-            .collect();
+        let fee_transfers: Vec<BlockFeetransfer> =
+            DbInternalCommand::from_precomputed(block, protocol)
+                .into_iter()
+                .map(|cmd| {
+                    DbInternalCommandWithData::from_internal_cmd(
+                        cmd,
+                        block.state_hash(),
+                        block.blockchain_length(),
+                        block.timestamp() as i64,
+                    )
+                })
+                .filter(|x| matches!(x, DbInternalCommandWithData::FeeTransfer { .. }))
+                .map(|ft| ft.into())
+                .collect();
 
         let user_commands: Vec<TransactionWithoutBlock> =
             SignedCommandWithData::from_precomputed(block)
@@ -903,6 +1004,28 @@ impl BlockWithoutCanonicity {
 }
 
 impl BlockQueryInput {
+    /// Checks the base58 inputs are of the expected kinds
+    pub fn validate(&self) -> Result<()> {
+        validate_optional_base58(Base58Kind::StateHash, self.state_hash.as_ref())?;
+        if let Some(creator_account) = self.creator_account.as_ref() {
+            validate_optional_base58(Base58Kind::PublicKey, creator_account.public_key.as_ref())?;
+        }
+        if let Some(coinbase_receiver) = self.coinbase_receiver.as_ref() {
+            validate_optional_base58(Base58Kind::PublicKey, coinbase_receiver.public_key.as_ref())?;
+        }
+        if let Some(protocol_state) = self.protocol_state.as_ref() {
+            validate_optional_base58(
+                Base58Kind::StateHash,
+                protocol_state.previous_state_hash.as_ref(),
+            )?;
+        }
+
+        for query in self.and.iter().chain(self.or.iter()).flatten() {
+            query.validate()?;
+        }
+        Ok(())
+    }
+
     pub fn matches(&self, block: &Block) -> bool {
         let Self {
             creator_account,
@@ -1173,6 +1296,7 @@ impl Block {
                 canonical,
                 epoch_num_user_commands,
                 total_num_user_commands,
+                &db.protocol_params(),
             ),
             epoch_num_slots_produced,
             num_unique_block_producers_last_n_blocks: None,
@@ -0,0 +1,197 @@
+use super::db;
+use crate::{
+    block::store::BlockStore,
+    chain::health::{BlockTimeStats, ChainHealth, ChainHealthStore, WindowDensityStats},
+    constants::DEFAULT_CHAIN_HEALTH_WINDOW,
+    web::graphql::limits::deadline,
+};
+use async_graphql::{Context, InputObject, Object, Result, SimpleObject};
+
+#[derive(Default)]
+pub struct ChainHealthQueryRoot;
+
+#[derive(InputObject)]
+pub struct ChainHealthQueryInput {
+    /// Inclusive lower global slot bound (default: 0)
+    #[graphql(name = "global_slot_gte")]
+    global_slot_gte: Option<u32>,
+
+    /// Inclusive upper global slot bound (default: best tip)
+    #[graphql(name = "global_slot_lte")]
+    global_slot_lte: Option<u32>,
+}
+
+#[derive(SimpleObject)]
+pub struct SlotRangeHealth {
+    #[graphql(name = "start_slot")]
+    start_slot: u32,
+
+    #[graphql(name = "end_slot")]
+    end_slot: u32,
+
+    #[graphql(name = "num_slots")]
+    num_slots: u32,
+
+    /// Number of slots with a canonical block
+    #[graphql(name = "num_slots_filled")]
+    num_slots_filled: u32,
+
+    #[graphql(name = "slot_fill_rate")]
+    slot_fill_rate: f64,
+
+    #[graphql(name = "num_blocks")]
+    num_blocks: u32,
+
+    #[graphql(name = "num_canonical_blocks")]
+    num_canonical_blocks: u32,
+
+    #[graphql(name = "num_orphaned_blocks")]
+    num_orphaned_blocks: u32,
+
+    /// Fraction of blocks which are not canonical
+    #[graphql(name = "orphan_rate")]
+    orphan_rate: f64,
+
+    /// Maximal runs of consecutive slots without a canonical block
+    #[graphql(name = "num_empty_slot_runs")]
+    num_empty_slot_runs: u32,
+
+    #[graphql(name = "longest_empty_slot_run_start")]
+    longest_empty_slot_run_start: Option<u32>,
+
+    #[graphql(name = "longest_empty_slot_run")]
+    longest_empty_slot_run: u32,
+
+    #[graphql(name = "mean_empty_slot_run")]
+    mean_empty_slot_run: f64,
+
+    /// Min window density of the canonical blocks
+    #[graphql(name = "min_window_density")]
+    min_window_density: Option<WindowDensity>,
+
+    /// Time between consecutive canonical blocks (millis)
+    #[graphql(name = "block_time")]
+    block_time: Option<BlockTime>,
+}
+
+#[derive(SimpleObject)]
+pub struct WindowDensity {
+    first: u32,
+    last: u32,
+    min: u32,
+    max: u32,
+}
+
+#[derive(SimpleObject)]
+pub struct BlockTime {
+    #[graphql(name = "num_intervals")]
+    num_intervals: u32,
+    mean: f64,
+    min: i64,
+    median: i64,
+    p90: i64,
+    max: i64,
+}
+
+#[Object]
+impl ChainHealthQueryRoot {
+    /// Chain health of the epoch's elapsed slots (default: current epoch)
+    #[graphql(name = "chainHealth")]
+    async fn chain_health<'ctx>(
+        &self,
+        ctx: &Context<'ctx>,
+        epoch: Option<u32>,
+    ) -> Result<SlotRangeHealth> {
+        let db = db(ctx);
+        let epoch = match epoch {
+            Some(epoch) => epoch,
+            None => db.get_current_epoch()?,
+        };
+
+        Ok(db.get_epoch_chain_health(epoch, deadline(ctx))?.into())
+    }
+
+    /// Chain health of sliding windows of `window` slots, starting every
+    /// `step` slots (default: `window`)
+    #[graphql(
+        name = "chainHealthTimeSeries",
+        complexity = "limit * child_complexity"
+    )]
+    async fn chain_health_time_series<'ctx>(
+        &self,
+        ctx: &Context<'ctx>,
+        query: Option<ChainHealthQueryInput>,
+        #[graphql(default_with = "DEFAULT_CHAIN_HEALTH_WINDOW")] window: u32,
+        step: Option<u32>,
+        #[graphql(default = 100)] limit: usize,
+    ) -> Result<Vec<SlotRangeHealth>> {
+        let db = db(ctx);
+        let start_slot = query
+            .as_ref()
+            .and_then(|q| q.global_slot_gte)
+            .unwrap_or_default();
+        let end_slot = query
+            .as_ref()
+            .and_then(|q| q.global_slot_lte)
+            .unwrap_or(u32::MAX);
+
+        Ok(db
+            .get_chain_health_time_series(
+                start_slot,
+                end_slot,
+                window,
+                step.unwrap_or(window),
+                limit,
+                deadline(ctx),
+            )?
+            .into_iter()
+            .map(Into::into)
+            .collect())
+    }
+}
+
+impl From<ChainHealth> for SlotRangeHealth {
+    fn from(value: ChainHealth) -> Self {
+        Self {
+            start_slot: value.start_slot,
+            end_slot: value.end_slot,
+            num_slots: value.num_slots,
+            num_slots_filled: value.num_slots_filled,
+            slot_fill_rate: value.slot_fill_rate,
+            num_blocks: value.num_blocks,
+            num_canonical_blocks: value.num_canonical_blocks,
+            num_orphaned_blocks: value.num_orphaned_blocks,
+            orphan_rate: value.orphan_rate,
+            num_empty_slot_runs: value.num_empty_slot_runs,
+            longest_empty_slot_run_start: value.longest_empty_slot_run.map(|run| run.start_slot),
+            longest_empty_slot_run: value.longest_empty_slot_run.map_or(0, |run| run.num_slots),
+            mean_empty_slot_run: value.mean_empty_slot_run,
+            min_window_density: value.min_window_density.map(Into::into),
+            block_time: value.block_time.map(Into::into),
+        }
+    }
+}
+
+impl From<WindowDensityStats> for WindowDensity {
+    fn from(value: WindowDensityStats) -> Self {
+        Self {
+            first: value.first,
+            last: value.last,
+            min: value.min,
+            max: value.max,
+        }
+    }
+}
+
+impl From<BlockTimeStats> for BlockTime {
+    fn from(value: BlockTimeStats) -> Self {
+        Self {
+            num_intervals: value.num_intervals,
+            mean: value.mean,
+            min: value.min,
+            median: value.median,
+            p90: value.p90,
+            max: value.max,
+        }
+    }
+}
@@ -0,0 +1,171 @@
+use super::{db, validate_base58};
+use crate::{
+    base::{base58::Base58Kind, public_key::PublicKey, state_hash::StateHash},
+    block::store::BlockStore,
+    constants::millis_to_iso_date_string,
+    ledger::store::delegations::{
+        DelegationChange, DelegationFlows, DelegationStore, DelegatorFlow,
+    },
+    web::graphql::limits::deadline,
+};
+use async_graphql::{Context, Object, Result, SimpleObject};
+
+#[derive(Default)]
+pub struct DelegationsQueryRoot;
+
+#[derive(SimpleObject)]
+pub struct AccountDelegationChange {
+    delegator: String,
+
+    delegate: String,
+
+    #[graphql(name = "previous_delegate")]
+    previous_delegate: Option<String>,
+
+    nonce: u32,
+
+    #[graphql(name = "txn_hash")]
+    txn_hash: Option<String>,
+
+    #[graphql(name = "state_hash")]
+    state_hash: String,
+
+    #[graphql(name = "block_height")]
+    block_height: u32,
+
+    #[graphql(name = "global_slot_since_genesis")]
+    global_slot_since_genesis: u32,
+
+    /// Value date_time as ISO 8601 string
+    #[graphql(name = "date_time")]
+    date_time: String,
+}
+
+#[derive(SimpleObject)]
+pub struct DelegateFlows {
+    delegate: String,
+
+    epoch: u32,
+
+    /// Delegators in the epoch's staking ledger, but not the previous epoch's
+    joined: Vec<DelegateFlow>,
+
+    /// Delegators in the previous epoch's staking ledger, but not the epoch's
+    left: Vec<DelegateFlow>,
+
+    #[graphql(name = "joined_balance")]
+    joined_balance: u64,
+
+    #[graphql(name = "left_balance")]
+    left_balance: u64,
+}
+
+#[derive(SimpleObject)]
+pub struct DelegateFlow {
+    #[graphql(name = "public_key")]
+    public_key: String,
+
+    balance: u64,
+
+    /// Delegate in the previous epoch's staking ledger
+    #[graphql(name = "from_delegate")]
+    from_delegate: Option<String>,
+
+    /// Delegate in the epoch's staking ledger
+    #[graphql(name = "to_delegate")]
+    to_delegate: Option<String>,
+}
+
+#[Object]
+impl DelegationsQueryRoot {
+    /// Best chain delegation changes of the account, most recent first
+    #[graphql(name = "delegationHistory", complexity = "limit * child_complexity")]
+    async fn delegation_history<'ctx>(
+        &self,
+        ctx: &Context<'ctx>,
+        public_key: String,
+        #[graphql(default = 100)] limit: usize,
+    ) -> Result<Vec<AccountDelegationChange>> {
+        let pk = validate_public_key(public_key)?;
+        Ok(db(ctx)
+            .get_delegation_history(&pk, limit, deadline(ctx))?
+            .into_iter()
+            .map(Into::into)
+            .collect())
+    }
+
+    /// Delegators who joined & left the delegate between the previous
+    /// epoch's & the epoch's staking ledgers (default: current epoch)
+    #[graphql(name = "delegationFlows")]
+    async fn delegation_flows<'ctx>(
+        &self,
+        ctx: &Context<'ctx>,
+        public_key: String,
+        epoch: Option<u32>,
+        genesis_state_hash: Option<String>,
+    ) -> Result<Option<DelegateFlows>> {
+        let db = db(ctx);
+        let pk = validate_public_key(public_key)?;
+
+        let epoch = match epoch {
+            Some(epoch) => epoch,
+            None => db.get_current_epoch()?,
+        };
+        let genesis_state_hash = match genesis_state_hash {
+            Some(hash) => {
+                validate_base58(Base58Kind::StateHash, &hash)?;
+                Some(StateHash::from(hash))
+            }
+            None => None,
+        };
+
+        Ok(db
+            .get_delegation_flows(&pk, epoch, genesis_state_hash.as_ref(), deadline(ctx))?
+            .map(Into::into))
+    }
+}
+
+fn validate_public_key(public_key: String) -> Result<PublicKey> {
+    validate_base58(Base58Kind::PublicKey, &public_key)?;
+    Ok(public_key.into())
+}
+
+impl From<DelegationChange> for AccountDelegationChange {
+    fn from(change: DelegationChange) -> Self {
+        Self {
+            delegator: change.delegator.0,
+            delegate: change.delegate.0,
+            previous_delegate: change.previous_delegate.map(|pk| pk.0),
+            nonce: change.nonce.0,
+            txn_hash: change.txn_hash.map(|hash| hash.ref_inner().to_owned()),
+            state_hash: change.state_hash.0,
+            block_height: change.blockchain_length,
+            global_slot_since_genesis: change.global_slot_since_genesis,
+            date_time: millis_to_iso_date_string(change.date_time),
+        }
+    }
+}
+
+impl From<DelegationFlows> for DelegateFlows {
+    fn from(flows: DelegationFlows) -> Self {
+        Self {
+            delegate: flows.delegate.0,
+            epoch: flows.epoch,
+            joined: flows.joined.into_iter().map(Into::into).collect(),
+            left: flows.left.into_iter().map(Into::into).collect(),
+            joined_balance: flows.joined_balance,
+            left_balance: flows.left_balance,
+        }
+    }
+}
+
+impl From<DelegatorFlow> for DelegateFlow {
+    fn from(flow: DelegatorFlow) -> Self {
+        Self {
+            public_key: flow.public_key.0,
+            balance: flow.balance,
+            from_delegate: flow.from_delegate.map(|pk| pk.0),
+            to_delegate: flow.to_delegate.map(|pk| pk.0),
+        }
+    }
+}
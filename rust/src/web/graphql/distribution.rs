@@ -0,0 +1,149 @@
+use super::{db, validate_base58};
+use crate::{
+    base::{base58::Base58Kind, state_hash::StateHash},
+    ledger::{
+        distribution::{BalanceBucket, StakeConcentration, WealthDistribution},
+        store::distribution::DistributionStore,
+    },
+};
+use async_graphql::{Context, Object, Result, SimpleObject};
+
+#[derive(Default)]
+pub struct DistributionQueryRoot;
+
+#[derive(SimpleObject)]
+pub struct LedgerDistribution {
+    /// Staking ledger epoch, `null` for the best ledger
+    epoch: Option<u32>,
+
+    #[graphql(name = "num_accounts")]
+    num_accounts: u32,
+
+    /// Number of accounts with delegated stake
+    #[graphql(name = "num_producers")]
+    num_producers: u32,
+
+    #[graphql(name = "total_balance")]
+    total_balance: u64,
+
+    /// Gini coefficient of the account balances
+    #[graphql(name = "balance_gini")]
+    balance_gini: f64,
+
+    /// Gini coefficient of the producers' stakes
+    #[graphql(name = "stake_gini")]
+    stake_gini: f64,
+
+    /// Minimum number of producers controlling more than 1/3 of the stake
+    #[graphql(name = "nakamoto_one_third")]
+    nakamoto_one_third: u32,
+
+    /// Minimum number of producers controlling more than 1/2 of the stake
+    #[graphql(name = "nakamoto_one_half")]
+    nakamoto_one_half: u32,
+
+    /// Stake held by the largest producers
+    #[graphql(name = "top_stake")]
+    top_stake: Vec<LedgerStakeConcentration>,
+
+    /// Account counts by balance bucket, in ascending balance order
+    #[graphql(name = "balance_histogram")]
+    balance_histogram: Vec<LedgerBalanceBucket>,
+}
+
+#[derive(SimpleObject)]
+pub struct LedgerStakeConcentration {
+    /// Number of largest producers
+    top: u32,
+
+    stake: u64,
+
+    share: f64,
+}
+
+#[derive(SimpleObject)]
+pub struct LedgerBalanceBucket {
+    /// Inclusive lower bound (nanomina)
+    #[graphql(name = "min_balance")]
+    min_balance: u64,
+
+    /// Exclusive upper bound (nanomina)
+    #[graphql(name = "max_balance")]
+    max_balance: Option<u64>,
+
+    #[graphql(name = "num_accounts")]
+    num_accounts: u32,
+
+    #[graphql(name = "total_balance")]
+    total_balance: u64,
+}
+
+#[Object]
+impl DistributionQueryRoot {
+    /// Wealth distribution & decentralization statistics of the epoch's
+    /// staking ledger (default: best ledger)
+    #[graphql(name = "wealthDistribution")]
+    async fn wealth_distribution<'ctx>(
+        &self,
+        ctx: &Context<'ctx>,
+        epoch: Option<u32>,
+        genesis_state_hash: Option<String>,
+    ) -> Result<Option<LedgerDistribution>> {
+        let db = db(ctx);
+        let Some(epoch) = epoch else {
+            return Ok(db
+                .get_best_ledger_distribution()?
+                .map(|dist| LedgerDistribution::new(None, dist)));
+        };
+
+        let genesis_state_hash = match genesis_state_hash {
+            Some(hash) => {
+                validate_base58(Base58Kind::StateHash, &hash)?;
+                Some(StateHash::from(hash))
+            }
+            None => None,
+        };
+
+        Ok(db
+            .get_epoch_distribution(epoch, genesis_state_hash.as_ref())?
+            .map(|dist| LedgerDistribution::new(Some(epoch), dist)))
+    }
+}
+
+impl LedgerDistribution {
+    fn new(epoch: Option<u32>, dist: WealthDistribution) -> Self {
+        Self {
+            epoch,
+            num_accounts: dist.num_accounts,
+            num_producers: dist.num_producers,
+            total_balance: dist.total_balance,
+            balance_gini: dist.balance_gini,
+            stake_gini: dist.stake_gini,
+            nakamoto_one_third: dist.nakamoto_one_third,
+            nakamoto_one_half: dist.nakamoto_one_half,
+            top_stake: dist.top_stake.into_iter().map(Into::into).collect(),
+            balance_histogram: dist.balance_histogram.into_iter().map(Into::into).collect(),
+        }
+    }
+}
+
+impl From<StakeConcentration> for LedgerStakeConcentration {
+    fn from(value: StakeConcentration) -> Self {
+        Self {
+            top: value.top,
+            stake: value.stake,
+            share: value.share,
+        }
+    }
+}
+
+impl From<BalanceBucket> for LedgerBalanceBucket {
+    fn from(value: BalanceBucket) -> Self {
+        Self {
+            min_balance: value.min_balance,
+            max_balance: value.max_balance,
+            num_accounts: value.num_accounts,
+            total_balance: value.total_balance,
+        }
+    }
+}
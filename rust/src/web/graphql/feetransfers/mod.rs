@@ -1,11 +1,11 @@
 use super::{
     blocks::{Block, BlockWithoutCanonicity},
     gen::BlockQueryInput,
-    get_block, get_block_canonicity,
+    get_block_canonicity, validate_optional_base58,
 };
 use crate::{
-    base::{public_key::PublicKey, state_hash::StateHash},
-    block::{precomputed::PrecomputedBlock, store::BlockStore},
+    base::{base58::Base58Kind, public_key::PublicKey, state_hash::StateHash},
+    block::store::BlockStore,
     command::{
         internal::{store::InternalCommandStore, DbInternalCommandWithData},
         store::UserCommandStore,
@@ -13,8 +13,16 @@ use crate::{
     constants::*,
     snark_work::store::SnarkStore,
     store::IndexerStore,
-    utility::store::common::{from_be_bytes, U32_LEN},
-    web::graphql::db,
+    utility::{
+        deadline::check_scan,
+        store::common::{from_be_bytes, U32_LEN},
+    },
+    web::graphql::{
+        db,
+        limits::{check_deadline, deadline},
+        loaders::{loader, BlockKey},
+        pagination::{paginate, SortKeyConnection, SortKeyCursor},
+    },
 };
 use async_graphql::{Context, Enum, InputObject, Object, Result, SimpleObject};
 use speedb::{Direction, IteratorMode};
@@ -43,9 +51,6 @@ pub struct FeetransferWithMeta {
     /// Value canonicity
     pub canonical: bool,
 
-    /// Value optional block
-    pub block: Option<PrecomputedBlock>,
-
     /// Value feetranser
     pub feetransfer: Feetransfer,
 }
@@ -74,7 +79,10 @@ impl FeetransferWithMeta {
         let total_num_user_commands = db.get_user_commands_total_count()?;
         let epoch_num_slots_produced = db.get_epoch_slots_produced_count(None)?;
 
-        if let Some(block) = self.block.clone() {
+        let block = loader(ctx)
+            .load_one(BlockKey(self.feetransfer.state_hash.clone().into()))
+            .await?;
+        if let Some(block) = block {
             let block_num_snarks = db
                 .get_block_snarks_count(&block.state_hash())?
                 .unwrap_or_default();
@@ -90,6 +98,7 @@ impl FeetransferWithMeta {
                     self.canonical,
                     epoch_num_user_commands,
                     total_num_user_commands,
+                    &db.protocol_params(),
                 ),
                 canonical: self.canonical,
                 epoch_num_blocks,
@@ -161,6 +170,7 @@ pub struct FeetransferQueryRoot;
 
 #[Object]
 impl FeetransferQueryRoot {
+    #[graphql(complexity = "limit * child_complexity")]
     async fn feetransfers<'ctx>(
         &self,
         ctx: &Context<'ctx>,
@@ -171,6 +181,9 @@ impl FeetransferQueryRoot {
         use FeetransferSortByInput::*;
 
         let db = db(ctx);
+        if let Some(query) = query.as_ref() {
+            query.validate()?;
+        }
         let epoch_num_internal_commands = db.get_internal_commands_epoch_count(None)?;
         let total_num_internal_commands = db.get_internal_commands_total_count()?;
         let mut fee_transfers = vec![];
@@ -232,6 +245,7 @@ impl FeetransferQueryRoot {
             };
 
             for (key, value) in iter.flatten() {
+                check_deadline(ctx)?;
                 let state_hash = StateHash::from_bytes(&key[U32_LEN..][..StateHash::LEN])?;
 
                 // avoid deserializing internal command & PCB if possible
@@ -257,7 +271,6 @@ impl FeetransferQueryRoot {
                 let feetransfer_with_meta = FeetransferWithMeta {
                     canonical,
                     feetransfer: ft,
-                    block: Some(get_block(db, &state_hash)),
                 };
 
                 if query
@@ -288,6 +301,7 @@ impl FeetransferQueryRoot {
             };
 
             for (key, value) in iter.flatten() {
+                check_deadline(ctx)?;
                 if key[..PublicKey::LEN] != *recipient.as_bytes() {
                     // we've gone beyond our recipient
                     break;
@@ -312,10 +326,8 @@ impl FeetransferQueryRoot {
                 }
 
                 let internal_command: DbInternalCommandWithData = serde_json::from_slice(&value)?;
-                let pcb = get_block(db, &state_hash);
                 let ft = FeetransferWithMeta {
                     canonical,
-                    block: Some(pcb),
                     feetransfer: Feetransfer::from((
                         internal_command,
                         epoch_num_internal_commands,
@@ -335,6 +347,7 @@ impl FeetransferQueryRoot {
         }
 
         get_default_fee_transfers(
+            ctx,
             db,
             query,
             sort_by,
@@ -343,9 +356,94 @@ impl FeetransferQueryRoot {
             total_num_internal_commands,
         )
     }
+
+    /// Cursor paginated fee transfers
+    ///
+    /// `totalCount` is only provided for unfiltered queries
+    #[graphql(complexity = "first * child_complexity")]
+    async fn feetransfers_connection<'ctx>(
+        &self,
+        ctx: &Context<'ctx>,
+        query: Option<FeetransferQueryInput>,
+        sort_by: Option<FeetransferSortByInput>,
+        #[graphql(default = 100)] first: usize,
+        after: Option<String>,
+    ) -> Result<SortKeyConnection<FeetransferWithMeta>> {
+        let db = db(ctx);
+        if let Some(query) = query.as_ref() {
+            query.validate()?;
+        }
+        let after = SortKeyCursor::decode(after.as_deref())?;
+        let epoch_num_internal_commands = db.get_internal_commands_epoch_count(None)?;
+        let total_num_internal_commands = db.get_internal_commands_total_count()?;
+
+        let (start, direction) = match sort_by {
+            Some(FeetransferSortByInput::BlockHeightAsc) => (0u32, Direction::Forward),
+            Some(FeetransferSortByInput::BlockHeightDesc) | None => (u32::MAX, Direction::Reverse),
+        };
+
+        // recipient keys are prefixed by the public key
+        let recipient = query.as_ref().and_then(|q| q.recipient.clone());
+        let prefix_len = recipient.as_ref().map_or(0, |_| PublicKey::LEN);
+        let start = start.to_be_bytes();
+        let iter = match recipient.as_ref() {
+            Some(recipient) => {
+                db.internal_commands_pk_block_height_iterator(recipient.clone().into(), direction)
+            }
+            None => db.internal_commands_block_height_iterator(SortKeyCursor::iterator_mode(
+                after.as_ref(),
+                &start,
+                direction,
+            )),
+        };
+
+        let deadline = deadline(ctx);
+        let nodes = iter
+            .flatten()
+            .take_while(|(key, _)| {
+                recipient
+                    .as_ref()
+                    .map_or(true, |pk| key[..PublicKey::LEN] == *pk.as_bytes())
+            })
+            .filter(|(key, _)| SortKeyCursor::precedes(after.as_ref(), key, direction))
+            .map(|(key, value)| {
+                check_scan(deadline)?;
+
+                let state_hash =
+                    StateHash::from_bytes(&key[prefix_len..][U32_LEN..][..StateHash::LEN])?;
+
+                // avoid deserializing internal command & PCB if possible
+                let canonical = get_block_canonicity(db, &state_hash);
+                if let Some(query_canonicity) = query.as_ref().and_then(|q| q.canonical) {
+                    if canonical != query_canonicity {
+                        return Ok(None);
+                    }
+                }
+
+                let internal_cmd: DbInternalCommandWithData = serde_json::from_slice(&value)?;
+                let feetransfer_with_meta = FeetransferWithMeta {
+                    canonical,
+                    feetransfer: Feetransfer::from((
+                        internal_cmd,
+                        epoch_num_internal_commands,
+                        total_num_internal_commands,
+                    )),
+                };
+
+                Ok(query
+                    .as_ref()
+                    .map_or(true, |q| q.matches(&feetransfer_with_meta))
+                    .then(|| (key.to_vec(), feetransfer_with_meta)))
+            })
+            .filter_map(anyhow::Result::transpose);
+
+        let total_count = query.is_none().then_some(total_num_internal_commands);
+        paginate(deadline, nodes, after.as_ref(), first, total_count)
+    }
 }
 
 fn get_default_fee_transfers(
+    ctx: &Context<'_>,
     db: &Arc<IndexerStore>,
     query: Option<FeetransferQueryInput>,
     sort_by: Option<FeetransferSortByInput>,
@@ -361,6 +459,7 @@ fn get_default_fee_transfers(
     };
 
     for (key, value) in db.internal_commands_block_height_iterator(mode).flatten() {
+        check_deadline(ctx)?;
         let state_hash = StateHash::from_bytes(&key[U32_LEN..][..StateHash::LEN])?;
         let canonical = get_block_canonicity(db, &state_hash);
         if let Some(q) = query.as_ref() {
@@ -380,11 +479,9 @@ fn get_default_fee_transfers(
             epoch_num_internal_commands,
             total_num_internal_commands,
         ));
-        let pcb = get_block(db, &state_hash);
         let feetransfer_with_meta = FeetransferWithMeta {
             canonical,
             feetransfer: ft,
-            block: Some(pcb),
         };
 
         if query
@@ -417,10 +514,9 @@ fn get_fee_transfers_for_state_hash(
         }
     }
 
-    let pcb = match db.get_block(state_hash) {
-        Ok(Some(pcb)) => pcb.0,
-        _ => return vec![],
-    };
+    if !matches!(db.get_block_height(state_hash), Ok(Some(_))) {
+        return vec![];
+    }
     match db.get_internal_commands(state_hash) {
         Ok(internal_commands) => {
             let mut internal_commands: Vec<FeetransferWithMeta> = internal_commands
@@ -432,7 +528,6 @@ fn get_fee_transfers_for_state_hash(
                         epoch_num_internal_commands,
                         total_num_internal_commands,
                     )),
-                    block: Some(pcb.clone()),
                 })
                 .filter(|ft| query.as_ref().map_or(true, |q| q.matches(ft)))
                 .collect();
@@ -502,6 +597,15 @@ impl From<(DbInternalCommandWithData, u32, u32)> for Feetransfer {
 }
 
 impl FeetransferQueryInput {
+    /// Checks the base58 inputs are of the expected kinds
+    fn validate(&self) -> Result<()> {
+        validate_optional_base58(Base58Kind::PublicKey, self.recipient.as_ref())?;
+        if let Some(block) = self.block_state_hash.as_ref() {
+            block.validate()?;
+        }
+        Ok(())
+    }
+
     pub fn matches(&self, ft: &FeetransferWithMeta) -> bool {
         let Self {
             block_height_gt,
@@ -527,8 +631,7 @@ impl FeetransferQueryInput {
             }
         }
 
-        let pcb = ft.block.as_ref().expect("block will exist");
-        let blockchain_length = pcb.blockchain_length();
+        let blockchain_length = ft.feetransfer.block_height;
         if let Some(height) = block_height.as_ref() {
             if blockchain_length != *height {
                 return false;
@@ -0,0 +1,303 @@
+//! Query depth, complexity, timeout & persisted query limits for the GraphQL
+//! endpoint
+
+use crate::{
+    constants::*,
+    utility::deadline::{check_scan, timed_out, Deadline},
+};
+use anyhow::{bail, Context as aContext};
+use async_graphql::{
+    extensions::{Extension, ExtensionContext, ExtensionFactory, NextExecute, NextPrepareRequest},
+    Context, Request, Response, ServerError, ServerResult, Value,
+};
+use log::info;
+use sha2::{Digest, Sha256};
+use std::{collections::HashMap, path::Path, sync::Arc, time::Duration};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GraphQLLimits {
+    /// Maximum depth of a query's selection set
+    pub max_depth: usize,
+
+    /// Maximum complexity of a query, list fields cost `limit` (or `first`)
+    /// times their selection set
+    pub max_complexity: usize,
+
+    /// Maximum execution time of a single request
+    pub timeout: Duration,
+
+    /// Persisted queries keyed by the hex sha256 hash of the query
+    pub persisted_queries: Arc<HashMap<String, String>>,
+
+    /// Only execute persisted queries
+    pub production: bool,
+}
+
+impl Default for GraphQLLimits {
+    fn default() -> Self {
+        Self {
+            max_depth: DEFAULT_GRAPHQL_MAX_DEPTH,
+            max_complexity: DEFAULT_GRAPHQL_MAX_COMPLEXITY,
+            timeout: Duration::from_secs(DEFAULT_GRAPHQL_TIMEOUT),
+            persisted_queries: Arc::new(HashMap::new()),
+            production: false,
+        }
+    }
+}
+
+impl GraphQLLimits {
+    pub fn new(
+        max_depth: usize,
+        max_complexity: usize,
+        timeout: u64,
+        persisted_queries_path: Option<&Path>,
+        production: bool,
+    ) -> anyhow::Result<Self> {
+        let persisted_queries = match persisted_queries_path {
+            Some(path) => Self::read_persisted_queries(path)?,
+            None if production => {
+                bail!("Production GraphQL mode requires a persisted queries file")
+            }
+            None => HashMap::new(),
+        };
+
+        Ok(Self {
+            max_depth,
+            max_complexity,
+            timeout: Duration::from_secs(timeout),
+            persisted_queries: Arc::new(persisted_queries),
+            production,
+        })
+    }
+
+    /// Read a JSON object of persisted queries, the keys are checked against
+    /// the hex sha256 hashes of the queries
+    pub fn read_persisted_queries(path: &Path) -> anyhow::Result<HashMap<String, String>> {
+        let contents = std::fs::read(path)
+            .with_context(|| format!("reading persisted queries {}", path.display()))?;
+        let queries: HashMap<String, String> = serde_json::from_slice(&contents)
+            .with_context(|| format!("parsing persisted queries {}", path.display()))?;
+
+        for (hash, query) in queries.iter() {
+            let query_hash = query_hash(query);
+            if *hash != query_hash {
+                bail!("Persisted query hash mismatch: expected {query_hash}, got {hash}")
+            }
+        }
+
+        info!(
+            "Read {} persisted GraphQL queries from {}",
+            queries.len(),
+            path.display()
+        );
+        Ok(queries)
+    }
+
+    /// Extension enforcing the persisted query allow-list & request timeout
+    pub fn extension(&self) -> QueryLimits {
+        QueryLimits(self.clone())
+    }
+}
+
+/// Hex sha256 hash of the query, matching Apollo's automatic persisted queries
+pub fn query_hash(query: &str) -> String {
+    hex::encode(Sha256::digest(query.as_bytes()))
+}
+
+/// The request's deadline
+pub fn deadline<'a>(ctx: &'a Context<'_>) -> Option<&'a Deadline> {
+    ctx.data_opt::<Deadline>()
+}
+
+/// Errors if the request's deadline has passed
+pub fn check_deadline(ctx: &Context<'_>) -> async_graphql::Result<()> {
+    Ok(check_scan(deadline(ctx))?)
+}
+
+pub struct QueryLimits(GraphQLLimits);
+
+impl ExtensionFactory for QueryLimits {
+    fn create(&self) -> Arc<dyn Extension> {
+        Arc::new(QueryLimitsExtension(self.0.clone()))
+    }
+}
+
+struct QueryLimitsExtension(GraphQLLimits);
+
+impl QueryLimitsExtension {
+    /// Persisted query hash supplied in the request's `persistedQuery`
+    /// extension
+    fn requested_hash(request: &Request) -> Option<String> {
+        match request.extensions.get("persistedQuery") {
+            Some(Value::Object(persisted_query)) => match persisted_query.get("sha256Hash") {
+                Some(Value::String(hash)) => Some(hash.to_owned()),
+                _ => None,
+            },
+            _ => None,
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl Extension for QueryLimitsExtension {
+    async fn prepare_request(
+        &self,
+        ctx: &ExtensionContext<'_>,
+        mut request: Request,
+        next: NextPrepareRequest<'_>,
+    ) -> ServerResult<Request> {
+        let requested_hash = Self::requested_hash(&request);
+        if request.query.is_empty() {
+            // look up the query by its hash
+            let hash = requested_hash
+                .ok_or_else(|| ServerError::new("Missing query or persisted query hash", None))?;
+
+            match self.0.persisted_queries.get(&hash) {
+                Some(query) => request.query = query.to_owned(),
+                None => return Err(ServerError::new("PersistedQueryNotFound", None)),
+            }
+        } else {
+            let hash = query_hash(&request.query);
+            if requested_hash.is_some_and(|requested| requested != hash) {
+                return Err(ServerError::new("provided sha does not match query", None));
+            }
+
+            if self.0.production && !self.0.persisted_queries.contains_key(&hash) {
+                return Err(ServerError::new(
+                    format!("Query {hash} is not in the persisted query allow-list"),
+                    None,
+                ));
+            }
+        }
+
+        let request = request.data(Deadline::new(self.0.timeout));
+        next.run(ctx, request).await
+    }
+
+    async fn execute(
+        &self,
+        ctx: &ExtensionContext<'_>,
+        operation_name: Option<&str>,
+        next: NextExecute<'_>,
+    ) -> Response {
+        // only interrupts at await points, synchronous store iteration is
+        // stopped by the resolvers' deadline checks
+        match tokio::time::timeout(self.0.timeout, next.run(ctx, operation_name)).await {
+            Ok(response) => response,
+            Err(_) => {
+                Response::from_errors(vec![ServerError::new(timed_out(self.0.timeout), None)])
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use async_graphql::{EmptyMutation, EmptySubscription, Object, Schema};
+    use std::{collections::HashMap, time::Instant};
+
+    struct Query;
+
+    #[Object]
+    impl Query {
+        async fn value(&self) -> u32 {
+            42
+        }
+
+        #[graphql(complexity = "limit * child_complexity")]
+        async fn values(&self, limit: usize) -> Vec<Inner> {
+            (0..limit).map(|_| Inner).collect()
+        }
+
+        /// Synchronously scans rows like the store-backed resolvers
+        async fn slow(&self, ctx: &Context<'_>) -> async_graphql::Result<u32> {
+            loop {
+                check_deadline(ctx)?;
+                std::thread::sleep(Duration::from_millis(10));
+            }
+        }
+    }
+
+    struct Inner;
+
+    #[Object]
+    impl Inner {
+        async fn inner(&self) -> Option<Inner> {
+            None
+        }
+    }
+
+    fn schema(limits: GraphQLLimits) -> Schema<Query, EmptyMutation, EmptySubscription> {
+        Schema::build(Query, EmptyMutation, EmptySubscription)
+            .limit_depth(limits.max_depth)
+            .limit_complexity(limits.max_complexity)
+            .extension(limits.extension())
+            .finish()
+    }
+
+    fn persisted(query: &str) -> GraphQLLimits {
+        GraphQLLimits {
+            persisted_queries: Arc::new(HashMap::from([(query_hash(query), query.to_string())])),
+            production: true,
+            ..Default::default()
+        }
+    }
+
+    #[tokio::test]
+    async fn depth_and_complexity() {
+        let schema = schema(GraphQLLimits {
+            max_depth: 3,
+            max_complexity: 20,
+            ..Default::default()
+        });
+
+        assert!(schema
+            .execute("{ values(limit: 1) { inner { inner { inner { __typename } } } } }")
+            .await
+            .is_err());
+        assert!(schema
+            .execute("{ values(limit: 20) { inner { __typename } } }")
+            .await
+            .is_ok());
+        assert!(schema
+            .execute("{ values(limit: 21) { inner { __typename } } }")
+            .await
+            .is_err());
+    }
+
+    #[tokio::test]
+    async fn persisted_query_allow_list() {
+        let query = "{ value }";
+        let schema = schema(persisted(query));
+        assert!(schema.execute(query).await.is_ok());
+        assert!(schema.execute("{ value __typename }").await.is_err());
+
+        // lookup by hash
+        let mut request = Request::new("");
+        request.extensions.insert(
+            "persistedQuery".into(),
+            Value::from_json(serde_json::json!({ "version": 1, "sha256Hash": query_hash(query) }))
+                .unwrap(),
+        );
+        let response = schema.execute(request).await;
+        assert_eq!(response.data.into_json().unwrap()["value"], 42);
+    }
+
+    #[tokio::test]
+    async fn slow_query_times_out() {
+        let schema = schema(GraphQLLimits {
+            timeout: Duration::from_millis(100),
+            ..Default::default()
+        });
+
+        let start = Instant::now();
+        let response = schema.execute("{ slow }").await;
+        assert!(start.elapsed() < Duration::from_secs(5));
+        assert_eq!(response.errors.len(), 1);
+        assert!(response.errors[0].message.starts_with("Query timed out"));
+
+        // other queries are unaffected
+        assert!(schema.execute("{ value }").await.is_ok());
+    }
+}
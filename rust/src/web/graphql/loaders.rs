@@ -0,0 +1,92 @@
+//! Batched store lookups for nested GraphQL fields
+
+use crate::{
+    base::{public_key::PublicKey, state_hash::StateHash},
+    block::precomputed::PrecomputedBlock,
+    store::{
+        multi_get::{MultiGetStore, PkCounts},
+        IndexerStore,
+    },
+};
+use async_graphql::{
+    dataloader::{DataLoader, Loader},
+    Context, Error, Result,
+};
+use std::{collections::HashMap, sync::Arc};
+
+/// Coalesces the point lookups of concurrently resolved fields into
+/// `multi_get_cf` reads
+pub struct IndexerLoader {
+    db: Arc<IndexerStore>,
+}
+
+pub type IndexerDataLoader = DataLoader<IndexerLoader>;
+
+/// Current username of the public key
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct UsernameKey(pub PublicKey);
+
+/// Current epoch & total counts of the public key
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct PkCountsKey(pub PublicKey);
+
+/// Block with the state hash
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct BlockKey(pub StateHash);
+
+impl IndexerLoader {
+    pub fn data_loader(db: Arc<IndexerStore>) -> IndexerDataLoader {
+        DataLoader::new(Self { db }, tokio::spawn)
+    }
+}
+
+pub(crate) fn loader<'a>(ctx: &'a Context) -> &'a IndexerDataLoader {
+    ctx.data::<IndexerDataLoader>()
+        .expect("Data loader should be in the context")
+}
+
+impl Loader<UsernameKey> for IndexerLoader {
+    type Value = String;
+    type Error = Error;
+
+    async fn load(&self, keys: &[UsernameKey]) -> Result<HashMap<UsernameKey, String>> {
+        let pks: Vec<_> = keys.iter().map(|key| key.0.clone()).collect();
+        let usernames = self.db.get_usernames(&pks)?;
+
+        Ok(keys
+            .iter()
+            .cloned()
+            .zip(usernames)
+            .filter_map(|(key, username)| username.map(|username| (key, username.0)))
+            .collect())
+    }
+}
+
+impl Loader<PkCountsKey> for IndexerLoader {
+    type Value = PkCounts;
+    type Error = Error;
+
+    async fn load(&self, keys: &[PkCountsKey]) -> Result<HashMap<PkCountsKey, PkCounts>> {
+        let pks: Vec<_> = keys.iter().map(|key| key.0.clone()).collect();
+        let counts = self.db.get_pk_counts(&pks, None)?;
+
+        Ok(keys.iter().cloned().zip(counts).collect())
+    }
+}
+
+impl Loader<BlockKey> for IndexerLoader {
+    type Value = Arc<PrecomputedBlock>;
+    type Error = Error;
+
+    async fn load(&self, keys: &[BlockKey]) -> Result<HashMap<BlockKey, Arc<PrecomputedBlock>>> {
+        let state_hashes: Vec<_> = keys.iter().map(|key| key.0.clone()).collect();
+        let blocks = self.db.get_blocks(&state_hashes)?;
+
+        Ok(keys
+            .iter()
+            .cloned()
+            .zip(blocks)
+            .filter_map(|(key, block)| block.map(|block| (key, Arc::new(block))))
+            .collect())
+    }
+}
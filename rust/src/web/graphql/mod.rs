@@ -1,45 +1,78 @@
 pub mod accounts;
+pub mod balance_history;
 pub mod blocks;
+pub mod chain_health;
+pub mod delegations;
+pub mod distribution;
 pub mod feetransfers;
 pub mod gen;
+pub mod limits;
+pub mod loaders;
+pub mod pagination;
+pub mod payouts;
+pub mod producer_performance;
+pub mod reorgs;
 pub mod snarks;
 pub mod staged_ledgers;
 pub mod stakes;
+pub mod supply;
 pub mod top_snarkers;
 pub mod top_stakers;
 pub mod transactions;
 pub mod version;
+pub mod vesting;
+pub mod witness_tree;
 
-use super::ENDPOINT_GRAPHQL;
+use self::limits::GraphQLLimits;
+use super::{NetworkParams, ENDPOINT_GRAPHQL};
 use crate::{
-    base::state_hash::StateHash,
+    base::{base58::Base58Kind, state_hash::StateHash},
     block::{precomputed::PrecomputedBlock, store::BlockStore},
     constants::*,
     store::IndexerStore,
 };
-use actix_web::HttpResponse;
+use actix_web::{error::ErrorBadRequest, web, web::Data, HttpRequest, HttpResponse};
 use anyhow::Context as aContext;
 use async_graphql::{
-    http::GraphiQLSource, Context, EmptyMutation, EmptySubscription, InputValueError,
-    InputValueResult, MergedObject, Scalar, ScalarType, Schema, SimpleObject, Value,
+    http::GraphiQLSource, Context, EmptyMutation, InputValueError, InputValueResult, MergedObject,
+    MergedSubscription, Scalar, ScalarType, Schema, SimpleObject, Value,
 };
+use async_graphql_actix_web::{GraphQLBatchRequest, GraphQLResponse, GraphQLSubscription};
 use serde::Serialize;
 use std::sync::Arc;
 
+/// Queries of the primary network. Other indexed networks are queried by
+/// their name (e.g. `devnet`) or chain id in the `network` URL query
+/// parameter, e.g. `/graphql?network=devnet`
 #[derive(MergedObject, Default)]
 pub struct Root(
     blocks::BlocksQueryRoot,
     stakes::StakeQueryRoot,
     accounts::AccountQueryRoot,
+    balance_history::BalanceHistoryQueryRoot,
+    chain_health::ChainHealthQueryRoot,
+    delegations::DelegationsQueryRoot,
+    distribution::DistributionQueryRoot,
     transactions::TransactionsQueryRoot,
     feetransfers::FeetransferQueryRoot,
     snarks::SnarkQueryRoot,
     staged_ledgers::StagedLedgerQueryRoot,
+    payouts::PayoutsQueryRoot,
+    producer_performance::ProducerPerformanceQueryRoot,
+    reorgs::ReorgsQueryRoot,
+    supply::SupplyQueryRoot,
+    vesting::VestingQueryRoot,
     top_stakers::TopStakersQueryRoot,
     top_snarkers::TopSnarkersQueryRoot,
     version::VersionQueryRoot,
+    witness_tree::WitnessTreeQueryRoot,
 );
 
+#[derive(MergedSubscription, Default)]
+pub struct SubscriptionRoot(reorgs::ReorgsSubscriptionRoot);
+
+pub type IndexerSchema = Schema<Root, EmptyMutation, SubscriptionRoot>;
+
 #[derive(SimpleObject)]
 pub struct Timing {
     #[graphql(name = "cliff_amount")]
@@ -59,16 +92,87 @@ pub struct Timing {
 }
 
 /// Build schema for all endpoints
-pub fn build_schema(store: Arc<IndexerStore>) -> Schema<Root, EmptyMutation, EmptySubscription> {
-    Schema::build(Root::default(), EmptyMutation, EmptySubscription)
+pub fn build_schema(store: Arc<IndexerStore>, limits: &GraphQLLimits) -> IndexerSchema {
+    Schema::build(Root::default(), EmptyMutation, SubscriptionRoot::default())
+        .limit_depth(limits.max_depth)
+        .limit_complexity(limits.max_complexity)
+        .extension(limits.extension())
+        .data(loaders::IndexerLoader::data_loader(store.clone()))
         .data(store)
         .finish()
 }
 
-pub async fn indexer_graphiql() -> actix_web::Result<HttpResponse> {
+/// Serve GraphiQL, querying the network of the `network` query parameter,
+/// e.g. `/graphql?network=devnet`
+pub async fn indexer_graphiql(
+    store: Data<Arc<IndexerStore>>,
+    network: web::Query<NetworkParams>,
+) -> actix_web::Result<HttpResponse> {
+    let (endpoint, title) = match network.network.as_deref() {
+        Some(name) => {
+            network.store(&store).map_err(ErrorBadRequest)?;
+            (
+                format!("{ENDPOINT_GRAPHQL}?network={name}"),
+                format!("Mina Indexer GraphiQL ({name})"),
+            )
+        }
+        None => (
+            ENDPOINT_GRAPHQL.to_string(),
+            "Mina Indexer GraphiQL".to_string(),
+        ),
+    };
+
     Ok(HttpResponse::Ok()
         .content_type("text/html; charset=utf-8")
-        .body(GraphiQLSource::build().endpoint(ENDPOINT_GRAPHQL).finish()))
+        .body(
+            GraphiQLSource::build()
+                .endpoint(&endpoint)
+                .subscription_endpoint(&endpoint)
+                .title(&title)
+                .finish(),
+        ))
+}
+
+/// Serve GraphQL queries against the store of the `network` query parameter
+pub async fn indexer_graphql(
+    schema: Data<IndexerSchema>,
+    store: Data<Arc<IndexerStore>>,
+    network: web::Query<NetworkParams>,
+    request: GraphQLBatchRequest,
+) -> actix_web::Result<GraphQLResponse> {
+    let mut request = request.into_inner();
+    if network.network.is_some() {
+        let db = network.store(&store).map_err(ErrorBadRequest)?;
+        for request in request.iter_mut() {
+            request
+                .data
+                .insert(loaders::IndexerLoader::data_loader(db.clone()));
+            request.data.insert(db.clone());
+        }
+    }
+
+    Ok(schema.execute_batch(request).await.into())
+}
+
+/// Serve GraphQL subscriptions over websocket, against the store of the
+/// `network` query parameter
+pub async fn indexer_subscription(
+    schema: Data<IndexerSchema>,
+    store: Data<Arc<IndexerStore>>,
+    network: web::Query<NetworkParams>,
+    req: HttpRequest,
+    payload: web::Payload,
+) -> actix_web::Result<HttpResponse> {
+    let mut subscription = GraphQLSubscription::new(Schema::clone(&*schema));
+    if network.network.is_some() {
+        let db = network.store(&store).map_err(ErrorBadRequest)?;
+        let mut data = async_graphql::Data::default();
+        data.insert(loaders::IndexerLoader::data_loader(db.clone()));
+        data.insert(db);
+        subscription = subscription.with_data(data);
+    }
+
+    subscription.start(&req, payload)
 }
 
 pub(crate) fn db<'a>(ctx: &'a Context) -> &'a Arc<IndexerStore> {
@@ -117,6 +221,21 @@ impl ScalarType for DateTime {
     }
 }
 
+/// Checks a base58check input is of the expected kind, e.g. rejects a ledger
+/// hash given as a state hash
+pub(crate) fn validate_base58(kind: Base58Kind, input: &str) -> async_graphql::Result<()> {
+    kind.validate(input)
+        .map_err(|e| async_graphql::Error::new(e.to_string()))
+}
+
+/// [validate_base58] for optional inputs
+pub(crate) fn validate_optional_base58(
+    kind: Base58Kind,
+    input: Option<&String>,
+) -> async_graphql::Result<()> {
+    input.map_or(Ok(()), |input| validate_base58(kind, input))
+}
+
 /// Convert epoch milliseconds to an ISO 8601 formatted [DateTime] Scalar.
 pub(crate) fn date_time_to_scalar(millis: i64) -> DateTime {
     DateTime(millis_to_iso_date_string(millis))
@@ -0,0 +1,182 @@
+//! Relay-style cursor pagination over sort CF keys
+
+use crate::utility::deadline::Deadline;
+use async_graphql::{
+    connection::{Connection, CursorType, Edge},
+    OutputType, Result, SimpleObject,
+};
+use base64::{prelude::BASE64_URL_SAFE_NO_PAD, DecodeError, Engine};
+use speedb::{Direction, IteratorMode};
+
+/// Opaque cursor wrapping the sort CF key of the edge's node
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SortKeyCursor(pub Vec<u8>);
+
+/// Fields common to all connections
+#[derive(SimpleObject)]
+pub struct ConnectionFields {
+    /// Total number of nodes matching the query, if it is known without
+    /// scanning the whole result set
+    pub total_count: Option<u32>,
+}
+
+pub type SortKeyConnection<T> = Connection<SortKeyCursor, T, ConnectionFields>;
+
+/// Default & maximum number of nodes per page
+pub const DEFAULT_PAGE_SIZE: usize = 100;
+pub const MAX_PAGE_SIZE: usize = 1000;
+
+impl CursorType for SortKeyCursor {
+    type Error = DecodeError;
+
+    fn decode_cursor(s: &str) -> Result<Self, Self::Error> {
+        BASE64_URL_SAFE_NO_PAD.decode(s).map(Self)
+    }
+
+    fn encode_cursor(&self) -> String {
+        BASE64_URL_SAFE_NO_PAD.encode(&self.0)
+    }
+}
+
+impl SortKeyCursor {
+    /// Decode the `after` cursor argument
+    pub fn decode(after: Option<&str>) -> Result<Option<Self>> {
+        after
+            .map(|after| {
+                Self::decode_cursor(after)
+                    .map_err(|e| async_graphql::Error::new(format!("Invalid cursor {after}: {e}")))
+            })
+            .transpose()
+    }
+
+    /// Iterator mode which seeks to the cursor, or to `start` if there is
+    /// no cursor. The cursor's own key must still be skipped with
+    /// [SortKeyCursor::precedes]
+    pub fn iterator_mode<'a>(
+        cursor: Option<&'a Self>,
+        start: &'a [u8],
+        direction: Direction,
+    ) -> IteratorMode<'a> {
+        IteratorMode::From(cursor.map_or(start, |cursor| &cursor.0), direction)
+    }
+
+    /// Returns true if `key` comes strictly after the cursor in `direction`
+    /// order, or if there is no cursor
+    pub fn precedes(cursor: Option<&Self>, key: &[u8], direction: Direction) -> bool {
+        cursor.map_or(true, |cursor| match direction {
+            Direction::Forward => key > cursor.0.as_slice(),
+            Direction::Reverse => key < cursor.0.as_slice(),
+        })
+    }
+}
+
+/// Collect the first `first` nodes into a connection page
+///
+/// `nodes` yields the matching (sort key, node) pairs which come after the
+/// `after` cursor, in order. Scanning stops with an error once the request's
+/// `deadline` passes
+pub fn paginate<T, I>(
+    deadline: Option<&Deadline>,
+    nodes: I,
+    after: Option<&SortKeyCursor>,
+    first: usize,
+    total_count: Option<u32>,
+) -> Result<SortKeyConnection<T>>
+where
+    T: OutputType,
+    I: Iterator<Item = anyhow::Result<(Vec<u8>, T)>>,
+{
+    let first = first.min(MAX_PAGE_SIZE);
+    let mut edges = Vec::with_capacity(first);
+    let mut has_next_page = false;
+
+    for node in nodes {
+        if let Some(deadline) = deadline {
+            deadline.check()?;
+        }
+
+        let (key, node) = node?;
+        if edges.len() >= first {
+            has_next_page = true;
+            break;
+        }
+
+        edges.push(Edge::new(SortKeyCursor(key), node));
+    }
+
+    let mut connection = Connection::with_additional_fields(
+        after.is_some(),
+        has_next_page,
+        ConnectionFields { total_count },
+    );
+    connection.edges = edges;
+    Ok(connection)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn cursor_round_trip() {
+        let key = [0u8, 0, 1, 44, 51, 78, 76];
+        let cursor = SortKeyCursor(key.to_vec());
+
+        let encoded = cursor.encode_cursor();
+        assert_eq!(SortKeyCursor::decode_cursor(&encoded).unwrap(), cursor);
+        assert!(SortKeyCursor::decode(Some("not a cursor!")).is_err());
+    }
+
+    #[test]
+    fn cursor_precedes() {
+        let cursor = SortKeyCursor(vec![0, 5]);
+
+        assert!(SortKeyCursor::precedes(None, &[0, 5], Direction::Forward));
+        assert!(SortKeyCursor::precedes(
+            Some(&cursor),
+            &[0, 6],
+            Direction::Forward
+        ));
+        assert!(!SortKeyCursor::precedes(
+            Some(&cursor),
+            &[0, 5],
+            Direction::Forward
+        ));
+        assert!(SortKeyCursor::precedes(
+            Some(&cursor),
+            &[0, 4],
+            Direction::Reverse
+        ));
+        assert!(!SortKeyCursor::precedes(
+            Some(&cursor),
+            &[0, 5],
+            Direction::Reverse
+        ));
+    }
+
+    #[test]
+    fn paginate_pages() {
+        let nodes = (0u32..5).map(|n| Ok((n.to_be_bytes().to_vec(), n)));
+        let page = paginate(None, nodes, None, 2, Some(5)).unwrap();
+
+        assert!(page.has_next_page);
+        assert!(!page.has_previous_page);
+        assert_eq!(page.edges.len(), 2);
+        assert_eq!(page.additional_fields.total_count, Some(5));
+
+        // resume after the last edge
+        let after = page.edges.last().map(|edge| edge.cursor.clone());
+        let nodes = (0u32..5)
+            .map(|n| (n.to_be_bytes().to_vec(), n))
+            .filter(|(key, _)| SortKeyCursor::precedes(after.as_ref(), key, Direction::Forward))
+            .map(Ok);
+        let page = paginate(None, nodes, after.as_ref(), 10, Some(5)).unwrap();
+
+        assert!(!page.has_next_page);
+        assert!(page.has_previous_page);
+        assert_eq!(
+            page.edges.iter().map(|edge| edge.node).collect::<Vec<_>>(),
+            vec![2, 3, 4]
+        );
+    }
+}
@@ -0,0 +1,194 @@
+use super::{db, validate_base58};
+use crate::{
+    base::{base58::Base58Kind, state_hash::StateHash},
+    block::store::BlockStore,
+    ledger::{
+        staking::payouts::{DelegatorPayout, EpochPayouts, PayoutBlock, PayoutConfig},
+        store::staking::StakingLedgerStore,
+    },
+    web::graphql::limits::deadline,
+};
+use async_graphql::{ComplexObject, Context, InputObject, Object, Result, SimpleObject};
+
+#[derive(Default)]
+pub struct PayoutsQueryRoot;
+
+#[derive(InputObject)]
+pub struct PayoutsQueryInput {
+    /// Block producer's public key
+    #[graphql(name = "public_key")]
+    public_key: String,
+
+    /// Staking epoch (default: current epoch)
+    epoch: Option<u32>,
+
+    #[graphql(name = "genesis_state_hash")]
+    genesis_state_hash: Option<String>,
+
+    /// Percentage of each delegator's share kept by the pool
+    #[graphql(name = "pool_fee", default = 0.0)]
+    pool_fee: f64,
+
+    #[graphql(name = "include_tx_fees", default = true)]
+    include_tx_fees: bool,
+
+    /// Only distribute supercharged coinbase bonuses to unlocked delegators
+    #[graphql(name = "supercharge_unlocked_only", default = true)]
+    supercharge_unlocked_only: bool,
+}
+
+#[derive(SimpleObject)]
+#[graphql(complex)]
+pub struct Payouts {
+    #[graphql(name = "public_key")]
+    public_key: String,
+
+    epoch: u32,
+
+    #[graphql(name = "ledger_hash")]
+    ledger_hash: String,
+
+    #[graphql(name = "total_stake")]
+    total_stake: u64,
+
+    #[graphql(name = "total_rewards")]
+    total_rewards: u64,
+
+    #[graphql(name = "total_pool_fees")]
+    total_pool_fees: u64,
+
+    blocks: Vec<PayoutsBlock>,
+
+    payouts: Vec<PayoutsDelegator>,
+
+    #[graphql(skip)]
+    inner: EpochPayouts,
+}
+
+#[derive(SimpleObject)]
+pub struct PayoutsBlock {
+    #[graphql(name = "state_hash")]
+    state_hash: String,
+
+    #[graphql(name = "block_height")]
+    block_height: u32,
+
+    #[graphql(name = "global_slot_since_genesis")]
+    global_slot_since_genesis: u32,
+
+    coinbase: u64,
+
+    supercharged: bool,
+
+    #[graphql(name = "tx_fees")]
+    tx_fees: u64,
+
+    #[graphql(name = "snark_fees")]
+    snark_fees: u64,
+}
+
+#[derive(SimpleObject)]
+pub struct PayoutsDelegator {
+    #[graphql(name = "public_key")]
+    public_key: String,
+
+    stake: u64,
+
+    /// Share of the block rewards before the pool fee
+    gross: u64,
+
+    #[graphql(name = "pool_fee")]
+    pool_fee: u64,
+
+    payout: u64,
+}
+
+#[Object]
+impl PayoutsQueryRoot {
+    /// Split the block producer's canonical epoch rewards among its delegators
+    async fn payouts<'ctx>(
+        &self,
+        ctx: &Context<'ctx>,
+        query: PayoutsQueryInput,
+    ) -> Result<Option<Payouts>> {
+        let db = db(ctx);
+        validate_base58(Base58Kind::PublicKey, &query.public_key)?;
+
+        let genesis_state_hash = match query.genesis_state_hash {
+            Some(hash) => {
+                validate_base58(Base58Kind::StateHash, &hash)?;
+                Some(StateHash::from(hash))
+            }
+            None => None,
+        };
+        let epoch = match query.epoch {
+            Some(epoch) => epoch,
+            None => db.get_current_epoch()?,
+        };
+        let config = PayoutConfig {
+            pool_fee: query.pool_fee,
+            include_tx_fees: query.include_tx_fees,
+            supercharge_unlocked_only: query.supercharge_unlocked_only,
+        };
+
+        Ok(db
+            .get_epoch_payouts(
+                &query.public_key.into(),
+                epoch,
+                genesis_state_hash.as_ref(),
+                config,
+                deadline(ctx),
+            )?
+            .map(Into::into))
+    }
+}
+
+#[ComplexObject]
+impl Payouts {
+    /// Delegator payouts as CSV
+    async fn csv(&self) -> Result<String> {
+        Ok(self.inner.to_csv()?)
+    }
+}
+
+impl From<EpochPayouts> for Payouts {
+    fn from(value: EpochPayouts) -> Self {
+        Self {
+            public_key: value.producer.0.clone(),
+            epoch: value.epoch,
+            ledger_hash: value.ledger_hash.0.clone(),
+            total_stake: value.total_stake,
+            total_rewards: value.total_rewards,
+            total_pool_fees: value.total_pool_fees,
+            blocks: value.blocks.iter().map(Into::into).collect(),
+            payouts: value.payouts.iter().map(Into::into).collect(),
+            inner: value,
+        }
+    }
+}
+
+impl From<&PayoutBlock> for PayoutsBlock {
+    fn from(value: &PayoutBlock) -> Self {
+        Self {
+            state_hash: value.state_hash.0.clone(),
+            block_height: value.blockchain_length,
+            global_slot_since_genesis: value.global_slot,
+            coinbase: value.coinbase,
+            supercharged: value.supercharged,
+            tx_fees: value.tx_fees,
+            snark_fees: value.snark_fees,
+        }
+    }
+}
+
+impl From<&DelegatorPayout> for PayoutsDelegator {
+    fn from(value: &DelegatorPayout) -> Self {
+        Self {
+            public_key: value.public_key.0.clone(),
+            stake: value.stake,
+            gross: value.gross,
+            pool_fee: value.pool_fee,
+            payout: value.payout,
+        }
+    }
+}
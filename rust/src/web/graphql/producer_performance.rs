@@ -0,0 +1,173 @@
+use super::{db, validate_base58};
+use crate::{
+    base::{base58::Base58Kind, state_hash::StateHash},
+    block::store::BlockStore,
+    ledger::{
+        staking::performance::{ProducerPerformance, ProducerPerformanceSort},
+        store::staking::StakingLedgerStore,
+    },
+    web::graphql::limits::deadline,
+};
+use async_graphql::{Context, Enum, InputObject, Object, Result, SimpleObject};
+
+#[derive(Default)]
+pub struct ProducerPerformanceQueryRoot;
+
+#[derive(InputObject)]
+pub struct ProducerPerformanceQueryInput {
+    /// Staking epoch (default: current epoch)
+    epoch: Option<u32>,
+
+    #[graphql(name = "public_key")]
+    public_key: Option<String>,
+
+    #[graphql(name = "genesis_state_hash")]
+    genesis_state_hash: Option<String>,
+}
+
+#[derive(Enum, Copy, Clone, Eq, PartialEq)]
+pub enum ProducerPerformanceSortByInput {
+    StakeDesc,
+    LuckAsc,
+    LuckDesc,
+    ReliabilityAsc,
+    ReliabilityDesc,
+    NumCanonicalBlocksProducedDesc,
+}
+
+#[derive(SimpleObject)]
+pub struct ProducerPerformanceReport {
+    #[graphql(name = "public_key")]
+    public_key: String,
+
+    epoch: u32,
+
+    /// Total stake delegated to the producer
+    stake: u64,
+
+    /// Staking ledger total currency
+    #[graphql(name = "total_currency")]
+    total_currency: u64,
+
+    #[graphql(name = "stake_share")]
+    stake_share: f64,
+
+    #[graphql(name = "num_blocks_produced")]
+    num_blocks_produced: u32,
+
+    #[graphql(name = "num_canonical_blocks_produced")]
+    num_canonical_blocks_produced: u32,
+
+    #[graphql(name = "num_orphaned_blocks")]
+    num_orphaned_blocks: u32,
+
+    #[graphql(name = "num_supercharged_blocks_produced")]
+    num_supercharged_blocks_produced: u32,
+
+    #[graphql(name = "num_slots_produced")]
+    num_slots_produced: u32,
+
+    /// Number of epoch slots filled by any producer
+    #[graphql(name = "epoch_slots_produced")]
+    epoch_slots_produced: u32,
+
+    /// Number of epoch slots which have passed
+    #[graphql(name = "epoch_slots_elapsed")]
+    epoch_slots_elapsed: u32,
+
+    #[graphql(name = "slot_fill_rate")]
+    slot_fill_rate: f64,
+
+    /// Expected number of canonical blocks given the stake share & slot fill
+    /// rate
+    #[graphql(name = "expected_blocks")]
+    expected_blocks: f64,
+
+    /// Canonical blocks produced per expected block
+    luck: Option<f64>,
+
+    /// Fraction of produced blocks which are canonical
+    reliability: Option<f64>,
+}
+
+#[Object]
+impl ProducerPerformanceQueryRoot {
+    /// Block producers' epoch block production relative to their stake
+    #[graphql(complexity = "limit * child_complexity")]
+    async fn producer_performance<'ctx>(
+        &self,
+        ctx: &Context<'ctx>,
+        query: Option<ProducerPerformanceQueryInput>,
+        sort_by: Option<ProducerPerformanceSortByInput>,
+        #[graphql(default = 100)] limit: usize,
+    ) -> Result<Vec<ProducerPerformanceReport>> {
+        use ProducerPerformanceSortByInput::*;
+        let db = db(ctx);
+
+        let epoch = match query.as_ref().and_then(|q| q.epoch) {
+            Some(epoch) => epoch,
+            None => db.get_current_epoch()?,
+        };
+        let genesis_state_hash = match query.as_ref().and_then(|q| q.genesis_state_hash.clone()) {
+            Some(hash) => {
+                validate_base58(Base58Kind::StateHash, &hash)?;
+                Some(StateHash::from(hash))
+            }
+            None => None,
+        };
+
+        // single producer
+        if let Some(pk) = query.as_ref().and_then(|q| q.public_key.clone()) {
+            validate_base58(Base58Kind::PublicKey, &pk)?;
+
+            return Ok(db
+                .get_producer_performance(&pk.into(), epoch, genesis_state_hash.as_ref())?
+                .into_iter()
+                .map(Into::into)
+                .collect());
+        }
+
+        let mut performances = db
+            .get_epoch_producer_performances(epoch, genesis_state_hash.as_ref(), deadline(ctx))?
+            .unwrap_or_default();
+        let sort = match sort_by.unwrap_or(StakeDesc) {
+            StakeDesc => ProducerPerformanceSort::Stake,
+            LuckAsc | LuckDesc => ProducerPerformanceSort::Luck,
+            ReliabilityAsc | ReliabilityDesc => ProducerPerformanceSort::Reliability,
+            NumCanonicalBlocksProducedDesc => ProducerPerformanceSort::CanonicalBlocks,
+        };
+        ProducerPerformance::sort(&mut performances, sort);
+        if matches!(sort_by, Some(LuckAsc | ReliabilityAsc)) {
+            performances.reverse();
+        }
+
+        Ok(performances
+            .into_iter()
+            .take(limit)
+            .map(Into::into)
+            .collect())
+    }
+}
+
+impl From<ProducerPerformance> for ProducerPerformanceReport {
+    fn from(value: ProducerPerformance) -> Self {
+        Self {
+            public_key: value.public_key.0,
+            epoch: value.epoch,
+            stake: value.stake,
+            total_currency: value.total_currency,
+            stake_share: value.stake_share,
+            num_blocks_produced: value.num_blocks_produced,
+            num_canonical_blocks_produced: value.num_canonical_blocks_produced,
+            num_orphaned_blocks: value.num_orphaned_blocks,
+            num_supercharged_blocks_produced: value.num_supercharged_blocks_produced,
+            num_slots_produced: value.num_slots_produced,
+            epoch_slots_produced: value.epoch_slots_produced,
+            epoch_slots_elapsed: value.epoch_slots_elapsed,
+            slot_fill_rate: value.slot_fill_rate,
+            expected_blocks: value.expected_blocks,
+            luck: value.luck,
+            reliability: value.reliability,
+        }
+    }
+}
@@ -0,0 +1,132 @@
+use super::db;
+use crate::{
+    base::state_hash::StateHash,
+    canonicity::reorg::{Reorg, ReorgStore},
+    command::signed::TxnHash,
+    constants::millis_to_iso_date_string,
+    web::graphql::limits::deadline,
+};
+use async_graphql::{
+    futures_util::{stream, Stream},
+    Context, Object, Result, SimpleObject, Subscription,
+};
+use log::warn;
+use tokio::sync::broadcast::error::RecvError;
+
+#[derive(Default)]
+pub struct ReorgsQueryRoot;
+
+#[derive(Default)]
+pub struct ReorgsSubscriptionRoot;
+
+#[derive(SimpleObject)]
+pub struct BestChainReorg {
+    #[graphql(name = "old_best_tip")]
+    old_best_tip: String,
+
+    #[graphql(name = "new_best_tip")]
+    new_best_tip: String,
+
+    #[graphql(name = "new_best_tip_height")]
+    new_best_tip_height: u32,
+
+    /// Last block shared by the old & new best chains
+    #[graphql(name = "common_ancestor")]
+    common_ancestor: String,
+
+    #[graphql(name = "common_ancestor_height")]
+    common_ancestor_height: u32,
+
+    /// Number of orphaned blocks
+    depth: u32,
+
+    /// Previously canonical blocks, in ascending height order
+    orphaned: Vec<String>,
+
+    /// Newly canonical blocks, in ascending height order
+    canonical: Vec<String>,
+
+    /// User commands which are no longer canonical
+    #[graphql(name = "orphaned_txn_hashes")]
+    orphaned_txn_hashes: Vec<String>,
+
+    /// User commands which became canonical
+    #[graphql(name = "canonical_txn_hashes")]
+    canonical_txn_hashes: Vec<String>,
+
+    /// Value date_time as ISO 8601 string
+    #[graphql(name = "date_time")]
+    date_time: String,
+}
+
+#[Object]
+impl ReorgsQueryRoot {
+    /// Best chain reorgs orphaning at least `minDepth` blocks whose new best
+    /// tip height is at least `since`, most recent first
+    #[graphql(complexity = "limit * child_complexity")]
+    async fn reorgs<'ctx>(
+        &self,
+        ctx: &Context<'ctx>,
+        #[graphql(default = 1)] min_depth: u32,
+        #[graphql(default = 0)] since: u32,
+        #[graphql(default = 100)] limit: usize,
+    ) -> Result<Vec<BestChainReorg>> {
+        Ok(db(ctx)
+            .get_reorgs(min_depth, since, limit, deadline(ctx))?
+            .into_iter()
+            .map(Into::into)
+            .collect())
+    }
+}
+
+#[Subscription]
+impl ReorgsSubscriptionRoot {
+    /// Best chain reorgs orphaning at least `minDepth` blocks, as they
+    /// happen
+    async fn reorgs<'ctx>(
+        &self,
+        ctx: &Context<'ctx>,
+        #[graphql(default = 1)] min_depth: u32,
+    ) -> Result<impl Stream<Item = BestChainReorg>> {
+        let reorgs = db(ctx).subscribe_reorgs();
+
+        Ok(stream::unfold(reorgs, move |mut reorgs| async move {
+            loop {
+                match reorgs.recv().await {
+                    Ok(reorg) if reorg.depth >= min_depth => {
+                        return Some((BestChainReorg::from(reorg), reorgs))
+                    }
+                    Ok(_) => continue,
+                    Err(RecvError::Lagged(num)) => warn!("Reorg subscriber skipped {num} reorgs"),
+                    Err(RecvError::Closed) => return None,
+                }
+            }
+        }))
+    }
+}
+
+impl From<Reorg> for BestChainReorg {
+    fn from(value: Reorg) -> Self {
+        let hashes = |hashes: Vec<StateHash>| hashes.into_iter().map(|hash| hash.0).collect();
+        let txn_hashes = |hashes: Vec<TxnHash>| {
+            hashes
+                .into_iter()
+                .map(|hash| hash.ref_inner().to_owned())
+                .collect()
+        };
+
+        Self {
+            old_best_tip: value.old_best_tip.0,
+            new_best_tip: value.new_best_tip.0,
+            new_best_tip_height: value.new_best_tip_height,
+            common_ancestor: value.common_ancestor.0,
+            common_ancestor_height: value.common_ancestor_height,
+            depth: value.depth,
+            orphaned: hashes(value.orphaned),
+            canonical: hashes(value.canonical),
+            orphaned_txn_hashes: txn_hashes(value.orphaned_txn_hashes),
+            canonical_txn_hashes: txn_hashes(value.canonical_txn_hashes),
+            date_time: millis_to_iso_date_string(value.date_time),
+        }
+    }
+}
@@ -1,13 +1,24 @@
-use super::{db, gen::BlockQueryInput, get_block, get_block_canonicity};
+use super::{
+    db,
+    gen::BlockQueryInput,
+    get_block, get_block_canonicity,
+    limits::{check_deadline, deadline},
+    pagination::{paginate, SortKeyConnection, SortKeyCursor},
+    validate_optional_base58,
+};
 use crate::{
-    base::{public_key::PublicKey, state_hash::StateHash},
+    base::{base58::Base58Kind, public_key::PublicKey, state_hash::StateHash},
     block::{precomputed::PrecomputedBlock, store::BlockStore},
     constants::*,
     snark_work::{store::SnarkStore, SnarkWorkSummary, SnarkWorkSummaryWithStateHash},
     store::IndexerStore,
-    utility::store::common::{from_be_bytes, state_hash_suffix, U32_LEN},
+    utility::{
+        deadline::check_scan,
+        store::common::{from_be_bytes, state_hash_suffix, U32_LEN},
+    },
 };
 use async_graphql::{ComplexObject, Context, Enum, InputObject, Object, Result, SimpleObject};
+use speedb::Direction;
 use std::sync::Arc;
 
 #[derive(SimpleObject, Debug)]
@@ -94,6 +105,7 @@ pub struct SnarkQueryRoot;
 #[Object]
 impl SnarkQueryRoot {
     #[allow(clippy::too_many_lines)]
+    #[graphql(complexity = "limit * child_complexity")]
     async fn snarks<'ctx>(
         &self,
         ctx: &Context<'ctx>,
@@ -102,6 +114,9 @@ impl SnarkQueryRoot {
         #[graphql(default = 100)] limit: usize,
     ) -> Result<Vec<SnarkWithCanonicity>> {
         let db = db(ctx);
+        if let Some(query) = query.as_ref() {
+            query.validate()?;
+        }
         let mut snarks = <Vec<SnarkWithCanonicity>>::new();
         let sort_by = sort_by.unwrap_or(SnarkSortByInput::BlockHeightDesc);
 
@@ -180,6 +195,7 @@ impl SnarkQueryRoot {
 
             // key should be typed
             'outer: for (key, snark) in db.snark_prover_block_height_iterator(mode).flatten() {
+                check_deadline(ctx)?;
                 // exit if prover isn't the same
                 if key[..PublicKey::LEN] != *prover.as_bytes() {
                     break;
@@ -236,6 +252,7 @@ impl SnarkQueryRoot {
             };
 
             'outer: for (key, snark) in db.snark_prover_block_height_iterator(mode).flatten() {
+                check_deadline(ctx)?;
                 if key[..PublicKey::LEN] != *prover.as_bytes() {
                     break;
                 }
@@ -361,6 +378,7 @@ impl SnarkQueryRoot {
         };
 
         'outer: for (key, _) in db.blocks_height_iterator(mode).flatten() {
+            check_deadline(ctx)?;
             let state_hash = state_hash_suffix(&key)?;
 
             // avoid deserializing PCB if possible
@@ -401,6 +419,134 @@ impl SnarkQueryRoot {
         }
         Ok(snarks)
     }
+
+    /// Cursor paginated SNARKs
+    ///
+    /// `totalCount` is only provided for unfiltered queries
+    #[graphql(complexity = "first * child_complexity")]
+    async fn snarks_connection<'ctx>(
+        &self,
+        ctx: &Context<'ctx>,
+        query: Option<SnarkQueryInput>,
+        sort_by: Option<SnarkSortByInput>,
+        #[graphql(default = 100)] first: usize,
+        after: Option<String>,
+    ) -> Result<SortKeyConnection<SnarkWithCanonicity>> {
+        let db = db(ctx);
+        if let Some(query) = query.as_ref() {
+            query.validate()?;
+        }
+        let after = SortKeyCursor::decode(after.as_deref())?;
+        let epoch_num_snarks = db.get_snarks_epoch_count(None)?;
+        let total_num_snarks = db.get_snarks_total_count()?;
+
+        let (start, direction) = match sort_by.unwrap_or(SnarkSortByInput::BlockHeightDesc) {
+            SnarkSortByInput::BlockHeightAsc => (0u32, Direction::Forward),
+            SnarkSortByInput::BlockHeightDesc => (u32::MAX, Direction::Reverse),
+        };
+
+        // prover keys are prefixed by the public key & a block may contain many
+        // SNARKs, so cursors extend the sort key with the SNARK's state hash or
+        // index in the block
+        let prover = query.as_ref().and_then(|q| q.prover.clone());
+        let (start, key_len) = match prover.as_ref() {
+            Some(prover) => (
+                [
+                    prover.as_bytes(),
+                    &start.to_be_bytes(),
+                    &start.to_be_bytes(),
+                ]
+                .concat(),
+                PublicKey::LEN + U32_LEN + U32_LEN,
+            ),
+            None => (start.to_be_bytes().to_vec(), U32_LEN + StateHash::LEN),
+        };
+        let seek = after
+            .as_ref()
+            .map(|after| SortKeyCursor(after.0[..key_len.min(after.0.len())].to_vec()));
+        let mode = SortKeyCursor::iterator_mode(seek.as_ref(), &start, direction);
+        let iter = match prover {
+            Some(_) => db.snark_prover_block_height_iterator(mode),
+            None => db.blocks_height_iterator(mode),
+        };
+
+        let snark_with_canonicity = |snark, state_hash: &StateHash, canonical| {
+            let sw = SnarkWithCanonicity {
+                canonical,
+                pcb: get_block(db, state_hash),
+                snark: (
+                    snark,
+                    state_hash.clone(),
+                    epoch_num_snarks,
+                    total_num_snarks,
+                )
+                    .into(),
+            };
+            query
+                .as_ref()
+                .map_or(true, |q| q.matches(&sw))
+                .then_some(sw)
+        };
+        let canonicity_matches = |state_hash: &StateHash| {
+            let canonical = get_block_canonicity(db, state_hash);
+            query
+                .as_ref()
+                .and_then(|q| q.canonical)
+                .map_or(true, |query_canonicity| canonical == query_canonicity)
+                .then_some(canonical)
+        };
+
+        let deadline = deadline(ctx);
+        let nodes = iter
+            .flatten()
+            .take_while(|(key, _)| {
+                prover
+                    .as_ref()
+                    .map_or(true, |pk| key[..PublicKey::LEN] == *pk.as_bytes())
+            })
+            .map(|(key, value)| {
+                check_scan(deadline)?;
+
+                let mut nodes = vec![];
+                if prover.is_some() {
+                    let block_height = from_be_bytes(key[PublicKey::LEN..][..U32_LEN].to_vec());
+                    for state_hash in db.get_blocks_at_height(block_height)? {
+                        if let Some(canonical) = canonicity_matches(&state_hash) {
+                            let snark: SnarkWorkSummary = serde_json::from_slice(&value)?;
+                            if let Some(sw) = snark_with_canonicity(snark, &state_hash, canonical) {
+                                nodes.push(([&key, state_hash.0.as_bytes()].concat(), sw));
+                            }
+                        }
+                    }
+                } else {
+                    let state_hash = state_hash_suffix(&key)?;
+                    if let Some(canonical) = canonicity_matches(&state_hash) {
+                        let snark_work = db.get_block_snark_work(&state_hash)?;
+                        for (index, snark) in snark_work.unwrap_or_default().into_iter().enumerate()
+                        {
+                            if let Some(sw) = snark_with_canonicity(snark, &state_hash, canonical) {
+                                nodes
+                                    .push(([&key, &(index as u32).to_be_bytes()[..]].concat(), sw));
+                            }
+                        }
+                    }
+                }
+
+                nodes.sort_by(|(a, _), (b, _)| a.cmp(b));
+                if let Direction::Reverse = direction {
+                    nodes.reverse();
+                }
+                nodes.retain(|(key, _)| SortKeyCursor::precedes(after.as_ref(), key, direction));
+                Ok(nodes)
+            })
+            .flat_map(|nodes: anyhow::Result<Vec<_>>| match nodes {
+                Ok(nodes) => nodes.into_iter().map(Ok).collect(),
+                Err(e) => vec![Err(e)],
+            });
+
+        let total_count = query.is_none().then_some(total_num_snarks);
+        paginate(deadline, nodes, after.as_ref(), first, total_count)
+    }
 }
 
 fn snark_summary_matches_query(
@@ -459,6 +605,19 @@ impl From<(SnarkWorkSummaryWithStateHash, u32, u32)> for Snark {
 }
 
 impl SnarkQueryInput {
+    /// Checks the base58 inputs are of the expected kinds
+    fn validate(&self) -> Result<()> {
+        validate_optional_base58(Base58Kind::PublicKey, self.prover.as_ref())?;
+        if let Some(block) = self.block.as_ref() {
+            block.validate()?;
+        }
+
+        for query in self.and.iter().chain(self.or.iter()).flatten() {
+            query.validate()?;
+        }
+        Ok(())
+    }
+
     pub fn matches(&self, snark: &SnarkWithCanonicity) -> bool {
         let Self {
             block,
@@ -1,5 +1,6 @@
-use super::{db, MAINNET_ACCOUNT_CREATION_FEE};
+use super::{db, validate_optional_base58, MAINNET_ACCOUNT_CREATION_FEE};
 use crate::{
+    base::base58::Base58Kind,
     canonicity::store::CanonicityStore,
     ledger::{account::Account, store::staged::StagedLedgerStore, token::TokenAddress},
 };
@@ -17,6 +18,16 @@ pub struct StagedLedgerQueryInput {
     blockchain_length: Option<u32>,
 }
 
+impl StagedLedgerQueryInput {
+    /// Checks the base58 inputs are of the expected kinds
+    fn validate(&self) -> Result<()> {
+        validate_optional_base58(Base58Kind::LedgerHash, self.ledger_hash.as_ref())?;
+        validate_optional_base58(Base58Kind::StateHash, self.state_hash.as_ref())?;
+        validate_optional_base58(Base58Kind::PublicKey, self.public_key.as_ref())?;
+        validate_optional_base58(Base58Kind::TokenId, self.token.as_ref())
+    }
+}
+
 #[derive(Enum, Copy, Clone, Eq, PartialEq)]
 pub enum StagedLedgerSortByInput {
     #[graphql(name = "BALANCE_ASC")]
@@ -32,7 +43,7 @@ pub struct StagedLedgerQueryRoot;
 #[Object]
 impl StagedLedgerQueryRoot {
     // Cache for 1 hour
-    #[graphql(cache_control(max_age = 3600))]
+    #[graphql(cache_control(max_age = 3600), complexity = "limit * child_complexity")]
     async fn staged_ledger_accounts<'ctx>(
         &self,
         ctx: &Context<'ctx>,
@@ -41,6 +52,9 @@ impl StagedLedgerQueryRoot {
         #[graphql(default = 100)] limit: usize,
     ) -> Result<Option<Vec<StagedLedgerAccount>>> {
         let db = db(ctx);
+        if let Some(query) = query.as_ref() {
+            query.validate()?;
+        }
         let token = query
             .as_ref()
             .map_or(TokenAddress::default(), |q| match q.token.to_owned() {
@@ -94,8 +108,8 @@ impl StagedLedgerQueryRoot {
                 .map(|token_ledger| {
                     token_ledger
                         .accounts
-                        .to_owned()
-                        .into_values()
+                        .values()
+                        .cloned()
                         .map(StagedLedgerAccount::from)
                         .collect()
                 })
@@ -1,17 +1,21 @@
-use super::db;
+use super::{db, validate_optional_base58};
 use crate::{
+    base::{base58::Base58Kind, state_hash::StateHash},
     block::store::BlockStore,
     chain::store::ChainStore,
     command::{internal::store::InternalCommandStore, store::UserCommandStore},
-    constants::MAINNET_GENESIS_HASH,
     ledger::{
         staking::{EpochStakeDelegation, StakingAccount},
         store::staking::{StakingAccountWithEpochDelegation, StakingLedgerStore},
     },
     snark_work::store::SnarkStore,
     store::{username::UsernameStore, IndexerStore},
-    utility::store::common::U32_LEN,
-    web::graphql::Timing,
+    utility::{deadline::check_scan, store::common::U32_LEN},
+    web::graphql::{
+        limits::{check_deadline, deadline},
+        pagination::{paginate, SortKeyConnection, SortKeyCursor},
+        Timing,
+    },
 };
 use async_graphql::{ComplexObject, Context, Enum, InputObject, Object, Result, SimpleObject};
 use rust_decimal::{prelude::ToPrimitive, Decimal};
@@ -51,7 +55,10 @@ pub struct StakeQueryRoot;
 #[Object]
 impl StakeQueryRoot {
     // Cache for 1 day
-    #[graphql(cache_control(max_age = 86400))]
+    #[graphql(
+        cache_control(max_age = 86400),
+        complexity = "limit * child_complexity"
+    )]
     async fn stakes<'ctx>(
         &self,
         ctx: &Context<'ctx>,
@@ -60,6 +67,9 @@ impl StakeQueryRoot {
         #[graphql(default = 100)] limit: usize,
     ) -> Result<Vec<StakesLedgerAccountWithMeta>> {
         let db = db(ctx);
+        if let Some(query) = query.as_ref() {
+            query.validate()?;
+        }
 
         // default to current epoch
         let curr_epoch = db.get_current_epoch()?;
@@ -82,28 +92,7 @@ impl StakeQueryRoot {
             }
         }
 
-        // if ledger hash is provided as a query input, use it for the ledger
-        // otherwise, use the provided or current epoch number
-        let (ledger_hash, epoch) = match query.as_ref().map(|q| (q.ledger_hash.clone(), q.epoch)) {
-            Some((Some(ledger_hash), Some(query_epoch))) => (ledger_hash, query_epoch),
-            Some((Some(ledger_hash), None)) => (
-                ledger_hash.clone(),
-                db.get_epoch(&ledger_hash.clone().into())?
-                    .unwrap_or_default(),
-            ),
-            Some((None, Some(query_epoch))) => (
-                db.get_staking_ledger_hash_by_epoch(query_epoch, None)?
-                    .unwrap_or_default()
-                    .0,
-                query_epoch,
-            ),
-            Some((None, None)) | None => (
-                db.get_staking_ledger_hash_by_epoch(epoch, None)?
-                    .unwrap_or_default()
-                    .0,
-                epoch,
-            ),
-        };
+        let (ledger_hash, epoch) = staking_ledger_hash_and_epoch(db, query.as_ref(), epoch)?;
         let total_currency = db
             .get_total_currency(&ledger_hash.clone().into())?
             .unwrap_or_default();
@@ -126,6 +115,7 @@ impl StakeQueryRoot {
         };
 
         for (key, value) in iter.flatten() {
+            check_deadline(ctx)?;
             if key[..U32_LEN] != epoch.to_be_bytes() || accounts.len() >= limit {
                 // no longer the desired staking ledger
                 break;
@@ -156,6 +146,151 @@ impl StakeQueryRoot {
         }
         Ok(accounts)
     }
+
+    /// Cursor paginated staking ledger accounts
+    ///
+    /// `totalCount` is only provided for queries which select the staking
+    /// ledger by epoch or ledger hash alone
+    #[graphql(
+        cache_control(max_age = 86400),
+        complexity = "first * child_complexity"
+    )]
+    async fn stakes_connection<'ctx>(
+        &self,
+        ctx: &Context<'ctx>,
+        query: Option<StakeQueryInput>,
+        sort_by: Option<StakeSortByInput>,
+        #[graphql(default = 100)] first: usize,
+        after: Option<String>,
+    ) -> Result<SortKeyConnection<StakesLedgerAccountWithMeta>> {
+        let db = db(ctx);
+        if let Some(query) = query.as_ref() {
+            query.validate()?;
+        }
+        let after = SortKeyCursor::decode(after.as_deref())?;
+
+        // default to current epoch
+        let epoch = match query.as_ref().and_then(|q| q.epoch) {
+            Some(epoch) => epoch,
+            None => db.get_current_epoch()?,
+        };
+        let (ledger_hash, epoch) = staking_ledger_hash_and_epoch(db, query.as_ref(), epoch)?;
+        let total_currency = db
+            .get_total_currency(&ledger_hash.clone().into())?
+            .unwrap_or_default();
+
+        let (iter, direction) = match sort_by {
+            Some(StakeSortByInput::StakeDesc) | None => (
+                db.staking_ledger_account_stake_iterator(epoch, Direction::Reverse),
+                Direction::Reverse,
+            ),
+            Some(StakeSortByInput::StakeAsc) => (
+                db.staking_ledger_account_stake_iterator(epoch, Direction::Forward),
+                Direction::Forward,
+            ),
+            Some(StakeSortByInput::BalanceDesc) => (
+                db.staking_ledger_account_balance_iterator(epoch, Direction::Reverse),
+                Direction::Reverse,
+            ),
+            Some(StakeSortByInput::BalanceAsc) => (
+                db.staking_ledger_account_balance_iterator(epoch, Direction::Forward),
+                Direction::Forward,
+            ),
+        };
+
+        let deadline = deadline(ctx);
+        let nodes = iter
+            .flatten()
+            .take_while(|(key, _)| key[..U32_LEN] == epoch.to_be_bytes())
+            .filter(|(key, _)| SortKeyCursor::precedes(after.as_ref(), key, direction))
+            .map(|(key, value)| {
+                check_scan(deadline)?;
+
+                let StakingAccountWithEpochDelegation {
+                    account,
+                    delegation,
+                } = serde_json::from_slice(&value)?;
+                if !StakeQueryInput::matches_staking_account(
+                    query.as_ref(),
+                    &account,
+                    &ledger_hash,
+                    epoch,
+                ) {
+                    return Ok(None);
+                }
+
+                let account = StakesLedgerAccountWithMeta::new(
+                    db,
+                    account,
+                    &delegation,
+                    epoch,
+                    ledger_hash.clone(),
+                    total_currency,
+                );
+                Ok(StakeQueryInput::matches(query.as_ref(), &account)
+                    .then(|| (key.to_vec(), account)))
+            })
+            .filter_map(anyhow::Result::transpose);
+
+        let total_count = match query.as_ref() {
+            Some(StakeQueryInput {
+                delegate: None,
+                stake_lte: None,
+                public_key: None,
+                username: None,
+                ..
+            })
+            | None => match staking_ledger_genesis_hash(db, &ledger_hash)? {
+                Some(genesis_state_hash) => {
+                    Some(db.get_staking_ledger_accounts_count_epoch(epoch, &genesis_state_hash)?)
+                }
+                None => None,
+            },
+            Some(_) => None,
+        };
+        paginate(deadline, nodes, after.as_ref(), first, total_count)
+    }
+}
+
+/// If ledger hash is provided as a query input, use it for the ledger
+/// otherwise, use the provided or current epoch number
+fn staking_ledger_hash_and_epoch(
+    db: &Arc<IndexerStore>,
+    query: Option<&StakeQueryInput>,
+    epoch: u32,
+) -> Result<(String, u32)> {
+    Ok(match query.map(|q| (q.ledger_hash.clone(), q.epoch)) {
+        Some((Some(ledger_hash), Some(query_epoch))) => (ledger_hash, query_epoch),
+        Some((Some(ledger_hash), None)) => (
+            ledger_hash.clone(),
+            db.get_epoch(&ledger_hash.clone().into())?
+                .unwrap_or_default(),
+        ),
+        Some((None, Some(query_epoch))) => (
+            db.get_staking_ledger_hash_by_epoch(query_epoch, None)?
+                .unwrap_or_default()
+                .0,
+            query_epoch,
+        ),
+        Some((None, None)) | None => (
+            db.get_staking_ledger_hash_by_epoch(epoch, None)?
+                .unwrap_or_default()
+                .0,
+            epoch,
+        ),
+    })
+}
+
+/// Genesis state hash of the staking ledger's chain, defaulting to the best
+/// block's chain if the ledger is unknown
+fn staking_ledger_genesis_hash(
+    db: &Arc<IndexerStore>,
+    ledger_hash: &str,
+) -> anyhow::Result<Option<StateHash>> {
+    match db.get_genesis_state_hash(&ledger_hash.to_owned().into())? {
+        Some(genesis_state_hash) => Ok(Some(genesis_state_hash)),
+        None => db.get_best_block_genesis_hash(),
+    }
 }
 
 #[derive(SimpleObject, Default)]
@@ -166,6 +301,10 @@ pub struct StakesLedgerAccountWithMeta {
     /// Value current ledger hash
     ledger_hash: String,
 
+    /// Value ledger source, imported or derived
+    #[graphql(name = "ledger_source")]
+    ledger_source: String,
+
     /// Value delegation totals
     delegation_totals: StakesDelegationTotals,
 
@@ -408,6 +547,13 @@ impl
 }
 
 impl StakeQueryInput {
+    /// Checks the base58 inputs are of the expected kinds
+    fn validate(&self) -> Result<()> {
+        validate_optional_base58(Base58Kind::PublicKey, self.public_key.as_ref())?;
+        validate_optional_base58(Base58Kind::PublicKey, self.delegate.as_ref())?;
+        validate_optional_base58(Base58Kind::LedgerHash, self.ledger_hash.as_ref())
+    }
+
     pub fn matches(
         query: Option<&Self>,
         stakes_ledger_account: &StakesLedgerAccountWithMeta,
@@ -500,6 +646,17 @@ impl StakesLedgerAccountWithMeta {
             vesting_period: Some(timing.vesting_period.0),
         });
         let chain_id = db.get_chain_id().expect("chain id").0;
+        let ledger_source = db
+            .get_staking_ledger_source(&ledger_hash.clone().into())
+            .expect("staking ledger source")
+            .unwrap_or_default()
+            .to_string();
+        let epoch_num_accounts = staking_ledger_genesis_hash(db, &ledger_hash)
+            .expect("staking ledger genesis state hash")
+            .map_or(0, |genesis_state_hash| {
+                db.get_staking_ledger_accounts_count_epoch(epoch, &genesis_state_hash)
+                    .expect("epoch staking ledger accounts count")
+            });
 
         // pk data counts
         let pk_epoch_num_blocks = db
@@ -540,6 +697,7 @@ impl StakesLedgerAccountWithMeta {
         Self {
             epoch,
             ledger_hash,
+            ledger_source,
             account: StakesLedgerAccount::from((
                 account,
                 chain_id,
@@ -591,9 +749,7 @@ impl StakesLedgerAccountWithMeta {
             total_num_internal_commands: db
                 .get_internal_commands_total_count()
                 .expect("total internal command count"),
-            epoch_num_accounts: db
-                .get_staking_ledger_accounts_count_epoch(epoch, &MAINNET_GENESIS_HASH.into())
-                .expect("total internal command count"),
+            epoch_num_accounts,
         }
     }
 }
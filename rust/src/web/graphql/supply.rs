@@ -0,0 +1,110 @@
+use super::db;
+use crate::{
+    block::store::BlockStore,
+    constants::millis_to_iso_date_string,
+    ledger::store::supply::{BlockSupply, SupplyStore},
+    web::graphql::limits::deadline,
+};
+use async_graphql::{Context, InputObject, Object, Result, SimpleObject};
+
+#[derive(Default)]
+pub struct SupplyQueryRoot;
+
+#[derive(InputObject)]
+pub struct SupplyQueryInput {
+    /// Inclusive lower global slot bound (default: 0)
+    #[graphql(name = "global_slot_gte")]
+    global_slot_gte: Option<u32>,
+
+    /// Inclusive upper global slot bound (default: best tip)
+    #[graphql(name = "global_slot_lte")]
+    global_slot_lte: Option<u32>,
+}
+
+#[derive(SimpleObject)]
+pub struct Supply {
+    #[graphql(name = "state_hash")]
+    state_hash: String,
+
+    #[graphql(name = "block_height")]
+    block_height: u32,
+
+    #[graphql(name = "global_slot_since_genesis")]
+    global_slot_since_genesis: u32,
+
+    /// Value date_time as ISO 8601 string
+    #[graphql(name = "date_time")]
+    date_time: String,
+
+    #[graphql(name = "total_currency")]
+    total_currency: u64,
+
+    /// Sum of the timed accounts' minimum balances, excluding off-chain time
+    /// locks
+    locked: u64,
+
+    /// Total currency minus the locked supply
+    circulating: u64,
+
+    /// Balance delegated to another account in the block's staking ledger
+    staked: Option<u64>,
+}
+
+#[Object]
+impl SupplyQueryRoot {
+    /// Supply time series of canonical blocks
+    #[graphql(complexity = "limit * child_complexity")]
+    async fn supply<'ctx>(
+        &self,
+        ctx: &Context<'ctx>,
+        query: Option<SupplyQueryInput>,
+        #[graphql(default = 100)] limit: usize,
+    ) -> Result<Vec<Supply>> {
+        let db = db(ctx);
+        let start_slot = query
+            .as_ref()
+            .and_then(|q| q.global_slot_gte)
+            .unwrap_or_default();
+        let end_slot = match query.as_ref().and_then(|q| q.global_slot_lte) {
+            Some(end_slot) => end_slot,
+            None => db.get_best_block_global_slot()?.unwrap_or_default(),
+        };
+
+        Ok(db
+            .get_supply_time_series(start_slot, end_slot, limit, deadline(ctx))?
+            .into_iter()
+            .map(Into::into)
+            .collect())
+    }
+
+    /// Locked supply of the best ledger's timed accounts at the global slot
+    /// (default: best tip)
+    #[graphql(name = "lockedSupply")]
+    async fn locked_supply<'ctx>(
+        &self,
+        ctx: &Context<'ctx>,
+        global_slot: Option<u32>,
+    ) -> Result<u64> {
+        let db = db(ctx);
+        let global_slot = match global_slot {
+            Some(global_slot) => global_slot,
+            None => db.get_best_block_global_slot()?.unwrap_or_default(),
+        };
+        Ok(db.get_locked_supply(global_slot, deadline(ctx))?)
+    }
+}
+
+impl From<BlockSupply> for Supply {
+    fn from(value: BlockSupply) -> Self {
+        Self {
+            state_hash: value.state_hash.0,
+            block_height: value.blockchain_length,
+            global_slot_since_genesis: value.global_slot_since_genesis,
+            date_time: millis_to_iso_date_string(value.date_time),
+            total_currency: value.total_currency,
+            locked: value.locked,
+            circulating: value.circulating,
+            staked: value.staked,
+        }
+    }
+}
@@ -3,11 +3,16 @@ use crate::{
     base::public_key::PublicKey,
     block::store::BlockStore,
     snark_work::store::SnarkStore,
-    store::username::UsernameStore,
+    store::{username::UsernameStore, IndexerStore},
     utility::store::common::{u32_from_be_bytes, U32_LEN, U64_LEN},
+    web::graphql::{
+        limits::{check_deadline, deadline},
+        pagination::{paginate, SortKeyConnection, SortKeyCursor},
+    },
 };
 use async_graphql::{Context, Enum, InputObject, Object, Result, SimpleObject};
 use speedb::Direction;
+use std::sync::Arc;
 
 #[derive(InputObject)]
 pub struct TopSnarkersQueryInput {
@@ -48,6 +53,7 @@ pub struct TopSnarker {
 
 #[Object]
 impl TopSnarkersQueryRoot {
+    #[graphql(complexity = "limit * child_complexity")]
     async fn top_snarkers<'ctx>(
         &self,
         ctx: &Context<'ctx>,
@@ -73,6 +79,7 @@ impl TopSnarkersQueryRoot {
         let mut snarkers = vec![];
 
         for (key, _) in iter.flatten() {
+            check_deadline(ctx)?;
             let key_epoch = u32_from_be_bytes(&key[..U32_LEN])?;
             if key_epoch != epoch {
                 // we've gone beyond the desired epoch
@@ -80,30 +87,7 @@ impl TopSnarkersQueryRoot {
             }
 
             let pk = PublicKey::from_bytes(&key[U32_LEN..][U64_LEN..])?;
-            let username = db
-                .get_username(&pk)
-                .ok()
-                .flatten()
-                .map_or("Unknown".to_string(), |u| u.0);
-            let total_fees = db
-                .get_snark_prover_epoch_fees(&pk, Some(epoch), None)?
-                .expect("total fees");
-            let min_fee = db
-                .get_snark_prover_epoch_min_fee(&pk, Some(epoch), None)?
-                .expect("min fee");
-            let max_fee = db
-                .get_snark_prover_epoch_max_fee(&pk, Some(epoch), None)?
-                .expect("max fee");
-            let snarks_sold = db.get_snarks_pk_epoch_count(&pk, Some(epoch))?;
-
-            snarkers.push(TopSnarker {
-                username,
-                public_key: pk.0,
-                total_fees,
-                min_fee,
-                max_fee,
-                snarks_sold,
-            });
+            snarkers.push(TopSnarker::new(db, pk, epoch)?);
 
             if snarkers.len() >= limit {
                 break;
@@ -111,4 +95,74 @@ impl TopSnarkersQueryRoot {
         }
         Ok(snarkers)
     }
+
+    /// Cursor paginated top SNARK provers
+    ///
+    /// `totalCount` is not provided
+    #[graphql(complexity = "first * child_complexity")]
+    async fn top_snarkers_connection<'ctx>(
+        &self,
+        ctx: &Context<'ctx>,
+        query: Option<TopSnarkersQueryInput>,
+        sort_by: Option<TopSnarkersSortByInput>,
+        #[graphql(default = 100)] first: usize,
+        after: Option<String>,
+    ) -> Result<SortKeyConnection<TopSnarker>> {
+        use TopSnarkersSortByInput::*;
+        let db = db(ctx);
+        let after = SortKeyCursor::decode(after.as_deref())?;
+        let epoch = match query {
+            Some(query) => query.epoch,
+            None => db.get_current_epoch()?,
+        };
+        let direction = match sort_by.unwrap_or_default() {
+            MaxFeeAsc | TotalFeesAsc => Direction::Forward,
+            MaxFeeDesc | TotalFeesDesc => Direction::Reverse,
+        };
+        let iter = match sort_by.unwrap_or_default() {
+            MaxFeeAsc | MaxFeeDesc => db.snark_prover_max_fee_epoch_iterator(epoch, direction),
+            TotalFeesAsc | TotalFeesDesc => {
+                db.snark_prover_total_fees_epoch_iterator(epoch, direction)
+            }
+        };
+
+        let nodes = iter
+            .flatten()
+            .take_while(|(key, _)| key[..U32_LEN] == epoch.to_be_bytes())
+            .filter(|(key, _)| SortKeyCursor::precedes(after.as_ref(), key, direction))
+            .map(|(key, _)| {
+                let pk = PublicKey::from_bytes(&key[U32_LEN..][U64_LEN..])?;
+                Ok((key.to_vec(), TopSnarker::new(db, pk, epoch)?))
+            });
+        paginate(deadline(ctx), nodes, after.as_ref(), first, None)
+    }
+}
+
+impl TopSnarker {
+    fn new(db: &Arc<IndexerStore>, pk: PublicKey, epoch: u32) -> anyhow::Result<Self> {
+        let username = db
+            .get_username(&pk)
+            .ok()
+            .flatten()
+            .map_or("Unknown".to_string(), |u| u.0);
+        let total_fees = db
+            .get_snark_prover_epoch_fees(&pk, Some(epoch), None)?
+            .expect("total fees");
+        let min_fee = db
+            .get_snark_prover_epoch_min_fee(&pk, Some(epoch), None)?
+            .expect("min fee");
+        let max_fee = db
+            .get_snark_prover_epoch_max_fee(&pk, Some(epoch), None)?
+            .expect("max fee");
+        let snarks_sold = db.get_snarks_pk_epoch_count(&pk, Some(epoch))?;
+
+        Ok(Self {
+            username,
+            public_key: pk.0,
+            total_fees,
+            min_fee,
+            max_fee,
+            snarks_sold,
+        })
+    }
 }
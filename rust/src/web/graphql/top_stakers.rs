@@ -3,12 +3,17 @@ use crate::{
     base::public_key::PublicKey,
     block::store::BlockStore,
     ledger::{account, store::best::BestLedgerStore, token::TokenAddress},
-    store::username::UsernameStore,
+    store::{username::UsernameStore, IndexerStore},
     utility::store::common::{from_be_bytes, U32_LEN},
+    web::graphql::{
+        limits::{check_deadline, deadline},
+        pagination::{paginate, SortKeyConnection, SortKeyCursor},
+    },
 };
 use anyhow::Context as aContext;
 use async_graphql::{Context, Enum, InputObject, Object, Result, SimpleObject};
 use speedb::Direction;
+use std::sync::Arc;
 
 #[derive(InputObject)]
 pub struct TopStakersQueryInput {
@@ -46,6 +51,7 @@ pub struct TopStakerAccount {
 
 #[Object]
 impl TopStakersQueryRoot {
+    #[graphql(complexity = "limit * child_complexity")]
     async fn top_stakers<'ctx>(
         &self,
         ctx: &Context<'ctx>,
@@ -69,34 +75,13 @@ impl TopStakersQueryRoot {
             .canonical_epoch_blocks_produced_iterator(Some(epoch), direction)
             .flatten()
         {
+            check_deadline(ctx)?;
             let key_epoch = from_be_bytes(key[..U32_LEN].to_vec());
             if key_epoch != epoch {
                 break;
             }
 
-            let num = from_be_bytes(key[U32_LEN..][..U32_LEN].to_vec());
-            let pk = PublicKey::from_bytes(&key[U32_LEN..][U32_LEN..])?;
-            let account = db
-                .get_best_account(&pk, &TokenAddress::default())? // always MINA
-                .with_context(|| format!("Account missing {pk}"))
-                .unwrap()
-                .display();
-
-            let username = match db.get_username(&pk) {
-                Ok(None) | Err(_) => None,
-                Ok(Some(username)) => Some(username.0),
-            };
-
-            let account = TopStakerAccount::from((
-                account.clone(),
-                db.get_block_production_pk_epoch_count(&pk, Some(epoch))?,
-                num,
-                db.get_block_production_pk_supercharged_epoch_count(&pk, Some(epoch))?,
-                db.get_pk_epoch_slots_produced_count(&pk, Some(epoch))?,
-                username,
-            ));
-
-            accounts.push(account);
+            accounts.push(TopStakerAccount::from_key(db, &key, epoch)?);
             if accounts.len() >= limit {
                 break;
             }
@@ -104,6 +89,66 @@ impl TopStakersQueryRoot {
 
         Ok(accounts)
     }
+
+    /// Cursor paginated top stakers
+    ///
+    /// `totalCount` is not provided
+    #[graphql(complexity = "first * child_complexity")]
+    async fn top_stakers_connection<'ctx>(
+        &self,
+        ctx: &Context<'ctx>,
+        query: Option<TopStakersQueryInput>,
+        sort_by: Option<TopStakersSortByInput>,
+        #[graphql(default = 100)] first: usize,
+        after: Option<String>,
+    ) -> Result<SortKeyConnection<TopStakerAccount>> {
+        let db = db(ctx);
+        let after = SortKeyCursor::decode(after.as_deref())?;
+        let epoch = match query {
+            Some(query) => query.epoch,
+            None => db.get_current_epoch()?,
+        };
+        let direction = match sort_by {
+            Some(TopStakersSortByInput::NumCanonicalBlocksProducedAsc) => Direction::Forward,
+            Some(TopStakersSortByInput::NumCanonicalBlocksProducedDesc) | None => {
+                Direction::Reverse
+            }
+        };
+
+        let nodes = db
+            .canonical_epoch_blocks_produced_iterator(Some(epoch), direction)
+            .flatten()
+            .take_while(|(key, _)| key[..U32_LEN] == epoch.to_be_bytes())
+            .filter(|(key, _)| SortKeyCursor::precedes(after.as_ref(), key, direction))
+            .map(|(key, _)| Ok((key.to_vec(), TopStakerAccount::from_key(db, &key, epoch)?)));
+        paginate(deadline(ctx), nodes, after.as_ref(), first, None)
+    }
+}
+
+impl TopStakerAccount {
+    /// Build the staker from a canonical epoch blocks produced sort key
+    fn from_key(db: &Arc<IndexerStore>, key: &[u8], epoch: u32) -> anyhow::Result<Self> {
+        let num = from_be_bytes(key[U32_LEN..][..U32_LEN].to_vec());
+        let pk = PublicKey::from_bytes(&key[U32_LEN..][U32_LEN..])?;
+        let account = db
+            .get_best_account(&pk, &TokenAddress::default())? // always MINA
+            .with_context(|| format!("Account missing {pk}"))?
+            .display(&db.protocol_params());
+
+        let username = match db.get_username(&pk) {
+            Ok(None) | Err(_) => None,
+            Ok(Some(username)) => Some(username.0),
+        };
+
+        Ok(Self::from((
+            account,
+            db.get_block_production_pk_epoch_count(&pk, Some(epoch))?,
+            num,
+            db.get_block_production_pk_supercharged_epoch_count(&pk, Some(epoch))?,
+            db.get_pk_epoch_slots_produced_count(&pk, Some(epoch))?,
+            username,
+        )))
+    }
 }
 
 impl From<(account::Account, u32, u32, u32, u32, Option<String>)> for TopStakerAccount {
@@ -1,22 +1,29 @@
-use super::{date_time_to_scalar, db, get_block_canonicity, PK};
+use super::{date_time_to_scalar, db, get_block_canonicity, validate_optional_base58, PK};
 use crate::{
-    base::public_key::PublicKey,
+    base::{base58::Base58Kind, public_key::PublicKey},
     block::store::BlockStore,
     command::{
         signed::{SignedCommandWithData, TxnHash},
         store::UserCommandStore,
         CommandStatusData,
     },
-    constants::millis_to_global_slot,
     store::IndexerStore,
-    utility::store::{
-        command::user::{
-            pk_txn_sort_key_prefix, txn_hash_of_key, user_commands_iterator_state_hash,
-            user_commands_iterator_txn_hash,
+    utility::{
+        deadline::check_scan,
+        store::{
+            command::user::{
+                pk_txn_sort_key_prefix, txn_hash_of_key, user_commands_iterator_state_hash,
+                user_commands_iterator_txn_hash,
+            },
+            common::{state_hash_suffix, U32_LEN},
         },
-        common::{state_hash_suffix, U32_LEN},
     },
-    web::graphql::{gen::TransactionQueryInput, DateTime},
+    web::graphql::{
+        gen::TransactionQueryInput,
+        limits::{check_deadline, deadline},
+        pagination::{paginate, SortKeyConnection, SortKeyCursor},
+        DateTime,
+    },
 };
 use async_graphql::{Context, Enum, Object, Result, SimpleObject};
 use serde::Serialize;
@@ -94,6 +101,7 @@ impl TransactionsQueryRoot {
         query: TransactionQueryInput,
     ) -> Result<Option<Transaction>> {
         let db = db(ctx);
+        query.validate()?;
         let epoch_num_user_commands = db.get_user_commands_epoch_count(None)?;
         let total_num_user_commands = db.get_user_commands_total_count()?;
         if let Some(hash) = query.hash {
@@ -108,6 +116,7 @@ impl TransactionsQueryRoot {
     }
 
     #[allow(clippy::too_many_lines)]
+    #[graphql(complexity = "limit * child_complexity")]
     pub async fn transactions(
         &self,
         ctx: &Context<'_>,
@@ -118,6 +127,9 @@ impl TransactionsQueryRoot {
         use TransactionSortByInput::*;
 
         let db = db(ctx);
+        if let Some(query) = query.as_ref() {
+            query.validate()?;
+        }
         let epoch_num_user_commands = db.get_user_commands_epoch_count(None)?;
         let total_num_user_commands = db.get_user_commands_total_count()?;
         let sort_by = sort_by.unwrap_or(TransactionSortByInput::BlockHeightDesc);
@@ -167,6 +179,7 @@ impl TransactionsQueryRoot {
             };
 
             for (key, _) in iter.flatten() {
+                check_deadline(ctx)?;
                 if key[..U32_LEN] < *min.to_be_bytes().as_slice()
                     || key[..U32_LEN] > *max.to_be_bytes().as_slice()
                 {
@@ -255,6 +268,7 @@ impl TransactionsQueryRoot {
             };
 
             for (key, _) in iter.flatten() {
+                check_deadline(ctx)?;
                 if key[..U32_LEN] != block_height.to_be_bytes() {
                     // we've gone beyond the desired block height
                     break;
@@ -310,6 +324,7 @@ impl TransactionsQueryRoot {
                 db.txn_to_height_iterator(mode).flatten()
             };
             for (key, _) in txn_iter {
+                check_deadline(ctx)?;
                 if key[..PublicKey::LEN] != *pk.as_bytes() {
                     // we've gone beyond the desired public key
                     break;
@@ -397,6 +412,7 @@ impl TransactionsQueryRoot {
             };
 
             for (key, _) in iter.flatten() {
+                check_deadline(ctx)?;
                 if key[..U32_LEN] > *max.to_be_bytes().as_slice()
                     || key[..U32_LEN] < *min.to_be_bytes().as_slice()
                 {
@@ -494,6 +510,7 @@ impl TransactionsQueryRoot {
             };
 
             for (key, _) in iter.flatten() {
+                check_deadline(ctx)?;
                 if key[..U32_LEN] > *max.to_be_bytes().as_slice()
                     || key[..U32_LEN] < *min.to_be_bytes().as_slice()
                 {
@@ -534,6 +551,7 @@ impl TransactionsQueryRoot {
             DateTimeDesc | GlobalSlotDesc => db.user_commands_slot_iterator(IteratorMode::End),
         };
         for (key, _) in iter.flatten() {
+            check_deadline(ctx)?;
             if let Some(ref q) = query {
                 // early exit if txn hashes don't match if we're filtering by it
                 if q.hash.is_some()
@@ -574,6 +592,91 @@ impl TransactionsQueryRoot {
 
         Ok(transactions)
     }
+
+    /// Cursor paginated transactions
+    ///
+    /// `totalCount` is only provided for unfiltered queries
+    #[graphql(complexity = "first * child_complexity")]
+    pub async fn transactions_connection(
+        &self,
+        ctx: &Context<'_>,
+        query: Option<TransactionQueryInput>,
+        sort_by: Option<TransactionSortByInput>,
+        #[graphql(default = 100)] first: usize,
+        after: Option<String>,
+    ) -> Result<SortKeyConnection<Transaction>> {
+        use TransactionSortByInput::*;
+
+        let db = db(ctx);
+        if let Some(query) = query.as_ref() {
+            query.validate()?;
+        }
+        let after = SortKeyCursor::decode(after.as_deref())?;
+        let epoch_num_user_commands = db.get_user_commands_epoch_count(None)?;
+        let total_num_user_commands = db.get_user_commands_total_count()?;
+        let sort_by = sort_by.unwrap_or(BlockHeightDesc);
+
+        let (start, direction) = match sort_by {
+            BlockHeightAsc | DateTimeAsc | GlobalSlotAsc => (0, Direction::Forward),
+            BlockHeightDesc | DateTimeDesc | GlobalSlotDesc => (u32::MAX, Direction::Reverse),
+        };
+
+        // from/to account (sender/receiver) keys are prefixed by the public key
+        let pk = query
+            .as_ref()
+            .and_then(|q| q.from.as_ref().or(q.to.as_ref()))
+            .map(|pk| PublicKey::from(pk as &str));
+        let start = match pk.as_ref() {
+            Some(pk) => pk_txn_sort_key_prefix(pk, start).to_vec(),
+            None => start.to_be_bytes().to_vec(),
+        };
+        let mode = SortKeyCursor::iterator_mode(after.as_ref(), &start, direction);
+        let from = query.as_ref().is_some_and(|q| q.from.is_some());
+        let iter = match sort_by {
+            _ if pk.is_some() && from => db.txn_from_height_iterator(mode),
+            _ if pk.is_some() => db.txn_to_height_iterator(mode),
+            BlockHeightAsc | BlockHeightDesc => db.user_commands_height_iterator(mode),
+            DateTimeAsc | DateTimeDesc | GlobalSlotAsc | GlobalSlotDesc => {
+                db.user_commands_slot_iterator(mode)
+            }
+        };
+
+        let deadline = deadline(ctx);
+        let nodes = iter
+            .flatten()
+            .take_while(|(key, _)| {
+                pk.as_ref()
+                    .map_or(true, |pk| key[..PublicKey::LEN] == *pk.0.as_bytes())
+            })
+            .filter(|(key, _)| SortKeyCursor::precedes(after.as_ref(), key, direction))
+            .map(|(key, _)| {
+                check_scan(deadline)?;
+
+                let state_hash = state_hash_suffix(&key)?;
+                if let Some(query_canonicity) = query.as_ref().and_then(|q| q.canonical) {
+                    if get_block_canonicity(db, &state_hash) != query_canonicity {
+                        return Ok(None);
+                    }
+                }
+
+                let txn_hash = if pk.is_some() {
+                    txn_hash_of_key(&key)
+                } else {
+                    user_commands_iterator_txn_hash(&key)?
+                };
+                let txn = db
+                    .get_user_command_state_hash(&txn_hash, &state_hash)?
+                    .map(|cmd| {
+                        Transaction::new(cmd, db, epoch_num_user_commands, total_num_user_commands)
+                    })
+                    .filter(|txn| query.as_ref().map_or(true, |q| q.matches(txn)));
+                Ok(txn.map(|txn| (key.to_vec(), txn)))
+            })
+            .filter_map(anyhow::Result::transpose);
+
+        let total_count = query.is_none().then_some(total_num_user_commands);
+        paginate(deadline, nodes, after.as_ref(), first, total_count)
+    }
 }
 
 fn calculate_inclusive_height_bounds(
@@ -614,6 +717,8 @@ fn calculate_inclusive_slot_bounds(
     date_time_lt: &Option<DateTime>,
     date_time_lte: &Option<DateTime>,
 ) -> Result<(u32, u32)> {
+    let protocol = db.protocol_params();
+    let millis_to_global_slot = |millis| protocol.millis_to_global_slot(millis);
     let min_bound = match (
         global_slot_gte.or(date_time_gte
             .as_ref()
@@ -716,6 +821,16 @@ impl TransactionWithoutBlock {
 }
 
 impl TransactionQueryInput {
+    /// Checks the base58 inputs are of the expected kinds
+    fn validate(&self) -> Result<()> {
+        validate_optional_base58(Base58Kind::PublicKey, self.from.as_ref())?;
+        validate_optional_base58(Base58Kind::PublicKey, self.to.as_ref())?;
+        if let Some(block) = self.block.as_ref() {
+            block.validate()?;
+        }
+        Ok(())
+    }
+
     #[allow(clippy::too_many_lines)]
     fn matches(&self, transaction: &Transaction) -> bool {
         let TransactionQueryInput {
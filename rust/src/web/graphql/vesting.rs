@@ -0,0 +1,151 @@
+use super::{db, validate_base58, Timing};
+use crate::{
+    base::{base58::Base58Kind, public_key::PublicKey},
+    constants::{millis_to_iso_date_string, DEFAULT_VESTING_SCHEDULE_INTERVAL},
+    ledger::{
+        account::vesting::{DelegatorVestingSchedules, VestingSchedule, VestingUnlock},
+        store::supply::SupplyStore,
+    },
+    web::graphql::limits::deadline,
+};
+use async_graphql::{ComplexObject, Context, Object, Result, SimpleObject};
+
+#[derive(Default)]
+pub struct VestingQueryRoot;
+
+#[derive(SimpleObject)]
+#[graphql(complex)]
+pub struct AccountVestingSchedule {
+    #[graphql(name = "public_key")]
+    public_key: String,
+
+    timing: Timing,
+
+    /// First global slot without a minimum balance, if any
+    #[graphql(name = "fully_vested_slot")]
+    fully_vested_slot: Option<u32>,
+
+    unlocks: Vec<AccountVestingUnlock>,
+
+    #[graphql(skip)]
+    inner: VestingSchedule,
+}
+
+#[derive(SimpleObject)]
+#[graphql(complex)]
+pub struct DelegatorsVestingSchedule {
+    delegate: String,
+
+    /// Combined unlocks of all delegators
+    unlocks: Vec<AccountVestingUnlock>,
+
+    schedules: Vec<AccountVestingSchedule>,
+
+    #[graphql(skip)]
+    inner: DelegatorVestingSchedules,
+}
+
+#[derive(SimpleObject)]
+pub struct AccountVestingUnlock {
+    #[graphql(name = "global_slot")]
+    global_slot: u32,
+
+    /// Value date_time as ISO 8601 string
+    #[graphql(name = "date_time")]
+    date_time: String,
+
+    /// Balance unlocked since the previous unlock
+    unlocked: u64,
+
+    #[graphql(name = "cumulative_unlocked")]
+    cumulative_unlocked: u64,
+
+    locked: u64,
+}
+
+#[Object]
+impl VestingQueryRoot {
+    /// Projected unlock schedule of the best ledger timed account, sampled
+    /// every `interval` slots
+    #[graphql(name = "vestingSchedule")]
+    async fn vesting_schedule<'ctx>(
+        &self,
+        ctx: &Context<'ctx>,
+        public_key: String,
+        #[graphql(default_with = "DEFAULT_VESTING_SCHEDULE_INTERVAL")] interval: u32,
+    ) -> Result<Option<AccountVestingSchedule>> {
+        let pk = validate_public_key(public_key)?;
+        Ok(db(ctx).get_vesting_schedule(&pk, interval)?.map(Into::into))
+    }
+
+    /// Combined projected unlock schedule of the best ledger timed accounts
+    /// delegating to the public key, sampled every `interval` slots
+    #[graphql(name = "delegatorsVestingSchedule")]
+    async fn delegators_vesting_schedule<'ctx>(
+        &self,
+        ctx: &Context<'ctx>,
+        public_key: String,
+        #[graphql(default_with = "DEFAULT_VESTING_SCHEDULE_INTERVAL")] interval: u32,
+    ) -> Result<DelegatorsVestingSchedule> {
+        let pk = validate_public_key(public_key)?;
+        Ok(db(ctx)
+            .get_delegator_vesting_schedules(&pk, interval, deadline(ctx))?
+            .into())
+    }
+}
+
+fn validate_public_key(public_key: String) -> Result<PublicKey> {
+    validate_base58(Base58Kind::PublicKey, &public_key)?;
+    Ok(public_key.into())
+}
+
+#[ComplexObject]
+impl AccountVestingSchedule {
+    /// Unlocks as CSV
+    async fn csv(&self) -> Result<String> {
+        Ok(self.inner.to_csv()?)
+    }
+}
+
+#[ComplexObject]
+impl DelegatorsVestingSchedule {
+    /// Combined & delegator unlocks as CSV
+    async fn csv(&self) -> Result<String> {
+        Ok(self.inner.to_csv()?)
+    }
+}
+
+impl From<VestingSchedule> for AccountVestingSchedule {
+    fn from(value: VestingSchedule) -> Self {
+        Self {
+            public_key: value.public_key.0.clone(),
+            timing: value.timing.clone().into(),
+            fully_vested_slot: value.fully_vested_slot,
+            unlocks: value.unlocks.iter().map(Into::into).collect(),
+            inner: value,
+        }
+    }
+}
+
+impl From<DelegatorVestingSchedules> for DelegatorsVestingSchedule {
+    fn from(value: DelegatorVestingSchedules) -> Self {
+        Self {
+            delegate: value.delegate.0.clone(),
+            unlocks: value.unlocks.iter().map(Into::into).collect(),
+            schedules: value.schedules.iter().cloned().map(Into::into).collect(),
+            inner: value,
+        }
+    }
+}
+
+impl From<&VestingUnlock> for AccountVestingUnlock {
+    fn from(value: &VestingUnlock) -> Self {
+        Self {
+            global_slot: value.global_slot,
+            date_time: millis_to_iso_date_string(value.date_time),
+            unlocked: value.unlocked,
+            cumulative_unlocked: value.cumulative_unlocked,
+            locked: value.locked,
+        }
+    }
+}
@@ -0,0 +1,115 @@
+use super::db;
+use crate::state::witness_tree::{WitnessBranch, WitnessTree, WitnessTreeNode};
+use async_graphql::{Context, Object, Result, SimpleObject};
+
+#[derive(Default)]
+pub struct WitnessTreeQueryRoot;
+
+#[derive(SimpleObject)]
+pub struct WitnessTreeView {
+    #[graphql(name = "best_tip")]
+    best_tip: String,
+
+    #[graphql(name = "canonical_root")]
+    canonical_root: String,
+
+    #[graphql(name = "num_blocks")]
+    num_blocks: usize,
+
+    /// Tree of blocks connecting back to a known ledger
+    #[graphql(name = "root_branch")]
+    root_branch: WitnessBranchView,
+
+    /// Trees of blocks waiting for their missing ancestors
+    #[graphql(name = "dangling_branches")]
+    dangling_branches: Vec<WitnessBranchView>,
+
+    /// Graphviz DOT representation
+    dot: String,
+}
+
+#[derive(SimpleObject)]
+pub struct WitnessBranchView {
+    root: String,
+
+    /// Parent of the branch root, i.e. the block needed to merge a dangling
+    /// branch
+    #[graphql(name = "root_parent")]
+    root_parent: String,
+
+    /// Branch blocks in level order
+    nodes: Vec<WitnessTreeNodeView>,
+}
+
+#[derive(SimpleObject)]
+pub struct WitnessTreeNodeView {
+    #[graphql(name = "state_hash")]
+    state_hash: String,
+
+    #[graphql(name = "parent_hash")]
+    parent_hash: String,
+
+    #[graphql(name = "blockchain_length")]
+    blockchain_length: u32,
+
+    #[graphql(name = "global_slot_since_genesis")]
+    global_slot_since_genesis: u32,
+
+    creator: Option<String>,
+
+    #[graphql(name = "last_vrf_output")]
+    last_vrf_output: String,
+
+    /// Canonical, Orphaned, or Pending
+    canonicity: String,
+}
+
+#[Object]
+impl WitnessTreeQueryRoot {
+    /// Current witness tree, `null` if the indexer state is not running
+    #[graphql(name = "witnessTree")]
+    async fn witness_tree<'ctx>(&self, ctx: &Context<'ctx>) -> Result<Option<WitnessTreeView>> {
+        Ok(db(ctx).get_witness_tree().await.map(Into::into))
+    }
+}
+
+impl From<WitnessTree> for WitnessTreeView {
+    fn from(value: WitnessTree) -> Self {
+        Self {
+            best_tip: value.best_tip.0.clone(),
+            canonical_root: value.canonical_root.0.clone(),
+            num_blocks: value.num_blocks(),
+            dot: value.to_dot(),
+            root_branch: value.root_branch.into(),
+            dangling_branches: value
+                .dangling_branches
+                .into_iter()
+                .map(Into::into)
+                .collect(),
+        }
+    }
+}
+
+impl From<WitnessBranch> for WitnessBranchView {
+    fn from(value: WitnessBranch) -> Self {
+        Self {
+            root: value.root.0,
+            root_parent: value.root_parent.0,
+            nodes: value.nodes.into_iter().map(Into::into).collect(),
+        }
+    }
+}
+
+impl From<WitnessTreeNode> for WitnessTreeNodeView {
+    fn from(value: WitnessTreeNode) -> Self {
+        Self {
+            state_hash: value.state_hash.0,
+            parent_hash: value.parent_hash.0,
+            blockchain_length: value.blockchain_length,
+            global_slot_since_genesis: value.global_slot_since_genesis,
+            creator: value.creator.map(|pk| pk.0),
+            last_vrf_output: value.last_vrf_output.to_string(),
+            canonicity: format!("{:?}", value.canonicity),
+        }
+    }
+}
@@ -4,23 +4,41 @@ pub mod rest;
 pub const ENDPOINT_GRAPHQL: &str = "/graphql";
 
 use self::{
-    graphql::{build_schema, indexer_graphiql},
-    rest::{accounts, blockchain, blocks, locked_balances::LockedBalances},
+    graphql::{build_schema, indexer_graphiql, indexer_subscription, limits::GraphQLLimits},
+    rest::{
+        accounts, blockchain, blocks, chain_health, supply,
+        upload::{self, Uploader},
+        witness_tree,
+    },
+};
+use crate::{
+    chain::{ChainId, Network},
+    constants::UPLOAD_PAYLOAD_LIMIT,
+    store::IndexerStore,
 };
-use crate::store::IndexerStore;
 use actix_cors::Cors;
 use actix_web::{guard, middleware, web, web::Data, App, HttpServer};
-use async_graphql_actix_web::GraphQL;
-use log::warn;
+use graphql::indexer_graphql;
+use serde::Deserialize;
 use std::{net, sync::Arc};
 use tokio_graceful_shutdown::{FutureExt, SubsystemHandle};
 
-fn load_locked_balances() -> LockedBalances {
-    match LockedBalances::new() {
-        Ok(locked_balances) => locked_balances,
-        Err(e) => {
-            warn!("locked supply csv ingestion failed. {}", e);
-            LockedBalances::default()
+/// `network` query parameter of GraphQL & REST requests, selecting one of the
+/// networks indexed in the database by name (e.g. `devnet`) or chain id
+#[derive(Debug, Default, Deserialize)]
+pub struct NetworkParams {
+    pub network: Option<String>,
+}
+
+impl NetworkParams {
+    /// Store of the selected network, the primary store by default
+    ///
+    /// Error if the network isn't indexed
+    pub fn store(&self, store: &Arc<IndexerStore>) -> anyhow::Result<Arc<IndexerStore>> {
+        match self.network.as_deref() {
+            Some(network) if ChainId::is_valid(network) => store.chain_id_store(&network.parse()?),
+            Some(network) => store.network_store(&Network::from(network)),
+            None => Ok(store.clone()),
         }
     }
 }
@@ -29,21 +47,35 @@ pub async fn start_web_server<A: net::ToSocketAddrs>(
     subsys: SubsystemHandle,
     state: Arc<IndexerStore>,
     addrs: A,
+    limits: GraphQLLimits,
+    uploader: Option<Uploader>,
 ) -> anyhow::Result<()> {
-    let locked = Arc::new(load_locked_balances());
-
     let _ = HttpServer::new(move || {
+        let schema = build_schema(state.clone(), &limits);
         App::new()
             .app_data(Data::new(state.clone()))
-            .app_data(Data::new(locked.clone()))
+            .app_data(Data::new(schema.clone()))
+            .app_data(Data::new(uploader.clone()))
+            .app_data(web::PayloadConfig::new(UPLOAD_PAYLOAD_LIMIT))
             .service(blocks::get_blocks)
             .service(blocks::get_block_by_state_hash)
             .service(accounts::get_account)
             .service(blockchain::get_blockchain_summary)
+            .service(supply::get_supply)
+            .service(chain_health::get_chain_health)
+            .service(witness_tree::get_witness_tree)
+            .service(upload::post_block)
+            .service(upload::post_staking_ledger)
             .service(
                 web::resource(ENDPOINT_GRAPHQL)
                     .guard(guard::Post())
-                    .to(GraphQL::new(build_schema(state.clone()))),
+                    .to(indexer_graphql),
+            )
+            .service(
+                web::resource(ENDPOINT_GRAPHQL)
+                    .guard(guard::Get())
+                    .guard(guard::Header("upgrade", "websocket"))
+                    .to(indexer_subscription),
             )
             .service(
                 web::resource(ENDPOINT_GRAPHQL)
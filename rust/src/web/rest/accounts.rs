@@ -5,6 +5,7 @@ use crate::{
     ledger::{account, store::best::BestLedgerStore, token::TokenAddress},
     snark_work::store::SnarkStore,
     store::IndexerStore,
+    web::NetworkParams,
 };
 use actix_web::{
     get,
@@ -33,9 +34,14 @@ struct Account {
 #[get("/accounts/{public_key}")]
 pub async fn get_account(
     store: Data<Arc<IndexerStore>>,
+    network: web::Query<NetworkParams>,
     public_key: web::Path<String>,
 ) -> HttpResponse {
-    let db = store.as_ref();
+    let store = match network.store(&store) {
+        Ok(store) => store,
+        Err(e) => return HttpResponse::BadRequest().body(e.to_string()),
+    };
+    let db = &store;
     let pk: PublicKey = public_key.clone().into();
 
     if let Ok(Some(account)) = db.get_best_account(&pk, &TokenAddress::default()) {
@@ -65,7 +71,7 @@ pub async fn get_account(
 
         return HttpResponse::Ok().content_type(ContentType::json()).body(
             serde_json::to_string_pretty(&Account {
-                account: account.account.clone().display(),
+                account: account.account.clone().display(&db.protocol_params()),
                 ..account
             })
             .expect("serde account bytes"),
@@ -1,19 +1,30 @@
 use crate::{
-    base::amount::Amount,
     block::{precomputed::PrecomputedBlock, store::BlockStore},
-    chain::store::ChainStore,
+    chain::{profile::ProtocolParams, store::ChainStore},
     command::{internal::store::InternalCommandStore, store::UserCommandStore},
-    constants::{MAINNET_EPOCH_SLOT_COUNT, VERSION},
-    ledger::store::best::BestLedgerStore,
+    constants::VERSION,
+    ledger::{
+        distribution::WealthDistribution,
+        store::{
+            best::BestLedgerStore,
+            distribution::DistributionStore,
+            supply::{BlockSupply, SupplyStore},
+        },
+    },
     snark_work::store::SnarkStore,
     store::{
         version::{IndexerStoreVersion, VersionStore},
         IndexerStore,
     },
     utility::functions::nanomina_to_mina,
-    web::rest::locked_balances::LockedBalances,
+    web::NetworkParams,
+};
+use actix_web::{
+    get,
+    http::header::ContentType,
+    web::{self, Data},
+    HttpResponse,
 };
-use actix_web::{get, http::header::ContentType, web::Data, HttpResponse};
 use chrono::DateTime;
 use log::trace;
 use serde::Serialize;
@@ -55,6 +66,10 @@ pub struct BlockchainSummary {
     epoch_num_internal_commands: u32,
     total_num_internal_commands: u32,
     total_num_canonical_internal_commands: u32,
+    staking_ledger_balance_gini: Option<f64>,
+    staking_ledger_stake_gini: Option<f64>,
+    staking_ledger_nakamoto_one_third: Option<u32>,
+    staking_ledger_nakamoto_one_half: Option<u32>,
     db_version: String,
     indexer_version: String,
 }
@@ -67,8 +82,10 @@ fn millis_to_date_string(millis: i64) -> String {
 
 struct SummaryInput {
     chain_id: String,
+    protocol: ProtocolParams,
     best_tip: PrecomputedBlock,
-    locked_balance: Option<Amount>,
+    supply: BlockSupply,
+    distribution: Option<WealthDistribution>,
     db_version: IndexerStoreVersion,
     indexer_version: String,
     epoch_num_blocks: u32,
@@ -92,8 +109,10 @@ struct SummaryInput {
 fn calculate_summary(input: SummaryInput) -> Option<BlockchainSummary> {
     let SummaryInput {
         chain_id,
+        protocol,
         best_tip,
-        locked_balance,
+        supply,
+        distribution,
         db_version,
         indexer_version,
         epoch_num_blocks,
@@ -120,18 +139,23 @@ fn calculate_summary(input: SummaryInput) -> Option<BlockchainSummary> {
     let min_window_density = best_tip.min_window_density();
     let next_epoch_ledger_hash = best_tip.next_epoch_ledger_hash().0;
     let previous_state_hash = best_tip.previous_state_hash().0;
-    let slot = global_slot % MAINNET_EPOCH_SLOT_COUNT;
-    let snarked_ledger_hash = best_tip.snarked_ledger_hash().map(|hash| hash.0);
+    let slot = protocol.epoch_slot(global_slot);
+    let snarked_ledger_hash = Some(best_tip.snarked_ledger_hash().0);
     let staged_ledger_hash = best_tip.staged_ledger_hash().0;
     let staking_epoch_ledger_hash = best_tip.staking_epoch_ledger_hash().0;
     let state_hash = best_tip.state_hash().0;
-    let total_currency_u64 = best_tip.total_currency();
-    let locked_currency_u64 = locked_balance.map(|a| a.0).unwrap_or_default();
-    let total_currency = nanomina_to_mina(total_currency_u64);
-    let circulating_supply = nanomina_to_mina(total_currency_u64 - locked_currency_u64);
-    let locked_supply = nanomina_to_mina(locked_currency_u64);
+    let total_currency = nanomina_to_mina(supply.total_currency);
+    let circulating_supply = nanomina_to_mina(supply.circulating);
+    let locked_supply = nanomina_to_mina(supply.locked);
     let db_version = db_version.to_string();
 
+    // current epoch staking ledger decentralization
+    let staking_ledger_balance_gini = distribution.as_ref().map(|dist| dist.balance_gini);
+    let staking_ledger_stake_gini = distribution.as_ref().map(|dist| dist.stake_gini);
+    let staking_ledger_nakamoto_one_third =
+        distribution.as_ref().map(|dist| dist.nakamoto_one_third);
+    let staking_ledger_nakamoto_one_half = distribution.as_ref().map(|dist| dist.nakamoto_one_half);
+
     Some(BlockchainSummary {
         blockchain_length,
         chain_id,
@@ -165,6 +189,10 @@ fn calculate_summary(input: SummaryInput) -> Option<BlockchainSummary> {
         epoch_num_internal_commands,
         total_num_internal_commands,
         total_num_canonical_internal_commands,
+        staking_ledger_balance_gini,
+        staking_ledger_stake_gini,
+        staking_ledger_nakamoto_one_third,
+        staking_ledger_nakamoto_one_half,
         db_version,
         indexer_version,
     })
@@ -173,9 +201,13 @@ fn calculate_summary(input: SummaryInput) -> Option<BlockchainSummary> {
 #[get("/summary")]
 pub async fn get_blockchain_summary(
     store: Data<Arc<IndexerStore>>,
-    locked_balances: Data<Arc<LockedBalances>>,
+    network: web::Query<NetworkParams>,
 ) -> HttpResponse {
-    let db = store.as_ref();
+    let store = match network.store(&store) {
+        Ok(store) => store,
+        Err(e) => return HttpResponse::BadRequest().body(e.to_string()),
+    };
+    let db = &store;
     if let Ok(Some(best_tip)) = db.get_best_block() {
         trace!("Found best tip: {}", best_tip.summary());
         let total_num_accounts = store
@@ -183,10 +215,26 @@ pub async fn get_blockchain_summary(
             .expect("num accounts")
             .unwrap_or_default();
 
-        // aggregated on-chain & off-chain time-locked tokens
+        // supply computed from the best ledger's timed accounts
         let chain_id = store.get_chain_id().expect("chain id").0;
-        let global_slot = best_tip.global_slot_since_genesis();
-        let locked_balance = locked_balances.get_locked_amount(global_slot);
+        let supply = match store
+            .get_block_supply(&best_tip.state_hash())
+            .expect("best tip supply")
+        {
+            Some(supply) => supply,
+            None => {
+                let global_slot = best_tip.global_slot_since_genesis();
+                let locked = store
+                    .get_locked_supply(global_slot, None)
+                    .expect("locked supply");
+                BlockSupply::new(&best_tip, locked, None)
+            }
+        };
+
+        // current epoch staking ledger wealth distribution
+        let distribution = store
+            .get_staking_ledger_distribution(&best_tip.staking_epoch_ledger_hash())
+            .expect("staking ledger distribution");
 
         // version info
         let db_version = store.get_db_version().expect("store version");
@@ -239,8 +287,10 @@ pub async fn get_blockchain_summary(
 
         if let Some(ref summary) = calculate_summary(SummaryInput {
             chain_id,
+            protocol: store.protocol_params(),
             best_tip,
-            locked_balance,
+            supply,
+            distribution,
             db_version,
             indexer_version,
             epoch_num_blocks,
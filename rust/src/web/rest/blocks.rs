@@ -6,6 +6,7 @@ use crate::{
         blocks::{get_counts, Block},
         get_block,
     },
+    web::NetworkParams,
 };
 use actix_web::{
     get,
@@ -33,9 +34,14 @@ fn format_blocks(blocks: Vec<Block>) -> String {
 #[get("/blocks")]
 pub async fn get_blocks(
     store: Data<Arc<IndexerStore>>,
+    network: web::Query<NetworkParams>,
     params: web::Query<Params>,
 ) -> HttpResponse {
-    let db = store.as_ref();
+    let store = match network.store(&store) {
+        Ok(store) => store,
+        Err(e) => return HttpResponse::BadRequest().body(e.to_string()),
+    };
+    let db = &store;
     let limit = get_limit(params.limit);
 
     // Check for height query parameter
@@ -93,9 +99,14 @@ pub async fn get_blocks(
 #[get("/blocks/{state_hash}")]
 pub async fn get_block_by_state_hash(
     store: Data<Arc<IndexerStore>>,
+    network: web::Query<NetworkParams>,
     state_hash: web::Path<String>,
 ) -> HttpResponse {
-    let db = store.as_ref();
+    let store = match network.store(&store) {
+        Ok(store) => store,
+        Err(e) => return HttpResponse::BadRequest().body(e.to_string()),
+    };
+    let db = &store;
 
     if StateHash::is_valid(&state_hash) {
         if let Ok(Some((ref block, _))) = db.get_block(&state_hash.clone().into()) {
@@ -0,0 +1,58 @@
+use crate::{
+    chain::health::{ChainHealth, ChainHealthStore},
+    constants::DEFAULT_CHAIN_HEALTH_WINDOW,
+    store::IndexerStore,
+    web::NetworkParams,
+};
+use actix_web::{
+    get,
+    http::header::ContentType,
+    web::{self, Data},
+    HttpResponse,
+};
+use serde::Deserialize;
+use std::sync::Arc;
+
+#[derive(Deserialize)]
+struct Params {
+    start_slot: Option<u32>,
+    end_slot: Option<u32>,
+    window: Option<u32>,
+    step: Option<u32>,
+    limit: Option<usize>,
+}
+
+fn get_limit(limit: Option<usize>) -> usize {
+    limit.map(|value| value.min(1000)).unwrap_or(100)
+}
+
+/// Time series of sliding window chain health
+#[get("/chain-health")]
+pub async fn get_chain_health(
+    store: Data<Arc<IndexerStore>>,
+    network: web::Query<NetworkParams>,
+    params: web::Query<Params>,
+) -> HttpResponse {
+    let store = match network.store(&store) {
+        Ok(store) => store,
+        Err(e) => return HttpResponse::BadRequest().body(e.to_string()),
+    };
+    let window = params.window.unwrap_or(DEFAULT_CHAIN_HEALTH_WINDOW);
+    match store.get_chain_health_time_series(
+        params.start_slot.unwrap_or_default(),
+        params.end_slot.unwrap_or(u32::MAX),
+        window,
+        params.step.unwrap_or(window),
+        get_limit(params.limit),
+        None,
+    ) {
+        Ok(series) => {
+            let body = serde_json::to_string_pretty::<Vec<ChainHealth>>(&series)
+                .expect("chain health time series");
+            HttpResponse::Ok()
+                .content_type(ContentType::json())
+                .body(body)
+        }
+        Err(_) => HttpResponse::InternalServerError().finish(),
+    }
+}
@@ -1,4 +1,7 @@
 pub mod accounts;
 pub mod blockchain;
 pub mod blocks;
-pub mod locked_balances;
+pub mod chain_health;
+pub mod supply;
+pub mod upload;
+pub mod witness_tree;
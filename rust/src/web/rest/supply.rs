@@ -0,0 +1,92 @@
+use crate::{
+    block::store::BlockStore,
+    ledger::store::supply::{BlockSupply, SupplyStore},
+    store::IndexerStore,
+    utility::functions::nanomina_to_mina,
+    web::NetworkParams,
+};
+use actix_web::{
+    get,
+    http::header::ContentType,
+    web::{self, Data},
+    HttpResponse,
+};
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+
+#[derive(Deserialize)]
+struct Params {
+    start_slot: Option<u32>,
+    end_slot: Option<u32>,
+    limit: Option<usize>,
+}
+
+/// Supply of a canonical block in MINA
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Supply {
+    state_hash: String,
+    blockchain_length: u32,
+    global_slot: u32,
+    date_time: i64,
+    total_currency: String,
+    locked_supply: String,
+    circulating_supply: String,
+    staked_supply: Option<String>,
+}
+
+fn get_limit(limit: Option<usize>) -> usize {
+    limit.map(|value| value.min(1000)).unwrap_or(100)
+}
+
+/// Time series of canonical block supplies
+#[get("/supply")]
+pub async fn get_supply(
+    store: Data<Arc<IndexerStore>>,
+    network: web::Query<NetworkParams>,
+    params: web::Query<Params>,
+) -> HttpResponse {
+    let store = match network.store(&store) {
+        Ok(store) => store,
+        Err(e) => return HttpResponse::BadRequest().body(e.to_string()),
+    };
+    let db = &store;
+    let end_slot = match params.end_slot {
+        Some(end_slot) => end_slot,
+        None => db
+            .get_best_block_global_slot()
+            .expect("best block global slot")
+            .unwrap_or_default(),
+    };
+
+    match db.get_supply_time_series(
+        params.start_slot.unwrap_or_default(),
+        end_slot,
+        get_limit(params.limit),
+        None,
+    ) {
+        Ok(supplies) => {
+            let supplies: Vec<Supply> = supplies.into_iter().map(Into::into).collect();
+            let body = serde_json::to_string_pretty(&supplies).expect("supply time series");
+            HttpResponse::Ok()
+                .content_type(ContentType::json())
+                .body(body)
+        }
+        Err(_) => HttpResponse::InternalServerError().finish(),
+    }
+}
+
+impl From<BlockSupply> for Supply {
+    fn from(value: BlockSupply) -> Self {
+        Self {
+            state_hash: value.state_hash.0,
+            blockchain_length: value.blockchain_length,
+            global_slot: value.global_slot_since_genesis,
+            date_time: value.date_time,
+            total_currency: nanomina_to_mina(value.total_currency),
+            locked_supply: nanomina_to_mina(value.locked),
+            circulating_supply: nanomina_to_mina(value.circulating),
+            staked_supply: value.staked.map(nanomina_to_mina),
+        }
+    }
+}
@@ -0,0 +1,258 @@
+//! Authenticated block & staking ledger uploads
+//!
+//! Bodies are precomputed block or staking ledger JSON, optionally gzipped,
+//! up to [UPLOAD_PAYLOAD_LIMIT] bytes before & after decompression. Uploads
+//! are forwarded to the indexer, which replies with the resulting status once
+//! it has processed them.
+//!
+//! Uploads are trusted: the indexer cannot compute a block's state hash from
+//! its contents (see [crate::block::verify]), so an uploaded block is indexed
+//! under the state hash it's uploaded with. Only hand the upload token to
+//! parties trusted to index blocks.
+
+use crate::{
+    base::state_hash::StateHash,
+    block::source::parse_block_contents,
+    chain::{ChainData, Network},
+    constants::UPLOAD_PAYLOAD_LIMIT,
+    ledger::{staking::StakingLedger, LedgerHash},
+    server::{Upload, UploadRequest},
+};
+use actix_web::{
+    http::header::{ContentType, AUTHORIZATION},
+    post,
+    web::{self, Bytes, Data},
+    HttpRequest, HttpResponse,
+};
+use flate2::read::GzDecoder;
+use serde::Deserialize;
+use std::{
+    borrow::Cow,
+    io::{Error, ErrorKind, Read},
+};
+use subtle::ConstantTimeEq;
+use tokio::sync::{mpsc, oneshot};
+
+const GZIP_MAGIC: [u8; 2] = [0x1f, 0x8b];
+
+/// Authorizes uploads & forwards them to the indexer
+#[derive(Debug, Clone)]
+pub struct Uploader {
+    pub token: String,
+    pub network: Network,
+    pub genesis_state_hash: StateHash,
+    pub sender: mpsc::Sender<UploadRequest>,
+}
+
+#[derive(Deserialize)]
+struct BlockParams {
+    /// Required unless the body wraps the block with its state hash or the
+    /// file name contains it. Trusted, must agree with the others if present.
+    state_hash: Option<String>,
+
+    /// Block file name, e.g. `mainnet-{height}-{state hash}.json`
+    file_name: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct StakingLedgerParams {
+    epoch: u32,
+    ledger_hash: String,
+}
+
+/// Add a precomputed block to the witness tree
+#[post("/blocks")]
+pub async fn post_block(
+    req: HttpRequest,
+    uploader: Data<Option<Uploader>>,
+    params: web::Query<BlockParams>,
+    body: Bytes,
+) -> HttpResponse {
+    let uploader = match authorize(&req, uploader.as_ref()) {
+        Ok(uploader) => uploader,
+        Err(response) => return response,
+    };
+    let contents = match decompress(&body) {
+        Ok(contents) => contents,
+        Err(e) => return bad_request(format!("Invalid gzip body: {e}")),
+    };
+
+    let name = params.file_name.as_deref().or(params.state_hash.as_deref());
+    let block = match parse_block_contents(name, &contents) {
+        Ok(Some((Some(state_hash), _)))
+            if params
+                .state_hash
+                .as_ref()
+                .is_some_and(|param| *param != state_hash.0) =>
+        {
+            return bad_request(format!(
+                "State hash {state_hash} disagrees with `state_hash` parameter"
+            ))
+        }
+        Ok(Some((Some(_), block))) => block,
+        Ok(Some((None, _))) => return bad_request("Unknown state hash, pass `state_hash`"),
+        Ok(None) => return bad_request("Body is not a precomputed block"),
+        Err(e) => return bad_request(format!("Invalid precomputed block: {e}")),
+    };
+
+    let genesis_state_hash = block.genesis_state_hash();
+    if genesis_state_hash != uploader.genesis_state_hash
+        && !ChainData::default().0.contains_key(&genesis_state_hash)
+    {
+        return bad_request(format!("Unknown genesis state hash {genesis_state_hash}"));
+    }
+
+    let block_bytes = contents.len() as u64;
+    send(uploader, Upload::Block { block, block_bytes }).await
+}
+
+/// Add the epoch's staking ledger
+#[post("/staking-ledgers")]
+pub async fn post_staking_ledger(
+    req: HttpRequest,
+    uploader: Data<Option<Uploader>>,
+    params: web::Query<StakingLedgerParams>,
+    body: Bytes,
+) -> HttpResponse {
+    let uploader = match authorize(&req, uploader.as_ref()) {
+        Ok(uploader) => uploader,
+        Err(response) => return response,
+    };
+    let ledger_hash = match LedgerHash::new(params.ledger_hash.as_str()) {
+        Ok(ledger_hash) => ledger_hash,
+        Err(e) => return bad_request(e.to_string()),
+    };
+    let contents = match decompress(&body) {
+        Ok(contents) => contents,
+        Err(e) => return bad_request(format!("Invalid gzip body: {e}")),
+    };
+
+    let staking_ledger = match StakingLedger::from_bytes(
+        &contents,
+        uploader.network.clone(),
+        params.epoch,
+        ledger_hash,
+        uploader.genesis_state_hash.clone(),
+    ) {
+        Ok(staking_ledger) => staking_ledger,
+        Err(e) => return bad_request(format!("Invalid staking ledger: {e}")),
+    };
+
+    send(uploader, Upload::StakingLedger(staking_ledger)).await
+}
+
+/// Uploads are disabled without a configured token
+fn authorize<'a>(
+    req: &HttpRequest,
+    uploader: &'a Option<Uploader>,
+) -> Result<&'a Uploader, HttpResponse> {
+    let Some(uploader) = uploader else {
+        return Err(HttpResponse::NotFound().finish());
+    };
+
+    let token = req
+        .headers()
+        .get(AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(bearer_token);
+    let authorized =
+        token.is_some_and(|token| token.as_bytes().ct_eq(uploader.token.as_bytes()).into());
+    if !authorized {
+        return Err(HttpResponse::Unauthorized()
+            .insert_header(("WWW-Authenticate", "Bearer"))
+            .finish());
+    }
+
+    Ok(uploader)
+}
+
+fn bearer_token(authorization: &str) -> Option<&str> {
+    let (scheme, token) = authorization.trim().split_once(' ')?;
+    scheme
+        .eq_ignore_ascii_case("bearer")
+        .then_some(token.trim())
+}
+
+/// Gunzip the body if it has the gzip magic bytes, up to
+/// [UPLOAD_PAYLOAD_LIMIT] bytes
+fn decompress(body: &[u8]) -> std::io::Result<Cow<[u8]>> {
+    decompress_with_limit(body, UPLOAD_PAYLOAD_LIMIT as u64)
+}
+
+fn decompress_with_limit(body: &[u8], limit: u64) -> std::io::Result<Cow<[u8]>> {
+    if !body.starts_with(&GZIP_MAGIC) {
+        return Ok(Cow::Borrowed(body));
+    }
+
+    let mut contents = vec![];
+    GzDecoder::new(body)
+        .take(limit + 1)
+        .read_to_end(&mut contents)?;
+    if contents.len() as u64 > limit {
+        return Err(Error::new(
+            ErrorKind::InvalidData,
+            format!("decompressed body exceeds {limit} bytes"),
+        ));
+    }
+    Ok(Cow::Owned(contents))
+}
+
+/// Wait for the indexer to process the upload
+async fn send(uploader: &Uploader, upload: Upload) -> HttpResponse {
+    let (reply, response) = oneshot::channel();
+    if uploader
+        .sender
+        .send(UploadRequest { upload, reply })
+        .await
+        .is_err()
+    {
+        return HttpResponse::ServiceUnavailable().finish();
+    }
+
+    match response.await {
+        Ok(Ok(status)) => HttpResponse::Ok()
+            .content_type(ContentType::json())
+            .body(serde_json::to_string_pretty(&status).expect("upload status")),
+        Ok(Err(e)) => HttpResponse::UnprocessableEntity().body(e.to_string()),
+        Err(_) => HttpResponse::ServiceUnavailable().finish(),
+    }
+}
+
+fn bad_request<T: Into<String>>(msg: T) -> HttpResponse {
+    HttpResponse::BadRequest().body(msg.into())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use flate2::{write::GzEncoder, Compression};
+    use std::io::Write;
+
+    #[test]
+    fn gzip_body() -> anyhow::Result<()> {
+        let body = br#"{"protocol_state": {}}"#;
+        assert_eq!(decompress(&gzip(body)?)?.as_ref(), body);
+        assert_eq!(decompress(body)?.as_ref(), body);
+        assert!(decompress(&GZIP_MAGIC).is_err());
+
+        // decompressed size limit
+        let compressed = gzip(&[0; 1024])?;
+        assert_eq!(decompress_with_limit(&compressed, 1024)?.len(), 1024);
+        assert!(decompress_with_limit(&compressed, 1023).is_err());
+        Ok(())
+    }
+
+    fn gzip(body: &[u8]) -> anyhow::Result<Vec<u8>> {
+        let mut encoder = GzEncoder::new(vec![], Compression::fast());
+        encoder.write_all(body)?;
+        Ok(encoder.finish()?)
+    }
+
+    #[test]
+    fn authorization_header() {
+        assert_eq!(bearer_token("Bearer secret"), Some("secret"));
+        assert_eq!(bearer_token("bearer  secret "), Some("secret"));
+        assert_eq!(bearer_token("Basic c2VjcmV0"), None);
+        assert_eq!(bearer_token("secret"), None);
+    }
+}
@@ -0,0 +1,44 @@
+use crate::{store::IndexerStore, web::NetworkParams};
+use actix_web::{
+    get,
+    http::header::ContentType,
+    web::{self, Data},
+    HttpResponse,
+};
+use serde::Deserialize;
+use std::sync::Arc;
+
+#[derive(Deserialize)]
+struct Params {
+    /// `json` (default) or `dot`
+    format: Option<String>,
+}
+
+/// Current witness tree as JSON or Graphviz DOT
+#[get("/witness-tree")]
+pub async fn get_witness_tree(
+    store: Data<Arc<IndexerStore>>,
+    network: web::Query<NetworkParams>,
+    params: web::Query<Params>,
+) -> HttpResponse {
+    let store = match network.store(&store) {
+        Ok(store) => store,
+        Err(e) => return HttpResponse::BadRequest().body(e.to_string()),
+    };
+    let Some(witness_tree) = store.get_witness_tree().await else {
+        return HttpResponse::NotFound().finish();
+    };
+
+    match params.format.as_deref() {
+        Some("dot") => HttpResponse::Ok()
+            .content_type("text/vnd.graphviz")
+            .body(witness_tree.to_dot()),
+        None | Some("json") => {
+            let body = serde_json::to_string_pretty(&witness_tree).expect("witness tree");
+            HttpResponse::Ok()
+                .content_type(ContentType::json())
+                .body(body)
+        }
+        Some(_) => HttpResponse::BadRequest().finish(),
+    }
+}
@@ -1,2 +1,4 @@
 mod parser;
+mod source;
 mod store;
+mod verify;
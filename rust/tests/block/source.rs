@@ -0,0 +1,173 @@
+use flate2::{write::GzEncoder, Compression};
+use mina_indexer::{
+    block::{
+        precomputed::{PcbVersion, PrecomputedBlock},
+        source::{BlockSource, NdjsonTail},
+    },
+    constants::MAINNET_TRANSITION_FRONTIER_K,
+    state::IndexerState,
+};
+use std::{
+    fs::{File, OpenOptions},
+    io::Write,
+    path::{Path, PathBuf},
+};
+
+const BLOCKS_DIR: &str = "./tests/data/sequential_blocks";
+const HASH_105489: &str = "3NK4huLvUDiL4XuCUcyrWCKynmvhqfKsx5h2MfBXVVUq2Qwzi5uT";
+const HASH_105490: &str = "3NKxEA9gztvEGxL4uk4eTncZAxuRmMsB8n81UkeAMevUjMbLHmkC";
+const HASH_105491: &str = "3NKizDx3nnhXha2WqHDNUvJk9jW7GsonsEGYs26tCPW2Wow1ZoR3";
+
+fn block_path(height: u32, state_hash: &str) -> PathBuf {
+    PathBuf::from(BLOCKS_DIR).join(format!("mainnet-{height}-{state_hash}.json"))
+}
+
+fn state_hashes(blocks: &[(PrecomputedBlock, u64)]) -> Vec<String> {
+    blocks
+        .iter()
+        .map(|(block, _)| block.state_hash().0)
+        .collect()
+}
+
+/// Reads the source's blocks, in batches of `batch_size`
+fn read_blocks(
+    source: &BlockSource,
+    batch_size: usize,
+) -> anyhow::Result<Vec<(PrecomputedBlock, u64)>> {
+    let mut blocks = vec![];
+    source.read_batches(batch_size, MAINNET_TRANSITION_FRONTIER_K, |batch| {
+        blocks.extend(batch);
+        Ok(())
+    })?;
+    Ok(blocks)
+}
+
+fn write_tar_gz(path: &Path, files: &[(PathBuf, &str)]) -> anyhow::Result<()> {
+    let mut archive = tar::Builder::new(GzEncoder::new(File::create(path)?, Compression::fast()));
+    for (file, name) in files {
+        archive.append_path_with_name(file, name)?;
+    }
+    archive.into_inner()?.finish()?;
+    Ok(())
+}
+
+/// Compact single-line JSON of the block file
+fn ndjson_line(path: &Path) -> anyhow::Result<String> {
+    let value: serde_json::Value = serde_json::from_slice(&std::fs::read(path)?)?;
+    Ok(serde_json::to_string(&value)?)
+}
+
+#[test]
+fn tarball_with_arbitrary_names() -> anyhow::Result<()> {
+    let tmp_dir = tempfile::TempDir::with_prefix("block-source-tar")?;
+    let tarball = tmp_dir.path().join("blocks.tar.gz");
+    write_tar_gz(
+        &tarball,
+        &[
+            (block_path(105490, HASH_105490), "b.json"),
+            (block_path(105489, HASH_105489), "a.json"),
+            (
+                block_path(105491, HASH_105491),
+                &format!("block_{HASH_105491}.json"),
+            ),
+        ],
+    )?;
+
+    let source = BlockSource::from_path(&tarball)?;
+    assert!(matches!(source, BlockSource::Tar { gzip: true, .. }));
+
+    // unnamed blocks are named by their children, in height order, also when
+    // the children are read in a later batch
+    let blocks = read_blocks(&source, 1)?;
+    assert_eq!(
+        state_hashes(&blocks),
+        vec![HASH_105489, HASH_105490, HASH_105491]
+    );
+    assert_eq!(
+        state_hashes(&read_blocks(&source, 1000)?),
+        state_hashes(&blocks)
+    );
+
+    // same as the canonically named block files
+    for (block, _) in blocks.iter() {
+        let expected = PrecomputedBlock::parse_file(
+            &block_path(block.blockchain_length(), &block.state_hash().0),
+            PcbVersion::V1,
+        )?;
+        assert_eq!(*block, expected);
+    }
+    Ok(())
+}
+
+#[test]
+fn ndjson_log() -> anyhow::Result<()> {
+    let tmp_dir = tempfile::TempDir::with_prefix("block-source-ndjson")?;
+    let log = tmp_dir.path().join("precomputed_blocks.log");
+
+    let lines = [
+        ndjson_line(&block_path(105489, HASH_105489))?,
+        r#"{"message": "not a block"}"#.to_string(),
+        format!(
+            r#"{{"state_hash": "{HASH_105490}", "precomputed_block": {}}}"#,
+            ndjson_line(&block_path(105490, HASH_105490))?
+        ),
+        ndjson_line(&block_path(105491, HASH_105491))?,
+    ];
+    std::fs::write(&log, lines.join("\n") + "\n")?;
+
+    // the unnamed tip is skipped
+    let blocks = read_blocks(&BlockSource::from_path(&log)?, 2)?;
+    assert_eq!(state_hashes(&blocks), vec![HASH_105489, HASH_105490]);
+
+    // tail the log as it's written
+    std::fs::write(&log, "")?;
+    let mut tail = NdjsonTail::from_start(&log, MAINNET_TRANSITION_FRONTIER_K);
+    let mut file = OpenOptions::new().append(true).open(&log)?;
+
+    // unnamed block waits for its child
+    writeln!(file, "{}", lines[0])?;
+    assert!(tail.poll()?.is_empty());
+
+    // partially written line
+    let (head, rest) = lines[2].split_at(100);
+    write!(file, "{head}")?;
+    assert!(tail.poll()?.is_empty());
+
+    writeln!(file, "{rest}")?;
+    assert_eq!(state_hashes(&tail.poll()?), vec![HASH_105489, HASH_105490]);
+    Ok(())
+}
+
+#[tokio::test]
+async fn add_block_source() -> anyhow::Result<()> {
+    let store_dir = tempfile::TempDir::with_prefix("block-source-state")?;
+    let tmp_dir = tempfile::TempDir::with_prefix("block-source-dir")?;
+    std::fs::copy(
+        block_path(105490, HASH_105490),
+        tmp_dir.path().join("some block.json"),
+    )?;
+    std::fs::copy(
+        block_path(105491, HASH_105491),
+        tmp_dir.path().join(format!("{HASH_105491}.json")),
+    )?;
+
+    let root_path = block_path(105489, HASH_105489);
+    let root_block = PrecomputedBlock::parse_file(&root_path, PcbVersion::V1)?;
+    let mut state = IndexerState::new_testing(
+        &root_block,
+        root_path.metadata()?.len(),
+        None,
+        Some(store_dir.path()),
+        None,
+        None,
+        None,
+    )?;
+
+    let source = BlockSource::from_path(tmp_dir.path())?;
+    assert_eq!(state.add_block_source(&source)?, 2);
+    assert_eq!(state.best_tip_block().state_hash.0, HASH_105491);
+
+    // already present blocks are skipped
+    assert_eq!(state.add_block_source(&source)?, 0);
+    Ok(())
+}
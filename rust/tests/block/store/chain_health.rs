@@ -0,0 +1,80 @@
+use crate::helpers::{state::*, store::*};
+use mina_indexer::{
+    block::{parser::BlockParser, precomputed::PcbVersion, store::BlockStore},
+    chain::health::ChainHealthStore,
+    constants::*,
+};
+use std::path::PathBuf;
+
+#[tokio::test]
+async fn epoch_and_window_chain_health() -> anyhow::Result<()> {
+    let store_dir = setup_new_db_dir("chain-health-db")?;
+    let block_dir = &PathBuf::from("./tests/data/canonical_chain_discovery/contiguous");
+
+    let mut state = mainnet_genesis_state(store_dir.as_ref())?;
+    let mut bp = BlockParser::new_with_canonical_chain_discovery(
+        block_dir,
+        PcbVersion::V1,
+        MAINNET_CANONICAL_THRESHOLD,
+        false,
+        BLOCK_REPORTING_FREQ_NUM,
+    )
+    .await?;
+    state.add_blocks(&mut bp).await?;
+
+    let store = state.indexer_store.as_ref().unwrap();
+    let best_tip = store.get_best_block()?.unwrap();
+    let best_slot = best_tip.global_slot_since_genesis();
+
+    // epoch 0 health covers the elapsed slots
+    let health = store.get_epoch_chain_health(0, None)?;
+    assert_eq!(health.start_slot, 0);
+    assert_eq!(health.end_slot, best_slot);
+    assert_eq!(health.num_slots, best_slot + 1);
+    assert_eq!(
+        health.num_canonical_blocks,
+        best_tip.blockchain_length(),
+        "one canonical block per height"
+    );
+    assert_eq!(health.num_slots_filled, health.num_canonical_blocks);
+    assert_eq!(
+        health.num_blocks,
+        health.num_canonical_blocks + health.num_orphaned_blocks
+    );
+    assert_eq!(
+        health.min_window_density.unwrap().last,
+        best_tip.min_window_density()
+    );
+
+    let block_time = health.block_time.unwrap();
+    assert!(block_time.min > 0);
+    assert!(block_time.min <= block_time.median && block_time.median <= block_time.p90);
+    assert_eq!(
+        block_time.min % MAINNET_BLOCK_SLOT_TIME_MILLIS as i64,
+        0,
+        "block times are multiples of the slot time"
+    );
+
+    // epochs past the max global slot error
+    assert!(store.get_epoch_chain_health(u32::MAX, None).is_err());
+
+    // windows partition the slots
+    let series = store.get_chain_health_time_series(0, u32::MAX, 10, 10, usize::MAX, None)?;
+    assert_eq!(series.len() as u32, best_slot / 10 + 1);
+    assert_eq!(
+        series.iter().map(|w| w.num_slots_filled).sum::<u32>(),
+        health.num_slots_filled
+    );
+    assert_eq!(
+        series.iter().map(|w| w.num_orphaned_blocks).sum::<u32>(),
+        health.num_orphaned_blocks
+    );
+
+    // the limit caps the number of windows
+    let series = store.get_chain_health_time_series(0, u32::MAX, 10, 5, 3, None)?;
+    assert_eq!(
+        series.iter().map(|w| w.start_slot).collect::<Vec<_>>(),
+        vec![0, 5, 10]
+    );
+    Ok(())
+}
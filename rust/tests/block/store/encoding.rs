@@ -0,0 +1,71 @@
+use crate::helpers::store::*;
+use mina_indexer::{
+    block::{parser::BlockParser, precomputed::PrecomputedBlock, store::BlockStore},
+    store::{column_families::ColumnFamilyHelpers, IndexerStore},
+    utility::store::block::is_json_block_value,
+};
+use std::path::PathBuf;
+
+#[tokio::test]
+async fn migrate_json_blocks() -> anyhow::Result<()> {
+    let store_dir = setup_new_db_dir("block-encoding-db")?;
+    let blocks_dir = PathBuf::from("./tests/data/sequential_blocks");
+    let db = IndexerStore::new(store_dir.path())?;
+    let mut bp = BlockParser::new_testing(&blocks_dir)?;
+
+    let mut blocks = vec![];
+    while let Some((block, block_bytes)) = bp.next_block().await? {
+        let block: PrecomputedBlock = block.into();
+        db.add_block(&block, block_bytes)?;
+        blocks.push((block, block_bytes));
+    }
+
+    // blocks are stored in the compact encoding
+    for (block, block_bytes) in blocks.iter() {
+        let state_hash = block.state_hash();
+        let value = db
+            .database
+            .get_cf(db.blocks_cf(), state_hash.0.as_bytes())?
+            .unwrap();
+
+        assert!(!is_json_block_value(&value));
+        assert_eq!(
+            db.get_block(&state_hash)?,
+            Some((block.clone(), *block_bytes))
+        );
+    }
+
+    // overwrite with the legacy JSON encoding
+    for (block, block_bytes) in blocks.iter() {
+        let mut value = block_bytes.to_be_bytes().to_vec();
+        value.append(&mut serde_json::to_vec(block)?);
+        db.database
+            .put_cf(db.blocks_cf(), block.state_hash().0.as_bytes(), value)?;
+    }
+
+    // legacy blocks are still readable
+    let (block, block_bytes) = &blocks[0];
+    assert_eq!(
+        db.get_block(&block.state_hash())?,
+        Some((block.clone(), *block_bytes))
+    );
+
+    // block creator index entries are untouched
+    assert_eq!(db.migrate_block_encoding()? as usize, blocks.len());
+    assert_eq!(db.migrate_block_encoding()?, 0);
+
+    for (block, block_bytes) in blocks.iter() {
+        let state_hash = block.state_hash();
+        let value = db
+            .database
+            .get_cf(db.blocks_cf(), state_hash.0.as_bytes())?
+            .unwrap();
+
+        assert!(!is_json_block_value(&value));
+        assert_eq!(
+            db.get_block(&state_hash)?,
+            Some((block.clone(), *block_bytes))
+        );
+    }
+    Ok(())
+}
@@ -5,4 +5,10 @@ mod blocks_at_height;
 #[cfg(all(test, feature = "tier2"))]
 mod blocks_at_slot;
 #[cfg(all(test, feature = "tier2"))]
+mod chain_health;
+#[cfg(all(test, feature = "tier2"))]
+mod encoding;
+#[cfg(all(test, feature = "tier2"))]
 mod genesis;
+#[cfg(all(test, feature = "tier2"))]
+mod multi_get;
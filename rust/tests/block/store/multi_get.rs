@@ -0,0 +1,89 @@
+use crate::helpers::store::*;
+use mina_indexer::{
+    base::public_key::PublicKey,
+    block::{
+        parser::BlockParser,
+        precomputed::{PcbVersion, PrecomputedBlock},
+        store::BlockStore,
+    },
+    command::{internal::store::InternalCommandStore, store::UserCommandStore},
+    constants::*,
+    snark_work::store::SnarkStore,
+    store::{multi_get::MultiGetStore, username::UsernameStore, IndexerStore},
+};
+use std::path::PathBuf;
+
+#[tokio::test]
+async fn multi_get_matches_get() -> anyhow::Result<()> {
+    let store_dir = setup_new_db_dir("blocks-multi-get")?;
+    let block_dir = &PathBuf::from("./tests/data/sequential_blocks");
+
+    let db = IndexerStore::new(store_dir.path())?;
+    let mut bp = BlockParser::new_with_canonical_chain_discovery(
+        block_dir,
+        PcbVersion::V1,
+        MAINNET_CANONICAL_THRESHOLD,
+        false,
+        BLOCK_REPORTING_FREQ_NUM,
+    )
+    .await?;
+
+    let mut state_hashes = vec![];
+    let mut creators = vec![];
+    while let Some((block, block_bytes)) = bp.next_block().await? {
+        let block: PrecomputedBlock = block.into();
+        db.add_block(&block, block_bytes)?;
+
+        state_hashes.push(block.state_hash());
+        creators.push(block.block_creator());
+    }
+
+    // unknown keys are missing
+    state_hashes.push(MAINNET_GENESIS_PREV_STATE_HASH.into());
+    creators.push(PublicKey::default());
+
+    let blocks = db.get_blocks(&state_hashes)?;
+    assert_eq!(blocks.len(), state_hashes.len());
+    for (state_hash, block) in state_hashes.iter().zip(blocks) {
+        assert_eq!(block, db.get_block(state_hash)?.map(|(block, _)| block));
+    }
+
+    let usernames = db.get_usernames(&creators)?;
+    let counts = db.get_pk_counts(&creators, None)?;
+    assert_eq!(counts.len(), creators.len());
+
+    for ((pk, username), counts) in creators.iter().zip(usernames).zip(counts) {
+        assert_eq!(username, db.get_username(pk)?);
+        assert_eq!(
+            counts.epoch_num_blocks,
+            db.get_block_production_pk_epoch_count(pk, None)?
+        );
+        assert_eq!(
+            counts.total_num_blocks,
+            db.get_block_production_pk_total_count(pk)?
+        );
+        assert_eq!(
+            counts.epoch_num_snarks,
+            db.get_snarks_pk_epoch_count(pk, None)?
+        );
+        assert_eq!(counts.total_num_snarks, db.get_snarks_pk_total_count(pk)?);
+        assert_eq!(
+            counts.epoch_num_user_commands,
+            db.get_user_commands_pk_epoch_count(pk, None)?
+        );
+        assert_eq!(
+            counts.total_num_user_commands,
+            db.get_user_commands_pk_total_count(pk)?
+        );
+        assert_eq!(
+            counts.epoch_num_internal_commands,
+            db.get_internal_commands_pk_epoch_count(pk, None)?
+        );
+        assert_eq!(
+            counts.total_num_internal_commands,
+            db.get_internal_commands_pk_total_count(pk)?
+        );
+    }
+
+    Ok(())
+}
@@ -0,0 +1,58 @@
+use mina_indexer::block::{
+    precomputed::{PcbVersion, PrecomputedBlock},
+    verify::{verify_blocks_dir, BlockFileIssue},
+};
+use std::path::PathBuf;
+
+#[test]
+fn mislabeled_block_files() -> anyhow::Result<()> {
+    let blocks_dir = tempfile::TempDir::with_prefix("verify-blocks")?;
+    let source_dir = PathBuf::from("./tests/data/sequential_blocks");
+
+    let root = "mainnet-105489-3NK4huLvUDiL4XuCUcyrWCKynmvhqfKsx5h2MfBXVVUq2Qwzi5uT.json";
+    let child = "mainnet-105490-3NKxEA9gztvEGxL4uk4eTncZAxuRmMsB8n81UkeAMevUjMbLHmkC.json";
+    for name in [root, child] {
+        std::fs::copy(source_dir.join(name), blocks_dir.path().join(name))?;
+    }
+
+    // correctly named files
+    let verification = verify_blocks_dir(blocks_dir.path())?;
+    assert!(verification.is_ok());
+    assert_eq!(verification.num_files, 2);
+    assert_eq!(verification.num_confirmed, 1);
+    assert!(verification.missing_parents.is_empty());
+
+    // child contents under another state hash & height
+    let mislabeled = blocks_dir
+        .path()
+        .join("mainnet-105491-3NKizDx3nnhXha2WqHDNUvJk9jW7GsonsEGYs26tCPW2Wow1ZoR3.json");
+    std::fs::copy(source_dir.join(child), &mislabeled)?;
+
+    let verification = verify_blocks_dir(blocks_dir.path())?;
+    assert!(!verification.is_ok());
+    assert_eq!(verification.num_files, 3);
+    assert_eq!(verification.issues.len(), 2);
+
+    let report = verification
+        .issues
+        .iter()
+        .find(|report| report.path == mislabeled)
+        .unwrap();
+    assert_eq!(
+        report.issues,
+        vec![
+            BlockFileIssue::HeightMismatch {
+                file_name: 105491,
+                protocol_state: 105490,
+            },
+            BlockFileIssue::DuplicateProtocolState {
+                path: blocks_dir.path().join(child),
+            },
+        ]
+    );
+
+    // the indexer rejects the mislabeled file
+    assert!(PrecomputedBlock::parse_file(&mislabeled, PcbVersion::V1).is_err());
+    assert!(PrecomputedBlock::parse_file_unverified(&mislabeled, PcbVersion::V1).is_ok());
+    Ok(())
+}
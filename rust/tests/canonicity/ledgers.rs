@@ -3,6 +3,7 @@ use mina_indexer::{
     base::public_key::PublicKey,
     block::{parser::BlockParser, store::BlockStore},
     canonicity::store::CanonicityStore,
+    chain::profile::ProtocolParams,
     ledger::{diff::LedgerDiff, store::staged::StagedLedgerStore, token::TokenAddress},
 };
 use std::path::PathBuf;
@@ -20,7 +21,9 @@ async fn test() -> anyhow::Result<()> {
     let indexer_store = state.indexer_store.as_ref().unwrap();
     let mut ledger_diff = indexer_store
         .get_staged_ledger_at_block_height(1, false)?
-        .unwrap();
+        .unwrap()
+        .as_ref()
+        .clone();
 
     for n in 1..=3 {
         let state_hash = indexer_store.get_canonical_hash_at_height(n)?.unwrap();
@@ -29,9 +32,12 @@ async fn test() -> anyhow::Result<()> {
             .get_staged_ledger_at_state_hash(&state_hash, false)?
             .unwrap();
 
-        ledger_diff._apply_diff(&LedgerDiff::from_precomputed(&block))?;
+        ledger_diff._apply_diff(&LedgerDiff::from_precomputed(
+            &block,
+            &ProtocolParams::default(),
+        ))?;
 
-        if ledger != ledger_diff {
+        if *ledger != ledger_diff {
             let mut keys: Vec<&PublicKey> = ledger
                 .tokens
                 .get(&TokenAddress::default())
@@ -106,8 +112,74 @@ async fn test() -> anyhow::Result<()> {
             }
         }
 
-        assert!(ledger == ledger_diff, "Different ledgers!");
+        assert!(*ledger == ledger_diff, "Different ledgers!");
     }
 
     Ok(())
 }
+
+#[tokio::test]
+async fn materialized() -> anyhow::Result<()> {
+    let store_dir = setup_new_db_dir("./test_materialized_ledgers_store")?;
+    let block_dir = PathBuf::from("./tests/data/canonical_chain_discovery/contiguous");
+
+    let mut state = mainnet_genesis_state(store_dir.as_ref())?;
+    let mut block_parser = BlockParser::new_testing(&block_dir)?;
+
+    state.add_blocks(&mut block_parser).await?;
+
+    // persist the ledger at height 3
+    let indexer_store = state.indexer_store.as_ref().unwrap();
+    let ledger_3 = indexer_store
+        .get_staged_ledger_at_block_height(3, true)?
+        .unwrap();
+
+    assert_eq!(
+        indexer_store.get_nearest_staged_ledger_height(3)?.unwrap(),
+        (3, indexer_store.get_canonical_hash_at_height(3)?.unwrap())
+    );
+
+    // materialize the ledger at height 2
+    let state_hash = indexer_store.get_canonical_hash_at_height(2)?.unwrap();
+    let block = indexer_store.get_block(&state_hash)?.unwrap().0;
+    let mut ledger_2 = indexer_store
+        .get_staged_ledger_at_block_height(2, false)?
+        .unwrap()
+        .as_ref()
+        .clone();
+
+    let mut expected = indexer_store
+        .get_staged_ledger_at_block_height(1, false)?
+        .unwrap()
+        .as_ref()
+        .clone();
+    expected._apply_diff(&LedgerDiff::from_precomputed(
+        &block,
+        &ProtocolParams::default(),
+    ))?;
+    assert!(ledger_2 == expected, "Different ledgers!");
+
+    // persist the ledger at height 1, the nearest ledgers are found on
+    // either side, preferring the lower height when equidistant
+    indexer_store.get_staged_ledger_at_block_height(1, true)?;
+    assert_eq!(
+        indexer_store.get_nearest_staged_ledger_height(2)?.unwrap(),
+        (1, indexer_store.get_canonical_hash_at_height(1)?.unwrap())
+    );
+    assert_eq!(
+        indexer_store.get_nearest_staged_ledger_height(4)?.unwrap(),
+        (3, indexer_store.get_canonical_hash_at_height(3)?.unwrap())
+    );
+
+    // apply the next block's diff
+    let state_hash = indexer_store.get_canonical_hash_at_height(3)?.unwrap();
+    let block = indexer_store.get_block(&state_hash)?.unwrap().0;
+
+    ledger_2._apply_diff(&LedgerDiff::from_precomputed(
+        &block,
+        &ProtocolParams::default(),
+    ))?;
+    assert!(ledger_2 == *ledger_3, "Different ledgers!");
+
+    Ok(())
+}
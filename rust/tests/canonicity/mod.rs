@@ -3,3 +3,5 @@ mod blocks;
 mod chain_discovery;
 #[cfg(all(test, feature = "tier2"))]
 mod ledgers;
+#[cfg(all(test, feature = "tier2"))]
+mod reorgs;
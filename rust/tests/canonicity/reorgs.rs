@@ -0,0 +1,202 @@
+use crate::helpers::store::*;
+use mina_indexer::{
+    block::{
+        parser::BlockParser,
+        precomputed::{PcbVersion, PrecomputedBlock},
+        store::BlockStore,
+    },
+    canonicity::{reorg::ReorgStore, store::CanonicityStore, Canonicity},
+    constants::*,
+    ledger::store::balance_history::{BalanceChange, BalanceHistoryStore},
+    store::IndexerStore,
+};
+use speedb::{Direction, IteratorMode};
+use std::path::PathBuf;
+
+#[tokio::test]
+async fn best_chain_reorg() -> anyhow::Result<()> {
+    let store_dir = setup_new_db_dir("canonicity-reorgs")?;
+    let blocks_dir = &PathBuf::from("./tests/data/sequential_blocks");
+    let store = IndexerStore::new(store_dir.path())?;
+    let mut bp = BlockParser::new_with_canonical_chain_discovery(
+        blocks_dir,
+        PcbVersion::V1,
+        MAINNET_CANONICAL_THRESHOLD,
+        false,
+        BLOCK_REPORTING_FREQ_NUM,
+    )
+    .await?;
+
+    while let Some((block, block_bytes)) = bp.next_block().await? {
+        let block: PrecomputedBlock = block.into();
+        store.add_block(&block, block_bytes)?;
+    }
+
+    // common ancestor at height 105493
+    let common_ancestor = "3NKakum3B2Tigw9TSsxwvXvV3x8L2LvrJ3yXFLEAJDMZu2vkn7db";
+    let old_best_tip = "3NL4zEKGtSokPMy29pGv7tm8uJt8GitM9JqrRg6Lkf3tRdnwrjpF";
+    let new_best_tip = "3NKBHgd9qR31HcnBRmyx5LDgXxhbmdVrfSbxtT8VJXBpQtdTsMev";
+
+    // extending the best chain is not a reorg
+    let mut subscriber = store.subscribe_reorgs();
+    store.set_best_block(&common_ancestor.into())?;
+    store.set_best_block(&old_best_tip.into())?;
+    assert!(store.get_reorgs(1, 0, usize::MAX, None)?.is_empty());
+
+    // switch to the other branch
+    store.set_best_block(&new_best_tip.into())?;
+
+    let reorgs = store.get_reorgs(1, 0, usize::MAX, None)?;
+    assert_eq!(reorgs.len(), 1);
+
+    // subscribers receive only the reorg
+    assert_eq!(subscriber.try_recv()?, reorgs[0]);
+    assert!(subscriber.try_recv().is_err());
+
+    let reorg = &reorgs[0];
+    assert_eq!(reorg.old_best_tip.0, old_best_tip);
+    assert_eq!(reorg.new_best_tip.0, new_best_tip);
+    assert_eq!(reorg.new_best_tip_height, 105501);
+    assert_eq!(reorg.common_ancestor.0, common_ancestor);
+    assert_eq!(reorg.common_ancestor_height, 105493);
+    assert_eq!(reorg.depth, 2);
+    assert_eq!(
+        reorg.orphaned,
+        vec![
+            "3NKXsaznJ6WdyA4PHfXxn25RzVanzQsNMZrxjidbhoBug8R4LZDy".into(),
+            old_best_tip.into(),
+        ]
+    );
+    assert_eq!(reorg.canonical.len(), 8);
+    assert_eq!(reorg.canonical.last().unwrap().0, new_best_tip);
+
+    // orphaned blocks were flipped
+    for state_hash in reorg.orphaned.iter() {
+        assert_eq!(
+            store.get_block_canonicity(state_hash)?,
+            Some(Canonicity::Orphaned)
+        );
+    }
+
+    // filters
+    assert!(store.get_reorgs(3, 0, usize::MAX, None)?.is_empty());
+    assert!(store.get_reorgs(1, 105502, usize::MAX, None)?.is_empty());
+    assert_eq!(store.get_reorgs(2, 105501, 1, None)?, reorgs);
+    Ok(())
+}
+
+#[tokio::test]
+async fn reorg_balance_history() -> anyhow::Result<()> {
+    let store_dir = setup_new_db_dir("canonicity-reorg-balance-history")?;
+    let blocks_dir = &PathBuf::from("./tests/data/sequential_blocks");
+    let store = IndexerStore::new(store_dir.path())?;
+    let mut bp = BlockParser::new_with_canonical_chain_discovery(
+        blocks_dir,
+        PcbVersion::V1,
+        MAINNET_CANONICAL_THRESHOLD,
+        false,
+        BLOCK_REPORTING_FREQ_NUM,
+    )
+    .await?;
+
+    while let Some((block, block_bytes)) = bp.next_block().await? {
+        let block: PrecomputedBlock = block.into();
+        store.add_block(&block, block_bytes)?;
+    }
+
+    // apply the old branch, then reorg onto the longer new branch
+    let old_best_tip = "3NL4zEKGtSokPMy29pGv7tm8uJt8GitM9JqrRg6Lkf3tRdnwrjpF";
+    let new_best_tip = "3NKBHgd9qR31HcnBRmyx5LDgXxhbmdVrfSbxtT8VJXBpQtdTsMev";
+
+    store.set_best_block(&"3NKakum3B2Tigw9TSsxwvXvV3x8L2LvrJ3yXFLEAJDMZu2vkn7db".into())?;
+    store.set_best_block(&old_best_tip.into())?;
+
+    // reorg updates carry each block's own height
+    let updates = store.reorg_blocks(&old_best_tip.into(), &new_best_tip.into())?;
+    assert_eq!(updates.apply.len(), 8);
+    assert_eq!(updates.unapply.len(), 2);
+    for update in updates.apply.iter().chain(updates.unapply.iter()) {
+        assert_eq!(
+            store.get_block_height(&update.state_hash)?,
+            Some(update.blockchain_length)
+        );
+    }
+
+    store.set_best_block(&new_best_tip.into())?;
+
+    let reorg = store.get_reorgs(1, 0, usize::MAX, None)?.remove(0);
+    let changes: Vec<BalanceChange> = store
+        .balance_history_iterator(IteratorMode::Start)
+        .flatten()
+        .map(|(_, bytes)| serde_json::from_slice(&bytes))
+        .collect::<Result<_, _>>()?;
+
+    // every change is recorded at its own block's height
+    for change in changes.iter() {
+        assert_eq!(
+            store.get_block_height(&change.state_hash)?,
+            Some(change.blockchain_length),
+            "{change:?}"
+        );
+        assert_eq!(
+            store
+                .get_balance_change(&change.public_key, &change.token, change.blockchain_length)?
+                .as_ref(),
+            Some(change)
+        );
+    }
+
+    // history is limited in either height direction
+    let change = changes.last().unwrap();
+    let history = store.get_balance_history(
+        &change.public_key,
+        &change.token,
+        0,
+        u32::MAX,
+        usize::MAX,
+        Direction::Forward,
+        None,
+    )?;
+    assert_eq!(history.last(), Some(change));
+
+    let latest = store.get_balance_history(
+        &change.public_key,
+        &change.token,
+        0,
+        u32::MAX,
+        1,
+        Direction::Reverse,
+        None,
+    )?;
+    assert_eq!(latest, vec![change.clone()]);
+
+    let earliest = store.get_balance_history(
+        &change.public_key,
+        &change.token,
+        0,
+        u32::MAX,
+        1,
+        Direction::Forward,
+        None,
+    )?;
+    assert_eq!(earliest[..], history[..1]);
+
+    // orphaned blocks' changes were removed
+    for state_hash in reorg.orphaned.iter() {
+        assert!(changes
+            .iter()
+            .all(|change| &change.state_hash != state_hash));
+    }
+
+    // each newly canonical block's changes were recorded
+    for state_hash in reorg.canonical.iter() {
+        assert!(
+            changes
+                .iter()
+                .any(|change| &change.state_hash == state_hash),
+            "missing balance changes for {state_hash}"
+        );
+    }
+
+    Ok(())
+}
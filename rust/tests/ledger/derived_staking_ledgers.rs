@@ -0,0 +1,207 @@
+use crate::helpers::{state::*, store::*};
+use mina_indexer::{
+    base::state_hash::StateHash,
+    block::{
+        parser::BlockParser,
+        precomputed::{PcbVersion, PrecomputedBlock},
+        store::BlockStore,
+    },
+    constants::*,
+    ledger::{
+        staking::{StakingAccount, StakingLedger, StakingLedgerSource},
+        store::{staged::StagedLedgerStore, staking::StakingLedgerStore},
+        token::TokenAddress,
+        LedgerHash,
+    },
+};
+use serde_json::{json, Value};
+use std::path::{Path, PathBuf};
+
+#[tokio::test]
+async fn derive_genesis_staking_ledgers() -> anyhow::Result<()> {
+    let store_dir = setup_new_db_dir("derived-staking-ledgers-db")?;
+    let block_dir = &PathBuf::from("./tests/data/canonical_chain_discovery/contiguous");
+    let ledger_path = PathBuf::from(format!(
+        "./tests/data/staking_ledgers/mainnet-0-{MAINNET_GENESIS_LEDGER_HASH}.json"
+    ));
+
+    let mut state = mainnet_genesis_state(store_dir.as_ref())?;
+    let mut bp = BlockParser::new_with_canonical_chain_discovery(
+        block_dir,
+        PcbVersion::V1,
+        MAINNET_CANONICAL_THRESHOLD,
+        false,
+        BLOCK_REPORTING_FREQ_NUM,
+    )
+    .await?;
+
+    // ingest the blocks without any staking ledger files
+    state.add_blocks(&mut bp).await?;
+
+    let store = state.indexer_store.as_ref().unwrap();
+    let genesis_state_hash: StateHash = MAINNET_GENESIS_HASH.into();
+    let ledger_hash = LedgerHash::new_or_panic(MAINNET_GENESIS_LEDGER_HASH.to_string());
+
+    // epochs 0 & 1 both use the genesis ledger
+    let imported = StakingLedger::parse_file(&ledger_path, genesis_state_hash.clone()).await?;
+    for epoch in [0, 1] {
+        let derived = store
+            .get_staking_ledger(&ledger_hash, Some(epoch), Some(&genesis_state_hash))?
+            .unwrap();
+        assert_eq!(derived.source, StakingLedgerSource::Derived);
+        assert_eq!(derived.total_currency, imported.total_currency);
+        assert_eq!(derived.staking_ledger.len(), imported.staking_ledger.len());
+
+        for (pk, account) in &imported.staking_ledger {
+            let derived_account = derived.staking_ledger.get(pk).unwrap();
+            assert_eq!(derived_account.balance, account.balance, "{pk}");
+            assert_eq!(derived_account.delegate, account.delegate, "{pk}");
+            assert_eq!(derived_account.timing, account.timing, "{pk}");
+        }
+    }
+
+    assert_eq!(
+        store.get_staking_ledger_source(&ledger_hash)?,
+        Some(StakingLedgerSource::Derived)
+    );
+    Ok(())
+}
+
+/// Write a child of `parent` at the global slot, reusing the parent's
+/// protocol state with an empty staged ledger diff
+fn write_child_block(
+    dir: &Path,
+    parent: &Value,
+    parent_state_hash: &StateHash,
+    state_hash: &str,
+    global_slot: u32,
+    update: impl FnOnce(&mut Value),
+) -> anyhow::Result<(PathBuf, Value)> {
+    let mut block = parent.clone();
+    let height: u32 = block["protocol_state"]["body"]["consensus_state"]["blockchain_length"]
+        .as_str()
+        .unwrap()
+        .parse()?;
+    let slots_per_epoch = MAINNET_EPOCH_SLOT_COUNT;
+
+    block["protocol_state"]["previous_state_hash"] = json!(parent_state_hash.0);
+    let consensus_state = &mut block["protocol_state"]["body"]["consensus_state"];
+    consensus_state["blockchain_length"] = json!((height + 1).to_string());
+    consensus_state["epoch_count"] = json!((global_slot / slots_per_epoch).to_string());
+    consensus_state["curr_global_slot"]["slot_number"] = json!(global_slot.to_string());
+    consensus_state["global_slot_since_genesis"] = json!(global_slot.to_string());
+    block["staged_ledger_diff"] = json!({
+        "diff": [{
+            "completed_works": [],
+            "commands": [],
+            "coinbase": ["Zero"],
+            "internal_command_balances": []
+        }, null]
+    });
+    update(&mut block);
+
+    let path = dir.join(format!("mainnet-{}-{state_hash}.json", height + 1));
+    std::fs::write(&path, serde_json::to_vec(&block)?)?;
+    Ok((path, block))
+}
+
+#[tokio::test]
+async fn derive_staking_ledger_past_epoch_1() -> anyhow::Result<()> {
+    let store_dir = setup_new_db_dir("derived-staking-ledgers-epoch-3-db")?;
+    let blocks_dir = tempfile::TempDir::with_prefix("derived-staking-ledgers-blocks")?;
+    let block_dir = &PathBuf::from("./tests/data/canonical_chain_discovery/contiguous");
+
+    let mut state = mainnet_genesis_state(store_dir.as_ref())?;
+    let mut bp = BlockParser::new_with_canonical_chain_discovery(
+        block_dir,
+        PcbVersion::V1,
+        MAINNET_CANONICAL_THRESHOLD,
+        false,
+        BLOCK_REPORTING_FREQ_NUM,
+    )
+    .await?;
+    state.add_blocks(&mut bp).await?;
+
+    let store = state.indexer_store.as_ref().unwrap().clone();
+    let best_tip = store.get_best_block()?.unwrap();
+    let best_tip_path = std::fs::read_dir(block_dir)?
+        .flatten()
+        .map(|entry| entry.path())
+        .find(|path| path.to_str().unwrap().contains(&best_tip.state_hash().0))
+        .unwrap();
+    let best_tip_json: Value = serde_json::from_slice(&std::fs::read(best_tip_path)?)?;
+
+    // a snarked ledger which is the staged ledger of an earlier block
+    let proven_block = best_tip.previous_state_hash();
+    let snarked_ledger_hash = store.get_block_staged_ledger_hash(&proven_block)?.unwrap();
+    assert_ne!(
+        snarked_ledger_hash.0, MAINNET_GENESIS_LEDGER_HASH,
+        "the proven block's transactions changed the genesis ledger"
+    );
+
+    // the last block of epoch 1 proves the snarked ledger
+    let checkpoint: StateHash = "3NK4huLvUDiL4XuCUcyrWCKynmvhqfKsx5h2MfBXVVUq2Qwzi5uT".into();
+    let (checkpoint_path, checkpoint_json) = write_child_block(
+        blocks_dir.path(),
+        &best_tip_json,
+        &best_tip.state_hash(),
+        &checkpoint.0,
+        2 * MAINNET_EPOCH_SLOT_COUNT - 1,
+        |block| {
+            block["protocol_state"]["body"]["blockchain_state"]["snarked_ledger_hash"] =
+                json!(snarked_ledger_hash.0)
+        },
+    )?;
+
+    // the first block of epoch 2 chooses it as the epoch 3 staking ledger
+    let epoch_2_block = "3NKxEA9gztvEGxL4uk4eTncZAxuRmMsB8n81UkeAMevUjMbLHmkC";
+    let (epoch_2_path, _) = write_child_block(
+        blocks_dir.path(),
+        &checkpoint_json,
+        &checkpoint,
+        epoch_2_block,
+        2 * MAINNET_EPOCH_SLOT_COUNT,
+        |block| {
+            let next_epoch_data =
+                &mut block["protocol_state"]["body"]["consensus_state"]["next_epoch_data"];
+            next_epoch_data["ledger"]["hash"] = json!(snarked_ledger_hash.0);
+            next_epoch_data["start_checkpoint"] = json!(checkpoint.0);
+        },
+    )?;
+
+    for path in [checkpoint_path, epoch_2_path] {
+        let block = PrecomputedBlock::parse_file(&path, PcbVersion::V1)?;
+        let block_bytes = std::fs::metadata(&path)?.len();
+        assert!(state.block_pipeline(&block, block_bytes)?);
+    }
+    assert_eq!(store.get_best_block_hash()?.unwrap().0, epoch_2_block);
+
+    // the epoch 3 staking ledger is derived from the proven block's staged ledger
+    let genesis_state_hash: StateHash = MAINNET_GENESIS_HASH.into();
+    let derived = store
+        .get_staking_ledger(&snarked_ledger_hash, Some(3), Some(&genesis_state_hash))?
+        .unwrap();
+    assert_eq!(derived.epoch, 3);
+    assert_eq!(derived.source, StakingLedgerSource::Derived);
+
+    let staged_ledger = store
+        .get_staged_ledger_at_state_hash(&proven_block, false)?
+        .unwrap();
+    let token_ledger = staged_ledger
+        .get_token_ledger(&TokenAddress::default())
+        .unwrap();
+    assert_eq!(derived.staking_ledger.len(), token_ledger.accounts.len());
+    for (pk, account) in &token_ledger.accounts {
+        let expected =
+            StakingAccount::from_ledger_account(account.clone(), &store.protocol_params());
+        assert_eq!(derived.staking_ledger.get(pk), Some(&expected), "{pk}");
+    }
+
+    // the epoch 2 staking ledger is still the genesis ledger
+    let genesis_ledger_hash = LedgerHash::new_or_panic(MAINNET_GENESIS_LEDGER_HASH.to_string());
+    assert_eq!(
+        store.get_staking_ledger_hash_by_epoch(2, Some(&genesis_state_hash))?,
+        Some(genesis_ledger_hash)
+    );
+    Ok(())
+}
@@ -1,10 +1,22 @@
 #[cfg(all(test, feature = "tier2"))]
 mod best_ledger_balance_sorted_accounts;
 #[cfg(all(test, feature = "tier2"))]
+mod derived_staking_ledgers;
+#[cfg(all(test, feature = "tier2"))]
+mod producer_performance;
+#[cfg(all(test, feature = "tier2"))]
 mod staged_ledger_balance_sorted_accounts;
 #[cfg(all(test, feature = "tier2"))]
 mod staking_ledger_balance_sorted_accounts;
 #[cfg(all(test, feature = "tier2"))]
+mod staking_payouts;
+#[cfg(all(test, feature = "tier2"))]
+mod supply;
+#[cfg(all(test, feature = "tier2"))]
 mod token_ledger;
 #[cfg(all(test, feature = "tier2"))]
+mod vesting;
+#[cfg(all(test, feature = "tier2"))]
+mod wealth_distribution;
+#[cfg(all(test, feature = "tier2"))]
 mod zkapp_best_ledger_accounts;
@@ -0,0 +1,75 @@
+use crate::helpers::{state::*, store::*};
+use mina_indexer::{
+    block::{parser::BlockParser, precomputed::PcbVersion, store::BlockStore},
+    constants::*,
+    ledger::store::staking::StakingLedgerStore,
+};
+use std::path::PathBuf;
+
+#[tokio::test]
+async fn epoch_producer_performances() -> anyhow::Result<()> {
+    let store_dir = setup_new_db_dir("producer-performance-db")?;
+    let block_dir = &PathBuf::from("./tests/data/canonical_chain_discovery/contiguous");
+    let ledgers_dir = PathBuf::from("./tests/data/staking_ledgers");
+
+    let mut state = mainnet_genesis_state(store_dir.as_ref())?;
+    let mut bp = BlockParser::new_with_canonical_chain_discovery(
+        block_dir,
+        PcbVersion::V1,
+        MAINNET_CANONICAL_THRESHOLD,
+        false,
+        BLOCK_REPORTING_FREQ_NUM,
+    )
+    .await?;
+
+    // ingest the blocks & staking ledgers
+    state.add_blocks(&mut bp).await?;
+    state
+        .add_startup_staking_ledgers_to_store(&ledgers_dir)
+        .await?;
+
+    let store = state.indexer_store.as_ref().unwrap();
+    let epoch = 0;
+    let performances = store
+        .get_epoch_producer_performances(epoch, None, None)?
+        .unwrap();
+
+    // every canonical block is produced by a staked producer
+    let num_canonical: u32 = performances
+        .iter()
+        .map(|perf| perf.num_canonical_blocks_produced)
+        .sum();
+    assert_eq!(
+        num_canonical,
+        store.get_block_production_canonical_epoch_count(Some(epoch))?
+    );
+
+    // expected blocks are proportional to the stake share
+    let epoch_slots_produced = store.get_epoch_slots_produced_count(Some(epoch))?;
+    let total_share: f64 = performances.iter().map(|perf| perf.stake_share).sum();
+    assert!(total_share <= 1.0 + f64::EPSILON);
+
+    for perf in performances.iter() {
+        assert_eq!(perf.epoch_slots_produced, epoch_slots_produced);
+        assert_eq!(
+            perf.num_orphaned_blocks,
+            perf.num_blocks_produced - perf.num_canonical_blocks_produced
+        );
+        assert!(
+            (perf.expected_blocks - perf.stake_share * epoch_slots_produced as f64).abs() < 1e-9
+        );
+    }
+
+    // single producer report matches
+    let producer = performances
+        .iter()
+        .find(|perf| perf.num_canonical_blocks_produced > 0)
+        .unwrap();
+    assert_eq!(
+        store
+            .get_producer_performance(&producer.public_key, epoch, None)?
+            .as_ref(),
+        Some(producer)
+    );
+    Ok(())
+}
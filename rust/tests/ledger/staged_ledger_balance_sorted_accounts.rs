@@ -36,7 +36,7 @@ async fn check_staged_accounts() -> anyhow::Result<()> {
         .get_staged_ledger_at_state_hash(&state_hash, false)?
         .unwrap();
 
-    if best_ledger != staged_ledger {
+    if best_ledger != *staged_ledger {
         for (token, token_ledger) in staged_ledger.tokens.iter() {
             for (pk, staged_acct) in token_ledger.accounts.iter() {
                 let staged_ledger_acct = &store
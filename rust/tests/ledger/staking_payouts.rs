@@ -0,0 +1,88 @@
+use crate::helpers::{state::*, store::*};
+use mina_indexer::{
+    block::{parser::BlockParser, precomputed::PcbVersion, store::BlockStore},
+    canonicity::{store::CanonicityStore, Canonicity},
+    constants::*,
+    ledger::{staking::payouts::PayoutConfig, store::staking::StakingLedgerStore},
+    utility::store::common::state_hash_suffix,
+};
+use speedb::IteratorMode;
+use std::path::PathBuf;
+
+#[tokio::test]
+async fn epoch_payouts() -> anyhow::Result<()> {
+    let store_dir = setup_new_db_dir("staking-payouts-db")?;
+    let block_dir = &PathBuf::from("./tests/data/canonical_chain_discovery/contiguous");
+    let ledgers_dir = PathBuf::from("./tests/data/staking_ledgers");
+
+    let mut state = mainnet_genesis_state(store_dir.as_ref())?;
+    let mut bp = BlockParser::new_with_canonical_chain_discovery(
+        block_dir,
+        PcbVersion::V1,
+        MAINNET_CANONICAL_THRESHOLD,
+        false,
+        BLOCK_REPORTING_FREQ_NUM,
+    )
+    .await?;
+
+    // ingest the blocks & staking ledgers
+    state.add_blocks(&mut bp).await?;
+    state
+        .add_startup_staking_ledgers_to_store(&ledgers_dir)
+        .await?;
+
+    let store = state.indexer_store.as_ref().unwrap();
+    let config = PayoutConfig {
+        pool_fee: 5.0,
+        ..Default::default()
+    };
+
+    // canonical block producers with epoch 0 delegators
+    let mut num_checked = 0;
+    for (key, _) in store.blocks_height_iterator(IteratorMode::Start).flatten() {
+        let state_hash = state_hash_suffix(&key)?;
+        if store.get_block_canonicity(&state_hash)? != Some(Canonicity::Canonical) {
+            continue;
+        }
+
+        let producer = store.get_block_creator(&state_hash)?.unwrap();
+        let payouts = store
+            .get_epoch_payouts(&producer, 0, None, config.clone(), None)?
+            .unwrap();
+        if payouts.payouts.is_empty() {
+            continue;
+        }
+
+        // the producer's canonical blocks are included
+        assert!(payouts
+            .blocks
+            .iter()
+            .any(|block| block.state_hash == state_hash));
+
+        // every delegator's payout is its gross share minus the pool fee
+        let mut total_gross = 0;
+        for payout in payouts.payouts.iter() {
+            assert_eq!(payout.payout + payout.pool_fee, payout.gross);
+            if payout.public_key == producer {
+                assert_eq!(payout.pool_fee, 0);
+            }
+            total_gross += payout.gross;
+        }
+
+        // pro rata rounding loses at most a nanomina per delegator per block
+        let total_rewards: u64 = payouts
+            .blocks
+            .iter()
+            .map(|block| block.reward(&config))
+            .sum();
+        let max_dust = (2 * payouts.payouts.len() * payouts.blocks.len()) as u64;
+        assert_eq!(payouts.total_rewards, total_rewards);
+        assert!(total_gross <= total_rewards);
+        assert!(total_rewards - total_gross <= max_dust);
+
+        num_checked += 1;
+    }
+
+    assert!(num_checked > 0);
+    Ok(())
+}
@@ -0,0 +1,72 @@
+use crate::helpers::{state::*, store::*};
+use mina_indexer::{
+    block::{parser::BlockParser, precomputed::PcbVersion, store::BlockStore},
+    constants::*,
+    ledger::store::{best::BestLedgerStore, supply::SupplyStore},
+};
+use std::path::PathBuf;
+
+#[tokio::test]
+async fn best_tip_supply() -> anyhow::Result<()> {
+    let store_dir = setup_new_db_dir("supply-db")?;
+    let block_dir = &PathBuf::from("./tests/data/canonical_chain_discovery/contiguous");
+    let ledgers_dir = PathBuf::from("./tests/data/staking_ledgers");
+
+    let mut state = mainnet_genesis_state(store_dir.as_ref())?;
+    let mut bp = BlockParser::new_with_canonical_chain_discovery(
+        block_dir,
+        PcbVersion::V1,
+        MAINNET_CANONICAL_THRESHOLD,
+        false,
+        BLOCK_REPORTING_FREQ_NUM,
+    )
+    .await?;
+
+    // ingest the staking ledgers & blocks
+    state
+        .add_startup_staking_ledgers_to_store(&ledgers_dir)
+        .await?;
+    state.add_blocks(&mut bp).await?;
+
+    let store = state.indexer_store.as_ref().unwrap();
+    let best_tip = store.get_best_block()?.unwrap();
+    let supply = store.get_block_supply(&best_tip.state_hash())?.unwrap();
+
+    // locked supply matches the best ledger's timed accounts
+    let global_slot = best_tip.global_slot_since_genesis();
+    let best_ledger = store.get_best_ledger(false)?.unwrap();
+    assert!(supply.locked > 0);
+    assert_eq!(supply.locked, store.get_locked_supply(global_slot, None)?);
+    assert_eq!(supply.locked, best_ledger.time_locked_amount(global_slot).0);
+    assert_eq!(supply.total_currency, best_tip.total_currency());
+    assert_eq!(supply.circulating + supply.locked, supply.total_currency);
+    assert!(supply.staked.is_some());
+
+    // later unlocks are applied to the locked supply aggregate
+    for slot in [global_slot + 1, global_slot + 7140, global_slot + 100_000] {
+        assert_eq!(
+            store.get_locked_supply(slot, None)?,
+            best_ledger.time_locked_amount(slot).0
+        );
+    }
+
+    // earlier slots are recomputed from the timed accounts
+    assert_eq!(
+        store.get_locked_supply(0, None)?,
+        best_ledger.time_locked_amount(0).0
+    );
+
+    // everything unlocks eventually
+    assert_eq!(store.get_locked_supply(u32::MAX, None)?, 0);
+
+    // canonical time series is sorted by global slot
+    let series = store.get_supply_time_series(0, global_slot, usize::MAX, None)?;
+    assert!(!series.is_empty());
+    assert!(series
+        .windows(2)
+        .all(|w| w[0].global_slot_since_genesis <= w[1].global_slot_since_genesis));
+
+    // locked supply never increases on the canonical chain
+    assert!(series.windows(2).all(|w| w[0].locked >= w[1].locked));
+    Ok(())
+}
@@ -2,6 +2,7 @@ use crate::helpers::{state::*, store::*};
 use anyhow::Context;
 use mina_indexer::{
     block::parser::BlockParser,
+    chain::profile::ProtocolParams,
     constants::*,
     ledger::{
         account::{Account, Permission, Permissions},
@@ -104,7 +105,7 @@ async fn check_token_accounts() -> anyhow::Result<()> {
 
         assert_eq!(*mina_account, expect);
         assert_eq!(
-            mina_account.clone().display(),
+            mina_account.clone().display(&ProtocolParams::default()),
             Account {
                 balance: 0.into(),
                 ..expect
@@ -160,7 +161,7 @@ async fn check_token_accounts() -> anyhow::Result<()> {
 
         assert_eq!(*mina_account, expect);
         assert_eq!(
-            mina_account.clone().display(),
+            mina_account.clone().display(&ProtocolParams::default()),
             Account {
                 balance: 0.into(),
                 ..expect
@@ -0,0 +1,77 @@
+use crate::helpers::{state::*, store::*};
+use mina_indexer::{
+    base::public_key::PublicKey,
+    constants::*,
+    ledger::{
+        store::{best::BestLedgerStore, supply::SupplyStore},
+        token::TokenAddress,
+    },
+};
+use speedb::IteratorMode;
+
+#[tokio::test]
+async fn genesis_vesting_schedules() -> anyhow::Result<()> {
+    let store_dir = setup_new_db_dir("vesting-db")?;
+    let state = mainnet_genesis_state(store_dir.as_ref())?;
+    let store = state.indexer_store.as_ref().unwrap();
+
+    // every genesis timed account fully unlocks by its projected slot
+    let mut num_checked = 0;
+    for (key, _) in store.timed_accounts_iterator(IteratorMode::Start).flatten() {
+        let pk = PublicKey::from_bytes(&key)?;
+        let schedule = store
+            .get_vesting_schedule(&pk, DEFAULT_VESTING_SCHEDULE_INTERVAL)?
+            .unwrap();
+        let initial = schedule.timing.initial_minimum_balance.0;
+
+        let first = schedule.unlocks.first().unwrap();
+        assert_eq!(first.global_slot, schedule.timing.cliff_time.0);
+        assert_eq!(
+            first.date_time as u64,
+            MAINNET_GENESIS_TIMESTAMP + first.global_slot as u64 * MAINNET_BLOCK_SLOT_TIME_MILLIS
+        );
+
+        let last = schedule.unlocks.last().unwrap();
+        if let Some(fully_vested_slot) = schedule.fully_vested_slot {
+            assert_eq!(last.global_slot, fully_vested_slot);
+            assert_eq!(last.locked, 0);
+            assert_eq!(last.cumulative_unlocked, initial);
+        }
+
+        let total_unlocked: u64 = schedule.unlocks.iter().map(|unlock| unlock.unlocked).sum();
+        assert_eq!(total_unlocked, last.cumulative_unlocked);
+
+        // the delegate's combined schedule includes the account
+        if num_checked < 10 {
+            let delegate = store
+                .get_best_account(&pk, &TokenAddress::default())?
+                .unwrap()
+                .delegate;
+            let delegators = store.get_delegator_vesting_schedules(
+                &delegate,
+                DEFAULT_VESTING_SCHEDULE_INTERVAL,
+                None,
+            )?;
+            assert!(delegators
+                .schedules
+                .iter()
+                .any(|schedule| schedule.public_key == pk));
+
+            let combined_initial: u64 = delegators
+                .schedules
+                .iter()
+                .map(|schedule| schedule.timing.initial_minimum_balance.0)
+                .sum();
+            let combined_last = delegators.unlocks.last().unwrap();
+            assert_eq!(
+                combined_last.cumulative_unlocked + combined_last.locked,
+                combined_initial
+            );
+        }
+
+        num_checked += 1;
+    }
+
+    assert!(num_checked > 0);
+    Ok(())
+}
@@ -0,0 +1,101 @@
+use crate::helpers::{state::*, store::*};
+use mina_indexer::{
+    block::{parser::BlockParser, precomputed::PcbVersion},
+    chain::profile::ProtocolParams,
+    constants::*,
+    ledger::{
+        distribution::WealthDistribution,
+        store::{
+            best::BestLedgerStore, distribution::DistributionStore, staking::StakingLedgerStore,
+        },
+        token::TokenAddress,
+    },
+    store::column_families::ColumnFamilyHelpers,
+};
+use speedb::IteratorMode;
+use std::path::PathBuf;
+
+#[tokio::test]
+async fn epoch_and_best_ledger_distributions() -> anyhow::Result<()> {
+    let store_dir = setup_new_db_dir("wealth-distribution-db")?;
+    let block_dir = &PathBuf::from("./tests/data/canonical_chain_discovery/contiguous");
+    let ledgers_dir = PathBuf::from("./tests/data/staking_ledgers");
+
+    let mut state = mainnet_genesis_state(store_dir.as_ref())?;
+    let mut bp = BlockParser::new_with_canonical_chain_discovery(
+        block_dir,
+        PcbVersion::V1,
+        MAINNET_CANONICAL_THRESHOLD,
+        false,
+        BLOCK_REPORTING_FREQ_NUM,
+    )
+    .await?;
+
+    // ingest the staking ledgers & blocks
+    state
+        .add_startup_staking_ledgers_to_store(&ledgers_dir)
+        .await?;
+    state.add_blocks(&mut bp).await?;
+
+    let store = state.indexer_store.as_ref().unwrap();
+
+    // epoch 0 distribution matches its staking ledger
+    let staking_ledger = store.build_staking_ledger(0, None)?.unwrap();
+    let epoch_dist = store.get_epoch_distribution(0, None)?.unwrap();
+    assert_eq!(
+        epoch_dist,
+        WealthDistribution::new(
+            staking_ledger
+                .staking_ledger
+                .values()
+                .map(|account| (account.balance, &account.delegate))
+        )
+    );
+    assert_eq!(
+        epoch_dist.num_accounts as usize,
+        staking_ledger.staking_ledger.len()
+    );
+    assert!(epoch_dist.nakamoto_one_third > 0);
+    assert!(epoch_dist.nakamoto_one_third <= epoch_dist.nakamoto_one_half);
+    assert!((0.0..=1.0).contains(&epoch_dist.stake_gini));
+    assert_eq!(
+        epoch_dist
+            .balance_histogram
+            .iter()
+            .map(|bucket| bucket.num_accounts)
+            .sum::<u32>(),
+        epoch_dist.num_accounts
+    );
+
+    // best ledger distribution covers the best ledger's MINA accounts
+    let best_ledger = store.get_best_ledger(false)?.unwrap();
+    let best_dist = store.get_best_ledger_distribution()?.unwrap();
+    let mina_accounts = &best_ledger.tokens[&TokenAddress::default()].accounts;
+    assert_eq!(best_dist.num_accounts as usize, mina_accounts.len());
+    assert_eq!(
+        best_dist.total_balance,
+        mina_accounts
+            .values()
+            .map(|account| account
+                .clone()
+                .display(&ProtocolParams::default())
+                .balance
+                .0)
+            .sum::<u64>()
+    );
+
+    // only the latest best tip's distribution is kept
+    store.update_best_ledger_distribution()?;
+    assert_eq!(store.get_best_ledger_distribution()?.unwrap(), best_dist);
+    assert_eq!(
+        store
+            .database
+            .iterator_cf(
+                store.best_ledger_wealth_distribution_cf(),
+                IteratorMode::Start
+            )
+            .count(),
+        1
+    );
+    Ok(())
+}
@@ -0,0 +1,92 @@
+#[cfg(feature = "loose_deserialization")]
+mod tests {
+    use mina_indexer::protocol::bin_prot::{value::json, BinProtRule};
+    use serde_json::json;
+
+    fn test_roundtrip(rule: &str, bytes: &[u8], expected: serde_json::Value) {
+        let rule: BinProtRule = serde_json::from_str(rule).unwrap();
+
+        let decoded = json::decode(bytes, &rule).unwrap();
+        assert_eq!(decoded, expected);
+        assert_eq!(json::encode(&decoded, &rule).unwrap(), bytes);
+    }
+
+    #[test]
+    fn test_option_tuple() {
+        let rule = r#"["Option", ["Tuple", [["Int"], ["Bool"]]]]"#;
+
+        test_roundtrip(rule, &[0x01, 0x05, 0x01], json!([5, true]));
+        test_roundtrip(rule, &[0x00], json!(null));
+    }
+
+    #[test]
+    fn test_record() {
+        let rule = r#"
+[
+  "Record",
+  [
+    { "field_name": "first", "field_rule": ["Int"] },
+    { "field_name": "second", "field_rule": ["Record", [{ "field_name": "inner", "field_rule": ["Bool"] }] ] },
+    { "field_name": "third", "field_rule": ["String"] }
+  ]
+]
+"#;
+
+        test_roundtrip(
+            rule,
+            &[0x05, 0x00, 0x02, b'h', b'i'],
+            json!({ "first": 5, "second": { "inner": false }, "third": "hi" }),
+        );
+    }
+
+    #[test]
+    fn test_sum() {
+        let rule = r#"
+[
+  "Sum",
+  [
+    { "ctor_name": "None", "index": 0, "ctor_args": [] },
+    { "ctor_name": "Pair", "index": 1, "ctor_args": [["Int"], ["List", ["Bool"]]] }
+  ]
+]
+"#;
+
+        test_roundtrip(rule, &[0x00], json!(["None"]));
+        test_roundtrip(rule, &[0x01, 0x07, 0x01, 0x01], json!(["Pair", 7, [true]]));
+    }
+
+    #[test]
+    fn test_big_int() {
+        let rule = r#"
+[
+  "Reference",
+  [
+    "Resolved",
+    {
+      "source_type_decl": "type t = Pasta_bindings.Fp.t",
+      "source_module_path": "Pasta_bindings.Fp",
+      "bin_io_derived": false,
+      "ref_rule": ["Custom", []]
+    }
+  ]
+]
+"#;
+
+        let bytes = [0xab; 32];
+        test_roundtrip(rule, &bytes, json!(hex::encode(bytes)));
+    }
+
+    #[test]
+    fn test_errors() {
+        let rule: BinProtRule = serde_json::from_str(r#"["Tuple", [["Int"], ["Bool"]]]"#).unwrap();
+
+        // trailing bytes
+        assert!(json::decode(&[0x01, 0x00, 0x00], &rule).is_err());
+
+        // wrong shape
+        assert_eq!(
+            json::encode(&json!([1, 2]), &rule).unwrap_err().to_string(),
+            "Expected a bool at $[1], got 2"
+        );
+    }
+}
@@ -1,5 +1,6 @@
 mod arrays;
 mod common;
+mod json;
 mod layouts;
 mod non_integers_repr;
 mod polyvar;
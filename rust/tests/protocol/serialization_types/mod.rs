@@ -1,2 +1,3 @@
 mod common;
 mod tests;
+mod v2;
@@ -0,0 +1,176 @@
+use mina_indexer::protocol::{
+    bin_prot::{
+        from_reader_strict, to_writer, value::json, BinProtRule, Deserializer, Layout, Value,
+    },
+    serialization_types::v2::{
+        pickles::ProofJson,
+        protocol_state::{ProtocolState, ProtocolStateJson},
+        staged_ledger_diff::{StagedLedgerDiff, StagedLedgerDiffJson, UserCommand},
+        PROTOCOL_STATE_LAYOUT, SIGNED_COMMAND_LAYOUT, STAGED_LEDGER_DIFF_LAYOUT,
+        ZKAPP_COMMAND_LAYOUT,
+    },
+};
+use pretty_assertions::assert_eq;
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use std::{fmt::Debug, io::Cursor, path::PathBuf};
+
+/// Berkeley & post-hardfork mainnet precomputed blocks
+fn v2_fixtures() -> impl Iterator<Item = (PathBuf, serde_json::Value)> {
+    glob::glob("./tests/data/berkeley/*/*.json")
+        .unwrap()
+        .chain(glob::glob("./tests/data/hardfork/*.json").unwrap())
+        .filter_map(|path| {
+            let path = path.unwrap();
+            let mut block: serde_json::Value =
+                serde_json::from_slice(&std::fs::read(&path).unwrap()).unwrap();
+
+            // skip the pre-hardfork blocks & the hardfork genesis block, which
+            // has placeholder hashes & no proof
+            let data = block["data"].take();
+            (!data["protocol_state_proof"].is_null()).then_some((path, data))
+        })
+}
+
+/// Serializes with bin_prot, deserializes the bytes & checks the result
+/// matches the original. Returns the bytes
+fn test_bin_roundtrip<T>(t: &T) -> Vec<u8>
+where
+    T: Serialize + DeserializeOwned + Debug + PartialEq,
+{
+    let mut bytes = vec![];
+    to_writer(&mut bytes, t).unwrap();
+
+    let de: T = from_reader_strict(bytes.as_slice()).unwrap();
+    assert_eq!(&de, t);
+    bytes
+}
+
+/// Loosely deserializes the bytes with the layout & checks the value, and its
+/// json, serialize to the same bytes
+fn test_layout_roundtrip(layout: &BinProtRule, bytes: &[u8]) {
+    let mut de = Deserializer::from_reader(Cursor::new(bytes)).with_layout(layout);
+    let value: Value = Deserialize::deserialize(&mut de).unwrap();
+
+    let mut reserialized = vec![];
+    to_writer(&mut reserialized, &value).unwrap();
+    assert_eq!(reserialized, bytes);
+
+    let json = json::decode(bytes, layout).unwrap();
+    assert_eq!(json::encode(&json, layout).unwrap(), bytes);
+}
+
+fn layout_rule(layout: &str) -> BinProtRule {
+    serde_json::from_str::<Layout>(layout)
+        .unwrap()
+        .bin_prot_rule
+}
+
+#[test]
+fn protocol_state_roundtrip() {
+    let layout = layout_rule(PROTOCOL_STATE_LAYOUT);
+
+    for (path, mut block) in v2_fixtures() {
+        let json = block["protocol_state"].take();
+        let protocol_state: ProtocolState =
+            serde_json::from_value::<ProtocolStateJson>(json.clone())
+                .unwrap_or_else(|e| panic!("{}: {e}", path.display()))
+                .into();
+
+        let bytes = test_bin_roundtrip(&protocol_state);
+        test_layout_roundtrip(&layout, &bytes);
+
+        let roundtrip: ProtocolStateJson = protocol_state.into();
+        assert_eq!(serde_json::to_value(roundtrip).unwrap(), json);
+    }
+}
+
+#[test]
+fn protocol_state_proof_roundtrip() {
+    for (_, mut block) in v2_fixtures() {
+        let json = block["protocol_state_proof"].take();
+        let proof: ProofJson = serde_json::from_value(json.clone()).unwrap();
+
+        test_bin_roundtrip(&proof.0);
+        assert_eq!(serde_json::to_value(proof).unwrap(), json);
+    }
+}
+
+#[test]
+fn staged_ledger_diff_roundtrip() {
+    let layout = layout_rule(STAGED_LEDGER_DIFF_LAYOUT);
+    let signed_command_layout = layout_rule(SIGNED_COMMAND_LAYOUT);
+    let zkapp_command_layout = layout_rule(ZKAPP_COMMAND_LAYOUT);
+
+    for (path, mut block) in v2_fixtures() {
+        let mut json = block["staged_ledger_diff"].take();
+        let proof = block["protocol_state_proof"].take();
+
+        // the daemon renders snark work & zkapp proofs as s-expressions, so
+        // the block's proof stands in for them
+        for pre_diff in json["diff"].as_array_mut().unwrap() {
+            if pre_diff.is_null() {
+                continue;
+            }
+
+            for work in pre_diff["completed_works"].as_array_mut().unwrap() {
+                for ledger_proof in work["proofs"].as_array_mut().unwrap().iter_mut().skip(1) {
+                    ledger_proof["proof"] = proof.clone();
+                }
+            }
+
+            for cmd in pre_diff["commands"].as_array_mut().unwrap() {
+                replace_proof_authorizations(&mut cmd["data"], &proof);
+            }
+        }
+
+        let diff: StagedLedgerDiff = serde_json::from_value::<StagedLedgerDiffJson>(json)
+            .unwrap_or_else(|e| panic!("{}: {e}", path.display()))
+            .into();
+
+        let bytes = test_bin_roundtrip(&diff);
+        test_layout_roundtrip(&layout, &bytes);
+
+        for cmd in diff.diff.0.commands {
+            match cmd.data {
+                UserCommand::SignedCommand(cmd) => {
+                    test_layout_roundtrip(&signed_command_layout, &test_bin_roundtrip(&cmd))
+                }
+                UserCommand::ZkappCommand(cmd) => {
+                    test_layout_roundtrip(&zkapp_command_layout, &test_bin_roundtrip(&cmd))
+                }
+            }
+        }
+    }
+
+    // the fixtures must exercise snark work & proof authorizations
+    let diffs: Vec<_> = v2_fixtures()
+        .map(|(_, block)| block["staged_ledger_diff"].to_string())
+        .collect();
+    assert!(diffs
+        .iter()
+        .any(|diff| diff.contains(r#""completed_works":[{"#)));
+    assert!(diffs
+        .iter()
+        .any(|diff| diff.contains(r#""authorization":["Proof","#)));
+}
+
+/// Replaces each proof authorization of the zkapp command `json` with `proof`
+fn replace_proof_authorizations(json: &mut serde_json::Value, proof: &serde_json::Value) {
+    match json {
+        serde_json::Value::Object(map) => {
+            for (key, value) in map.iter_mut() {
+                if key == "authorization" && value[0] == "Proof" {
+                    value[1] = proof.clone();
+                } else {
+                    replace_proof_authorizations(value, proof);
+                }
+            }
+        }
+        serde_json::Value::Array(values) => {
+            for value in values {
+                replace_proof_authorizations(value, proof);
+            }
+        }
+        _ => (),
+    }
+}
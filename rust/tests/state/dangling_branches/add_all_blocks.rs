@@ -1,8 +1,9 @@
 use mina_indexer::{
     base::amount::Amount,
     block::{parser::BlockParser, precomputed::PrecomputedBlock},
+    chain::profile::ProtocolParams,
     constants::MINA_SCALE,
-    ledger::{account::Account, genesis::GenesisLedger, token::TokenAddress, Ledger},
+    ledger::{account::Account, genesis::GenesisLedger, token::TokenAddress},
     state::IndexerState,
 };
 use std::path::PathBuf;
@@ -19,7 +20,7 @@ async fn extension() -> anyhow::Result<()> {
 
     // root ledger
     let genesis_ledger = GenesisLedger::new_v1()?;
-    let mut ledger: Ledger = genesis_ledger.into();
+    let mut ledger = genesis_ledger.into_ledger(&ProtocolParams::default());
 
     // add required accounts with sufficient balance
     ledger.insert_account(
@@ -1,6 +1,7 @@
 use mina_indexer::{
     base::public_key::PublicKey,
     block::parser::BlockParser,
+    chain::profile::ProtocolParams,
     ledger::{diff::LedgerDiff, Ledger},
 };
 use std::{collections::HashSet, path::PathBuf, str::FromStr};
@@ -14,7 +15,7 @@ async fn account_diffs() -> anyhow::Result<()> {
     let (block, _) = block_parser
         .get_precomputed_block("3NKxEA9gztvEGxL4uk4eTncZAxuRmMsB8n81UkeAMevUjMbLHmkC")
         .await?;
-    let diff = LedgerDiff::from_precomputed(&block);
+    let diff = LedgerDiff::from_precomputed(&block, &ProtocolParams::default());
     let ledger = Ledger::from(vec![
         (
             "B62qrRvo5wngd5WA1dgXkQpCdQMRDndusmjfWXWT1LgsSFFdBS9RCsV",
@@ -1,6 +1,7 @@
 use mina_indexer::{
     base::public_key::PublicKey,
     block::parser::BlockParser,
+    chain::profile::ProtocolParams,
     ledger::diff::{
         account::{
             AccountDiff, CoinbaseDiff, DelegationDiff, FailedTransactionNonceDiff, PaymentDiff,
@@ -21,7 +22,7 @@ async fn account_diffs() {
         .get_precomputed_block("3NKxEA9gztvEGxL4uk4eTncZAxuRmMsB8n81UkeAMevUjMbLHmkC")
         .await
         .unwrap();
-    let diff = LedgerDiff::from_precomputed(&block);
+    let diff = LedgerDiff::from_precomputed(&block, &ProtocolParams::default());
     let mut ledger: HashMap<PublicKey, (i64, u32)> = HashMap::from(
         [
             (
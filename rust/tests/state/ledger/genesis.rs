@@ -1,19 +1,18 @@
 use mina_indexer::{
     base::public_key::PublicKey,
     block::genesis::GenesisBlock,
-    chain::Network,
+    chain::{profile::ProtocolParams, Network},
     constants::MAINNET_ACCOUNT_CREATION_FEE,
     ledger::{
         genesis::{GenesisLedger, GenesisRoot},
         token::TokenAddress,
-        Ledger,
     },
 };
 #[test]
 fn test_mainnet_genesis_parser() -> anyhow::Result<()> {
     let genesis_root = GenesisRoot::parse_file("./data/genesis_ledgers/mainnet.json")?;
-    let genesis_ledger: GenesisLedger = genesis_root.clone().into();
-    let ledger: Ledger = genesis_ledger.into();
+    let genesis_ledger: GenesisLedger = genesis_root.clone().try_into()?;
+    let ledger = genesis_ledger.into_ledger(&ProtocolParams::default());
     let mina_accounts = &ledger
         .tokens
         .get(&TokenAddress::default())
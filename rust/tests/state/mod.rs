@@ -4,3 +4,7 @@ mod ledger;
 #[cfg(all(test, feature = "tier2"))]
 mod orphaned_blocks;
 mod root_branch;
+mod upload;
+mod witness_tree;
+#[cfg(all(test, feature = "tier2"))]
+mod witness_tree_store;
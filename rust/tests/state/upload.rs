@@ -0,0 +1,80 @@
+use mina_indexer::{
+    base::state_hash::StateHash,
+    block::precomputed::{PcbVersion, PrecomputedBlock},
+    canonicity::Canonicity,
+    chain::Network,
+    constants::{MAINNET_GENESIS_HASH, MAINNET_GENESIS_LEDGER_HASH},
+    ledger::{staking::StakingLedger, LedgerHash},
+    state::IndexerState,
+};
+use std::path::PathBuf;
+
+const BLOCKS_DIR: &str = "./tests/data/sequential_blocks";
+const HASH_105489: &str = "3NK4huLvUDiL4XuCUcyrWCKynmvhqfKsx5h2MfBXVVUq2Qwzi5uT";
+const HASH_105490: &str = "3NKxEA9gztvEGxL4uk4eTncZAxuRmMsB8n81UkeAMevUjMbLHmkC";
+
+fn block_path(height: u32, state_hash: &str) -> PathBuf {
+    PathBuf::from(BLOCKS_DIR).join(format!("mainnet-{height}-{state_hash}.json"))
+}
+
+#[tokio::test]
+async fn uploads() -> anyhow::Result<()> {
+    let store_dir = tempfile::TempDir::with_prefix("upload-state")?;
+    let root_path = block_path(105489, HASH_105489);
+    let root_block = PrecomputedBlock::parse_file(&root_path, PcbVersion::V1)?;
+    let mut state = IndexerState::new_testing(
+        &root_block,
+        root_path.metadata()?.len(),
+        None,
+        Some(store_dir.path()),
+        None,
+        None,
+        None,
+    )?;
+
+    // the uploaded block extends the best tip
+    let path = block_path(105490, HASH_105490);
+    let block = PrecomputedBlock::parse_file(&path, PcbVersion::V1)?;
+    let status = state.add_uploaded_block(&block, path.metadata()?.len())?;
+
+    assert!(status.added);
+    assert_eq!(status.state_hash.0, HASH_105490);
+    assert_eq!(status.blockchain_length, 105490);
+    assert_eq!(status.canonicity, Canonicity::Pending);
+    assert!(status.on_best_chain);
+    assert_eq!(status.best_tip_state_hash.0, HASH_105490);
+    assert_eq!(status.best_tip_blockchain_length, 105490);
+
+    // re-uploading reports the current status
+    let status = state.add_uploaded_block(&block, path.metadata()?.len())?;
+    assert!(!status.added);
+    assert_eq!(status.best_tip_state_hash.0, HASH_105490);
+
+    // staking ledgers are added once per epoch
+    let ledger_hash = LedgerHash::new(MAINNET_GENESIS_LEDGER_HASH)?;
+    let bytes = std::fs::read(format!(
+        "./tests/data/staking_ledgers/mainnet-0-{MAINNET_GENESIS_LEDGER_HASH}.json"
+    ))?;
+    let staking_ledger = || {
+        StakingLedger::from_bytes(
+            &bytes,
+            Network::Mainnet,
+            0,
+            ledger_hash.clone(),
+            StateHash::from(MAINNET_GENESIS_HASH),
+        )
+    };
+
+    let status = state.add_uploaded_staking_ledger(staking_ledger()?)?;
+    assert!(status.added);
+    assert_eq!(status.epoch, 0);
+    assert_eq!(status.ledger_hash, ledger_hash);
+    assert_eq!(
+        state.staking_ledgers.lock().unwrap().get(&0),
+        Some(&ledger_hash)
+    );
+
+    let status = state.add_uploaded_staking_ledger(staking_ledger()?)?;
+    assert!(!status.added);
+    Ok(())
+}
@@ -0,0 +1,84 @@
+use mina_indexer::{
+    block::parser::BlockParser,
+    canonicity::Canonicity,
+    state::{witness_tree::WitnessTree, ExtensionType, IndexerState},
+};
+use std::path::PathBuf;
+
+/// Snapshot & export the witness tree before & after merging a dangling branch
+#[tokio::test]
+async fn snapshot() -> anyhow::Result<()> {
+    let blocks_dir = PathBuf::from("./tests/data/sequential_blocks");
+    let mut block_parser = BlockParser::new_testing(&blocks_dir)?;
+
+    let root_hash = "3NK4huLvUDiL4XuCUcyrWCKynmvhqfKsx5h2MfBXVVUq2Qwzi5uT";
+    let middle_hash = "3NKxEA9gztvEGxL4uk4eTncZAxuRmMsB8n81UkeAMevUjMbLHmkC";
+    let leaf_hash = "3NKizDx3nnhXha2WqHDNUvJk9jW7GsonsEGYs26tCPW2Wow1ZoR3";
+
+    let (root_block, root_block_bytes) = block_parser.get_precomputed_block(root_hash).await?;
+    let (middle_block, _) = block_parser.get_precomputed_block(middle_hash).await?;
+    let (leaf_block, _) = block_parser.get_precomputed_block(leaf_hash).await?;
+
+    let mut state =
+        IndexerState::new_testing(&root_block, root_block_bytes, None, None, None, None, None)?;
+
+    // the leaf's parent is missing
+    let (extension_type, _) = state.add_block_to_witness_tree(&leaf_block, true, true)?;
+    assert_eq!(extension_type, ExtensionType::DanglingNew);
+
+    let witness_tree = state.witness_tree();
+    assert_eq!(witness_tree.best_tip.0, root_hash);
+    assert_eq!(witness_tree.root_branch.nodes.len(), 1);
+    assert_eq!(witness_tree.dangling_branches.len(), 1);
+
+    let dangling = &witness_tree.dangling_branches[0];
+    assert_eq!(dangling.root.0, leaf_hash);
+    assert_eq!(dangling.root_parent.0, middle_hash);
+    assert_eq!(dangling.nodes[0].blockchain_length, 105491);
+    assert_eq!(
+        dangling.nodes[0].global_slot_since_genesis,
+        leaf_block.global_slot_since_genesis()
+    );
+    assert_eq!(
+        dangling.nodes[0].last_vrf_output,
+        leaf_block.hash_last_vrf_output()
+    );
+
+    // without a store, witness tree blocks are pending
+    assert_eq!(dangling.nodes[0].canonicity, Canonicity::Pending);
+    assert_eq!(dangling.nodes[0].creator, None);
+
+    let dot = witness_tree.to_dot();
+    assert!(dot.contains("subgraph cluster_dangling_branch_0 {"));
+    assert!(dot.contains(&format!(
+        "\"{middle_hash}\" -> \"{leaf_hash}\" [style=dashed];"
+    )));
+
+    // adding the missing parent merges the dangling branch
+    state.add_block_to_witness_tree(&middle_block, true, true)?;
+
+    let witness_tree = state.witness_tree();
+    assert_eq!(witness_tree.best_tip.0, leaf_hash);
+    assert_eq!(witness_tree.num_blocks(), 3);
+    assert!(witness_tree.dangling_branches.is_empty());
+    assert_eq!(
+        witness_tree
+            .root_branch
+            .nodes
+            .iter()
+            .map(|node| node.state_hash.0.as_str())
+            .collect::<Vec<_>>(),
+        vec![root_hash, middle_hash, leaf_hash]
+    );
+
+    let dot = witness_tree.to_dot();
+    assert!(dot.contains(&format!("\"{root_hash}\" -> \"{middle_hash}\";")));
+    assert!(dot.contains(&format!("\"{middle_hash}\" -> \"{leaf_hash}\";")));
+    assert!(!dot.contains("dangling"));
+
+    // round trip JSON
+    let json = serde_json::to_string(&witness_tree)?;
+    assert_eq!(serde_json::from_str::<WitnessTree>(&json)?, witness_tree);
+    Ok(())
+}
+
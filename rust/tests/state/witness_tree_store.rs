@@ -0,0 +1,39 @@
+use crate::helpers::{state::*, store::*};
+use mina_indexer::block::parser::BlockParser;
+use std::{path::PathBuf, sync::Arc};
+use tokio::sync::RwLock;
+
+/// The store builds witness tree snapshots from the running state on request
+#[tokio::test]
+async fn store_snapshot_on_request() -> anyhow::Result<()> {
+    let store_dir = setup_new_db_dir("witness-tree-snapshot-db")?;
+    let blocks_dir = PathBuf::from("./tests/data/canonical_chain_discovery/contiguous");
+    let mut block_parser = BlockParser::new_testing(&blocks_dir)?;
+
+    let state = mainnet_genesis_state(store_dir.as_ref())?;
+    let store = state.indexer_store.clone().unwrap();
+    let state = Arc::new(RwLock::new(state));
+
+    // no running state
+    assert_eq!(store.get_witness_tree().await, None);
+
+    // genesis snapshot
+    store.set_witness_tree_source(&state);
+    let witness_tree = store.get_witness_tree().await.unwrap();
+    assert_eq!(witness_tree.num_blocks(), 1);
+
+    // blocks added since are included without publishing
+    state.write().await.add_blocks(&mut block_parser).await?;
+    let witness_tree = store.get_witness_tree().await.unwrap();
+    assert_eq!(witness_tree, state.read().await.witness_tree());
+    assert_eq!(
+        witness_tree.best_tip,
+        state.read().await.best_tip_block().state_hash
+    );
+    assert!(witness_tree.num_blocks() > 1);
+
+    // the state has stopped
+    drop(state);
+    assert_eq!(store.get_witness_tree().await, None);
+    Ok(())
+}